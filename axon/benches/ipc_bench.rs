@@ -17,6 +17,9 @@ fn make_envelope() -> Envelope {
             "data": {"step": 3, "total": 10, "message": "Compiling module xyz"},
             "importance": "medium"
         })),
+        topic: None,
+        thread_id: None,
+        headers: serde_json::Map::new(),
         from: Some(AgentId::from(format!("ed25519.{}", "a".repeat(32)))),
         to: Some(AgentId::from(format!("ed25519.{}", "b".repeat(32)))),
     }
@@ -106,6 +109,11 @@ fn bench_daemon_reply_serialize(c: &mut Criterion) {
             status: "connected".to_string(),
             rtt_ms: Some(1.2),
             source: "static".to_string(),
+            first_seen_ms: 1_771_108_000_000,
+            expires_in_ms: None,
+            traffic: Default::default(),
+            version: Some("0.7.2".to_string()),
+            tags: Vec::new(),
         }],
         req_id: Some("req-3".to_string()),
     };
@@ -119,6 +127,9 @@ fn bench_daemon_reply_serialize(c: &mut Criterion) {
         peers_connected: 2,
         messages_sent: 10,
         messages_received: 5,
+        messages_rate_limited: 0,
+        lifetime: Default::default(),
+        degraded: Vec::new(),
         req_id: Some("req-4".to_string()),
     };
     group.bench_function("status", |b| {
@@ -140,12 +151,7 @@ fn bench_daemon_reply_serialize(c: &mut Criterion) {
         b.iter(|| serde_json::to_string(black_box(&whoami)).unwrap())
     });
 
-    let err = DaemonReply::Error {
-        ok: false,
-        error: IpcErrorCode::InvalidCommand,
-        message: IpcErrorCode::InvalidCommand.message(),
-        req_id: Some("req-6".to_string()),
-    };
+    let err = DaemonReply::error(IpcErrorCode::InvalidCommand, Some("req-6".to_string()));
     group.bench_function("error", |b| {
         b.iter(|| serde_json::to_string(black_box(&err)).unwrap())
     });
@@ -10,6 +10,9 @@ fn make_small_envelope() -> Envelope {
         kind: MessageKind::Request,
         ref_id: None,
         payload: Envelope::raw_json(&json!({})),
+        topic: None,
+        thread_id: None,
+        headers: serde_json::Map::new(),
         from: Some(AgentId::from(format!("ed25519.{}", "a".repeat(32)))),
         to: Some(AgentId::from(format!("ed25519.{}", "b".repeat(32)))),
     }
@@ -26,6 +29,9 @@ fn make_medium_envelope() -> Envelope {
             "max_tokens": 1024,
             "deadline_ms": 30000
         })),
+        topic: None,
+        thread_id: None,
+        headers: serde_json::Map::new(),
         from: Some(AgentId::from(format!("ed25519.{}", "a".repeat(32)))),
         to: Some(AgentId::from(format!("ed25519.{}", "b".repeat(32)))),
     }
@@ -45,6 +51,9 @@ fn make_large_envelope() -> Envelope {
             "tokens_used": 4096,
             "truncated": false
         })),
+        topic: None,
+        thread_id: None,
+        headers: serde_json::Map::new(),
         from: Some(AgentId::from(format!("ed25519.{}", "a".repeat(32)))),
         to: Some(AgentId::from(format!("ed25519.{}", "b".repeat(32)))),
     }
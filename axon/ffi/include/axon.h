@@ -0,0 +1,68 @@
+/*
+ * C ABI for the AXON IPC client (axon-ffi). Link against libaxon_ffi.
+ *
+ * Every call blocks the calling thread. JSON crosses the boundary as
+ * NUL-terminated UTF-8; strings returned through an out parameter are owned
+ * by the caller and released with axon_string_free(). Functions returning
+ * int report failure as AXON_ERR; axon_last_error() then describes it.
+ */
+#ifndef AXON_H
+#define AXON_H
+
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define AXON_OK 0
+#define AXON_ERR (-1)
+#define AXON_TIMEOUT 1
+#define AXON_CLOSED 2
+
+typedef struct AxonClient AxonClient;
+
+/* Receives one IPC event as JSON, valid only during the call. Runs on the
+ * client's event thread, outside its runtime, so it may call any axon_*
+ * function on the client, including axon_client_free() and
+ * axon_client_on_event(). No further events arrive after it frees or
+ * replaces itself; events queue while it blocks. */
+typedef void (*AxonEventCallback)(const char *event_json, void *user_data);
+
+/* Connect to the daemon socket; NULL selects $AXON_ROOT or ~/.axon.
+ * Returns NULL on failure. */
+AxonClient *axon_client_connect(const char *socket_path);
+
+/* Close the connection and release the client. NULL is ignored. */
+void axon_client_free(AxonClient *client);
+
+/* Send an IPC command (spec/IPC.md) and store the reply in *reply_out.
+ * A reply with "ok": false still returns AXON_OK. */
+int axon_client_command(AxonClient *client, const char *command_json, char **reply_out);
+
+/* Send payload_json to `to` as a `kind` envelope ("request", "message").
+ * For requests the reply carries the peer's "response". */
+int axon_client_send(AxonClient *client, const char *to, const char *kind,
+                     const char *payload_json, char **reply_out);
+
+/* Wait up to timeout_ms (0 to only check) for the next event received since
+ * connecting. Returns AXON_OK with *event_out set, AXON_TIMEOUT, or
+ * AXON_CLOSED once the connection is gone. Not for concurrent use. */
+int axon_client_poll_event(AxonClient *client, uint32_t timeout_ms, char **event_out);
+
+/* Call `callback` with every event from now on. Replaces any earlier
+ * callback; NULL removes it. On return, and after axon_client_free(), the
+ * earlier callback is no longer running unless the call came from it. */
+int axon_client_on_event(AxonClient *client, AxonEventCallback callback, void *user_data);
+
+/* Release a string returned by this library. NULL is ignored. */
+void axon_string_free(char *value);
+
+/* The calling thread's last error message, or NULL. Do not free. */
+const char *axon_last_error(void);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* AXON_H */
@@ -0,0 +1,86 @@
+use std::ffi::{CString, c_void};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::JoinHandle;
+
+use axon::client::IpcEvents;
+use tokio::runtime::Handle;
+use tokio::sync::oneshot;
+
+use crate::AxonEventCallback;
+
+/// `user_data` is only handed back to the caller's callback, which the
+/// caller made safe to run on another thread.
+struct UserData(*mut c_void);
+
+// SAFETY: see `UserData`.
+unsafe impl Send for UserData {}
+
+/// A thread of its own that feeds events to the caller's callback.
+///
+/// It is not a runtime worker, so the callback may call back into the
+/// client, including `axon_client_free` and `axon_client_on_event`, without
+/// nesting `block_on` or dropping the runtime from inside it.
+pub(crate) struct EventThread {
+    stopped: Arc<AtomicBool>,
+    wake: Option<oneshot::Sender<()>>,
+    thread: JoinHandle<()>,
+}
+
+impl EventThread {
+    pub(crate) fn spawn(
+        runtime: Handle,
+        mut events: IpcEvents,
+        callback: AxonEventCallback,
+        user_data: *mut c_void,
+    ) -> std::io::Result<Self> {
+        let stopped = Arc::new(AtomicBool::new(false));
+        let (wake, mut woken) = oneshot::channel();
+        let user_data = UserData(user_data);
+        let thread = std::thread::Builder::new()
+            .name("axon-ffi-events".to_string())
+            .spawn({
+                let stopped = stopped.clone();
+                move || {
+                    let user_data = user_data;
+                    loop {
+                        let next = runtime.block_on(async {
+                            tokio::select! {
+                                event = events.next() => event,
+                                _ = &mut woken => None,
+                            }
+                        });
+                        // The callback may have freed the client or replaced
+                        // itself; in both cases the runtime is off limits.
+                        let Some(event) = next.filter(|_| !stopped.load(Ordering::Acquire)) else {
+                            return;
+                        };
+                        let Ok(event) = CString::new(event.to_string()) else {
+                            continue;
+                        };
+                        // SAFETY: the caller guarantees `callback` accepts
+                        // `user_data` on this thread.
+                        unsafe { callback(event.as_ptr(), user_data.0) };
+                        if stopped.load(Ordering::Acquire) {
+                            return;
+                        }
+                    }
+                }
+            })?;
+        Ok(Self {
+            stopped,
+            wake: Some(wake),
+            thread,
+        })
+    }
+
+    /// Deliver no further events. Waits for a running callback to return,
+    /// unless this is called from that callback.
+    pub(crate) fn stop(mut self) {
+        self.stopped.store(true, Ordering::Release);
+        drop(self.wake.take());
+        if self.thread.thread().id() != std::thread::current().id() {
+            let _ = self.thread.join();
+        }
+    }
+}
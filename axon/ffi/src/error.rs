@@ -0,0 +1,47 @@
+use std::cell::RefCell;
+use std::ffi::{CStr, CString, c_char};
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Record `message` as the calling thread's last error.
+pub(crate) fn set_last_error(message: impl std::fmt::Display) {
+    // Interior NULs would truncate the message; replace them.
+    let message = message.to_string().replace('\0', " ");
+    let message = CString::new(message).unwrap_or_default();
+    LAST_ERROR.with(|slot| *slot.borrow_mut() = Some(message));
+}
+
+/// Pointer to the last error message, or NULL if there is none. Valid until
+/// the thread's next error.
+pub(crate) fn last_error() -> *const c_char {
+    LAST_ERROR.with(|slot| {
+        slot.borrow()
+            .as_ref()
+            .map_or(std::ptr::null(), |message| message.as_ptr())
+    })
+}
+
+/// JSON text as a heap C string owned by the caller, released with
+/// `axon_string_free`. serde_json escapes NUL, so none is lost.
+pub(crate) fn into_c_string(json: String) -> *mut c_char {
+    CString::new(json).unwrap_or_default().into_raw()
+}
+
+/// Borrow a UTF-8 argument, naming it in the error when it is NULL or not
+/// UTF-8.
+///
+/// # Safety
+///
+/// `ptr` must be NULL or point to a NUL-terminated string that outlives the
+/// returned borrow.
+pub(crate) unsafe fn read_str<'a>(ptr: *const c_char, name: &str) -> Result<&'a str, String> {
+    if ptr.is_null() {
+        return Err(format!("{name} is NULL"));
+    }
+    // SAFETY: non-NULL and NUL-terminated per the caller's contract.
+    unsafe { CStr::from_ptr(ptr) }
+        .to_str()
+        .map_err(|_| format!("{name} is not valid UTF-8"))
+}
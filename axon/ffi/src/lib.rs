@@ -0,0 +1,304 @@
+//! Stable C ABI over [`axon::client::IpcClient`], for runtimes that cannot
+//! link Rust directly (Go via cgo, C++, Swift). The declarations live in
+//! `include/axon.h`.
+//!
+//! Every call blocks the calling thread, which may be any thread. A client owns a
+//! small tokio runtime; inbound callbacks run on a separate event thread,
+//! outside the runtime, so they may call back into the client. JSON
+//! crosses the boundary as NUL-terminated UTF-8, and strings returned to the
+//! caller are released with [`axon_string_free`]. Functions returning
+//! `int` report failure as [`AXON_ERR`] and leave a message for
+//! [`axon_last_error`].
+
+mod callback;
+mod error;
+#[cfg(feature = "python")]
+mod python;
+
+use std::ffi::{c_char, c_int, c_void};
+use std::time::Duration;
+
+use axon::client::{IpcClient, IpcEvents};
+use axon::config::AxonPaths;
+use serde_json::{Value, json};
+use tokio::runtime::Runtime;
+
+use callback::EventThread;
+use error::{into_c_string, read_str, set_last_error};
+
+/// The call succeeded.
+pub const AXON_OK: c_int = 0;
+/// The call failed; see [`axon_last_error`].
+pub const AXON_ERR: c_int = -1;
+/// [`axon_client_poll_event`] saw no event before its timeout.
+pub const AXON_TIMEOUT: c_int = 1;
+/// The event stream has ended: the daemon closed the connection, or the
+/// inbox fell more than `EVENT_BUFFER` events behind.
+pub const AXON_CLOSED: c_int = 2;
+
+/// Receives one IPC event as JSON, valid only for the duration of the call.
+pub type AxonEventCallback =
+    unsafe extern "C" fn(event_json: *const c_char, user_data: *mut c_void);
+
+/// Opaque client handle.
+pub struct AxonClient {
+    client: IpcClient,
+    inbox: IpcEvents,
+    callback: Option<EventThread>,
+    // Dropped last, once nothing else needs it.
+    runtime: Runtime,
+}
+
+impl Drop for AxonClient {
+    fn drop(&mut self) {
+        if let Some(callback) = self.callback.take() {
+            callback.stop();
+        }
+    }
+}
+
+/// Connect to the daemon socket at `socket_path`, or to the default socket
+/// (`AXON_ROOT` or `~/.axon`) when it is NULL. Returns NULL on failure.
+///
+/// # Safety
+///
+/// `socket_path` must be NULL or a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn axon_client_connect(socket_path: *const c_char) -> *mut AxonClient {
+    let socket = if socket_path.is_null() {
+        AxonPaths::discover_with_override(None).map(|paths| paths.socket)
+    } else {
+        // SAFETY: forwarded caller contract.
+        unsafe { read_str(socket_path, "socket_path") }
+            .map(Into::into)
+            .map_err(anyhow::Error::msg)
+    };
+    let result = socket.and_then(|socket| -> anyhow::Result<AxonClient> {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .thread_name("axon-ffi")
+            .enable_all()
+            .build()?;
+        let client = runtime.block_on(IpcClient::connect(&socket))?;
+        let inbox = client.events();
+        Ok(AxonClient {
+            client,
+            inbox,
+            callback: None,
+            runtime,
+        })
+    });
+    match result {
+        Ok(client) => Box::into_raw(Box::new(client)),
+        Err(err) => {
+            set_last_error(format!("{err:#}"));
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Close the connection and release `client`. NULL is ignored.
+///
+/// # Safety
+///
+/// `client` must be NULL or a handle from [`axon_client_connect`] that is
+/// not used afterwards.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn axon_client_free(client: *mut AxonClient) {
+    if !client.is_null() {
+        // SAFETY: the handle came from `Box::into_raw` per the contract.
+        drop(unsafe { Box::from_raw(client) });
+    }
+}
+
+/// Send an IPC command (`spec/IPC.md`) and store the daemon's reply in
+/// `*reply_out`. A reply with `"ok": false` is still [`AXON_OK`].
+///
+/// # Safety
+///
+/// `client` must be a live handle, `command_json` a NUL-terminated string,
+/// and `reply_out` a valid pointer.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn axon_client_command(
+    client: *mut AxonClient,
+    command_json: *const c_char,
+    reply_out: *mut *mut c_char,
+) -> c_int {
+    // SAFETY: forwarded caller contract.
+    let command = match unsafe { read_str(command_json, "command_json") } {
+        Ok(command) => serde_json::from_str::<Value>(command)
+            .map_err(|err| format!("command_json is not valid JSON: {err}")),
+        Err(err) => Err(err),
+    };
+    match command {
+        // SAFETY: forwarded caller contract.
+        Ok(command) => unsafe { run_command(client, command, reply_out) },
+        Err(err) => fail(err),
+    }
+}
+
+/// Send `payload_json` to `to` as a `kind` envelope (`request`, `message`)
+/// and store the daemon's reply in `*reply_out`. For requests the reply
+/// carries the peer's `response`.
+///
+/// # Safety
+///
+/// `client` must be a live handle, the strings NUL-terminated, and
+/// `reply_out` a valid pointer.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn axon_client_send(
+    client: *mut AxonClient,
+    to: *const c_char,
+    kind: *const c_char,
+    payload_json: *const c_char,
+    reply_out: *mut *mut c_char,
+) -> c_int {
+    // SAFETY: forwarded caller contract.
+    let args = unsafe {
+        (
+            read_str(to, "to"),
+            read_str(kind, "kind"),
+            read_str(payload_json, "payload_json"),
+        )
+    };
+    let command = match args {
+        (Ok(to), Ok(kind), Ok(payload)) => serde_json::from_str::<Value>(payload)
+            .map(|payload| json!({"cmd": "send", "to": to, "kind": kind, "payload": payload}))
+            .map_err(|err| format!("payload_json is not valid JSON: {err}")),
+        (Err(err), _, _) | (_, Err(err), _) | (_, _, Err(err)) => Err(err),
+    };
+    match command {
+        // SAFETY: forwarded caller contract.
+        Ok(command) => unsafe { run_command(client, command, reply_out) },
+        Err(err) => fail(err),
+    }
+}
+
+/// Wait up to `timeout_ms` (0 to only check) for the next event in the
+/// client's inbox and store it in `*event_out`. Returns [`AXON_OK`],
+/// [`AXON_TIMEOUT`], or [`AXON_CLOSED`]. The inbox holds every event since
+/// the client connected, independent of any callback.
+///
+/// # Safety
+///
+/// `client` must be a live handle not polled from another thread at the
+/// same time, and `event_out` a valid pointer.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn axon_client_poll_event(
+    client: *mut AxonClient,
+    timeout_ms: u32,
+    event_out: *mut *mut c_char,
+) -> c_int {
+    // SAFETY: forwarded caller contract.
+    let Some(client) = (unsafe { client.as_mut() }) else {
+        return fail("client is NULL");
+    };
+    if event_out.is_null() {
+        return fail("event_out is NULL");
+    }
+    let wait = Duration::from_millis(timeout_ms.into());
+    let inbox = &mut client.inbox;
+    let next = client
+        .runtime
+        .block_on(async { tokio::time::timeout(wait, inbox.next()).await });
+    match next {
+        Ok(Some(event)) => {
+            // SAFETY: checked non-NULL above; validity per the contract.
+            unsafe { *event_out = into_c_string(event.to_string()) };
+            AXON_OK
+        }
+        Ok(None) => AXON_CLOSED,
+        Err(_) => AXON_TIMEOUT,
+    }
+}
+
+/// Call `callback` with every event from now on, on the client's event
+/// thread. Replaces any earlier callback; NULL removes it. Once this returns
+/// or the client is freed, the earlier callback is no longer running, except
+/// when called from that callback itself.
+///
+/// The callback may call any `axon_*` function, including freeing the
+/// client; no further events are delivered after it does. Events wait while
+/// the callback blocks.
+///
+/// # Safety
+///
+/// `client` must be a live handle. `callback` must be safe to call from
+/// another thread with `user_data` until it is replaced or the client is
+/// freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn axon_client_on_event(
+    client: *mut AxonClient,
+    callback: Option<AxonEventCallback>,
+    user_data: *mut c_void,
+) -> c_int {
+    // SAFETY: forwarded caller contract.
+    let Some(client) = (unsafe { client.as_mut() }) else {
+        return fail("client is NULL");
+    };
+    if let Some(previous) = client.callback.take() {
+        previous.stop();
+    }
+    let Some(callback) = callback else {
+        return AXON_OK;
+    };
+    let events = client.client.events();
+    match EventThread::spawn(client.runtime.handle().clone(), events, callback, user_data) {
+        Ok(thread) => client.callback = Some(thread),
+        Err(err) => return fail(format!("failed to start the event thread: {err}")),
+    }
+    AXON_OK
+}
+
+/// Release a string returned by this library. NULL is ignored.
+///
+/// # Safety
+///
+/// `value` must be NULL or a string from this library not freed before.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn axon_string_free(value: *mut c_char) {
+    if !value.is_null() {
+        // SAFETY: the string came from `CString::into_raw` per the contract.
+        drop(unsafe { std::ffi::CString::from_raw(value) });
+    }
+}
+
+/// Message for the calling thread's last failure, or NULL. Valid until the
+/// thread's next failure; do not free it.
+#[unsafe(no_mangle)]
+pub extern "C" fn axon_last_error() -> *const c_char {
+    error::last_error()
+}
+
+/// # Safety
+///
+/// `client` must be NULL or a live handle, and `reply_out` NULL or valid.
+unsafe fn run_command(
+    client: *mut AxonClient,
+    command: Value,
+    reply_out: *mut *mut c_char,
+) -> c_int {
+    // SAFETY: forwarded caller contract.
+    let Some(client) = (unsafe { client.as_ref() }) else {
+        return fail("client is NULL");
+    };
+    if reply_out.is_null() {
+        return fail("reply_out is NULL");
+    }
+    match client.runtime.block_on(client.client.command(command)) {
+        Ok(reply) => {
+            // SAFETY: checked non-NULL above; validity per the contract.
+            unsafe { *reply_out = into_c_string(reply.to_string()) };
+            AXON_OK
+        }
+        Err(err) => fail(format!("{err:#}")),
+    }
+}
+
+fn fail(message: impl std::fmt::Display) -> c_int {
+    set_last_error(message);
+    AXON_ERR
+}
+
+#[cfg(test)]
+mod tests;
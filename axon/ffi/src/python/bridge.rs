@@ -0,0 +1,175 @@
+//! Futures run on the binding's tokio runtime and awaited from asyncio.
+//!
+//! Runtime threads only enter the interpreter through [`GATE`]. An `atexit`
+//! hook closes it and waits for threads already inside, so none is holding
+//! or waiting for the GIL once the interpreter starts finalizing, which
+//! would abort the process.
+
+use std::future::Future;
+use std::sync::{Condvar, Mutex, MutexGuard, OnceLock, PoisonError};
+
+use pyo3::exceptions::PyRuntimeError;
+use pyo3::prelude::*;
+use tokio::runtime::Runtime;
+use tokio::task::AbortHandle;
+
+static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+static GATE: Gate = Gate::new();
+
+/// Build the runtime and register the exit hook; called on import.
+pub(super) fn start(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    if RUNTIME.get().is_none() {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(2)
+            .thread_name("axon-py")
+            .enable_all()
+            .build()
+            .map_err(|err| PyRuntimeError::new_err(format!("failed to start runtime: {err}")))?;
+        let _ = RUNTIME.set(runtime);
+    }
+    module
+        .py()
+        .import("atexit")?
+        .call_method1("register", (wrap_pyfunction!(close_gate, module)?,))?;
+    Ok(())
+}
+
+/// An asyncio future on the running loop, resolved with `convert` applied
+/// to what `future` yields. `future` runs on the runtime; cancelling the
+/// asyncio future drops it.
+pub(super) fn awaitable<'py, F, T, C>(
+    py: Python<'py>,
+    future: F,
+    convert: C,
+) -> PyResult<Bound<'py, PyAny>>
+where
+    F: Future<Output = PyResult<T>> + Send + 'static,
+    T: Send + 'static,
+    C: FnOnce(Python<'_>, T) -> PyResult<PyObject> + Send + 'static,
+{
+    let Some(runtime) = RUNTIME.get() else {
+        return Err(PyRuntimeError::new_err("axon runtime is not running"));
+    };
+    let event_loop = py.import("asyncio")?.call_method0("get_running_loop")?;
+    let result = event_loop.call_method0("create_future")?;
+    let (event_loop, target) = (event_loop.unbind(), result.clone().unbind());
+    let task = runtime.spawn(async move {
+        let outcome = future.await;
+        GATE.with_gil(move |py| {
+            let (error, value) = match outcome.and_then(|value| convert(py, value)) {
+                Ok(value) => (py.None(), value),
+                Err(err) => (err.into_value(py).into_any(), py.None()),
+            };
+            // Fails only when the loop is closed, with nobody left to tell.
+            let _ = wrap_pyfunction!(resolve, py).and_then(|resolve| {
+                event_loop
+                    .bind(py)
+                    .call_method1("call_soon_threadsafe", (resolve, target, error, value))
+            });
+        });
+    });
+    result.call_method1("add_done_callback", (AbortOnCancel(task.abort_handle()),))?;
+    Ok(result)
+}
+
+/// Settle `future` on its loop unless it was cancelled meanwhile.
+#[pyfunction]
+fn resolve(
+    future: &Bound<'_, PyAny>,
+    error: &Bound<'_, PyAny>,
+    value: &Bound<'_, PyAny>,
+) -> PyResult<()> {
+    if future.call_method0("done")?.is_truthy()? {
+        return Ok(());
+    }
+    if error.is_none() {
+        future.call_method1("set_result", (value,))?;
+    } else {
+        future.call_method1("set_exception", (error,))?;
+    }
+    Ok(())
+}
+
+#[pyfunction]
+fn close_gate(py: Python<'_>) {
+    py.allow_threads(|| GATE.close());
+}
+
+/// Done callback that stops the runtime task behind a cancelled future.
+#[pyclass(frozen)]
+struct AbortOnCancel(AbortHandle);
+
+#[pymethods]
+impl AbortOnCancel {
+    fn __call__(&self, future: &Bound<'_, PyAny>) -> PyResult<()> {
+        if future.call_method0("cancelled")?.is_truthy()? {
+            self.0.abort();
+        }
+        Ok(())
+    }
+}
+
+/// Counts runtime threads inside the interpreter; closed at exit.
+struct Gate {
+    state: Mutex<GateState>,
+    idle: Condvar,
+}
+
+struct GateState {
+    entered: usize,
+    closed: bool,
+}
+
+impl Gate {
+    const fn new() -> Self {
+        Self {
+            state: Mutex::new(GateState {
+                entered: 0,
+                closed: false,
+            }),
+            idle: Condvar::new(),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, GateState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Run `f` with the GIL, or skip it once the interpreter is exiting.
+    fn with_gil(&self, f: impl FnOnce(Python<'_>)) {
+        {
+            let mut state = self.lock();
+            if state.closed {
+                return;
+            }
+            state.entered += 1;
+        }
+        let _left = Left(self);
+        Python::with_gil(f);
+    }
+
+    /// Turn away further threads and wait for those inside to leave.
+    fn close(&self) {
+        let mut state = self.lock();
+        state.closed = true;
+        while state.entered > 0 {
+            state = self
+                .idle
+                .wait(state)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+}
+
+/// Marks a thread as out of the interpreter, even if `f` panicked.
+struct Left<'a>(&'a Gate);
+
+impl Drop for Left<'_> {
+    fn drop(&mut self) {
+        let mut state = self.0.lock();
+        state.entered -= 1;
+        if state.entered == 0 {
+            self.0.idle.notify_all();
+        }
+    }
+}
@@ -0,0 +1,212 @@
+//! Python bindings (`python` feature): the `axon` extension module, an
+//! asyncio client over [`axon::client::IpcClient`]. Build it with maturin
+//! from `axon/ffi/pyproject.toml`.
+//!
+//! Every coroutine method returns an asyncio future on the running loop;
+//! the work runs on the module's own tokio runtime (`bridge.rs`). JSON
+//! crosses the boundary through Python's `json` module, so commands,
+//! payloads, replies, and events are plain dicts.
+
+mod bridge;
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use axon::client::{IpcClient, IpcEvents};
+use axon::config::AxonPaths;
+use pyo3::exceptions::{PyException, PyStopAsyncIteration};
+use pyo3::prelude::*;
+use serde_json::{Value, json};
+use tokio::sync::Mutex;
+
+use bridge::awaitable;
+
+pyo3::create_exception!(axon, AxonError, PyException);
+
+/// Connect to the daemon socket at `socket`, or to the default socket
+/// (`AXON_ROOT` or `~/.axon`) when it is `None`. Awaits to a `Client`.
+#[pyfunction]
+#[pyo3(signature = (socket = None))]
+fn connect(py: Python<'_>, socket: Option<PathBuf>) -> PyResult<Bound<'_, PyAny>> {
+    let socket = match socket {
+        Some(socket) => socket,
+        None => {
+            AxonPaths::discover_with_override(None)
+                .map_err(error)?
+                .socket
+        }
+    };
+    awaitable(
+        py,
+        async move { IpcClient::connect(&socket).await.map_err(error) },
+        |py, client| {
+            let client = Client {
+                client: Arc::new(client),
+            };
+            Ok(Py::new(py, client)?.into_any())
+        },
+    )
+}
+
+/// A connection to the daemon. Commands may run concurrently; replies are
+/// matched by `req_id`.
+#[pyclass(frozen, module = "axon")]
+struct Client {
+    client: Arc<IpcClient>,
+}
+
+#[pymethods]
+impl Client {
+    /// Send an IPC command (`spec/IPC.md`) and await the daemon's reply. A
+    /// reply with `"ok": False` is returned, not raised.
+    fn command<'py>(
+        &self,
+        py: Python<'py>,
+        command: &Bound<'py, PyAny>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let command = to_value(command)?;
+        self.run(py, command)
+    }
+
+    /// Send `payload` to `to` as a `message` and await the daemon's reply.
+    #[pyo3(signature = (to, payload, *, req_id = None))]
+    fn send<'py>(
+        &self,
+        py: Python<'py>,
+        to: &str,
+        payload: &Bound<'py, PyAny>,
+        req_id: Option<String>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let mut command =
+            json!({"cmd": "send", "to": to, "kind": "message", "payload": to_value(payload)?});
+        if let Some(req_id) = req_id {
+            command["req_id"] = json!(req_id);
+        }
+        self.run(py, command)
+    }
+
+    /// Send `payload` to `to` as a `request` and await the daemon's reply,
+    /// which carries the peer's `response`.
+    #[pyo3(signature = (to, payload, *, timeout_secs = None, req_id = None))]
+    fn request<'py>(
+        &self,
+        py: Python<'py>,
+        to: &str,
+        payload: &Bound<'py, PyAny>,
+        timeout_secs: Option<u64>,
+        req_id: Option<String>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let mut command =
+            json!({"cmd": "send", "to": to, "kind": "request", "payload": to_value(payload)?});
+        if let Some(timeout_secs) = timeout_secs {
+            command["timeout_secs"] = json!(timeout_secs);
+        }
+        if let Some(req_id) = req_id {
+            command["req_id"] = json!(req_id);
+        }
+        self.run(py, command)
+    }
+
+    /// Subscribe to `topics` and await an `Events` iterator that starts
+    /// before the subscription, so no matching `inbound` event is missed.
+    /// Raises `AxonError` when the daemon refuses the subscription.
+    fn subscribe<'py>(&self, py: Python<'py>, topics: Vec<String>) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.client.clone();
+        let events = self.client.events();
+        let subscribed = async move {
+            let reply = client
+                .command(json!({"cmd": "subscribe", "topics": topics}))
+                .await
+                .map_err(error)?;
+            if reply["ok"] != json!(true) {
+                return Err(AxonError::new_err(reply.to_string()));
+            }
+            Ok(events)
+        };
+        awaitable(py, subscribed, |py, events| {
+            Ok(Py::new(py, Events::new(events))?.into_any())
+        })
+    }
+
+    /// Every event the daemon sends from now on, as an async iterator of
+    /// dicts. It ends when the connection closes.
+    fn events(&self) -> Events {
+        Events::new(self.client.events())
+    }
+}
+
+impl Client {
+    fn run<'py>(&self, py: Python<'py>, command: Value) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.client.clone();
+        awaitable(
+            py,
+            async move { client.command(command).await.map_err(error) },
+            |py, reply| to_python(py, &reply),
+        )
+    }
+}
+
+/// Daemon events, from `Client.events()` or `Client.subscribe()`.
+#[pyclass(frozen, module = "axon")]
+struct Events {
+    events: Arc<Mutex<IpcEvents>>,
+}
+
+impl Events {
+    fn new(events: IpcEvents) -> Self {
+        Self {
+            events: Arc::new(Mutex::new(events)),
+        }
+    }
+}
+
+#[pymethods]
+impl Events {
+    fn __aiter__(slf: Py<Self>) -> Py<Self> {
+        slf
+    }
+
+    fn __anext__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let events = self.events.clone();
+        let next = async move {
+            events
+                .lock()
+                .await
+                .next()
+                .await
+                .ok_or_else(|| PyStopAsyncIteration::new_err(()))
+        };
+        awaitable(py, next, |py, event| to_python(py, &event))
+    }
+}
+
+fn to_value(object: &Bound<'_, PyAny>) -> PyResult<Value> {
+    let text: String = object
+        .py()
+        .import("json")?
+        .call_method1("dumps", (object,))?
+        .extract()?;
+    serde_json::from_str(&text).map_err(error)
+}
+
+fn to_python(py: Python<'_>, value: &Value) -> PyResult<PyObject> {
+    Ok(py
+        .import("json")?
+        .call_method1("loads", (value.to_string(),))?
+        .unbind())
+}
+
+fn error(err: impl std::fmt::Display) -> PyErr {
+    AxonError::new_err(format!("{err:#}"))
+}
+
+#[pymodule]
+#[pyo3(name = "axon")]
+fn python_module(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    bridge::start(module)?;
+    module.add_function(wrap_pyfunction!(connect, module)?)?;
+    module.add_class::<Client>()?;
+    module.add_class::<Events>()?;
+    module.add("AxonError", module.py().get_type::<AxonError>())?;
+    Ok(())
+}
@@ -0,0 +1,239 @@
+use super::*;
+
+#[test]
+fn null_arguments_are_named_in_the_error() {
+    let dir = tempdir().unwrap();
+    let socket = dir.path().join("axon.sock");
+    let server = stub_daemon(&socket, 0);
+    let client = connect(&socket);
+    let null = std::ptr::null::<c_char>();
+    let command = CString::new(r#"{"cmd":"status"}"#).unwrap();
+    let to = CString::new("ed25519.00000000000000000000000000000000").unwrap();
+    let kind = CString::new("message").unwrap();
+    let payload = CString::new("{}").unwrap();
+    let mut reply = std::ptr::null_mut();
+
+    let expect_err = |code: c_int, message: &str| {
+        assert_eq!(code, AXON_ERR, "{message}");
+        assert_eq!(last_error_text(), message);
+    };
+    unsafe {
+        expect_err(
+            axon_client_command(client, command.as_ptr(), std::ptr::null_mut()),
+            "reply_out is NULL",
+        );
+        expect_err(
+            axon_client_send(client, null, kind.as_ptr(), payload.as_ptr(), &mut reply),
+            "to is NULL",
+        );
+        expect_err(
+            axon_client_send(client, to.as_ptr(), null, payload.as_ptr(), &mut reply),
+            "kind is NULL",
+        );
+        expect_err(
+            axon_client_send(client, to.as_ptr(), kind.as_ptr(), null, &mut reply),
+            "payload_json is NULL",
+        );
+        expect_err(
+            axon_client_send(
+                client,
+                to.as_ptr(),
+                kind.as_ptr(),
+                payload.as_ptr(),
+                std::ptr::null_mut(),
+            ),
+            "reply_out is NULL",
+        );
+        expect_err(
+            axon_client_poll_event(client, 0, std::ptr::null_mut()),
+            "event_out is NULL",
+        );
+    }
+    assert!(reply.is_null());
+
+    let mut event = std::ptr::null_mut();
+    assert_eq!(
+        unsafe { axon_client_poll_event(std::ptr::null_mut(), 0, &mut event) },
+        AXON_ERR
+    );
+    assert_eq!(last_error_text(), "client is NULL");
+    assert_eq!(
+        unsafe {
+            axon_client_on_event(
+                std::ptr::null_mut(),
+                Some(forward_event),
+                std::ptr::null_mut(),
+            )
+        },
+        AXON_ERR
+    );
+    assert_eq!(last_error_text(), "client is NULL");
+    // A NULL callback on a live client just removes any earlier one.
+    assert_eq!(
+        unsafe { axon_client_on_event(client, None, std::ptr::null_mut()) },
+        AXON_OK
+    );
+
+    // Releasing NULL is a no-op for both kinds of owned pointer.
+    unsafe {
+        axon_string_free(std::ptr::null_mut());
+        axon_client_free(std::ptr::null_mut());
+        axon_client_free(client);
+    }
+    server.join().unwrap();
+}
+
+#[test]
+fn invalid_utf8_arguments_are_named_in_the_error() {
+    let bad = CString::new(vec![b'a', 0xff, 0xfe]).unwrap();
+    let good = CString::new("{}").unwrap();
+    let mut reply = std::ptr::null_mut();
+
+    assert!(unsafe { axon_client_connect(bad.as_ptr()) }.is_null());
+    assert_eq!(last_error_text(), "socket_path is not valid UTF-8");
+
+    // Arguments are checked before the client, so a NULL one is fine here.
+    let client = std::ptr::null_mut();
+    assert_eq!(
+        unsafe { axon_client_command(client, bad.as_ptr(), &mut reply) },
+        AXON_ERR
+    );
+    assert_eq!(last_error_text(), "command_json is not valid UTF-8");
+    let cases = [
+        (bad.as_ptr(), good.as_ptr(), good.as_ptr(), "to"),
+        (good.as_ptr(), bad.as_ptr(), good.as_ptr(), "kind"),
+        (good.as_ptr(), good.as_ptr(), bad.as_ptr(), "payload_json"),
+    ];
+    for (to, kind, payload, name) in cases {
+        assert_eq!(
+            unsafe { axon_client_send(client, to, kind, payload, &mut reply) },
+            AXON_ERR
+        );
+        assert_eq!(last_error_text(), format!("{name} is not valid UTF-8"));
+    }
+    assert!(reply.is_null());
+}
+
+#[test]
+fn return_codes_follow_the_reply_and_the_connection() {
+    let dir = tempdir().unwrap();
+    let socket = dir.path().join("axon.sock");
+    let server = stub_daemon(&socket, 1);
+    let client = connect(&socket);
+    let mut reply = std::ptr::null_mut();
+
+    let malformed = CString::new("{not json").unwrap();
+    assert_eq!(
+        unsafe { axon_client_command(client, malformed.as_ptr(), &mut reply) },
+        AXON_ERR
+    );
+    assert!(last_error_text().starts_with("command_json is not valid JSON"));
+    let array = CString::new("[1]").unwrap();
+    assert_eq!(
+        unsafe { axon_client_command(client, array.as_ptr(), &mut reply) },
+        AXON_ERR
+    );
+    assert_eq!(last_error_text(), "IPC command must be a JSON object");
+    let to = CString::new("ed25519.00000000000000000000000000000000").unwrap();
+    let kind = CString::new("message").unwrap();
+    assert_eq!(
+        unsafe {
+            axon_client_send(
+                client,
+                to.as_ptr(),
+                kind.as_ptr(),
+                malformed.as_ptr(),
+                &mut reply,
+            )
+        },
+        AXON_ERR
+    );
+    assert!(last_error_text().starts_with("payload_json is not valid JSON"));
+    assert!(reply.is_null(), "failures leave reply_out untouched");
+
+    // A daemon-side failure is still a delivered reply.
+    let command = CString::new(r#"{"cmd":"status"}"#).unwrap();
+    assert_eq!(
+        unsafe { axon_client_command(client, command.as_ptr(), &mut reply) },
+        AXON_OK
+    );
+    let answered = take_string(reply);
+    assert_eq!(answered["ok"], false);
+    assert_eq!(answered["cmd"], "status");
+
+    // The stub hangs up after one reply: the inbox ends, then commands fail.
+    server.join().unwrap();
+    let mut event = std::ptr::null_mut();
+    assert_eq!(
+        unsafe { axon_client_poll_event(client, 5000, &mut event) },
+        AXON_CLOSED
+    );
+    assert!(event.is_null());
+    reply = std::ptr::null_mut();
+    assert_eq!(
+        unsafe { axon_client_command(client, command.as_ptr(), &mut reply) },
+        AXON_ERR
+    );
+    assert_eq!(last_error_text(), "not connected to the daemon");
+    assert!(reply.is_null());
+    unsafe { axon_client_free(client) };
+}
+
+#[test]
+fn returned_strings_are_owned_by_the_caller() {
+    let dir = tempdir().unwrap();
+    let socket = dir.path().join("axon.sock");
+    let server = stub_daemon(&socket, 2);
+    let client = connect(&socket);
+    let status = CString::new(r#"{"cmd":"status"}"#).unwrap();
+    let whoami = CString::new(r#"{"cmd":"whoami"}"#).unwrap();
+    let mut first = std::ptr::null_mut();
+    let mut second = std::ptr::null_mut();
+    unsafe {
+        assert_eq!(
+            axon_client_command(client, status.as_ptr(), &mut first),
+            AXON_OK
+        );
+        assert_eq!(
+            axon_client_command(client, whoami.as_ptr(), &mut second),
+            AXON_OK
+        );
+    }
+    // Each reply is its own allocation: freeing one leaves the other intact,
+    // and each is freed exactly once (a second free is undefined behaviour).
+    assert_ne!(first, second);
+    assert_eq!(take_string(first)["cmd"], "status");
+    assert_eq!(take_string(second)["cmd"], "whoami");
+    // The client outlives the daemon connection and is still freed once.
+    server.join().unwrap();
+    unsafe { axon_client_free(client) };
+}
+
+#[test]
+fn last_error_is_per_thread_and_owned_by_the_library() {
+    let mut reply = std::ptr::null_mut();
+    let command = CString::new(r#"{"cmd":"status"}"#).unwrap();
+    assert_eq!(
+        unsafe { axon_client_command(std::ptr::null_mut(), command.as_ptr(), &mut reply) },
+        AXON_ERR
+    );
+    let main_error = axon_last_error();
+
+    std::thread::spawn(|| {
+        assert!(axon_last_error().is_null(), "no failure on this thread yet");
+        let mut event = std::ptr::null_mut();
+        assert_eq!(
+            unsafe { axon_client_poll_event(std::ptr::null_mut(), 0, &mut event) },
+            AXON_ERR
+        );
+        assert_eq!(last_error_text(), "client is NULL");
+    })
+    .join()
+    .unwrap();
+
+    // Another thread's failure neither moves nor frees this thread's message,
+    // and reading it twice returns the same library-owned pointer.
+    assert_eq!(axon_last_error(), main_error);
+    assert_eq!(last_error_text(), "client is NULL");
+    assert_eq!(axon_last_error(), axon_last_error());
+}
@@ -0,0 +1,102 @@
+use std::time::Duration;
+
+use super::*;
+
+/// What a re-entrant callback needs: the client it was registered on, where
+/// to report, and whether to free the client once it has run.
+struct Reentry {
+    client: *mut AxonClient,
+    tx: mpsc::Sender<String>,
+    free: bool,
+}
+
+unsafe extern "C" fn call_back_in(_event_json: *const c_char, user_data: *mut c_void) {
+    // SAFETY: the test passes a live `Reentry`.
+    let reentry = unsafe { &*(user_data as *const Reentry) };
+    let command = CString::new(r#"{"cmd":"status"}"#).unwrap();
+    let mut reply = std::ptr::null_mut();
+    let code = unsafe { axon_client_command(reentry.client, command.as_ptr(), &mut reply) };
+    let outcome = match code {
+        AXON_OK => take_string(reply)["cmd"].to_string(),
+        _ => last_error_text(),
+    };
+    if reentry.free {
+        unsafe { axon_client_free(reentry.client) };
+    }
+    let _ = reentry.tx.send(outcome);
+}
+
+/// Accept one connection on `socket`, send `events` events once `go` fires,
+/// then answer every command with an `"ok": true` reply until hung up on.
+fn event_source(socket: &Path, events: usize, go: mpsc::Receiver<()>) -> JoinHandle<()> {
+    let listener = UnixListener::bind(socket).unwrap();
+    std::thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut writer = stream.try_clone().unwrap();
+        go.recv().unwrap();
+        for n in 0..events {
+            writeln!(writer, r#"{{"event":"inbound","n":{n}}}"#).unwrap();
+        }
+        for line in BufReader::new(stream).lines() {
+            let Ok(line) = line else { break };
+            let command: Value = serde_json::from_str(&line).unwrap();
+            if writeln!(writer, r#"{{"ok":true,"cmd":{}}}"#, command["cmd"]).is_err() {
+                break;
+            }
+        }
+    })
+}
+
+#[test]
+fn callback_may_call_back_into_the_client() {
+    let dir = tempdir().unwrap();
+    let socket = dir.path().join("axon.sock");
+    let (go_tx, go_rx) = mpsc::channel();
+    let server = event_source(&socket, 1, go_rx);
+    let client = connect(&socket);
+    let (tx, rx) = mpsc::channel();
+    let reentry = Reentry {
+        client,
+        tx,
+        free: false,
+    };
+    let user_data = &reentry as *const Reentry as *mut c_void;
+    assert_eq!(
+        unsafe { axon_client_on_event(client, Some(call_back_in), user_data) },
+        AXON_OK
+    );
+    go_tx.send(()).unwrap();
+
+    let outcome = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert_eq!(outcome, r#""status""#);
+    // Freeing from outside waits for the event thread, which is idle again.
+    unsafe { axon_client_free(client) };
+    server.join().unwrap();
+}
+
+#[test]
+fn callback_may_free_the_client() {
+    let dir = tempdir().unwrap();
+    let socket = dir.path().join("axon.sock");
+    let (go_tx, go_rx) = mpsc::channel();
+    let server = event_source(&socket, 2, go_rx);
+    let client = connect(&socket);
+    let (tx, rx) = mpsc::channel();
+    let reentry = Reentry {
+        client,
+        tx,
+        free: true,
+    };
+    let user_data = &reentry as *const Reentry as *mut c_void;
+    assert_eq!(
+        unsafe { axon_client_on_event(client, Some(call_back_in), user_data) },
+        AXON_OK
+    );
+    go_tx.send(()).unwrap();
+
+    let outcome = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert_eq!(outcome, r#""status""#);
+    // The client is gone, so the second event is never delivered.
+    server.join().unwrap();
+    assert!(rx.recv_timeout(Duration::from_millis(200)).is_err());
+}
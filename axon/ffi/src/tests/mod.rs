@@ -0,0 +1,164 @@
+use std::ffi::{CStr, CString};
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::UnixListener;
+use std::path::Path;
+use std::sync::mpsc;
+use std::thread::JoinHandle;
+
+use serde_json::Value;
+use tempfile::tempdir;
+
+use super::*;
+
+mod arguments;
+mod callbacks;
+
+fn take_string(ptr: *mut c_char) -> Value {
+    // SAFETY: `ptr` was just returned by this library.
+    let text = unsafe { CStr::from_ptr(ptr) }.to_str().unwrap().to_string();
+    unsafe { axon_string_free(ptr) };
+    serde_json::from_str(&text).unwrap()
+}
+
+fn last_error_text() -> String {
+    // SAFETY: set by the failing call on this thread.
+    unsafe { CStr::from_ptr(axon_last_error()) }
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Accept one connection on `socket`, answer `replies` commands with an
+/// `"ok": false` reply naming the command, then hang up.
+fn stub_daemon(socket: &Path, replies: usize) -> JoinHandle<()> {
+    let listener = UnixListener::bind(socket).unwrap();
+    std::thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut writer = stream.try_clone().unwrap();
+        let mut lines = BufReader::new(stream).lines();
+        for _ in 0..replies {
+            let command: Value = serde_json::from_str(&lines.next().unwrap().unwrap()).unwrap();
+            writeln!(
+                writer,
+                r#"{{"ok":false,"error":"invalid_command","cmd":{}}}"#,
+                command["cmd"]
+            )
+            .unwrap();
+        }
+    })
+}
+
+fn connect(socket: &Path) -> *mut AxonClient {
+    let path = CString::new(socket.to_str().unwrap()).unwrap();
+    let client = unsafe { axon_client_connect(path.as_ptr()) };
+    assert!(!client.is_null(), "{}", last_error_text());
+    client
+}
+
+unsafe extern "C" fn forward_event(event_json: *const c_char, user_data: *mut c_void) {
+    // SAFETY: the test passes a live `mpsc::Sender<String>`.
+    let tx = unsafe { &*(user_data as *const mpsc::Sender<String>) };
+    let event = unsafe { CStr::from_ptr(event_json) };
+    let _ = tx.send(event.to_string_lossy().into_owned());
+}
+
+#[test]
+fn command_send_poll_and_callback_round_trip() {
+    let dir = tempdir().unwrap();
+    let socket = dir.path().join("axon.sock");
+    let listener = UnixListener::bind(&socket).unwrap();
+    let (go_tx, go_rx) = mpsc::channel::<()>();
+    let server = std::thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut writer = stream.try_clone().unwrap();
+        let mut lines = BufReader::new(stream).lines();
+        let mut commands = Vec::new();
+        for _ in 0..2 {
+            let command: Value = serde_json::from_str(&lines.next().unwrap().unwrap()).unwrap();
+            writeln!(writer, r#"{{"ok":true,"cmd":{}}}"#, command["cmd"]).unwrap();
+            commands.push(command);
+        }
+        go_rx.recv().unwrap();
+        writeln!(writer, r#"{{"event":"inbound","n":1}}"#).unwrap();
+        commands
+    });
+
+    let path = CString::new(socket.to_str().unwrap()).unwrap();
+    let client = unsafe { axon_client_connect(path.as_ptr()) };
+    assert!(!client.is_null(), "{}", last_error_text());
+
+    let mut reply = std::ptr::null_mut();
+    let command = CString::new(r#"{"cmd":"status"}"#).unwrap();
+    assert_eq!(
+        unsafe { axon_client_command(client, command.as_ptr(), &mut reply) },
+        AXON_OK
+    );
+    assert_eq!(take_string(reply)["cmd"], "status");
+
+    let to = CString::new("ed25519.00000000000000000000000000000000").unwrap();
+    let kind = CString::new("message").unwrap();
+    let payload = CString::new(r#"{"hello":"world"}"#).unwrap();
+    assert_eq!(
+        unsafe {
+            axon_client_send(
+                client,
+                to.as_ptr(),
+                kind.as_ptr(),
+                payload.as_ptr(),
+                &mut reply,
+            )
+        },
+        AXON_OK
+    );
+    assert_eq!(take_string(reply)["cmd"], "send");
+
+    let mut event = std::ptr::null_mut();
+    assert_eq!(
+        unsafe { axon_client_poll_event(client, 0, &mut event) },
+        AXON_TIMEOUT
+    );
+
+    let (tx, rx) = mpsc::channel::<String>();
+    let user_data = &tx as *const mpsc::Sender<String> as *mut c_void;
+    assert_eq!(
+        unsafe { axon_client_on_event(client, Some(forward_event), user_data) },
+        AXON_OK
+    );
+    go_tx.send(()).unwrap();
+    let commands = server.join().unwrap();
+    assert_eq!(commands[1]["payload"]["hello"], "world");
+
+    let delivered: Value = serde_json::from_str(&rx.recv().unwrap()).unwrap();
+    assert_eq!(delivered["n"], 1);
+    assert_eq!(
+        unsafe { axon_client_poll_event(client, 1000, &mut event) },
+        AXON_OK
+    );
+    assert_eq!(take_string(event)["event"], "inbound");
+    assert_eq!(
+        unsafe { axon_client_poll_event(client, 1000, &mut event) },
+        AXON_CLOSED
+    );
+    unsafe { axon_client_free(client) };
+}
+
+#[test]
+fn failures_set_the_last_error() {
+    let dir = tempdir().unwrap();
+    let missing = CString::new(dir.path().join("none.sock").to_str().unwrap()).unwrap();
+    assert!(unsafe { axon_client_connect(missing.as_ptr()) }.is_null());
+    assert!(last_error_text().contains("failed to connect"));
+
+    let mut reply = std::ptr::null_mut();
+    let command = CString::new(r#"{"cmd":"status"}"#).unwrap();
+    assert_eq!(
+        unsafe { axon_client_command(std::ptr::null_mut(), command.as_ptr(), &mut reply) },
+        AXON_ERR
+    );
+    assert_eq!(last_error_text(), "client is NULL");
+
+    assert_eq!(
+        unsafe { axon_client_command(std::ptr::null_mut(), std::ptr::null(), &mut reply) },
+        AXON_ERR
+    );
+    assert_eq!(last_error_text(), "command_json is NULL");
+}
@@ -0,0 +1,18 @@
+//! Fuzz target: import arbitrary input as an identity backup.
+//! Uses lossy UTF-8 conversion since backups are JSON text. A fixed
+//! passphrase drives encrypted inputs through key derivation and decryption;
+//! the iteration ceiling bounds how long one input can take.
+//! Must not panic regardless of input.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use axon::identity::Identity;
+
+fuzz_target!(|data: &[u8]| {
+    let text = String::from_utf8_lossy(data);
+    let _ = Identity::backup_is_encrypted(&text);
+    let _ = Identity::from_backup(&text, None);
+    let _ = Identity::from_backup(&text, Some("fuzz passphrase"));
+});
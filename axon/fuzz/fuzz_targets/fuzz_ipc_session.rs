@@ -50,6 +50,7 @@ fuzz_target!(|data: &[u8]| {
             version: "0.5.0".to_string(),
             max_client_queue: 64,
             uptime_secs: Arc::new(|| 0),
+            ..IpcServerConfig::default()
         };
 
         let Ok((server, _rx)) = IpcServer::bind(socket_path.clone(), 8, config).await else {
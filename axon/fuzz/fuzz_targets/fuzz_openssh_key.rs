@@ -0,0 +1,14 @@
+//! Fuzz target: import arbitrary input as an OpenSSH ed25519 private key.
+//! Uses lossy UTF-8 conversion since key files are text-based.
+//! Must not panic regardless of input.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use axon::identity::Identity;
+
+fuzz_target!(|data: &[u8]| {
+    let text = String::from_utf8_lossy(data);
+    let _ = Identity::from_openssh(&text);
+});
@@ -0,0 +1,26 @@
+//! Fuzz target: decode arbitrary input as an `axon://` peer token.
+//! Each input is decoded as-is and again as the query of a token with a valid
+//! pubkey and address, so `exp`/`sig` parsing and signature checks are reached.
+//! Must not panic regardless of input.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use axon::peer_token;
+
+/// Public key of the all-zero ed25519 seed, base64url without padding.
+const PUBKEY: &str = "O2onvM62pC1io6jQKm8Nc2UyFXcd4kOmOsBIoYtZ2ik";
+
+fuzz_target!(|data: &[u8]| {
+    let text = String::from_utf8_lossy(data);
+    for token in [
+        text.to_string(),
+        format!("axon://{PUBKEY}@127.0.0.1:7100?{text}"),
+    ] {
+        if let Ok(decoded) = peer_token::decode(&token) {
+            let _ = decoded.ensure_signed();
+            let _ = decoded.ensure_unexpired(u64::MAX);
+        }
+    }
+});
@@ -0,0 +1,14 @@
+//! Fuzz target: load arbitrary input as an `axon record` recording.
+//! Uses lossy UTF-8 conversion since recordings are JSON lines.
+//! Must not panic regardless of input.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use axon::ipc::parse_recording;
+
+fuzz_target!(|data: &[u8]| {
+    let text = String::from_utf8_lossy(data);
+    let _ = parse_recording(&text);
+});
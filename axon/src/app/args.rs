@@ -0,0 +1,263 @@
+//! Command-line grammar: the `axon` parser and its subcommands.
+
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand};
+
+use super::{cli, doctor};
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "axon",
+    about = "AXON — Agent eXchange Over Network",
+    version = env!("CARGO_PKG_VERSION"),
+    propagate_version = true
+)]
+pub(crate) struct Cli {
+    /// AXON state root directory (socket/identity/config/known_peers).
+    /// Falls back to AXON_ROOT, then ~/.axon.
+    #[arg(
+        long = "state-root",
+        visible_aliases = ["state", "root"],
+        global = true,
+        value_name = "DIR"
+    )]
+    pub state_root: Option<PathBuf>,
+
+    /// Use the named profile from `profiles` in config.yaml: its port, name,
+    /// peers, and identity, with its own socket and caches under
+    /// `<state root>/profiles/<name>/`.
+    #[arg(long, global = true, env = "AXON_PROFILE", value_name = "NAME")]
+    pub profile: Option<String>,
+
+    /// Increase log verbosity (-v = debug, -vv = trace). Conflicts with --quiet.
+    #[arg(long, short = 'v', global = true, action = clap::ArgAction::Count, conflicts_with = "quiet")]
+    pub verbose: u8,
+
+    /// Suppress per-message logs (warn-level only); for `request`, `notify`,
+    /// `publish`, and `cancel`, also the JSON reply, leaving only the exit code. Conflicts with -v.
+    #[arg(long, short = 'q', global = true, conflicts_with = "verbose")]
+    pub quiet: bool,
+
+    /// Output format for `peers`, `status`, and `whoami`: `json` and `yaml`
+    /// print a versioned document (see spec/SPEC.md), `table` the default text.
+    #[arg(long, global = true, value_name = "FORMAT")]
+    pub output: Option<cli::output::OutputFormat>,
+
+    #[command(subcommand)]
+    pub command: Commands,
+}
+
+#[derive(Debug, Subcommand)]
+pub(crate) enum Commands {
+    /// Start the daemon (runs in foreground unless --detach).
+    Daemon {
+        #[arg(long)]
+        port: Option<u16>,
+        /// Disable mDNS discovery (use static peers only).
+        #[arg(long, env = "AXON_DISABLE_MDNS")]
+        disable_mdns: bool,
+        /// Run in the background; logs go to daemon.log under the state root.
+        #[arg(long)]
+        detach: bool,
+        /// Smoke-test the install: run a throwaway daemon and a probe peer on
+        /// loopback, exchange every message kind, and print a pass/fail report.
+        #[arg(long, conflicts_with = "detach")]
+        self_test: bool,
+    },
+    /// Stop the running daemon (IPC shutdown, SIGTERM fallback).
+    Stop {
+        /// Seconds to wait for the daemon to exit.
+        #[arg(long, value_name = "SECONDS", default_value_t = 10)]
+        timeout: u64,
+    },
+    /// Stop the running daemon (if any) and start it again in the background.
+    Restart {
+        #[arg(long)]
+        port: Option<u16>,
+        /// Disable mDNS discovery (use static peers only).
+        #[arg(long, env = "AXON_DISABLE_MDNS")]
+        disable_mdns: bool,
+        /// Seconds to wait for the old daemon to exit.
+        #[arg(long, value_name = "SECONDS", default_value_t = 10)]
+        timeout: u64,
+    },
+    /// Send a request to another agent and wait for a response.
+    Request(cli::send_cmd::RequestArgs),
+    /// Send a fire-and-forget message to another agent.
+    Notify(cli::send_cmd::NotifyArgs),
+    /// Subscribe to topics published by peers (replaces the current set;
+    /// no topics clears it). Patterns may end in `.*`, or be `*` for all.
+    Subscribe {
+        #[arg(value_name = "TOPIC")]
+        topics: Vec<String>,
+    },
+    /// Send a fire-and-forget message to every peer subscribed to a topic.
+    Publish(cli::send_cmd::PublishArgs),
+    /// Ask another agent to abort work started by an earlier request.
+    ///
+    /// Sent as a fire-and-forget message whose `ref` is the request ID and
+    /// whose payload is {"cancel":true} (plus "reason" when given).
+    Cancel(cli::send_cmd::CancelArgs),
+    /// Measure request throughput and latency to a peer through the daemon.
+    Bench(cli::bench::BenchArgs),
+    /// List discovered and connected peers, tag them, or export/import the
+    /// static peer set.
+    #[command(args_conflicts_with_subcommands = true)]
+    Peers {
+        #[command(subcommand)]
+        command: Option<cli::peer_tags::PeersCommand>,
+        /// Only list peers carrying this tag.
+        #[arg(long, value_name = "TAG")]
+        tag: Option<String>,
+        /// Print machine-readable JSON.
+        #[arg(long)]
+        json: bool,
+        /// Keep the table on screen and update it as peers connect,
+        /// disconnect, are discovered, or are lost (Ctrl-C to quit).
+        #[arg(long, conflicts_with = "json")]
+        watch: bool,
+    },
+    /// Show daemon status.
+    Status {
+        /// Print machine-readable JSON.
+        #[arg(long)]
+        json: bool,
+    },
+    /// Check daemon subsystem readiness (exit 2 when unhealthy).
+    Health {
+        /// Print machine-readable JSON.
+        #[arg(long)]
+        json: bool,
+    },
+    /// List source addresses temporarily banned for repeated handshake failures.
+    Bans {
+        /// Print machine-readable JSON.
+        #[arg(long)]
+        json: bool,
+    },
+    /// Ask the running daemon to re-read config.yaml (same as SIGHUP).
+    Reload {
+        /// Print machine-readable JSON.
+        #[arg(long)]
+        json: bool,
+    },
+    /// Check the integrity of the audit log (requires audit.enabled).
+    Audit(cli::audit_cmd::AuditArgs),
+    /// Show sent and received envelopes recorded by the daemon (requires history.enabled).
+    History(cli::history_args::HistoryArgs),
+    /// Stream inbound messages as they arrive (Ctrl-C to stop).
+    Watch(cli::watch::WatchArgs),
+    /// Capture IPC commands, envelopes, and connection events to a file (Ctrl-C to stop).
+    Record(cli::record::RecordArgs),
+    /// Re-issue the IPC commands from an `axon record` file against the daemon.
+    Replay(cli::record::ReplayArgs),
+    /// Interactive prompt: send, notify, peers, status, with inbound messages shown inline.
+    Shell,
+    /// Serve the Model Context Protocol on stdin/stdout so MCP clients can
+    /// message peers (tools: send_request, notify, list_peers, read_inbox).
+    Mcp,
+    /// Live dashboard of peers, message rates, and recent inbound messages (Ctrl-C to quit).
+    Top(cli::top::TopArgs),
+    /// Print this agent's identity, or export/import it as a backup file.
+    Identity(cli::identity_cmd::IdentityArgs),
+    /// Enroll a peer from an `axon://` token.
+    Connect(cli::connect_cmd::ConnectArgs),
+    /// List, show, or clear pinned peer keys (e.g. after a peer is reinstalled).
+    Pins(cli::pins_cmd::PinsArgs),
+    /// Revoke a peer's key so it is never accepted again, even via discovery or a token.
+    Revoke(cli::revoke_cmd::RevokeArgs),
+    /// Disconnect a peer and refuse it until `axon unblock`; config.yaml is left alone.
+    Block(cli::block_cmd::BlockArgs),
+    /// Lift a block; static peers return on `axon reload`, discovered ones when next announced.
+    Unblock(cli::block_cmd::BlockArgs),
+    /// List blocked peers.
+    Blocked {
+        /// Print machine-readable JSON.
+        #[arg(long)]
+        json: bool,
+    },
+    /// Name agents so commands accept `<alias>` in place of an agent ID.
+    Alias(cli::alias::AliasArgs),
+    /// Show everything the daemon knows about one peer.
+    Whois {
+        #[arg(value_parser = cli::alias::parse_agent_ref_arg)]
+        agent_id: String,
+        /// Print machine-readable JSON.
+        #[arg(long)]
+        json: bool,
+    },
+    /// Print running daemon identity and metadata via IPC.
+    Whoami {
+        /// Print machine-readable JSON.
+        #[arg(long)]
+        json: bool,
+    },
+    /// Diagnose local AXON state and optionally apply safe repairs.
+    Doctor(doctor::DoctorArgs),
+    /// Read/write scalar config values.
+    Config(cli::config_cmd::ConfigArgs),
+    /// Delete local runtime state (peer cache, history, stats, socket) and back up the identity.
+    Reset(cli::reset::ResetArgs),
+    /// Install the daemon as a system service.
+    Service(cli::service_cmd::ServiceArgs),
+    /// Print example interactions.
+    Examples,
+    /// Generate shell completions and man page (internal, for packaging).
+    #[cfg(feature = "generate-docs")]
+    #[command(hide = true)]
+    GenDocs {
+        /// Output directory (creates completions/ and man/ inside it).
+        #[arg(long, value_name = "DIR")]
+        out_dir: PathBuf,
+    },
+}
+
+impl Cli {
+    /// Whether stdout carries a protocol or a single JSON document, so logs
+    /// must go to stderr.
+    pub(crate) fn stdout_is_protocol(&self) -> bool {
+        match &self.command {
+            Commands::Mcp => true,
+            Commands::Doctor(args) => args.json,
+            _ => false,
+        }
+    }
+}
+
+impl Commands {
+    /// Arguments parsed by `parse_agent_ref_arg` that may still hold an alias.
+    pub(crate) fn agent_refs_mut(&mut self) -> Vec<&mut String> {
+        match self {
+            Commands::Request(cli::send_cmd::RequestArgs { agent_id, .. })
+            | Commands::Notify(cli::send_cmd::NotifyArgs { agent_id, .. })
+            | Commands::Cancel(cli::send_cmd::CancelArgs { agent_id, .. })
+            | Commands::Whois { agent_id, .. } => vec![agent_id],
+            Commands::Bench(args) => vec![&mut args.agent_id],
+            Commands::Watch(args) => args.from.iter_mut().collect(),
+            Commands::History(args) => args.peer.iter_mut().collect(),
+            Commands::Peers {
+                command: Some(command),
+                ..
+            } => command.agent_id_mut().into_iter().collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl Commands {
+    /// The output schema and `--json` flag of commands that accept `--output`.
+    pub(crate) fn output_schema(&self) -> Option<(cli::output::Schema, bool)> {
+        match self {
+            Commands::Peers {
+                command: None,
+                json,
+                watch: false,
+                ..
+            } => Some((cli::output::Schema::Peers, *json)),
+            Commands::Status { json } => Some((cli::output::Schema::Status, *json)),
+            Commands::Whoami { json } => Some((cli::output::Schema::Whoami, *json)),
+            _ => None,
+        }
+    }
+}
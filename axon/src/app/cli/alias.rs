@@ -0,0 +1,134 @@
+use std::collections::BTreeMap;
+use std::process::ExitCode;
+
+use anyhow::{Result, bail};
+use axon::config::{
+    AxonPaths, MAX_ALIAS_LEN, is_valid_alias, load_persisted_config, save_persisted_config,
+};
+use axon::message::AgentId;
+use clap::{Args, Subcommand};
+use serde_json::{Value, json};
+
+use crate::app::run::{canonicalize_agent_id, parse_agent_id_arg};
+
+#[derive(Debug, Clone, Args)]
+pub struct AliasArgs {
+    #[command(subcommand)]
+    pub command: AliasCommand,
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum AliasCommand {
+    /// Name an agent; an existing alias with the same name is replaced.
+    Set {
+        #[arg(value_parser = parse_agent_id_arg)]
+        agent_id: String,
+        #[arg(value_parser = parse_alias_name)]
+        name: String,
+    },
+    /// Remove an alias.
+    Remove { name: String },
+    /// List aliases.
+    List {
+        /// Print machine-readable JSON.
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+pub async fn run(paths: &AxonPaths, args: AliasArgs) -> Result<ExitCode> {
+    let mut persisted = load_persisted_config(&paths.config).await?;
+    match args.command {
+        AliasCommand::Set { agent_id, name } => {
+            persisted.aliases.insert(name, AgentId::from(agent_id));
+            save_persisted_config(&paths.config, &persisted).await?;
+        }
+        AliasCommand::Remove { name } => {
+            if persisted.aliases.remove(&name).is_none() {
+                bail!("no alias named '{name}'");
+            }
+            save_persisted_config(&paths.config, &persisted).await?;
+        }
+        AliasCommand::List { json } => {
+            if json {
+                println!("{}", serde_json::to_string_pretty(&persisted.aliases)?);
+            } else {
+                for (name, agent_id) in &persisted.aliases {
+                    println!("{name}\t{agent_id}");
+                }
+            }
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+pub(crate) fn parse_alias_name(input: &str) -> Result<String, String> {
+    if is_valid_alias(input) {
+        Ok(input.to_string())
+    } else {
+        Err(format!(
+            "invalid alias '{input}'; expected a letter followed by letters, digits, '-' or '_' \
+             (at most {MAX_ALIAS_LEN} characters)"
+        ))
+    }
+}
+
+/// Clap parser for arguments that take an agent ID or an alias. Agent IDs
+/// are canonicalized; alias names pass through for [`resolve_agent_ref`].
+pub(crate) fn parse_agent_ref_arg(input: &str) -> Result<String, String> {
+    if let Some(agent_id) = canonicalize_agent_id(input) {
+        return Ok(agent_id);
+    }
+    if is_valid_alias(input) {
+        return Ok(input.to_string());
+    }
+    Err(format!(
+        "invalid agent_id '{input}'; expected format ed25519.<32 hex> or an alias"
+    ))
+}
+
+/// Map an argument accepted by [`parse_agent_ref_arg`] to an agent ID.
+pub(crate) fn resolve_agent_ref(
+    input: &str,
+    aliases: &BTreeMap<String, AgentId>,
+) -> Result<String, String> {
+    if let Some(agent_id) = canonicalize_agent_id(input) {
+        return Ok(agent_id);
+    }
+    aliases
+        .get(input)
+        .map(|agent_id| agent_id.to_string())
+        .ok_or_else(|| {
+            format!(
+                "invalid agent_id '{input}'; expected format ed25519.<32 hex> or an alias \
+                 (see `axon alias list`)"
+            )
+        })
+}
+
+/// Add `"alias"` to each entry of a `peers` reply whose agent has one.
+pub(crate) fn annotate_peers(response: &mut Value, aliases: &BTreeMap<String, AgentId>) {
+    let Some(peers) = response.get_mut("peers").and_then(Value::as_array_mut) else {
+        return;
+    };
+    for peer in peers {
+        annotate_peer(peer, aliases);
+    }
+}
+
+/// Add `"alias"` to one peer object (a `peers` entry or the `whois` peer).
+pub(crate) fn annotate_peer(peer: &mut Value, aliases: &BTreeMap<String, AgentId>) {
+    let Some(agent_id) = peer.get("agent_id").and_then(Value::as_str) else {
+        return;
+    };
+    if let Some(name) = aliases
+        .iter()
+        .find_map(|(name, id)| (id == agent_id).then_some(name))
+    {
+        peer["alias"] = json!(name);
+    }
+}
+
+#[cfg(test)]
+#[path = "alias_tests.rs"]
+mod tests;
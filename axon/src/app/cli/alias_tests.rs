@@ -0,0 +1,47 @@
+use std::collections::BTreeMap;
+
+use axon::message::AgentId;
+use serde_json::json;
+
+use super::{annotate_peers, parse_agent_ref_arg, resolve_agent_ref};
+
+const AGENT_A: &str = "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+const AGENT_B: &str = "ed25519.bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb";
+
+fn aliases() -> BTreeMap<String, AgentId> {
+    BTreeMap::from([("laptop".to_string(), AgentId::from(AGENT_A))])
+}
+
+#[test]
+fn agent_ref_arg_accepts_ids_and_alias_names() {
+    assert_eq!(
+        parse_agent_ref_arg("ED25519.AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA").as_deref(),
+        Ok(AGENT_A)
+    );
+    assert_eq!(parse_agent_ref_arg("laptop").as_deref(), Ok("laptop"));
+    assert!(parse_agent_ref_arg("ed25519.short").is_err());
+    assert!(parse_agent_ref_arg("not an alias").is_err());
+}
+
+#[test]
+fn resolve_maps_known_aliases_only() {
+    let aliases = aliases();
+    assert_eq!(
+        resolve_agent_ref("laptop", &aliases).as_deref(),
+        Ok(AGENT_A)
+    );
+    assert_eq!(resolve_agent_ref(AGENT_B, &aliases).as_deref(), Ok(AGENT_B));
+    let err = resolve_agent_ref("desktop", &aliases).unwrap_err();
+    assert!(err.contains("invalid agent_id 'desktop'"));
+}
+
+#[test]
+fn annotate_peers_adds_alias_to_matching_entries() {
+    let mut response = json!({
+        "ok": true,
+        "peers": [{"agent_id": AGENT_A}, {"agent_id": AGENT_B}]
+    });
+    annotate_peers(&mut response, &aliases());
+    assert_eq!(response["peers"][0]["alias"], "laptop");
+    assert!(response["peers"][1].get("alias").is_none());
+}
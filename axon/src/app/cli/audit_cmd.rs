@@ -0,0 +1,110 @@
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use anyhow::{Context, Result, anyhow};
+use axon::config::AxonPaths;
+use axon::daemon::{AuditVerification, verify_audit_log};
+use base64::{Engine as _, engine::general_purpose::STANDARD};
+use clap::{Args, Subcommand};
+use ed25519_dalek::VerifyingKey;
+
+#[derive(Debug, Clone, Args)]
+pub struct AuditArgs {
+    #[command(subcommand)]
+    pub command: AuditCommand,
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum AuditCommand {
+    /// Check the audit log's hash chain and checkpoint signatures (exit 1 if tampered).
+    Verify {
+        /// Audit log to check, with its rotated files (default: audit.jsonl in the state root).
+        #[arg(long, value_name = "PATH")]
+        file: Option<PathBuf>,
+        /// Base64 public key that signed the log (default: this agent's identity.pub).
+        #[arg(long, value_name = "BASE64")]
+        pubkey: Option<String>,
+        /// Print the verification report as JSON.
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+pub fn run(paths: &AxonPaths, args: &AuditArgs) -> Result<ExitCode> {
+    let AuditCommand::Verify { file, pubkey, json } = &args.command;
+    let pubkey = match pubkey {
+        Some(pubkey) => pubkey.clone(),
+        None => std::fs::read_to_string(&paths.identity_pub).with_context(|| {
+            format!(
+                "failed to read {}; pass --pubkey to verify another agent's log",
+                paths.identity_pub.display()
+            )
+        })?,
+    };
+    let key = decode_verifying_key(&pubkey)?;
+    let path = file.as_ref().unwrap_or(&paths.audit);
+    let report = verify_audit_log(path, &key)?;
+
+    if *json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        println!("{}", render_report(&report));
+    }
+    Ok(if report.ok() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::from(1)
+    })
+}
+
+fn decode_verifying_key(pubkey: &str) -> Result<VerifyingKey> {
+    let bytes = STANDARD
+        .decode(pubkey.trim())
+        .context("public key is not valid base64")?;
+    let bytes: [u8; 32] = bytes.try_into().map_err(|bytes: Vec<u8>| {
+        anyhow!("public key must decode to 32 bytes, got {}", bytes.len())
+    })?;
+    VerifyingKey::from_bytes(&bytes).context("not a valid Ed25519 public key")
+}
+
+fn render_report(report: &AuditVerification) -> String {
+    let mut lines = Vec::new();
+    if report.ok() {
+        lines.push(format!(
+            "✓ audit log intact: {} records in {} file(s), {} checkpoint(s)",
+            report.records,
+            report.files.len(),
+            report.checkpoints
+        ));
+    } else {
+        lines.push(format!(
+            "✗ audit log failed verification ({} problem(s)):",
+            report.problems.len()
+        ));
+        lines.extend(report.problems.iter().map(|problem| format!("  {problem}")));
+    }
+    if let (Some(first), Some(last)) = (report.first_seq, report.last_seq) {
+        lines.push(format!("  seq {first}..{last}"));
+    }
+    match report.last_signed_seq {
+        Some(seq) => lines.push(format!("  last signed checkpoint: seq {seq}")),
+        None if report.records > 0 => lines.push("  no signed checkpoint yet".to_string()),
+        None => {}
+    }
+    if report.unsigned_tail > 0 {
+        lines.push(format!(
+            "  {} record(s) after the last checkpoint are not yet signed",
+            report.unsigned_tail
+        ));
+    }
+    if report.records > 0 && !report.starts_at_genesis {
+        lines.push("  older records were rotated away or predate hash chaining".to_string());
+    }
+    if report.legacy_records > 0 {
+        lines.push(format!(
+            "  {} record(s) predate hash chaining and were not checked",
+            report.legacy_records
+        ));
+    }
+    lines.join("\n")
+}
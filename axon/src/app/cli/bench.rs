@@ -0,0 +1,246 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use axon::config::AxonPaths;
+use clap::Args;
+use serde::Serialize;
+use serde_json::{Value, json};
+use tokio::task::JoinSet;
+
+use crate::app::cli::alias::parse_agent_ref_arg;
+use crate::app::cli::ipc_client::IpcConnection;
+
+/// Largest `--size`: leaves room for the envelope and IPC command inside the
+/// 64KB message and IPC line limits.
+pub const MAX_BENCH_PAYLOAD: usize = 60_000;
+/// Largest `--concurrency`: each worker holds an IPC connection and the
+/// daemon accepts at most 64.
+pub const MAX_BENCH_CONCURRENCY: u64 = 32;
+
+#[derive(Debug, Clone, Args)]
+pub struct BenchArgs {
+    #[arg(value_parser = parse_agent_ref_arg)]
+    pub agent_id: String,
+    /// Payload size in bytes (the length of the `message` string).
+    #[arg(long, value_name = "BYTES", default_value_t = 64, value_parser = parse_size)]
+    pub size: usize,
+    /// Total number of requests.
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = 100,
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    pub count: u64,
+    /// Requests in flight at once, each on its own IPC connection.
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = 1,
+        value_parser = clap::value_parser!(u64).range(1..=MAX_BENCH_CONCURRENCY)
+    )]
+    pub concurrency: u64,
+    /// Per-request timeout in seconds.
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        default_value_t = 30,
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    pub timeout: u64,
+    /// Print the report as JSON.
+    #[arg(long)]
+    pub json: bool,
+}
+
+fn parse_size(input: &str) -> Result<usize, String> {
+    let size: usize = input
+        .parse()
+        .map_err(|_| format!("invalid size '{input}'"))?;
+    if size > MAX_BENCH_PAYLOAD {
+        return Err(format!("size must be at most {MAX_BENCH_PAYLOAD} bytes"));
+    }
+    Ok(size)
+}
+
+/// Result of one request as seen by the CLI.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+    /// The peer answered with a `response`.
+    Ok(Duration),
+    /// The peer answered with an `error` envelope, or the daemon failed the
+    /// send; the label is the error code.
+    Failed(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LatencySummary {
+    pub min: f64,
+    pub p50: f64,
+    pub p95: f64,
+    pub p99: f64,
+    pub max: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BenchReport {
+    pub to: String,
+    pub size: usize,
+    pub concurrency: u64,
+    pub sent: u64,
+    pub ok: u64,
+    pub failed: u64,
+    /// Failure count per error code.
+    pub errors: BTreeMap<String, u64>,
+    pub elapsed_secs: f64,
+    /// Successful requests per second over the whole run.
+    pub throughput_rps: f64,
+    /// Round-trip latency of successful requests, in milliseconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<LatencySummary>,
+}
+
+impl BenchReport {
+    pub fn from_outcomes(args: &BenchArgs, outcomes: &[Outcome], elapsed: Duration) -> Self {
+        let mut latencies = Vec::new();
+        let mut errors = BTreeMap::new();
+        for outcome in outcomes {
+            match outcome {
+                Outcome::Ok(latency) => latencies.push(*latency),
+                Outcome::Failed(code) => *errors.entry(code.clone()).or_insert(0) += 1,
+            }
+        }
+        latencies.sort_unstable();
+        let ok = latencies.len() as u64;
+        let elapsed_secs = elapsed.as_secs_f64();
+        Self {
+            to: args.agent_id.clone(),
+            size: args.size,
+            concurrency: args.concurrency,
+            sent: outcomes.len() as u64,
+            ok,
+            failed: outcomes.len() as u64 - ok,
+            errors,
+            elapsed_secs,
+            throughput_rps: if elapsed_secs > 0.0 {
+                ok as f64 / elapsed_secs
+            } else {
+                0.0
+            },
+            latency_ms: (!latencies.is_empty()).then(|| LatencySummary {
+                min: millis(latencies[0]),
+                p50: millis(percentile(&latencies, 50)),
+                p95: millis(percentile(&latencies, 95)),
+                p99: millis(percentile(&latencies, 99)),
+                max: millis(latencies[latencies.len() - 1]),
+            }),
+        }
+    }
+
+    pub fn render_human(&self) -> String {
+        let mut out = format!(
+            "Sent {} requests of {} bytes to {} (concurrency {}) in {:.2}s\n\
+             ok: {}  failed: {}  throughput: {:.1} req/s",
+            self.sent,
+            self.size,
+            self.to,
+            self.concurrency,
+            self.elapsed_secs,
+            self.ok,
+            self.failed,
+            self.throughput_rps
+        );
+        if let Some(latency) = &self.latency_ms {
+            out.push_str(&format!(
+                "\nlatency ms: min {:.2}  p50 {:.2}  p95 {:.2}  p99 {:.2}  max {:.2}",
+                latency.min, latency.p50, latency.p95, latency.p99, latency.max
+            ));
+        }
+        for (code, count) in &self.errors {
+            out.push_str(&format!("\nerror {code}: {count}"));
+        }
+        out
+    }
+}
+
+/// Nearest-rank percentile of an ascending, non-empty slice.
+pub fn percentile(sorted: &[Duration], pct: usize) -> Duration {
+    let rank = (pct * sorted.len()).div_ceil(100).max(1);
+    sorted[rank.min(sorted.len()) - 1]
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+/// Classify a `send` reply for a request.
+pub fn classify_reply(reply: &Value, latency: Duration) -> Outcome {
+    if reply.get("ok") != Some(&json!(true)) {
+        let code = reply
+            .get("error")
+            .and_then(Value::as_str)
+            .unwrap_or("unknown");
+        return Outcome::Failed(code.to_string());
+    }
+    let response = reply.get("response");
+    if response.and_then(|r| r.get("kind")).and_then(Value::as_str) == Some("error") {
+        let code = response
+            .and_then(|r| r.get("payload"))
+            .and_then(|payload| payload.get("code"))
+            .and_then(Value::as_str)
+            .unwrap_or("unknown");
+        return Outcome::Failed(format!("remote:{code}"));
+    }
+    Outcome::Ok(latency)
+}
+
+/// Drive `count` requests through the daemon with `concurrency` workers.
+/// Connection failures abort the run; per-request failures are counted.
+pub async fn run(paths: &AxonPaths, args: &BenchArgs) -> Result<BenchReport> {
+    let command = json!({
+        "cmd": "send",
+        "to": args.agent_id,
+        "kind": "request",
+        "timeout_secs": args.timeout,
+        "payload": { "message": "x".repeat(args.size) },
+    });
+    let mut connections = Vec::new();
+    for _ in 0..args.concurrency.min(args.count) {
+        connections.push(IpcConnection::connect(paths).await?);
+    }
+
+    let next = Arc::new(AtomicU64::new(0));
+    let command = Arc::new(command);
+    let started = Instant::now();
+    let mut workers = JoinSet::new();
+    for mut connection in connections {
+        let next = next.clone();
+        let command = command.clone();
+        let count = args.count;
+        workers.spawn(async move {
+            let mut outcomes = Vec::new();
+            while next.fetch_add(1, Ordering::Relaxed) < count {
+                let sent = Instant::now();
+                let reply = connection.command(&command).await?;
+                outcomes.push(classify_reply(&reply, sent.elapsed()));
+            }
+            anyhow::Ok(outcomes)
+        });
+    }
+    let mut outcomes = Vec::new();
+    while let Some(worker) = workers.join_next().await {
+        outcomes.extend(worker.context("bench worker panicked")??);
+    }
+    Ok(BenchReport::from_outcomes(
+        args,
+        &outcomes,
+        started.elapsed(),
+    ))
+}
+
+#[cfg(test)]
+#[path = "bench_tests.rs"]
+mod tests;
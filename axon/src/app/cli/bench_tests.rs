@@ -0,0 +1,93 @@
+use super::*;
+use clap::Parser;
+
+#[derive(Debug, Parser)]
+struct TestCli {
+    #[command(flatten)]
+    bench: BenchArgs,
+}
+
+fn args(extra: &[&str]) -> Result<BenchArgs, clap::Error> {
+    let mut argv = vec!["bench", "ed25519.00000000000000000000000000000000"];
+    argv.extend_from_slice(extra);
+    TestCli::try_parse_from(argv).map(|cli| cli.bench)
+}
+
+fn ms(millis: u64) -> Duration {
+    Duration::from_millis(millis)
+}
+
+#[test]
+fn defaults_and_bounds() {
+    let parsed = args(&[]).expect("defaults");
+    assert_eq!(
+        (
+            parsed.size,
+            parsed.count,
+            parsed.concurrency,
+            parsed.timeout
+        ),
+        (64, 100, 1, 30)
+    );
+    assert!(args(&["--size", "60001"]).is_err());
+    assert!(args(&["--count", "0"]).is_err());
+    assert!(args(&["--concurrency", "33"]).is_err());
+}
+
+#[test]
+fn percentile_uses_nearest_rank() {
+    let samples: Vec<Duration> = (1..=100).map(ms).collect();
+    assert_eq!(percentile(&samples, 50), ms(50));
+    assert_eq!(percentile(&samples, 99), ms(99));
+    assert_eq!(percentile(&[ms(7)], 95), ms(7));
+    assert_eq!(percentile(&[ms(1), ms(2), ms(3)], 50), ms(2));
+}
+
+#[test]
+fn classify_reply_separates_daemon_and_remote_errors() {
+    let ok = json!({"ok": true, "response": {"kind": "response", "payload": {}}});
+    assert_eq!(classify_reply(&ok, ms(3)), Outcome::Ok(ms(3)));
+
+    let timeout = json!({"ok": false, "error": "timeout"});
+    assert_eq!(
+        classify_reply(&timeout, ms(3)),
+        Outcome::Failed("timeout".to_string())
+    );
+
+    let remote =
+        json!({"ok": true, "response": {"kind": "error", "payload": {"code": "unhandled"}}});
+    assert_eq!(
+        classify_reply(&remote, ms(3)),
+        Outcome::Failed("remote:unhandled".to_string())
+    );
+}
+
+#[test]
+fn report_counts_errors_and_summarizes_successes() {
+    let outcomes = vec![
+        Outcome::Ok(ms(10)),
+        Outcome::Ok(ms(30)),
+        Outcome::Ok(ms(20)),
+        Outcome::Failed("timeout".to_string()),
+        Outcome::Failed("timeout".to_string()),
+    ];
+    let report = BenchReport::from_outcomes(&args(&[]).unwrap(), &outcomes, Duration::from_secs(2));
+    assert_eq!((report.sent, report.ok, report.failed), (5, 3, 2));
+    assert_eq!(report.errors.get("timeout"), Some(&2));
+    assert_eq!(report.throughput_rps, 1.5);
+    let latency = report.latency_ms.as_ref().expect("latency");
+    assert_eq!((latency.min, latency.p50, latency.max), (10.0, 20.0, 30.0));
+
+    let rendered = report.render_human();
+    assert!(rendered.contains("ok: 3  failed: 2"));
+    assert!(rendered.contains("error timeout: 2"));
+}
+
+#[test]
+fn report_without_successes_has_no_latency() {
+    let outcomes = vec![Outcome::Failed("peer_unreachable".to_string())];
+    let report = BenchReport::from_outcomes(&args(&[]).unwrap(), &outcomes, ms(5));
+    assert!(report.latency_ms.is_none());
+    assert_eq!(report.throughput_rps, 0.0);
+    assert!(!report.render_human().contains("latency"));
+}
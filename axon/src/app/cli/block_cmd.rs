@@ -0,0 +1,98 @@
+use std::process::ExitCode;
+
+use anyhow::{Result, anyhow, bail};
+use axon::config::{
+    AxonPaths, BlockedPeer, add_block, load_blocklist, load_persisted_config, remove_block,
+};
+use axon::ipc::IpcErrorCode;
+use axon::message::now_millis;
+use clap::Args;
+use serde_json::{Value, json};
+
+use crate::app::cli::alias::resolve_agent_ref;
+use crate::app::cli::format::render_blocked_human;
+use crate::app::cli::ipc_client::{ResponseMode, daemon_reply_exit_code, render_json, send_ipc};
+use crate::app::cli::revoke_cmd::resolve_target;
+
+#[derive(Debug, Clone, Args)]
+pub struct BlockArgs {
+    /// Agent ID or alias of the peer.
+    pub peer: String,
+}
+
+/// Add a peer to `blocklist.json`, through the daemon when it is running so
+/// the peer is dropped and disconnected at once.
+pub async fn block(paths: &AxonPaths, args: &BlockArgs) -> Result<()> {
+    let target = resolve_target(paths, &args.peer).await?;
+
+    if paths.socket.exists() {
+        let response = send_ipc(
+            paths,
+            json!({"cmd": "block_peer", "agent_id": target.agent_id}),
+        )
+        .await?;
+        if response.get("ok") != Some(&json!(true)) {
+            let rendered =
+                serde_json::to_string_pretty(&response).unwrap_or_else(|_| response.to_string());
+            bail!("daemon rejected block.\nDaemon response: {rendered}");
+        }
+    } else {
+        add_block(
+            &paths.blocklist,
+            BlockedPeer {
+                agent_id: target.agent_id.clone(),
+                pubkey: target.pubkey,
+                blocked_at_unix_ms: now_millis(),
+            },
+        )
+        .await?;
+    }
+
+    println!("✓ Blocked {}", target.agent_id);
+    Ok(())
+}
+
+/// Remove a peer from `blocklist.json`, through the daemon when it is
+/// running so its handshakes are accepted again at once.
+pub async fn unblock(paths: &AxonPaths, args: &BlockArgs) -> Result<ExitCode> {
+    let aliases = load_persisted_config(&paths.config).await?.aliases;
+    let agent_id = resolve_agent_ref(&args.peer, &aliases).map_err(|err| anyhow!(err))?;
+
+    let response = if paths.socket.exists() {
+        send_ipc(paths, json!({"cmd": "unblock_peer", "agent_id": agent_id})).await?
+    } else {
+        match remove_block(&paths.blocklist, &agent_id).await? {
+            Some(entry) => json!({"ok": true, "agent_id": entry.agent_id, "pubkey": entry.pubkey}),
+            None => {
+                let error = IpcErrorCode::PeerNotFound;
+                json!({"ok": false, "error": error, "message": error.message()})
+            }
+        }
+    };
+
+    if response.get("ok") == Some(&json!(true)) {
+        println!("✓ Unblocked {agent_id}");
+    } else {
+        println!("{}", render_json(&response)?);
+    }
+    Ok(daemon_reply_exit_code(&response, ResponseMode::Generic))
+}
+
+/// List blocked peers, from the daemon when it is running and from
+/// `blocklist.json` otherwise.
+pub async fn list(paths: &AxonPaths, json: bool) -> Result<ExitCode> {
+    let response: Value = if paths.socket.exists() {
+        send_ipc(paths, json!({"cmd": "blocked"})).await?
+    } else {
+        json!({"ok": true, "blocked": load_blocklist(&paths.blocklist).await?})
+    };
+
+    if json {
+        println!("{}", render_json(&response)?);
+    } else if let Some(rendered) = render_blocked_human(&response) {
+        println!("{rendered}");
+    } else {
+        println!("{}", render_json(&response)?);
+    }
+    Ok(daemon_reply_exit_code(&response, ResponseMode::Generic))
+}
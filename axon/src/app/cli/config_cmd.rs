@@ -2,9 +2,11 @@ use std::process::ExitCode;
 
 use anyhow::{Context, Result, anyhow};
 use axon::config::{
-    AxonPaths, PeerAddr, PersistedConfig, load_persisted_config, save_persisted_config,
+    AxonPaths, ConfigReport, ConfigSource, LayeredConfig, PeerAddr, PersistedConfig, Severity,
+    config_schema, env_vars, load_layered, load_persisted_config, save_persisted_config,
+    system_config_path, validate_config_file,
 };
-use clap::{ArgGroup, Args, ValueEnum};
+use clap::{ArgGroup, Args, Subcommand, ValueEnum};
 use serde_json::{Map, Value, json};
 use tokio::process::Command;
 
@@ -16,12 +18,17 @@ pub enum ConfigKey {
 }
 
 #[derive(Debug, Clone, Args)]
-#[command(group(
-    ArgGroup::new("mode")
-        .args(["list", "unset", "edit"])
-        .multiple(false)
-))]
+#[command(
+    args_conflicts_with_subcommands = true,
+    group(
+        ArgGroup::new("mode")
+            .args(["list", "unset", "edit"])
+            .multiple(false)
+    )
+)]
 pub struct ConfigArgs {
+    #[command(subcommand)]
+    pub command: Option<ConfigCommand>,
     /// List configured scalar values.
     #[arg(long)]
     pub list: bool,
@@ -41,6 +48,28 @@ pub struct ConfigArgs {
     pub value: Option<String>,
 }
 
+#[derive(Debug, Clone, Subcommand)]
+pub enum ConfigCommand {
+    /// Check config.yaml: unknown keys, peer pubkeys and addresses, aliases.
+    Validate {
+        /// Print the report as JSON.
+        #[arg(long)]
+        json: bool,
+    },
+    /// Print a JSON Schema for config.yaml (for editor integration).
+    Schema,
+    /// Print the effective config: the system file, then config.yaml, then
+    /// AXON_* environment variables.
+    Show {
+        /// List every key with the layer that set it.
+        #[arg(long)]
+        provenance: bool,
+        /// Print as JSON.
+        #[arg(long)]
+        json: bool,
+    },
+}
+
 #[derive(Debug)]
 enum ConfigAction {
     List,
@@ -51,6 +80,55 @@ enum ConfigAction {
 }
 
 pub async fn run(paths: &AxonPaths, args: ConfigArgs) -> Result<ExitCode> {
+    match args.command {
+        Some(ConfigCommand::Validate { json }) => {
+            let report = validate_config_file(&paths.config).await?;
+            if json {
+                let rendered = serde_json::to_string_pretty(&report)
+                    .context("failed to encode validation report")?;
+                println!("{rendered}");
+            } else {
+                println!("{}", render_report_text(&report));
+            }
+            return Ok(if report.valid {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::from(2)
+            });
+        }
+        Some(ConfigCommand::Show { provenance, json }) => {
+            let mut layered = load_layered(
+                system_config_path().as_deref(),
+                &paths.config,
+                paths.profile.as_deref(),
+                env_vars(),
+            )
+            .await?;
+            redact_secrets(&mut layered.config);
+            let rendered = if json {
+                let config = serde_json::to_value(&layered.config)?;
+                let document = if provenance {
+                    json!({"config": config, "provenance": layered.provenance})
+                } else {
+                    config
+                };
+                serde_json::to_string_pretty(&document).context("failed to encode config")?
+            } else if provenance {
+                render_provenance_text(&layered)?
+            } else {
+                serde_yaml::to_string(&layered.config).context("failed to encode config")?
+            };
+            println!("{}", rendered.trim_end());
+            return Ok(ExitCode::SUCCESS);
+        }
+        Some(ConfigCommand::Schema) => {
+            let rendered = serde_json::to_string_pretty(&config_schema())
+                .context("failed to encode config schema")?;
+            println!("{rendered}");
+            return Ok(ExitCode::SUCCESS);
+        }
+        None => {}
+    }
     let action = parse_action(&args)?;
 
     match action {
@@ -135,6 +213,85 @@ fn parse_action(args: &ConfigArgs) -> Result<ConfigAction> {
     Ok(ConfigAction::Get(key))
 }
 
+fn render_report_text(report: &ConfigReport) -> String {
+    if !report.exists {
+        return format!("{}: not present (defaults apply)", report.config);
+    }
+    let mut lines: Vec<String> = report
+        .issues
+        .iter()
+        .map(|issue| {
+            let severity = match issue.severity {
+                Severity::Error => "error",
+                Severity::Warning => "warning",
+            };
+            if issue.path.is_empty() {
+                format!("{severity}: {}", issue.message)
+            } else {
+                format!("{severity}: {}: {}", issue.path, issue.message)
+            }
+        })
+        .collect();
+    let errors = report
+        .issues
+        .iter()
+        .filter(|issue| issue.severity == Severity::Error)
+        .count();
+    let warnings = report.issues.len() - errors;
+    lines.push(if report.valid {
+        format!("{}: valid ({warnings} warnings)", report.config)
+    } else {
+        format!(
+            "{}: invalid ({errors} errors, {warnings} warnings)",
+            report.config
+        )
+    });
+    lines.join("\n")
+}
+
+/// One `key = value  (layer)` line per config key. Keys no layer set show
+/// the schema default, or `(unset)` when there is none.
+fn render_provenance_text(layered: &LayeredConfig) -> Result<String> {
+    let config = serde_json::to_value(&layered.config).context("failed to encode config")?;
+    let schema = config_schema();
+    let lines = layered
+        .provenance
+        .iter()
+        .map(|(key, source)| {
+            let pointer = format!("/{}", key.replace('.', "/"));
+            let value = match source {
+                ConfigSource::Default => schema_default(&schema, key)
+                    .map(|value| value.to_string())
+                    .unwrap_or_else(|| "(unset)".to_string()),
+                _ => config
+                    .pointer(&pointer)
+                    .map(|value| value.to_string())
+                    .unwrap_or_default(),
+            };
+            format!("{key} = {value}  ({source})")
+        })
+        .collect::<Vec<_>>();
+    Ok(lines.join("\n"))
+}
+
+/// Text printed by `config show` in place of a secret.
+const REDACTED_SECRET: &str = "[redacted]";
+
+/// Hide secrets such as `webhooks[].secret` from `config show`.
+fn redact_secrets(config: &mut PersistedConfig) {
+    for webhook in &mut config.webhooks {
+        if webhook.secret.is_some() {
+            webhook.secret = Some(REDACTED_SECRET.to_string());
+        }
+    }
+}
+
+fn schema_default<'a>(schema: &'a Value, key: &str) -> Option<&'a Value> {
+    key.split('.')
+        .try_fold(schema, |node, segment| node["properties"].get(segment))?
+        .get("default")
+}
+
 fn key_display_name(key: ConfigKey) -> &'static str {
     match key {
         ConfigKey::Name => "name",
@@ -1,9 +1,13 @@
-use super::{ConfigArgs, ConfigKey, apply_set, key_display_name, parse_action, render_list_text};
-use axon::config::PersistedConfig;
+use super::{
+    ConfigArgs, ConfigKey, REDACTED_SECRET, apply_set, key_display_name, parse_action,
+    redact_secrets, render_list_text, render_report_text,
+};
+use axon::config::{ConfigIssue, ConfigReport, PersistedConfig, Severity, WebhookConfig};
 
 #[test]
 fn parse_action_rejects_json_without_list() {
     let args = ConfigArgs {
+        command: None,
         list: false,
         unset: None,
         edit: false,
@@ -67,6 +71,7 @@ fn render_list_text_only_includes_set_keys() {
         port: None,
         advertise_addr: Some("host:7100".to_string()),
         peers: Vec::new(),
+        ..Default::default()
     };
 
     let rendered = render_list_text(&config);
@@ -74,3 +79,53 @@ fn render_list_text_only_includes_set_keys() {
     assert!(rendered.contains("advertise_addr=host:7100"));
     assert!(!rendered.contains("port="));
 }
+
+#[test]
+fn report_text_lists_issues_and_summary() {
+    let report = ConfigReport {
+        config: "config.yaml".to_string(),
+        exists: true,
+        valid: false,
+        issues: vec![
+            ConfigIssue {
+                severity: Severity::Error,
+                path: "peers[0].pubkey".to_string(),
+                message: "not valid base64".to_string(),
+            },
+            ConfigIssue {
+                severity: Severity::Warning,
+                path: "prot".to_string(),
+                message: "unknown key (ignored by the daemon)".to_string(),
+            },
+        ],
+    };
+    assert_eq!(
+        render_report_text(&report),
+        "error: peers[0].pubkey: not valid base64\n\
+         warning: prot: unknown key (ignored by the daemon)\n\
+         config.yaml: invalid (1 errors, 1 warnings)"
+    );
+}
+
+#[test]
+fn redact_secrets_hides_webhook_secrets() {
+    let mut config = PersistedConfig {
+        webhooks: vec![
+            WebhookConfig {
+                url: "https://hooks.example.com/a".to_string(),
+                secret: Some("s3cret".to_string()),
+                ..WebhookConfig::default()
+            },
+            WebhookConfig {
+                url: "https://hooks.example.com/b".to_string(),
+                ..WebhookConfig::default()
+            },
+        ],
+        ..PersistedConfig::default()
+    };
+    redact_secrets(&mut config);
+    assert_eq!(config.webhooks[0].secret.as_deref(), Some(REDACTED_SECRET));
+    assert_eq!(config.webhooks[1].secret, None);
+    let rendered = serde_yaml::to_string(&config).expect("yaml");
+    assert!(!rendered.contains("s3cret"), "{rendered}");
+}
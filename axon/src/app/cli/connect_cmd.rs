@@ -0,0 +1,105 @@
+use anyhow::{Context, Result, bail};
+use axon::config::{
+    AxonPaths, PeerAddr, PersistedStaticPeerConfig, load_persisted_config, load_revocations,
+    save_persisted_config,
+};
+use axon::identity::Identity;
+use axon::peer_token;
+use clap::Args;
+use serde_json::json;
+
+use crate::app::cli::ipc_client::send_ipc;
+
+#[derive(Debug, Clone, Args)]
+pub struct ConnectArgs {
+    pub token: String,
+    /// Accept a legacy token without a signature (no tamper or expiry check).
+    #[arg(long)]
+    pub allow_unsigned: bool,
+}
+
+/// `axon connect`: check the token, add it to the static peers in
+/// `config.yaml`, and hot-load it into a running daemon.
+pub async fn run(paths: &AxonPaths, args: &ConnectArgs) -> Result<()> {
+    let identity = Identity::load_or_generate(paths)?;
+    let decoded = peer_token::decode(&args.token).context("failed to parse peer token")?;
+    decoded.ensure_unexpired(axon::message::now_millis() / 1000)?;
+    if !args.allow_unsigned {
+        decoded
+            .ensure_signed()
+            .context("pass --allow-unsigned to enroll a legacy token anyway")?;
+    } else if !decoded.signed {
+        eprintln!(
+            "warning: peer token is unsigned, so it cannot be checked for tampering; \
+             confirm agent ID {} with the peer",
+            decoded.agent_id
+        );
+    }
+
+    if decoded.agent_id.as_str() == identity.agent_id() {
+        bail!("refusing to enroll self ({})", decoded.agent_id);
+    }
+    if load_revocations(&paths.revocations)
+        .await?
+        .iter()
+        .any(|revoked| revoked.pubkey == decoded.pubkey)
+    {
+        bail!(
+            "refusing to enroll revoked peer {} (listed in {})",
+            decoded.agent_id,
+            paths.revocations.display()
+        );
+    }
+
+    let mut persisted = load_persisted_config(&paths.config).await?;
+    let peers = persisted.static_peers_mut(paths.profile.as_deref())?;
+    if peers
+        .iter()
+        .any(|peer| peer.agent_id.as_str() == decoded.agent_id.as_str())
+    {
+        bail!(
+            "peer {} already exists in {}",
+            decoded.agent_id,
+            paths.config.display()
+        );
+    }
+
+    let parsed_addr = PeerAddr::parse(&decoded.addr).context("peer token has invalid addr")?;
+    peers.push(PersistedStaticPeerConfig {
+        agent_id: decoded.agent_id.clone(),
+        addr: parsed_addr,
+        pubkey: decoded.pubkey.clone(),
+        tuning: Default::default(),
+    });
+    save_persisted_config(&paths.config, &persisted).await?;
+
+    if paths.socket.exists() {
+        let hotload = send_ipc(
+            paths,
+            json!({"cmd": "add_peer", "pubkey": decoded.pubkey, "addr": decoded.addr}),
+        )
+        .await;
+        match hotload {
+            Ok(response) if response.get("ok") == Some(&json!(true)) => {}
+            Ok(response) => {
+                let rendered = serde_json::to_string_pretty(&response)
+                    .unwrap_or_else(|_| response.to_string());
+                bail!(
+                    "peer saved to {} but daemon hot-load failed.\nDaemon response: {}",
+                    paths.config.display(),
+                    rendered
+                );
+            }
+            Err(err) => {
+                bail!(
+                    "peer saved to {} but daemon hot-load failed: {}",
+                    paths.config.display(),
+                    err
+                );
+            }
+        }
+    }
+
+    println!("✓ Added peer {} ({})", decoded.agent_id, decoded.addr);
+    Ok(())
+}
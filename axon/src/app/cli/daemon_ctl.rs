@@ -0,0 +1,209 @@
+use std::fs::{self, OpenOptions};
+use std::os::unix::fs::OpenOptionsExt;
+use std::os::unix::process::CommandExt;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use axon::config::AxonPaths;
+use serde_json::json;
+
+use super::ipc_client;
+
+pub(crate) const DAEMON_PID_FILE_NAME: &str = "daemon.pid";
+pub(crate) const DAEMON_LOG_FILE_NAME: &str = "daemon.log";
+
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(10);
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+/// How long to wait for the process to vanish after it removed its pidfile.
+const EXIT_GRACE: Duration = Duration::from_secs(2);
+
+/// Options forwarded to a detached `axon daemon` child.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct DetachOptions {
+    pub(crate) port: Option<u16>,
+    pub(crate) disable_mdns: bool,
+    pub(crate) verbose: u8,
+    pub(crate) quiet: bool,
+}
+
+pub(crate) enum StopOutcome {
+    NotRunning,
+    Stopped(u32),
+}
+
+pub(crate) fn pid_is_alive(pid: u32) -> bool {
+    if pid == 0 || pid > i32::MAX as u32 {
+        return false;
+    }
+
+    // SAFETY: kill(pid, 0) does not send a signal; it only checks process existence/permissions.
+    let rc = unsafe { libc::kill(pid as libc::pid_t, 0) };
+    if rc == 0 {
+        return true;
+    }
+
+    matches!(
+        std::io::Error::last_os_error().raw_os_error(),
+        Some(code) if code == libc::EPERM
+    )
+}
+
+/// PID recorded in `daemon.pid`, if the file exists, parses, and names a live
+/// process.
+pub(crate) fn running_daemon_pid(paths: &AxonPaths) -> Option<u32> {
+    let pid = read_pid(&paths.root.join(DAEMON_PID_FILE_NAME))?;
+    pid_is_alive(pid).then_some(pid)
+}
+
+fn daemon_args(paths: &AxonPaths, opts: &DetachOptions) -> Vec<String> {
+    let mut args = vec![
+        "--state-root".to_string(),
+        paths.base_root().to_string_lossy().into_owned(),
+    ];
+    if let Some(profile) = &paths.profile {
+        args.extend(["--profile".to_string(), profile.clone()]);
+    }
+    if opts.quiet {
+        args.push("--quiet".to_string());
+    } else if opts.verbose > 0 {
+        args.push(format!("-{}", "v".repeat(opts.verbose as usize)));
+    }
+    args.push("daemon".to_string());
+    if let Some(port) = opts.port {
+        args.extend(["--port".to_string(), port.to_string()]);
+    }
+    if opts.disable_mdns {
+        args.push("--disable-mdns".to_string());
+    }
+    args
+}
+
+/// Start `axon daemon` as a detached background process (new session, stdio
+/// redirected to `daemon.log` under the state root) and wait until it has
+/// written its pidfile and bound the IPC socket.
+pub(crate) async fn spawn_detached(paths: &AxonPaths, opts: &DetachOptions) -> Result<u32> {
+    if let Some(pid) = running_daemon_pid(paths) {
+        anyhow::bail!("daemon already running (pid {pid}) on this state root");
+    }
+    paths.ensure_root_exists()?;
+
+    let log_path = paths.root.join(DAEMON_LOG_FILE_NAME);
+    let log = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .mode(0o600)
+        .open(&log_path)
+        .with_context(|| format!("failed to open daemon log: {}", log_path.display()))?;
+    let log_err = log
+        .try_clone()
+        .context("failed to duplicate daemon log handle")?;
+
+    let exe = std::env::current_exe().context("failed to resolve axon executable path")?;
+    let mut command = Command::new(exe);
+    command
+        .args(daemon_args(paths, opts))
+        .env("NO_COLOR", "1")
+        .stdin(Stdio::null())
+        .stdout(log)
+        .stderr(log_err);
+    // SAFETY: setsid is async-signal-safe; it detaches the child from the
+    // caller's terminal and process group.
+    unsafe {
+        command.pre_exec(|| {
+            if libc::setsid() < 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
+    let mut child = command.spawn().context("failed to spawn daemon process")?;
+    let pid = child.id();
+
+    let deadline = Instant::now() + STARTUP_TIMEOUT;
+    loop {
+        if let Some(status) = child.try_wait().context("failed to poll daemon process")? {
+            anyhow::bail!(
+                "daemon exited during startup ({status}); see {}",
+                log_path.display()
+            );
+        }
+        if paths.socket.exists() && read_pid(&paths.root.join(DAEMON_PID_FILE_NAME)) == Some(pid) {
+            return Ok(pid);
+        }
+        if Instant::now() >= deadline {
+            anyhow::bail!(
+                "daemon (pid {pid}) did not become ready within {}s; see {}",
+                STARTUP_TIMEOUT.as_secs(),
+                log_path.display()
+            );
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+fn read_pid(path: &Path) -> Option<u32> {
+    fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+/// Stop the daemon recorded in `daemon.pid`: request an IPC `shutdown`, fall
+/// back to SIGTERM if the socket is unreachable, then wait for the process to
+/// exit.
+pub(crate) async fn stop(paths: &AxonPaths, timeout: Duration) -> Result<StopOutcome> {
+    let Some(pid) = running_daemon_pid(paths) else {
+        return Ok(StopOutcome::NotRunning);
+    };
+
+    let acknowledged = matches!(
+        ipc_client::send_ipc(paths, json!({"cmd": "shutdown"})).await,
+        Ok(reply) if reply.get("ok") == Some(&json!(true))
+    );
+    if !acknowledged {
+        // SAFETY: kill sends SIGTERM to the pid recorded by the daemon lock.
+        let rc = unsafe { libc::kill(pid as libc::pid_t, libc::SIGTERM) };
+        if rc != 0 {
+            return Err(std::io::Error::last_os_error())
+                .with_context(|| format!("failed to send SIGTERM to daemon (pid {pid})"));
+        }
+    }
+
+    // The daemon removes its pidfile as the last shutdown step, so that counts
+    // as exited even if the process lingers unreaped.
+    let pid_path = paths.root.join(DAEMON_PID_FILE_NAME);
+    let deadline = Instant::now() + timeout;
+    while pid_is_alive(pid) && read_pid(&pid_path) == Some(pid) {
+        if Instant::now() >= deadline {
+            anyhow::bail!(
+                "daemon (pid {pid}) did not exit within {}s",
+                timeout.as_secs()
+            );
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+    // Sockets are released as the process exits; give it a moment so an
+    // immediate restart can rebind the QUIC port.
+    let grace_deadline = Instant::now() + EXIT_GRACE;
+    while pid_is_alive(pid) && Instant::now() < grace_deadline {
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+    Ok(StopOutcome::Stopped(pid))
+}
+
+/// `axon restart`: stop the running daemon, if any, and start it detached.
+pub(crate) async fn restart(
+    paths: &AxonPaths,
+    opts: &DetachOptions,
+    timeout: Duration,
+) -> Result<()> {
+    if let StopOutcome::Stopped(pid) = stop(paths, timeout).await? {
+        println!("daemon stopped (pid {pid})");
+    }
+    let pid = spawn_detached(paths, opts).await?;
+    println!("daemon started (pid {pid})");
+    Ok(())
+}
+
+#[cfg(test)]
+#[path = "daemon_ctl_tests.rs"]
+mod tests;
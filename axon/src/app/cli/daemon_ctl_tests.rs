@@ -0,0 +1,50 @@
+use std::path::PathBuf;
+
+use axon::config::AxonPaths;
+use tempfile::tempdir;
+
+use super::{DAEMON_PID_FILE_NAME, DetachOptions, daemon_args, running_daemon_pid};
+
+#[test]
+fn daemon_args_forward_options_after_global_flags() {
+    let paths = AxonPaths::from_root(PathBuf::from("/tmp/axon-root"));
+    let args = daemon_args(
+        &paths,
+        &DetachOptions {
+            port: Some(7200),
+            disable_mdns: true,
+            verbose: 2,
+            quiet: false,
+        },
+    );
+    assert_eq!(
+        args,
+        [
+            "--state-root",
+            "/tmp/axon-root",
+            "-vv",
+            "daemon",
+            "--port",
+            "7200",
+            "--disable-mdns"
+        ]
+    );
+}
+
+#[test]
+fn running_daemon_pid_ignores_missing_invalid_and_dead_pids() {
+    let dir = tempdir().unwrap();
+    let paths = AxonPaths::from_root(dir.path().to_path_buf());
+    let pid_path = dir.path().join(DAEMON_PID_FILE_NAME);
+
+    assert_eq!(running_daemon_pid(&paths), None);
+
+    std::fs::write(&pid_path, "not-a-pid\n").unwrap();
+    assert_eq!(running_daemon_pid(&paths), None);
+
+    std::fs::write(&pid_path, format!("{}\n", u32::MAX)).unwrap();
+    assert_eq!(running_daemon_pid(&paths), None);
+
+    std::fs::write(&pid_path, format!("{}\n", std::process::id())).unwrap();
+    assert_eq!(running_daemon_pid(&paths), Some(std::process::id()));
+}
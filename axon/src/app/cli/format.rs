@@ -1,4 +1,5 @@
 use crate::app::doctor::DoctorReport;
+use crate::app::self_test::SelfTestReport;
 use serde_json::Value;
 
 pub fn render_peers_human(response: &Value) -> Option<String> {
@@ -7,13 +8,13 @@ pub fn render_peers_human(response: &Value) -> Option<String> {
         return Some("No peers.".to_string());
     }
 
-    let mut rows: Vec<[String; 5]> = Vec::with_capacity(peers.len());
+    let mut rows: Vec<[String; 6]> = Vec::with_capacity(peers.len());
     for peer in peers {
-        let agent_id = peer
-            .get("agent_id")
-            .and_then(Value::as_str)
-            .unwrap_or("?")
-            .to_string();
+        let agent_id = peer.get("agent_id").and_then(Value::as_str).unwrap_or("?");
+        let agent_id = match peer.get("alias").and_then(Value::as_str) {
+            Some(alias) => format!("{agent_id} ({alias})"),
+            None => agent_id.to_string(),
+        };
         let addr = peer
             .get("addr")
             .and_then(Value::as_str)
@@ -34,10 +35,20 @@ pub fn render_peers_human(response: &Value) -> Option<String> {
             .and_then(Value::as_str)
             .unwrap_or("?")
             .to_string();
-        rows.push([agent_id, addr, status, rtt_ms, source]);
+        let tags: Vec<&str> = peer
+            .get("tags")
+            .and_then(Value::as_array)
+            .map(|tags| tags.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default();
+        let tags = if tags.is_empty() {
+            "-".to_string()
+        } else {
+            tags.join(",")
+        };
+        rows.push([agent_id, addr, status, rtt_ms, source, tags]);
     }
 
-    let mut widths = [8usize, 4usize, 6usize, 6usize, 6usize];
+    let mut widths = [8usize, 4usize, 6usize, 6usize, 6usize, 4usize];
     for row in &rows {
         for (idx, cell) in row.iter().enumerate() {
             widths[idx] = widths[idx].max(cell.len());
@@ -46,12 +57,13 @@ pub fn render_peers_human(response: &Value) -> Option<String> {
 
     let mut out = String::new();
     out.push_str(&format!(
-        "{:<w0$}  {:<w1$}  {:<w2$}  {:>w3$}  {:<w4$}\n",
+        "{:<w0$}  {:<w1$}  {:<w2$}  {:>w3$}  {:<w4$}  {}\n",
         "AGENT_ID",
         "ADDR",
         "STATUS",
         "RTT_MS",
         "SOURCE",
+        "TAGS",
         w0 = widths[0],
         w1 = widths[1],
         w2 = widths[2],
@@ -60,22 +72,24 @@ pub fn render_peers_human(response: &Value) -> Option<String> {
     ));
 
     out.push_str(&format!(
-        "{}  {}  {}  {}  {}\n",
+        "{}  {}  {}  {}  {}  {}\n",
         "-".repeat(widths[0]),
         "-".repeat(widths[1]),
         "-".repeat(widths[2]),
         "-".repeat(widths[3]),
-        "-".repeat(widths[4])
+        "-".repeat(widths[4]),
+        "-".repeat(widths[5])
     ));
 
     for row in rows {
         out.push_str(&format!(
-            "{:<w0$}  {:<w1$}  {:<w2$}  {:>w3$}  {:<w4$}\n",
+            "{:<w0$}  {:<w1$}  {:<w2$}  {:>w3$}  {:<w4$}  {}\n",
             row[0],
             row[1],
             row[2],
             row[3],
             row[4],
+            row[5],
             w0 = widths[0],
             w1 = widths[1],
             w2 = widths[2],
@@ -88,15 +102,282 @@ pub fn render_peers_human(response: &Value) -> Option<String> {
 }
 
 pub fn render_status_human(response: &Value) -> Option<String> {
-    Some(format!(
+    let mut out = format!(
         "Uptime: {}s\nPeers Connected: {}\nMessages Sent: {}\nMessages Received: {}",
         response.get("uptime_secs")?.as_u64()?,
         response.get("peers_connected")?.as_u64()?,
         response.get("messages_sent")?.as_u64()?,
         response.get("messages_received")?.as_u64()?
+    );
+    if let Some(limited) = response
+        .get("messages_rate_limited")
+        .and_then(Value::as_u64)
+    {
+        out.push_str(&format!("\nMessages Rate-Limited: {limited}"));
+    }
+    if let Some(lifetime) = response.get("lifetime") {
+        let field = |name: &str| lifetime.get(name).and_then(Value::as_u64).unwrap_or(0);
+        out.push_str(&format!(
+            "\nLifetime ({} starts since {}):\n  Uptime: {}s\n  Messages Sent: {}\n  Messages Received: {}",
+            field("daemon_starts"),
+            format_utc_ms(field("first_started_ms")),
+            field("uptime_secs"),
+            field("messages_sent"),
+            field("messages_received")
+        ));
+    }
+    if let Some(degraded) = response.get("degraded").and_then(Value::as_array) {
+        let names: Vec<&str> = degraded.iter().filter_map(Value::as_str).collect();
+        out.push_str(&format!("\nDegraded: {}", names.join(", ")));
+    }
+    Some(out)
+}
+
+pub fn render_health_human(response: &Value) -> Option<String> {
+    let healthy = response.get("healthy")?.as_bool()?;
+    let subsystems = response.get("subsystems")?;
+    let mark = |section: &Value| {
+        if section.get("ok").and_then(Value::as_bool) == Some(true) {
+            "✓"
+        } else {
+            "✗"
+        }
+    };
+
+    let transport = subsystems.get("transport")?;
+    let discovery = subsystems.get("discovery")?;
+    let ipc = subsystems.get("ipc")?;
+    let persistence = subsystems.get("persistence")?;
+    let history = subsystems.get("history")?;
+
+    let persistence_detail = match (
+        persistence.get("last_error").and_then(Value::as_str),
+        persistence.get("last_write_ms").and_then(Value::as_u64),
+    ) {
+        (Some(err), _) => format!("last write failed: {err}"),
+        (None, Some(ms)) => format!("last write {}", format_utc_ms(ms)),
+        (None, None) => "no writes yet".to_string(),
+    };
+    let history_detail = if history.get("enabled")?.as_bool()? {
+        "enabled"
+    } else {
+        "disabled"
+    };
+
+    let mut lines = vec![
+        format!(
+            "Health: {}",
+            if healthy {
+                "✓ healthy"
+            } else {
+                "✗ unhealthy"
+            }
+        ),
+        format!("Uptime: {}s", response.get("uptime_secs")?.as_u64()?),
+        format!(
+            "  {} transport: {}, {} connections",
+            mark(transport),
+            transport
+                .get("bound_addr")
+                .and_then(Value::as_str)
+                .unwrap_or("not bound"),
+            transport.get("connections")?.as_u64()?
+        ),
+        format!(
+            "  {} discovery: mdns {}, {} static peers",
+            mark(discovery),
+            discovery.get("mdns")?.as_str()?,
+            discovery.get("static_peers")?.as_u64()?
+        ),
+        format!(
+            "  {} ipc: {}/{} clients",
+            mark(ipc),
+            ipc.get("clients")?.as_u64()?,
+            ipc.get("max_clients")?.as_u64()?
+        ),
+        format!("  {} persistence: {persistence_detail}", mark(persistence)),
+        format!("  {} history: {history_detail}", mark(history)),
+    ];
+    if let Some(tasks) = subsystems.get("tasks") {
+        lines.push(format!("  {} tasks: {}", mark(tasks), tasks_detail(tasks)?));
+    }
+    Some(lines.join("\n"))
+}
+
+fn tasks_detail(tasks: &Value) -> Option<String> {
+    let tasks = tasks.get("tasks")?.as_array()?;
+    let running = tasks
+        .iter()
+        .filter(|task| task.get("state").and_then(Value::as_str) == Some("running"))
+        .count();
+    let mut detail = format!("{running} running");
+    for task in tasks {
+        if task.get("state")?.as_str()? != "restarting" {
+            continue;
+        }
+        detail.push_str(&format!(
+            "; {} restarting after {} restarts",
+            task.get("name")?.as_str()?,
+            task.get("restarts")?.as_u64()?
+        ));
+        if let Some(err) = task.get("last_error").and_then(Value::as_str) {
+            detail.push_str(&format!(" ({err})"));
+        }
+    }
+    Some(detail)
+}
+
+pub fn render_reload_human(response: &Value) -> Option<String> {
+    Some(format!(
+        "Config reloaded\nPeers Added: {}\nPeers Removed: {}\nPeers Updated: {}",
+        response.get("peers_added")?.as_u64()?,
+        response.get("peers_removed")?.as_u64()?,
+        response.get("peers_updated")?.as_u64()?
     ))
 }
 
+pub fn render_bans_human(response: &Value) -> Option<String> {
+    let bans = response.get("bans")?.as_array()?;
+    if bans.is_empty() {
+        return Some("No banned addresses.".to_string());
+    }
+    let mut lines = vec![format!(
+        "{:<39}  {:>7}  {:>9}",
+        "ADDRESS", "STRIKES", "REMAINING"
+    )];
+    for ban in bans {
+        lines.push(format!(
+            "{:<39}  {:>7}  {:>8}s",
+            ban.get("addr")?.as_str()?,
+            ban.get("strikes")?.as_u64()?,
+            ban.get("remaining_secs")?.as_u64()?
+        ));
+    }
+    Some(lines.join("\n"))
+}
+
+pub fn render_blocked_human(response: &Value) -> Option<String> {
+    let blocked = response.get("blocked")?.as_array()?;
+    if blocked.is_empty() {
+        return Some("No blocked peers.".to_string());
+    }
+    let mut lines = vec![format!(
+        "{:<41}  {:<20}  {}",
+        "AGENT ID", "BLOCKED", "PUBKEY"
+    )];
+    for entry in blocked {
+        lines.push(format!(
+            "{:<41}  {:<20}  {}",
+            entry.get("agent_id")?.as_str()?,
+            format_utc_ms(entry.get("blocked_at_unix_ms")?.as_u64()?),
+            entry
+                .get("pubkey")
+                .and_then(Value::as_str)
+                .unwrap_or("(unknown)")
+        ));
+    }
+    Some(lines.join("\n"))
+}
+
+pub fn render_pins_human(response: &Value) -> Option<String> {
+    let pins = response.get("pins")?.as_array()?;
+    if pins.is_empty() {
+        return Some("No pinned peers.".to_string());
+    }
+    let mut lines = vec![format!(
+        "{:<41}  {:<10}  {:<21}  {}",
+        "AGENT ID", "SOURCE", "ADDRESS", "PUBKEY"
+    )];
+    for pin in pins {
+        lines.push(format!(
+            "{:<41}  {:<10}  {:<21}  {}",
+            pin.get("agent_id")?.as_str()?,
+            pin.get("source")?.as_str()?,
+            pin.get("addr")?.as_str()?,
+            pin.get("pubkey")?.as_str()?
+        ));
+    }
+    Some(lines.join("\n"))
+}
+
+/// One line per `inbound` event: receive time, kind, sender, message ID,
+/// `ref` when present, and the full payload.
+pub fn render_inbound_human(event: &Value, received_ms: u64) -> Option<String> {
+    let envelope = event.get("envelope")?;
+    let mut line = format!(
+        "{}  {:<8}  {}  {}",
+        format_utc_ms(received_ms),
+        envelope.get("kind")?.as_str()?,
+        event.get("from")?.as_str()?,
+        envelope.get("id")?.as_str()?
+    );
+    if let Some(ref_id) = envelope.get("ref").and_then(Value::as_str) {
+        line.push_str(&format!("  ref={ref_id}"));
+    }
+    if let Some(thread_id) = envelope.get("thread_id").and_then(Value::as_str) {
+        line.push_str(&format!("  thread={thread_id}"));
+    }
+    let payload = envelope
+        .get("payload")
+        .map(Value::to_string)
+        .unwrap_or_default();
+    line.push_str(&format!("  {payload}"));
+    Some(line)
+}
+
+/// Longest payload preview shown per history line.
+const HISTORY_PAYLOAD_PREVIEW: usize = 80;
+
+pub fn render_history_human(response: &Value) -> Option<String> {
+    let entries = response.get("entries")?.as_array()?;
+    if entries.is_empty() {
+        return Some("No history entries.".to_string());
+    }
+
+    let mut lines = Vec::with_capacity(entries.len());
+    for entry in entries {
+        let envelope = entry.get("envelope")?;
+        let payload = envelope
+            .get("payload")
+            .map(Value::to_string)
+            .unwrap_or_default();
+        let payload = match payload.char_indices().nth(HISTORY_PAYLOAD_PREVIEW) {
+            Some((idx, _)) => format!("{}…", &payload[..idx]),
+            None => payload,
+        };
+        lines.push(format!(
+            "{}  {:<3}  {:<8}  {}  {}  {}",
+            format_utc_ms(entry.get("ts_ms")?.as_u64()?),
+            entry.get("direction")?.as_str()?,
+            envelope.get("kind")?.as_str()?,
+            entry.get("peer")?.as_str()?,
+            envelope.get("id")?.as_str()?,
+            payload
+        ));
+    }
+    Some(lines.join("\n"))
+}
+
+/// Format Unix milliseconds as `YYYY-MM-DDTHH:MM:SS.mmmZ`.
+pub(crate) fn format_utc_ms(ms: u64) -> String {
+    let secs = ms / 1000;
+    let (hh, mm, ss) = (secs / 3600 % 24, secs / 60 % 60, secs % 60);
+    // Civil-from-days (Howard Hinnant), valid for dates after 1970.
+    let z = secs / 86_400 + 719_468;
+    let era = z / 146_097;
+    let doe = z % 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+    format!(
+        "{year:04}-{month:02}-{day:02}T{hh:02}:{mm:02}:{ss:02}.{:03}Z",
+        ms % 1000
+    )
+}
+
 pub fn render_whoami_human(response: &Value) -> Option<String> {
     let name = response
         .get("name")
@@ -112,6 +393,49 @@ pub fn render_whoami_human(response: &Value) -> Option<String> {
     ))
 }
 
+pub fn render_whois_human(response: &Value) -> Option<String> {
+    let peer = response.get("peer")?;
+    let text = |name: &str| peer.get(name).and_then(Value::as_str);
+    let agent_id = match text("alias") {
+        Some(alias) => format!("{} ({alias})", text("agent_id")?),
+        None => text("agent_id")?.to_string(),
+    };
+    let rtt = peer
+        .get("rtt_ms")
+        .and_then(Value::as_f64)
+        .map_or_else(|| "-".to_string(), |rtt| format!("{rtt:.2} ms"));
+    let reconnect = match peer.get("reconnect") {
+        None => "-".to_string(),
+        Some(state) if state.get("in_flight") == Some(&Value::Bool(true)) => {
+            "attempt in progress".to_string()
+        }
+        Some(state) => format!(
+            "next attempt in {:.1}s (backoff {}s)",
+            state.get("next_attempt_in_ms")?.as_u64()? as f64 / 1000.0,
+            state.get("backoff_secs")?.as_u64()?
+        ),
+    };
+    let topics = match peer.get("topics").and_then(Value::as_array) {
+        Some(topics) if !topics.is_empty() => topics
+            .iter()
+            .filter_map(Value::as_str)
+            .collect::<Vec<_>>()
+            .join(", "),
+        _ => "-".to_string(),
+    };
+    Some(format!(
+        "Agent ID: {agent_id}\nPublic Key: {}\nAddress: {}\nSource: {}\nStatus: {}\nRTT: {rtt}\nFirst Seen: {}\nLast Seen: {}\nMessages Sent: {}\nMessages Received: {}\nTopics: {topics}\nReconnect: {reconnect}",
+        text("pubkey")?,
+        text("addr")?,
+        text("source")?,
+        text("status")?,
+        format_utc_ms(peer.get("first_seen_ms")?.as_u64()?),
+        format_utc_ms(peer.get("last_seen_ms")?.as_u64()?),
+        peer.get("messages_sent")?.as_u64()?,
+        peer.get("messages_received")?.as_u64()?,
+    ))
+}
+
 pub fn render_doctor_human(report: &DoctorReport) -> String {
     let marker = if report.ok { "✓" } else { "✗" };
     let mut out = format!(
@@ -140,6 +464,26 @@ pub fn render_doctor_human(report: &DoctorReport) -> String {
     out
 }
 
+pub fn render_self_test_human(report: &SelfTestReport) -> String {
+    let marker = if report.ok { "✓" } else { "✗" };
+    let mut out = format!(
+        "Self-test: {marker} {}\nState Root: {} (removed)",
+        if report.ok { "PASS" } else { "FAIL" },
+        report.state_root
+    );
+
+    out.push_str("\n\nSteps:");
+    for step in &report.steps {
+        let step_marker = if step.ok { "✓" } else { "✗" };
+        out.push_str(&format!(
+            "\n  {step_marker} {}: {}",
+            step.name, step.message
+        ));
+    }
+
+    out
+}
+
 #[cfg(test)]
 #[path = "format_tests.rs"]
 mod tests;
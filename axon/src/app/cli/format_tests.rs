@@ -1,6 +1,12 @@
 use serde_json::json;
 
-use super::{render_peers_human, render_status_human, render_whoami_human};
+use super::{
+    format_utc_ms, render_bans_human, render_blocked_human, render_health_human,
+    render_history_human, render_inbound_human, render_peers_human, render_pins_human,
+    render_reload_human, render_self_test_human, render_status_human, render_whoami_human,
+    render_whois_human,
+};
+use crate::app::self_test::{SelfTestReport, SelfTestStep};
 
 #[test]
 fn peers_renderer_outputs_table_headers() {
@@ -11,13 +17,15 @@ fn peers_renderer_outputs_table_headers() {
             "addr": "127.0.0.1:7100",
             "status": "connected",
             "rtt_ms": 1.25,
-            "source": "static"
+            "source": "static",
+            "tags": ["prod", "gpu"]
         }]
     }))
     .expect("table output");
 
     assert!(output.contains("AGENT_ID"));
     assert!(output.contains("127.0.0.1:7100"));
+    assert!(output.contains("prod,gpu"));
 }
 
 #[test]
@@ -27,12 +35,75 @@ fn status_renderer_outputs_key_lines() {
         "uptime_secs": 7,
         "peers_connected": 2,
         "messages_sent": 10,
-        "messages_received": 4
+        "messages_received": 4,
+        "messages_rate_limited": 3,
+        "lifetime": {
+            "messages_sent": 110,
+            "messages_received": 40,
+            "messages_rate_limited": 3,
+            "uptime_secs": 86400,
+            "daemon_starts": 4,
+            "first_started_ms": 0
+        },
+        "degraded": ["mdns_discovery"]
     }))
     .expect("status output");
 
     assert!(output.contains("Uptime: 7s"));
     assert!(output.contains("Peers Connected: 2"));
+    assert!(output.contains("Messages Rate-Limited: 3"));
+    assert!(output.contains("Lifetime (4 starts since 1970-01-01"));
+    assert!(output.contains("  Messages Sent: 110"));
+    assert!(output.contains("Degraded: mdns_discovery"));
+}
+
+#[test]
+fn reload_renderer_outputs_peer_counts() {
+    let output = render_reload_human(&json!({
+        "ok": true,
+        "peers_added": 1,
+        "peers_removed": 0,
+        "peers_updated": 2
+    }))
+    .expect("reload output");
+
+    assert!(output.contains("Config reloaded"));
+    assert!(output.contains("Peers Added: 1"));
+    assert!(output.contains("Peers Updated: 2"));
+}
+
+#[test]
+fn bans_renderer_lists_addresses_or_none() {
+    let output = render_bans_human(&json!({
+        "ok": true,
+        "bans": [{"addr": "192.0.2.7", "strikes": 2, "remaining_secs": 95}]
+    }))
+    .expect("bans output");
+    assert!(output.starts_with("ADDRESS"));
+    assert!(output.contains("192.0.2.7"));
+    assert!(output.contains("95s"));
+
+    let empty = render_bans_human(&json!({"ok": true, "bans": []})).expect("empty output");
+    assert_eq!(empty, "No banned addresses.");
+}
+
+#[test]
+fn blocked_renderer_lists_entries_or_none() {
+    let output = render_blocked_human(&json!({
+        "ok": true,
+        "blocked": [{
+            "agent_id": "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+            "pubkey": "Zm9v",
+            "blocked_at_unix_ms": 0
+        }]
+    }))
+    .expect("blocked output");
+    assert!(output.starts_with("AGENT ID"));
+    assert!(output.contains("ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa"));
+    assert!(output.contains("Zm9v"));
+
+    let empty = render_blocked_human(&json!({"ok": true, "blocked": []})).expect("empty output");
+    assert_eq!(empty, "No blocked peers.");
 }
 
 #[test]
@@ -58,3 +129,162 @@ fn whoami_renderer_prints_name_or_unset() {
     .expect("whoami output");
     assert!(unnamed.contains("Name: (unset)"));
 }
+
+#[test]
+fn history_renderer_outputs_one_line_per_entry() {
+    let output = render_history_human(&json!({
+        "ok": true,
+        "entries": [{
+            "seq": 1,
+            "ts_ms": 1_700_000_000_123u64,
+            "direction": "out",
+            "peer": "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+            "envelope": {
+                "id": "7f3c1d2e-0000-4000-8000-000000000001",
+                "kind": "request",
+                "payload": {"question": "ping"}
+            }
+        }]
+    }))
+    .expect("history output");
+
+    assert!(output.starts_with("2023-11-14T22:13:20.123Z  out  request "));
+    assert!(output.contains("ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa"));
+    assert!(output.ends_with(r#"{"question":"ping"}"#));
+    assert_eq!(
+        render_history_human(&json!({"ok": true, "entries": []})).as_deref(),
+        Some("No history entries.")
+    );
+}
+
+#[test]
+fn format_utc_ms_handles_leap_day() {
+    assert_eq!(format_utc_ms(0), "1970-01-01T00:00:00.000Z");
+    assert_eq!(format_utc_ms(1_709_164_800_000), "2024-02-29T00:00:00.000Z");
+}
+
+#[test]
+fn inbound_renderer_shows_ref_thread_and_full_payload() {
+    let output = render_inbound_human(
+        &json!({
+            "event": "inbound",
+            "from": "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+            "envelope": {
+                "id": "6f1c1a4e-2d3b-4c5a-9e8f-0a1b2c3d4e5f",
+                "kind": "message",
+                "ref": "0b9a8c7d-6e5f-4a3b-8c2d-1e0f9a8b7c6d",
+                "thread_id": "t-1",
+                "payload": {"cancel": true}
+            }
+        }),
+        0,
+    )
+    .expect("inbound output");
+
+    assert_eq!(
+        output,
+        "1970-01-01T00:00:00.000Z  message   ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa  \
+         6f1c1a4e-2d3b-4c5a-9e8f-0a1b2c3d4e5f  ref=0b9a8c7d-6e5f-4a3b-8c2d-1e0f9a8b7c6d  \
+         thread=t-1  {\"cancel\":true}"
+    );
+}
+
+#[test]
+fn health_renderer_marks_failing_subsystems() {
+    let output = render_health_human(&json!({
+        "ok": true,
+        "healthy": false,
+        "uptime_secs": 3,
+        "subsystems": {
+            "transport": {"ok": true, "bound_addr": "0.0.0.0:7100", "connections": 1},
+            "discovery": {"ok": false, "mdns": "failed", "static_peers": 2},
+            "ipc": {"ok": true, "clients": 1, "max_clients": 64},
+            "persistence": {"ok": true},
+            "history": {"ok": true, "enabled": false},
+            "tasks": {"ok": false, "tasks": [
+                {"name": "inbound_forwarder", "state": "restarting", "restarts": 2, "last_error": "inbound subscription closed"},
+                {"name": "static_discovery", "state": "running", "restarts": 0}
+            ]}
+        }
+    }))
+    .expect("health output");
+
+    assert!(output.starts_with("Health: ✗ unhealthy"));
+    assert!(output.contains("✓ transport: 0.0.0.0:7100, 1 connections"));
+    assert!(output.contains("✗ discovery: mdns failed, 2 static peers"));
+    assert!(output.contains("✓ persistence: no writes yet"));
+    assert!(output.contains(
+        "✗ tasks: 1 running; inbound_forwarder restarting after 2 restarts (inbound subscription closed)"
+    ));
+}
+
+#[test]
+fn self_test_renderer_lists_steps() {
+    let output = render_self_test_human(&SelfTestReport {
+        ok: false,
+        state_root: "/tmp/axon-self-test-1234abcd".to_string(),
+        steps: vec![
+            SelfTestStep {
+                name: "daemon_start",
+                ok: true,
+                message: "listening on UDP 7100".to_string(),
+            },
+            SelfTestStep {
+                name: "ipc_whoami",
+                ok: false,
+                message: "IPC command timed out".to_string(),
+            },
+        ],
+    });
+
+    assert!(output.starts_with("Self-test: ✗ FAIL"));
+    assert!(output.contains("✓ daemon_start: listening on UDP 7100"));
+    assert!(output.contains("✗ ipc_whoami: IPC command timed out"));
+}
+
+#[test]
+fn whois_renderer_shows_record_and_reconnect_state() {
+    let output = render_whois_human(&json!({
+        "ok": true,
+        "peer": {
+            "agent_id": "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+            "alias": "laptop",
+            "pubkey": "Zm9v",
+            "addr": "10.0.0.2:7100",
+            "status": "disconnected",
+            "source": "static",
+            "first_seen_ms": 0,
+            "last_seen_ms": 0,
+            "messages_sent": 3,
+            "messages_received": 2,
+            "topics": ["builds.*", "deploys.prod"],
+            "reconnect": {"in_flight": false, "next_attempt_in_ms": 1500, "backoff_secs": 4}
+        }
+    }))
+    .expect("render whois");
+    assert!(output.starts_with("Agent ID: ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa (laptop)\n"));
+    assert!(output.contains("Status: disconnected\nRTT: -\nFirst Seen: 1970-01-01T00:00:00.000Z\nLast Seen: 1970-01-01T00:00:00.000Z"));
+    assert!(output.contains("Messages Sent: 3\nMessages Received: 2"));
+    assert!(output.contains("Topics: builds.*, deploys.prod\n"));
+    assert!(output.ends_with("Reconnect: next attempt in 1.5s (backoff 4s)"));
+}
+
+#[test]
+fn pins_renderer_lists_pins_or_none() {
+    let output = render_pins_human(&json!({
+        "ok": true,
+        "pins": [{
+            "agent_id": "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+            "pubkey": "Zm9v",
+            "addr": "192.0.2.7:7100",
+            "source": "cached"
+        }]
+    }))
+    .expect("pins output");
+    assert!(output.starts_with("AGENT ID"));
+    assert!(output.contains("cached"));
+    assert!(output.contains("Zm9v"));
+
+    let empty = render_pins_human(&json!({"ok": true, "pins": []})).expect("empty output");
+    assert_eq!(empty, "No pinned peers.");
+}
@@ -0,0 +1,89 @@
+use std::time::Duration;
+
+use clap::Args;
+use serde_json::{Map, Value, json};
+use uuid::Uuid;
+
+use crate::app::cli::alias::parse_agent_ref_arg;
+
+#[derive(Debug, Clone, Args)]
+pub struct HistoryArgs {
+    /// Only envelopes exchanged with this agent (agent ID or alias).
+    #[arg(long, value_name = "AGENT_ID", value_parser = parse_agent_ref_arg)]
+    pub peer: Option<String>,
+    /// Only this envelope kind.
+    #[arg(long, value_parser = ["request", "response", "message", "error"])]
+    pub kind: Option<String>,
+    /// Only received (`in`) or sent (`out`) envelopes.
+    #[arg(long, value_parser = ["in", "out"])]
+    pub direction: Option<String>,
+    /// Only this message and envelopes that reference it.
+    #[arg(long, value_name = "UUID")]
+    pub msg_id: Option<Uuid>,
+    /// Only envelopes in this conversation thread.
+    #[arg(long, value_name = "THREAD_ID")]
+    pub thread: Option<String>,
+    /// Only envelopes recorded within this age (e.g. 30s, 15m, 2h, 7d).
+    #[arg(long, value_name = "AGE", value_parser = parse_age)]
+    pub since: Option<Duration>,
+    /// Maximum entries to show (the newest are kept).
+    #[arg(long, default_value_t = 50)]
+    pub limit: usize,
+    /// Print machine-readable JSON.
+    #[arg(long)]
+    pub json: bool,
+}
+
+impl HistoryArgs {
+    /// Build the IPC `history` command; `now_ms` anchors `--since`.
+    pub fn to_command(&self, now_ms: u64) -> Value {
+        let mut filter = Map::new();
+        if let Some(ref peer) = self.peer {
+            filter.insert("peer".into(), json!(peer));
+        }
+        if let Some(ref kind) = self.kind {
+            filter.insert("kind".into(), json!(kind));
+        }
+        if let Some(ref direction) = self.direction {
+            filter.insert("direction".into(), json!(direction));
+        }
+        if let Some(msg_id) = self.msg_id {
+            filter.insert("msg_id".into(), json!(msg_id));
+        }
+        if let Some(ref thread) = self.thread {
+            filter.insert("thread_id".into(), json!(thread));
+        }
+        if let Some(since) = self.since {
+            let since_ms = now_ms.saturating_sub(since.as_millis() as u64);
+            filter.insert("since_ms".into(), json!(since_ms));
+        }
+        filter.insert("limit".into(), json!(self.limit));
+        json!({"cmd": "history", "filter": filter})
+    }
+}
+
+/// Parse an age like `90s`, `15m`, `2h`, or `7d`.
+pub(crate) fn parse_age(input: &str) -> Result<Duration, String> {
+    let input = input.trim();
+    let split = input
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(input.len());
+    let (digits, unit) = input.split_at(split);
+    let value: u64 = digits
+        .parse()
+        .map_err(|_| format!("invalid age '{input}'; expected e.g. 30s, 15m, 2h, 7d"))?;
+    let secs = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        _ => {
+            return Err(format!("invalid age unit in '{input}'; use s, m, h, or d"));
+        }
+    };
+    Ok(Duration::from_secs(value.saturating_mul(secs)))
+}
+
+#[cfg(test)]
+#[path = "history_args_tests.rs"]
+mod tests;
@@ -0,0 +1,44 @@
+use std::time::Duration;
+
+use serde_json::json;
+
+use super::{HistoryArgs, parse_age};
+
+#[test]
+fn parse_age_accepts_units() {
+    assert_eq!(parse_age("30s"), Ok(Duration::from_secs(30)));
+    assert_eq!(parse_age("15m"), Ok(Duration::from_secs(900)));
+    assert_eq!(parse_age("2h"), Ok(Duration::from_secs(7200)));
+    assert_eq!(parse_age("7d"), Ok(Duration::from_secs(604_800)));
+    assert!(parse_age("10").is_err());
+    assert!(parse_age("h").is_err());
+    assert!(parse_age("3w").is_err());
+}
+
+#[test]
+fn to_command_builds_filter_from_set_flags() {
+    let args = HistoryArgs {
+        peer: Some("ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa".to_string()),
+        kind: Some("request".to_string()),
+        direction: None,
+        msg_id: None,
+        thread: Some("t-1".to_string()),
+        since: Some(Duration::from_secs(60)),
+        limit: 10,
+        json: false,
+    };
+
+    assert_eq!(
+        args.to_command(100_000),
+        json!({
+            "cmd": "history",
+            "filter": {
+                "peer": "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+                "kind": "request",
+                "thread_id": "t-1",
+                "since_ms": 40_000,
+                "limit": 10
+            }
+        })
+    );
+}
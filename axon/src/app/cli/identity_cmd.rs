@@ -0,0 +1,265 @@
+use std::fs;
+use std::io::{BufRead, IsTerminal, Write};
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{Context, Result, bail};
+use axon::config::{AxonPaths, Config, PeerAddr};
+use axon::identity::Identity;
+use axon::peer_token;
+use clap::{Args, Subcommand};
+
+use crate::app::cli::daemon_ctl::running_daemon_pid;
+use crate::app::cli::history_args::parse_age;
+use crate::app::cli::identity_output::{render_identity_human, render_identity_json};
+use crate::app::doctor::backup_file_with_timestamp;
+
+/// Environment variable read for the backup passphrase before prompting.
+pub const PASSPHRASE_ENV: &str = "AXON_BACKUP_PASSPHRASE";
+
+#[derive(Debug, Clone, Args)]
+pub struct IdentityArgs {
+    #[command(subcommand)]
+    pub command: Option<IdentityCommand>,
+    /// Print rich identity metadata as JSON.
+    #[arg(long)]
+    pub json: bool,
+    /// Override address used for URI output (`host:port` or `ip:port`).
+    #[arg(long, value_name = "ADDR")]
+    pub addr: Option<String>,
+    /// Make the token expire after this long (e.g. 30m, 24h, 7d).
+    #[arg(long, value_name = "AGE", value_parser = parse_age)]
+    pub expires: Option<Duration>,
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum IdentityCommand {
+    /// Write the keypair to a backup file (contains the private key).
+    Export {
+        /// Backup file to create.
+        #[arg(long, value_name = "PATH")]
+        out: PathBuf,
+        /// Encrypt the backup with a passphrase (prompted, or AXON_BACKUP_PASSPHRASE).
+        #[arg(long)]
+        encrypt: bool,
+    },
+    /// Restore the keypair from a backup file, keeping its agent ID.
+    Import {
+        file: PathBuf,
+        /// Replace a different existing identity (the old key files are kept as `.bak.<ts>`).
+        #[arg(long)]
+        force: bool,
+    },
+    /// Adopt an unencrypted OpenSSH ed25519 private key (e.g. `~/.ssh/id_ed25519`) as the identity.
+    ImportSsh {
+        file: PathBuf,
+        /// Replace a different existing identity (the old key files are kept as `.bak.<ts>`).
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+/// `axon identity`: print the peer token, or run an export/import.
+pub async fn run(paths: &AxonPaths, args: &IdentityArgs) -> Result<()> {
+    match &args.command {
+        Some(IdentityCommand::Export { out, encrypt }) => export(paths, out, *encrypt),
+        Some(IdentityCommand::Import { file, force }) => import(paths, file, *force),
+        Some(IdentityCommand::ImportSsh { file, force }) => import_ssh(paths, file, *force),
+        None => show(paths, args).await,
+    }
+}
+
+/// Print this agent's signed peer token (and, with `--json`, its parts).
+async fn show(paths: &AxonPaths, args: &IdentityArgs) -> Result<()> {
+    let identity = Identity::load_or_generate(paths)?;
+    let config = Config::load_for(paths).await?;
+    let port = config.effective_port(None);
+    let addr = select_identity_addr(args.addr.as_deref(), config.advertise_addr.as_deref(), port)
+        .context("failed to determine identity advertise address")?;
+    let expires_at = args
+        .expires
+        .map(|age| (axon::message::now_millis() / 1000).saturating_add(age.as_secs()));
+    let uri = peer_token::encode_signed(&identity, &addr, expires_at)
+        .context("failed to construct peer URI")?;
+    if args.json {
+        let (addr_host, addr_port) = split_addr_port(&addr)?;
+        let rendered = render_identity_json(
+            identity.agent_id(),
+            identity.public_key_base64(),
+            &addr_host,
+            addr_port,
+            &uri,
+            expires_at,
+        )?;
+        println!("{rendered}");
+    } else {
+        println!("{}", render_identity_human(&uri));
+    }
+    Ok(())
+}
+
+pub fn export(paths: &AxonPaths, out: &Path, encrypt: bool) -> Result<()> {
+    let identity = Identity::load_or_generate(paths)?;
+    let passphrase = if encrypt {
+        Some(read_passphrase(true)?)
+    } else {
+        None
+    };
+    let backup = identity.export_backup(passphrase.as_deref())?;
+    fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(out)
+        .and_then(|mut file| file.write_all(backup.as_bytes()))
+        .with_context(|| format!("failed to write identity backup: {}", out.display()))?;
+    println!("Exported {} to {}", identity.agent_id(), out.display());
+    if !encrypt {
+        eprintln!("warning: the backup holds the unencrypted private key; store it securely");
+    }
+    Ok(())
+}
+
+pub fn import(paths: &AxonPaths, file: &Path, force: bool) -> Result<()> {
+    let text = fs::read_to_string(file)
+        .with_context(|| format!("failed to read identity backup: {}", file.display()))?;
+    let passphrase = if Identity::backup_is_encrypted(&text)? {
+        Some(read_passphrase(false)?)
+    } else {
+        None
+    };
+    let identity = Identity::from_backup(&text, passphrase.as_deref())?;
+    install(paths, &identity, force)
+}
+
+pub fn import_ssh(paths: &AxonPaths, file: &Path, force: bool) -> Result<()> {
+    let text = fs::read_to_string(file)
+        .with_context(|| format!("failed to read SSH private key: {}", file.display()))?;
+    let identity = Identity::from_openssh(&text)
+        .with_context(|| format!("cannot import {}", file.display()))?;
+    install(paths, &identity, force)
+}
+
+/// Install `identity` into the state root unless it would silently replace a
+/// different one or the daemon is using the current key.
+fn install(paths: &AxonPaths, identity: &Identity, force: bool) -> Result<()> {
+    if Identity::exists(paths)? {
+        // An unreadable existing key counts as a different identity.
+        if let Ok(current) = Identity::load_or_generate(paths)
+            && current.agent_id() == identity.agent_id()
+        {
+            println!("Identity {} is already installed", identity.agent_id());
+            return Ok(());
+        }
+        if !force {
+            bail!(
+                "{} already holds a different identity; pass --force to replace it",
+                paths.identity_key.display()
+            );
+        }
+    }
+    if let Some(pid) = running_daemon_pid(paths) {
+        bail!("daemon is running (pid {pid}); stop it with `axon stop` before importing");
+    }
+    for path in [&paths.identity_key, &paths.identity_pub] {
+        if path.exists() {
+            let backup = backup_file_with_timestamp(path)?;
+            eprintln!("Moved {} to {}", path.display(), backup.display());
+        }
+    }
+    identity.install(paths)?;
+    println!("Imported {}", identity.agent_id());
+    Ok(())
+}
+
+fn select_identity_addr(
+    addr_override: Option<&str>,
+    advertise_addr: Option<&str>,
+    port: u16,
+) -> Result<String> {
+    if let Some(addr) = addr_override {
+        return normalize_addr(addr);
+    }
+    if let Some(addr) = advertise_addr {
+        return normalize_addr(addr);
+    }
+
+    normalize_addr(&format!("{}:{port}", default_identity_host()))
+}
+
+fn normalize_addr(input: &str) -> Result<String> {
+    let parsed = PeerAddr::parse(input)?;
+    Ok(parsed.to_string())
+}
+
+fn split_addr_port(addr: &str) -> Result<(String, u16)> {
+    let parsed = PeerAddr::parse(addr)?;
+    match parsed {
+        PeerAddr::Socket(socket) => Ok((socket.ip().to_string(), socket.port())),
+        PeerAddr::Host { host, port } => Ok((host, port)),
+    }
+}
+
+fn default_identity_host() -> String {
+    for key in ["HOSTNAME", "COMPUTERNAME"] {
+        if let Ok(value) = std::env::var(key) {
+            let value = value.trim();
+            if !value.is_empty() && !value.contains(':') {
+                return value.to_string();
+            }
+        }
+    }
+
+    "localhost".to_string()
+}
+
+/// Passphrase from [`PASSPHRASE_ENV`], else prompted on the terminal with
+/// echo off (twice when `confirm` is set).
+fn read_passphrase(confirm: bool) -> Result<String> {
+    if let Ok(passphrase) = std::env::var(PASSPHRASE_ENV) {
+        if passphrase.is_empty() {
+            bail!("{PASSPHRASE_ENV} is set but empty");
+        }
+        return Ok(passphrase);
+    }
+    if !std::io::stdin().is_terminal() {
+        bail!("a passphrase is required; set {PASSPHRASE_ENV} or run from a terminal");
+    }
+    let passphrase = prompt_hidden("Backup passphrase: ")?;
+    if passphrase.is_empty() {
+        bail!("passphrase must not be empty");
+    }
+    if confirm && prompt_hidden("Confirm passphrase: ")? != passphrase {
+        bail!("passphrases do not match");
+    }
+    Ok(passphrase)
+}
+
+fn prompt_hidden(prompt: &str) -> Result<String> {
+    eprint!("{prompt}");
+    std::io::stderr().flush().ok();
+    let fd = libc::STDIN_FILENO;
+    // SAFETY: termios is plain old data; tcgetattr fully initializes it on success.
+    let mut saved: libc::termios = unsafe { std::mem::zeroed() };
+    // SAFETY: fd is stdin and `saved` is a valid termios out-pointer.
+    let echo_disabled = unsafe { libc::tcgetattr(fd, &mut saved) } == 0 && {
+        let mut quiet = saved;
+        quiet.c_lflag &= !libc::ECHO;
+        // SAFETY: `quiet` is a copy of the settings tcgetattr returned.
+        unsafe { libc::tcsetattr(fd, libc::TCSANOW, &quiet) == 0 }
+    };
+    let mut line = String::new();
+    let read = std::io::stdin().lock().read_line(&mut line);
+    if echo_disabled {
+        // SAFETY: restores the settings captured above.
+        unsafe { libc::tcsetattr(fd, libc::TCSANOW, &saved) };
+    }
+    eprintln!();
+    read.context("failed to read passphrase")?;
+    Ok(line.trim_end_matches(['\r', '\n']).to_string())
+}
+
+#[cfg(test)]
+#[path = "identity_cmd_tests.rs"]
+mod tests;
@@ -0,0 +1,20 @@
+use super::{select_identity_addr, split_addr_port};
+
+#[test]
+fn select_identity_addr_prefers_override_then_config() {
+    let override_addr =
+        select_identity_addr(Some("10.0.0.1:7100"), Some("ignored:7200"), 7300).expect("override");
+    assert_eq!(override_addr, "10.0.0.1:7100");
+
+    let config_addr =
+        select_identity_addr(None, Some("alice.tailnet:7100"), 7300).expect("config advertise");
+    assert_eq!(config_addr, "alice.tailnet:7100");
+}
+
+#[test]
+fn select_identity_addr_falls_back_to_local_host_without_network_probe() {
+    let addr = select_identity_addr(None, None, 7300).expect("fallback addr");
+    let (host, port) = split_addr_port(&addr).expect("split");
+    assert!(!host.is_empty());
+    assert_eq!(port, 7300);
+}
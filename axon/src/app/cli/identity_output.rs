@@ -11,13 +11,17 @@ pub fn render_identity_json(
     addr: &str,
     port: u16,
     uri: &str,
+    expires_at: Option<u64>,
 ) -> Result<String> {
-    serde_json::to_string_pretty(&json!({
+    let mut rendered = json!({
         "agent_id": agent_id,
         "public_key": public_key,
         "addr": addr,
         "port": port,
         "uri": uri,
-    }))
-    .context("failed to encode identity output")
+    });
+    if let Some(expires_at) = expires_at {
+        rendered["expires_at"] = json!(expires_at);
+    }
+    serde_json::to_string_pretty(&rendered).context("failed to encode identity output")
 }
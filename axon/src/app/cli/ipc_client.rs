@@ -1,9 +1,14 @@
+use std::io::ErrorKind;
+use std::path::PathBuf;
 use std::process::ExitCode;
 
 use anyhow::{Context, Result, anyhow};
+use axon::client::IpcClient;
 use axon::config::AxonPaths;
+use axon::errors::{
+    EXIT_DAEMON_NOT_RUNNING, EXIT_FAILURE_REPLY, EXIT_IPC_AUTH, EXIT_RUNTIME, IpcErrorCode,
+};
 use serde_json::{Value, json};
-use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::net::UnixStream;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -18,10 +23,10 @@ pub fn is_unsolicited_event(decoded: &Value) -> bool {
 
 pub fn daemon_reply_exit_code(response: &Value, mode: ResponseMode) -> ExitCode {
     if response.get("ok") == Some(&json!(false)) {
-        if response.get("error").and_then(Value::as_str) == Some("timeout") {
-            return ExitCode::from(3);
-        }
-        return ExitCode::from(2);
+        let code = response
+            .get("error")
+            .and_then(|error| serde_json::from_value::<IpcErrorCode>(error.clone()).ok());
+        return ExitCode::from(code.map_or(EXIT_FAILURE_REPLY, IpcErrorCode::exit_code));
     }
 
     if mode == ResponseMode::Request
@@ -31,76 +36,122 @@ pub fn daemon_reply_exit_code(response: &Value, mode: ResponseMode) -> ExitCode
             .and_then(Value::as_str)
             == Some("error")
     {
-        return ExitCode::from(2);
+        return ExitCode::from(EXIT_FAILURE_REPLY);
     }
 
     ExitCode::SUCCESS
 }
 
+/// The daemon socket could not be opened.
+#[derive(Debug)]
+pub struct IpcConnectError {
+    socket: PathBuf,
+    source: std::io::Error,
+}
+
+impl IpcConnectError {
+    /// Permission errors mean the socket belongs to another user (it is
+    /// mode 0600); anything else means no daemon is listening.
+    pub fn exit_code(&self) -> u8 {
+        if self.source.kind() == ErrorKind::PermissionDenied {
+            EXIT_IPC_AUTH
+        } else {
+            EXIT_DAEMON_NOT_RUNNING
+        }
+    }
+}
+
+impl std::fmt::Display for IpcConnectError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.exit_code() == EXIT_IPC_AUTH {
+            write!(
+                f,
+                "not permitted to connect to daemon socket: {}. Is it owned by another user?",
+                self.socket.display()
+            )
+        } else {
+            write!(
+                f,
+                "failed to connect to daemon socket: {}. Is the daemon running?",
+                self.socket.display()
+            )
+        }
+    }
+}
+
+impl std::error::Error for IpcConnectError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+/// Open the daemon socket. Shared by every IPC client so connect failures
+/// map to the same exit codes.
+pub async fn connect_socket(paths: &AxonPaths) -> Result<UnixStream> {
+    tracing::debug!(socket = %paths.socket.display(), "connecting to daemon IPC socket");
+    UnixStream::connect(&paths.socket).await.map_err(|source| {
+        anyhow!(IpcConnectError {
+            socket: paths.socket.clone(),
+            source,
+        })
+    })
+}
+
+/// Exit code for an error returned by a command: the connect codes for
+/// [`IpcConnectError`], otherwise 1.
+pub fn error_exit_code(err: &anyhow::Error) -> ExitCode {
+    let code = err
+        .chain()
+        .find_map(|cause| cause.downcast_ref::<IpcConnectError>())
+        .map_or(EXIT_RUNTIME, IpcConnectError::exit_code);
+    ExitCode::from(code)
+}
+
 pub fn render_json(value: &Value) -> Result<String> {
     serde_json::to_string_pretty(value).context("failed to encode response output")
 }
 
+pub fn print_json_value(value: &Value) -> Result<()> {
+    println!("{}", render_json(value)?);
+    Ok(())
+}
+
 pub async fn send_ipc(paths: &AxonPaths, command: Value) -> Result<Value> {
-    let line = serde_json::to_string(&command).context("failed to serialize IPC command")?;
+    encode_command(&command)?;
+    IpcConnection::connect(paths).await?.command(&command).await
+}
+
+pub(crate) fn encode_command(command: &Value) -> Result<String> {
+    let line = serde_json::to_string(command).context("failed to serialize IPC command")?;
     if line.len() > axon::ipc::MAX_IPC_LINE_LENGTH {
         anyhow::bail!(
             "IPC command size ({} bytes) exceeds the 64KB limit",
             line.len()
         );
     }
+    Ok(line)
+}
 
-    tracing::debug!(socket = %paths.socket.display(), "connecting to daemon IPC socket");
-
-    let mut stream = UnixStream::connect(&paths.socket).await.with_context(|| {
-        format!(
-            "failed to connect to daemon socket: {}. Is the daemon running?",
-            paths.socket.display()
-        )
-    })?;
-
-    tracing::debug!(cmd_bytes = line.len(), "sending IPC command");
-
-    stream
-        .write_all(line.as_bytes())
-        .await
-        .context("failed to write IPC command")?;
-    stream
-        .write_all(b"\n")
-        .await
-        .context("failed to write IPC newline")?;
-
-    let mut reader = BufReader::new(stream);
-    loop {
-        let mut response = Vec::new();
-        let bytes = reader
-            .read_until(b'\n', &mut response)
-            .await
-            .context("failed to read IPC response")?;
-        if bytes == 0 {
-            return Err(anyhow!(
-                "daemon closed connection without a command response"
-            ));
-        }
-
-        if response.last() == Some(&b'\n') {
-            response.pop();
-        }
+/// One IPC connection carrying commands one at a time, for callers that
+/// issue several commands (e.g. `axon bench`).
+pub struct IpcConnection {
+    client: IpcClient,
+}
 
-        let line = std::str::from_utf8(&response)
-            .context("failed to decode IPC response as UTF-8")?
-            .trim();
-        if line.is_empty() {
-            continue;
-        }
+impl IpcConnection {
+    pub async fn connect(paths: &AxonPaths) -> Result<Self> {
+        let stream = connect_socket(paths).await?;
+        Ok(Self {
+            client: IpcClient::from_stream(stream),
+        })
+    }
 
-        let decoded: Value = serde_json::from_str(line).context("failed to decode IPC response")?;
-        if is_unsolicited_event(&decoded) {
-            tracing::debug!("skipping unsolicited IPC event");
-            continue;
-        }
+    /// Send `command` and wait for its reply, skipping unsolicited events.
+    pub async fn command(&mut self, command: &Value) -> Result<Value> {
+        encode_command(command)?;
+        let reply = self.client.command(command.clone()).await?;
         tracing::debug!("received IPC command response");
-        return Ok(decoded);
+        Ok(reply)
     }
 }
 
@@ -3,24 +3,61 @@ use std::process::ExitCode;
 
 use serde_json::json;
 
-use super::{ResponseMode, daemon_reply_exit_code, is_unsolicited_event, send_ipc};
+use super::{
+    ResponseMode, daemon_reply_exit_code, error_exit_code, is_unsolicited_event, send_ipc,
+};
+
+fn missing_paths() -> axon::config::AxonPaths {
+    axon::config::AxonPaths {
+        root: PathBuf::from("/tmp/axon-test-nonexistent"),
+        socket: PathBuf::from("/tmp/axon-test-nonexistent/axon.sock"),
+        config: PathBuf::from("/tmp/axon-test-nonexistent/config.yaml"),
+        known_peers: PathBuf::from("/tmp/axon-test-nonexistent/known_peers.json"),
+        revocations: PathBuf::from("/tmp/axon-test-nonexistent/revocations.json"),
+        blocklist: PathBuf::from("/tmp/axon-test-nonexistent/blocklist.json"),
+        identity_key: PathBuf::from("/tmp/axon-test-nonexistent/identity.key"),
+        identity_pub: PathBuf::from("/tmp/axon-test-nonexistent/identity.pub"),
+        history: PathBuf::from("/tmp/axon-test-nonexistent/history.sqlite3"),
+        audit: PathBuf::from("/tmp/axon-test-nonexistent/audit.jsonl"),
+        events: PathBuf::from("/tmp/axon-test-nonexistent/events.jsonl"),
+        stats: PathBuf::from("/tmp/axon-test-nonexistent/stats.json"),
+        profile: None,
+    }
+}
 
 #[test]
-fn daemon_error_maps_to_exit_two() {
+fn generic_daemon_error_maps_to_exit_two() {
     let code = daemon_reply_exit_code(
-        &json!({"ok": false, "error": "peer_not_found"}),
+        &json!({"ok": false, "error": "invalid_command"}),
         ResponseMode::Generic,
     );
     assert_eq!(code, ExitCode::from(2));
 }
 
 #[test]
-fn timeout_error_maps_to_exit_three() {
-    let code = daemon_reply_exit_code(
-        &json!({"ok": false, "error": "timeout"}),
-        ResponseMode::Request,
+fn typed_daemon_errors_map_to_distinct_exit_codes() {
+    for (error, expected) in [
+        ("peer_not_found", 3),
+        ("timeout", 4),
+        ("peer_unreachable", 5),
+    ] {
+        let code =
+            daemon_reply_exit_code(&json!({"ok": false, "error": error}), ResponseMode::Request);
+        assert_eq!(code, ExitCode::from(expected), "{error}");
+    }
+}
+
+#[tokio::test]
+async fn missing_daemon_socket_maps_to_exit_seven() {
+    let err = send_ipc(&missing_paths(), json!({"cmd": "status"}))
+        .await
+        .expect_err("no daemon listening");
+    assert!(err.to_string().contains("Is the daemon running?"), "{err}");
+    assert_eq!(error_exit_code(&err), ExitCode::from(7));
+    assert_eq!(
+        error_exit_code(&anyhow::anyhow!("unrelated")),
+        ExitCode::FAILURE
     );
-    assert_eq!(code, ExitCode::from(3));
 }
 
 #[test]
@@ -38,14 +75,7 @@ fn request_with_embedded_error_envelope_maps_to_exit_two() {
 
 #[tokio::test]
 async fn send_ipc_rejects_oversized_command() {
-    let paths = axon::config::AxonPaths {
-        root: PathBuf::from("/tmp/axon-test-nonexistent"),
-        socket: PathBuf::from("/tmp/axon-test-nonexistent/axon.sock"),
-        config: PathBuf::from("/tmp/axon-test-nonexistent/config.yaml"),
-        known_peers: PathBuf::from("/tmp/axon-test-nonexistent/known_peers.json"),
-        identity_key: PathBuf::from("/tmp/axon-test-nonexistent/identity.key"),
-        identity_pub: PathBuf::from("/tmp/axon-test-nonexistent/identity.pub"),
-    };
+    let paths = missing_paths();
 
     let big_payload = "x".repeat(70_000);
     let command = json!({"cmd": "send", "to": "ed25519.00000000000000000000000000000000", "kind": "message", "payload": big_payload});
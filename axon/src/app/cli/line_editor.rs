@@ -0,0 +1,202 @@
+use std::io::{BufRead, IsTerminal, Read, Write};
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::mpsc;
+
+/// Produces completion for the current line: the replacement line and, when
+/// several candidates remain, the candidates to list.
+pub type Completer = Arc<dyn Fn(&str) -> (String, Vec<String>) + Send + Sync>;
+
+/// Line being edited, shared so output can be printed above the prompt.
+#[derive(Default)]
+struct EditState {
+    /// Whether a prompt is currently on screen.
+    active: bool,
+    line: String,
+}
+
+/// Minimal terminal line editor for `axon shell`: raw-mode input with
+/// backspace, Ctrl-U, Ctrl-C and Tab completion. Without a terminal it reads
+/// plain lines and prints no prompt.
+///
+/// Input runs on its own thread and reads one line per [`LineEditor::ready`]
+/// call, so command output never interleaves with the prompt.
+pub struct LineEditor {
+    state: Arc<Mutex<EditState>>,
+    prompt: &'static str,
+    ready_tx: std::sync::mpsc::Sender<()>,
+    lines: mpsc::Receiver<String>,
+}
+
+impl LineEditor {
+    pub fn spawn(prompt: &'static str, completer: Completer) -> Self {
+        let state = Arc::new(Mutex::new(EditState::default()));
+        let (ready_tx, ready_rx) = std::sync::mpsc::channel::<()>();
+        let (line_tx, lines) = mpsc::channel(1);
+        let thread_state = state.clone();
+        std::thread::spawn(move || {
+            let interactive = std::io::stdin().is_terminal();
+            while ready_rx.recv().is_ok() {
+                let line = if interactive {
+                    read_line_raw(prompt, &thread_state, &completer)
+                } else {
+                    read_line_plain()
+                };
+                match line {
+                    Some(line) => {
+                        if line_tx.blocking_send(line).is_err() {
+                            break;
+                        }
+                    }
+                    None => break,
+                }
+            }
+        });
+        Self {
+            state,
+            prompt,
+            ready_tx,
+            lines,
+        }
+    }
+
+    /// Allow the input thread to read the next line.
+    pub fn ready(&self) {
+        let _ = self.ready_tx.send(());
+    }
+
+    /// Next entered line; `None` at end of input (Ctrl-D).
+    pub async fn next_line(&mut self) -> Option<String> {
+        self.lines.recv().await
+    }
+
+    /// Print `text` without corrupting a prompt that is being edited.
+    pub fn print_above(&self, text: &str) {
+        let Ok(state) = self.state.lock() else {
+            println!("{text}");
+            return;
+        };
+        let mut out = std::io::stdout().lock();
+        if state.active {
+            let _ = write!(out, "\r\x1b[K{text}\n{}{}", self.prompt, state.line);
+        } else {
+            let _ = writeln!(out, "{text}");
+        }
+        let _ = out.flush();
+    }
+}
+
+fn read_line_plain() -> Option<String> {
+    let mut line = String::new();
+    match std::io::stdin().lock().read_line(&mut line) {
+        Ok(0) | Err(_) => None,
+        Ok(_) => Some(line.trim_end_matches(['\r', '\n']).to_string()),
+    }
+}
+
+/// Terminal settings restored on drop.
+struct RawMode {
+    saved: libc::termios,
+}
+
+impl RawMode {
+    fn enable() -> Option<Self> {
+        let fd = libc::STDIN_FILENO;
+        // SAFETY: termios is plain old data; tcgetattr fully initializes it on success.
+        let mut saved: libc::termios = unsafe { std::mem::zeroed() };
+        // SAFETY: fd is stdin and `saved` is a valid termios out-pointer.
+        if unsafe { libc::tcgetattr(fd, &mut saved) } != 0 {
+            return None;
+        }
+        let mut raw = saved;
+        // Byte-at-a-time input without echo; Ctrl-C arrives as a byte. Output
+        // processing stays on, so `\n` still moves to column 0.
+        raw.c_lflag &= !(libc::ICANON | libc::ECHO | libc::ISIG);
+        raw.c_cc[libc::VMIN] = 1;
+        raw.c_cc[libc::VTIME] = 0;
+        // SAFETY: `raw` is a modified copy of the settings tcgetattr returned.
+        (unsafe { libc::tcsetattr(fd, libc::TCSANOW, &raw) } == 0).then_some(Self { saved })
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        // SAFETY: restores the settings captured in `enable`.
+        unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &self.saved) };
+    }
+}
+
+fn read_line_raw(prompt: &str, state: &Mutex<EditState>, completer: &Completer) -> Option<String> {
+    let Some(_raw) = RawMode::enable() else {
+        return read_line_plain();
+    };
+    let redraw = |line: &str| {
+        let mut out = std::io::stdout().lock();
+        let _ = write!(out, "\r\x1b[K{prompt}{line}");
+        let _ = out.flush();
+    };
+    let finish = |line: Option<String>| {
+        if let Ok(mut state) = state.lock() {
+            state.active = false;
+            state.line.clear();
+        }
+        println!();
+        line
+    };
+    if let Ok(mut state) = state.lock() {
+        state.active = true;
+        redraw(&state.line);
+    }
+
+    let mut stdin = std::io::stdin().lock();
+    let mut pending = Vec::new();
+    let mut byte = [0u8; 1];
+    loop {
+        if !matches!(stdin.read(&mut byte), Ok(1)) {
+            return finish(None);
+        }
+        let Ok(mut state) = state.lock() else {
+            return finish(None);
+        };
+        match byte[0] {
+            b'\r' | b'\n' => {
+                let line = std::mem::take(&mut state.line);
+                drop(state);
+                return finish(Some(line));
+            }
+            // Ctrl-D ends the session on an empty line.
+            0x04 if state.line.is_empty() => {
+                drop(state);
+                return finish(None);
+            }
+            // Ctrl-C and Ctrl-U discard the line.
+            0x03 | 0x15 => state.line.clear(),
+            0x7f | 0x08 => {
+                state.line.pop();
+            }
+            b'\t' => {
+                let (line, options) = completer(&state.line);
+                if !options.is_empty() {
+                    println!("\r\x1b[K{}", options.join("  "));
+                }
+                state.line = line;
+            }
+            // Escape sequences (arrow keys): consume `[` and the final byte.
+            0x1b => {
+                let mut seq = [0u8; 2];
+                let _ = stdin.read_exact(&mut seq);
+            }
+            b if b < 0x20 => {}
+            b => {
+                pending.push(b);
+                if let Ok(text) = std::str::from_utf8(&pending) {
+                    state.line.push_str(text);
+                    pending.clear();
+                } else if pending.len() >= 4 {
+                    pending.clear();
+                }
+            }
+        }
+        redraw(&state.line);
+    }
+}
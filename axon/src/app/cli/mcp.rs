@@ -0,0 +1,302 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+use anyhow::{Context, Result};
+use axon::client::IpcClient;
+use axon::config::{AxonPaths, load_persisted_config};
+use axon::message::AgentId;
+use serde_json::{Value, json};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use crate::app::cli::alias::{annotate_peers, resolve_agent_ref};
+use crate::app::cli::ipc_client::connect_socket;
+
+/// MCP revisions this server speaks, newest first.
+const PROTOCOL_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+/// Inbound envelopes kept for `read_inbox`; the oldest are dropped first.
+const INBOX_CAPACITY: usize = 256;
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// Serve the Model Context Protocol on stdin/stdout until stdin closes,
+/// translating tool calls into IPC commands on one daemon connection.
+pub async fn run(paths: &AxonPaths) -> Result<()> {
+    let stream = connect_socket(paths).await?;
+    let aliases = load_persisted_config(&paths.config).await?.aliases;
+    let server = Arc::new(McpServer::new(IpcClient::from_stream(stream), aliases));
+
+    let (out_tx, mut out_rx) = mpsc::unbounded_channel::<Value>();
+    let writer = tokio::spawn(async move {
+        let mut stdout = tokio::io::stdout();
+        while let Some(message) = out_rx.recv().await {
+            let mut line = message.to_string();
+            line.push('\n');
+            stdout.write_all(line.as_bytes()).await?;
+            stdout.flush().await?;
+        }
+        anyhow::Ok(())
+    });
+
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    while let Some(line) = lines.next_line().await.context("failed to read stdin")? {
+        if line.trim().is_empty() {
+            continue;
+        }
+        // Calls run concurrently so a slow `send_request` does not hold up
+        // `ping` or other tools.
+        let (server, out_tx) = (server.clone(), out_tx.clone());
+        tokio::spawn(async move {
+            if let Some(reply) = server.handle_line(&line).await {
+                let _ = out_tx.send(reply);
+            }
+        });
+    }
+    drop(out_tx);
+    writer.await.context("stdout writer failed")?
+}
+
+/// MCP request handling over one daemon connection.
+pub struct McpServer {
+    client: IpcClient,
+    aliases: BTreeMap<String, AgentId>,
+    inbox: Arc<Mutex<Inbox>>,
+    collector: JoinHandle<()>,
+}
+
+#[derive(Default)]
+struct Inbox {
+    messages: VecDeque<Value>,
+    dropped: u64,
+}
+
+impl Drop for McpServer {
+    fn drop(&mut self) {
+        self.collector.abort();
+    }
+}
+
+impl McpServer {
+    pub fn new(client: IpcClient, aliases: BTreeMap<String, AgentId>) -> Self {
+        let inbox = Arc::new(Mutex::new(Inbox::default()));
+        let mut events = client.events();
+        let collected = inbox.clone();
+        let collector = tokio::spawn(async move {
+            while let Some(event) = events.next().await {
+                if event.get("event").and_then(Value::as_str) != Some("inbound") {
+                    continue;
+                }
+                let Some(envelope) = event.get("envelope") else {
+                    continue;
+                };
+                let mut inbox = collected.lock().unwrap_or_else(|err| err.into_inner());
+                if inbox.messages.len() == INBOX_CAPACITY {
+                    inbox.messages.pop_front();
+                    inbox.dropped += 1;
+                }
+                inbox.messages.push_back(envelope.clone());
+            }
+        });
+        Self {
+            client,
+            aliases,
+            inbox,
+            collector,
+        }
+    }
+
+    /// Answer one JSON-RPC line; `None` for notifications.
+    pub async fn handle_line(&self, line: &str) -> Option<Value> {
+        let message: Value = match serde_json::from_str(line) {
+            Ok(message) => message,
+            Err(err) => return Some(error(Value::Null, PARSE_ERROR, err.to_string())),
+        };
+        let Some(method) = message.get("method").and_then(Value::as_str) else {
+            // Responses to requests we never send, or malformed input.
+            return message
+                .get("id")
+                .map(|id| error(id.clone(), INVALID_REQUEST, "missing method"));
+        };
+        let id = message.get("id")?.clone();
+        let params = message.get("params").cloned().unwrap_or_else(|| json!({}));
+        let result = match method {
+            "initialize" => Ok(initialize(&params)),
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(json!({"tools": tools()})),
+            "tools/call" => self.call_tool(&params).await,
+            _ => Err((METHOD_NOT_FOUND, format!("unknown method '{method}'"))),
+        };
+        Some(match result {
+            Ok(result) => json!({"jsonrpc": "2.0", "id": id, "result": result}),
+            Err((code, message)) => error(id, code, message),
+        })
+    }
+
+    async fn call_tool(&self, params: &Value) -> Result<Value, (i64, String)> {
+        let name = params.get("name").and_then(Value::as_str).unwrap_or("");
+        let args = params
+            .get("arguments")
+            .cloned()
+            .unwrap_or_else(|| json!({}));
+        let command = match name {
+            "send_request" | "notify" => {
+                let kind = if name == "send_request" {
+                    "request"
+                } else {
+                    "message"
+                };
+                let to = args.get("to").and_then(Value::as_str).unwrap_or("");
+                let to = match resolve_agent_ref(to, &self.aliases) {
+                    Ok(to) => to,
+                    Err(message) => return Ok(tool_error(message)),
+                };
+                let mut command = json!({
+                    "cmd": "send",
+                    "to": to,
+                    "kind": kind,
+                    "payload": args.get("payload").cloned().unwrap_or_else(|| json!({})),
+                });
+                if let Some(timeout) = args.get("timeout_secs").filter(|_| kind == "request") {
+                    command["timeout_secs"] = timeout.clone();
+                }
+                command
+            }
+            "list_peers" => {
+                let mut command = json!({"cmd": "peers"});
+                if let Some(tag) = args.get("tag") {
+                    command["tag"] = tag.clone();
+                }
+                command
+            }
+            "read_inbox" => return Ok(self.read_inbox(&args)),
+            _ => return Err((INVALID_PARAMS, format!("unknown tool '{name}'"))),
+        };
+        let mut reply = match self.client.command(command).await {
+            Ok(reply) => reply,
+            Err(err) => return Ok(tool_error(format!("{err:#}"))),
+        };
+        if name == "list_peers" {
+            annotate_peers(&mut reply, &self.aliases);
+        }
+        let failed = reply.get("ok") == Some(&Value::Bool(false));
+        Ok(tool_result(reply, failed))
+    }
+
+    fn read_inbox(&self, args: &Value) -> Value {
+        let mut inbox = self.inbox.lock().unwrap_or_else(|err| err.into_inner());
+        let limit = args
+            .get("limit")
+            .and_then(Value::as_u64)
+            .map_or(inbox.messages.len(), |limit| limit as usize);
+        let take = limit.min(inbox.messages.len());
+        let messages: Vec<Value> = inbox.messages.drain(..take).collect();
+        let result = json!({
+            "messages": messages,
+            "remaining": inbox.messages.len(),
+            "dropped": inbox.dropped,
+        });
+        inbox.dropped = 0;
+        tool_result(result, false)
+    }
+}
+
+fn initialize(params: &Value) -> Value {
+    let requested = params.get("protocolVersion").and_then(Value::as_str);
+    let version = requested
+        .filter(|version| PROTOCOL_VERSIONS.contains(version))
+        .unwrap_or(PROTOCOL_VERSIONS[0]);
+    json!({
+        "protocolVersion": version,
+        "capabilities": {"tools": {}},
+        "serverInfo": {"name": "axon", "version": env!("CARGO_PKG_VERSION")},
+        "instructions": "Tools for messaging other agents on the local AXON mesh. \
+            Agents are addressed by agent ID (ed25519.<32 hex>) or a configured alias; \
+            list_peers shows who is reachable.",
+    })
+}
+
+fn tools() -> Value {
+    let to = json!({
+        "type": "string",
+        "description": "Recipient agent ID (ed25519.<32 hex>) or alias.",
+    });
+    let payload = json!({
+        "type": "object",
+        "description": "Application payload delivered to the peer.",
+    });
+    json!([
+        {
+            "name": "send_request",
+            "description": "Send a request to a peer and wait for its response.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "to": to,
+                    "payload": payload,
+                    "timeout_secs": {"type": "integer", "minimum": 1, "description": "Seconds to wait for the response (daemon default 30)."},
+                },
+                "required": ["to", "payload"],
+            },
+        },
+        {
+            "name": "notify",
+            "description": "Send a fire-and-forget message to a peer.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {"to": to, "payload": payload},
+                "required": ["to", "payload"],
+            },
+        },
+        {
+            "name": "list_peers",
+            "description": "List peers known to the daemon with their connection status.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "tag": {"type": "string", "description": "Only peers carrying this tag."},
+                },
+            },
+        },
+        {
+            "name": "read_inbox",
+            "description": "Take inbound envelopes received since the last call, oldest first.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "limit": {"type": "integer", "minimum": 1, "description": "Most envelopes to return; the rest stay queued."},
+                },
+            },
+        },
+    ])
+}
+
+fn tool_result(value: Value, is_error: bool) -> Value {
+    json!({
+        "content": [{"type": "text", "text": value.to_string()}],
+        "structuredContent": value,
+        "isError": is_error,
+    })
+}
+
+fn tool_error(message: impl Into<String>) -> Value {
+    json!({
+        "content": [{"type": "text", "text": message.into()}],
+        "isError": true,
+    })
+}
+
+fn error(id: Value, code: i64, message: impl Into<String>) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": {"code": code, "message": message.into()},
+    })
+}
+
+#[cfg(test)]
+#[path = "mcp_tests.rs"]
+mod tests;
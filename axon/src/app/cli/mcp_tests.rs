@@ -0,0 +1,203 @@
+use tempfile::tempdir;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{UnixListener, UnixStream};
+
+use super::*;
+
+const PEER: &str = "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+
+/// A server wired to a fake daemon that first sends `events`, then answers
+/// each command by echoing it (`ok: false` for sends to agent `b…`).
+async fn server_with_daemon(events: Vec<Value>) -> (McpServer, tempfile::TempDir) {
+    let dir = tempdir().unwrap();
+    let socket = dir.path().join("axon.sock");
+    let listener = UnixListener::bind(&socket).unwrap();
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let (read_half, mut writer) = stream.into_split();
+        for event in events {
+            writer
+                .write_all(format!("{event}\n").as_bytes())
+                .await
+                .unwrap();
+        }
+        let mut lines = BufReader::new(read_half).lines();
+        while let Some(line) = lines.next_line().await.unwrap() {
+            let command: Value = serde_json::from_str(&line).unwrap();
+            let ok = command["to"] != "ed25519.bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb";
+            let reply = json!({"ok": ok, "echo": command, "peers": [{"agent_id": PEER}]});
+            writer
+                .write_all(format!("{reply}\n").as_bytes())
+                .await
+                .unwrap();
+        }
+    });
+    let client = IpcClient::from_stream(UnixStream::connect(&socket).await.unwrap());
+    let aliases = BTreeMap::from([("worker".to_string(), AgentId::from(PEER))]);
+    (McpServer::new(client, aliases), dir)
+}
+
+async fn call(server: &McpServer, request: Value) -> Value {
+    server
+        .handle_line(&request.to_string())
+        .await
+        .expect("reply")
+}
+
+#[tokio::test]
+async fn initialize_negotiates_version_and_lists_tools() {
+    let (server, _dir) = server_with_daemon(Vec::new()).await;
+    let reply = call(
+        &server,
+        json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {"protocolVersion": "2024-11-05"}}),
+    )
+    .await;
+    assert_eq!(reply["id"], 1);
+    assert_eq!(reply["result"]["protocolVersion"], "2024-11-05");
+    assert_eq!(reply["result"]["serverInfo"]["name"], "axon");
+
+    let reply = call(
+        &server,
+        json!({"jsonrpc": "2.0", "id": 2, "method": "initialize", "params": {"protocolVersion": "1999-01-01"}}),
+    )
+    .await;
+    assert_eq!(reply["result"]["protocolVersion"], PROTOCOL_VERSIONS[0]);
+
+    let notification = json!({"jsonrpc": "2.0", "method": "notifications/initialized"});
+    assert!(
+        server
+            .handle_line(&notification.to_string())
+            .await
+            .is_none()
+    );
+
+    let reply = call(
+        &server,
+        json!({"jsonrpc": "2.0", "id": "t", "method": "tools/list"}),
+    )
+    .await;
+    let names: Vec<&str> = reply["result"]["tools"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|tool| tool["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        names,
+        ["send_request", "notify", "list_peers", "read_inbox"]
+    );
+}
+
+#[tokio::test]
+async fn protocol_errors_use_json_rpc_codes() {
+    let (server, _dir) = server_with_daemon(Vec::new()).await;
+    let reply = server.handle_line("{not json").await.unwrap();
+    assert_eq!(reply["error"]["code"], PARSE_ERROR);
+
+    let reply = call(
+        &server,
+        json!({"jsonrpc": "2.0", "id": 1, "method": "resources/list"}),
+    )
+    .await;
+    assert_eq!(reply["error"]["code"], METHOD_NOT_FOUND);
+
+    let reply = call(
+        &server,
+        json!({"jsonrpc": "2.0", "id": 2, "method": "tools/call", "params": {"name": "teleport"}}),
+    )
+    .await;
+    assert_eq!(reply["error"]["code"], INVALID_PARAMS);
+}
+
+#[tokio::test]
+async fn tools_translate_to_ipc_commands() {
+    let (server, _dir) = server_with_daemon(Vec::new()).await;
+    let reply = call(
+        &server,
+        json!({"jsonrpc": "2.0", "id": 1, "method": "tools/call", "params": {
+            "name": "send_request",
+            "arguments": {"to": "worker", "payload": {"q": 1}, "timeout_secs": 5},
+        }}),
+    )
+    .await;
+    let result = &reply["result"];
+    assert_eq!(result["isError"], false);
+    assert_eq!(
+        result["structuredContent"]["echo"],
+        json!({"cmd": "send", "to": PEER, "kind": "request", "payload": {"q": 1}, "timeout_secs": 5})
+    );
+
+    let reply = call(
+        &server,
+        json!({"jsonrpc": "2.0", "id": 2, "method": "tools/call", "params": {
+            "name": "notify",
+            "arguments": {"to": "ed25519.bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb", "payload": {}, "timeout_secs": 5},
+        }}),
+    )
+    .await;
+    assert_eq!(reply["result"]["isError"], true, "daemon ok:false");
+    assert_eq!(
+        reply["result"]["structuredContent"]["echo"]["kind"],
+        "message"
+    );
+    assert!(
+        reply["result"]["structuredContent"]["echo"]
+            .get("timeout_secs")
+            .is_none()
+    );
+
+    let reply = call(
+        &server,
+        json!({"jsonrpc": "2.0", "id": 3, "method": "tools/call", "params": {
+            "name": "notify", "arguments": {"to": "nobody", "payload": {}},
+        }}),
+    )
+    .await;
+    assert_eq!(reply["result"]["isError"], true);
+    assert!(
+        reply["result"]["content"][0]["text"]
+            .as_str()
+            .unwrap()
+            .contains("alias")
+    );
+
+    let reply = call(
+        &server,
+        json!({"jsonrpc": "2.0", "id": 4, "method": "tools/call", "params": {"name": "list_peers"}}),
+    )
+    .await;
+    let peers = &reply["result"]["structuredContent"]["peers"];
+    assert_eq!(peers[0]["alias"], "worker");
+}
+
+#[tokio::test]
+async fn read_inbox_drains_inbound_envelopes() {
+    let events = vec![
+        json!({"event": "peer_connected", "agent_id": PEER}),
+        json!({"event": "inbound", "from": PEER, "envelope": {"n": 1}}),
+        json!({"event": "inbound", "from": PEER, "envelope": {"n": 2}}),
+    ];
+    let (server, _dir) = server_with_daemon(events).await;
+    let read = |limit: Value| {
+        json!({"jsonrpc": "2.0", "id": 1, "method": "tools/call", "params": {
+            "name": "read_inbox", "arguments": {"limit": limit},
+        }})
+    };
+    // Wait until both events have been collected.
+    for _ in 0..100 {
+        if server.inbox.lock().unwrap().messages.len() == 2 {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    }
+
+    let reply = call(&server, read(json!(1))).await;
+    let inbox = &reply["result"]["structuredContent"];
+    assert_eq!(inbox["messages"], json!([{"n": 1}]));
+    assert_eq!(inbox["remaining"], 1);
+
+    let reply = call(&server, read(Value::Null)).await;
+    let inbox = &reply["result"]["structuredContent"];
+    assert_eq!(inbox["messages"], json!([{"n": 2}]));
+    assert_eq!(inbox["remaining"], 0);
+}
@@ -1,5 +1,33 @@
+pub mod alias;
+pub mod audit_cmd;
+pub mod bench;
+pub mod block_cmd;
 pub mod config_cmd;
+pub mod connect_cmd;
+pub mod daemon_ctl;
 pub mod format;
+pub mod history_args;
+pub mod identity_cmd;
 pub mod identity_output;
 pub mod ipc_client;
+pub mod line_editor;
+pub mod mcp;
 pub mod notify_payload;
+pub mod notify_stdin;
+pub mod output;
+pub mod payload_input;
+pub mod peer_tags;
+pub mod peers_file;
+pub mod peers_watch;
+pub mod pins_cmd;
+pub mod query_cmd;
+pub mod record;
+pub mod reset;
+pub mod revoke_cmd;
+pub mod send_cmd;
+pub mod service_cmd;
+pub mod service_manager;
+pub mod service_units;
+pub mod shell;
+pub mod top;
+pub mod watch;
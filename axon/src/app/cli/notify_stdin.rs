@@ -0,0 +1,67 @@
+use std::process::ExitCode;
+
+use anyhow::{Context, Result};
+use axon::config::AxonPaths;
+use axon::message::MessageKind;
+use serde_json::{Value, json};
+use tokio::io::{AsyncBufReadExt, BufReader};
+
+use crate::app::cli::ipc_client::IpcConnection;
+use crate::app::cli::payload_input::{apply_send_options, parse_payload};
+
+/// `axon notify <agent> --stdin`: send each NDJSON line read from stdin as
+/// its own notify over one IPC connection, printing one result line per
+/// input line as it is acknowledged (none with `quiet`). Every notify joins
+/// `thread` when given and carries `headers`.
+pub async fn run(
+    paths: &AxonPaths,
+    to: &str,
+    thread: Option<&str>,
+    headers: &[(String, String)],
+    quiet: bool,
+) -> Result<ExitCode> {
+    let mut conn = IpcConnection::connect(paths).await?;
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    let mut line_no = 0u64;
+    let mut failed = false;
+    while let Some(line) = lines.next_line().await.context("failed to read stdin")? {
+        line_no += 1;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let reply = match parse_payload(&line, MessageKind::Message) {
+            Ok(payload) => {
+                let mut command =
+                    json!({"cmd": "send", "to": to, "kind": "message", "payload": payload});
+                apply_send_options(&mut command, thread, headers);
+                conn.command(&command).await?
+            }
+            Err(err) => {
+                json!({"ok": false, "error": "invalid_payload", "message": format!("{err:#}")})
+            }
+        };
+        failed |= reply.get("ok") != Some(&json!(true));
+        if !quiet {
+            println!("{}", line_result(line_no, reply));
+        }
+    }
+    Ok(if failed {
+        ExitCode::from(2)
+    } else {
+        ExitCode::SUCCESS
+    })
+}
+
+/// The daemon reply (or local parse error) tagged with its 1-based input
+/// line, as one compact JSON line.
+pub(crate) fn line_result(line_no: u64, reply: Value) -> String {
+    let mut result = json!({ "line": line_no });
+    if let (Some(out), Value::Object(fields)) = (result.as_object_mut(), reply) {
+        out.extend(fields);
+    }
+    result.to_string()
+}
+
+#[cfg(test)]
+#[path = "notify_stdin_tests.rs"]
+mod tests;
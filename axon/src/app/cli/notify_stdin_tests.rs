@@ -0,0 +1,24 @@
+use super::*;
+
+#[test]
+fn line_result_tags_reply_with_input_line() {
+    let result: Value = serde_json::from_str(&line_result(
+        3,
+        json!({"ok": true, "msg_id": "550e8400-e29b-41d4-a716-446655440000"}),
+    ))
+    .expect("decode");
+    assert_eq!(
+        result,
+        json!({"line": 3, "ok": true, "msg_id": "550e8400-e29b-41d4-a716-446655440000"})
+    );
+}
+
+#[test]
+fn line_result_is_a_single_line() {
+    let rendered = line_result(
+        1,
+        json!({"ok": false, "error": "invalid_payload", "message": "payload must be a JSON object"}),
+    );
+    assert!(!rendered.contains('\n'));
+    assert!(rendered.starts_with("{\"error\":\"invalid_payload\",\"line\":1,"));
+}
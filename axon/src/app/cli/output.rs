@@ -0,0 +1,192 @@
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::app::cli::format::{render_peers_human, render_status_human, render_whoami_human};
+use crate::app::cli::ipc_client::render_json;
+
+/// Version of every `--output json|yaml` document. Adding a field is not a
+/// breaking change; renaming, removing, or retyping one bumps the version.
+pub const OUTPUT_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Json,
+    Yaml,
+    Table,
+}
+
+/// Commands with a versioned output document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Schema {
+    Peers,
+    Status,
+    Whoami,
+}
+
+impl Schema {
+    pub fn name(self) -> &'static str {
+        match self {
+            Schema::Peers => "axon.peers",
+            Schema::Status => "axon.status",
+            Schema::Whoami => "axon.whoami",
+        }
+    }
+}
+
+// The document bodies list their fields explicitly so new IPC reply fields do
+// not leak into the schema unversioned. Optional values serialize as `null`
+// rather than being omitted, keeping every key present.
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PeersBody {
+    peers: Vec<PeerEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PeerEntry {
+    agent_id: String,
+    #[serde(default)]
+    alias: Option<String>,
+    #[serde(default)]
+    addr: Option<String>,
+    status: String,
+    #[serde(default)]
+    rtt_ms: Option<f64>,
+    #[serde(default)]
+    source: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct StatusBody {
+    uptime_secs: u64,
+    peers_connected: u64,
+    messages_sent: u64,
+    messages_received: u64,
+    #[serde(default)]
+    messages_rate_limited: u64,
+    #[serde(default)]
+    lifetime: Option<LifetimeBody>,
+    #[serde(default)]
+    degraded: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct LifetimeBody {
+    #[serde(default)]
+    uptime_secs: u64,
+    #[serde(default)]
+    messages_sent: u64,
+    #[serde(default)]
+    messages_received: u64,
+    #[serde(default)]
+    messages_rate_limited: u64,
+    #[serde(default)]
+    daemon_starts: u64,
+    #[serde(default)]
+    first_started_ms: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct WhoamiBody {
+    agent_id: String,
+    public_key: String,
+    #[serde(default)]
+    name: Option<String>,
+    version: String,
+    uptime_secs: u64,
+}
+
+/// Every document starts with its schema name and version.
+#[derive(Debug, Serialize)]
+struct Document<T> {
+    schema: &'static str,
+    version: u32,
+    #[serde(flatten)]
+    body: T,
+}
+
+#[derive(Debug, Serialize)]
+struct ErrorBody<'a> {
+    error: &'a str,
+    message: &'a str,
+}
+
+/// Render a reply for `--output`; `table` is the default human rendering.
+/// For `json`/`yaml`, failed replies become an `axon.error` document
+/// carrying the IPC error code.
+pub fn render_output(schema: Schema, reply: &Value, format: OutputFormat) -> Result<String> {
+    if format == OutputFormat::Table {
+        let human = match schema {
+            Schema::Peers => render_peers_human(reply),
+            Schema::Status => render_status_human(reply),
+            Schema::Whoami => render_whoami_human(reply),
+        };
+        return human.map_or_else(|| render_json(reply), Ok);
+    }
+    if reply.get("ok") == Some(&json!(false)) {
+        let field = |name: &str| reply.get(name).and_then(Value::as_str);
+        let body = ErrorBody {
+            error: field("error").unwrap_or("unknown"),
+            message: field("message").unwrap_or(""),
+        };
+        return encode("axon.error", body, format);
+    }
+    match schema {
+        Schema::Peers => encode(schema.name(), parse::<PeersBody>(schema, reply)?, format),
+        Schema::Status => encode(schema.name(), parse::<StatusBody>(schema, reply)?, format),
+        Schema::Whoami => encode(schema.name(), parse::<WhoamiBody>(schema, reply)?, format),
+    }
+}
+
+fn parse<T: DeserializeOwned>(schema: Schema, reply: &Value) -> Result<T> {
+    serde_json::from_value(reply.clone()).with_context(|| {
+        format!(
+            "daemon reply does not match the {} v{OUTPUT_SCHEMA_VERSION} schema",
+            schema.name()
+        )
+    })
+}
+
+fn encode<T: Serialize>(schema: &'static str, body: T, format: OutputFormat) -> Result<String> {
+    let document = Document {
+        schema,
+        version: OUTPUT_SCHEMA_VERSION,
+        body,
+    };
+    if format == OutputFormat::Yaml {
+        serde_yaml::to_string(&document)
+            .map(|yaml| yaml.trim_end().to_string())
+            .context("failed to encode YAML output")
+    } else {
+        serde_json::to_string_pretty(&document).context("failed to encode JSON output")
+    }
+}
+
+/// Print a `peers`/`status`/`whoami` reply: `--output` when given, the raw
+/// IPC reply for `--json`, otherwise the table.
+pub fn print_reply(
+    schema: Schema,
+    reply: &Value,
+    json: bool,
+    output: Option<OutputFormat>,
+) -> Result<()> {
+    let format = match output {
+        Some(format) => format,
+        None if json => {
+            println!("{}", render_json(reply)?);
+            return Ok(());
+        }
+        None => OutputFormat::Table,
+    };
+    println!("{}", render_output(schema, reply, format)?);
+    Ok(())
+}
+
+#[cfg(test)]
+#[path = "output_tests.rs"]
+mod tests;
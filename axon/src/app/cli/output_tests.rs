@@ -0,0 +1,72 @@
+use super::*;
+
+const AGENT: &str = "ed25519.aaaa0000000000000000000000000000";
+
+fn document(schema: Schema, reply: &Value) -> Result<Value> {
+    let rendered = render_output(schema, reply, OutputFormat::Json)?;
+    Ok(serde_json::from_str(&rendered).expect("valid JSON"))
+}
+
+#[test]
+fn peers_document_keeps_schema_fields_only() {
+    let reply = json!({"ok": true, "peers": [{
+        "agent_id": AGENT, "addr": "10.0.0.2:7100", "status": "connected",
+        "rtt_ms": 1.25, "source": "static", "tags": ["prod"], "future_field": 1
+    }]});
+    assert_eq!(
+        document(Schema::Peers, &reply).expect("document"),
+        json!({
+            "schema": "axon.peers",
+            "version": OUTPUT_SCHEMA_VERSION,
+            "peers": [{
+                "agent_id": AGENT, "alias": null, "addr": "10.0.0.2:7100",
+                "status": "connected", "rtt_ms": 1.25, "source": "static",
+                "tags": ["prod"]
+            }]
+        })
+    );
+}
+
+#[test]
+fn status_document_fills_optional_fields() {
+    let reply = json!({"ok": true, "uptime_secs": 5, "peers_connected": 0,
+        "messages_sent": 1, "messages_received": 2});
+    let status = document(Schema::Status, &reply).expect("document");
+    assert_eq!(status["schema"], "axon.status");
+    assert_eq!(status["messages_rate_limited"], 0);
+    assert_eq!(status["lifetime"], Value::Null);
+    assert_eq!(status["degraded"], json!([]));
+    assert!(status.get("ok").is_none());
+}
+
+#[test]
+fn whoami_document_requires_identity_fields() {
+    let reply = json!({"ok": true, "agent_id": AGENT, "public_key": "cGs=",
+        "version": "0.5.0", "uptime_secs": 9});
+    let whoami = document(Schema::Whoami, &reply).expect("document");
+    assert_eq!(whoami["schema"], "axon.whoami");
+    assert_eq!(whoami["name"], Value::Null);
+
+    let err = document(Schema::Whoami, &json!({"ok": true})).expect_err("missing fields");
+    assert!(err.to_string().contains("axon.whoami v1"));
+}
+
+#[test]
+fn failed_reply_becomes_error_document() {
+    let reply = json!({"ok": false, "error": "internal_error", "message": "boom"});
+    assert_eq!(
+        document(Schema::Peers, &reply).expect("document"),
+        json!({"schema": "axon.error", "version": 1, "error": "internal_error", "message": "boom"})
+    );
+}
+
+#[test]
+fn yaml_and_table_formats() {
+    let reply = json!({"ok": true, "peers": []});
+    let yaml = render_output(Schema::Peers, &reply, OutputFormat::Yaml).expect("yaml");
+    assert_eq!(yaml, "schema: axon.peers\nversion: 1\npeers: []");
+    let json = render_output(Schema::Peers, &reply, OutputFormat::Json).expect("json");
+    assert!(json.starts_with("{\n  \"schema\": \"axon.peers\",\n  \"version\": 1,"));
+    let table = render_output(Schema::Peers, &reply, OutputFormat::Table).expect("table");
+    assert_eq!(table, "No peers.");
+}
@@ -0,0 +1,93 @@
+use std::io::Read;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result, bail};
+use axon::message::{Envelope, MAX_MESSAGE_SIZE, MessageKind};
+use clap::Args;
+use serde_json::{Value, json};
+
+/// Placeholder agent ID for sizing; routing fields are not sent on the wire.
+const SIZING_AGENT: &str = "ed25519.00000000000000000000000000000000";
+
+/// Structured payload flags shared by `axon request` and `axon notify`. The
+/// JSON object is sent as the whole envelope payload, without the
+/// `message`/`data` wrapper used for text arguments.
+#[derive(Debug, Clone, Default, Args)]
+pub struct PayloadArgs {
+    /// Envelope payload as a JSON object, or `-` to read it from stdin.
+    #[arg(long, value_name = "JSON|-", conflicts_with = "payload_file")]
+    pub payload: Option<String>,
+    /// Read the envelope payload (a JSON object) from a file.
+    #[arg(long, value_name = "PATH")]
+    pub payload_file: Option<PathBuf>,
+}
+
+impl PayloadArgs {
+    /// The structured payload, if one was given, checked to fit in a single
+    /// `kind` envelope.
+    pub fn load(&self, kind: MessageKind) -> Result<Option<Value>> {
+        let text = match (&self.payload, &self.payload_file) {
+            (Some(inline), _) if inline == "-" => read_limited(std::io::stdin().lock())
+                .context("failed to read payload from stdin")?,
+            (Some(inline), _) => inline.clone(),
+            (None, Some(path)) => {
+                let file = std::fs::File::open(path)
+                    .with_context(|| format!("failed to open {}", path.display()))?;
+                read_limited(file).with_context(|| format!("failed to read {}", path.display()))?
+            }
+            (None, None) => return Ok(None),
+        };
+        parse_payload(&text, kind).map(Some)
+    }
+}
+
+/// Read at most one byte past the message limit; anything longer is
+/// rejected by [`parse_payload`] without buffering the rest.
+fn read_limited(reader: impl Read) -> std::io::Result<String> {
+    let mut text = String::new();
+    reader
+        .take(u64::from(MAX_MESSAGE_SIZE) + 1)
+        .read_to_string(&mut text)?;
+    Ok(text)
+}
+
+pub fn parse_payload(text: &str, kind: MessageKind) -> Result<Value> {
+    if text.len() > MAX_MESSAGE_SIZE as usize {
+        bail!("payload exceeds the {MAX_MESSAGE_SIZE}-byte message limit");
+    }
+    let payload: Value = serde_json::from_str(text).context("invalid JSON payload")?;
+    if !payload.is_object() {
+        bail!("payload must be a JSON object");
+    }
+    Envelope::new(SIZING_AGENT, SIZING_AGENT, kind, payload.clone())
+        .wire_encode()
+        .context("payload does not fit in one envelope")?;
+    Ok(payload)
+}
+
+/// Parse a `--header KEY=VALUE` argument. The value is sent as a JSON string.
+pub fn parse_header_arg(input: &str) -> Result<(String, String), String> {
+    match input.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+        _ => Err(format!("expected KEY=VALUE, got '{input}'")),
+    }
+}
+
+/// Set the optional `thread_id` and `headers` fields of a `send` command.
+/// A repeated header key keeps its last value.
+pub fn apply_send_options(command: &mut Value, thread: Option<&str>, headers: &[(String, String)]) {
+    if let Some(thread) = thread {
+        command["thread_id"] = json!(thread);
+    }
+    if !headers.is_empty() {
+        let headers: serde_json::Map<String, Value> = headers
+            .iter()
+            .map(|(key, value)| (key.clone(), json!(value)))
+            .collect();
+        command["headers"] = Value::Object(headers);
+    }
+}
+
+#[cfg(test)]
+#[path = "payload_input_tests.rs"]
+mod tests;
@@ -0,0 +1,101 @@
+use serde_json::json;
+
+use super::*;
+
+#[test]
+fn accepts_json_objects() {
+    let payload = parse_payload(r#"{"task": "build", "args": [1, 2]}"#, MessageKind::Request)
+        .expect("object payload");
+    assert_eq!(payload, json!({"task": "build", "args": [1, 2]}));
+}
+
+#[test]
+fn rejects_non_objects_and_bad_json() {
+    let err = parse_payload("[1, 2]", MessageKind::Message).expect_err("array payload");
+    assert!(err.to_string().contains("JSON object"));
+    let err = parse_payload("{bad", MessageKind::Message).expect_err("invalid JSON");
+    assert!(err.to_string().contains("invalid JSON"));
+}
+
+#[test]
+fn rejects_payloads_over_the_wire_limit() {
+    // Fits under the raw limit but not once wrapped in an envelope.
+    let text = json!({ "blob": "x".repeat(MAX_MESSAGE_SIZE as usize - 20) }).to_string();
+    let err = parse_payload(&text, MessageKind::Request).expect_err("too large");
+    assert!(format!("{err:#}").contains("exceeds maximum"), "{err:#}");
+
+    let text = format!(" {}", "x".repeat(MAX_MESSAGE_SIZE as usize));
+    let err = parse_payload(&text, MessageKind::Request).expect_err("too large");
+    assert!(err.to_string().contains("message limit"));
+}
+
+#[test]
+fn loads_from_inline_value_and_file() {
+    let inline = PayloadArgs {
+        payload: Some(r#"{"a": 1}"#.to_string()),
+        payload_file: None,
+    };
+    assert_eq!(
+        inline.load(MessageKind::Request).expect("inline"),
+        Some(json!({"a": 1}))
+    );
+
+    let dir = tempfile::tempdir().expect("tempdir");
+    let path = dir.path().join("payload.json");
+    std::fs::write(&path, "{\"b\": true}\n").expect("write payload");
+    let from_file = PayloadArgs {
+        payload: None,
+        payload_file: Some(path),
+    };
+    assert_eq!(
+        from_file.load(MessageKind::Message).expect("file"),
+        Some(json!({"b": true}))
+    );
+
+    assert_eq!(
+        PayloadArgs::default()
+            .load(MessageKind::Message)
+            .expect("none"),
+        None
+    );
+
+    let missing = PayloadArgs {
+        payload: None,
+        payload_file: Some(dir.path().join("missing.json")),
+    };
+    let err = missing
+        .load(MessageKind::Message)
+        .expect_err("missing file");
+    assert!(err.to_string().contains("failed to open"));
+}
+
+#[test]
+fn header_args_split_on_first_equals() {
+    assert_eq!(
+        parse_header_arg("trace_id=a=b").unwrap(),
+        ("trace_id".to_string(), "a=b".to_string())
+    );
+    assert_eq!(
+        parse_header_arg("empty=").unwrap(),
+        ("empty".to_string(), String::new())
+    );
+    assert!(parse_header_arg("no-equals").is_err());
+    assert!(parse_header_arg("=value").is_err());
+}
+
+#[test]
+fn send_options_set_only_given_fields() {
+    let mut command = json!({"cmd": "send"});
+    apply_send_options(&mut command, None, &[]);
+    assert_eq!(command, json!({"cmd": "send"}));
+
+    let headers = [
+        ("tenant".to_string(), "a".to_string()),
+        ("tenant".to_string(), "b".to_string()),
+    ];
+    apply_send_options(&mut command, Some("t-1"), &headers);
+    assert_eq!(
+        command,
+        json!({"cmd": "send", "thread_id": "t-1", "headers": {"tenant": "b"}})
+    );
+}
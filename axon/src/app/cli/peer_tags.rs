@@ -0,0 +1,133 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use anyhow::{Result, bail};
+use axon::config::{
+    AxonPaths, MAX_TAG_LEN, is_valid_tag, load_persisted_config, save_persisted_config,
+};
+use axon::message::AgentId;
+use clap::Subcommand;
+use serde_json::json;
+
+use crate::app::cli::alias::parse_agent_ref_arg;
+use crate::app::cli::ipc_client::send_ipc;
+use crate::app::cli::peers_file;
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum PeersCommand {
+    /// Add tags to a peer (stored in config.yaml).
+    Tag {
+        #[arg(value_parser = parse_agent_ref_arg)]
+        agent_id: String,
+        #[arg(required = true, value_parser = parse_tag_arg)]
+        tags: Vec<String>,
+    },
+    /// Remove tags from a peer, or all of its tags when none are given.
+    Untag {
+        #[arg(value_parser = parse_agent_ref_arg)]
+        agent_id: String,
+        #[arg(value_parser = parse_tag_arg)]
+        tags: Vec<String>,
+    },
+    /// Print the static peers in config.yaml, with their tags, as YAML.
+    Export,
+    /// Add or update static peers from a file written by `peers export`.
+    Import { file: PathBuf },
+}
+
+impl PeersCommand {
+    pub(crate) fn agent_id_mut(&mut self) -> Option<&mut String> {
+        match self {
+            PeersCommand::Tag { agent_id, .. } | PeersCommand::Untag { agent_id, .. } => {
+                Some(agent_id)
+            }
+            PeersCommand::Export | PeersCommand::Import { .. } => None,
+        }
+    }
+}
+
+fn parse_tag_arg(input: &str) -> Result<String, String> {
+    if is_valid_tag(input) {
+        Ok(input.to_string())
+    } else {
+        Err(format!(
+            "invalid tag '{input}'; tags start with a letter or digit, use only letters, digits, '.', '-', '_', and are at most {MAX_TAG_LEN} characters"
+        ))
+    }
+}
+
+/// Add `add` to the tags of `agent_id`, skipping ones it already has.
+pub(crate) fn add_tags(tags: &mut BTreeMap<AgentId, Vec<String>>, agent_id: &str, add: &[String]) {
+    let current = tags.entry(AgentId::from(agent_id)).or_default();
+    for tag in add {
+        if !current.contains(tag) {
+            current.push(tag.clone());
+        }
+    }
+}
+
+/// Remove `remove` from the tags of `agent_id` (all of them when `remove` is
+/// empty), dropping the entry once it has none. Returns whether anything was
+/// removed.
+pub(crate) fn remove_tags(
+    tags: &mut BTreeMap<AgentId, Vec<String>>,
+    agent_id: &str,
+    remove: &[String],
+) -> bool {
+    let Some(current) = tags.get_mut(agent_id) else {
+        return false;
+    };
+    let before = current.len();
+    if remove.is_empty() {
+        current.clear();
+    } else {
+        current.retain(|tag| !remove.contains(tag));
+    }
+    let removed = current.len() != before;
+    if current.is_empty() {
+        tags.remove(agent_id);
+    }
+    removed
+}
+
+/// Edit peer tags in `config.yaml`, then ask a running daemon to reload so
+/// `peers` reports them at once. `export` and `import` go to `peers_file`.
+pub async fn run(paths: &AxonPaths, command: &PeersCommand) -> Result<()> {
+    let mut persisted = load_persisted_config(&paths.config).await?;
+    let agent_id = match command {
+        PeersCommand::Export => return peers_file::run_export(paths).await,
+        PeersCommand::Import { file } => return peers_file::run_import(paths, file).await,
+        PeersCommand::Tag { agent_id, tags } => {
+            add_tags(&mut persisted.tags, agent_id, tags);
+            agent_id
+        }
+        PeersCommand::Untag { agent_id, tags } => {
+            if !remove_tags(&mut persisted.tags, agent_id, tags) {
+                bail!("{agent_id} has none of the given tags");
+            }
+            agent_id
+        }
+    };
+    save_persisted_config(&paths.config, &persisted).await?;
+
+    let current = persisted
+        .tags
+        .get(agent_id.as_str())
+        .map(|tags| tags.join(", "))
+        .unwrap_or_else(|| "(none)".to_string());
+    println!("✓ Tags for {agent_id}: {current}");
+
+    if paths.socket.exists() {
+        let response = send_ipc(paths, json!({"cmd": "reload"})).await?;
+        if response.get("ok") != Some(&json!(true)) {
+            eprintln!(
+                "warning: config.yaml was updated but the daemon did not reload it; run `axon reload`"
+            );
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[path = "peer_tags_tests.rs"]
+mod tests;
@@ -0,0 +1,37 @@
+use super::*;
+
+const PEER: &str = "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+
+fn tags(list: &[&str]) -> Vec<String> {
+    list.iter().map(|tag| tag.to_string()).collect()
+}
+
+#[test]
+fn add_tags_skips_duplicates_and_keeps_order() {
+    let mut map = BTreeMap::new();
+    add_tags(&mut map, PEER, &tags(&["prod", "gpu"]));
+    add_tags(&mut map, PEER, &tags(&["gpu", "eu-west"]));
+    assert_eq!(map[PEER], tags(&["prod", "gpu", "eu-west"]));
+}
+
+#[test]
+fn remove_tags_drops_empty_entries() {
+    let mut map = BTreeMap::new();
+    add_tags(&mut map, PEER, &tags(&["prod", "gpu"]));
+    assert!(remove_tags(&mut map, PEER, &tags(&["gpu"])));
+    assert_eq!(map[PEER], tags(&["prod"]));
+    assert!(!remove_tags(&mut map, PEER, &tags(&["gpu"])));
+
+    assert!(remove_tags(&mut map, PEER, &[]));
+    assert!(map.is_empty());
+    assert!(!remove_tags(&mut map, PEER, &[]));
+}
+
+#[test]
+fn tag_arguments_are_validated() {
+    assert!(parse_tag_arg("prod").is_ok());
+    assert!(parse_tag_arg("v1.2_x-y").is_ok());
+    assert!(parse_tag_arg("-prod").is_err());
+    assert!(parse_tag_arg("has space").is_err());
+    assert!(parse_tag_arg("").is_err());
+}
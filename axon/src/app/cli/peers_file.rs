@@ -0,0 +1,199 @@
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+use axon::config::{
+    AxonPaths, PeerAddr, PeerTuningConfig, PersistedConfig, PersistedStaticPeerConfig,
+    load_persisted_config, load_revocations, save_persisted_config,
+};
+use axon::identity::Identity;
+use axon::message::AgentId;
+use axon::peer_token::derive_agent_id_from_pubkey_base64;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::app::cli::ipc_client::send_ipc;
+use crate::app::cli::peer_tags::add_tags;
+
+/// Version written by `axon peers export` and accepted by `axon peers import`.
+pub const PEERS_FILE_VERSION: u32 = 1;
+
+/// A static peer set shared between machines as YAML.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PeersFile {
+    pub version: u32,
+    #[serde(default)]
+    pub peers: Vec<PeersFileEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PeersFileEntry {
+    pub agent_id: AgentId,
+    pub addr: PeerAddr,
+    pub pubkey: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(flatten)]
+    pub tuning: PeerTuningConfig,
+}
+
+/// What `merge` did with each imported peer.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ImportSummary {
+    pub added: usize,
+    pub updated: usize,
+    pub unchanged: usize,
+}
+
+/// The static peers of `config` for `profile`, with their tags.
+pub fn export(config: &PersistedConfig, profile: Option<&str>) -> PeersFile {
+    let peers = config
+        .static_peers(profile)
+        .iter()
+        .map(|peer| PeersFileEntry {
+            agent_id: peer.agent_id.clone(),
+            addr: peer.addr.clone(),
+            pubkey: peer.pubkey.clone(),
+            tuning: peer.tuning,
+            tags: config
+                .tags
+                .get(peer.agent_id.as_str())
+                .cloned()
+                .unwrap_or_default(),
+        })
+        .collect();
+    PeersFile {
+        version: PEERS_FILE_VERSION,
+        peers,
+    }
+}
+
+pub fn parse(raw: &str) -> Result<PeersFile> {
+    let file: PeersFile = serde_yaml::from_str(raw).context("invalid peers file")?;
+    if file.version != PEERS_FILE_VERSION {
+        bail!(
+            "unsupported peers file version {} (expected {PEERS_FILE_VERSION})",
+            file.version
+        );
+    }
+    for (idx, peer) in file.peers.iter().enumerate() {
+        let derived = derive_agent_id_from_pubkey_base64(&peer.pubkey)
+            .with_context(|| format!("peers[{idx}].pubkey is invalid"))?;
+        if derived != peer.agent_id {
+            bail!(
+                "peers[{idx}].agent_id {} does not match pubkey, which derives {derived}",
+                peer.agent_id
+            );
+        }
+    }
+    Ok(file)
+}
+
+/// Add or update the peers of `file` in the static peers of `profile`. An
+/// existing peer keeps its tags and gains any new ones from the file; its
+/// address and connection overrides are replaced.
+pub fn merge(
+    config: &mut PersistedConfig,
+    profile: Option<&str>,
+    file: &PeersFile,
+) -> Result<ImportSummary> {
+    let mut summary = ImportSummary::default();
+    for entry in &file.peers {
+        let tags_before = config.tags.get(entry.agent_id.as_str()).cloned();
+        if !entry.tags.is_empty() {
+            add_tags(&mut config.tags, entry.agent_id.as_str(), &entry.tags);
+        }
+        let tags_changed = config.tags.get(entry.agent_id.as_str()) != tags_before.as_ref();
+        let peers = config.static_peers_mut(profile)?;
+        let existing = peers
+            .iter_mut()
+            .find(|peer| peer.agent_id == entry.agent_id);
+        match existing {
+            None => {
+                peers.push(PersistedStaticPeerConfig {
+                    agent_id: entry.agent_id.clone(),
+                    addr: entry.addr.clone(),
+                    pubkey: entry.pubkey.clone(),
+                    tuning: entry.tuning,
+                });
+                summary.added += 1;
+            }
+            Some(peer) if peer.addr != entry.addr || peer.tuning != entry.tuning => {
+                peer.addr = entry.addr.clone();
+                peer.tuning = entry.tuning;
+                summary.updated += 1;
+            }
+            Some(_) if tags_changed => summary.updated += 1,
+            Some(_) => summary.unchanged += 1,
+        }
+    }
+    Ok(summary)
+}
+
+/// Print the static peers of `config.yaml` as a peers file.
+pub async fn run_export(paths: &AxonPaths) -> Result<()> {
+    let persisted = load_persisted_config(&paths.config).await?;
+    let yaml = serde_yaml::to_string(&export(&persisted, paths.profile.as_deref()))
+        .context("failed to render peers")?;
+    print!("{yaml}");
+    Ok(())
+}
+
+/// Merge a peers file into `config.yaml`, then ask a running daemon to reload
+/// so the new static peers are dialed at once. Nothing is written when any
+/// entry is invalid or revoked.
+pub async fn run_import(paths: &AxonPaths, file: &Path) -> Result<()> {
+    let raw = tokio::fs::read_to_string(file)
+        .await
+        .with_context(|| format!("failed to read {}", file.display()))?;
+    let mut peers_file = parse(&raw).with_context(|| file.display().to_string())?;
+
+    let identity = Identity::load_or_generate(paths)?;
+    let before = peers_file.peers.len();
+    peers_file
+        .peers
+        .retain(|peer| peer.agent_id.as_str() != identity.agent_id());
+    let skipped_self = before != peers_file.peers.len();
+
+    let revocations = load_revocations(&paths.revocations).await?;
+    if let Some(peer) = peers_file
+        .peers
+        .iter()
+        .find(|peer| revocations.iter().any(|r| r.pubkey == peer.pubkey))
+    {
+        bail!(
+            "refusing to import revoked peer {} (listed in {})",
+            peer.agent_id,
+            paths.revocations.display()
+        );
+    }
+
+    let mut persisted = load_persisted_config(&paths.config).await?;
+    let summary = merge(&mut persisted, paths.profile.as_deref(), &peers_file)?;
+    if summary.added + summary.updated > 0 {
+        save_persisted_config(&paths.config, &persisted).await?;
+    }
+    if skipped_self {
+        println!("Skipped this agent's own entry.");
+    }
+    println!(
+        "✓ Imported {} peers: {} added, {} updated, {} unchanged",
+        peers_file.peers.len(),
+        summary.added,
+        summary.updated,
+        summary.unchanged
+    );
+
+    if summary.added + summary.updated > 0 && paths.socket.exists() {
+        let response = send_ipc(paths, json!({"cmd": "reload"})).await?;
+        if response.get("ok") != Some(&json!(true)) {
+            eprintln!(
+                "warning: config.yaml was updated but the daemon did not reload it; run `axon reload`"
+            );
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[path = "peers_file_tests.rs"]
+mod tests;
@@ -0,0 +1,95 @@
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+
+use super::*;
+
+fn entry(seed: u8, addr: &str, tags: &[&str]) -> PeersFileEntry {
+    let pubkey = STANDARD.encode([seed; 32]);
+    PeersFileEntry {
+        agent_id: derive_agent_id_from_pubkey_base64(&pubkey).unwrap(),
+        addr: PeerAddr::parse(addr).unwrap(),
+        pubkey,
+        tags: tags.iter().map(|tag| tag.to_string()).collect(),
+        tuning: PeerTuningConfig::default(),
+    }
+}
+
+#[test]
+fn export_round_trips_through_parse() {
+    let mut config = PersistedConfig::default();
+    let file = PeersFile {
+        version: PEERS_FILE_VERSION,
+        peers: vec![
+            entry(1, "10.0.0.1:7100", &["prod"]),
+            entry(2, "build.local:7100", &[]),
+        ],
+    };
+    merge(&mut config, None, &file).unwrap();
+
+    let exported = export(&config, None);
+    assert_eq!(exported, file);
+    let yaml = serde_yaml::to_string(&exported).unwrap();
+    assert_eq!(parse(&yaml).unwrap(), file);
+}
+
+#[test]
+fn parse_rejects_mismatched_agent_id_and_unknown_version() {
+    let mut bad = entry(1, "10.0.0.1:7100", &[]);
+    bad.agent_id = entry(2, "10.0.0.1:7100", &[]).agent_id;
+    let yaml = serde_yaml::to_string(&PeersFile {
+        version: PEERS_FILE_VERSION,
+        peers: vec![bad],
+    })
+    .unwrap();
+    let err = parse(&yaml).unwrap_err();
+    assert!(err.to_string().contains("does not match pubkey"), "{err}");
+
+    let err = parse("version: 2\npeers: []\n").unwrap_err();
+    assert!(err.to_string().contains("unsupported"), "{err}");
+}
+
+#[test]
+fn merge_adds_updates_and_keeps_existing_tags() {
+    let mut config = PersistedConfig::default();
+    merge(
+        &mut config,
+        None,
+        &PeersFile {
+            version: PEERS_FILE_VERSION,
+            peers: vec![entry(1, "10.0.0.1:7100", &["prod"])],
+        },
+    )
+    .unwrap();
+
+    let summary = merge(
+        &mut config,
+        None,
+        &PeersFile {
+            version: PEERS_FILE_VERSION,
+            peers: vec![
+                entry(1, "10.0.0.9:7100", &["gpu"]),
+                entry(2, "10.0.0.2:7100", &[]),
+            ],
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        summary,
+        ImportSummary {
+            added: 1,
+            updated: 1,
+            unchanged: 0
+        }
+    );
+    assert_eq!(config.peers.len(), 2);
+    assert_eq!(
+        config.peers[0].addr,
+        PeerAddr::parse("10.0.0.9:7100").unwrap()
+    );
+    let id = entry(1, "10.0.0.1:7100", &[]).agent_id;
+    assert_eq!(config.tags[id.as_str()], vec!["prod", "gpu"]);
+
+    let exported = export(&config, None);
+    let again = merge(&mut config, None, &exported).unwrap();
+    assert_eq!(again.unchanged, 2);
+}
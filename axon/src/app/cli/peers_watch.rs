@@ -0,0 +1,161 @@
+use std::collections::BTreeMap;
+
+use anyhow::{Context, Result, bail};
+use axon::config::{AxonPaths, load_persisted_config};
+use axon::message::AgentId;
+use serde_json::{Value, json};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+use crate::app::cli::alias::{annotate_peer, annotate_peers};
+use crate::app::cli::format::render_peers_human;
+use crate::app::cli::ipc_client::{connect_socket, encode_command, is_unsolicited_event};
+use crate::app::cli::top::AltScreen;
+
+/// What an IPC event means for the table.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Update {
+    Unchanged,
+    Redraw,
+    /// A peer the table does not list connected; fetch `peers` again.
+    Refresh,
+}
+
+/// The `peers` table kept current by `connected`, `disconnected`,
+/// `discovered`, and `lost` events.
+#[derive(Debug, Default)]
+pub struct PeerView {
+    peers: Vec<Value>,
+    /// With `--tag`, discovered peers (which carry no tags) are not shown.
+    tag: Option<String>,
+    aliases: BTreeMap<String, AgentId>,
+}
+
+impl PeerView {
+    pub fn new(tag: Option<String>, aliases: BTreeMap<String, AgentId>) -> Self {
+        Self {
+            peers: Vec::new(),
+            tag,
+            aliases,
+        }
+    }
+
+    pub fn apply_peers(&mut self, mut reply: Value) {
+        annotate_peers(&mut reply, &self.aliases);
+        self.peers = reply
+            .get("peers")
+            .and_then(Value::as_array)
+            .cloned()
+            .unwrap_or_default();
+    }
+
+    pub fn apply_event(&mut self, event: &Value) -> Update {
+        let Some(agent_id) = event.get("agent_id").and_then(Value::as_str) else {
+            return Update::Unchanged;
+        };
+        let position = self
+            .peers
+            .iter()
+            .position(|peer| peer.get("agent_id").and_then(Value::as_str) == Some(agent_id));
+        match (event.get("event").and_then(Value::as_str), position) {
+            (Some("connected"), Some(index)) => {
+                self.peers[index]["status"] = json!("connected");
+                Update::Redraw
+            }
+            (Some("connected"), None) if self.tag.is_none() => Update::Refresh,
+            (Some("disconnected"), Some(index)) => {
+                let peer = &mut self.peers[index];
+                peer["status"] = json!("disconnected");
+                peer["rtt_ms"] = Value::Null;
+                Update::Redraw
+            }
+            (Some("discovered"), None) if self.tag.is_none() => {
+                let mut peer = json!({
+                    "agent_id": agent_id,
+                    "addr": event.get("addr").cloned().unwrap_or(json!("?")),
+                    "status": "discovered",
+                    "source": "discovered",
+                });
+                annotate_peer(&mut peer, &self.aliases);
+                self.peers.push(peer);
+                Update::Redraw
+            }
+            (Some("lost"), Some(index)) => {
+                self.peers.remove(index);
+                Update::Redraw
+            }
+            _ => Update::Unchanged,
+        }
+    }
+
+    pub fn render(&self) -> String {
+        render_peers_human(&json!({"peers": self.peers})).unwrap_or_default()
+    }
+}
+
+async fn request_peers(
+    writer: &mut tokio::net::unix::OwnedWriteHalf,
+    tag: Option<&str>,
+) -> Result<()> {
+    let mut line = encode_command(&json!({"cmd": "peers", "tag": tag}))?;
+    line.push('\n');
+    writer
+        .write_all(line.as_bytes())
+        .await
+        .context("failed to write IPC command")
+}
+
+/// Show the `peers` table and redraw it in place as peers connect,
+/// disconnect, are discovered, or are lost. `peers` is sent once, and again
+/// only when an unlisted peer connects. Runs until Ctrl-C.
+pub async fn run(paths: &AxonPaths, tag: Option<String>) -> Result<()> {
+    let stream = connect_socket(paths).await?;
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader).lines();
+    let aliases = load_persisted_config(&paths.config)
+        .await
+        .map(|config| config.aliases)
+        .unwrap_or_default();
+
+    let mut view = PeerView::new(tag.clone(), aliases);
+    request_peers(&mut writer, tag.as_deref()).await?;
+    let mut pending = 1usize;
+    let screen = AltScreen::enter();
+    loop {
+        tokio::select! {
+            line = lines.next_line() => {
+                let Some(line) = line.context("failed to read IPC response")? else {
+                    bail!("daemon closed the IPC connection");
+                };
+                let Ok(decoded) = serde_json::from_str::<Value>(&line) else {
+                    continue;
+                };
+                if is_unsolicited_event(&decoded) {
+                    match view.apply_event(&decoded) {
+                        Update::Unchanged => continue,
+                        Update::Redraw => {}
+                        Update::Refresh => {
+                            // One refresh at a time covers every peer it lists.
+                            if pending == 0 {
+                                request_peers(&mut writer, tag.as_deref()).await?;
+                                pending += 1;
+                            }
+                            continue;
+                        }
+                    }
+                } else {
+                    if decoded.get("ok") != Some(&json!(true)) {
+                        bail!("daemon rejected `peers`: {decoded}");
+                    }
+                    pending = pending.saturating_sub(1);
+                    view.apply_peers(decoded);
+                }
+                screen.draw(&view.render());
+            }
+            _ = tokio::signal::ctrl_c() => return Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "peers_watch_tests.rs"]
+mod tests;
@@ -0,0 +1,72 @@
+use super::*;
+
+const PEER: &str = "ed25519.aaaa0000000000000000000000000000";
+const OTHER: &str = "ed25519.bbbb0000000000000000000000000000";
+
+fn listed() -> Value {
+    json!({
+        "ok": true,
+        "peers": [{
+            "agent_id": PEER,
+            "addr": "127.0.0.1:7100",
+            "status": "connected",
+            "rtt_ms": 1.25,
+            "source": "static"
+        }]
+    })
+}
+
+#[test]
+fn connection_events_update_status_in_place() {
+    let mut view = PeerView::default();
+    view.apply_peers(listed());
+
+    let update = view
+        .apply_event(&json!({"event": "disconnected", "agent_id": PEER, "reason": "idle_timeout"}));
+    assert_eq!(update, Update::Redraw);
+    assert_eq!(view.peers[0]["status"], json!("disconnected"));
+    assert!(view.render().contains("disconnected"));
+    assert!(!view.render().contains("1.25"));
+
+    assert_eq!(
+        view.apply_event(&json!({"event": "connected", "agent_id": PEER})),
+        Update::Redraw
+    );
+    assert_eq!(view.peers[0]["status"], json!("connected"));
+}
+
+#[test]
+fn discovery_events_add_and_remove_rows() {
+    let mut view = PeerView::default();
+    view.apply_peers(listed());
+
+    let discovered = json!({"event": "discovered", "agent_id": OTHER, "addr": "10.0.0.5:7100"});
+    assert_eq!(view.apply_event(&discovered), Update::Redraw);
+    assert!(view.render().contains("10.0.0.5:7100"));
+    // A repeated announcement does not duplicate the row.
+    assert_eq!(view.apply_event(&discovered), Update::Unchanged);
+
+    assert_eq!(
+        view.apply_event(&json!({"event": "lost", "agent_id": OTHER})),
+        Update::Redraw
+    );
+    assert_eq!(view.peers.len(), 1);
+}
+
+#[test]
+fn unlisted_peers_refresh_unless_filtered_by_tag() {
+    let connected = json!({"event": "connected", "agent_id": OTHER});
+    let mut view = PeerView::default();
+    view.apply_peers(listed());
+    assert_eq!(view.apply_event(&connected), Update::Refresh);
+    assert_eq!(
+        view.apply_event(&json!({"event": "inbound", "from": PEER})),
+        Update::Unchanged
+    );
+
+    let mut tagged = PeerView::new(Some("prod".to_string()), BTreeMap::new());
+    tagged.apply_peers(listed());
+    assert_eq!(tagged.apply_event(&connected), Update::Unchanged);
+    let discovered = json!({"event": "discovered", "agent_id": OTHER, "addr": "10.0.0.5:7100"});
+    assert_eq!(tagged.apply_event(&discovered), Update::Unchanged);
+}
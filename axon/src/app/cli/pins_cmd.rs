@@ -0,0 +1,165 @@
+use std::process::ExitCode;
+
+use anyhow::{Result, anyhow, bail};
+use axon::config::{
+    AxonPaths, KnownPeerSource, load_known_peers, load_persisted_config, save_known_peers,
+};
+use axon::ipc::IpcErrorCode;
+use clap::{Args, Subcommand};
+use serde_json::{Value, json};
+
+use crate::app::cli::alias::{parse_agent_ref_arg, resolve_agent_ref};
+use crate::app::cli::format::render_pins_human;
+use crate::app::cli::ipc_client::{ResponseMode, daemon_reply_exit_code, render_json, send_ipc};
+
+#[derive(Debug, Clone, Args)]
+pub struct PinsArgs {
+    #[command(subcommand)]
+    pub command: PinsCommand,
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum PinsCommand {
+    /// List every pinned peer key (static, discovered, and cached).
+    List {
+        /// Print machine-readable JSON.
+        #[arg(long)]
+        json: bool,
+    },
+    /// Show the pinned key for one peer.
+    Show {
+        #[arg(value_parser = parse_agent_ref_arg)]
+        agent_id: String,
+        /// Print machine-readable JSON.
+        #[arg(long)]
+        json: bool,
+    },
+    /// Forget a discovered or cached peer's key so discovery pins its new key.
+    Clear {
+        #[arg(value_parser = parse_agent_ref_arg)]
+        agent_id: String,
+    },
+}
+
+fn source_str(source: KnownPeerSource) -> &'static str {
+    match source {
+        KnownPeerSource::Static => "static",
+        KnownPeerSource::Discovered => "discovered",
+        KnownPeerSource::Cached => "cached",
+    }
+}
+
+fn peer_not_found() -> Value {
+    let error = IpcErrorCode::PeerNotFound;
+    json!({"ok": false, "error": error, "message": error.message()})
+}
+
+/// Pins as the daemon would report them, read from `config.yaml` and
+/// `known_peers.json` while it is not running.
+async fn offline_pins(paths: &AxonPaths) -> Result<Vec<Value>> {
+    let persisted = load_persisted_config(&paths.config).await?;
+    let mut pins: Vec<Value> = persisted
+        .static_peers(paths.profile.as_deref())
+        .iter()
+        .map(|peer| {
+            json!({
+                "agent_id": peer.agent_id,
+                "pubkey": peer.pubkey,
+                "addr": peer.addr.to_string(),
+                "source": "static",
+            })
+        })
+        .collect();
+    for peer in load_known_peers(&paths.known_peers).await? {
+        if pins
+            .iter()
+            .any(|pin| pin["agent_id"] == peer.agent_id.as_str())
+        {
+            continue;
+        }
+        pins.push(json!({
+            "agent_id": peer.agent_id,
+            "pubkey": peer.pubkey,
+            "addr": peer.addr.to_string(),
+            "source": source_str(peer.source),
+        }));
+    }
+    pins.sort_by(|a, b| a["agent_id"].as_str().cmp(&b["agent_id"].as_str()));
+    Ok(pins)
+}
+
+async fn list(paths: &AxonPaths, agent_id: Option<&str>) -> Result<Value> {
+    if paths.socket.exists() {
+        return send_ipc(paths, json!({"cmd": "pins", "agent_id": agent_id})).await;
+    }
+    let mut pins = offline_pins(paths).await?;
+    if let Some(agent_id) = agent_id {
+        pins.retain(|pin| pin["agent_id"] == agent_id);
+        if pins.is_empty() {
+            return Ok(peer_not_found());
+        }
+    }
+    Ok(json!({"ok": true, "pins": pins}))
+}
+
+async fn clear(paths: &AxonPaths, agent_id: &str) -> Result<Value> {
+    let persisted = load_persisted_config(&paths.config).await?;
+    if persisted
+        .static_peers(paths.profile.as_deref())
+        .iter()
+        .any(|peer| peer.agent_id.as_str() == agent_id)
+    {
+        bail!(
+            "{agent_id} is a static peer from config.yaml; edit its pubkey there and run `axon reload`"
+        );
+    }
+    if paths.socket.exists() {
+        return send_ipc(paths, json!({"cmd": "clear_pin", "agent_id": agent_id})).await;
+    }
+    let mut known = load_known_peers(&paths.known_peers).await?;
+    let Some(index) = known
+        .iter()
+        .position(|peer| peer.agent_id.as_str() == agent_id)
+    else {
+        return Ok(peer_not_found());
+    };
+    let peer = known.remove(index);
+    save_known_peers(&paths.known_peers, &known).await?;
+    Ok(json!({
+        "ok": true,
+        "agent_id": peer.agent_id,
+        "pubkey": peer.pubkey,
+        "source": source_str(peer.source),
+    }))
+}
+
+/// List, show, or clear pinned peer keys, through the daemon when it is
+/// running and from the state files otherwise.
+pub async fn run(paths: &AxonPaths, args: &PinsArgs) -> Result<ExitCode> {
+    let aliases = load_persisted_config(&paths.config).await?.aliases;
+    let resolve =
+        |agent_id: &str| resolve_agent_ref(agent_id, &aliases).map_err(|err| anyhow!(err));
+
+    let (response, json) = match &args.command {
+        PinsCommand::List { json } => (list(paths, None).await?, *json),
+        PinsCommand::Show { agent_id, json } => {
+            (list(paths, Some(&resolve(agent_id)?)).await?, *json)
+        }
+        PinsCommand::Clear { agent_id } => (clear(paths, &resolve(agent_id)?).await?, false),
+    };
+
+    if response.get("ok") == Some(&json!(true)) && matches!(args.command, PinsCommand::Clear { .. })
+    {
+        println!(
+            "✓ Cleared pinned key for {}; the next discovery announcement pins its current key",
+            response["agent_id"].as_str().unwrap_or_default()
+        );
+    } else if json {
+        println!("{}", render_json(&response)?);
+    } else if let Some(rendered) = render_pins_human(&response) {
+        println!("{rendered}");
+    } else {
+        println!("{}", render_json(&response)?);
+    }
+    Ok(daemon_reply_exit_code(&response, ResponseMode::Generic))
+}
@@ -0,0 +1,117 @@
+use std::process::ExitCode;
+
+use anyhow::Result;
+use axon::config::{AxonPaths, load_persisted_config};
+use serde_json::{Value, json};
+
+use crate::app::cli::alias::{annotate_peer, annotate_peers};
+use crate::app::cli::format::{
+    render_bans_human, render_health_human, render_history_human, render_reload_human,
+    render_whois_human,
+};
+use crate::app::cli::history_args::HistoryArgs;
+use crate::app::cli::ipc_client::{
+    ResponseMode, daemon_reply_exit_code, print_json_value, send_ipc,
+};
+use crate::app::cli::output::{OutputFormat, Schema, print_reply};
+
+/// `axon peers`: the peer table, with alias names added.
+pub async fn peers(
+    paths: &AxonPaths,
+    tag: Option<String>,
+    json: bool,
+    output: Option<OutputFormat>,
+) -> Result<ExitCode> {
+    let mut response = send_ipc(paths, json!({"cmd": "peers", "tag": tag})).await?;
+    let aliases = load_persisted_config(&paths.config)
+        .await
+        .map(|config| config.aliases)
+        .unwrap_or_default();
+    annotate_peers(&mut response, &aliases);
+    print_reply(Schema::Peers, &response, json, output)?;
+    Ok(daemon_reply_exit_code(&response, ResponseMode::Generic))
+}
+
+/// `axon status`.
+pub async fn status(
+    paths: &AxonPaths,
+    json: bool,
+    output: Option<OutputFormat>,
+) -> Result<ExitCode> {
+    let response = send_ipc(paths, json!({"cmd": "status"})).await?;
+    print_reply(Schema::Status, &response, json, output)?;
+    Ok(daemon_reply_exit_code(&response, ResponseMode::Generic))
+}
+
+/// `axon whoami`.
+pub async fn whoami(
+    paths: &AxonPaths,
+    json: bool,
+    output: Option<OutputFormat>,
+) -> Result<ExitCode> {
+    let response = send_ipc(paths, json!({"cmd": "whoami"})).await?;
+    print_reply(Schema::Whoami, &response, json, output)?;
+    Ok(daemon_reply_exit_code(&response, ResponseMode::Generic))
+}
+
+/// `axon health`: exit 2 when a subsystem is not ready.
+pub async fn health(paths: &AxonPaths, json: bool) -> Result<ExitCode> {
+    let response = send_ipc(paths, json!({"cmd": "health"})).await?;
+    print_rendered(&response, json, render_health_human)?;
+    if response.get("healthy") == Some(&json!(false)) {
+        return Ok(ExitCode::from(2));
+    }
+    Ok(daemon_reply_exit_code(&response, ResponseMode::Generic))
+}
+
+/// `axon bans`.
+pub async fn bans(paths: &AxonPaths, json: bool) -> Result<ExitCode> {
+    let response = send_ipc(paths, json!({"cmd": "bans"})).await?;
+    print_rendered(&response, json, render_bans_human)?;
+    Ok(daemon_reply_exit_code(&response, ResponseMode::Generic))
+}
+
+/// `axon reload`.
+pub async fn reload(paths: &AxonPaths, json: bool) -> Result<ExitCode> {
+    let response = send_ipc(paths, json!({"cmd": "reload"})).await?;
+    print_rendered(&response, json, render_reload_human)?;
+    Ok(daemon_reply_exit_code(&response, ResponseMode::Generic))
+}
+
+/// `axon history`.
+pub async fn history(paths: &AxonPaths, args: &HistoryArgs) -> Result<ExitCode> {
+    let command = args.to_command(axon::message::now_millis());
+    let response = send_ipc(paths, command).await?;
+    print_rendered(&response, args.json, render_history_human)?;
+    Ok(daemon_reply_exit_code(&response, ResponseMode::Generic))
+}
+
+/// `axon whois`: one peer's record, with its alias added.
+pub async fn whois(paths: &AxonPaths, agent_id: &str, json: bool) -> Result<ExitCode> {
+    let mut response = send_ipc(paths, json!({"cmd": "whois", "agent_id": agent_id})).await?;
+    if let Some(peer) = response.get_mut("peer") {
+        let aliases = load_persisted_config(&paths.config)
+            .await
+            .map(|config| config.aliases)
+            .unwrap_or_default();
+        annotate_peer(peer, &aliases);
+    }
+    print_rendered(&response, json, render_whois_human)?;
+    Ok(daemon_reply_exit_code(&response, ResponseMode::Generic))
+}
+
+/// Print `response` as text via `render`, or as JSON when asked for or when
+/// it cannot be rendered (e.g. an error reply).
+fn print_rendered(
+    response: &Value,
+    json: bool,
+    render: impl FnOnce(&Value) -> Option<String>,
+) -> Result<()> {
+    match render(response) {
+        Some(rendered) if !json => {
+            println!("{rendered}");
+            Ok(())
+        }
+        _ => print_json_value(response),
+    }
+}
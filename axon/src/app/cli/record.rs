@@ -0,0 +1,204 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result, bail};
+use axon::client::IpcClient;
+use axon::config::AxonPaths;
+use axon::ipc::{RECORDING_FORMAT_VERSION, parse_recording, recording_entry};
+use axon::message::now_millis;
+use clap::Args;
+use serde_json::{Value, json};
+use tokio::fs::OpenOptions;
+use tokio::io::AsyncWriteExt;
+use tokio::task::JoinSet;
+use tokio::time::Instant;
+
+use crate::app::cli::ipc_client::connect_socket;
+
+/// Recorded commands `replay` never re-issues: they would stop the target
+/// daemon or turn the replaying connection into a recorder.
+const SKIPPED_COMMANDS: &[&str] = &["shutdown", "tap"];
+
+#[derive(Debug, Clone, Args)]
+pub struct RecordArgs {
+    /// Recording to write (JSON lines, mode 0600; replaced if it exists).
+    #[arg(long, value_name = "FILE")]
+    pub out: PathBuf,
+    /// Stop after this many seconds instead of waiting for Ctrl-C.
+    #[arg(long, value_name = "SECONDS")]
+    pub duration: Option<u64>,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct ReplayArgs {
+    /// Recording made by `axon record`.
+    pub file: PathBuf,
+    /// Playback speed: 2 halves the recorded gaps, 0 sends commands back to
+    /// back.
+    #[arg(long, default_value_t = 1.0, value_parser = parse_speed)]
+    pub speed: f64,
+}
+
+fn parse_speed(value: &str) -> Result<f64, String> {
+    match value.parse::<f64>() {
+        Ok(speed) if speed.is_finite() && speed >= 0.0 => Ok(speed),
+        _ => Err(format!("invalid speed '{value}': expected a number >= 0")),
+    }
+}
+
+/// Capture daemon traffic to `args.out` until Ctrl-C, `--duration`, or the
+/// daemon closing the connection: commands from every other IPC client,
+/// envelopes sent and received, and connection events.
+pub async fn record(paths: &AxonPaths, args: &RecordArgs) -> Result<()> {
+    let client = IpcClient::from_stream(connect_socket(paths).await?);
+    let mut events = client.events();
+    let whoami = client.command(json!({"cmd": "whoami"})).await?;
+    let reply = client.command(json!({"cmd": "tap"})).await?;
+    if reply.get("ok") != Some(&json!(true)) {
+        bail!("daemon refused tap: {reply}");
+    }
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(&args.out)
+        .await
+        .with_context(|| format!("failed to create {}", args.out.display()))?;
+    let header = json!({
+        "axonrec": RECORDING_FORMAT_VERSION,
+        "agent_id": whoami["agent_id"],
+        "started_ms": now_millis(),
+    });
+    write_line(&mut file, &header).await?;
+    eprintln!("recording to {} (Ctrl-C to stop)", args.out.display());
+
+    let started = Instant::now();
+    let deadline = args
+        .duration
+        .map(|secs| started + Duration::from_secs(secs));
+    let mut recorded = 0u64;
+    loop {
+        let event = tokio::select! {
+            event = events.next() => event,
+            _ = tokio::signal::ctrl_c() => break,
+            _ = sleep_until(deadline) => break,
+        };
+        let Some(event) = event else {
+            eprintln!("daemon closed the IPC connection");
+            break;
+        };
+        let t_ms = started.elapsed().as_millis() as u64;
+        write_line(&mut file, &recording_entry(&event, t_ms)).await?;
+        recorded += 1;
+    }
+    eprintln!("recorded {recorded} entries to {}", args.out.display());
+    Ok(())
+}
+
+async fn sleep_until(deadline: Option<Instant>) {
+    match deadline {
+        Some(deadline) => tokio::time::sleep_until(deadline).await,
+        None => std::future::pending().await,
+    }
+}
+
+async fn write_line(file: &mut tokio::fs::File, value: &Value) -> Result<()> {
+    let mut line = value.to_string();
+    line.push('\n');
+    file.write_all(line.as_bytes())
+        .await
+        .context("failed to write recording")?;
+    // Flushed per line so a recording cut short by a crash stays usable.
+    file.flush().await.context("failed to write recording")
+}
+
+/// Re-issue the recorded IPC commands against the running daemon at their
+/// recorded offsets, printing each reply as a JSON line. Commands run
+/// concurrently, as they did when recorded. Envelopes from peers cannot be
+/// injected into a live daemon; they are counted and skipped. Returns
+/// whether every replayed command succeeded.
+pub async fn replay(paths: &AxonPaths, args: &ReplayArgs) -> Result<bool> {
+    let text = tokio::fs::read_to_string(&args.file)
+        .await
+        .with_context(|| format!("failed to read {}", args.file.display()))?;
+    let (_, entries) = parse_recording(&text)?;
+    let client = Arc::new(IpcClient::from_stream(connect_socket(paths).await?));
+
+    let started = Instant::now();
+    let mut in_flight = JoinSet::new();
+    let mut tally = Tally::default();
+    for entry in entries {
+        match entry.get("type").and_then(Value::as_str) {
+            Some("command") => {}
+            Some("inbound" | "outbound") => {
+                tally.envelopes += 1;
+                continue;
+            }
+            _ => continue,
+        }
+        let Some(mut command) = entry.get("command").cloned() else {
+            continue;
+        };
+        let name = command["cmd"].as_str().unwrap_or_default().to_string();
+        if SKIPPED_COMMANDS.contains(&name.as_str()) {
+            tally.skipped += 1;
+            continue;
+        }
+        // The recorded req_id belonged to another connection.
+        if let Some(command) = command.as_object_mut() {
+            command.remove("req_id");
+        }
+        let t_ms = entry["t_ms"].as_u64().unwrap_or(0);
+        if args.speed > 0.0 {
+            let offset = Duration::from_secs_f64(t_ms as f64 / 1000.0 / args.speed);
+            tokio::time::sleep_until(started + offset).await;
+        }
+        let client = client.clone();
+        in_flight.spawn(async move {
+            let reply = client.command(command).await;
+            (t_ms, name, reply)
+        });
+        while let Some(joined) = in_flight.try_join_next() {
+            tally.report(joined)?;
+        }
+    }
+    while let Some(joined) = in_flight.join_next().await {
+        tally.report(joined)?;
+    }
+    eprintln!(
+        "replayed {} commands, skipped {}; {} recorded envelopes not injected",
+        tally.replayed, tally.skipped, tally.envelopes
+    );
+    Ok(tally.failed == 0)
+}
+
+type Replayed = (u64, String, Result<Value>);
+
+#[derive(Default)]
+struct Tally {
+    replayed: u64,
+    skipped: u64,
+    envelopes: u64,
+    failed: u64,
+}
+
+impl Tally {
+    /// Print one finished command's reply as a JSON line.
+    fn report(&mut self, joined: Result<Replayed, tokio::task::JoinError>) -> Result<()> {
+        let (t_ms, cmd, reply) = joined.context("replay task failed")?;
+        let reply = reply?;
+        if reply.get("ok") != Some(&json!(true)) {
+            self.failed += 1;
+        }
+        println!("{}", json!({"t_ms": t_ms, "cmd": cmd, "reply": reply}));
+        self.replayed += 1;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[path = "record_tests.rs"]
+mod tests;
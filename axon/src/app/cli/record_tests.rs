@@ -0,0 +1,10 @@
+use super::parse_speed;
+
+#[test]
+fn speed_must_be_a_non_negative_number() {
+    assert_eq!(parse_speed("0"), Ok(0.0));
+    assert_eq!(parse_speed("2.5"), Ok(2.5));
+    assert!(parse_speed("-1").is_err());
+    assert!(parse_speed("inf").is_err());
+    assert!(parse_speed("fast").is_err());
+}
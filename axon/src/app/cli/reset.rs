@@ -0,0 +1,117 @@
+use std::fs;
+use std::io::{BufRead, ErrorKind, IsTerminal, Write};
+use std::path::PathBuf;
+
+use anyhow::{Context, Result, bail};
+use axon::config::AxonPaths;
+use clap::Args;
+
+use crate::app::cli::daemon_ctl::{DAEMON_PID_FILE_NAME, running_daemon_pid};
+use crate::app::doctor::backup_file_with_timestamp;
+
+#[derive(Debug, Clone, Args)]
+pub struct ResetArgs {
+    /// Keep identity.key and identity.pub; only runtime state is removed.
+    #[arg(long)]
+    pub keep_identity: bool,
+    /// Do not ask for confirmation (required when stdin is not a terminal).
+    #[arg(long, short = 'y')]
+    pub yes: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum ResetAction {
+    Remove(PathBuf),
+    /// Identity files are moved aside rather than deleted, as `doctor --rekey` does.
+    Backup(PathBuf),
+}
+
+/// State `axon reset` clears, limited to files that exist. `config.yaml`, the
+/// revocation list, the blocklist, and the audit log are never touched: they
+/// record operator decisions rather than runtime state (DEC-013).
+pub(crate) fn plan_reset(paths: &AxonPaths, keep_identity: bool) -> Vec<ResetAction> {
+    let history = paths.history.to_string_lossy().into_owned();
+    let mut actions: Vec<ResetAction> = [
+        paths.known_peers.clone(),
+        paths.stats.clone(),
+        paths.history.clone(),
+        PathBuf::from(format!("{history}-wal")),
+        PathBuf::from(format!("{history}-shm")),
+        PathBuf::from(format!("{history}-journal")),
+        paths.socket.clone(),
+        paths.root.join(DAEMON_PID_FILE_NAME),
+    ]
+    .into_iter()
+    .map(ResetAction::Remove)
+    .collect();
+    if !keep_identity {
+        actions.push(ResetAction::Backup(paths.identity_key.clone()));
+        actions.push(ResetAction::Backup(paths.identity_pub.clone()));
+    }
+    actions.retain(|action| {
+        let (ResetAction::Remove(path) | ResetAction::Backup(path)) = action;
+        fs::symlink_metadata(path).is_ok()
+    });
+    actions
+}
+
+pub fn run(paths: &AxonPaths, args: &ResetArgs) -> Result<()> {
+    if let Some(pid) = running_daemon_pid(paths) {
+        bail!("daemon is running (pid {pid}); stop it with `axon stop` before resetting");
+    }
+    let actions = plan_reset(paths, args.keep_identity);
+    if actions.is_empty() {
+        println!("Nothing to reset in {}", paths.root.display());
+        return Ok(());
+    }
+    if !args.yes {
+        if !std::io::stdin().is_terminal() {
+            bail!("refusing to reset without confirmation; pass --yes");
+        }
+        eprintln!("This will reset {}:", paths.root.display());
+        for action in &actions {
+            match action {
+                ResetAction::Remove(path) => eprintln!("  delete  {}", path.display()),
+                ResetAction::Backup(path) => eprintln!("  back up {}", path.display()),
+            }
+        }
+        if actions.iter().any(|a| matches!(a, ResetAction::Backup(_))) {
+            eprintln!("The next daemon start generates a new identity; peers must re-enroll it.");
+        }
+        if !confirm("Continue? [y/N] ")? {
+            bail!("reset cancelled");
+        }
+    }
+    for action in actions {
+        match action {
+            ResetAction::Remove(path) => match fs::remove_file(&path) {
+                Ok(()) => println!("Removed {}", path.display()),
+                Err(err) if err.kind() == ErrorKind::NotFound => {}
+                Err(err) => {
+                    return Err(err)
+                        .with_context(|| format!("failed to remove {}", path.display()));
+                }
+            },
+            ResetAction::Backup(path) => {
+                let backup = backup_file_with_timestamp(&path)?;
+                println!("Moved {} to {}", path.display(), backup.display());
+            }
+        }
+    }
+    Ok(())
+}
+
+fn confirm(prompt: &str) -> Result<bool> {
+    eprint!("{prompt}");
+    std::io::stderr().flush().ok();
+    let mut line = String::new();
+    std::io::stdin()
+        .lock()
+        .read_line(&mut line)
+        .context("failed to read confirmation")?;
+    Ok(matches!(line.trim(), "y" | "Y" | "yes"))
+}
+
+#[cfg(test)]
+#[path = "reset_tests.rs"]
+mod tests;
@@ -0,0 +1,81 @@
+use std::fs;
+
+use tempfile::tempdir;
+
+use super::*;
+
+#[test]
+fn plan_lists_only_existing_state_and_spares_config() {
+    let dir = tempdir().expect("temp dir");
+    let paths = AxonPaths::from_root(dir.path().to_path_buf());
+    for path in [
+        &paths.config,
+        &paths.audit,
+        &paths.revocations,
+        &paths.blocklist,
+        &paths.known_peers,
+        &paths.stats,
+        &paths.identity_key,
+        &paths.identity_pub,
+    ] {
+        fs::write(path, "x").expect("write state file");
+    }
+    fs::write(dir.path().join("history.sqlite3-wal"), "x").expect("write wal");
+
+    assert_eq!(
+        plan_reset(&paths, false),
+        vec![
+            ResetAction::Remove(paths.known_peers.clone()),
+            ResetAction::Remove(paths.stats.clone()),
+            ResetAction::Remove(dir.path().join("history.sqlite3-wal")),
+            ResetAction::Backup(paths.identity_key.clone()),
+            ResetAction::Backup(paths.identity_pub.clone()),
+        ]
+    );
+}
+
+#[test]
+fn keep_identity_leaves_key_files_out_of_the_plan() {
+    let dir = tempdir().expect("temp dir");
+    let paths = AxonPaths::from_root(dir.path().to_path_buf());
+    fs::write(&paths.identity_key, "x").expect("write key");
+    fs::write(&paths.known_peers, "[]").expect("write known peers");
+
+    assert_eq!(
+        plan_reset(&paths, true),
+        vec![ResetAction::Remove(paths.known_peers.clone())]
+    );
+}
+
+#[test]
+fn run_removes_state_and_backs_up_identity() {
+    let dir = tempdir().expect("temp dir");
+    let paths = AxonPaths::from_root(dir.path().to_path_buf());
+    fs::write(&paths.known_peers, "[]").expect("write known peers");
+    fs::write(&paths.identity_key, "key").expect("write key");
+    fs::write(&paths.config, "port: 7100\n").expect("write config");
+
+    run(
+        &paths,
+        &ResetArgs {
+            keep_identity: false,
+            yes: true,
+        },
+    )
+    .expect("reset");
+
+    assert!(!paths.known_peers.exists());
+    assert!(!paths.identity_key.exists());
+    assert!(paths.config.exists());
+    let backups = fs::read_dir(dir.path())
+        .expect("list root")
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_string_lossy()
+                .starts_with("identity.key.bak.")
+        })
+        .count();
+    assert_eq!(backups, 1);
+}
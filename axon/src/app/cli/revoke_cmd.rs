@@ -0,0 +1,108 @@
+use anyhow::{Result, anyhow, bail};
+use axon::config::{
+    AxonPaths, RevokedKey, add_revocation, load_known_peers, load_persisted_config,
+    save_persisted_config,
+};
+use axon::message::{AgentId, now_millis};
+use axon::peer_token::derive_agent_id_from_pubkey_base64;
+use clap::Args;
+use serde_json::{Value, json};
+
+use crate::app::cli::alias::resolve_agent_ref;
+use crate::app::cli::ipc_client::send_ipc;
+
+#[derive(Debug, Clone, Args)]
+pub struct RevokeArgs {
+    /// Agent ID, alias, or base64 public key of the peer to revoke.
+    pub peer: String,
+}
+
+/// A revoked or blocked peer as far as it could be resolved before asking
+/// the daemon.
+pub(crate) struct Target {
+    pub(crate) agent_id: AgentId,
+    pub(crate) pubkey: Option<String>,
+}
+
+pub(crate) async fn resolve_target(paths: &AxonPaths, peer: &str) -> Result<Target> {
+    if let Ok(agent_id) = derive_agent_id_from_pubkey_base64(peer) {
+        return Ok(Target {
+            agent_id,
+            pubkey: Some(peer.trim().to_string()),
+        });
+    }
+    let persisted = load_persisted_config(&paths.config).await?;
+    let agent_id =
+        AgentId::from(resolve_agent_ref(peer, &persisted.aliases).map_err(|err| anyhow!(err))?);
+    let pubkey = match persisted
+        .static_peers(paths.profile.as_deref())
+        .iter()
+        .find(|p| p.agent_id.as_str() == agent_id.as_str())
+    {
+        Some(peer) => Some(peer.pubkey.clone()),
+        None => load_known_peers(&paths.known_peers)
+            .await?
+            .into_iter()
+            .find(|p| p.agent_id.as_str() == agent_id.as_str())
+            .map(|p| p.pubkey),
+    };
+    Ok(Target { agent_id, pubkey })
+}
+
+/// Add a peer's key to `revocations.json`, through the daemon when it is
+/// running so the peer is dropped and disconnected at once, and remove the
+/// peer from `config.yaml`.
+pub async fn run(paths: &AxonPaths, args: &RevokeArgs) -> Result<()> {
+    let mut target = resolve_target(paths, &args.peer).await?;
+
+    if paths.socket.exists() {
+        let command = match &target.pubkey {
+            Some(pubkey) => json!({"cmd": "revoke", "pubkey": pubkey}),
+            None => json!({"cmd": "revoke", "agent_id": target.agent_id}),
+        };
+        let response = send_ipc(paths, command).await?;
+        if response.get("ok") != Some(&json!(true)) {
+            let rendered =
+                serde_json::to_string_pretty(&response).unwrap_or_else(|_| response.to_string());
+            bail!("daemon rejected revoke.\nDaemon response: {rendered}");
+        }
+        target.pubkey = response
+            .get("pubkey")
+            .and_then(Value::as_str)
+            .map(str::to_string);
+    } else {
+        let Some(pubkey) = &target.pubkey else {
+            bail!(
+                "no public key on record for {}; pass the peer's base64 public key instead",
+                target.agent_id
+            );
+        };
+        add_revocation(
+            &paths.revocations,
+            RevokedKey {
+                agent_id: target.agent_id.clone(),
+                pubkey: pubkey.clone(),
+                revoked_at_unix_ms: now_millis(),
+            },
+        )
+        .await?;
+    }
+
+    let mut persisted = load_persisted_config(&paths.config).await?;
+    // A revoked key is dropped from every profile, not just the active one.
+    let mut removed = false;
+    for peers in persisted.all_static_peers_mut() {
+        let before = peers.len();
+        peers.retain(|peer| {
+            peer.agent_id.as_str() != target.agent_id.as_str()
+                && Some(&peer.pubkey) != target.pubkey.as_ref()
+        });
+        removed |= peers.len() != before;
+    }
+    if removed {
+        save_persisted_config(&paths.config, &persisted).await?;
+    }
+
+    println!("✓ Revoked {}", target.agent_id);
+    Ok(())
+}
@@ -0,0 +1,195 @@
+use std::process::ExitCode;
+
+use anyhow::Result;
+use axon::config::AxonPaths;
+use axon::message::MessageKind;
+use clap::Args;
+use serde_json::json;
+
+use crate::app::cli::alias::parse_agent_ref_arg;
+use crate::app::cli::ipc_client::{
+    ResponseMode, daemon_reply_exit_code, print_json_value, send_ipc,
+};
+use crate::app::cli::notify_payload::parse_notify_payload;
+use crate::app::cli::notify_stdin;
+use crate::app::cli::payload_input::{PayloadArgs, apply_send_options, parse_header_arg};
+
+#[derive(Debug, Clone, Args)]
+pub struct RequestArgs {
+    #[arg(value_parser = parse_agent_ref_arg)]
+    pub agent_id: String,
+    /// Timeout in seconds while waiting for a response.
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        default_value_t = 30,
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    pub timeout: u64,
+    /// Reuse the same key when retrying: the peer answers duplicates from
+    /// its response cache instead of running the request again.
+    #[arg(long, value_name = "KEY")]
+    pub idempotency_key: Option<String>,
+    /// Conversation thread to tag the request with; the reply carries it too.
+    #[arg(long, value_name = "THREAD_ID")]
+    pub thread: Option<String>,
+    /// Envelope header as KEY=VALUE (repeatable), e.g. a trace ID.
+    #[arg(long = "header", value_name = "KEY=VALUE", value_parser = parse_header_arg)]
+    pub headers: Vec<(String, String)>,
+    /// Text payload (sent as {"message":"<TEXT>"} on the wire).
+    #[arg(required_unless_present_any = ["payload", "payload_file"], conflicts_with_all = ["payload", "payload_file"])]
+    pub message: Option<String>,
+    #[command(flatten)]
+    pub payload: PayloadArgs,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct NotifyArgs {
+    #[arg(value_parser = parse_agent_ref_arg)]
+    pub agent_id: String,
+    /// Parse payload as JSON (default sends literal text). Payload is sent as {"data": <value>}.
+    #[arg(long, conflicts_with_all = ["payload", "payload_file", "stdin"])]
+    pub json: bool,
+    /// Payload data (sent as {"data":"<TEXT>"}, or {"data":<JSON>} with --json).
+    #[arg(required_unless_present_any = ["payload", "payload_file", "stdin"], conflicts_with_all = ["payload", "payload_file", "stdin"])]
+    pub data: Option<String>,
+    #[command(flatten)]
+    pub payload: PayloadArgs,
+    /// Send each line of stdin (a JSON object) as its own notify over one
+    /// connection, printing a result line per input line.
+    #[arg(long, conflicts_with_all = ["payload", "payload_file"])]
+    pub stdin: bool,
+    /// Conversation thread to tag the message with.
+    #[arg(long, value_name = "THREAD_ID")]
+    pub thread: Option<String>,
+    /// Envelope header as KEY=VALUE (repeatable), e.g. a trace ID.
+    #[arg(long = "header", value_name = "KEY=VALUE", value_parser = parse_header_arg)]
+    pub headers: Vec<(String, String)>,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct PublishArgs {
+    /// Dot-separated topic, e.g. `builds.main`.
+    pub topic: String,
+    /// Parse payload as JSON (default sends literal text). Payload is sent as {"data": <value>}.
+    #[arg(long, conflicts_with_all = ["payload", "payload_file"])]
+    pub json: bool,
+    /// Payload data (sent as {"data":"<TEXT>"}, or {"data":<JSON>} with --json).
+    #[arg(required_unless_present_any = ["payload", "payload_file"], conflicts_with_all = ["payload", "payload_file"])]
+    pub data: Option<String>,
+    #[command(flatten)]
+    pub payload: PayloadArgs,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct CancelArgs {
+    #[arg(value_parser = parse_agent_ref_arg)]
+    pub agent_id: String,
+    /// ID of the request to cancel (the `msg_id` it was sent with).
+    #[arg(long = "ref", value_name = "MSG_ID")]
+    pub ref_id: uuid::Uuid,
+    /// Why the work is being cancelled, passed to the peer.
+    #[arg(long)]
+    pub reason: Option<String>,
+}
+
+/// `axon request`: send and wait for the response.
+pub async fn request(paths: &AxonPaths, args: &RequestArgs, quiet: bool) -> Result<ExitCode> {
+    let payload = match args.payload.load(MessageKind::Request)? {
+        Some(payload) => payload,
+        None => json!({ "message": args.message }),
+    };
+    let mut command = json!({
+        "cmd": "send",
+        "to": args.agent_id,
+        "kind": "request",
+        "timeout_secs": args.timeout,
+        "payload": payload
+    });
+    if let Some(key) = &args.idempotency_key {
+        command["idempotency_key"] = json!(key);
+    }
+    apply_send_options(&mut command, args.thread.as_deref(), &args.headers);
+    let response = send_ipc(paths, command).await?;
+    if !quiet {
+        print_json_value(&response)?;
+    }
+    Ok(daemon_reply_exit_code(&response, ResponseMode::Request))
+}
+
+/// `axon notify`: one fire-and-forget message, or one per stdin line.
+pub async fn notify(paths: &AxonPaths, args: &NotifyArgs, quiet: bool) -> Result<ExitCode> {
+    if args.stdin {
+        return notify_stdin::run(
+            paths,
+            &args.agent_id,
+            args.thread.as_deref(),
+            &args.headers,
+            quiet,
+        )
+        .await;
+    }
+    let payload = match args.payload.load(MessageKind::Message)? {
+        Some(payload) => payload,
+        None => json!({
+            "data": parse_notify_payload(args.data.as_deref().unwrap_or_default(), args.json)?
+        }),
+    };
+    let mut command =
+        json!({"cmd": "send", "to": args.agent_id, "kind": "message", "payload": payload});
+    apply_send_options(&mut command, args.thread.as_deref(), &args.headers);
+    let response = send_ipc(paths, command).await?;
+    if !quiet {
+        print_json_value(&response)?;
+    }
+    Ok(daemon_reply_exit_code(&response, ResponseMode::Generic))
+}
+
+/// `axon subscribe`: replace this agent's topic subscriptions.
+pub async fn subscribe(paths: &AxonPaths, topics: &[String]) -> Result<ExitCode> {
+    let response = send_ipc(paths, json!({"cmd": "subscribe", "topics": topics})).await?;
+    print_json_value(&response)?;
+    Ok(daemon_reply_exit_code(&response, ResponseMode::Generic))
+}
+
+/// `axon publish`: send to every peer subscribed to the topic.
+pub async fn publish(paths: &AxonPaths, args: &PublishArgs, quiet: bool) -> Result<ExitCode> {
+    let payload = match args.payload.load(MessageKind::Message)? {
+        Some(payload) => payload,
+        None => json!({
+            "data": parse_notify_payload(args.data.as_deref().unwrap_or_default(), args.json)?
+        }),
+    };
+    let response = send_ipc(
+        paths,
+        json!({"cmd": "publish", "topic": args.topic, "payload": payload}),
+    )
+    .await?;
+    if !quiet {
+        print_json_value(&response)?;
+    }
+    Ok(daemon_reply_exit_code(&response, ResponseMode::Generic))
+}
+
+/// `axon cancel`: a message whose `ref` names the request to abort.
+pub async fn cancel(paths: &AxonPaths, args: &CancelArgs, quiet: bool) -> Result<ExitCode> {
+    let mut payload = json!({ "cancel": true });
+    if let Some(reason) = &args.reason {
+        payload["reason"] = json!(reason);
+    }
+    let response = send_ipc(
+        paths,
+        json!({
+            "cmd": "send",
+            "to": args.agent_id,
+            "kind": "message",
+            "ref": args.ref_id,
+            "payload": payload
+        }),
+    )
+    .await?;
+    if !quiet {
+        print_json_value(&response)?;
+    }
+    Ok(daemon_reply_exit_code(&response, ResponseMode::Generic))
+}
@@ -0,0 +1,225 @@
+use std::path::PathBuf;
+use std::process::{Command, ExitCode, Stdio};
+
+use anyhow::{Context, Result, bail};
+use axon::config::{AxonPaths, Config};
+use clap::{Args, Subcommand};
+
+use crate::app::cli::service_manager::{
+    ServiceManager, activation_commands, current_uid, deactivation_commands, installed_files,
+    launchd_target, query_manager, resolve_unit_dir, run_manager_command, systemctl,
+};
+use crate::app::cli::service_units::{
+    LAUNCHD_LABEL, LAUNCHD_PLIST_NAME, SERVICE_UNIT_NAME, SOCKET_UNIT_NAME, UnitSpec,
+    render_launchd_plist, render_service_unit, render_socket_unit,
+};
+
+#[derive(Debug, Clone, Args)]
+pub struct ServiceArgs {
+    #[command(subcommand)]
+    pub command: ServiceCommand,
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum ServiceCommand {
+    /// Install and enable a per-user service for the daemon.
+    Install(InstallArgs),
+    /// Stop, disable, and remove the installed service.
+    Uninstall(TargetArgs),
+    /// Show whether the service is installed and running.
+    Status(TargetArgs),
+}
+
+/// Which service manager to target and where its definitions live.
+#[derive(Debug, Clone, Args)]
+pub struct TargetArgs {
+    /// Target systemd (the default on Linux).
+    #[arg(long, conflicts_with = "launchd")]
+    pub systemd: bool,
+    /// Target launchd (the default on macOS).
+    #[arg(long)]
+    pub launchd: bool,
+    /// Per-user service (the default; system-wide services are not supported).
+    #[arg(long)]
+    pub user: bool,
+    /// Directory holding the service definition (default: the manager's
+    /// per-user directory). The service manager is not invoked for a custom
+    /// directory.
+    #[arg(long, value_name = "DIR")]
+    pub unit_dir: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct InstallArgs {
+    #[command(flatten)]
+    pub target: TargetArgs,
+    /// Also generate axon.socket so systemd owns the IPC and QUIC sockets.
+    #[arg(long, conflicts_with = "launchd")]
+    pub socket_activation: bool,
+    /// Print the service definitions to stdout instead of writing them.
+    #[arg(long)]
+    pub print: bool,
+    /// Overwrite existing service definitions.
+    #[arg(long)]
+    pub force: bool,
+    /// Write the service definitions without enabling or starting them.
+    #[arg(long)]
+    pub no_enable: bool,
+}
+
+pub async fn run(paths: &AxonPaths, args: ServiceArgs) -> Result<ExitCode> {
+    match args.command {
+        ServiceCommand::Install(install) => install_service(paths, install).await,
+        ServiceCommand::Uninstall(target) => uninstall_service(&target),
+        ServiceCommand::Status(target) => service_status(&target),
+    }
+}
+
+async fn install_service(paths: &AxonPaths, args: InstallArgs) -> Result<ExitCode> {
+    if let Some(profile) = &paths.profile {
+        bail!(
+            "service install runs the default profile only; start profile '{profile}' with `axon --profile {profile} daemon --detach`"
+        );
+    }
+    let manager = ServiceManager::select(&args.target);
+    let config = Config::load_for(paths).await?;
+    let spec = UnitSpec {
+        exe: std::env::current_exe().context("failed to resolve axon executable path")?,
+        state_root: paths.root.clone(),
+        socket: paths.socket.clone(),
+        port: config.effective_port(None),
+        socket_activation: args.socket_activation,
+    };
+
+    let units = match manager {
+        ServiceManager::Systemd => {
+            let mut units = vec![(SERVICE_UNIT_NAME, render_service_unit(&spec))];
+            if spec.socket_activation {
+                units.push((SOCKET_UNIT_NAME, render_socket_unit(&spec)));
+            }
+            units
+        }
+        ServiceManager::Launchd => vec![(LAUNCHD_PLIST_NAME, render_launchd_plist(&spec))],
+    };
+
+    if args.print {
+        for (name, body) in &units {
+            println!("# {name}\n{body}");
+        }
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let unit_dir = resolve_unit_dir(manager, &args.target)?;
+    std::fs::create_dir_all(&unit_dir)
+        .with_context(|| format!("failed to create {}", unit_dir.display()))?;
+    for (name, _) in &units {
+        let path = unit_dir.join(name);
+        if path.exists() && !args.force {
+            anyhow::bail!(
+                "{} already exists; re-run with --force to overwrite",
+                path.display()
+            );
+        }
+    }
+    for (name, body) in &units {
+        let path = unit_dir.join(name);
+        std::fs::write(&path, body)
+            .with_context(|| format!("failed to write {}", path.display()))?;
+        println!("Wrote {}", path.display());
+    }
+
+    let commands = activation_commands(
+        manager,
+        spec.socket_activation,
+        &unit_dir.join(LAUNCHD_PLIST_NAME),
+        current_uid(),
+    );
+    if args.no_enable || args.target.unit_dir.is_some() {
+        let next: Vec<String> = commands.iter().map(|argv| argv.join(" ")).collect();
+        println!("Next: {}", next.join(" && "));
+        return Ok(ExitCode::SUCCESS);
+    }
+    for argv in &commands {
+        run_manager_command(argv)?;
+    }
+    println!("Enabled {} service", manager.name());
+    Ok(ExitCode::SUCCESS)
+}
+
+fn uninstall_service(target: &TargetArgs) -> Result<ExitCode> {
+    let manager = ServiceManager::select(target);
+    let unit_dir = resolve_unit_dir(manager, target)?;
+    let installed = installed_files(manager, &unit_dir);
+    if installed.is_empty() {
+        println!(
+            "No {} service installed in {}",
+            manager.name(),
+            unit_dir.display()
+        );
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let manage = target.unit_dir.is_none();
+    if manage {
+        let names: Vec<&str> = installed.iter().map(|(name, _)| *name).collect();
+        for argv in deactivation_commands(manager, &names, current_uid()) {
+            // Not loaded is fine; the files are removed either way.
+            if let Err(err) = run_manager_command(&argv) {
+                eprintln!("warning: {err:#}");
+            }
+        }
+    }
+    for (_, path) in &installed {
+        std::fs::remove_file(path)
+            .with_context(|| format!("failed to remove {}", path.display()))?;
+        println!("Removed {}", path.display());
+    }
+    if manage && manager == ServiceManager::Systemd {
+        run_manager_command(&systemctl(&["daemon-reload"]))?;
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn service_status(target: &TargetArgs) -> Result<ExitCode> {
+    let manager = ServiceManager::select(target);
+    let unit_dir = resolve_unit_dir(manager, target)?;
+    let installed = installed_files(manager, &unit_dir);
+    println!("Manager: {} (user)", manager.name());
+    if installed.is_empty() {
+        println!("Installed: no ({})", unit_dir.display());
+        return Ok(ExitCode::SUCCESS);
+    }
+    for (_, path) in &installed {
+        println!("Installed: {}", path.display());
+    }
+    if target.unit_dir.is_some() {
+        return Ok(ExitCode::SUCCESS);
+    }
+    match manager {
+        ServiceManager::Systemd => {
+            for (name, _) in &installed {
+                let enabled = query_manager(&systemctl(&["is-enabled", name]));
+                let active = query_manager(&systemctl(&["is-active", name]));
+                println!(
+                    "{name}: {} ({})",
+                    active.as_deref().unwrap_or("unknown"),
+                    enabled.as_deref().unwrap_or("unknown")
+                );
+            }
+        }
+        ServiceManager::Launchd => {
+            let loaded = Command::new("launchctl")
+                .arg("print")
+                .arg(launchd_target(current_uid()))
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .status()
+                .is_ok_and(|status| status.success());
+            println!(
+                "{LAUNCHD_LABEL}: {}",
+                if loaded { "loaded" } else { "not loaded" }
+            );
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
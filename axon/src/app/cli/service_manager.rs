@@ -0,0 +1,167 @@
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use anyhow::{Context, Result};
+
+use crate::app::cli::service_cmd::TargetArgs;
+use crate::app::cli::service_units::{
+    LAUNCHD_LABEL, LAUNCHD_PLIST_NAME, SERVICE_UNIT_NAME, SOCKET_UNIT_NAME,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ServiceManager {
+    Systemd,
+    Launchd,
+}
+
+impl ServiceManager {
+    pub(crate) fn select(target: &TargetArgs) -> Self {
+        if target.launchd || (!target.systemd && cfg!(target_os = "macos")) {
+            Self::Launchd
+        } else {
+            Self::Systemd
+        }
+    }
+
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Self::Systemd => "systemd",
+            Self::Launchd => "launchd",
+        }
+    }
+
+    /// File names this manager's definitions use, in install order.
+    pub(crate) fn file_names(self) -> &'static [&'static str] {
+        match self {
+            Self::Systemd => &[SERVICE_UNIT_NAME, SOCKET_UNIT_NAME],
+            Self::Launchd => &[LAUNCHD_PLIST_NAME],
+        }
+    }
+}
+
+pub(crate) fn resolve_unit_dir(manager: ServiceManager, target: &TargetArgs) -> Result<PathBuf> {
+    match (&target.unit_dir, manager) {
+        (Some(dir), _) => Ok(dir.clone()),
+        (None, ServiceManager::Systemd) => default_unit_dir(),
+        (None, ServiceManager::Launchd) => {
+            let home = std::env::var_os("HOME").context("HOME is not set")?;
+            Ok(PathBuf::from(home).join("Library").join("LaunchAgents"))
+        }
+    }
+}
+
+pub(crate) fn installed_files(
+    manager: ServiceManager,
+    unit_dir: &Path,
+) -> Vec<(&'static str, PathBuf)> {
+    manager
+        .file_names()
+        .iter()
+        .map(|name| (*name, unit_dir.join(name)))
+        .filter(|(_, path)| path.exists())
+        .collect()
+}
+
+pub(crate) fn systemctl(args: &[&str]) -> Vec<String> {
+    ["systemctl", "--user"]
+        .iter()
+        .chain(args)
+        .map(|arg| arg.to_string())
+        .collect()
+}
+
+pub(crate) fn launchd_target(uid: u32) -> String {
+    format!("gui/{uid}/{LAUNCHD_LABEL}")
+}
+
+/// Commands that load and start a freshly written service definition.
+pub(crate) fn activation_commands(
+    manager: ServiceManager,
+    socket_activation: bool,
+    plist: &Path,
+    uid: u32,
+) -> Vec<Vec<String>> {
+    match manager {
+        ServiceManager::Systemd => {
+            let unit = if socket_activation {
+                SOCKET_UNIT_NAME
+            } else {
+                SERVICE_UNIT_NAME
+            };
+            vec![
+                systemctl(&["daemon-reload"]),
+                systemctl(&["enable", "--now", unit]),
+            ]
+        }
+        ServiceManager::Launchd => vec![vec![
+            "launchctl".to_string(),
+            "bootstrap".to_string(),
+            format!("gui/{uid}"),
+            plist.to_string_lossy().into_owned(),
+        ]],
+    }
+}
+
+/// Commands that stop and disable the installed definitions in `names`.
+pub(crate) fn deactivation_commands(
+    manager: ServiceManager,
+    names: &[&str],
+    uid: u32,
+) -> Vec<Vec<String>> {
+    match manager {
+        ServiceManager::Systemd => {
+            // Socket first, so it cannot re-activate the service being stopped.
+            let mut args = vec!["disable", "--now"];
+            args.extend(names.iter().rev());
+            vec![systemctl(&args)]
+        }
+        ServiceManager::Launchd => vec![vec![
+            "launchctl".to_string(),
+            "bootout".to_string(),
+            launchd_target(uid),
+        ]],
+    }
+}
+
+pub(crate) fn run_manager_command(argv: &[String]) -> Result<()> {
+    let status = Command::new(&argv[0])
+        .args(&argv[1..])
+        .status()
+        .with_context(|| format!("failed to run {}", argv[0]))?;
+    if !status.success() {
+        anyhow::bail!("`{}` failed ({status})", argv.join(" "));
+    }
+    Ok(())
+}
+
+/// First line of a query command's stdout, even when it exits non-zero
+/// (`systemctl is-active` reports `inactive` with exit code 3).
+pub(crate) fn query_manager(argv: &[String]) -> Option<String> {
+    let output = Command::new(&argv[0])
+        .args(&argv[1..])
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    stdout.lines().next().map(|line| line.trim().to_string())
+}
+
+pub(crate) fn current_uid() -> u32 {
+    // SAFETY: getuid has no preconditions and cannot fail.
+    unsafe { libc::getuid() }
+}
+
+fn default_unit_dir() -> Result<PathBuf> {
+    let config_home = match std::env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => {
+            let home = std::env::var_os("HOME").context("HOME is not set")?;
+            PathBuf::from(home).join(".config")
+        }
+    };
+    Ok(config_home.join("systemd").join("user"))
+}
+
+#[cfg(test)]
+#[path = "service_manager_tests.rs"]
+mod tests;
@@ -0,0 +1,55 @@
+use std::path::PathBuf;
+
+use super::{ServiceManager, activation_commands, deactivation_commands};
+
+#[test]
+fn activation_enables_socket_unit_when_socket_activated() {
+    let plist = PathBuf::from("/Users/alice/Library/LaunchAgents/x.plist");
+    let systemd = activation_commands(ServiceManager::Systemd, true, &plist, 501);
+    assert_eq!(
+        systemd,
+        vec![
+            vec!["systemctl", "--user", "daemon-reload"],
+            vec!["systemctl", "--user", "enable", "--now", "axon.socket"],
+        ]
+    );
+    let launchd = activation_commands(ServiceManager::Launchd, false, &plist, 501);
+    assert_eq!(
+        launchd,
+        vec![vec![
+            "launchctl",
+            "bootstrap",
+            "gui/501",
+            "/Users/alice/Library/LaunchAgents/x.plist"
+        ]]
+    );
+}
+
+#[test]
+fn deactivation_disables_socket_before_service() {
+    let systemd = deactivation_commands(
+        ServiceManager::Systemd,
+        &["axon.service", "axon.socket"],
+        1000,
+    );
+    assert_eq!(
+        systemd,
+        vec![vec![
+            "systemctl",
+            "--user",
+            "disable",
+            "--now",
+            "axon.socket",
+            "axon.service"
+        ]]
+    );
+    let launchd = deactivation_commands(ServiceManager::Launchd, &[], 501);
+    assert_eq!(
+        launchd,
+        vec![vec![
+            "launchctl",
+            "bootout",
+            "gui/501/com.github.hwbehrens.axon"
+        ]]
+    );
+}
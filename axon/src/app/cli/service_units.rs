@@ -0,0 +1,150 @@
+use std::path::{Path, PathBuf};
+
+use crate::app::cli::daemon_ctl::DAEMON_LOG_FILE_NAME;
+
+pub(crate) const SERVICE_UNIT_NAME: &str = "axon.service";
+pub(crate) const SOCKET_UNIT_NAME: &str = "axon.socket";
+pub(crate) const LAUNCHD_LABEL: &str = "com.github.hwbehrens.axon";
+pub(crate) const LAUNCHD_PLIST_NAME: &str = "com.github.hwbehrens.axon.plist";
+
+/// Inputs for unit rendering.
+#[derive(Debug, Clone)]
+pub(crate) struct UnitSpec {
+    pub(crate) exe: PathBuf,
+    pub(crate) state_root: PathBuf,
+    pub(crate) socket: PathBuf,
+    pub(crate) port: u16,
+    pub(crate) socket_activation: bool,
+}
+
+/// Quote a path for a systemd command line: double-quoted with `\` and `"`
+/// escaped, and `%` doubled so it is not read as a specifier.
+pub(crate) fn systemd_quote(path: &Path) -> String {
+    let raw = path.to_string_lossy();
+    let mut out = String::with_capacity(raw.len() + 2);
+    out.push('"');
+    for ch in raw.chars() {
+        match ch {
+            '\\' => out.push_str("\\\\"),
+            '"' => out.push_str("\\\""),
+            '%' => out.push_str("%%"),
+            _ => out.push(ch),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Escape `%` for unit settings that take a bare path.
+fn systemd_path(path: &Path) -> String {
+    path.to_string_lossy().replace('%', "%%")
+}
+
+pub(crate) fn render_service_unit(spec: &UnitSpec) -> String {
+    let requires = if spec.socket_activation {
+        format!("Requires={SOCKET_UNIT_NAME}\nAfter={SOCKET_UNIT_NAME}\n")
+    } else {
+        String::new()
+    };
+    format!(
+        "[Unit]
+Description=AXON agent messaging daemon
+Documentation=https://github.com/hwbehrens/axon
+Wants=network-online.target
+After=network-online.target
+{requires}
+[Service]
+Type=notify
+ExecStart={exe} --state-root {root} daemon
+ExecReload=/bin/kill -HUP $MAINPID
+Restart=on-failure
+RestartSec=2
+UMask=0077
+NoNewPrivileges=yes
+LockPersonality=yes
+MemoryDenyWriteExecute=yes
+RestrictRealtime=yes
+RestrictSUIDSGID=yes
+RestrictNamespaces=yes
+SystemCallArchitectures=native
+RestrictAddressFamilies=AF_UNIX AF_INET AF_INET6 AF_NETLINK
+
+[Install]
+WantedBy=default.target
+",
+        exe = systemd_quote(&spec.exe),
+        root = systemd_quote(&spec.state_root),
+    )
+}
+
+pub(crate) fn render_socket_unit(spec: &UnitSpec) -> String {
+    format!(
+        "[Unit]
+Description=AXON daemon sockets (IPC + QUIC)
+
+[Socket]
+ListenStream={socket}
+SocketMode=0600
+DirectoryMode=0700
+ListenDatagram=0.0.0.0:{port}
+Service={SERVICE_UNIT_NAME}
+
+[Install]
+WantedBy=sockets.target
+",
+        socket = systemd_path(&spec.socket),
+        port = spec.port,
+    )
+}
+
+fn xml_escape(raw: &str) -> String {
+    raw.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// A launchd agent that runs the daemon at login and restarts it when it
+/// exits with an error. Output goes to `daemon.log` as with `--detach`.
+pub(crate) fn render_launchd_plist(spec: &UnitSpec) -> String {
+    let exe = xml_escape(&spec.exe.to_string_lossy());
+    let root = xml_escape(&spec.state_root.to_string_lossy());
+    let log = xml_escape(&spec.state_root.join(DAEMON_LOG_FILE_NAME).to_string_lossy());
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{LAUNCHD_LABEL}</string>
+    <key>ProgramArguments</key>
+    <array>
+        <string>{exe}</string>
+        <string>--state-root</string>
+        <string>{root}</string>
+        <string>daemon</string>
+    </array>
+    <key>RunAtLoad</key>
+    <true/>
+    <key>KeepAlive</key>
+    <dict>
+        <key>SuccessfulExit</key>
+        <false/>
+    </dict>
+    <key>ThrottleInterval</key>
+    <integer>2</integer>
+    <key>Umask</key>
+    <integer>63</integer>
+    <key>StandardOutPath</key>
+    <string>{log}</string>
+    <key>StandardErrorPath</key>
+    <string>{log}</string>
+</dict>
+</plist>
+"#
+    )
+}
+
+#[cfg(test)]
+#[path = "service_units_tests.rs"]
+mod tests;
@@ -0,0 +1,61 @@
+use std::path::PathBuf;
+
+use super::{
+    UnitSpec, render_launchd_plist, render_service_unit, render_socket_unit, systemd_quote,
+};
+
+fn spec(socket_activation: bool) -> UnitSpec {
+    UnitSpec {
+        exe: PathBuf::from("/usr/local/bin/axon"),
+        state_root: PathBuf::from("/home/alice/.axon"),
+        socket: PathBuf::from("/home/alice/.axon/axon.sock"),
+        port: 7100,
+        socket_activation,
+    }
+}
+
+#[test]
+fn service_unit_uses_notify_and_sighup_reload() {
+    let unit = render_service_unit(&spec(false));
+    assert!(unit.contains("Type=notify"));
+    assert!(
+        unit.contains(
+            "ExecStart=\"/usr/local/bin/axon\" --state-root \"/home/alice/.axon\" daemon"
+        )
+    );
+    assert!(unit.contains("ExecReload=/bin/kill -HUP $MAINPID"));
+    assert!(unit.contains("NoNewPrivileges=yes"));
+    assert!(!unit.contains("Requires=axon.socket"));
+}
+
+#[test]
+fn socket_activation_adds_socket_unit_dependency() {
+    let unit = render_service_unit(&spec(true));
+    assert!(unit.contains("Requires=axon.socket"));
+
+    let socket = render_socket_unit(&spec(true));
+    assert!(socket.contains("ListenStream=/home/alice/.axon/axon.sock"));
+    assert!(socket.contains("SocketMode=0600"));
+    assert!(socket.contains("ListenDatagram=0.0.0.0:7100"));
+}
+
+#[test]
+fn systemd_quote_escapes_quotes_backslashes_and_specifiers() {
+    assert_eq!(
+        systemd_quote(&PathBuf::from("/opt/a \"b\"\\c%d")),
+        "\"/opt/a \\\"b\\\"\\\\c%%d\""
+    );
+}
+
+#[test]
+fn launchd_plist_runs_daemon_for_state_root_and_escapes_paths() {
+    let mut spec = spec(false);
+    spec.state_root = PathBuf::from("/Users/a&b/.axon");
+    let plist = render_launchd_plist(&spec);
+    assert!(plist.contains("<string>com.github.hwbehrens.axon</string>"));
+    assert!(plist.contains(
+        "<string>/usr/local/bin/axon</string>\n        <string>--state-root</string>\n        <string>/Users/a&amp;b/.axon</string>\n        <string>daemon</string>"
+    ));
+    assert!(plist.contains("<key>SuccessfulExit</key>\n        <false/>"));
+    assert!(plist.contains("<string>/Users/a&amp;b/.axon/daemon.log</string>"));
+}
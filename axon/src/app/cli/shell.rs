@@ -0,0 +1,372 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+use anyhow::{Context, Result, bail};
+use axon::config::{AxonPaths, load_persisted_config};
+use axon::message::AgentId;
+use serde_json::{Value, json};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines};
+use tokio::net::unix::{OwnedReadHalf, OwnedWriteHalf};
+
+use crate::app::cli::alias::{annotate_peers, resolve_agent_ref};
+use crate::app::cli::format::{render_inbound_human, render_peers_human, render_status_human};
+use crate::app::cli::ipc_client::{connect_socket, encode_command, is_unsolicited_event};
+use crate::app::cli::line_editor::{Completer, LineEditor};
+
+const PROMPT: &str = "axon> ";
+
+/// Shell commands, in the order `help` lists them.
+const COMMANDS: &[(&str, &str)] = &[
+    (
+        "send",
+        "send <agent_id|alias> <text>   request and print the reply",
+    ),
+    (
+        "notify",
+        "notify <agent_id|alias> <text> fire-and-forget message",
+    ),
+    ("peers", "peers                          list peers"),
+    ("status", "status                         daemon status"),
+    (
+        "watch",
+        "watch on|off                   show inbound messages inline",
+    ),
+    (
+        "inbox",
+        "inbox                          (not available; see watch)",
+    ),
+    ("help", "help                           this list"),
+    (
+        "quit",
+        "quit                           leave the shell (or Ctrl-D)",
+    ),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ShellCommand {
+    Empty,
+    Send { to: String, text: String },
+    Notify { to: String, text: String },
+    Peers,
+    Status,
+    Watch(bool),
+    Inbox,
+    Help,
+    Quit,
+}
+
+pub fn parse_line(line: &str) -> Result<ShellCommand, String> {
+    let line = line.trim();
+    let (word, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    let rest = rest.trim_start();
+    let no_args = |command: ShellCommand| {
+        if rest.is_empty() {
+            Ok(command)
+        } else {
+            Err(format!("`{word}` takes no arguments"))
+        }
+    };
+    match word {
+        "" => Ok(ShellCommand::Empty),
+        "send" | "notify" => {
+            let Some((to, text)) = rest.split_once(char::is_whitespace) else {
+                return Err(format!("usage: {word} <agent_id|alias> <text>"));
+            };
+            let (to, text) = (to.to_string(), text.trim_start().to_string());
+            Ok(if word == "send" {
+                ShellCommand::Send { to, text }
+            } else {
+                ShellCommand::Notify { to, text }
+            })
+        }
+        "watch" => match rest {
+            "on" => Ok(ShellCommand::Watch(true)),
+            "off" => Ok(ShellCommand::Watch(false)),
+            _ => Err("usage: watch on|off".to_string()),
+        },
+        "peers" => no_args(ShellCommand::Peers),
+        "status" => no_args(ShellCommand::Status),
+        "inbox" => no_args(ShellCommand::Inbox),
+        "help" | "?" => no_args(ShellCommand::Help),
+        "quit" | "exit" => no_args(ShellCommand::Quit),
+        other => Err(format!("unknown command '{other}'; type `help`")),
+    }
+}
+
+/// Tab completion: command names for the first word, agent IDs and aliases
+/// for the target of `send`/`notify`, and `on`/`off` after `watch`.
+pub fn complete(line: &str, agents: &[String]) -> (String, Vec<String>) {
+    let (head, word) = match line.rfind(char::is_whitespace) {
+        Some(idx) => line.split_at(idx + 1),
+        None => ("", line),
+    };
+    let words: Vec<&str> = head.split_whitespace().collect();
+    let candidates: Vec<&str> = match words.as_slice() {
+        [] => COMMANDS.iter().map(|(name, _)| *name).collect(),
+        ["send" | "notify"] => agents.iter().map(String::as_str).collect(),
+        ["watch"] => vec!["on", "off"],
+        _ => Vec::new(),
+    };
+    let matches: Vec<&str> = candidates
+        .into_iter()
+        .filter(|candidate| candidate.starts_with(word))
+        .collect();
+    match matches.as_slice() {
+        [] => (line.to_string(), Vec::new()),
+        [only] => (format!("{head}{only} "), Vec::new()),
+        several => {
+            let prefix = common_prefix(several);
+            let options = if prefix.len() > word.len() {
+                Vec::new()
+            } else {
+                several.iter().map(|s| s.to_string()).collect()
+            };
+            (format!("{head}{prefix}"), options)
+        }
+    }
+}
+
+fn common_prefix<'a>(words: &[&'a str]) -> &'a str {
+    let first = words[0];
+    let len = words[1..].iter().fold(first.len(), |len, word| {
+        first
+            .char_indices()
+            .zip(word.chars())
+            .take_while(|((idx, a), b)| *idx < len && a == b)
+            .count()
+    });
+    let end = first
+        .char_indices()
+        .nth(len)
+        .map_or(first.len(), |(idx, _)| idx);
+    &first[..end]
+}
+
+enum Next {
+    Line(Option<String>),
+    Event(Option<String>),
+}
+
+struct Session {
+    lines: Lines<BufReader<OwnedReadHalf>>,
+    writer: OwnedWriteHalf,
+    editor: LineEditor,
+    aliases: BTreeMap<String, AgentId>,
+    agents: Arc<Mutex<Vec<String>>>,
+    watch: bool,
+}
+
+/// Interactive prompt over one IPC connection. Inbound messages are printed
+/// above the prompt while `watch` is on (the default).
+pub async fn run(paths: &AxonPaths) -> Result<()> {
+    let stream = connect_socket(paths).await?;
+    let (reader, writer) = stream.into_split();
+    let aliases = load_persisted_config(&paths.config)
+        .await
+        .map(|config| config.aliases)
+        .unwrap_or_default();
+    let agents = Arc::new(Mutex::new(aliases.keys().cloned().collect::<Vec<_>>()));
+    let completion_agents = agents.clone();
+    let completer: Completer = Arc::new(move |line: &str| {
+        let agents = completion_agents
+            .lock()
+            .map(|agents| agents.clone())
+            .unwrap_or_default();
+        complete(line, &agents)
+    });
+    let mut session = Session {
+        lines: BufReader::new(reader).lines(),
+        writer,
+        editor: LineEditor::spawn(PROMPT, completer),
+        aliases,
+        agents,
+        watch: true,
+    };
+    if std::io::IsTerminal::is_terminal(&std::io::stdin()) {
+        println!(
+            "Connected to {}. Type `help` for commands.",
+            paths.socket.display()
+        );
+        // Seed completion with peer IDs; the table itself is not printed.
+        let reply = session.command(json!({"cmd": "peers"})).await?;
+        session.remember_peers(&reply);
+    }
+
+    let mut prompting = false;
+    loop {
+        if !prompting {
+            session.editor.ready();
+            prompting = true;
+        }
+        let next = tokio::select! {
+            line = session.editor.next_line() => Next::Line(line),
+            event = session.lines.next_line() => Next::Event(event.context("failed to read IPC event")?),
+        };
+        let line = match next {
+            Next::Event(event) => {
+                session.handle_event(event)?;
+                continue;
+            }
+            Next::Line(None) => return Ok(()),
+            Next::Line(Some(line)) => line,
+        };
+        prompting = false;
+        match parse_line(&line) {
+            Ok(ShellCommand::Quit) => return Ok(()),
+            Ok(command) => session.execute(command).await?,
+            Err(message) => session.editor.print_above(&message),
+        }
+    }
+}
+
+impl Session {
+    async fn execute(&mut self, command: ShellCommand) -> Result<()> {
+        let output = match command {
+            ShellCommand::Empty | ShellCommand::Quit => return Ok(()),
+            ShellCommand::Help => COMMANDS
+                .iter()
+                .map(|(_, usage)| *usage)
+                .collect::<Vec<_>>()
+                .join("\n"),
+            ShellCommand::Inbox => "The daemon keeps no inbox; inbound messages are shown \
+                                   inline while `watch on` is set (see `axon history`)."
+                .to_string(),
+            ShellCommand::Watch(on) => {
+                self.watch = on;
+                format!("watch {}", if on { "on" } else { "off" })
+            }
+            ShellCommand::Peers => {
+                let mut reply = self.command(json!({"cmd": "peers"})).await?;
+                self.remember_peers(&reply);
+                annotate_peers(&mut reply, &self.aliases);
+                render_peers_human(&reply).unwrap_or_else(|| render_failure(&reply))
+            }
+            ShellCommand::Status => {
+                let reply = self.command(json!({"cmd": "status"})).await?;
+                render_status_human(&reply).unwrap_or_else(|| render_failure(&reply))
+            }
+            ShellCommand::Send { to, text } => self.send(&to, "request", text).await?,
+            ShellCommand::Notify { to, text } => self.send(&to, "message", text).await?,
+        };
+        self.editor.print_above(&output);
+        Ok(())
+    }
+
+    async fn send(&mut self, to: &str, kind: &str, text: String) -> Result<String> {
+        let to = match resolve_agent_ref(to, &self.aliases) {
+            Ok(to) => to,
+            Err(message) => return Ok(message),
+        };
+        // Same payload shapes as `axon request` and `axon notify`.
+        let payload = if kind == "request" {
+            json!({ "message": text })
+        } else {
+            json!({ "data": text })
+        };
+        let reply = self
+            .command(json!({"cmd": "send", "to": to, "kind": kind, "payload": payload}))
+            .await?;
+        Ok(render_send_reply(&reply))
+    }
+
+    /// Send one command, printing inbound events that arrive before its reply.
+    async fn command(&mut self, command: Value) -> Result<Value> {
+        let mut line = encode_command(&command)?;
+        line.push('\n');
+        self.writer
+            .write_all(line.as_bytes())
+            .await
+            .context("failed to write IPC command")?;
+        loop {
+            let line = self
+                .lines
+                .next_line()
+                .await
+                .context("failed to read IPC response")?;
+            let Some(decoded) = self.decode(line)? else {
+                continue;
+            };
+            if is_unsolicited_event(&decoded) {
+                self.show_event(&decoded);
+                continue;
+            }
+            return Ok(decoded);
+        }
+    }
+
+    fn handle_event(&mut self, line: Option<String>) -> Result<()> {
+        if let Some(decoded) = self.decode(line)?
+            && is_unsolicited_event(&decoded)
+        {
+            self.show_event(&decoded);
+        }
+        Ok(())
+    }
+
+    fn decode(&self, line: Option<String>) -> Result<Option<Value>> {
+        let Some(line) = line else {
+            bail!("daemon closed the IPC connection");
+        };
+        Ok(serde_json::from_str(&line).ok())
+    }
+
+    fn show_event(&self, event: &Value) {
+        if !self.watch {
+            return;
+        }
+        if let Some(rendered) = render_inbound_human(event, axon::message::now_millis()) {
+            self.editor.print_above(&rendered);
+        }
+    }
+
+    fn remember_peers(&self, reply: &Value) {
+        let Some(peers) = reply.get("peers").and_then(Value::as_array) else {
+            return;
+        };
+        let mut agents: Vec<String> = self.aliases.keys().cloned().collect();
+        agents.extend(
+            peers
+                .iter()
+                .filter_map(|peer| peer.get("agent_id").and_then(Value::as_str))
+                .map(str::to_string),
+        );
+        if let Ok(mut current) = self.agents.lock() {
+            *current = agents;
+        }
+    }
+}
+
+fn render_failure(reply: &Value) -> String {
+    format!(
+        "error: {}: {}",
+        reply
+            .get("error")
+            .and_then(Value::as_str)
+            .unwrap_or("unknown"),
+        reply.get("message").and_then(Value::as_str).unwrap_or("")
+    )
+}
+
+pub fn render_send_reply(reply: &Value) -> String {
+    if reply.get("ok") != Some(&json!(true)) {
+        return render_failure(reply);
+    }
+    match reply.get("response") {
+        Some(response) => {
+            let payload = response.get("payload").map(Value::to_string);
+            let payload = payload.unwrap_or_default();
+            match response.get("kind").and_then(Value::as_str) {
+                Some("error") => format!("error reply: {payload}"),
+                _ => payload,
+            }
+        }
+        None => format!(
+            "sent {}",
+            reply.get("msg_id").and_then(Value::as_str).unwrap_or("?")
+        ),
+    }
+}
+
+#[cfg(test)]
+#[path = "shell_tests.rs"]
+mod tests;
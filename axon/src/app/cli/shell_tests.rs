@@ -0,0 +1,111 @@
+use super::*;
+
+fn agents() -> Vec<String> {
+    vec![
+        "ed25519.aaaa0000000000000000000000000000".to_string(),
+        "ed25519.aaab0000000000000000000000000000".to_string(),
+        "laptop".to_string(),
+    ]
+}
+
+#[test]
+fn parse_line_recognizes_commands() {
+    assert_eq!(parse_line("   "), Ok(ShellCommand::Empty));
+    assert_eq!(parse_line("peers"), Ok(ShellCommand::Peers));
+    assert_eq!(parse_line(" status "), Ok(ShellCommand::Status));
+    assert_eq!(parse_line("watch off"), Ok(ShellCommand::Watch(false)));
+    assert_eq!(parse_line("?"), Ok(ShellCommand::Help));
+    assert_eq!(parse_line("exit"), Ok(ShellCommand::Quit));
+    assert_eq!(
+        parse_line("send laptop  hello  there"),
+        Ok(ShellCommand::Send {
+            to: "laptop".to_string(),
+            text: "hello  there".to_string(),
+        })
+    );
+    assert_eq!(
+        parse_line("notify laptop ping"),
+        Ok(ShellCommand::Notify {
+            to: "laptop".to_string(),
+            text: "ping".to_string(),
+        })
+    );
+}
+
+#[test]
+fn parse_line_rejects_malformed_input() {
+    assert!(parse_line("send laptop").unwrap_err().contains("usage"));
+    assert!(parse_line("watch maybe").unwrap_err().contains("usage"));
+    assert!(
+        parse_line("peers now")
+            .unwrap_err()
+            .contains("no arguments")
+    );
+    assert!(parse_line("bogus").unwrap_err().contains("unknown command"));
+}
+
+#[test]
+fn complete_fills_unique_command() {
+    assert_eq!(complete("pe", &[]), ("peers ".to_string(), Vec::new()));
+    assert_eq!(
+        complete("watch o", &[]),
+        (
+            "watch o".to_string(),
+            vec!["on".to_string(), "off".to_string()]
+        )
+    );
+    assert_eq!(complete("zzz", &[]), ("zzz".to_string(), Vec::new()));
+}
+
+#[test]
+fn complete_lists_ambiguous_commands() {
+    // `send` and `status` share only the typed prefix.
+    let (line, options) = complete("s", &[]);
+    assert_eq!(line, "s");
+    assert_eq!(options, vec!["send".to_string(), "status".to_string()]);
+}
+
+#[test]
+fn complete_targets_agents_after_send() {
+    assert_eq!(
+        complete("send la", &agents()),
+        ("send laptop ".to_string(), Vec::new())
+    );
+    // Extends to the shared prefix before listing candidates.
+    let (line, options) = complete("notify ed", &agents());
+    assert_eq!(line, "notify ed25519.aaa");
+    assert!(options.is_empty());
+    let (_, options) = complete("notify ed25519.aaa", &agents());
+    assert_eq!(options.len(), 2);
+    // No completion once the text has started.
+    assert_eq!(
+        complete("send laptop he", &agents()),
+        ("send laptop he".to_string(), Vec::new())
+    );
+}
+
+#[test]
+fn common_prefix_respects_char_boundaries() {
+    assert_eq!(common_prefix(&["peers", "peer"]), "peer");
+    assert_eq!(common_prefix(&["héllo", "hélp"]), "hél");
+    assert_eq!(common_prefix(&["abc", "xyz"]), "");
+}
+
+#[test]
+fn render_send_reply_shapes() {
+    let response = json!({"ok": true, "msg_id": "m1", "response": {"kind": "response", "payload": {"answer": 42}}});
+    assert_eq!(render_send_reply(&response), r#"{"answer":42}"#);
+
+    let remote =
+        json!({"ok": true, "response": {"kind": "error", "payload": {"code": "not_found"}}});
+    assert!(render_send_reply(&remote).starts_with("error reply: "));
+
+    let sent = json!({"ok": true, "msg_id": "m2"});
+    assert_eq!(render_send_reply(&sent), "sent m2");
+
+    let failed = json!({"ok": false, "error": "peer_not_found", "message": "unknown peer"});
+    assert_eq!(
+        render_send_reply(&failed),
+        "error: peer_not_found: unknown peer"
+    );
+}
@@ -0,0 +1,368 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::io::Write;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result, bail};
+use axon::config::{AxonPaths, load_persisted_config};
+use clap::Args;
+use serde_json::{Value, json};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+use crate::app::cli::alias::annotate_peers;
+use crate::app::cli::format::{format_utc_ms, render_inbound_human};
+use crate::app::cli::ipc_client::{connect_socket, encode_command, is_unsolicited_event};
+
+/// Inbound messages kept in the recent pane.
+const RECENT_INBOUND: usize = 10;
+/// Longest inbound line shown; the rest of the payload is elided.
+const INBOUND_PREVIEW: usize = 160;
+
+#[derive(Debug, Clone, Args)]
+pub struct TopArgs {
+    /// Seconds between `status` and `peers` polls.
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        default_value_t = 2,
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    pub interval: u64,
+    /// Print one frame and exit instead of redrawing the terminal.
+    #[arg(long)]
+    pub once: bool,
+}
+
+/// Connection changes seen for one peer while `axon top` runs.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct LinkState {
+    /// `connected` events; more than one means the peer reconnected.
+    pub connects: u64,
+    /// Reason from the most recent `disconnected` event.
+    pub last_disconnect: Option<String>,
+    /// Unix ms of the most recent change.
+    pub changed_ms: Option<u64>,
+}
+
+/// Everything the dashboard shows, fed by poll replies and IPC events.
+#[derive(Debug, Default)]
+pub struct Dashboard {
+    status: Option<Value>,
+    peers: Vec<Value>,
+    /// Previous `status` sample: when it arrived, messages sent and received.
+    sample: Option<(Instant, u64, u64)>,
+    /// Messages sent and received per second between the last two polls.
+    rates: Option<(f64, f64)>,
+    links: BTreeMap<String, LinkState>,
+    recent: VecDeque<String>,
+}
+
+impl Dashboard {
+    pub fn apply_status(&mut self, status: Value, at: Instant) {
+        let counter = |name: &str| status.get(name).and_then(Value::as_u64).unwrap_or(0);
+        let (sent, received) = (counter("messages_sent"), counter("messages_received"));
+        if let Some((previous, prev_sent, prev_received)) = self.sample {
+            let elapsed = at.saturating_duration_since(previous).as_secs_f64();
+            if elapsed > 0.0 {
+                self.rates = Some((
+                    sent.saturating_sub(prev_sent) as f64 / elapsed,
+                    received.saturating_sub(prev_received) as f64 / elapsed,
+                ));
+            }
+        }
+        self.sample = Some((at, sent, received));
+        self.status = Some(status);
+    }
+
+    pub fn apply_peers(&mut self, reply: &Value) {
+        self.peers = reply
+            .get("peers")
+            .and_then(Value::as_array)
+            .cloned()
+            .unwrap_or_default();
+    }
+
+    pub fn apply_event(&mut self, event: &Value, now_ms: u64) {
+        let agent_id = event.get("agent_id").and_then(Value::as_str);
+        match (event.get("event").and_then(Value::as_str), agent_id) {
+            (Some("inbound"), _) => {
+                if let Some(line) = render_inbound_human(event, now_ms) {
+                    if self.recent.len() == RECENT_INBOUND {
+                        self.recent.pop_front();
+                    }
+                    self.recent.push_back(truncate(&line, INBOUND_PREVIEW));
+                }
+            }
+            (Some("connected"), Some(agent_id)) => {
+                let link = self.links.entry(agent_id.to_string()).or_default();
+                link.connects += 1;
+                link.changed_ms = Some(now_ms);
+            }
+            (Some("disconnected"), Some(agent_id)) => {
+                let link = self.links.entry(agent_id.to_string()).or_default();
+                link.last_disconnect = Some(
+                    event
+                        .get("reason")
+                        .and_then(Value::as_str)
+                        .unwrap_or("unknown")
+                        .to_string(),
+                );
+                link.changed_ms = Some(now_ms);
+            }
+            _ => {}
+        }
+    }
+
+    pub fn render(&self) -> String {
+        let status = self.status.as_ref();
+        let counter = |name: &str| {
+            status
+                .and_then(|status| status.get(name))
+                .and_then(Value::as_u64)
+                .unwrap_or(0)
+        };
+        let mut out = format!(
+            "axon top  uptime {}s  peers connected {}",
+            counter("uptime_secs"),
+            counter("peers_connected")
+        );
+        if let Some(degraded) = status
+            .and_then(|status| status.get("degraded"))
+            .and_then(Value::as_array)
+        {
+            let names: Vec<&str> = degraded.iter().filter_map(Value::as_str).collect();
+            out.push_str(&format!("  degraded: {}", names.join(", ")));
+        }
+
+        out.push_str("\n\nPEERS\n");
+        out.push_str(&self.render_peers());
+
+        let rate = |pick: fn((f64, f64)) -> f64| {
+            self.rates
+                .map(|rates| format!("{:.1}/s", pick(rates)))
+                .unwrap_or_else(|| "-".to_string())
+        };
+        out.push_str(&format!(
+            "\n\nTRAFFIC\nsent {} ({})  received {} ({})  rate-limited {}",
+            counter("messages_sent"),
+            rate(|(sent, _)| sent),
+            counter("messages_received"),
+            rate(|(_, received)| received),
+            counter("messages_rate_limited")
+        ));
+
+        out.push_str("\n\nRECENT INBOUND\n");
+        if self.recent.is_empty() {
+            out.push_str("(none yet)");
+        } else {
+            let lines: Vec<&str> = self.recent.iter().rev().map(String::as_str).collect();
+            out.push_str(&lines.join("\n"));
+        }
+        out
+    }
+
+    fn render_peers(&self) -> String {
+        if self.peers.is_empty() && self.links.is_empty() {
+            return "No peers.".to_string();
+        }
+        let mut rows: Vec<[String; 5]> = Vec::new();
+        let mut listed = Vec::new();
+        for peer in &self.peers {
+            let field = |name: &str| peer.get(name).and_then(Value::as_str).unwrap_or("?");
+            let agent_id = field("agent_id");
+            listed.push(agent_id);
+            let label = match peer.get("alias").and_then(Value::as_str) {
+                Some(alias) => format!("{agent_id} ({alias})"),
+                None => agent_id.to_string(),
+            };
+            let rtt = peer
+                .get("rtt_ms")
+                .and_then(Value::as_f64)
+                .map(|rtt| format!("{rtt:.2}"))
+                .unwrap_or_else(|| "-".to_string());
+            rows.push(self.peer_row(label, field("status"), rtt, agent_id));
+        }
+        // Peers that dropped out of the table since the last poll.
+        for agent_id in self.links.keys() {
+            if !listed.contains(&agent_id.as_str()) {
+                rows.push(self.peer_row(agent_id.clone(), "gone", "-".to_string(), agent_id));
+            }
+        }
+
+        let header = [
+            "AGENT_ID",
+            "STATUS",
+            "RTT_MS",
+            "CONNECTS",
+            "LAST_DISCONNECT",
+        ];
+        let mut widths = header.map(str::len);
+        for row in &rows {
+            for (idx, cell) in row.iter().enumerate() {
+                widths[idx] = widths[idx].max(cell.chars().count());
+            }
+        }
+        let line = |cells: [&str; 5]| {
+            format!(
+                "{:<w0$}  {:<w1$}  {:>w2$}  {:>w3$}  {}",
+                cells[0],
+                cells[1],
+                cells[2],
+                cells[3],
+                cells[4],
+                w0 = widths[0],
+                w1 = widths[1],
+                w2 = widths[2],
+                w3 = widths[3],
+            )
+            .trim_end()
+            .to_string()
+        };
+        let mut lines = vec![line(header)];
+        lines.extend(
+            rows.iter()
+                .map(|row| line(row.each_ref().map(String::as_str))),
+        );
+        lines.join("\n")
+    }
+
+    fn peer_row(&self, label: String, status: &str, rtt: String, agent_id: &str) -> [String; 5] {
+        let link = self.links.get(agent_id);
+        let connects = link
+            .map(|link| link.connects.to_string())
+            .unwrap_or_else(|| "-".to_string());
+        let last_disconnect = link
+            .and_then(|link| {
+                let reason = link.last_disconnect.as_deref()?;
+                Some(match link.changed_ms {
+                    Some(ms) => format!("{reason} at {}", format_utc_ms(ms)),
+                    None => reason.to_string(),
+                })
+            })
+            .unwrap_or_default();
+        [label, status.to_string(), rtt, connects, last_disconnect]
+    }
+}
+
+fn truncate(line: &str, max_chars: usize) -> String {
+    match line.char_indices().nth(max_chars) {
+        Some((idx, _)) => format!("{}…", &line[..idx]),
+        None => line.to_string(),
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Poll {
+    Status,
+    Peers,
+}
+
+impl Poll {
+    fn cmd(self) -> &'static str {
+        match self {
+            Poll::Status => "status",
+            Poll::Peers => "peers",
+        }
+    }
+}
+
+/// Alternate terminal screen, restored on drop.
+pub(crate) struct AltScreen;
+
+impl AltScreen {
+    pub(crate) fn enter() -> Self {
+        print!("\x1b[?1049h\x1b[?25l");
+        let _ = std::io::stdout().flush();
+        Self
+    }
+
+    pub(crate) fn draw(&self, frame: &str) {
+        let mut out = std::io::stdout().lock();
+        let _ = write!(out, "\x1b[H\x1b[2J{frame}");
+        let _ = out.flush();
+    }
+}
+
+impl Drop for AltScreen {
+    fn drop(&mut self) {
+        print!("\x1b[?25h\x1b[?1049l");
+        let _ = std::io::stdout().flush();
+    }
+}
+
+/// Poll `status` and `peers` every `interval` on one IPC connection and
+/// redraw after each round; `connected`/`disconnected`/`inbound` events on the
+/// same connection update the panes in between. Runs until Ctrl-C.
+pub async fn run(paths: &AxonPaths, args: &TopArgs) -> Result<()> {
+    let stream = connect_socket(paths).await?;
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader).lines();
+    let aliases = load_persisted_config(&paths.config)
+        .await
+        .map(|config| config.aliases)
+        .unwrap_or_default();
+
+    let mut dashboard = Dashboard::default();
+    let mut pending: VecDeque<Poll> = VecDeque::new();
+    let mut ticker = tokio::time::interval(Duration::from_secs(args.interval));
+    let screen = (!args.once).then(AltScreen::enter);
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {
+                // Skip a round while the previous one is still unanswered.
+                if !pending.is_empty() {
+                    continue;
+                }
+                for poll in [Poll::Status, Poll::Peers] {
+                    let mut line = encode_command(&json!({"cmd": poll.cmd()}))?;
+                    line.push('\n');
+                    writer
+                        .write_all(line.as_bytes())
+                        .await
+                        .context("failed to write IPC command")?;
+                    pending.push_back(poll);
+                }
+            }
+            line = lines.next_line() => {
+                let Some(line) = line.context("failed to read IPC response")? else {
+                    bail!("daemon closed the IPC connection");
+                };
+                let Ok(decoded) = serde_json::from_str::<Value>(&line) else {
+                    continue;
+                };
+                if is_unsolicited_event(&decoded) {
+                    dashboard.apply_event(&decoded, axon::message::now_millis());
+                } else {
+                    let Some(poll) = pending.pop_front() else {
+                        continue;
+                    };
+                    if decoded.get("ok") != Some(&json!(true)) {
+                        bail!("daemon rejected `{}`: {decoded}", poll.cmd());
+                    }
+                    match poll {
+                        Poll::Status => dashboard.apply_status(decoded, Instant::now()),
+                        Poll::Peers => {
+                            let mut reply = decoded;
+                            annotate_peers(&mut reply, &aliases);
+                            dashboard.apply_peers(&reply);
+                        }
+                    }
+                }
+                if !pending.is_empty() || dashboard.status.is_none() {
+                    continue;
+                }
+                match &screen {
+                    Some(screen) => screen.draw(&dashboard.render()),
+                    None => {
+                        println!("{}", dashboard.render());
+                        return Ok(());
+                    }
+                }
+            }
+            _ = tokio::signal::ctrl_c() => return Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "top_tests.rs"]
+mod tests;
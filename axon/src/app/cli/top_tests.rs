@@ -0,0 +1,95 @@
+use super::*;
+
+const PEER: &str = "ed25519.aaaa0000000000000000000000000000";
+
+fn status(sent: u64, received: u64) -> Value {
+    json!({
+        "ok": true,
+        "uptime_secs": 90,
+        "peers_connected": 1,
+        "messages_sent": sent,
+        "messages_received": received,
+        "messages_rate_limited": 0
+    })
+}
+
+#[test]
+fn rates_come_from_consecutive_status_samples() {
+    let mut dashboard = Dashboard::default();
+    let start = Instant::now();
+    dashboard.apply_status(status(10, 4), start);
+    assert!(dashboard.render().contains("sent 10 (-)  received 4 (-)"));
+
+    dashboard.apply_status(status(20, 5), start + Duration::from_secs(2));
+    assert_eq!(dashboard.rates, Some((5.0, 0.5)));
+    assert!(
+        dashboard
+            .render()
+            .contains("sent 20 (5.0/s)  received 5 (0.5/s)")
+    );
+}
+
+#[test]
+fn connection_events_track_reconnects() {
+    let mut dashboard = Dashboard::default();
+    dashboard.apply_event(&json!({"event": "connected", "agent_id": PEER}), 1_000);
+    dashboard.apply_event(
+        &json!({"event": "disconnected", "agent_id": PEER, "reason": "idle_timeout"}),
+        2_000,
+    );
+    dashboard.apply_event(&json!({"event": "connected", "agent_id": PEER}), 3_000);
+    assert_eq!(
+        dashboard.links[PEER],
+        LinkState {
+            connects: 2,
+            last_disconnect: Some("idle_timeout".to_string()),
+            changed_ms: Some(3_000),
+        }
+    );
+}
+
+#[test]
+fn peers_pane_merges_poll_and_events() {
+    let mut dashboard = Dashboard::default();
+    assert!(dashboard.render().contains("PEERS\nNo peers."));
+
+    dashboard.apply_peers(&json!({"ok": true, "peers": [
+        {"agent_id": PEER, "alias": "laptop", "status": "connected", "rtt_ms": 1.5}
+    ]}));
+    dashboard.apply_event(
+        &json!({"event": "disconnected", "agent_id": "ed25519.bbbb0000000000000000000000000000", "reason": "reset"}),
+        0,
+    );
+    let rendered = dashboard.render();
+    assert!(rendered.contains(&format!("{PEER} (laptop)")), "{rendered}");
+    assert!(rendered.contains("1.50"), "{rendered}");
+    assert!(
+        rendered.contains("gone") && rendered.contains("reset at 1970-01-01T00:00:00.000Z"),
+        "{rendered}"
+    );
+}
+
+#[test]
+fn recent_inbound_is_bounded_and_newest_first() {
+    let mut dashboard = Dashboard::default();
+    for idx in 0..(RECENT_INBOUND + 2) {
+        dashboard.apply_event(
+            &json!({"event": "inbound", "from": PEER, "envelope": {
+                "id": format!("msg-{idx}"), "kind": "message", "payload": {"n": idx}
+            }}),
+            0,
+        );
+    }
+    assert_eq!(dashboard.recent.len(), RECENT_INBOUND);
+    let rendered = dashboard.render();
+    let inbound = rendered.split("RECENT INBOUND\n").nth(1).expect("pane");
+    assert!(inbound.starts_with("1970-01-01T00:00:00.000Z  message"));
+    assert!(inbound.lines().next().expect("line").contains("msg-11"));
+    assert!(!inbound.contains("msg-1 "));
+}
+
+#[test]
+fn truncate_marks_elided_text() {
+    assert_eq!(truncate("short", 10), "short");
+    assert_eq!(truncate("ééééé", 3), "ééé…");
+}
@@ -0,0 +1,81 @@
+use anyhow::{Context, Result, bail};
+use axon::config::AxonPaths;
+use clap::Args;
+use serde_json::Value;
+use tokio::io::{AsyncBufReadExt, BufReader};
+
+use crate::app::cli::alias::parse_agent_ref_arg;
+use crate::app::cli::format::render_inbound_human;
+use crate::app::cli::ipc_client::connect_socket;
+
+#[derive(Debug, Clone, Args)]
+pub struct WatchArgs {
+    /// Only envelopes of this kind (repeatable).
+    #[arg(long, value_parser = ["request", "response", "message", "error"])]
+    pub kind: Vec<String>,
+    /// Only envelopes from this agent, by agent ID or alias (repeatable).
+    #[arg(long, value_name = "AGENT_ID", value_parser = parse_agent_ref_arg)]
+    pub from: Vec<String>,
+    /// Only envelopes in this conversation thread (repeatable).
+    #[arg(long, value_name = "THREAD_ID")]
+    pub thread: Vec<String>,
+    /// Print each inbound event as one line of JSON.
+    #[arg(long)]
+    pub json: bool,
+}
+
+impl WatchArgs {
+    /// Whether an IPC line is an `inbound` event passing the filters.
+    pub fn matches(&self, event: &Value) -> bool {
+        if event.get("event").and_then(Value::as_str) != Some("inbound") {
+            return false;
+        }
+        let field = |name: &str| {
+            event
+                .get("envelope")
+                .and_then(|envelope| envelope.get(name))
+                .and_then(Value::as_str)
+        };
+        let kind = field("kind");
+        if !self.kind.is_empty() && !self.kind.iter().any(|k| Some(k.as_str()) == kind) {
+            return false;
+        }
+        let thread = field("thread_id");
+        if !self.thread.is_empty() && !self.thread.iter().any(|t| Some(t.as_str()) == thread) {
+            return false;
+        }
+        let from = event.get("from").and_then(Value::as_str);
+        self.from.is_empty() || self.from.iter().any(|f| Some(f.as_str()) == from)
+    }
+}
+
+/// Print inbound envelopes as they arrive until the daemon closes the
+/// connection. Every connected IPC client receives `inbound` events, so no
+/// command is sent.
+pub async fn watch(paths: &AxonPaths, args: &WatchArgs) -> Result<()> {
+    let stream = connect_socket(paths).await?;
+    let mut lines = BufReader::new(stream).lines();
+    while let Some(line) = lines
+        .next_line()
+        .await
+        .context("failed to read IPC event")?
+    {
+        let Ok(event) = serde_json::from_str::<Value>(&line) else {
+            tracing::debug!("skipping undecodable IPC line");
+            continue;
+        };
+        if !args.matches(&event) {
+            continue;
+        }
+        if args.json {
+            println!("{line}");
+        } else if let Some(rendered) = render_inbound_human(&event, axon::message::now_millis()) {
+            println!("{rendered}");
+        }
+    }
+    bail!("daemon closed the IPC connection")
+}
+
+#[cfg(test)]
+#[path = "watch_tests.rs"]
+mod tests;
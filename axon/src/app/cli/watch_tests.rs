@@ -0,0 +1,52 @@
+use serde_json::{Value, json};
+
+use super::WatchArgs;
+
+const PEER_A: &str = "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+const PEER_B: &str = "ed25519.bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb";
+
+fn args(kind: &[&str], from: &[&str]) -> WatchArgs {
+    WatchArgs {
+        kind: kind.iter().map(|k| k.to_string()).collect(),
+        from: from.iter().map(|f| f.to_string()).collect(),
+        thread: Vec::new(),
+        json: false,
+    }
+}
+
+fn inbound(from: &str, kind: &str) -> Value {
+    json!({
+        "event": "inbound",
+        "from": from,
+        "envelope": {"id": "6f1c1a4e-2d3b-4c5a-9e8f-0a1b2c3d4e5f", "kind": kind, "payload": {}}
+    })
+}
+
+#[test]
+fn unfiltered_matches_only_inbound_events() {
+    let args = args(&[], &[]);
+    assert!(args.matches(&inbound(PEER_A, "message")));
+    assert!(!args.matches(&json!({"event": "connected", "agent_id": PEER_A})));
+    assert!(!args.matches(&json!({"ok": true})));
+}
+
+#[test]
+fn kind_and_from_filters_combine() {
+    let args = args(&["request", "message"], &[PEER_A]);
+    assert!(args.matches(&inbound(PEER_A, "request")));
+    assert!(args.matches(&inbound(PEER_A, "message")));
+    assert!(!args.matches(&inbound(PEER_A, "error")));
+    assert!(!args.matches(&inbound(PEER_B, "request")));
+}
+
+#[test]
+fn thread_filter_requires_matching_thread_id() {
+    let mut args = args(&[], &[]);
+    args.thread = vec!["t-1".to_string()];
+    let mut threaded = inbound(PEER_A, "message");
+    threaded["envelope"]["thread_id"] = json!("t-1");
+    assert!(args.matches(&threaded));
+    threaded["envelope"]["thread_id"] = json!("t-2");
+    assert!(!args.matches(&threaded));
+    assert!(!args.matches(&inbound(PEER_A, "message")));
+}
@@ -16,7 +16,7 @@ pub(in crate::app::doctor) async fn check_config(
         return Ok(());
     }
 
-    match Config::load(&paths.config).await {
+    match Config::load_for(paths).await {
         Ok(cfg) => {
             report.add_check(
                 "config",
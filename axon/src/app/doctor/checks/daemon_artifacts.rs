@@ -7,10 +7,9 @@ use anyhow::{Context, Result};
 
 use axon::config::AxonPaths;
 
+use crate::app::cli::daemon_ctl::{DAEMON_PID_FILE_NAME, pid_is_alive};
 use crate::app::doctor::{DoctorArgs, DoctorReport};
 
-const DAEMON_PID_FILE_NAME: &str = "daemon.pid";
-
 pub(in crate::app::doctor) fn check_daemon_artifacts(
     paths: &AxonPaths,
     args: &DoctorArgs,
@@ -193,20 +192,3 @@ fn compact_value(value: &str) -> String {
     prefix.push_str("...");
     prefix
 }
-
-fn pid_is_alive(pid: u32) -> bool {
-    if pid == 0 || pid > i32::MAX as u32 {
-        return false;
-    }
-
-    // SAFETY: kill(pid, 0) probes process existence/permission without sending a signal.
-    let rc = unsafe { libc::kill(pid as libc::pid_t, 0) };
-    if rc == 0 {
-        return true;
-    }
-
-    matches!(
-        std::io::Error::last_os_error().raw_os_error(),
-        Some(code) if code == libc::EPERM
-    )
-}
@@ -97,7 +97,7 @@ pub(in crate::app::doctor) async fn check_duplicate_peer_addrs(
         by_addr.entry(peer.addr).or_default().push(peer);
     }
 
-    let static_ids: HashSet<String> = Config::load(&paths.config)
+    let static_ids: HashSet<String> = Config::load_for(paths)
         .await
         .map(|cfg| cfg.peers.iter().map(|p| p.agent_id.to_string()).collect())
         .unwrap_or_default();
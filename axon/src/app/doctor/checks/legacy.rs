@@ -0,0 +1,285 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde_json::{Map, Value};
+
+use axon::config::{
+    AxonPaths, KnownPeer, PersistedConfig, save_known_peers, save_persisted_config,
+};
+
+use crate::app::doctor::{DoctorArgs, DoctorReport};
+
+use super::backup_file_with_timestamp;
+
+/// Config file name used before `config.yaml`.
+const LEGACY_CONFIG_FILE_NAME: &str = "config.toml";
+
+/// Detect state written by older releases and, with `--fix`, rewrite it in
+/// the current format after backing up the original. Runs before the
+/// `config` and `known_peers` checks so those see migrated files rather
+/// than resetting them as corrupt.
+pub(in crate::app::doctor) async fn check_legacy_state(
+    paths: &AxonPaths,
+    args: &DoctorArgs,
+    report: &mut DoctorReport,
+) -> Result<()> {
+    let checks_before = report.checks.len();
+    check_config_toml(paths, args, report).await?;
+    check_config_peer_ids(paths, args, report).await?;
+    check_known_peers_format(paths, args, report).await?;
+    if report.checks.len() == checks_before {
+        report.add_check(
+            "legacy_state",
+            true,
+            false,
+            "no legacy state files found".to_string(),
+        );
+    }
+    Ok(())
+}
+
+async fn check_config_toml(
+    paths: &AxonPaths,
+    args: &DoctorArgs,
+    report: &mut DoctorReport,
+) -> Result<()> {
+    let toml_path = paths.root.join(LEGACY_CONFIG_FILE_NAME);
+    let Some(raw) = read_optional(&toml_path).await? else {
+        return Ok(());
+    };
+
+    if paths.config.exists() {
+        if args.fix {
+            let backup = backup_file_with_timestamp(&toml_path)?;
+            report.add_fix(
+                "legacy_config_toml_backup",
+                format!(
+                    "moved superseded config.toml to {} (config.yaml is unchanged)",
+                    backup.display()
+                ),
+            );
+            report.add_check(
+                "legacy_config_toml",
+                true,
+                true,
+                "superseded config.toml moved aside".to_string(),
+            );
+        } else {
+            report.add_check(
+                "legacy_config_toml",
+                false,
+                true,
+                "config.toml is ignored because config.yaml exists; run `axon doctor --fix` to move it aside".to_string(),
+            );
+        }
+        return Ok(());
+    }
+
+    let config = match config_from_toml(&raw) {
+        Ok(config) => config,
+        Err(err) => {
+            report.add_check(
+                "legacy_config_toml",
+                false,
+                false,
+                format!(
+                    "config.toml is not used and cannot be converted ({err:#}); copy its settings into config.yaml by hand"
+                ),
+            );
+            return Ok(());
+        }
+    };
+    if args.fix {
+        let backup = backup_file_with_timestamp(&toml_path)?;
+        save_persisted_config(&paths.config, &config).await?;
+        report.add_fix(
+            "legacy_config_migrated",
+            format!(
+                "converted config.toml to config.yaml ({} static peers); original kept at {}",
+                config.peers.len(),
+                backup.display()
+            ),
+        );
+        report.add_check(
+            "legacy_config_toml",
+            true,
+            true,
+            "config.toml migrated to config.yaml".to_string(),
+        );
+    } else {
+        report.add_check(
+            "legacy_config_toml",
+            false,
+            true,
+            "config.toml is no longer read; run `axon doctor --fix` to convert it to config.yaml"
+                .to_string(),
+        );
+    }
+    Ok(())
+}
+
+async fn check_config_peer_ids(
+    paths: &AxonPaths,
+    args: &DoctorArgs,
+    report: &mut DoctorReport,
+) -> Result<()> {
+    let Some(raw) = read_optional(&paths.config).await? else {
+        return Ok(());
+    };
+    // Files that are not YAML at all are the `config` check's to report.
+    let Ok(mut config) = serde_yaml::from_str::<serde_yaml::Value>(&raw) else {
+        return Ok(());
+    };
+    let renamed = rename_yaml_peer_ids(&mut config);
+    if renamed == 0 {
+        return Ok(());
+    }
+
+    if args.fix {
+        let serialized = serde_yaml::to_string(&config).context("failed to serialize config")?;
+        let backup = backup_file_with_timestamp(&paths.config)?;
+        tokio::fs::write(&paths.config, serialized)
+            .await
+            .with_context(|| format!("failed to write config: {}", paths.config.display()))?;
+        report.add_fix(
+            "legacy_peer_id_migrated",
+            format!(
+                "renamed `id` to `agent_id` on {renamed} peers in config.yaml; original kept at {}",
+                backup.display()
+            ),
+        );
+        report.add_check(
+            "legacy_peer_id",
+            true,
+            true,
+            "config.yaml peers use `agent_id`".to_string(),
+        );
+    } else {
+        report.add_check(
+            "legacy_peer_id",
+            false,
+            true,
+            format!(
+                "{renamed} peers in config.yaml use the old `id` field; run `axon doctor --fix` to rename it to `agent_id`"
+            ),
+        );
+    }
+    Ok(())
+}
+
+async fn check_known_peers_format(
+    paths: &AxonPaths,
+    args: &DoctorArgs,
+    report: &mut DoctorReport,
+) -> Result<()> {
+    let Some(raw) = read_optional(&paths.known_peers).await? else {
+        return Ok(());
+    };
+    if serde_json::from_str::<Vec<KnownPeer>>(&raw).is_ok() {
+        return Ok(());
+    }
+    // Anything that does not convert cleanly is left to the `known_peers`
+    // check, which backs it up and resets it.
+    let Some(peers) = migrate_known_peers(&raw) else {
+        return Ok(());
+    };
+
+    if args.fix {
+        let backup = backup_file_with_timestamp(&paths.known_peers)?;
+        save_known_peers(&paths.known_peers, &peers).await?;
+        report.add_fix(
+            "legacy_known_peers_migrated",
+            format!(
+                "rewrote {} known_peers.json entries in the current format; original kept at {}",
+                peers.len(),
+                backup.display()
+            ),
+        );
+        report.add_check(
+            "legacy_known_peers",
+            true,
+            true,
+            "known_peers.json migrated".to_string(),
+        );
+    } else {
+        report.add_check(
+            "legacy_known_peers",
+            false,
+            true,
+            "known_peers.json uses an old format and is ignored by the daemon; run `axon doctor --fix` to migrate it".to_string(),
+        );
+    }
+    Ok(())
+}
+
+/// Text of `path`, or `None` if it is missing or not UTF-8 (the other
+/// checks report unreadable files).
+async fn read_optional(path: &Path) -> Result<Option<String>> {
+    match tokio::fs::read(path).await {
+        Ok(raw) => Ok(String::from_utf8(raw).ok()),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err).with_context(|| format!("failed to read {}", path.display())),
+    }
+}
+
+/// Parse a pre-YAML `config.toml`. The keys match `config.yaml`, except
+/// that peers may name their agent `id`.
+fn config_from_toml(raw: &str) -> Result<PersistedConfig> {
+    let mut config: Value = toml::from_str(raw).context("invalid TOML")?;
+    if let Some(peers) = config.get_mut("peers").and_then(Value::as_array_mut) {
+        for peer in peers.iter_mut().filter_map(Value::as_object_mut) {
+            rename_json_id(peer);
+        }
+    }
+    serde_json::from_value(config).context("unsupported settings")
+}
+
+/// Rename `id` to `agent_id` on each `peers` entry that lacks `agent_id`,
+/// keeping key order. Returns how many entries changed.
+fn rename_yaml_peer_ids(config: &mut serde_yaml::Value) -> usize {
+    let Some(peers) = config
+        .get_mut("peers")
+        .and_then(serde_yaml::Value::as_sequence_mut)
+    else {
+        return 0;
+    };
+    let mut renamed = 0;
+    for peer in peers
+        .iter_mut()
+        .filter_map(serde_yaml::Value::as_mapping_mut)
+    {
+        if peer.contains_key("agent_id") || !peer.contains_key("id") {
+            continue;
+        }
+        *peer = std::mem::take(peer)
+            .into_iter()
+            .map(|(key, value)| match key.as_str() {
+                Some("id") => (serde_yaml::Value::from("agent_id"), value),
+                _ => (key, value),
+            })
+            .collect();
+        renamed += 1;
+    }
+    renamed
+}
+
+/// Convert a `known_peers.json` from before peer `source` tracking (and
+/// the `id` field) into current entries, or `None` if it is not one.
+fn migrate_known_peers(raw: &str) -> Option<Vec<KnownPeer>> {
+    let mut entries: Vec<Map<String, Value>> = serde_json::from_str(raw).ok()?;
+    for entry in &mut entries {
+        rename_json_id(entry);
+        entry
+            .entry("source")
+            .or_insert_with(|| Value::from("cached"));
+    }
+    serde_json::from_value(Value::from(entries)).ok()
+}
+
+fn rename_json_id(entry: &mut Map<String, Value>) {
+    if !entry.contains_key("agent_id")
+        && let Some(id) = entry.remove("id")
+    {
+        entry.insert("agent_id".to_string(), id);
+    }
+}
@@ -1,6 +1,9 @@
 mod config;
 mod daemon_artifacts;
 mod known_peers;
+mod legacy;
+mod network;
+mod peer_versions;
 mod state_root;
 
 use std::fs;
@@ -12,9 +15,12 @@ use anyhow::{Context, Result, anyhow};
 pub(super) use config::check_config;
 pub(super) use daemon_artifacts::check_daemon_artifacts;
 pub(super) use known_peers::{check_duplicate_peer_addrs, check_known_peers};
+pub(super) use legacy::check_legacy_state;
+pub(super) use network::check_network;
+pub(super) use peer_versions::check_peer_versions;
 pub(super) use state_root::check_state_root;
 
-pub(super) fn backup_file_with_timestamp(path: &Path) -> Result<PathBuf> {
+pub(crate) fn backup_file_with_timestamp(path: &Path) -> Result<PathBuf> {
     let ts = SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .map_err(|err| anyhow!("system time error: {err}"))?
@@ -0,0 +1,342 @@
+use std::io::ErrorKind;
+use std::net::{SocketAddr, UdpSocket};
+use std::time::Duration;
+
+use anyhow::Result;
+use serde_json::{Value, json};
+
+use axon::config::{AxonPaths, Config, StaticPeerConfig};
+use axon::discovery::mdns_advertisement_visible;
+use axon::identity::Identity;
+use axon::peer_table::{PeerRecord, PeerTable};
+use axon::transport::QuicTransport;
+
+use crate::app::cli::daemon_ctl::running_daemon_pid;
+use crate::app::cli::ipc_client::send_ipc;
+use crate::app::doctor::DoctorReport;
+
+/// How long to wait for an ICMP port-unreachable after a UDP probe.
+const UDP_PROBE_WAIT: Duration = Duration::from_secs(1);
+const QUIC_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(5);
+const MDNS_BROWSE_TIME: Duration = Duration::from_secs(3);
+
+/// Active probes behind `axon doctor --network`. Nothing here is fixable;
+/// every failure carries remediation text instead.
+pub(in crate::app::doctor) async fn check_network(
+    paths: &AxonPaths,
+    report: &mut DoctorReport,
+) -> Result<()> {
+    let Ok(config) = Config::load_for(paths).await else {
+        report.add_check(
+            "network",
+            false,
+            false,
+            "config.yaml could not be loaded; fix it before running network checks".to_string(),
+        );
+        return Ok(());
+    };
+    let daemon_pid = running_daemon_pid(paths);
+    let port = config.effective_port(None);
+
+    check_udp_port(port, daemon_pid, report);
+
+    let connected = match daemon_pid {
+        Some(_) => send_ipc(paths, json!({"cmd": "peers"})).await.ok(),
+        None => None,
+    };
+    for peer in &config.peers {
+        check_peer_udp(peer, report).await;
+        match &connected {
+            Some(reply) => check_peer_connected(peer, reply, report),
+            None if daemon_pid.is_some() => report.add_check(
+                "peer_quic",
+                false,
+                false,
+                format!(
+                    "{}: daemon is running but did not answer `peers` over IPC; check `axon health`",
+                    peer.agent_id
+                ),
+            ),
+            None => check_peer_handshake(paths, peer, report).await,
+        }
+    }
+
+    check_mdns(paths, daemon_pid.is_some(), report).await;
+    Ok(())
+}
+
+fn check_udp_port(port: u16, daemon_pid: Option<u32>, report: &mut DoctorReport) {
+    if let Some(pid) = daemon_pid {
+        report.add_check(
+            "udp_port",
+            true,
+            false,
+            format!("UDP port {port} is held by the running daemon (pid {pid})"),
+        );
+        return;
+    }
+    match UdpSocket::bind(("0.0.0.0", port)) {
+        Ok(_) => report.add_check("udp_port", true, false, format!("UDP port {port} is free")),
+        Err(err) if err.kind() == ErrorKind::AddrInUse => report.add_check(
+            "udp_port",
+            false,
+            false,
+            format!(
+                "UDP port {port} is in use by another process; stop it (see `ss -ulpn`) or choose \
+                 another `port` in config.yaml"
+            ),
+        ),
+        Err(err) => report.add_check(
+            "udp_port",
+            false,
+            false,
+            format!("cannot bind UDP port {port}: {err}"),
+        ),
+    }
+}
+
+/// Send one datagram and wait briefly for an ICMP port-unreachable, which
+/// Linux reports as `ConnectionRefused` on the connected socket. Silence
+/// means the datagram was not rejected: the port is open or filtered.
+async fn check_peer_udp(peer: &StaticPeerConfig, report: &mut DoctorReport) {
+    // A blocking socket: the pending ICMP error does not wake tokio's `recv`.
+    let addr = peer.addr;
+    let outcome = tokio::task::spawn_blocking(move || {
+        let socket = UdpSocket::bind(unspecified_for(addr))?;
+        socket.connect(addr)?;
+        socket.set_read_timeout(Some(UDP_PROBE_WAIT))?;
+        socket.send(&[0u8])?;
+        let mut buf = [0u8; 64];
+        match socket.recv(&mut buf) {
+            Err(err) if err.kind() == ErrorKind::ConnectionRefused => Err(err),
+            _ => Ok(()),
+        }
+    })
+    .await
+    .unwrap_or_else(|err| Err(std::io::Error::other(err)));
+    match outcome {
+        Ok(()) => report.add_check(
+            "peer_udp",
+            true,
+            false,
+            format!("{} at {}: datagram not rejected", peer.agent_id, peer.addr),
+        ),
+        Err(err) if err.kind() == ErrorKind::ConnectionRefused => report.add_check(
+            "peer_udp",
+            false,
+            false,
+            format!(
+                "{} at {}: port closed (ICMP port unreachable); is the peer daemon running on \
+                 port {}?",
+                peer.agent_id,
+                peer.addr,
+                peer.addr.port()
+            ),
+        ),
+        Err(err) => report.add_check(
+            "peer_udp",
+            false,
+            false,
+            format!(
+                "{} at {}: cannot send UDP ({err}); check routing to the peer (VPN up?)",
+                peer.agent_id, peer.addr
+            ),
+        ),
+    }
+}
+
+/// With the daemon running, report its connection state instead of dialing:
+/// a second connection under the same agent ID would replace the daemon's
+/// own connection on the peer.
+fn check_peer_connected(peer: &StaticPeerConfig, reply: &Value, report: &mut DoctorReport) {
+    let entry = reply
+        .get("peers")
+        .and_then(Value::as_array)
+        .and_then(|peers| {
+            peers.iter().find(|entry| {
+                entry.get("agent_id").and_then(Value::as_str) == Some(peer.agent_id.as_str())
+            })
+        });
+    let status = entry
+        .and_then(|entry| entry.get("status"))
+        .and_then(Value::as_str)
+        .unwrap_or("unknown");
+    if status == "connected" {
+        let rtt = entry
+            .and_then(|entry| entry.get("rtt_ms"))
+            .and_then(Value::as_f64)
+            .map(|rtt| format!(" (rtt {rtt:.1} ms)"))
+            .unwrap_or_default();
+        report.add_check(
+            "peer_quic",
+            true,
+            false,
+            format!("{}: connected{rtt}", peer.agent_id),
+        );
+    } else {
+        report.add_check(
+            "peer_quic",
+            false,
+            false,
+            format!(
+                "{}: daemon reports status '{status}'; make sure the peer has enrolled this agent \
+                 (`axon connect` with this agent's `axon identity` token) and that UDP {} is open",
+                peer.agent_id,
+                peer.addr.port()
+            ),
+        );
+    }
+}
+
+async fn check_peer_handshake(
+    paths: &AxonPaths,
+    peer: &StaticPeerConfig,
+    report: &mut DoctorReport,
+) {
+    if !Identity::exists(paths).unwrap_or(false) {
+        report.add_check(
+            "peer_quic",
+            false,
+            false,
+            format!(
+                "{}: no identity to handshake with; run `axon doctor --fix` first",
+                peer.agent_id
+            ),
+        );
+        return;
+    }
+    let outcome = async {
+        let identity = Identity::load_or_generate(paths)?;
+        let table = PeerTable::new();
+        table.upsert_static(peer).await;
+        let transport =
+            QuicTransport::bind(unspecified_for(peer.addr), &identity, 1, table.pubkey_map())
+                .await?;
+        let result = tokio::time::timeout(
+            QUIC_HANDSHAKE_TIMEOUT,
+            transport.ensure_connection(&PeerRecord::from_static(peer)),
+        )
+        .await;
+        transport.close_all().await;
+        anyhow::Ok(result)
+    }
+    .await;
+    match outcome {
+        Ok(Ok(Ok(_))) => report.add_check(
+            "peer_quic",
+            true,
+            false,
+            format!(
+                "{} at {}: QUIC handshake succeeded",
+                peer.agent_id, peer.addr
+            ),
+        ),
+        Ok(Ok(Err(err))) => report.add_check(
+            "peer_quic",
+            false,
+            false,
+            format!(
+                "{} at {}: handshake failed ({err:#}); the peer may not have enrolled this agent \
+                 (run `axon connect` on the peer with this agent's `axon identity` token), or \
+                 its pubkey in config.yaml is stale",
+                peer.agent_id, peer.addr
+            ),
+        ),
+        Ok(Err(_)) => report.add_check(
+            "peer_quic",
+            false,
+            false,
+            format!(
+                "{} at {}: no QUIC response within {}s; the peer daemon is down or a firewall \
+                 drops UDP {}",
+                peer.agent_id,
+                peer.addr,
+                QUIC_HANDSHAKE_TIMEOUT.as_secs(),
+                peer.addr.port()
+            ),
+        ),
+        Err(err) => report.add_check(
+            "peer_quic",
+            false,
+            false,
+            format!(
+                "{}: could not start handshake probe: {err:#}",
+                peer.agent_id
+            ),
+        ),
+    }
+}
+
+async fn check_mdns(paths: &AxonPaths, daemon_running: bool, report: &mut DoctorReport) {
+    if !daemon_running {
+        report.add_check(
+            "mdns_advertisement",
+            true,
+            false,
+            "skipped: daemon not running".to_string(),
+        );
+        return;
+    }
+    let mdns_state = send_ipc(paths, json!({"cmd": "health"}))
+        .await
+        .ok()
+        .and_then(|reply| {
+            reply
+                .pointer("/subsystems/discovery/mdns")
+                .and_then(Value::as_str)
+                .map(str::to_string)
+        });
+    if mdns_state.as_deref() != Some("running") {
+        report.add_check(
+            "mdns_advertisement",
+            true,
+            false,
+            format!(
+                "skipped: daemon mDNS is {}",
+                mdns_state.as_deref().unwrap_or("unknown")
+            ),
+        );
+        return;
+    }
+    let Ok(identity) = Identity::load_or_generate(paths) else {
+        report.add_check(
+            "mdns_advertisement",
+            false,
+            false,
+            "cannot read identity to look for this agent's advertisement".to_string(),
+        );
+        return;
+    };
+    match mdns_advertisement_visible(identity.agent_id(), MDNS_BROWSE_TIME).await {
+        Ok(true) => report.add_check(
+            "mdns_advertisement",
+            true,
+            false,
+            "this agent's mDNS advertisement is visible on the local network".to_string(),
+        ),
+        Ok(false) => report.add_check(
+            "mdns_advertisement",
+            false,
+            false,
+            format!(
+                "this agent's advertisement was not seen within {}s; multicast UDP 5353 may be \
+                 blocked by a firewall or unsupported on this network (use static peers)",
+                MDNS_BROWSE_TIME.as_secs()
+            ),
+        ),
+        Err(err) => report.add_check(
+            "mdns_advertisement",
+            false,
+            false,
+            format!("cannot browse mDNS: {err:#}"),
+        ),
+    }
+}
+
+fn unspecified_for(addr: SocketAddr) -> SocketAddr {
+    if addr.is_ipv4() {
+        SocketAddr::from(([0, 0, 0, 0], 0))
+    } else {
+        SocketAddr::from(([0u16; 8], 0))
+    }
+}
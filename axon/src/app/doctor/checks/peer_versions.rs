@@ -0,0 +1,62 @@
+use anyhow::Result;
+use serde_json::json;
+
+use axon::config::AxonPaths;
+use axon::peer_table::VersionCompatibility;
+
+use crate::app::cli::daemon_ctl::running_daemon_pid;
+use crate::app::cli::ipc_client::send_ipc;
+use crate::app::doctor::DoctorReport;
+
+/// Compare the versions connected peers report in their heartbeats with the
+/// running daemon's. Skipped when no daemon answers over IPC; peers that
+/// predate version reporting (or run with heartbeats off) are not listed.
+pub(in crate::app::doctor) async fn check_peer_versions(
+    paths: &AxonPaths,
+    report: &mut DoctorReport,
+) -> Result<()> {
+    if running_daemon_pid(paths).is_none() {
+        return Ok(());
+    }
+    let (Ok(whoami), Ok(peers)) = (
+        send_ipc(paths, json!({"cmd": "whoami"})).await,
+        send_ipc(paths, json!({"cmd": "peers"})).await,
+    ) else {
+        return Ok(());
+    };
+    let local = whoami["version"]
+        .as_str()
+        .unwrap_or(env!("CARGO_PKG_VERSION"));
+
+    let (mut reported, mut incompatible) = (0, 0);
+    for peer in peers["peers"].as_array().into_iter().flatten() {
+        let (Some(agent_id), Some(version)) = (peer["agent_id"].as_str(), peer["version"].as_str())
+        else {
+            continue;
+        };
+        reported += 1;
+        let advice = match VersionCompatibility::of(local, version) {
+            VersionCompatibility::Older => "upgrade the peer",
+            VersionCompatibility::Newer => "upgrade this agent",
+            VersionCompatibility::Compatible | VersionCompatibility::Unknown => continue,
+        };
+        incompatible += 1;
+        report.add_check(
+            "peer_version",
+            false,
+            false,
+            format!(
+                "{agent_id} runs axon {version}, incompatible with this daemon's {local}; {advice}"
+            ),
+        );
+    }
+    if incompatible == 0 {
+        report.add_check(
+            "peer_version",
+            true,
+            false,
+            format!("{reported} peers reported versions compatible with {local}"),
+        );
+    }
+    Ok(())
+}
@@ -15,7 +15,19 @@ pub(super) fn check_identity(
     args: &DoctorArgs,
     report: &mut DoctorReport,
 ) -> Result<()> {
-    match inspect_identity_key(&paths.identity_key)? {
+    let health = match inspect_identity_key(&paths.identity_key)? {
+        IdentityHealth::Missing if Identity::exists(paths)? => IdentityHealth::InKeychain,
+        health => health,
+    };
+    match health {
+        IdentityHealth::InKeychain => {
+            report.add_check(
+                "identity",
+                true,
+                false,
+                "identity seed is in the keychain".to_string(),
+            );
+        }
         IdentityHealth::Valid => {
             report.add_check(
                 "identity",
@@ -125,6 +137,7 @@ fn backup_identity_files(paths: &AxonPaths) -> Result<Vec<String>> {
 #[derive(Debug, Clone, PartialEq, Eq)]
 enum IdentityHealth {
     Valid,
+    InKeychain,
     Missing,
     Invalid(String),
 }
@@ -7,6 +7,8 @@ use axon::config::AxonPaths;
 mod checks;
 mod identity_check;
 
+pub(crate) use checks::backup_file_with_timestamp;
+
 #[derive(Debug, Clone, Args)]
 pub struct DoctorArgs {
     /// Print machine-readable JSON report.
@@ -18,6 +20,10 @@ pub struct DoctorArgs {
     /// Allow destructive identity reset when key data is unrecoverable.
     #[arg(long, requires = "fix")]
     pub rekey: bool,
+    /// Also run active network probes (UDP port, static peer reachability and
+    /// QUIC handshakes, mDNS visibility). Takes a few seconds.
+    #[arg(long)]
+    pub network: bool,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -77,9 +83,14 @@ pub async fn run(paths: &AxonPaths, args: &DoctorArgs) -> Result<DoctorReport> {
     checks::check_state_root(paths, args, &mut report)?;
     identity_check::check_identity(paths, args, &mut report)?;
     checks::check_daemon_artifacts(paths, args, &mut report)?;
+    checks::check_legacy_state(paths, args, &mut report).await?;
     checks::check_known_peers(paths, args, &mut report).await?;
     checks::check_duplicate_peer_addrs(paths, args, &mut report).await?;
     checks::check_config(paths, args, &mut report).await?;
+    checks::check_peer_versions(paths, &mut report).await?;
+    if args.network {
+        checks::check_network(paths, &mut report).await?;
+    }
 
     Ok(report)
 }
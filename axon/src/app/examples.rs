@@ -111,7 +111,7 @@ All connected clients receive inbound messages as broadcast events.
 
 # 4. Daemon status
 → {{"cmd":"status"}}
-← {{"ok":true,"uptime_secs":3600,"peers_connected":1,"messages_sent":42,"messages_received":38}}
+← {{"ok":true,"uptime_secs":3600,"peers_connected":1,"messages_sent":42,"messages_received":38,"messages_rate_limited":0,"lifetime":{{"messages_sent":1290,"messages_received":1107,"messages_rate_limited":0,"uptime_secs":864000,"daemon_starts":7,"first_started_ms":1771400000000}}}}
 
 # 5. Daemon identity
 → {{"cmd":"whoami"}}
@@ -0,0 +1,128 @@
+use std::io;
+use std::os::unix::net::UnixDatagram;
+
+use super::{Record, SYSLOG_FACILITY};
+
+/// Send `datagram` to journald. One too large for a socket datagram is
+/// passed as a sealed memfd instead, as `sd_journal_send` does; failures
+/// are dropped like any other log write failure.
+pub(super) fn send(socket: &UnixDatagram, datagram: &[u8]) {
+    if let Err(err) = socket.send(datagram)
+        && matches!(err.raw_os_error(), Some(libc::EMSGSIZE | libc::ENOBUFS))
+    {
+        let _ = send_memfd(socket, datagram);
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn send_memfd(socket: &UnixDatagram, datagram: &[u8]) -> io::Result<()> {
+    use std::fs::File;
+    use std::io::Write;
+    use std::os::fd::{AsRawFd, FromRawFd};
+
+    // SAFETY: the name is a NUL-terminated literal and the flags are valid.
+    let fd = unsafe {
+        libc::memfd_create(
+            c"axon-journal".as_ptr(),
+            libc::MFD_CLOEXEC | libc::MFD_ALLOW_SEALING,
+        )
+    };
+    if fd < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    // SAFETY: `fd` was just created and is owned by nothing else.
+    let mut memfd = unsafe { File::from_raw_fd(fd) };
+    memfd.write_all(datagram)?;
+    // journald refuses memfds that could still change under it.
+    let seals = libc::F_SEAL_SHRINK | libc::F_SEAL_GROW | libc::F_SEAL_WRITE | libc::F_SEAL_SEAL;
+    // SAFETY: `fd` is a valid memfd created with MFD_ALLOW_SEALING.
+    if unsafe { libc::fcntl(fd, libc::F_ADD_SEALS, seals) } < 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    // An empty datagram whose only content is the descriptor (SCM_RIGHTS).
+    let fd_len = std::mem::size_of::<libc::c_int>() as u32;
+    // SAFETY: CMSG_SPACE only computes a size.
+    let mut control = vec![0u8; unsafe { libc::CMSG_SPACE(fd_len) } as usize];
+    // SAFETY: msghdr is plain old data; all-zero is an empty message.
+    let mut message: libc::msghdr = unsafe { std::mem::zeroed() };
+    message.msg_control = control.as_mut_ptr().cast();
+    message.msg_controllen = control.len() as _;
+    // SAFETY: `control` has room for one header carrying one descriptor, so
+    // CMSG_FIRSTHDR is non-null and CMSG_DATA points inside the buffer.
+    unsafe {
+        let header = libc::CMSG_FIRSTHDR(&message);
+        (*header).cmsg_level = libc::SOL_SOCKET;
+        (*header).cmsg_type = libc::SCM_RIGHTS;
+        (*header).cmsg_len = libc::CMSG_LEN(fd_len) as _;
+        std::ptr::write_unaligned(
+            libc::CMSG_DATA(header).cast::<libc::c_int>(),
+            memfd.as_raw_fd(),
+        );
+    }
+    // SAFETY: `message` and the buffers it points to outlive the call.
+    if unsafe { libc::sendmsg(socket.as_raw_fd(), &message, libc::MSG_NOSIGNAL) } < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// journald only runs on Linux; elsewhere an oversized event is dropped.
+#[cfg(not(target_os = "linux"))]
+fn send_memfd(_socket: &UnixDatagram, _datagram: &[u8]) -> io::Result<()> {
+    Err(io::Error::from(io::ErrorKind::Unsupported))
+}
+
+/// Native journal protocol: `KEY=value` lines, with multi-line values
+/// length-prefixed.
+pub(super) fn datagram(record: &Record<'_>) -> Vec<u8> {
+    let mut datagram = Vec::new();
+    let mut field = |key: &str, value: &str| {
+        datagram.extend_from_slice(key.as_bytes());
+        if value.contains('\n') {
+            datagram.push(b'\n');
+            datagram.extend_from_slice(&(value.len() as u64).to_le_bytes());
+        } else {
+            datagram.push(b'=');
+        }
+        datagram.extend_from_slice(value.as_bytes());
+        datagram.push(b'\n');
+    };
+    field("MESSAGE", &record.message);
+    field("PRIORITY", &record.severity().to_string());
+    field("SYSLOG_IDENTIFIER", "axon");
+    field("SYSLOG_FACILITY", &SYSLOG_FACILITY.to_string());
+    field("TARGET", record.target);
+    if let Some(file) = record.file {
+        field("CODE_FILE", file);
+    }
+    if let Some(line) = record.line {
+        field("CODE_LINE", &line.to_string());
+    }
+    field("AGENT_ID", record.agent_id);
+    if let Some(peer) = &record.peer {
+        field("PEER", peer);
+    }
+    if let Some(msg_id) = &record.msg_id {
+        field("MSG_ID", msg_id);
+    }
+    for (name, value) in &record.fields {
+        field(&field_name(name), value);
+    }
+    datagram
+}
+
+/// Journal field names are uppercase letters, digits, and `_`, and may not
+/// start with `_` (reserved for trusted fields).
+fn field_name(name: &str) -> String {
+    name.trim_start_matches('_')
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
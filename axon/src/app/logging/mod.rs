@@ -0,0 +1,328 @@
+use std::fmt::{self, Write as _};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::os::unix::fs::OpenOptionsExt;
+use std::os::unix::net::UnixDatagram;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+use anyhow::{Context as _, Result};
+use axon::config::{LogTarget, LoggingConfig};
+use axon::message::now_millis;
+use serde_json::{Map, Value, json};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer, Registry, reload};
+
+const JOURNALD_SOCKET: &str = "/run/systemd/journal/socket";
+const SYSLOG_SOCKET: &str = "/dev/log";
+/// `daemon` facility.
+const SYSLOG_FACILITY: u8 = 3;
+/// RFC 5424 SD-ID for the structured fields; 32473 is the example
+/// enterprise number reserved by RFC 5612.
+const SYSLOG_SD_ID: &str = "axon@32473";
+
+mod journald;
+
+type BoxedLayer = Box<dyn Layer<Registry> + Send + Sync>;
+
+static SINK: OnceLock<reload::Handle<BoxedLayer, Registry>> = OnceLock::new();
+
+/// Install the global subscriber: `filter` in front of human-readable
+/// output on stdout (or stderr), swappable later by [`apply`].
+pub(crate) fn init(filter: EnvFilter, to_stderr: bool) {
+    let output: BoxedLayer = if to_stderr {
+        tracing_subscriber::fmt::layer()
+            .with_writer(std::io::stderr)
+            .boxed()
+    } else {
+        tracing_subscriber::fmt::layer().boxed()
+    };
+    let (sink, handle) = reload::Layer::new(output);
+    if Registry::default()
+        .with(sink)
+        .with(filter)
+        .try_init()
+        .is_ok()
+    {
+        let _ = SINK.set(handle);
+    }
+}
+
+/// Switch log output to the `logging.target` sink. Stdout keeps the
+/// output installed by [`init`].
+pub(crate) fn apply(config: &LoggingConfig, root: &Path, agent_id: &str) -> Result<()> {
+    let layer = match config.target() {
+        LogTarget::Stdout => return Ok(()),
+        LogTarget::Journald => StructuredLayer::journald(Path::new(JOURNALD_SOCKET), agent_id)?,
+        LogTarget::Syslog => StructuredLayer::syslog(Path::new(SYSLOG_SOCKET), agent_id)?,
+        LogTarget::File => StructuredLayer::file(
+            &config.file_path(root),
+            config.max_file_bytes(),
+            config.max_files(),
+            agent_id,
+        )?,
+    };
+    let Some(handle) = SINK.get() else {
+        return Ok(());
+    };
+    handle
+        .reload(Box::new(layer) as BoxedLayer)
+        .context("failed to switch log output")
+}
+
+/// Writes each event with its severity and the local agent ID, peer, and
+/// message ID as separate fields. Write failures are dropped: there is
+/// nowhere left to report them.
+pub(crate) struct StructuredLayer {
+    sink: Sink,
+    agent_id: String,
+}
+
+enum Sink {
+    Journald(UnixDatagram),
+    Syslog(UnixDatagram),
+    File(Mutex<LogFile>),
+}
+
+impl StructuredLayer {
+    pub(crate) fn journald(socket: &Path, agent_id: &str) -> Result<Self> {
+        Ok(Self::new(Sink::Journald(connect(socket)?), agent_id))
+    }
+
+    pub(crate) fn syslog(socket: &Path, agent_id: &str) -> Result<Self> {
+        Ok(Self::new(Sink::Syslog(connect(socket)?), agent_id))
+    }
+
+    /// Append JSON lines to `path` (created 0600 with its parent), rotating
+    /// it to `path.1` … `path.<max_files>` before it would pass `max_bytes`.
+    pub(crate) fn file(
+        path: &Path,
+        max_bytes: u64,
+        max_files: usize,
+        agent_id: &str,
+    ) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("failed to create {}", parent.display()))?;
+        }
+        let file = LogFile::open(path, max_bytes, max_files)
+            .with_context(|| format!("failed to open log file {}", path.display()))?;
+        Ok(Self::new(Sink::File(Mutex::new(file)), agent_id))
+    }
+
+    fn new(sink: Sink, agent_id: &str) -> Self {
+        Self {
+            sink,
+            agent_id: agent_id.to_string(),
+        }
+    }
+}
+
+/// The `target: file` log, with its size for rotation.
+struct LogFile {
+    path: PathBuf,
+    file: File,
+    len: u64,
+    max_bytes: u64,
+    max_files: usize,
+}
+
+impl LogFile {
+    fn open(path: &Path, max_bytes: u64, max_files: usize) -> std::io::Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .mode(0o600)
+            .open(path)?;
+        let len = file.metadata()?.len();
+        Ok(Self {
+            path: path.to_path_buf(),
+            file,
+            len,
+            max_bytes,
+            max_files,
+        })
+    }
+
+    fn write_line(&mut self, line: &[u8]) -> std::io::Result<()> {
+        if self.len > 0 && self.len + line.len() as u64 > self.max_bytes {
+            self.rotate()?;
+        }
+        self.file.write_all(line)?;
+        self.len += line.len() as u64;
+        Ok(())
+    }
+
+    /// Shift `path.N-1` → `path.N` … `path` → `path.1`, dropping the
+    /// oldest, and start a new file.
+    fn rotate(&mut self) -> std::io::Result<()> {
+        let _ = std::fs::remove_file(rotated_path(&self.path, self.max_files));
+        for n in (1..self.max_files).rev() {
+            let _ = std::fs::rename(rotated_path(&self.path, n), rotated_path(&self.path, n + 1));
+        }
+        std::fs::rename(&self.path, rotated_path(&self.path, 1))?;
+        *self = Self::open(&self.path, self.max_bytes, self.max_files)?;
+        Ok(())
+    }
+}
+
+fn rotated_path(path: &Path, n: usize) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".{n}"));
+    PathBuf::from(name)
+}
+
+fn connect(socket: &Path) -> Result<UnixDatagram> {
+    let datagram = UnixDatagram::unbound()?;
+    datagram
+        .connect(socket)
+        .with_context(|| format!("failed to connect to {}", socket.display()))?;
+    Ok(datagram)
+}
+
+impl<S: Subscriber> Layer<S> for StructuredLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let record = Record::from_event(event, &self.agent_id);
+        match &self.sink {
+            Sink::Journald(socket) => {
+                journald::send(socket, &journald::datagram(&record));
+            }
+            Sink::Syslog(socket) => {
+                let _ = socket.send(syslog_message(&record).as_bytes());
+            }
+            Sink::File(file) => {
+                let mut line = json_line(&record).to_string();
+                line.push('\n');
+                let mut file = file.lock().unwrap_or_else(|err| err.into_inner());
+                let _ = file.write_line(line.as_bytes());
+            }
+        }
+    }
+}
+
+/// One event with the well-known fields pulled out.
+struct Record<'a> {
+    level: Level,
+    target: &'a str,
+    file: Option<&'a str>,
+    line: Option<u32>,
+    agent_id: &'a str,
+    message: String,
+    peer: Option<String>,
+    msg_id: Option<String>,
+    fields: Vec<(&'static str, String)>,
+}
+
+impl<'a> Record<'a> {
+    fn from_event(event: &'a Event<'_>, agent_id: &'a str) -> Self {
+        let metadata = event.metadata();
+        let mut record = Self {
+            level: *metadata.level(),
+            target: metadata.target(),
+            file: metadata.file(),
+            line: metadata.line(),
+            agent_id,
+            message: String::new(),
+            peer: None,
+            msg_id: None,
+            fields: Vec::new(),
+        };
+        event.record(&mut record);
+        record
+    }
+
+    /// Syslog severity: err, warning, info, or debug.
+    fn severity(&self) -> u8 {
+        match self.level {
+            Level::ERROR => 3,
+            Level::WARN => 4,
+            Level::INFO => 6,
+            _ => 7,
+        }
+    }
+}
+
+impl Visit for Record<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.record_value(field.name(), value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.record_value(field.name(), format!("{value:?}"));
+    }
+}
+
+impl Record<'_> {
+    fn record_value(&mut self, name: &'static str, value: String) {
+        match name {
+            "message" => self.message = value,
+            "msg_id" => self.msg_id = Some(value),
+            "peer" | "peer_id" => self.peer = Some(value),
+            // Events name the agent they are about `agent_id`; the local
+            // agent is already on every record.
+            "agent_id" if value == self.agent_id => {}
+            "agent_id" if self.peer.is_none() => self.peer = Some(value),
+            _ => self.fields.push((name, value)),
+        }
+    }
+}
+
+/// RFC 5424 message; the receiver fills in the timestamp and hostname.
+fn syslog_message(record: &Record<'_>) -> String {
+    let mut message = format!(
+        "<{}>1 - - axon {} - [{SYSLOG_SD_ID} agent_id=\"{}\"",
+        SYSLOG_FACILITY * 8 + record.severity(),
+        std::process::id(),
+        sd_escape(record.agent_id)
+    );
+    if let Some(peer) = &record.peer {
+        let _ = write!(message, " peer=\"{}\"", sd_escape(peer));
+    }
+    if let Some(msg_id) = &record.msg_id {
+        let _ = write!(message, " msg_id=\"{}\"", sd_escape(msg_id));
+    }
+    let _ = write!(message, "] {}", record.message);
+    for (name, value) in &record.fields {
+        let _ = write!(message, " {name}={value}");
+    }
+    message
+}
+
+fn sd_escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace(']', "\\]")
+}
+
+fn json_line(record: &Record<'_>) -> Value {
+    let mut line = json!({
+        "ts_ms": now_millis(),
+        "level": record.level.as_str(),
+        "target": record.target,
+        "agent_id": record.agent_id,
+        "message": record.message,
+    });
+    if let Some(peer) = &record.peer {
+        line["peer"] = json!(peer);
+    }
+    if let Some(msg_id) = &record.msg_id {
+        line["msg_id"] = json!(msg_id);
+    }
+    if !record.fields.is_empty() {
+        let fields: Map<String, Value> = record
+            .fields
+            .iter()
+            .map(|(name, value)| (name.to_string(), json!(value)))
+            .collect();
+        line["fields"] = Value::Object(fields);
+    }
+    line
+}
+
+#[cfg(test)]
+#[path = "tests.rs"]
+mod tests;
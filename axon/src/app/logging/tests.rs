@@ -0,0 +1,183 @@
+use std::os::unix::fs::PermissionsExt;
+
+use tempfile::tempdir;
+use tracing_subscriber::layer::SubscriberExt;
+
+use super::*;
+
+const LOCAL: &str = "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+const PEER: &str = "ed25519.bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb";
+
+fn emit(layer: StructuredLayer, log: impl FnOnce()) {
+    tracing::subscriber::with_default(Registry::default().with(layer), log);
+}
+
+fn receive(socket: &UnixDatagram) -> Vec<u8> {
+    let mut buf = vec![0; 64 * 1024];
+    let len = socket.recv(&mut buf).unwrap();
+    buf.truncate(len);
+    buf
+}
+
+#[test]
+fn journald_gets_priority_and_structured_fields() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("journal.sock");
+    let journal = UnixDatagram::bind(&path).unwrap();
+
+    emit(StructuredLayer::journald(&path, LOCAL).unwrap(), || {
+        tracing::warn!(
+            peer = PEER,
+            msg_id = "m-1",
+            error = "boom\nat line 2",
+            "send failed"
+        );
+    });
+    let datagram = receive(&journal);
+    let text = String::from_utf8_lossy(&datagram);
+    for line in [
+        "MESSAGE=send failed\n",
+        "PRIORITY=4\n",
+        "SYSLOG_IDENTIFIER=axon\n",
+        &format!("AGENT_ID={LOCAL}\n"),
+        &format!("PEER={PEER}\n"),
+        "MSG_ID=m-1\n",
+    ] {
+        assert!(text.contains(line), "missing {line:?} in {text:?}");
+    }
+    let mut multiline = b"ERROR\n".to_vec();
+    multiline.extend_from_slice(&14u64.to_le_bytes());
+    multiline.extend_from_slice(b"boom\nat line 2\n");
+    assert!(datagram.ends_with(&multiline), "{text:?}");
+}
+
+/// Receive a datagram that carries one descriptor and no bytes.
+#[cfg(target_os = "linux")]
+fn receive_fd(socket: &UnixDatagram) -> std::fs::File {
+    use std::os::fd::{AsRawFd, FromRawFd};
+
+    let fd_len = std::mem::size_of::<libc::c_int>() as u32;
+    // SAFETY: CMSG_SPACE only computes a size.
+    let mut control = vec![0u8; unsafe { libc::CMSG_SPACE(fd_len) } as usize];
+    // SAFETY: msghdr is plain old data; all-zero is an empty message.
+    let mut message: libc::msghdr = unsafe { std::mem::zeroed() };
+    message.msg_control = control.as_mut_ptr().cast();
+    message.msg_controllen = control.len() as _;
+    // SAFETY: `message` points at `control`, which outlives the call.
+    let len = unsafe { libc::recvmsg(socket.as_raw_fd(), &mut message, 0) };
+    assert_eq!(len, 0, "expected an empty datagram");
+    // SAFETY: recvmsg filled in one SCM_RIGHTS header within `control`.
+    unsafe {
+        let header = libc::CMSG_FIRSTHDR(&message);
+        assert!(!header.is_null());
+        assert_eq!((*header).cmsg_type, libc::SCM_RIGHTS);
+        let fd = std::ptr::read_unaligned(libc::CMSG_DATA(header).cast::<libc::c_int>());
+        std::fs::File::from_raw_fd(fd)
+    }
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn journald_sends_oversized_events_as_a_sealed_memfd() {
+    use std::io::{Read, Seek};
+
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("journal.sock");
+    let journal = UnixDatagram::bind(&path).unwrap();
+    let big = "x".repeat(512 * 1024);
+
+    emit(StructuredLayer::journald(&path, LOCAL).unwrap(), || {
+        tracing::info!(payload = big.as_str(), "large event");
+    });
+    let mut memfd = receive_fd(&journal);
+    let mut contents = Vec::new();
+    memfd.rewind().unwrap();
+    memfd.read_to_end(&mut contents).unwrap();
+    let text = String::from_utf8_lossy(&contents);
+    assert!(text.starts_with("MESSAGE=large event\n"), "{}", &text[..64]);
+    assert!(text.contains(&format!("AGENT_ID={LOCAL}\n")));
+    assert!(text.ends_with(&format!("PAYLOAD={big}\n")));
+    assert!(memfd.set_len(0).is_err(), "memfd must be sealed");
+}
+
+#[test]
+fn syslog_uses_rfc5424_structured_data() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("log.sock");
+    let syslog = UnixDatagram::bind(&path).unwrap();
+
+    emit(StructuredLayer::syslog(&path, LOCAL).unwrap(), || {
+        tracing::error!(agent_id = PEER, reason = "quo\"te]", "peer rejected");
+        tracing::debug!(agent_id = LOCAL, "tick");
+    });
+    let message = String::from_utf8(receive(&syslog)).unwrap();
+    let pid = std::process::id();
+    assert_eq!(
+        message,
+        format!(
+            "<27>1 - - axon {pid} - [axon@32473 agent_id=\"{LOCAL}\" peer=\"{PEER}\"] peer rejected reason=quo\"te]"
+        )
+    );
+    let message = String::from_utf8(receive(&syslog)).unwrap();
+    assert!(
+        message.starts_with(&format!(
+            "<31>1 - - axon {pid} - [axon@32473 agent_id=\"{LOCAL}\"] tick"
+        )),
+        "the local agent is not reported as a peer: {message}"
+    );
+}
+
+#[test]
+fn file_target_appends_json_lines() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("logs/axon.log");
+
+    emit(
+        StructuredLayer::file(&path, 1 << 20, 2, LOCAL).unwrap(),
+        || {
+            tracing::info!(msg_id = "m-2", port = 7100, "starting");
+        },
+    );
+    emit(
+        StructuredLayer::file(&path, 1 << 20, 2, LOCAL).unwrap(),
+        || {
+            tracing::info!(peer_id = PEER, "connected");
+        },
+    );
+    let lines: Vec<Value> = std::fs::read_to_string(&path)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0]["level"], "INFO");
+    assert_eq!(lines[0]["agent_id"], LOCAL);
+    assert_eq!(lines[0]["msg_id"], "m-2");
+    assert_eq!(lines[0]["fields"], json!({"port": "7100"}));
+    assert_eq!(lines[1]["peer"], PEER);
+    assert_eq!(lines[1]["message"], "connected");
+    assert!(lines[1].get("fields").is_none());
+
+    let mode = std::fs::metadata(&path).unwrap().permissions().mode() & 0o777;
+    assert_eq!(mode, 0o600);
+}
+
+#[test]
+fn file_target_rotates_before_passing_max_bytes() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("axon.log");
+
+    emit(StructuredLayer::file(&path, 200, 2, LOCAL).unwrap(), || {
+        for n in 0..8 {
+            tracing::info!(n, "line");
+        }
+    });
+    let rotated = |n: usize| dir.path().join(format!("axon.log.{n}"));
+    for file in [path.clone(), rotated(1), rotated(2)] {
+        let len = std::fs::metadata(&file).unwrap().len();
+        assert!(len > 0 && len <= 200, "{}: {len} bytes", file.display());
+    }
+    assert!(!rotated(3).exists());
+    let last = std::fs::read_to_string(&path).unwrap();
+    assert!(last.contains("\"n\":\"7\""), "{last}");
+}
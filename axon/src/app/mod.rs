@@ -1,4 +1,7 @@
+pub(crate) mod args;
 pub(crate) mod cli;
 pub(crate) mod doctor;
 pub(crate) mod examples;
+pub(crate) mod logging;
 pub(crate) mod run;
+pub(crate) mod self_test;
@@ -1,324 +1,270 @@
-use std::path::PathBuf;
 use std::process::ExitCode;
+use std::time::Duration;
 
 use anyhow::{Context, Result};
-#[cfg(feature = "generate-docs")]
 use clap::CommandFactory;
-use clap::{Parser, Subcommand};
-use serde_json::json;
 use tracing_subscriber::EnvFilter;
 
-use axon::config::{
-    AxonPaths, Config, PeerAddr, PersistedStaticPeerConfig, load_persisted_config,
-    save_persisted_config,
-};
+use axon::config::{AxonPaths, Config, load_persisted_config};
 use axon::daemon::{DaemonOptions, run_daemon};
 use axon::identity::Identity;
-use axon::peer_token;
-
-use super::{cli, doctor, examples};
-
-#[derive(Debug, Parser)]
-#[command(
-    name = "axon",
-    about = "AXON — Agent eXchange Over Network",
-    version = env!("CARGO_PKG_VERSION"),
-    propagate_version = true
-)]
-pub(crate) struct Cli {
-    /// AXON state root directory (socket/identity/config/known_peers).
-    /// Falls back to AXON_ROOT, then ~/.axon.
-    #[arg(
-        long = "state-root",
-        visible_aliases = ["state", "root"],
-        global = true,
-        value_name = "DIR"
-    )]
-    pub state_root: Option<PathBuf>,
-
-    /// Increase log verbosity (-v = debug, -vv = trace). Conflicts with --quiet.
-    #[arg(long, short = 'v', global = true, action = clap::ArgAction::Count, conflicts_with = "quiet")]
-    pub verbose: u8,
 
-    /// Suppress per-message logs (warn-level only). Conflicts with -v.
-    #[arg(long, short = 'q', global = true, conflicts_with = "verbose")]
-    pub quiet: bool,
+pub(crate) use super::args::{Cli, Commands};
+use super::{cli, doctor, examples, logging, self_test};
 
-    #[command(subcommand)]
-    command: Commands,
-}
-
-#[derive(Debug, Subcommand)]
-pub(crate) enum Commands {
-    /// Start the daemon (runs in foreground).
-    Daemon {
-        #[arg(long)]
-        port: Option<u16>,
-        /// Disable mDNS discovery (use static peers only).
-        #[arg(long)]
-        disable_mdns: bool,
-    },
-    /// Send a request to another agent and wait for a response.
-    Request {
-        #[arg(value_parser = parse_agent_id_arg)]
-        agent_id: String,
-        /// Timeout in seconds while waiting for a response.
-        #[arg(
-            long,
-            value_name = "SECONDS",
-            default_value_t = 30,
-            value_parser = clap::value_parser!(u64).range(1..)
-        )]
-        timeout: u64,
-        /// Text payload (sent as {"message":"<TEXT>"} on the wire).
-        message: String,
-    },
-    /// Send a fire-and-forget message to another agent.
-    Notify {
-        #[arg(value_parser = parse_agent_id_arg)]
-        agent_id: String,
-        /// Parse payload as JSON (default sends literal text). Payload is sent as {"data": <value>}.
-        #[arg(long)]
-        json: bool,
-        /// Payload data (sent as {"data":"<TEXT>"}, or {"data":<JSON>} with --json).
-        data: String,
-    },
-    /// List discovered and connected peers.
-    Peers {
-        /// Print machine-readable JSON.
-        #[arg(long)]
-        json: bool,
-    },
-    /// Show daemon status.
-    Status {
-        /// Print machine-readable JSON.
-        #[arg(long)]
-        json: bool,
-    },
-    /// Print this agent's identity.
-    Identity {
-        /// Print rich identity metadata as JSON.
-        #[arg(long)]
-        json: bool,
-        /// Override address used for URI output (`host:port` or `ip:port`).
-        #[arg(long, value_name = "ADDR")]
-        addr: Option<String>,
-    },
-    /// Enroll a peer from an `axon://` token.
-    Connect { token: String },
-    /// Print running daemon identity and metadata via IPC.
-    Whoami {
-        /// Print machine-readable JSON.
-        #[arg(long)]
-        json: bool,
-    },
-    /// Diagnose local AXON state and optionally apply safe repairs.
-    Doctor(doctor::DoctorArgs),
-    /// Read/write scalar config values.
-    Config(cli::config_cmd::ConfigArgs),
-    /// Print example interactions.
-    Examples,
-    /// Generate shell completions and man page (internal, for packaging).
-    #[cfg(feature = "generate-docs")]
-    #[command(hide = true)]
-    GenDocs {
-        /// Output directory (creates completions/ and man/ inside it).
-        #[arg(long, value_name = "DIR")]
-        out_dir: PathBuf,
-    },
+/// Replace alias arguments with agent IDs from `config.yaml`. An unknown
+/// alias is reported as a usage error (exit code 2), like a malformed ID.
+async fn resolve_agent_refs(command: &mut Commands, paths: &AxonPaths) -> Result<()> {
+    let refs = command.agent_refs_mut();
+    if refs.iter().all(|arg| canonicalize_agent_id(arg).is_some()) {
+        return Ok(());
+    }
+    let aliases = load_persisted_config(&paths.config).await?.aliases;
+    for arg in refs {
+        match cli::alias::resolve_agent_ref(arg, &aliases) {
+            Ok(agent_id) => *arg = agent_id,
+            Err(message) => Cli::command()
+                .error(clap::error::ErrorKind::ValueValidation, message)
+                .exit(),
+        }
+    }
+    Ok(())
 }
 
 pub(crate) async fn run(cli: Cli) -> Result<ExitCode> {
     let Cli {
         state_root,
-        verbose: _,
-        quiet: _,
-        command,
+        profile,
+        verbose,
+        quiet,
+        output,
+        mut command,
     } = cli;
-    let resolve_paths = || AxonPaths::discover_with_override(state_root.as_deref());
+    if output.is_some() {
+        let message = match command.output_schema() {
+            None => Some("--output is supported by `peers`, `status`, and `whoami`; use --json"),
+            Some((_, true)) => Some("--output cannot be combined with --json"),
+            Some((_, false)) => None,
+        };
+        if let Some(message) = message {
+            Cli::command()
+                .error(clap::error::ErrorKind::ArgumentConflict, message)
+                .exit();
+        }
+    }
+    let resolve_paths = || {
+        let paths = AxonPaths::discover_with_override(state_root.as_deref())?;
+        match &profile {
+            Some(name) => paths.with_profile(name),
+            None => Ok(paths),
+        }
+    };
+    if !command.agent_refs_mut().is_empty() {
+        resolve_agent_refs(&mut command, &resolve_paths()?).await?;
+    }
 
     match command {
-        Commands::Daemon { port, disable_mdns } => {
+        Commands::Daemon {
+            port,
+            self_test: true,
+            ..
+        } => {
+            let report = self_test::run(port).await?;
+            println!("{}", cli::format::render_self_test_human(&report));
+            if !report.ok {
+                return Ok(ExitCode::from(2));
+            }
+        }
+        Commands::Daemon {
+            port,
+            disable_mdns,
+            detach: true,
+            ..
+        } => {
+            let paths = resolve_paths()?;
+            let pid = cli::daemon_ctl::spawn_detached(
+                &paths,
+                &cli::daemon_ctl::DetachOptions {
+                    port,
+                    disable_mdns,
+                    verbose,
+                    quiet,
+                },
+            )
+            .await?;
+            println!("daemon started (pid {pid})");
+        }
+        Commands::Daemon {
+            port,
+            disable_mdns,
+            detach: false,
+            ..
+        } => {
             let paths = resolve_paths()?;
+            apply_logging_config(&paths).await;
             run_daemon(DaemonOptions {
                 port,
                 disable_mdns,
-                axon_root: Some(paths.root.clone()),
+                axon_root: Some(paths.base_root().to_path_buf()),
+                profile: paths.profile.clone(),
                 cancel: None,
+                handle_signals: true,
+                response_handler: None,
             })
             .await?;
         }
-        Commands::Request {
-            agent_id,
+        Commands::Stop { timeout } => {
+            let paths = resolve_paths()?;
+            match cli::daemon_ctl::stop(&paths, Duration::from_secs(timeout)).await? {
+                cli::daemon_ctl::StopOutcome::NotRunning => println!("daemon not running"),
+                cli::daemon_ctl::StopOutcome::Stopped(pid) => {
+                    println!("daemon stopped (pid {pid})")
+                }
+            }
+        }
+        Commands::Restart {
+            port,
+            disable_mdns,
             timeout,
-            message,
         } => {
             let paths = resolve_paths()?;
-            let payload = json!({ "message": message });
-            let response = cli::ipc_client::send_ipc(
-                &paths,
-                json!({
-                    "cmd": "send",
-                    "to": agent_id,
-                    "kind": "request",
-                    "timeout_secs": timeout,
-                    "payload": payload
-                }),
-            )
-            .await?;
-            print_json_value(&response)?;
-            return Ok(cli::ipc_client::daemon_reply_exit_code(
-                &response,
-                cli::ipc_client::ResponseMode::Request,
-            ));
-        }
-        Commands::Notify {
-            agent_id,
-            json,
-            data,
+            let opts = cli::daemon_ctl::DetachOptions {
+                port,
+                disable_mdns,
+                verbose,
+                quiet,
+            };
+            cli::daemon_ctl::restart(&paths, &opts, Duration::from_secs(timeout)).await?;
+        }
+        Commands::Request(args) => {
+            return cli::send_cmd::request(&resolve_paths()?, &args, quiet).await;
+        }
+        Commands::Notify(args) => {
+            return cli::send_cmd::notify(&resolve_paths()?, &args, quiet).await;
+        }
+        Commands::Subscribe { topics } => {
+            return cli::send_cmd::subscribe(&resolve_paths()?, &topics).await;
+        }
+        Commands::Publish(args) => {
+            return cli::send_cmd::publish(&resolve_paths()?, &args, quiet).await;
+        }
+        Commands::Cancel(args) => {
+            return cli::send_cmd::cancel(&resolve_paths()?, &args, quiet).await;
+        }
+        Commands::Peers {
+            command: Some(command),
+            ..
         } => {
             let paths = resolve_paths()?;
-            let parsed_data = cli::notify_payload::parse_notify_payload(&data, json)?;
-            let payload = json!({ "data": parsed_data });
-            let response = cli::ipc_client::send_ipc(
-                &paths,
-                json!({"cmd": "send", "to": agent_id, "kind": "message", "payload": payload}),
-            )
-            .await?;
-            print_json_value(&response)?;
-            return Ok(cli::ipc_client::daemon_reply_exit_code(
-                &response,
-                cli::ipc_client::ResponseMode::Generic,
-            ));
+            cli::peer_tags::run(&paths, &command).await?;
         }
-        Commands::Peers { json } => {
+        Commands::Peers {
+            command: None,
+            tag,
+            watch: true,
+            ..
+        } => {
             let paths = resolve_paths()?;
-            let response = cli::ipc_client::send_ipc(&paths, json!({"cmd": "peers"})).await?;
-            if json {
-                print_json_value(&response)?;
-            } else if let Some(rendered) = cli::format::render_peers_human(&response) {
-                println!("{rendered}");
-            } else {
-                print_json_value(&response)?;
-            }
-            return Ok(cli::ipc_client::daemon_reply_exit_code(
-                &response,
-                cli::ipc_client::ResponseMode::Generic,
-            ));
+            cli::peers_watch::run(&paths, tag).await?;
+        }
+        Commands::Peers {
+            command: None,
+            tag,
+            json,
+            watch: false,
+        } => {
+            return cli::query_cmd::peers(&resolve_paths()?, tag, json, output).await;
         }
         Commands::Status { json } => {
+            return cli::query_cmd::status(&resolve_paths()?, json, output).await;
+        }
+        Commands::Health { json } => {
+            return cli::query_cmd::health(&resolve_paths()?, json).await;
+        }
+        Commands::Bans { json } => {
+            return cli::query_cmd::bans(&resolve_paths()?, json).await;
+        }
+        Commands::Reload { json } => {
+            return cli::query_cmd::reload(&resolve_paths()?, json).await;
+        }
+        Commands::History(args) => {
+            return cli::query_cmd::history(&resolve_paths()?, &args).await;
+        }
+        Commands::Bench(args) => {
             let paths = resolve_paths()?;
-            let response = cli::ipc_client::send_ipc(&paths, json!({"cmd": "status"})).await?;
-            if json {
-                print_json_value(&response)?;
-            } else if let Some(rendered) = cli::format::render_status_human(&response) {
-                println!("{rendered}");
+            let report = cli::bench::run(&paths, &args).await?;
+            if args.json {
+                cli::ipc_client::print_json_value(&serde_json::to_value(&report)?)?;
             } else {
-                print_json_value(&response)?;
+                println!("{}", report.render_human());
+            }
+            if report.failed > 0 {
+                return Ok(ExitCode::from(2));
             }
-            return Ok(cli::ipc_client::daemon_reply_exit_code(
-                &response,
-                cli::ipc_client::ResponseMode::Generic,
-            ));
         }
-        Commands::Identity { json, addr } => {
+        Commands::Watch(args) => {
             let paths = resolve_paths()?;
-            let identity = Identity::load_or_generate(&paths)?;
-            let config = Config::load(&paths.config).await?;
-            let port = config.effective_port(None);
-            let addr =
-                select_identity_addr(addr.as_deref(), config.advertise_addr.as_deref(), port)
-                    .context("failed to determine identity advertise address")?;
-            let uri = peer_token::encode(identity.public_key_base64(), &addr)
-                .context("failed to construct peer URI")?;
-            if json {
-                let (addr_host, addr_port) = split_addr_port(&addr)?;
-                let rendered = cli::identity_output::render_identity_json(
-                    identity.agent_id(),
-                    identity.public_key_base64(),
-                    &addr_host,
-                    addr_port,
-                    &uri,
-                )?;
-                println!("{rendered}");
-            } else {
-                println!("{}", cli::identity_output::render_identity_human(&uri));
-            }
+            cli::watch::watch(&paths, &args).await?;
         }
-        Commands::Connect { token } => {
+        Commands::Record(args) => {
             let paths = resolve_paths()?;
-            let identity = Identity::load_or_generate(&paths)?;
-            let decoded = peer_token::decode(&token).context("failed to parse peer token")?;
-
-            if decoded.agent_id.as_str() == identity.agent_id() {
-                anyhow::bail!("refusing to enroll self ({})", decoded.agent_id);
-            }
-
-            let mut persisted = load_persisted_config(&paths.config).await?;
-            if persisted
-                .peers
-                .iter()
-                .any(|peer| peer.agent_id.as_str() == decoded.agent_id.as_str())
-            {
-                anyhow::bail!(
-                    "peer {} already exists in {}",
-                    decoded.agent_id,
-                    paths.config.display()
-                );
-            }
-
-            let parsed_addr =
-                PeerAddr::parse(&decoded.addr).context("peer token has invalid addr")?;
-            persisted.peers.push(PersistedStaticPeerConfig {
-                agent_id: decoded.agent_id.clone(),
-                addr: parsed_addr,
-                pubkey: decoded.pubkey.clone(),
-            });
-            save_persisted_config(&paths.config, &persisted).await?;
-
-            if paths.socket.exists() {
-                let hotload = cli::ipc_client::send_ipc(
-                    &paths,
-                    json!({"cmd": "add_peer", "pubkey": decoded.pubkey, "addr": decoded.addr}),
-                )
-                .await;
-                match hotload {
-                    Ok(response) if response.get("ok") == Some(&json!(true)) => {}
-                    Ok(response) => {
-                        let rendered = serde_json::to_string_pretty(&response)
-                            .unwrap_or_else(|_| response.to_string());
-                        anyhow::bail!(
-                            "peer saved to {} but daemon hot-load failed.\nDaemon response: {}",
-                            paths.config.display(),
-                            rendered
-                        );
-                    }
-                    Err(err) => {
-                        anyhow::bail!(
-                            "peer saved to {} but daemon hot-load failed: {}",
-                            paths.config.display(),
-                            err
-                        );
-                    }
-                }
-            }
-
-            println!("✓ Added peer {} ({})", decoded.agent_id, decoded.addr);
+            cli::record::record(&paths, &args).await?;
         }
-        Commands::Whoami { json } => {
+        Commands::Replay(args) => {
             let paths = resolve_paths()?;
-            let response = cli::ipc_client::send_ipc(&paths, json!({"cmd": "whoami"})).await?;
-            if json {
-                print_json_value(&response)?;
-            } else if let Some(rendered) = cli::format::render_whoami_human(&response) {
-                println!("{rendered}");
-            } else {
-                print_json_value(&response)?;
+            if !cli::record::replay(&paths, &args).await? {
+                return Ok(ExitCode::from(2));
             }
-            return Ok(cli::ipc_client::daemon_reply_exit_code(
-                &response,
-                cli::ipc_client::ResponseMode::Generic,
-            ));
+        }
+        Commands::Shell => {
+            let paths = resolve_paths()?;
+            cli::shell::run(&paths).await?;
+        }
+        Commands::Mcp => {
+            let paths = resolve_paths()?;
+            cli::mcp::run(&paths).await?;
+        }
+        Commands::Top(args) => {
+            let paths = resolve_paths()?;
+            cli::top::run(&paths, &args).await?;
+        }
+        Commands::Identity(args) => {
+            let paths = resolve_paths()?;
+            cli::identity_cmd::run(&paths, &args).await?;
+        }
+        Commands::Connect(args) => {
+            let paths = resolve_paths()?;
+            cli::connect_cmd::run(&paths, &args).await?;
+        }
+        Commands::Revoke(args) => {
+            let paths = resolve_paths()?;
+            cli::revoke_cmd::run(&paths, &args).await?;
+        }
+        Commands::Block(args) => {
+            let paths = resolve_paths()?;
+            cli::block_cmd::block(&paths, &args).await?;
+        }
+        Commands::Unblock(args) => {
+            let paths = resolve_paths()?;
+            return cli::block_cmd::unblock(&paths, &args).await;
+        }
+        Commands::Blocked { json } => {
+            let paths = resolve_paths()?;
+            return cli::block_cmd::list(&paths, json).await;
+        }
+        Commands::Pins(args) => {
+            let paths = resolve_paths()?;
+            return cli::pins_cmd::run(&paths, &args).await;
+        }
+        Commands::Audit(args) => {
+            let paths = resolve_paths()?;
+            return cli::audit_cmd::run(&paths, &args);
+        }
+        Commands::Whois { agent_id, json } => {
+            return cli::query_cmd::whois(&resolve_paths()?, &agent_id, json).await;
+        }
+        Commands::Whoami { json } => {
+            return cli::query_cmd::whoami(&resolve_paths()?, json, output).await;
         }
         Commands::Doctor(args) => {
             let paths = resolve_paths()?;
@@ -335,10 +281,22 @@ pub(crate) async fn run(cli: Cli) -> Result<ExitCode> {
             }
             return Ok(ExitCode::from(2));
         }
+        Commands::Alias(args) => {
+            let paths = resolve_paths()?;
+            return cli::alias::run(&paths, args).await;
+        }
         Commands::Config(args) => {
             let paths = resolve_paths()?;
             return cli::config_cmd::run(&paths, args).await;
         }
+        Commands::Reset(args) => {
+            let paths = resolve_paths()?;
+            cli::reset::run(&paths, &args)?;
+        }
+        Commands::Service(args) => {
+            let paths = resolve_paths()?;
+            return cli::service_cmd::run(&paths, args).await;
+        }
         Commands::Examples => {
             examples::print_annotated_examples();
         }
@@ -351,11 +309,6 @@ pub(crate) async fn run(cli: Cli) -> Result<ExitCode> {
     Ok(ExitCode::SUCCESS)
 }
 
-fn print_json_value(value: &serde_json::Value) -> Result<()> {
-    println!("{}", cli::ipc_client::render_json(value)?);
-    Ok(())
-}
-
 #[cfg(feature = "generate-docs")]
 fn generate_docs(out_dir: &std::path::Path) -> Result<()> {
     use clap_complete::{Shell, generate_to};
@@ -393,7 +346,9 @@ fn generate_docs(out_dir: &std::path::Path) -> Result<()> {
     Ok(())
 }
 
-pub(crate) fn init_tracing(verbose: u8, quiet: bool) {
+/// Logs go to stderr when stdout carries a protocol (`axon mcp`) or a JSON
+/// report (`axon doctor --json`).
+pub(crate) fn init_tracing(verbose: u8, quiet: bool, to_stderr: bool) {
     let level = if quiet {
         "warn"
     } else {
@@ -405,15 +360,30 @@ pub(crate) fn init_tracing(verbose: u8, quiet: bool) {
     };
     let default = format!("{level},mdns_sd=warn");
     let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(default));
-    let _ = tracing_subscriber::fmt().with_env_filter(filter).try_init();
+    logging::init(filter, to_stderr);
+}
+
+/// Route daemon logs to the configured `logging.target`; on failure, keep
+/// logging to stdout so the daemon still starts.
+async fn apply_logging_config(paths: &AxonPaths) {
+    let result = async {
+        let config = Config::load_for(paths).await?;
+        paths.ensure_root_exists()?;
+        let identity = Identity::load_or_generate(paths)?;
+        logging::apply(&config.logging, &paths.root, identity.agent_id())
+    }
+    .await;
+    if let Err(err) = result {
+        tracing::warn!(error = %format!("{err:#}"), "keeping log output on stdout");
+    }
 }
 
-fn parse_agent_id_arg(input: &str) -> std::result::Result<String, String> {
+pub(crate) fn parse_agent_id_arg(input: &str) -> std::result::Result<String, String> {
     canonicalize_agent_id(input)
         .ok_or_else(|| format!("invalid agent_id '{input}'; expected format ed25519.<32 hex>"))
 }
 
-fn canonicalize_agent_id(input: &str) -> Option<String> {
+pub(crate) fn canonicalize_agent_id(input: &str) -> Option<String> {
     let (prefix, hex) = input.split_once('.')?;
     if !prefix.eq_ignore_ascii_case("ed25519") {
         return None;
@@ -424,47 +394,6 @@ fn canonicalize_agent_id(input: &str) -> Option<String> {
     Some(format!("ed25519.{}", hex.to_ascii_lowercase()))
 }
 
-fn select_identity_addr(
-    addr_override: Option<&str>,
-    advertise_addr: Option<&str>,
-    port: u16,
-) -> Result<String> {
-    if let Some(addr) = addr_override {
-        return normalize_addr(addr);
-    }
-    if let Some(addr) = advertise_addr {
-        return normalize_addr(addr);
-    }
-
-    normalize_addr(&format!("{}:{port}", default_identity_host()))
-}
-
-fn normalize_addr(input: &str) -> Result<String> {
-    let parsed = PeerAddr::parse(input)?;
-    Ok(parsed.to_string())
-}
-
-fn split_addr_port(addr: &str) -> Result<(String, u16)> {
-    let parsed = PeerAddr::parse(addr)?;
-    match parsed {
-        PeerAddr::Socket(socket) => Ok((socket.ip().to_string(), socket.port())),
-        PeerAddr::Host { host, port } => Ok((host, port)),
-    }
-}
-
-fn default_identity_host() -> String {
-    for key in ["HOSTNAME", "COMPUTERNAME"] {
-        if let Ok(value) = std::env::var(key) {
-            let value = value.trim();
-            if !value.is_empty() && !value.contains(':') {
-                return value.to_string();
-            }
-        }
-    }
-
-    "localhost".to_string()
-}
-
 #[cfg(test)]
 #[path = "run_tests.rs"]
 mod tests;
@@ -1,5 +1,7 @@
 use super::*;
+use clap::Parser;
 use clap::error::ErrorKind;
+use std::path::PathBuf;
 
 #[test]
 fn parse_agent_id_arg_normalizes_case() {
@@ -41,6 +43,13 @@ fn cli_quiet_and_verbose_conflict() {
     assert_eq!(err.kind(), ErrorKind::ArgumentConflict);
 }
 
+#[test]
+fn daemon_self_test_conflicts_with_detach() {
+    let err = Cli::try_parse_from(["axon", "daemon", "--self-test", "--detach"])
+        .expect_err("--self-test and --detach should conflict");
+    assert_eq!(err.kind(), ErrorKind::ArgumentConflict);
+}
+
 #[test]
 fn doctor_rekey_requires_fix_flag() {
     let err = Cli::try_parse_from(["axon", "doctor", "--rekey"])
@@ -48,25 +57,57 @@ fn doctor_rekey_requires_fix_flag() {
     assert_eq!(err.kind(), ErrorKind::MissingRequiredArgument);
 }
 
+#[test]
+fn logs_leave_stdout_for_mcp_and_doctor_json() {
+    let parse = |args: &[&str]| Cli::try_parse_from(args).expect("parse");
+    assert!(parse(&["axon", "mcp"]).stdout_is_protocol());
+    assert!(parse(&["axon", "doctor", "--json"]).stdout_is_protocol());
+    assert!(!parse(&["axon", "doctor"]).stdout_is_protocol());
+    assert!(!parse(&["axon", "status"]).stdout_is_protocol());
+}
+
 #[test]
 fn identity_flags_parse_json_and_addr() {
     let cli = Cli::try_parse_from(["axon", "identity", "--json", "--addr", "10.0.0.7:7100"])
         .expect("parse identity flags");
     match cli.command {
-        Commands::Identity { json, addr } => {
-            assert!(json);
-            assert_eq!(addr.as_deref(), Some("10.0.0.7:7100"));
+        Commands::Identity(args) => {
+            assert!(args.json);
+            assert_eq!(args.addr.as_deref(), Some("10.0.0.7:7100"));
+            assert!(args.command.is_none());
         }
         _ => panic!("expected identity command"),
     }
 }
 
+#[test]
+fn identity_export_parses_out_and_encrypt() {
+    let cli = Cli::try_parse_from(["axon", "identity", "export", "--out", "b.axon", "--encrypt"])
+        .expect("parse identity export");
+    match cli.command {
+        Commands::Identity(cli::identity_cmd::IdentityArgs {
+            command: Some(cli::identity_cmd::IdentityCommand::Export { out, encrypt }),
+            ..
+        }) => {
+            assert_eq!(out, std::path::PathBuf::from("b.axon"));
+            assert!(encrypt);
+        }
+        _ => panic!("expected identity export command"),
+    }
+}
+
 #[test]
 fn connect_command_parses_token() {
     let cli = Cli::try_parse_from(["axon", "connect", "axon://abc@127.0.0.1:7100"])
         .expect("parse connect");
     match cli.command {
-        Commands::Connect { token } => assert_eq!(token, "axon://abc@127.0.0.1:7100"),
+        Commands::Connect(cli::connect_cmd::ConnectArgs {
+            token,
+            allow_unsigned,
+        }) => {
+            assert_eq!(token, "axon://abc@127.0.0.1:7100");
+            assert!(!allow_unsigned);
+        }
         _ => panic!("expected connect command"),
     }
 }
@@ -78,52 +119,174 @@ fn request_command_parses_timeout() {
         "request",
         "--timeout",
         "9",
+        "--thread",
+        "t-1",
+        "--header",
+        "trace_id=abc",
         "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
         "hello",
     ])
     .expect("parse request");
 
     match cli.command {
-        Commands::Request {
+        Commands::Request(cli::send_cmd::RequestArgs {
             agent_id,
             timeout,
+            idempotency_key,
+            thread,
+            headers,
             message,
-        } => {
+            payload,
+        }) => {
             assert_eq!(agent_id, "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa");
             assert_eq!(timeout, 9);
-            assert_eq!(message, "hello");
+            assert_eq!(idempotency_key, None);
+            assert_eq!(thread.as_deref(), Some("t-1"));
+            assert_eq!(headers, vec![("trace_id".to_string(), "abc".to_string())]);
+            assert_eq!(message.as_deref(), Some("hello"));
+            assert!(payload.payload.is_none() && payload.payload_file.is_none());
         }
         _ => panic!("expected request command"),
     }
 }
 
 #[test]
-fn send_subcommand_is_not_accepted() {
-    let err = Cli::try_parse_from([
+fn request_and_notify_take_payload_flags_instead_of_text() {
+    let cli = Cli::try_parse_from([
         "axon",
-        "send",
+        "request",
         "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
-        "hello",
+        "--payload-file",
+        "body.json",
     ])
-    .expect_err("send should be unknown");
-    assert_eq!(err.kind(), ErrorKind::InvalidSubcommand);
+    .expect("parse request");
+    match cli.command {
+        Commands::Request(args) => {
+            let cli::send_cmd::RequestArgs {
+                message, payload, ..
+            } = args;
+            assert_eq!(message, None);
+            assert_eq!(payload.payload_file, Some(PathBuf::from("body.json")));
+        }
+        _ => panic!("expected request command"),
+    }
+
+    let cli = Cli::try_parse_from([
+        "axon",
+        "notify",
+        "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+        "--payload",
+        "-",
+    ])
+    .expect("parse notify");
+    assert!(matches!(
+        cli.command,
+        Commands::Notify(args) if args.data.is_none() && args.payload.payload.as_deref() == Some("-")
+    ));
+
+    for argv in [
+        vec![
+            "axon",
+            "request",
+            "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+        ],
+        vec![
+            "axon",
+            "request",
+            "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+            "hi",
+            "--payload",
+            "{}",
+        ],
+        vec![
+            "axon",
+            "notify",
+            "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+            "--json",
+            "--payload",
+            "{}",
+        ],
+        vec![
+            "axon",
+            "notify",
+            "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+            "--payload",
+            "-",
+            "--payload-file",
+            "x",
+        ],
+    ] {
+        assert!(
+            Cli::try_parse_from(&argv).is_err(),
+            "{argv:?} should not parse"
+        );
+    }
 }
 
 #[test]
-fn select_identity_addr_prefers_override_then_config() {
-    let override_addr =
-        select_identity_addr(Some("10.0.0.1:7100"), Some("ignored:7200"), 7300).expect("override");
-    assert_eq!(override_addr, "10.0.0.1:7100");
-
-    let config_addr =
-        select_identity_addr(None, Some("alice.tailnet:7100"), 7300).expect("config advertise");
-    assert_eq!(config_addr, "alice.tailnet:7100");
+fn notify_stdin_replaces_data_and_excludes_other_payload_sources() {
+    let cli = Cli::try_parse_from([
+        "axon",
+        "notify",
+        "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+        "--stdin",
+    ])
+    .expect("parse notify --stdin");
+    assert!(matches!(
+        cli.command,
+        Commands::Notify(cli::send_cmd::NotifyArgs {
+            data: None,
+            stdin: true,
+            ..
+        })
+    ));
+
+    for extra in [
+        vec!["hello"],
+        vec!["--json"],
+        vec!["--payload", "-"],
+        vec!["--payload-file", "x"],
+    ] {
+        let mut argv = vec![
+            "axon",
+            "notify",
+            "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+            "--stdin",
+        ];
+        argv.extend(extra);
+        assert!(
+            Cli::try_parse_from(&argv).is_err(),
+            "{argv:?} should not parse"
+        );
+    }
 }
 
 #[test]
-fn select_identity_addr_falls_back_to_local_host_without_network_probe() {
-    let addr = select_identity_addr(None, None, 7300).expect("fallback addr");
-    let (host, port) = split_addr_port(&addr).expect("split");
-    assert!(!host.is_empty());
-    assert_eq!(port, 7300);
+fn request_command_parses_idempotency_key() {
+    let cli = Cli::try_parse_from([
+        "axon",
+        "request",
+        "--idempotency-key",
+        "job-42",
+        "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+        "hello",
+    ])
+    .expect("parse request");
+
+    match cli.command {
+        Commands::Request(args) => assert_eq!(args.idempotency_key.as_deref(), Some("job-42")),
+        _ => panic!("expected request command"),
+    }
+}
+
+#[test]
+fn send_subcommand_is_not_accepted() {
+    let err = Cli::try_parse_from([
+        "axon",
+        "send",
+        "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+        "hello",
+    ])
+    .expect_err("send should be unknown");
+    assert_eq!(err.kind(), ErrorKind::InvalidSubcommand);
 }
@@ -0,0 +1,473 @@
+//! `axon daemon --self-test`: end-to-end smoke test against a throwaway
+//! daemon and an in-process probe peer, both on loopback.
+
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result, anyhow, bail, ensure};
+use serde::Serialize;
+use serde_json::{Value, json};
+use tokio::io::{AsyncBufReadExt, BufReader, Lines};
+use tokio::net::UnixStream;
+use tokio::task::JoinHandle;
+use tokio::time::{Instant, sleep, timeout};
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+use axon::config::AxonPaths;
+use axon::daemon::{DaemonOptions, run_daemon};
+use axon::identity::Identity;
+use axon::message::{Envelope, MessageKind};
+use axon::peer_table::PeerTable;
+use axon::transport::{QuicTransport, ResponseHandlerFn};
+
+use super::cli::ipc_client::send_ipc;
+
+const STEP_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SelfTestStep {
+    pub name: &'static str,
+    pub ok: bool,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SelfTestReport {
+    pub ok: bool,
+    pub state_root: String,
+    pub steps: Vec<SelfTestStep>,
+}
+
+impl SelfTestReport {
+    /// Record `result` as step `name`. Returns the value on success.
+    fn step<T>(&mut self, name: &'static str, result: Result<(T, String)>) -> Option<T> {
+        match result {
+            Ok((value, message)) => {
+                self.steps.push(SelfTestStep {
+                    name,
+                    ok: true,
+                    message,
+                });
+                Some(value)
+            }
+            Err(err) => {
+                self.ok = false;
+                self.steps.push(SelfTestStep {
+                    name,
+                    ok: false,
+                    message: format!("{err:#}"),
+                });
+                None
+            }
+        }
+    }
+}
+
+/// Daemon under test, running in a task on its own temporary state root.
+struct TestDaemon {
+    paths: AxonPaths,
+    port: u16,
+    cancel: CancellationToken,
+    handle: JoinHandle<Result<()>>,
+}
+
+/// Ephemeral second identity that answers and sends over loopback QUIC.
+struct Probe {
+    identity: Identity,
+    transport: QuicTransport,
+    peers: PeerTable,
+}
+
+/// Run the self-test. The temporary state root is removed afterwards.
+pub async fn run(port: Option<u16>) -> Result<SelfTestReport> {
+    let root = std::env::temp_dir().join(format!(
+        "axon-self-test-{}",
+        &Uuid::new_v4().simple().to_string()[..8]
+    ));
+    let mut report = SelfTestReport {
+        ok: true,
+        state_root: root.display().to_string(),
+        steps: Vec::new(),
+    };
+
+    let daemon = report.step("daemon_start", start_daemon(root.clone(), port).await);
+    if let Some(daemon) = daemon {
+        if exercise(&mut report, &daemon).await {
+            report.step("ipc_shutdown", shutdown(daemon).await);
+        } else {
+            daemon.cancel.cancel();
+            let _ = timeout(STEP_TIMEOUT, daemon.handle).await;
+        }
+    }
+
+    if let Err(err) = tokio::fs::remove_dir_all(&root).await
+        && err.kind() != std::io::ErrorKind::NotFound
+    {
+        tracing::warn!(path = %root.display(), error = %err, "failed to remove self-test state root");
+    }
+    Ok(report)
+}
+
+/// Steps between startup and shutdown. Stops at the first failure, since
+/// later steps depend on earlier ones; returns `true` if all passed.
+async fn exercise(report: &mut SelfTestReport, daemon: &TestDaemon) -> bool {
+    let Some(daemon_id) = report.step("ipc_whoami", whoami(daemon).await) else {
+        return false;
+    };
+    if report.step("ipc_status", status(daemon).await).is_none() {
+        return false;
+    }
+    let Some(probe) = report.step("probe_start", start_probe(daemon, &daemon_id).await) else {
+        return false;
+    };
+    let Some(mut events) = report.step("ipc_subscribe", subscribe(daemon).await) else {
+        return false;
+    };
+
+    report
+        .step("ipc_add_peer", add_peer(daemon, &probe).await)
+        .is_some()
+        && report
+            .step(
+                "request_response",
+                send_request(daemon, &probe, "response", "response").await,
+            )
+            .is_some()
+        && report
+            .step(
+                "request_error",
+                send_request(daemon, &probe, "error", "error").await,
+            )
+            .is_some()
+        && report
+            .step("message_outbound", send_message(daemon, &probe).await)
+            .is_some()
+        && report
+            .step(
+                "message_inbound",
+                receive_message(&probe, &daemon_id, &mut events).await,
+            )
+            .is_some()
+        && report
+            .step("request_inbound", receive_request(&probe, &daemon_id).await)
+            .is_some()
+        && report
+            .step("ipc_peers", peers(daemon, &probe).await)
+            .is_some()
+}
+
+async fn start_daemon(root: PathBuf, port: Option<u16>) -> Result<(TestDaemon, String)> {
+    let port = match port {
+        Some(port) => port,
+        None => std::net::UdpSocket::bind("127.0.0.1:0")
+            .and_then(|socket| socket.local_addr())
+            .context("failed to pick a free UDP port")?
+            .port(),
+    };
+    let paths = AxonPaths::from_root(root.clone());
+    let cancel = CancellationToken::new();
+    let handle = tokio::spawn(run_daemon(DaemonOptions {
+        port: Some(port),
+        disable_mdns: true,
+        axon_root: Some(root),
+        profile: None,
+        cancel: Some(cancel.clone()),
+        handle_signals: true,
+        response_handler: None,
+    }));
+
+    let deadline = Instant::now() + STEP_TIMEOUT;
+    while UnixStream::connect(&paths.socket).await.is_err() {
+        if handle.is_finished() {
+            return match handle.await {
+                Ok(Err(err)) => Err(err.context("daemon exited during startup")),
+                _ => Err(anyhow!("daemon exited during startup")),
+            };
+        }
+        ensure!(
+            Instant::now() < deadline,
+            "IPC socket did not appear within {}s",
+            STEP_TIMEOUT.as_secs()
+        );
+        sleep(Duration::from_millis(50)).await;
+    }
+    let message = format!("listening on UDP {port}, IPC {}", paths.socket.display());
+    Ok((
+        TestDaemon {
+            paths,
+            port,
+            cancel,
+            handle,
+        },
+        message,
+    ))
+}
+
+/// Send one IPC command and require `ok: true`.
+async fn command(daemon: &TestDaemon, command: Value) -> Result<Value> {
+    let reply = timeout(STEP_TIMEOUT, send_ipc(&daemon.paths, command))
+        .await
+        .context("IPC command timed out")??;
+    if reply.get("ok") != Some(&json!(true)) {
+        bail!("daemon replied {reply}");
+    }
+    Ok(reply)
+}
+
+async fn whoami(daemon: &TestDaemon) -> Result<(String, String)> {
+    let reply = command(daemon, json!({"cmd": "whoami"})).await?;
+    let agent_id = reply["agent_id"]
+        .as_str()
+        .context("whoami reply has no agent_id")?
+        .to_string();
+    let message = format!("agent {agent_id}");
+    Ok((agent_id, message))
+}
+
+async fn status(daemon: &TestDaemon) -> Result<((), String)> {
+    let reply = command(daemon, json!({"cmd": "status"})).await?;
+    ensure!(
+        reply["uptime_secs"].is_u64(),
+        "status reply has no uptime_secs"
+    );
+    Ok(((), "status reply well-formed".to_string()))
+}
+
+async fn start_probe(daemon: &TestDaemon, daemon_id: &str) -> Result<(Probe, String)> {
+    let whoami = command(daemon, json!({"cmd": "whoami"})).await?;
+    let daemon_pubkey = whoami["public_key"]
+        .as_str()
+        .context("whoami reply has no public_key")?
+        .to_string();
+
+    let identity =
+        Identity::load_or_generate(&AxonPaths::from_root(daemon.paths.root.join("probe")))?;
+    let peers = PeerTable::new();
+    peers
+        .upsert_discovered(
+            daemon_id.into(),
+            ([127, 0, 0, 1], daemon.port).into(),
+            daemon_pubkey,
+        )
+        .await;
+    let transport = QuicTransport::bind_cancellable(
+        "127.0.0.1:0".parse()?,
+        &identity,
+        CancellationToken::new(),
+        4,
+        Duration::from_secs(15),
+        Duration::from_secs(60),
+        Some(probe_handler()),
+        Duration::from_secs(10),
+        peers.pubkey_map(),
+    )
+    .await?;
+    let message = format!(
+        "probe {} on {}",
+        identity.agent_id(),
+        transport.local_addr()?
+    );
+    Ok((
+        Probe {
+            identity,
+            transport,
+            peers,
+        },
+        message,
+    ))
+}
+
+/// Answer `{"probe": "response"}` with a `response` and anything else with
+/// an `error`, so the daemon sees both reply kinds.
+fn probe_handler() -> ResponseHandlerFn {
+    Arc::new(|request| {
+        Box::pin(async move {
+            let from = request.to.as_ref()?.to_string();
+            let wants_response = request
+                .payload_value()
+                .ok()
+                .is_some_and(|payload| payload["probe"] == "response");
+            Some(if wants_response {
+                Envelope::response_to(
+                    &request,
+                    from,
+                    MessageKind::Response,
+                    json!({"probe": "ok"}),
+                )
+            } else {
+                Envelope::response_to(
+                    &request,
+                    from,
+                    MessageKind::Error,
+                    json!({"code": "self_test", "message": "expected error", "retryable": false}),
+                )
+            })
+        })
+    })
+}
+
+async fn subscribe(daemon: &TestDaemon) -> Result<(Lines<BufReader<UnixStream>>, String)> {
+    let stream = UnixStream::connect(&daemon.paths.socket)
+        .await
+        .context("failed to open event connection")?;
+    Ok((
+        BufReader::new(stream).lines(),
+        "event connection open".to_string(),
+    ))
+}
+
+async fn add_peer(daemon: &TestDaemon, probe: &Probe) -> Result<((), String)> {
+    let reply = command(
+        daemon,
+        json!({
+            "cmd": "add_peer",
+            "pubkey": probe.identity.public_key_base64(),
+            "addr": probe.transport.local_addr()?.to_string(),
+        }),
+    )
+    .await?;
+    ensure!(
+        reply["agent_id"] == probe.identity.agent_id(),
+        "add_peer returned {}",
+        reply["agent_id"]
+    );
+    Ok(((), "probe enrolled".to_string()))
+}
+
+async fn send_request(
+    daemon: &TestDaemon,
+    probe: &Probe,
+    probe_kind: &str,
+    expected_kind: &str,
+) -> Result<((), String)> {
+    let reply = command(
+        daemon,
+        json!({
+            "cmd": "send",
+            "to": probe.identity.agent_id(),
+            "kind": "request",
+            "timeout_secs": STEP_TIMEOUT.as_secs(),
+            "payload": {"probe": probe_kind},
+        }),
+    )
+    .await?;
+    let kind = &reply["response"]["kind"];
+    ensure!(
+        kind == expected_kind,
+        "expected {expected_kind} reply, got {kind}"
+    );
+    Ok(((), format!("request answered with {expected_kind}")))
+}
+
+async fn send_message(daemon: &TestDaemon, probe: &Probe) -> Result<((), String)> {
+    let mut inbound = probe.transport.subscribe_inbound();
+    let reply = command(
+        daemon,
+        json!({
+            "cmd": "send",
+            "to": probe.identity.agent_id(),
+            "kind": "message",
+            "payload": {"probe": "message"},
+        }),
+    )
+    .await?;
+    let msg_id = reply["msg_id"].as_str().unwrap_or_default().to_string();
+    wait_for(async {
+        loop {
+            let envelope = inbound.recv().await?;
+            if envelope.kind == MessageKind::Message && envelope.id.to_string() == msg_id {
+                return Ok(());
+            }
+        }
+    })
+    .await?;
+    Ok(((), "probe received message".to_string()))
+}
+
+async fn receive_message(
+    probe: &Probe,
+    daemon_id: &str,
+    events: &mut Lines<BufReader<UnixStream>>,
+) -> Result<((), String)> {
+    let daemon = probe
+        .peers
+        .get(daemon_id)
+        .await
+        .context("daemon missing from probe peer table")?;
+    let envelope = Envelope::new(
+        probe.identity.agent_id(),
+        daemon_id,
+        MessageKind::Message,
+        json!({"probe": "inbound"}),
+    );
+    let id = envelope.id.to_string();
+    probe.transport.send(&daemon, envelope).await?;
+    wait_for(async {
+        while let Some(line) = events.next_line().await? {
+            let event: Value = serde_json::from_str(&line)?;
+            if event["event"] == "inbound" && event["envelope"]["id"] == id.as_str() {
+                return Ok(());
+            }
+        }
+        bail!("daemon closed the event connection")
+    })
+    .await?;
+    Ok(((), "inbound event delivered over IPC".to_string()))
+}
+
+async fn receive_request(probe: &Probe, daemon_id: &str) -> Result<((), String)> {
+    let daemon = probe
+        .peers
+        .get(daemon_id)
+        .await
+        .context("daemon missing from probe peer table")?;
+    let request = Envelope::new(
+        probe.identity.agent_id(),
+        daemon_id,
+        MessageKind::Request,
+        json!({"probe": "request"}),
+    );
+    let reply = probe
+        .transport
+        .send(&daemon, request)
+        .await?
+        .context("daemon sent no reply")?;
+    ensure!(
+        reply.kind.is_response(),
+        "daemon replied with {}",
+        reply.kind
+    );
+    Ok(((), format!("daemon answered request with {}", reply.kind)))
+}
+
+async fn peers(daemon: &TestDaemon, probe: &Probe) -> Result<((), String)> {
+    let reply = command(daemon, json!({"cmd": "peers"})).await?;
+    let listed = reply["peers"].as_array().is_some_and(|peers| {
+        peers.iter().any(|peer| {
+            peer["agent_id"] == probe.identity.agent_id() && peer["status"] == "connected"
+        })
+    });
+    ensure!(listed, "probe not listed as connected: {}", reply["peers"]);
+    Ok(((), "probe listed as connected".to_string()))
+}
+
+async fn shutdown(daemon: TestDaemon) -> Result<((), String)> {
+    command(&daemon, json!({"cmd": "shutdown"})).await?;
+    wait_for(async { daemon.handle.await.context("daemon task panicked")? })
+        .await
+        .context("daemon did not exit cleanly")?;
+    ensure!(
+        !daemon.paths.socket.exists(),
+        "IPC socket left behind after shutdown"
+    );
+    Ok(((), "daemon exited cleanly".to_string()))
+}
+
+async fn wait_for<T>(fut: impl Future<Output = Result<T>>) -> Result<T> {
+    timeout(STEP_TIMEOUT, fut)
+        .await
+        .with_context(|| format!("timed out after {}s", STEP_TIMEOUT.as_secs()))?
+}
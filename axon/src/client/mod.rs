@@ -0,0 +1,417 @@
+//! Async client for the daemon's IPC socket.
+//!
+//! [`IpcClient`] speaks the line-delimited JSON protocol from `spec/IPC.md`
+//! so Rust tools need not reimplement it. Commands may be issued
+//! concurrently: while one is in flight, the client tags the others with a
+//! `req_id` of its own unless the caller set one, and matches replies on
+//! the echoed value. Events (`inbound`, `pair_request`, ...) go to every
+//! [`IpcEvents`] stream.
+//!
+//! There is no handshake: the daemon authorizes a connection by the peer
+//! UID of the socket, so connecting is all the setup there is.
+//!
+//! ```no_run
+//! # async fn demo() -> anyhow::Result<()> {
+//! use axon::client::IpcClient;
+//! use axon::config::AxonPaths;
+//! use serde_json::json;
+//!
+//! let client = IpcClient::connect(&AxonPaths::discover()?.socket).await?;
+//! let mut events = client.events();
+//! let status = client.command(json!({"cmd": "status"})).await?;
+//! println!("{} peers connected", status["peers_connected"]);
+//! while let Some(event) = events.next().await {
+//!     println!("{event}");
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context as TaskContext, Poll, ready};
+use std::time::Duration;
+
+use anyhow::{Context, Result, anyhow, bail};
+use serde_json::Value;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines};
+use tokio::net::UnixStream;
+use tokio::net::unix::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
+
+use crate::ipc::MAX_IPC_LINE_LENGTH;
+use crate::message::Envelope;
+
+/// Events buffered per [`IpcEvents`] stream. A stream that falls further
+/// behind is dropped, as the daemon drops lagging IPC clients.
+pub const EVENT_BUFFER: usize = 1024;
+
+/// Prefix of the `req_id` the client assigns to commands without one.
+const INTERNAL_REQ_ID_PREFIX: &str = "axon-client-";
+
+const RECONNECT_INITIAL_BACKOFF: Duration = Duration::from_millis(100);
+const RECONNECT_MAX_BACKOFF: Duration = Duration::from_secs(5);
+
+/// Connection to the daemon's IPC socket. Dropping it closes the
+/// connection and ends every [`IpcEvents`] stream.
+pub struct IpcClient {
+    shared: Arc<Shared>,
+    reader: JoinHandle<()>,
+}
+
+impl std::fmt::Debug for IpcClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IpcClient")
+            .field("socket", &self.shared.socket)
+            .finish_non_exhaustive()
+    }
+}
+
+struct Shared {
+    /// Socket to reconnect to; `None` when reconnection is off.
+    socket: Option<PathBuf>,
+    writer: tokio::sync::Mutex<Option<OwnedWriteHalf>>,
+    state: Mutex<State>,
+    next_req_id: AtomicU64,
+}
+
+#[derive(Default)]
+struct State {
+    /// Commands awaiting a reply, oldest first.
+    pending: VecDeque<(String, oneshot::Sender<Value>)>,
+    subscribers: Vec<mpsc::Sender<Value>>,
+    /// The connection is gone for good; events streams have ended.
+    closed: bool,
+}
+
+impl IpcClient {
+    /// Connect to the socket at `socket`. The client stays disconnected once
+    /// the daemon closes the connection.
+    pub async fn connect(socket: impl AsRef<Path>) -> Result<Self> {
+        let stream = open(socket.as_ref()).await?;
+        Ok(Self::spawn(stream, None))
+    }
+
+    /// Connect to the socket at `socket` and reconnect with backoff whenever
+    /// the connection drops, e.g. across a daemon restart. Commands in
+    /// flight at the drop fail; events streams carry on after reconnecting.
+    /// Topic subscriptions and `own_replies` are per connection and must be
+    /// renewed.
+    pub async fn connect_reconnecting(socket: impl AsRef<Path>) -> Result<Self> {
+        let socket = socket.as_ref();
+        let stream = open(socket).await?;
+        Ok(Self::spawn(stream, Some(socket.to_path_buf())))
+    }
+
+    /// Wrap an already connected socket, for callers that map connect
+    /// errors themselves. Never reconnects.
+    pub fn from_stream(stream: UnixStream) -> Self {
+        Self::spawn(stream, None)
+    }
+
+    fn spawn(stream: UnixStream, socket: Option<PathBuf>) -> Self {
+        let (read_half, write_half) = stream.into_split();
+        let shared = Arc::new(Shared {
+            socket,
+            writer: tokio::sync::Mutex::new(Some(write_half)),
+            state: Mutex::new(State::default()),
+            next_req_id: AtomicU64::new(1),
+        });
+        let reader = tokio::spawn(read_loop(shared.clone(), BufReader::new(read_half).lines()));
+        Self { shared, reader }
+    }
+
+    /// Send `command` and wait for its reply, which is returned whether `ok`
+    /// is true or not. A `req_id` set by the caller is kept and must be
+    /// unique among commands in flight; one assigned by the client is
+    /// removed from the reply.
+    pub async fn command(&self, command: Value) -> Result<Value> {
+        let Value::Object(mut command) = command else {
+            bail!("IPC command must be a JSON object");
+        };
+        let (tx, rx) = oneshot::channel();
+        let assigned = {
+            // Registering under the writer lock orders this command before
+            // or after a disconnect, which fails everything pending.
+            let mut writer = self.shared.writer.lock().await;
+            let Some(stream) = writer.as_mut() else {
+                bail!("not connected to the daemon");
+            };
+            let (req_id, assigned) = match command.get("req_id").and_then(Value::as_str) {
+                Some(req_id) => (req_id.to_string(), false),
+                // A lone command is matched as the next reply, so its line
+                // goes out exactly as given.
+                None if !self.shared.in_flight() => (String::new(), false),
+                None => {
+                    let n = self.shared.next_req_id.fetch_add(1, Ordering::Relaxed);
+                    let req_id = format!("{INTERNAL_REQ_ID_PREFIX}{n}");
+                    command.insert("req_id".to_string(), Value::String(req_id.clone()));
+                    (req_id, true)
+                }
+            };
+            let line = encode_line(&Value::Object(command))?;
+            self.shared.register(&req_id, tx)?;
+            tracing::debug!(cmd_bytes = line.len(), "sending IPC command");
+            if let Err(err) = stream.write_all(line.as_bytes()).await {
+                self.shared.unregister(&req_id);
+                return Err(err).context("failed to write IPC command");
+            }
+            assigned
+        };
+
+        let mut reply = rx
+            .await
+            .map_err(|_| anyhow!("daemon closed connection without a command response"))?;
+        if assigned && let Some(reply) = reply.as_object_mut() {
+            reply.remove("req_id");
+        }
+        Ok(reply)
+    }
+
+    /// Envelopes peers send from now on, decoded from `inbound` events. Ends
+    /// like [`events`](Self::events).
+    pub fn inbound(&self) -> InboundEnvelopes {
+        InboundEnvelopes {
+            events: self.events(),
+            client: None,
+        }
+    }
+
+    /// Events the daemon sends from now on. The stream ends when the
+    /// connection closes for good or falls [`EVENT_BUFFER`] events behind.
+    pub fn events(&self) -> IpcEvents {
+        let (tx, rx) = mpsc::channel(EVENT_BUFFER);
+        let mut state = self.shared.lock_state();
+        if !state.closed {
+            state.subscribers.push(tx);
+        }
+        IpcEvents { rx }
+    }
+}
+
+impl Drop for IpcClient {
+    fn drop(&mut self) {
+        self.reader.abort();
+    }
+}
+
+/// Stream of IPC event objects (lines with an `event` key) from an
+/// [`IpcClient`].
+#[derive(Debug)]
+pub struct IpcEvents {
+    rx: mpsc::Receiver<Value>,
+}
+
+impl IpcEvents {
+    /// The next event, or `None` once the stream has ended.
+    pub async fn next(&mut self) -> Option<Value> {
+        self.rx.recv().await
+    }
+}
+
+impl futures_core::Stream for IpcEvents {
+    type Item = Value;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Option<Value>> {
+        self.rx.poll_recv(cx)
+    }
+}
+
+/// Stream of inbound [`Envelope`]s from an [`IpcClient`]; other events are
+/// skipped.
+#[derive(Debug)]
+pub struct InboundEnvelopes {
+    events: IpcEvents,
+    /// Keeps a client the stream was made from open for as long as the
+    /// stream lives.
+    client: Option<IpcClient>,
+}
+
+impl InboundEnvelopes {
+    /// The next envelope, or `None` once the stream has ended.
+    pub async fn next(&mut self) -> Option<Envelope> {
+        std::future::poll_fn(|cx| futures_core::Stream::poll_next(Pin::new(&mut *self), cx)).await
+    }
+
+    /// This stream, holding `client` open until it is dropped.
+    pub(crate) fn owning(mut self, client: IpcClient) -> Self {
+        self.client = Some(client);
+        self
+    }
+}
+
+impl futures_core::Stream for InboundEnvelopes {
+    type Item = Envelope;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Option<Envelope>> {
+        loop {
+            let Some(event) = ready!(self.events.rx.poll_recv(cx)) else {
+                return Poll::Ready(None);
+            };
+            if event.get("event").and_then(Value::as_str) != Some("inbound") {
+                continue;
+            }
+            match serde_json::from_value(event["envelope"].clone()) {
+                Ok(envelope) => return Poll::Ready(Some(envelope)),
+                Err(err) => tracing::warn!(error = %err, "skipping undecodable inbound event"),
+            }
+        }
+    }
+}
+
+impl Shared {
+    fn lock_state(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    fn in_flight(&self) -> bool {
+        self.lock_state()
+            .pending
+            .iter()
+            .any(|(_, tx)| !tx.is_closed())
+    }
+
+    /// Track a command awaiting its reply; `req_id` is empty for one sent
+    /// without.
+    fn register(&self, req_id: &str, tx: oneshot::Sender<Value>) -> Result<()> {
+        let mut state = self.lock_state();
+        if !req_id.is_empty()
+            && state
+                .pending
+                .iter()
+                .any(|(id, tx)| id == req_id && !tx.is_closed())
+        {
+            bail!("req_id {req_id:?} is already in flight");
+        }
+        state.pending.push_back((req_id.to_string(), tx));
+        Ok(())
+    }
+
+    fn unregister(&self, req_id: &str) {
+        let mut state = self.lock_state();
+        if let Some(i) = state.pending.iter().rposition(|(id, _)| id == req_id) {
+            state.pending.remove(i);
+        }
+    }
+
+    fn dispatch(&self, line: &str) {
+        let line = line.trim();
+        if line.is_empty() {
+            return;
+        }
+        let Ok(decoded) = serde_json::from_str::<Value>(line) else {
+            tracing::debug!("skipping undecodable IPC line");
+            return;
+        };
+        let mut state = self.lock_state();
+        if decoded.get("event").is_some() {
+            state
+                .subscribers
+                .retain(|tx| match tx.try_send(decoded.clone()) {
+                    Ok(()) => true,
+                    Err(mpsc::error::TrySendError::Full(_)) => {
+                        tracing::warn!("dropping IPC events stream that fell behind");
+                        false
+                    }
+                    Err(mpsc::error::TrySendError::Closed(_)) => false,
+                });
+            return;
+        }
+
+        // Replies echo `req_id`. One without answers the oldest command:
+        // either it was sent without one, or the daemon could not parse it
+        // (e.g. `command_too_large`).
+        let position = match decoded.get("req_id").and_then(Value::as_str) {
+            Some(req_id) => state.pending.iter().position(|(id, _)| id == req_id),
+            None => state.pending.iter().position(|(_, tx)| !tx.is_closed()),
+        };
+        match position.and_then(|i| state.pending.remove(i)) {
+            Some((_, tx)) => {
+                let _ = tx.send(decoded);
+            }
+            None => tracing::debug!("skipping IPC reply with no pending command"),
+        }
+    }
+
+    /// Forget the connection and fail every pending command.
+    async fn disconnected(&self) {
+        let mut writer = self.writer.lock().await;
+        *writer = None;
+        self.lock_state().pending.clear();
+    }
+
+    /// End every events stream.
+    fn close(&self) {
+        let mut state = self.lock_state();
+        state.closed = true;
+        state.subscribers.clear();
+    }
+
+    /// Reconnect with backoff until the socket accepts a connection.
+    async fn reconnect(&self, socket: &Path) -> Lines<BufReader<OwnedReadHalf>> {
+        let mut backoff = RECONNECT_INITIAL_BACKOFF;
+        loop {
+            tokio::time::sleep(backoff).await;
+            match UnixStream::connect(socket).await {
+                Ok(stream) => {
+                    tracing::debug!(socket = %socket.display(), "reconnected to daemon IPC socket");
+                    let (read_half, write_half) = stream.into_split();
+                    *self.writer.lock().await = Some(write_half);
+                    return BufReader::new(read_half).lines();
+                }
+                Err(err) => {
+                    tracing::debug!(error = %err, "IPC reconnect failed");
+                    backoff = (backoff * 2).min(RECONNECT_MAX_BACKOFF);
+                }
+            }
+        }
+    }
+}
+
+async fn read_loop(shared: Arc<Shared>, mut lines: Lines<BufReader<OwnedReadHalf>>) {
+    loop {
+        loop {
+            match lines.next_line().await {
+                Ok(Some(line)) => shared.dispatch(&line),
+                Ok(None) => break,
+                Err(err) => {
+                    tracing::debug!(error = %err, "failed to read from IPC socket");
+                    break;
+                }
+            }
+        }
+        shared.disconnected().await;
+        let Some(socket) = shared.socket.clone() else {
+            shared.close();
+            return;
+        };
+        lines = shared.reconnect(&socket).await;
+    }
+}
+
+async fn open(socket: &Path) -> Result<UnixStream> {
+    UnixStream::connect(socket)
+        .await
+        .with_context(|| format!("failed to connect to {}", socket.display()))
+}
+
+/// `command` as one protocol line, newline included.
+fn encode_line(command: &Value) -> Result<String> {
+    let mut line = serde_json::to_string(command).context("failed to serialize IPC command")?;
+    if line.len() > MAX_IPC_LINE_LENGTH {
+        bail!(
+            "IPC command size ({} bytes) exceeds the 64KB limit",
+            line.len()
+        );
+    }
+    line.push('\n');
+    Ok(line)
+}
+
+#[cfg(test)]
+#[path = "tests.rs"]
+mod tests;
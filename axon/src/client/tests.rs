@@ -0,0 +1,200 @@
+use serde_json::json;
+use tempfile::tempdir;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::UnixListener;
+
+use super::*;
+
+async fn read_command(lines: &mut Lines<BufReader<OwnedReadHalf>>) -> Value {
+    let line = lines.next_line().await.unwrap().expect("command line");
+    serde_json::from_str(&line).unwrap()
+}
+
+async fn write_line(writer: &mut OwnedWriteHalf, value: Value) {
+    let mut line = value.to_string();
+    line.push('\n');
+    writer.write_all(line.as_bytes()).await.unwrap();
+}
+
+#[tokio::test]
+async fn concurrent_commands_are_matched_by_req_id() {
+    let dir = tempdir().unwrap();
+    let socket = dir.path().join("axon.sock");
+    let listener = UnixListener::bind(&socket).unwrap();
+    let server = tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let (read_half, mut writer) = stream.into_split();
+        let mut lines = BufReader::new(read_half).lines();
+        let mut commands = Vec::new();
+        for _ in 0..3 {
+            commands.push(read_command(&mut lines).await);
+        }
+        assert!(
+            commands[0].get("req_id").is_none(),
+            "lone command is sent as given"
+        );
+        write_line(&mut writer, json!({"event": "inbound", "from": "x"})).await;
+        for command in commands.into_iter().rev() {
+            let mut reply = json!({"ok": true, "cmd": command["cmd"]});
+            if let Some(req_id) = command.get("req_id") {
+                reply["req_id"] = req_id.clone();
+            }
+            write_line(&mut writer, reply).await;
+        }
+    });
+
+    let client = IpcClient::connect(&socket).await.unwrap();
+    let (status, peers, whoami) = tokio::join!(
+        client.command(json!({"cmd": "status"})),
+        client.command(json!({"cmd": "peers", "req_id": "mine"})),
+        client.command(json!({"cmd": "whoami"})),
+    );
+    assert_eq!(status.unwrap(), json!({"ok": true, "cmd": "status"}));
+    let peers = peers.unwrap();
+    assert_eq!(peers["cmd"], "peers");
+    assert_eq!(peers["req_id"], "mine");
+    assert_eq!(
+        whoami.unwrap(),
+        json!({"ok": true, "cmd": "whoami"}),
+        "assigned req_id is removed"
+    );
+    server.await.unwrap();
+}
+
+#[tokio::test]
+async fn reply_without_req_id_answers_oldest_command() {
+    let dir = tempdir().unwrap();
+    let socket = dir.path().join("axon.sock");
+    let listener = UnixListener::bind(&socket).unwrap();
+    let server = tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let (read_half, mut writer) = stream.into_split();
+        let mut lines = BufReader::new(read_half).lines();
+        read_command(&mut lines).await;
+        write_line(
+            &mut writer,
+            json!({"ok": false, "error": "command_too_large"}),
+        )
+        .await;
+        read_command(&mut lines).await;
+        // Close without answering the second command.
+    });
+
+    let client = IpcClient::connect(&socket).await.unwrap();
+    let reply = client.command(json!({"cmd": "status"})).await.unwrap();
+    assert_eq!(reply["error"], "command_too_large");
+
+    let err = client.command(json!({"cmd": "status"})).await.unwrap_err();
+    assert!(
+        err.to_string().contains("without a command response"),
+        "error: {err}"
+    );
+    server.await.unwrap();
+
+    let mut events = client.events();
+    assert_eq!(events.next().await, None);
+}
+
+#[tokio::test]
+async fn events_reach_every_stream_until_the_connection_closes() {
+    let dir = tempdir().unwrap();
+    let socket = dir.path().join("axon.sock");
+    let listener = UnixListener::bind(&socket).unwrap();
+    let (ready_tx, ready_rx) = oneshot::channel();
+    let server = tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let (_read_half, mut writer) = stream.into_split();
+        ready_rx.await.unwrap();
+        write_line(&mut writer, json!({"event": "inbound", "n": 1})).await;
+        write_line(&mut writer, json!({"event": "pair_request", "n": 2})).await;
+    });
+
+    let client = IpcClient::connect(&socket).await.unwrap();
+    let mut first = client.events();
+    let mut second = client.events();
+    ready_tx.send(()).unwrap();
+    server.await.unwrap();
+
+    for events in [&mut first, &mut second] {
+        assert_eq!(events.next().await.unwrap()["n"], 1);
+        assert_eq!(events.next().await.unwrap()["n"], 2);
+        assert_eq!(events.next().await, None);
+    }
+}
+
+#[tokio::test]
+async fn reconnecting_client_resumes_after_the_daemon_returns() {
+    let dir = tempdir().unwrap();
+    let socket = dir.path().join("axon.sock");
+    let listener = UnixListener::bind(&socket).unwrap();
+    let server = tokio::spawn(async move {
+        // First connection is dropped straight away, as on a daemon restart.
+        drop(listener.accept().await.unwrap());
+        let (stream, _) = listener.accept().await.unwrap();
+        let (read_half, mut writer) = stream.into_split();
+        let mut lines = BufReader::new(read_half).lines();
+        write_line(&mut writer, json!({"event": "inbound", "n": 1})).await;
+        let command = read_command(&mut lines).await;
+        write_line(&mut writer, json!({"ok": true, "cmd": command["cmd"]})).await;
+    });
+
+    let client = IpcClient::connect_reconnecting(&socket).await.unwrap();
+    let mut events = client.events();
+    assert_eq!(events.next().await.unwrap()["n"], 1);
+    let reply = client.command(json!({"cmd": "status"})).await.unwrap();
+    assert_eq!(reply, json!({"ok": true, "cmd": "status"}));
+    server.await.unwrap();
+}
+
+#[tokio::test]
+async fn oversized_and_non_object_commands_are_rejected() {
+    let dir = tempdir().unwrap();
+    let socket = dir.path().join("axon.sock");
+    let _listener = UnixListener::bind(&socket).unwrap();
+    let client = IpcClient::connect(&socket).await.unwrap();
+
+    let big = json!({"cmd": "send", "payload": "x".repeat(MAX_IPC_LINE_LENGTH)});
+    let err = client.command(big).await.unwrap_err();
+    assert!(err.to_string().contains("exceeds"), "error: {err}");
+
+    let err = client.command(json!(["status"])).await.unwrap_err();
+    assert!(err.to_string().contains("JSON object"), "error: {err}");
+}
+
+#[tokio::test]
+async fn inbound_stream_yields_only_decoded_envelopes() {
+    fn assert_stream<S: futures_core::Stream<Item = Envelope>>(_: &S) {}
+
+    let dir = tempdir().unwrap();
+    let socket = dir.path().join("axon.sock");
+    let listener = UnixListener::bind(&socket).unwrap();
+    let (ready_tx, ready_rx) = oneshot::channel();
+    let envelope = Envelope::new(
+        "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+        "ed25519.bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb",
+        crate::message::MessageKind::Message,
+        json!({"n": 1}),
+    );
+    let sent = serde_json::to_value(&envelope).unwrap();
+    let server = tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let (_read_half, mut writer) = stream.into_split();
+        ready_rx.await.unwrap();
+        write_line(&mut writer, json!({"event": "pair_request"})).await;
+        write_line(
+            &mut writer,
+            json!({"event": "inbound", "envelope": "garbage"}),
+        )
+        .await;
+        write_line(&mut writer, json!({"event": "inbound", "envelope": sent})).await;
+    });
+
+    let client = IpcClient::connect(&socket).await.unwrap();
+    let mut inbound = client.inbound();
+    assert_stream(&inbound);
+    ready_tx.send(()).unwrap();
+    server.await.unwrap();
+
+    assert_eq!(inbound.next().await.unwrap().id, envelope.id);
+    assert!(inbound.next().await.is_none());
+}
@@ -0,0 +1,101 @@
+use std::collections::HashSet;
+use std::io::ErrorKind;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::message::AgentId;
+
+/// One entry in `blocklist.json`: a peer the operator has chosen not to talk
+/// to. Unlike a revocation, a block is about the peer rather than a
+/// compromised key: it leaves `config.yaml` alone and can be lifted.
+///
+/// `pubkey` is absent when the peer was blocked before its key was known;
+/// such an entry refuses whichever key derives to `agent_id`.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+pub struct BlockedPeer {
+    pub agent_id: AgentId,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pubkey: Option<String>,
+    pub blocked_at_unix_ms: u64,
+}
+
+impl BlockedPeer {
+    /// The entry in the refused set: the public key, or the agent ID when
+    /// the key is not known.
+    pub fn refused_key(&self) -> &str {
+        self.pubkey.as_deref().unwrap_or(self.agent_id.as_str())
+    }
+}
+
+pub async fn load_blocklist(path: &Path) -> Result<Vec<BlockedPeer>> {
+    let raw = match tokio::fs::read_to_string(path).await {
+        Ok(raw) => raw,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => {
+            return Err(err)
+                .with_context(|| format!("failed to read blocklist: {}", path.display()));
+        }
+    };
+    serde_json::from_str(&raw)
+        .with_context(|| format!("failed to parse blocklist: {}", path.display()))
+}
+
+pub async fn save_blocklist(path: &Path, blocked: &[BlockedPeer]) -> Result<()> {
+    if let Some(parent) = path.parent()
+        && !parent.exists()
+    {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .with_context(|| format!("failed to create directory: {}", parent.display()))?;
+    }
+
+    let data = serde_json::to_vec_pretty(blocked).context("failed to encode blocklist")?;
+    tokio::fs::write(path, data)
+        .await
+        .with_context(|| format!("failed to write blocklist: {}", path.display()))?;
+    Ok(())
+}
+
+/// Append `entry` to the blocklist unless its agent is already listed.
+/// Returns `false` when it was.
+pub async fn add_block(path: &Path, entry: BlockedPeer) -> Result<bool> {
+    let mut blocked = load_blocklist(path).await?;
+    if blocked
+        .iter()
+        .any(|existing| existing.agent_id == entry.agent_id)
+    {
+        return Ok(false);
+    }
+    blocked.push(entry);
+    save_blocklist(path, &blocked).await?;
+    Ok(true)
+}
+
+/// Remove `agent_id` from the blocklist, returning its entry if it had one.
+pub async fn remove_block(path: &Path, agent_id: &str) -> Result<Option<BlockedPeer>> {
+    let mut blocked = load_blocklist(path).await?;
+    let Some(index) = blocked
+        .iter()
+        .position(|entry| entry.agent_id.as_str() == agent_id)
+    else {
+        return Ok(None);
+    };
+    let removed = blocked.remove(index);
+    save_blocklist(path, &blocked).await?;
+    Ok(Some(removed))
+}
+
+/// The refused set for [`crate::peer_table::PeerTable::set_blocked`]: each
+/// entry's public key, or its agent ID when the key is not known.
+pub fn blocked_keys(blocked: &[BlockedPeer]) -> HashSet<String> {
+    blocked
+        .iter()
+        .map(|entry| entry.refused_key().to_string())
+        .collect()
+}
+
+#[cfg(test)]
+#[path = "blocklist_tests.rs"]
+mod tests;
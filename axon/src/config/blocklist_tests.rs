@@ -0,0 +1,72 @@
+use super::*;
+use tempfile::tempdir;
+
+fn entry(agent: char, pubkey: &str) -> BlockedPeer {
+    BlockedPeer {
+        agent_id: format!("ed25519.{}", agent.to_string().repeat(32)).into(),
+        pubkey: Some(pubkey.to_string()),
+        blocked_at_unix_ms: 123,
+    }
+}
+
+#[tokio::test]
+async fn blocklist_empty_when_missing() {
+    let dir = tempdir().expect("temp dir");
+    let loaded = load_blocklist(&dir.path().join("missing.json"))
+        .await
+        .expect("load");
+    assert!(loaded.is_empty());
+}
+
+#[tokio::test]
+async fn blocks_are_added_once_and_removed_by_agent_id() {
+    let dir = tempdir().expect("temp dir");
+    let path = dir.path().join("nested").join("blocklist.json");
+
+    assert!(add_block(&path, entry('a', "Zm9v")).await.expect("add"));
+    assert!(!add_block(&path, entry('a', "Zm9v")).await.expect("re-add"));
+    assert!(
+        add_block(&path, entry('b', "YmFy"))
+            .await
+            .expect("add second")
+    );
+    assert_eq!(
+        blocked_keys(&load_blocklist(&path).await.expect("load")),
+        HashSet::from(["Zm9v".to_string(), "YmFy".to_string()])
+    );
+
+    let removed = remove_block(&path, entry('a', "Zm9v").agent_id.as_str())
+        .await
+        .expect("remove");
+    assert_eq!(removed, Some(entry('a', "Zm9v")));
+    assert_eq!(
+        remove_block(&path, entry('a', "Zm9v").agent_id.as_str())
+            .await
+            .expect("remove again"),
+        None
+    );
+    assert_eq!(
+        load_blocklist(&path).await.expect("load"),
+        vec![entry('b', "YmFy")]
+    );
+}
+
+#[tokio::test]
+async fn entry_without_pubkey_is_refused_by_agent_id() {
+    let dir = tempdir().expect("temp dir");
+    let path = dir.path().join("blocklist.json");
+    let unknown = BlockedPeer {
+        pubkey: None,
+        ..entry('c', "")
+    };
+
+    assert!(add_block(&path, unknown.clone()).await.expect("add"));
+    let raw = tokio::fs::read_to_string(&path).await.expect("read");
+    assert!(!raw.contains("pubkey"), "{raw}");
+    let loaded = load_blocklist(&path).await.expect("load");
+    assert_eq!(loaded, vec![unknown.clone()]);
+    assert_eq!(
+        blocked_keys(&loaded),
+        HashSet::from([unknown.agent_id.to_string()])
+    );
+}
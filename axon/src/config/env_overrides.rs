@@ -0,0 +1,113 @@
+use anyhow::{Context, Result, bail};
+use serde_json::Value as JsonValue;
+use serde_yaml::{Mapping, Value};
+
+use super::PersistedConfig;
+use super::validate::config_schema;
+
+/// Prefix of environment variables that override `config.yaml` keys.
+pub const ENV_PREFIX: &str = "AXON_";
+
+/// Separates a section from a key inside it, as in `AXON_RATE_LIMIT__MESSAGES_PER_SEC`.
+const NESTING: &str = "__";
+
+/// Apply `AXON_<KEY>` and `AXON_<SECTION>__<KEY>` variables from `vars` over
+/// `config`. Names match schema keys case-insensitively; variables naming no
+/// top-level key (such as `AXON_ROOT`) are ignored, while an unknown key
+/// inside a known section is an error. Values are parsed as YAML, except for
+/// string keys, which take the value verbatim; an empty value unsets the key.
+/// Returns the config and the names of the variables applied, in order.
+pub fn apply_env_overrides(
+    config: PersistedConfig,
+    vars: impl IntoIterator<Item = (String, String)>,
+) -> Result<(PersistedConfig, Vec<String>)> {
+    let mut document = match serde_yaml::to_value(&config).context("failed to serialize config")? {
+        Value::Mapping(mapping) => mapping,
+        _ => Mapping::new(),
+    };
+    let applied = apply_to_document(&mut document, vars)?;
+    if applied.is_empty() {
+        return Ok((config, Vec::new()));
+    }
+    let names: Vec<String> = applied.into_iter().map(|(name, _)| name).collect();
+    let config = serde_yaml::from_value(Value::Mapping(document))
+        .with_context(|| format!("invalid config from environment ({})", names.join(", ")))?;
+    Ok((config, names))
+}
+
+/// [`apply_env_overrides`] on a serialized config. Returns each applied
+/// variable with the key path it set.
+pub(super) fn apply_to_document(
+    document: &mut Mapping,
+    vars: impl IntoIterator<Item = (String, String)>,
+) -> Result<Vec<(String, Vec<String>)>> {
+    let schema = config_schema();
+    let mut overrides: Vec<(String, Vec<String>, String)> = Vec::new();
+    for (name, value) in vars {
+        let Some(rest) = name.strip_prefix(ENV_PREFIX) else {
+            continue;
+        };
+        let path: Vec<String> = rest.split(NESTING).map(str::to_ascii_lowercase).collect();
+        if schema["properties"].get(&path[0]).is_none() {
+            continue;
+        }
+        overrides.push((name, path, value));
+    }
+    // A whole section applies before the keys inside it.
+    overrides.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut applied = Vec::with_capacity(overrides.len());
+    for (name, path, raw) in overrides {
+        let key_schema = key_schema(&schema, &path)
+            .with_context(|| format!("{name} does not name a config key"))?;
+        let value = if raw.is_empty() {
+            None
+        } else if key_schema["type"] == "string" {
+            Some(Value::String(raw))
+        } else {
+            Some(serde_yaml::from_str(&raw).with_context(|| format!("{name} is not valid YAML"))?)
+        };
+        set_path(document, &path, value);
+        applied.push((name, path));
+    }
+    Ok(applied)
+}
+
+fn key_schema<'a>(schema: &'a JsonValue, path: &[String]) -> Result<&'a JsonValue> {
+    let mut current = schema;
+    for segment in path {
+        match current["properties"].get(segment) {
+            Some(child) => current = child,
+            None => bail!("no key `{segment}`"),
+        }
+    }
+    Ok(current)
+}
+
+fn set_path(document: &mut Mapping, path: &[String], value: Option<Value>) {
+    let key = Value::String(path[0].clone());
+    if path.len() == 1 {
+        match value {
+            Some(value) => {
+                document.insert(key, value);
+            }
+            None => {
+                document.remove(&key);
+            }
+        }
+        return;
+    }
+    let section = document
+        .entry(key)
+        .or_insert_with(|| Value::Mapping(Mapping::new()));
+    if !section.is_mapping() {
+        *section = Value::Mapping(Mapping::new());
+    }
+    if let Value::Mapping(section) = section {
+        set_path(section, &path[1..], value);
+    }
+}
+
+#[cfg(test)]
+#[path = "env_overrides_tests.rs"]
+mod tests;
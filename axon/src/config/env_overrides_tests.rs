@@ -0,0 +1,98 @@
+use super::*;
+use crate::config::{PersistedConfig, RateLimitConfig};
+
+fn vars(list: &[(&str, &str)]) -> Vec<(String, String)> {
+    list.iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect()
+}
+
+#[test]
+fn top_level_and_section_keys_are_overridden() {
+    let config = PersistedConfig {
+        port: Some(7100),
+        ..Default::default()
+    };
+    let (config, applied) = apply_env_overrides(
+        config,
+        vars(&[
+            ("AXON_PORT", "7200"),
+            ("AXON_NAME", "0042"),
+            ("AXON_RATE_LIMIT__MESSAGES_PER_SEC", "50"),
+            ("AXON_HISTORY__ENABLED", "true"),
+            ("HOME", "/root"),
+        ]),
+    )
+    .unwrap();
+
+    assert_eq!(config.port, Some(7200));
+    // String keys are taken verbatim rather than parsed as a number.
+    assert_eq!(config.name.as_deref(), Some("0042"));
+    assert_eq!(config.rate_limit.messages_per_sec, Some(50));
+    assert_eq!(config.history.enabled, Some(true));
+    assert_eq!(
+        applied,
+        vec![
+            "AXON_HISTORY__ENABLED",
+            "AXON_NAME",
+            "AXON_PORT",
+            "AXON_RATE_LIMIT__MESSAGES_PER_SEC"
+        ]
+    );
+}
+
+#[test]
+fn unrelated_axon_variables_are_ignored() {
+    let (config, applied) = apply_env_overrides(
+        PersistedConfig::default(),
+        vars(&[("AXON_ROOT", "/tmp/axon"), ("AXON_BACKUP_PASSPHRASE", "x")]),
+    )
+    .unwrap();
+    assert!(applied.is_empty());
+    assert_eq!(config.port, None);
+}
+
+#[test]
+fn whole_sections_apply_before_their_keys_and_empty_unsets() {
+    let config = PersistedConfig {
+        name: Some("alice".into()),
+        ..Default::default()
+    };
+    let (config, _) = apply_env_overrides(
+        config,
+        vars(&[
+            ("AXON_RATE_LIMIT__BYTES_PER_SEC", "1000"),
+            (
+                "AXON_RATE_LIMIT",
+                "{messages_per_sec: 5, bytes_per_sec: 10}",
+            ),
+            ("AXON_NAME", ""),
+        ]),
+    )
+    .unwrap();
+    assert_eq!(
+        config.rate_limit,
+        RateLimitConfig {
+            messages_per_sec: Some(5),
+            bytes_per_sec: Some(1000),
+        }
+    );
+    assert_eq!(config.name, None);
+}
+
+#[test]
+fn unknown_keys_and_bad_values_are_errors() {
+    let err = apply_env_overrides(
+        PersistedConfig::default(),
+        vars(&[("AXON_RATE_LIMIT__TYPO", "1")]),
+    )
+    .unwrap_err();
+    assert!(
+        format!("{err:#}").contains("AXON_RATE_LIMIT__TYPO"),
+        "{err:#}"
+    );
+
+    let err = apply_env_overrides(PersistedConfig::default(), vars(&[("AXON_PORT", "http")]))
+        .unwrap_err();
+    assert!(format!("{err:#}").contains("AXON_PORT"), "{err:#}");
+}
@@ -0,0 +1,56 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// Machine-readable event log (`event_log:` section of `config.yaml`).
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, Default, PartialEq, Eq)]
+pub struct EventLogConfig {
+    /// Append sends, receives, connects, and errors to `events.jsonl`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    /// Rotate `events.jsonl` once it would grow past this many bytes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_file_bytes: Option<u64>,
+    /// Rotate `events.jsonl` once it is this many seconds old; 0 rotates by
+    /// size only.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_file_age_secs: Option<u64>,
+    /// Rotated files kept (`events.jsonl.1` … `events.jsonl.N`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_files: Option<usize>,
+}
+
+impl EventLogConfig {
+    pub const DEFAULT_MAX_FILE_BYTES: u64 = 10 * 1024 * 1024;
+    pub const DEFAULT_MAX_FILE_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+    pub const DEFAULT_MAX_FILES: usize = 7;
+
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.enabled.unwrap_or(false)
+    }
+
+    pub fn max_file_bytes(&self) -> u64 {
+        self.max_file_bytes
+            .filter(|bytes| *bytes > 0)
+            .unwrap_or(Self::DEFAULT_MAX_FILE_BYTES)
+    }
+
+    /// `None` when time-based rotation is off.
+    pub fn max_file_age(&self) -> Option<Duration> {
+        match self.max_file_age_secs {
+            Some(0) => None,
+            Some(secs) => Some(Duration::from_secs(secs)),
+            None => Some(Self::DEFAULT_MAX_FILE_AGE),
+        }
+    }
+
+    pub fn max_files(&self) -> usize {
+        self.max_files
+            .filter(|n| *n > 0)
+            .unwrap_or(Self::DEFAULT_MAX_FILES)
+    }
+}
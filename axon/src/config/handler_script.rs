@@ -0,0 +1,27 @@
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+use rhai::{AST, Engine};
+
+/// Function a `handler.script` must define, taking the request envelope.
+pub const HANDLER_SCRIPT_ENTRY: &str = "handle";
+
+/// Read and compile the `handler.script` at `path`, checking that it defines
+/// `handle(request)`. Shared by the daemon and `axon config validate`.
+pub fn compile_handler_script(engine: &Engine, path: &Path) -> Result<AST> {
+    let source = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    let ast = engine
+        .compile(&source)
+        .map_err(|err| anyhow::anyhow!("{}: {err}", path.display()))?;
+    let has_entry = ast
+        .iter_functions()
+        .any(|function| function.name == HANDLER_SCRIPT_ENTRY && function.params.len() == 1);
+    if !has_entry {
+        bail!(
+            "{} does not define {HANDLER_SCRIPT_ENTRY}(request)",
+            path.display()
+        );
+    }
+    Ok(ast)
+}
@@ -0,0 +1,83 @@
+use std::io::ErrorKind;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_yaml::Value;
+
+use super::{
+    AxonPaths, ENV_PREFIX, PersistedConfig, apply_env_overrides, env_vars, system_config_path,
+};
+
+/// Where the identity seed is kept (`identity:` section of `config.yaml`).
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, Default, PartialEq, Eq)]
+pub struct IdentityConfig {
+    /// Unset keeps the seed in `identity.key`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub storage: Option<IdentityStorage>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum IdentityStorage {
+    /// Base64 seed in `identity.key`, mode 0600.
+    #[default]
+    File,
+    /// macOS Keychain or Linux Secret Service (`keychain` build feature);
+    /// `identity.key` is used while neither is reachable.
+    Keychain,
+}
+
+impl IdentityConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn storage(&self) -> IdentityStorage {
+        self.storage.unwrap_or_default()
+    }
+
+    /// The `identity.storage` setting for `paths`: the system config, then
+    /// the user config, then `AXON_IDENTITY__STORAGE`. Read synchronously,
+    /// because identities are loaded outside the async runtime too, and from
+    /// the `identity` section alone, so `axon doctor` can still load the
+    /// identity of a root whose other settings need repair.
+    pub fn for_paths(paths: &AxonPaths) -> Result<Self> {
+        let mut identity = Self::default();
+        for path in system_config_path()
+            .into_iter()
+            .chain([paths.config.clone()])
+        {
+            let layer = read_section(&path)?;
+            if layer.storage.is_some() {
+                identity = layer;
+            }
+        }
+        let prefix = format!("{ENV_PREFIX}IDENTITY__");
+        let vars = env_vars()
+            .into_iter()
+            .filter(|(name, _)| name.starts_with(&prefix));
+        let config = PersistedConfig {
+            identity,
+            ..PersistedConfig::default()
+        };
+        Ok(apply_env_overrides(config, vars)?.0.identity)
+    }
+}
+
+fn read_section(path: &Path) -> Result<IdentityConfig> {
+    let raw = match std::fs::read_to_string(path) {
+        Ok(raw) => raw,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(IdentityConfig::default()),
+        Err(err) => {
+            return Err(err).with_context(|| format!("failed to read config: {}", path.display()));
+        }
+    };
+    let document: Value = serde_yaml::from_str(&raw)
+        .with_context(|| format!("failed to parse config: {}", path.display()))?;
+    match document.get("identity") {
+        Some(section) => serde_yaml::from_value(section.clone())
+            .with_context(|| format!("invalid identity section in {}", path.display())),
+        None => Ok(IdentityConfig::default()),
+    }
+}
@@ -0,0 +1,94 @@
+use std::io::ErrorKind;
+use std::net::SocketAddr;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncWriteExt;
+use tracing::warn;
+
+use crate::message::AgentId;
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+pub struct KnownPeer {
+    pub agent_id: AgentId,
+    pub addr: SocketAddr,
+    pub pubkey: String,
+    pub last_seen_unix_ms: u64,
+    /// Absent in caches written before first-seen tracking; loading such an
+    /// entry starts the clock at load time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub first_seen_unix_ms: Option<u64>,
+    pub source: KnownPeerSource,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum KnownPeerSource {
+    Static,
+    Discovered,
+    Cached,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, Deserialize)]
+struct LegacyKnownPeer {
+    agent_id: AgentId,
+    addr: SocketAddr,
+    pubkey: String,
+    last_seen_unix_ms: u64,
+}
+
+pub async fn load_known_peers(path: &Path) -> Result<Vec<KnownPeer>> {
+    let raw = match tokio::fs::read_to_string(path).await {
+        Ok(raw) => raw,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => {
+            return Err(err)
+                .with_context(|| format!("failed to read known peers: {}", path.display()));
+        }
+    };
+    let peers = match serde_json::from_str::<Vec<KnownPeer>>(&raw) {
+        Ok(peers) => peers,
+        Err(parse_err) => {
+            if serde_json::from_str::<Vec<LegacyKnownPeer>>(&raw).is_ok() {
+                warn!(
+                    path = %path.display(),
+                    "ignoring legacy known_peers cache without source metadata; cache will be rebuilt"
+                );
+                return Ok(Vec::new());
+            }
+            return Err(parse_err)
+                .with_context(|| format!("failed to parse known peers: {}", path.display()));
+        }
+    };
+    Ok(peers)
+}
+
+/// Write `known_peers.json` through a synced temporary file and a rename, so
+/// a crash mid-write leaves either the previous file or the new one.
+pub async fn save_known_peers(path: &Path, peers: &[KnownPeer]) -> Result<()> {
+    if let Some(parent) = path.parent()
+        && !parent.exists()
+    {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .with_context(|| format!("failed to create directory: {}", parent.display()))?;
+    }
+
+    let data = serde_json::to_vec(peers).context("failed to encode known peers")?;
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let write_tmp = async {
+        let mut file = tokio::fs::File::create(&tmp).await?;
+        file.write_all(&data).await?;
+        file.sync_all().await
+    };
+    write_tmp
+        .await
+        .with_context(|| format!("failed to write known peers: {}", path.display()))?;
+    tokio::fs::rename(&tmp, path)
+        .await
+        .with_context(|| format!("failed to write known peers: {}", path.display()))?;
+    Ok(())
+}
@@ -0,0 +1,234 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+use serde_yaml::{Mapping, Value};
+
+use super::PersistedConfig;
+use super::env_overrides::apply_to_document;
+use super::profiles::{PROFILE_KEYS, unknown_profile};
+use super::validate::config_schema;
+
+/// System-wide config, read before the per-user `config.yaml`.
+pub const SYSTEM_CONFIG_PATH: &str = "/etc/axon/config.yaml";
+
+/// Replaces [`SYSTEM_CONFIG_PATH`]; an empty value skips the system layer.
+pub const SYSTEM_CONFIG_ENV: &str = "AXON_SYSTEM_CONFIG";
+
+/// The layer an effective config value came from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "layer", rename_all = "snake_case")]
+pub enum ConfigSource {
+    Default,
+    System { path: PathBuf },
+    User { path: PathBuf },
+    Profile { name: String },
+    Env { var: String },
+}
+
+impl fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigSource::Default => write!(f, "default"),
+            ConfigSource::System { path } => write!(f, "system {}", path.display()),
+            ConfigSource::User { path } => write!(f, "user {}", path.display()),
+            ConfigSource::Profile { name } => write!(f, "profile {name}"),
+            ConfigSource::Env { var } => write!(f, "env {var}"),
+        }
+    }
+}
+
+/// The merged config and, per key, the layer that set it.
+#[derive(Debug, Clone)]
+pub struct LayeredConfig {
+    pub config: PersistedConfig,
+    /// Every key in [`config_schema`] by dotted path (`rate_limit.bytes_per_sec`);
+    /// lists and maps such as `peers` and `policy.peers` count as one key.
+    pub provenance: BTreeMap<String, ConfigSource>,
+}
+
+/// The system config path, honoring [`SYSTEM_CONFIG_ENV`].
+pub fn system_config_path() -> Option<PathBuf> {
+    match std::env::var_os(SYSTEM_CONFIG_ENV) {
+        Some(path) if path.is_empty() => None,
+        Some(path) => Some(PathBuf::from(path)),
+        None => Some(PathBuf::from(SYSTEM_CONFIG_PATH)),
+    }
+}
+
+/// Merge `system`, then `user`, then the overrides of `profile`, then
+/// `AXON_*` variables from `vars`. A later layer replaces a key set by an
+/// earlier one; sections merge key by key, while lists and maps are replaced
+/// whole. Missing files are empty layers.
+pub async fn load_layered(
+    system: Option<&Path>,
+    user: &Path,
+    profile: Option<&str>,
+    vars: impl IntoIterator<Item = (String, String)>,
+) -> Result<LayeredConfig> {
+    let schema = config_schema();
+    let system_layer = match system {
+        Some(path) => read_layer(path, &schema).await?,
+        None => Mapping::new(),
+    };
+    let user_layer = read_layer(user, &schema).await?;
+
+    let mut document = system_layer.clone();
+    merge(&mut document, &user_layer, &schema);
+    let profile_keys = match profile {
+        Some(name) => apply_profile(&mut document, name)?,
+        None => Vec::new(),
+    };
+    let applied = apply_to_document(&mut document, vars)?;
+
+    let mut provenance = BTreeMap::new();
+    for key in leaf_keys(&schema) {
+        let path: Vec<&str> = key.split('.').collect();
+        let source = if lookup(&document, &path).is_none() {
+            ConfigSource::Default
+        } else if let Some((var, _)) = applied.iter().rev().find(|(_, set)| covers(set, &path)) {
+            ConfigSource::Env { var: var.clone() }
+        } else if let Some(name) = profile.filter(|_| profile_keys.contains(&key)) {
+            ConfigSource::Profile {
+                name: name.to_string(),
+            }
+        } else if lookup(&user_layer, &path).is_some() {
+            ConfigSource::User {
+                path: user.to_path_buf(),
+            }
+        } else {
+            ConfigSource::System {
+                path: system.map(Path::to_path_buf).unwrap_or_default(),
+            }
+        };
+        provenance.insert(key, source);
+    }
+
+    let config = serde_yaml::from_value(Value::Mapping(document)).with_context(|| {
+        let names: Vec<&str> = applied.iter().map(|(name, _)| name.as_str()).collect();
+        format!("invalid config from environment ({})", names.join(", "))
+    })?;
+    Ok(LayeredConfig { config, provenance })
+}
+
+/// A config file as a mapping of the keys it sets; unknown keys are dropped.
+async fn read_layer(path: &Path, schema: &JsonValue) -> Result<Mapping> {
+    let raw = match tokio::fs::read_to_string(path).await {
+        Ok(raw) => raw,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(Mapping::new()),
+        Err(err) => {
+            return Err(err).with_context(|| format!("failed to read config: {}", path.display()));
+        }
+    };
+    let parsed = serde_yaml::from_str::<PersistedConfig>(&raw)
+        .with_context(|| format!("failed to parse config: {}", path.display()))?;
+    let Value::Mapping(mut normalized) =
+        serde_yaml::to_value(parsed).context("failed to serialize config")?
+    else {
+        return Ok(Mapping::new());
+    };
+    // Serializing fills in keys the file left out, such as `peers: []`;
+    // those must not hide the same key in an earlier layer.
+    match serde_yaml::from_str::<Value>(&raw) {
+        Ok(Value::Mapping(written)) => retain_written(&mut normalized, &written, schema),
+        _ => normalized.clear(),
+    }
+    Ok(normalized)
+}
+
+/// Copy the keys `profiles.<name>` sets over the top-level ones; returns
+/// those keys.
+fn apply_profile(document: &mut Mapping, name: &str) -> Result<Vec<String>> {
+    let profiles = document.get("profiles").and_then(Value::as_mapping);
+    let Some(overrides) = profiles
+        .and_then(|profiles| profiles.get(name))
+        .and_then(Value::as_mapping)
+        .cloned()
+    else {
+        let defined: Vec<&str> = profiles
+            .map(|profiles| profiles.keys().filter_map(Value::as_str).collect())
+            .unwrap_or_default();
+        bail!(unknown_profile(name, &defined));
+    };
+    let mut keys = Vec::new();
+    for key in PROFILE_KEYS {
+        if let Some(value) = overrides.get(key) {
+            document.insert(Value::from(key), value.clone());
+            keys.push(key.to_string());
+        }
+    }
+    Ok(keys)
+}
+
+fn retain_written(normalized: &mut Mapping, written: &Mapping, schema: &JsonValue) {
+    normalized.retain(|key, value| {
+        let Some(written_value) = written.get(key) else {
+            return false;
+        };
+        let child_schema = key.as_str().and_then(|key| schema["properties"].get(key));
+        if let (Value::Mapping(section), Value::Mapping(written_section), Some(child_schema)) =
+            (value, written_value, child_schema)
+            && child_schema.get("properties").is_some()
+        {
+            retain_written(section, written_section, child_schema);
+        }
+        true
+    });
+}
+
+fn merge(base: &mut Mapping, over: &Mapping, schema: &JsonValue) {
+    for (key, value) in over {
+        let child_schema = key.as_str().and_then(|key| schema["properties"].get(key));
+        let is_section = child_schema.is_some_and(|child| child.get("properties").is_some());
+        match (base.get_mut(key), value) {
+            (Some(Value::Mapping(base_section)), Value::Mapping(over_section)) if is_section => {
+                merge(
+                    base_section,
+                    over_section,
+                    child_schema.unwrap_or(&JsonValue::Null),
+                );
+            }
+            _ => {
+                base.insert(key.clone(), value.clone());
+            }
+        }
+    }
+}
+
+fn leaf_keys(schema: &JsonValue) -> Vec<String> {
+    let mut keys = Vec::new();
+    if let Some(properties) = schema["properties"].as_object() {
+        for (key, child) in properties {
+            match child["properties"].as_object() {
+                Some(fields) => keys.extend(fields.keys().map(|field| format!("{key}.{field}"))),
+                None => keys.push(key.clone()),
+            }
+        }
+    }
+    keys
+}
+
+/// Whether setting `set` also set the key at `path`.
+fn covers(set: &[String], path: &[&str]) -> bool {
+    set.len() <= path.len() && set.iter().zip(path).all(|(a, b)| a == b)
+}
+
+fn lookup<'a>(document: &'a Mapping, path: &[&str]) -> Option<&'a Value> {
+    let (first, rest) = path.split_first()?;
+    let value = document.get(*first)?;
+    if rest.is_empty() {
+        return Some(value);
+    }
+    match value {
+        Value::Mapping(section) => lookup(section, rest),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+#[path = "layers_tests.rs"]
+mod tests;
@@ -0,0 +1,115 @@
+use super::*;
+
+fn vars(list: &[(&str, &str)]) -> Vec<(String, String)> {
+    list.iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect()
+}
+
+#[tokio::test]
+async fn later_layers_win_and_sections_merge_by_key() {
+    let dir = tempfile::tempdir().unwrap();
+    let system = dir.path().join("system.yaml");
+    let user = dir.path().join("user.yaml");
+    std::fs::write(
+        &system,
+        "name: fleet\nport: 7000\nrate_limit:\n  messages_per_sec: 10\n  bytes_per_sec: 1000\n",
+    )
+    .unwrap();
+    std::fs::write(&user, "port: 7100\nrate_limit:\n  bytes_per_sec: 2000\n").unwrap();
+
+    let layered = load_layered(Some(&system), &user, None, vars(&[("AXON_PORT", "7200")]))
+        .await
+        .unwrap();
+    assert_eq!(layered.config.name.as_deref(), Some("fleet"));
+    assert_eq!(layered.config.port, Some(7200));
+    assert_eq!(layered.config.rate_limit.messages_per_sec, Some(10));
+    assert_eq!(layered.config.rate_limit.bytes_per_sec, Some(2000));
+
+    let source = |key: &str| layered.provenance[key].clone();
+    assert_eq!(
+        source("name"),
+        ConfigSource::System {
+            path: system.clone()
+        }
+    );
+    assert_eq!(
+        source("port"),
+        ConfigSource::Env {
+            var: "AXON_PORT".into()
+        }
+    );
+    assert_eq!(
+        source("rate_limit.messages_per_sec"),
+        ConfigSource::System { path: system }
+    );
+    assert_eq!(
+        source("rate_limit.bytes_per_sec"),
+        ConfigSource::User { path: user }
+    );
+    assert_eq!(source("history.enabled"), ConfigSource::Default);
+    assert_eq!(source("peers"), ConfigSource::Default);
+}
+
+#[tokio::test]
+async fn missing_files_are_empty_layers() {
+    let dir = tempfile::tempdir().unwrap();
+    let layered = load_layered(
+        Some(&dir.path().join("absent-system.yaml")),
+        &dir.path().join("absent-user.yaml"),
+        None,
+        Vec::new(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(layered.config.port, None);
+    assert!(
+        layered
+            .provenance
+            .values()
+            .all(|source| *source == ConfigSource::Default)
+    );
+}
+
+#[tokio::test]
+async fn lists_are_replaced_and_unset_env_falls_back_to_default() {
+    let dir = tempfile::tempdir().unwrap();
+    let system = dir.path().join("system.yaml");
+    let user = dir.path().join("user.yaml");
+    std::fs::write(
+        &system,
+        "aliases:\n  build: ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa\n",
+    )
+    .unwrap();
+    std::fs::write(
+        &user,
+        "name: me\naliases:\n  ci: ed25519.bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb\n",
+    )
+    .unwrap();
+
+    let layered = load_layered(Some(&system), &user, None, vars(&[("AXON_NAME", "")]))
+        .await
+        .unwrap();
+    assert_eq!(
+        layered.config.aliases.keys().collect::<Vec<_>>(),
+        vec!["ci"]
+    );
+    assert_eq!(layered.config.name, None);
+    assert_eq!(layered.provenance["name"], ConfigSource::Default);
+}
+
+#[tokio::test]
+async fn a_broken_system_file_is_reported_by_path() {
+    let dir = tempfile::tempdir().unwrap();
+    let system = dir.path().join("system.yaml");
+    std::fs::write(&system, "port: [").unwrap();
+    let err = load_layered(
+        Some(&system),
+        &dir.path().join("user.yaml"),
+        None,
+        Vec::new(),
+    )
+    .await
+    .unwrap_err();
+    assert!(format!("{err:#}").contains("system.yaml"), "{err:#}");
+}
@@ -0,0 +1,162 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::message::MAX_MESSAGE_SIZE;
+
+/// Per-peer inbound rate limits (`rate_limit:` section of `config.yaml`).
+///
+/// Unset or zero values disable the corresponding limit.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, Default, PartialEq, Eq)]
+pub struct RateLimitConfig {
+    /// Messages each peer may send per second, with a one-second burst.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub messages_per_sec: Option<u32>,
+    /// Envelope bytes each peer may send per second, with a one-second burst.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bytes_per_sec: Option<u64>,
+}
+
+impl RateLimitConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn messages_per_sec(&self) -> Option<u32> {
+        self.messages_per_sec.filter(|n| *n > 0)
+    }
+
+    pub fn bytes_per_sec(&self) -> Option<u64> {
+        self.bytes_per_sec.filter(|n| *n > 0)
+    }
+}
+
+/// Per-kind caps on encoded envelope size (`max_message_bytes:` section of
+/// `config.yaml`).
+///
+/// Unset or zero values mean the 64KB wire limit; others are clamped to
+/// [`MIN_BYTES`](Self::MIN_BYTES) and the wire limit.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, Default, PartialEq, Eq)]
+pub struct MaxMessageBytesConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<u32>,
+}
+
+impl MaxMessageBytesConfig {
+    /// Smallest cap, so daemon-generated `error` replies always fit.
+    pub const MIN_BYTES: u32 = 1024;
+
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// `(key, value)` for each configured cap, in file order.
+    pub fn entries(&self) -> [(&'static str, Option<u32>); 4] {
+        [
+            ("request", self.request),
+            ("response", self.response),
+            ("message", self.message),
+            ("error", self.error),
+        ]
+    }
+
+    /// Effective cap for a configured value, or `None` for the wire limit.
+    pub fn effective(value: Option<u32>) -> Option<usize> {
+        value
+            .filter(|n| *n > 0)
+            .map(|n| n.clamp(Self::MIN_BYTES, MAX_MESSAGE_SIZE) as usize)
+    }
+}
+
+/// Response cache for deduplicating retried requests (`idempotency:` section
+/// of `config.yaml`).
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, Default, PartialEq, Eq)]
+pub struct IdempotencyConfig {
+    /// Seconds a response stays cached; `0` disables the cache.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub window_secs: Option<u64>,
+    /// Cached responses kept across all peers; the oldest is evicted first.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_entries: Option<usize>,
+}
+
+impl IdempotencyConfig {
+    pub const DEFAULT_WINDOW: Duration = Duration::from_secs(300);
+    pub const DEFAULT_MAX_ENTRIES: usize = 1024;
+
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn window(&self) -> Option<Duration> {
+        match self.window_secs {
+            Some(0) => None,
+            Some(secs) => Some(Duration::from_secs(secs)),
+            None => Some(Self::DEFAULT_WINDOW),
+        }
+    }
+
+    pub fn max_entries(&self) -> usize {
+        self.max_entries
+            .filter(|n| *n > 0)
+            .unwrap_or(Self::DEFAULT_MAX_ENTRIES)
+    }
+}
+
+/// Temporary bans for source addresses whose inbound handshakes keep failing
+/// (`handshake_ban:` section of `config.yaml`).
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, Default, PartialEq, Eq)]
+pub struct HandshakeBanConfig {
+    /// Failed handshakes within `window_secs` that ban the address; `0`
+    /// disables banning.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_failures: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub window_secs: Option<u64>,
+    /// First ban length; repeat bans double it up to `max_ban_secs`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ban_secs: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_ban_secs: Option<u64>,
+}
+
+impl HandshakeBanConfig {
+    pub const DEFAULT_MAX_FAILURES: u32 = 10;
+    pub const DEFAULT_WINDOW: Duration = Duration::from_secs(60);
+    pub const DEFAULT_BAN: Duration = Duration::from_secs(60);
+    pub const DEFAULT_MAX_BAN: Duration = Duration::from_secs(3600);
+
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn max_failures(&self) -> u32 {
+        self.max_failures.unwrap_or(Self::DEFAULT_MAX_FAILURES)
+    }
+
+    pub fn window(&self) -> Duration {
+        self.window_secs
+            .filter(|secs| *secs > 0)
+            .map_or(Self::DEFAULT_WINDOW, Duration::from_secs)
+    }
+
+    pub fn ban(&self) -> Duration {
+        self.ban_secs
+            .filter(|secs| *secs > 0)
+            .map_or(Self::DEFAULT_BAN, Duration::from_secs)
+    }
+
+    /// Never shorter than the first ban.
+    pub fn max_ban(&self) -> Duration {
+        self.max_ban_secs
+            .filter(|secs| *secs > 0)
+            .map_or(Self::DEFAULT_MAX_BAN, Duration::from_secs)
+            .max(self.ban())
+    }
+}
@@ -0,0 +1,65 @@
+use std::env;
+use std::io::ErrorKind;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+use super::{PeerAddr, PersistedConfig, PersistedStaticPeerConfig, StaticPeerConfig};
+use crate::message::AgentId;
+
+/// The process environment as UTF-8 pairs, for [`load_layered`](super::load_layered).
+pub fn env_vars() -> Vec<(String, String)> {
+    env::vars_os()
+        .filter_map(|(name, value)| Some((name.into_string().ok()?, value.into_string().ok()?)))
+        .collect()
+}
+
+pub async fn load_persisted_config(path: &Path) -> Result<PersistedConfig> {
+    let raw = match tokio::fs::read_to_string(path).await {
+        Ok(raw) => raw,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(PersistedConfig::default()),
+        Err(err) => {
+            return Err(err).with_context(|| format!("failed to read config: {}", path.display()));
+        }
+    };
+    let parsed = serde_yaml::from_str::<PersistedConfig>(&raw)
+        .with_context(|| format!("failed to parse config: {}", path.display()))?;
+    Ok(parsed)
+}
+
+pub async fn save_persisted_config(path: &Path, config: &PersistedConfig) -> Result<()> {
+    if let Some(parent) = path.parent()
+        && !parent.exists()
+    {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .with_context(|| format!("failed to create directory: {}", parent.display()))?;
+    }
+
+    let serialized = serde_yaml::to_string(config)
+        .with_context(|| format!("failed to serialize config: {}", path.display()))?;
+    tokio::fs::write(path, serialized)
+        .await
+        .with_context(|| format!("failed to write config: {}", path.display()))?;
+    Ok(())
+}
+
+pub async fn append_static_peer(path: &Path, peer: PersistedStaticPeerConfig) -> Result<()> {
+    let mut config = load_persisted_config(path).await?;
+    config.peers.push(peer);
+    save_persisted_config(path, &config).await
+}
+
+pub async fn resolve_static_peer(
+    agent_id: AgentId,
+    addr: &str,
+    pubkey: String,
+) -> Result<StaticPeerConfig> {
+    let addr = PeerAddr::parse(addr)?;
+    let resolved = addr.resolve_for_config_load().await?;
+    Ok(StaticPeerConfig {
+        agent_id,
+        addr: resolved,
+        pubkey,
+    })
+}
@@ -0,0 +1,68 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+/// Where daemon logs go (`logging:` section of `config.yaml`).
+#[derive(Debug, Clone, Deserialize, Serialize, Default, PartialEq, Eq)]
+pub struct LoggingConfig {
+    /// Log sink; unset writes human-readable lines to stdout.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<LogTarget>,
+    /// JSON-lines file for `target: file`; relative paths are under the
+    /// state root.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+    /// Rotate the log file once it would grow past this many bytes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_file_bytes: Option<u64>,
+    /// Rotated log files kept (`axon.log.1` … `axon.log.N`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_files: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LogTarget {
+    #[default]
+    Stdout,
+    /// systemd-journald native protocol, one journal field per event field.
+    Journald,
+    /// RFC 5424 messages on `/dev/log` with structured data.
+    Syslog,
+    /// One JSON object per line.
+    File,
+}
+
+impl LoggingConfig {
+    pub const DEFAULT_FILE_NAME: &str = "axon.log";
+    pub const DEFAULT_MAX_FILE_BYTES: u64 = 10 * 1024 * 1024;
+    pub const DEFAULT_MAX_FILES: usize = 5;
+
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn target(&self) -> LogTarget {
+        self.target.unwrap_or_default()
+    }
+
+    /// Log file for `target: file`, resolved against `root`.
+    pub fn file_path(&self, root: &Path) -> PathBuf {
+        match &self.path {
+            Some(path) => root.join(path),
+            None => root.join(Self::DEFAULT_FILE_NAME),
+        }
+    }
+
+    pub fn max_file_bytes(&self) -> u64 {
+        self.max_file_bytes
+            .filter(|bytes| *bytes > 0)
+            .unwrap_or(Self::DEFAULT_MAX_FILE_BYTES)
+    }
+
+    pub fn max_files(&self) -> usize {
+        self.max_files
+            .filter(|n| *n > 0)
+            .unwrap_or(Self::DEFAULT_MAX_FILES)
+    }
+}
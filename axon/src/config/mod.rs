@@ -1,88 +1,70 @@
-use std::env;
-use std::fs;
-use std::io::ErrorKind;
-use std::net::{SocketAddr, ToSocketAddrs};
-use std::os::unix::fs::PermissionsExt;
-use std::path::{Path, PathBuf};
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+use std::path::Path;
 
-use anyhow::{Context, Result, anyhow};
+use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use tracing::warn;
 
-use crate::message::AgentId;
-
-#[derive(Debug, Clone)]
-pub struct AxonPaths {
-    pub root: PathBuf,
-    pub identity_key: PathBuf,
-    pub identity_pub: PathBuf,
-    pub config: PathBuf,
-    pub known_peers: PathBuf,
-    pub socket: PathBuf,
-}
-
-impl AxonPaths {
-    pub fn discover_with_override(override_root: Option<&Path>) -> Result<Self> {
-        if let Some(root) = override_root {
-            return Ok(Self::from_root(root.to_path_buf()));
-        }
-
-        if let Ok(root) = env::var("AXON_ROOT")
-            && !root.trim().is_empty()
-        {
-            return Ok(Self::from_root(PathBuf::from(root)));
-        }
-
-        Self::discover()
-    }
-
-    pub fn discover() -> Result<Self> {
-        let home = env::var("HOME").context("HOME is not set")?;
-        let root = Path::new(&home).join(".axon");
-        Ok(Self::from_root(root))
-    }
-
-    pub fn from_root(root: PathBuf) -> Self {
-        Self {
-            identity_key: root.join("identity.key"),
-            identity_pub: root.join("identity.pub"),
-            config: root.join("config.yaml"),
-            known_peers: root.join("known_peers.json"),
-            socket: root.join("axon.sock"),
-            root,
-        }
-    }
-
-    pub fn ensure_root_exists(&self) -> Result<()> {
-        if self.root.exists() {
-            // Reject symlinked root directory (security: IPC.md §2.2)
-            let meta = fs::symlink_metadata(&self.root).with_context(|| {
-                format!(
-                    "failed to read metadata for AXON root: {}",
-                    self.root.display()
-                )
-            })?;
-            if meta.file_type().is_symlink() {
-                anyhow::bail!(
-                    "AXON root directory is a symlink (security violation): {}. \
-                     Remove the symlink and restart.",
-                    self.root.display()
-                );
-            }
-        } else {
-            fs::create_dir_all(&self.root).with_context(|| {
-                format!("failed to create AXON root dir: {}", self.root.display())
-            })?;
-        }
-        fs::set_permissions(&self.root, fs::Permissions::from_mode(0o700)).with_context(|| {
-            format!(
-                "failed to set AXON dir permissions: {}",
-                self.root.display()
-            )
-        })?;
-        Ok(())
-    }
-}
+use crate::message::{AgentId, MessageKind};
+
+mod blocklist;
+mod env_overrides;
+mod event_log;
+#[cfg(feature = "script")]
+mod handler_script;
+mod identity;
+mod known_peers;
+mod layers;
+mod limits;
+mod loading;
+mod logging;
+mod paths;
+mod peer_addr;
+mod peer_tuning;
+mod prewarm;
+mod profiles;
+mod revocations;
+mod sections;
+mod transforms;
+mod transport;
+mod validate;
+mod webhooks;
+pub use blocklist::{
+    BlockedPeer, add_block, blocked_keys, load_blocklist, remove_block, save_blocklist,
+};
+pub use env_overrides::{ENV_PREFIX, apply_env_overrides};
+pub use event_log::EventLogConfig;
+#[cfg(feature = "script")]
+pub use handler_script::{HANDLER_SCRIPT_ENTRY, compile_handler_script};
+pub use identity::{IdentityConfig, IdentityStorage};
+pub use known_peers::{KnownPeer, KnownPeerSource, load_known_peers, save_known_peers};
+pub use layers::{
+    ConfigSource, LayeredConfig, SYSTEM_CONFIG_ENV, SYSTEM_CONFIG_PATH, load_layered,
+    system_config_path,
+};
+pub use limits::{HandshakeBanConfig, IdempotencyConfig, MaxMessageBytesConfig, RateLimitConfig};
+pub use loading::{
+    append_static_peer, env_vars, load_persisted_config, resolve_static_peer, save_persisted_config,
+};
+pub use logging::{LogTarget, LoggingConfig};
+pub use paths::AxonPaths;
+pub use peer_addr::PeerAddr;
+pub use peer_tuning::PeerTuningConfig;
+pub use prewarm::PrewarmConfig;
+pub use profiles::{MAX_PROFILE_NAME_LEN, PROFILE_ENV, ProfileConfig, is_valid_profile_name};
+pub use revocations::{
+    RevokedKey, add_revocation, load_revocations, revoked_pubkeys, save_revocations,
+};
+pub use sections::{
+    AuditConfig, HandlerConfig, HeartbeatConfig, HistoryConfig, IpcConfig, PeerPolicyConfig,
+    PolicyConfig, ShutdownConfig, StalenessConfig,
+};
+pub use transforms::TransformConfig;
+pub use transport::{CongestionControl, TransportConfig};
+pub(crate) use validate::is_agent_id;
+pub use validate::{ConfigIssue, ConfigReport, Severity, config_schema, validate_config_file};
+pub use webhooks::{WebhookConfig, WebhookUrl};
 
 #[derive(Debug, Clone, Deserialize, Serialize, Default)]
 pub struct Config {
@@ -94,117 +76,95 @@ pub struct Config {
     pub advertise_addr: Option<String>,
     #[serde(default)]
     pub peers: Vec<StaticPeerConfig>,
+    #[serde(default, skip_serializing_if = "IpcConfig::is_default")]
+    pub ipc: IpcConfig,
+    #[serde(default, skip_serializing_if = "HandlerConfig::is_default")]
+    pub handler: HandlerConfig,
+    #[serde(default, skip_serializing_if = "HistoryConfig::is_default")]
+    pub history: HistoryConfig,
+    #[serde(default, skip_serializing_if = "IdentityConfig::is_default")]
+    pub identity: IdentityConfig,
+    #[serde(default, skip_serializing_if = "AuditConfig::is_default")]
+    pub audit: AuditConfig,
+    #[serde(default, skip_serializing_if = "EventLogConfig::is_default")]
+    pub event_log: EventLogConfig,
+    #[serde(default, skip_serializing_if = "RateLimitConfig::is_default")]
+    pub rate_limit: RateLimitConfig,
+    #[serde(default, skip_serializing_if = "ShutdownConfig::is_default")]
+    pub shutdown: ShutdownConfig,
+    #[serde(default, skip_serializing_if = "IdempotencyConfig::is_default")]
+    pub idempotency: IdempotencyConfig,
+    #[serde(default, skip_serializing_if = "HandshakeBanConfig::is_default")]
+    pub handshake_ban: HandshakeBanConfig,
+    #[serde(default, skip_serializing_if = "TransportConfig::is_default")]
+    pub transport: TransportConfig,
+    #[serde(default, skip_serializing_if = "HeartbeatConfig::is_default")]
+    pub heartbeat: HeartbeatConfig,
+    #[serde(default, skip_serializing_if = "PrewarmConfig::is_default")]
+    pub prewarm: PrewarmConfig,
+    #[serde(default, skip_serializing_if = "StalenessConfig::is_default")]
+    pub staleness: StalenessConfig,
+    #[serde(default, skip_serializing_if = "MaxMessageBytesConfig::is_default")]
+    pub max_message_bytes: MaxMessageBytesConfig,
+    #[serde(default, skip_serializing_if = "PolicyConfig::is_default")]
+    pub policy: PolicyConfig,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub webhooks: Vec<WebhookConfig>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub transforms: Vec<TransformConfig>,
+    #[serde(default, skip_serializing_if = "LoggingConfig::is_default")]
+    pub logging: LoggingConfig,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub aliases: BTreeMap<String, AgentId>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub tags: BTreeMap<AgentId, Vec<String>>,
     #[serde(skip)]
     pub persisted_peers: Vec<PersistedStaticPeerConfig>,
+    /// The profile this config was loaded for.
+    #[serde(skip)]
+    pub profile: Option<String>,
 }
 
 impl Config {
+    /// Load the system config, then `path`, then `AXON_*` environment
+    /// overrides (see [`load_layered`]).
     pub async fn load(path: &Path) -> Result<Self> {
-        let persisted = load_persisted_config(path).await?;
-        Ok(persisted.resolve(path).await)
+        Self::load_profile(path, None).await
     }
 
-    pub fn effective_port(&self, cli_override: Option<u16>) -> u16 {
-        cli_override.or(self.port).unwrap_or(7100)
-    }
-}
-
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub enum PeerAddr {
-    Socket(SocketAddr),
-    Host { host: String, port: u16 },
-}
-
-impl PeerAddr {
-    fn resolve_host(host: &str, port: u16) -> Result<SocketAddr> {
-        let addrs: Vec<SocketAddr> = (host, port)
-            .to_socket_addrs()
-            .with_context(|| format!("failed to resolve '{host}:{port}'"))?
-            .collect();
-        if let Some(addr) = addrs.iter().copied().find(SocketAddr::is_ipv4) {
-            return Ok(addr);
-        }
-        addrs
-            .into_iter()
-            .next()
-            .ok_or_else(|| anyhow!("resolution returned no addresses for '{host}:{port}'"))
+    /// Like [`load`](Self::load), with the overrides of `profile` applied.
+    pub async fn load_profile(path: &Path, profile: Option<&str>) -> Result<Self> {
+        let layered =
+            load_layered(system_config_path().as_deref(), path, profile, env_vars()).await?;
+        let mut config = layered.config.resolve(path).await;
+        config.profile = profile.map(str::to_string);
+        Ok(config)
     }
 
-    pub fn parse(input: &str) -> Result<Self> {
-        if let Ok(addr) = input.parse::<SocketAddr>() {
-            return Ok(Self::Socket(addr));
-        }
-
-        let (host, port) = input
-            .rsplit_once(':')
-            .ok_or_else(|| anyhow!("expected host:port or ip:port"))?;
-        if host.is_empty() {
-            anyhow::bail!("host cannot be empty");
-        }
-
-        let port = port
-            .parse::<u16>()
-            .with_context(|| format!("invalid port '{port}'"))?;
-        Ok(Self::Host {
-            host: host.to_string(),
-            port,
-        })
+    /// The config for `paths`, honoring its profile.
+    pub async fn load_for(paths: &AxonPaths) -> Result<Self> {
+        Self::load_profile(&paths.config, paths.profile.as_deref()).await
     }
 
-    pub fn resolve(&self) -> Result<SocketAddr> {
-        match self {
-            PeerAddr::Socket(addr) => Ok(*addr),
-            PeerAddr::Host { host, port } => Self::resolve_host(host, *port),
-        }
+    /// Connection overrides from the static peer entry for `agent_id`.
+    pub fn peer_tuning(&self, agent_id: &str) -> PeerTuningConfig {
+        self.persisted_peers
+            .iter()
+            .find(|peer| peer.agent_id.as_str() == agent_id)
+            .map(|peer| peer.tuning)
+            .unwrap_or_default()
     }
 
-    pub async fn resolve_for_config_load(&self) -> Result<SocketAddr> {
-        match self {
-            PeerAddr::Socket(addr) => Ok(*addr),
-            PeerAddr::Host { host, port } => {
-                let host_for_lookup = host.clone();
-                let host_for_error = host.clone();
-                let port = *port;
-                tokio::task::spawn_blocking(move || Self::resolve_host(&host_for_lookup, port))
-                    .await
-                    .map_err(|err| {
-                        anyhow!(
-                            "hostname resolution task failed for '{host_for_error}:{port}': {err}"
-                        )
-                    })?
-            }
-        }
-    }
-}
-
-impl std::fmt::Display for PeerAddr {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            PeerAddr::Socket(addr) => write!(f, "{addr}"),
-            PeerAddr::Host { host, port } => write!(f, "{host}:{port}"),
-        }
-    }
-}
-
-impl<'de> Deserialize<'de> for PeerAddr {
-    fn deserialize<D: serde::Deserializer<'de>>(
-        deserializer: D,
-    ) -> std::result::Result<Self, D::Error> {
-        let raw = String::deserialize(deserializer)?;
-        Self::parse(&raw).map_err(serde::de::Error::custom)
+    pub fn effective_port(&self, cli_override: Option<u16>) -> u16 {
+        cli_override.or(self.port).unwrap_or(7100)
     }
 }
 
-impl Serialize for PeerAddr {
-    fn serialize<S: serde::Serializer>(
-        &self,
-        serializer: S,
-    ) -> std::result::Result<S::Ok, S::Error> {
-        match self {
-            PeerAddr::Socket(addr) => serializer.serialize_str(&addr.to_string()),
-            PeerAddr::Host { host, port } => serializer.serialize_str(&format!("{host}:{port}")),
-        }
-    }
+/// The application kind spelled `name`, if any.
+pub fn application_kind(name: &str) -> Option<MessageKind> {
+    serde_json::from_value::<MessageKind>(serde_json::Value::String(name.to_string()))
+        .ok()
+        .filter(|kind| kind.is_application())
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
@@ -227,6 +187,54 @@ pub struct PersistedConfig {
     pub advertise_addr: Option<String>,
     #[serde(default)]
     pub peers: Vec<PersistedStaticPeerConfig>,
+    #[serde(default, skip_serializing_if = "IpcConfig::is_default")]
+    pub ipc: IpcConfig,
+    #[serde(default, skip_serializing_if = "HandlerConfig::is_default")]
+    pub handler: HandlerConfig,
+    #[serde(default, skip_serializing_if = "HistoryConfig::is_default")]
+    pub history: HistoryConfig,
+    #[serde(default, skip_serializing_if = "IdentityConfig::is_default")]
+    pub identity: IdentityConfig,
+    #[serde(default, skip_serializing_if = "AuditConfig::is_default")]
+    pub audit: AuditConfig,
+    #[serde(default, skip_serializing_if = "EventLogConfig::is_default")]
+    pub event_log: EventLogConfig,
+    #[serde(default, skip_serializing_if = "RateLimitConfig::is_default")]
+    pub rate_limit: RateLimitConfig,
+    #[serde(default, skip_serializing_if = "ShutdownConfig::is_default")]
+    pub shutdown: ShutdownConfig,
+    #[serde(default, skip_serializing_if = "IdempotencyConfig::is_default")]
+    pub idempotency: IdempotencyConfig,
+    #[serde(default, skip_serializing_if = "HandshakeBanConfig::is_default")]
+    pub handshake_ban: HandshakeBanConfig,
+    #[serde(default, skip_serializing_if = "TransportConfig::is_default")]
+    pub transport: TransportConfig,
+    #[serde(default, skip_serializing_if = "HeartbeatConfig::is_default")]
+    pub heartbeat: HeartbeatConfig,
+    #[serde(default, skip_serializing_if = "PrewarmConfig::is_default")]
+    pub prewarm: PrewarmConfig,
+    #[serde(default, skip_serializing_if = "StalenessConfig::is_default")]
+    pub staleness: StalenessConfig,
+    #[serde(default, skip_serializing_if = "MaxMessageBytesConfig::is_default")]
+    pub max_message_bytes: MaxMessageBytesConfig,
+    #[serde(default, skip_serializing_if = "PolicyConfig::is_default")]
+    pub policy: PolicyConfig,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub webhooks: Vec<WebhookConfig>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub transforms: Vec<TransformConfig>,
+    #[serde(default, skip_serializing_if = "LoggingConfig::is_default")]
+    pub logging: LoggingConfig,
+    /// CLI shorthand names for agent IDs (`axon alias`); unused by the daemon.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub aliases: BTreeMap<String, AgentId>,
+    /// Free-form labels per peer (`axon peers tag`), reported and filterable
+    /// in `peers`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub tags: BTreeMap<AgentId, Vec<String>>,
+    /// Named overrides chosen with `axon --profile <name>`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, ProfileConfig>,
 }
 
 impl PersistedConfig {
@@ -257,7 +265,29 @@ impl PersistedConfig {
             port: self.port,
             advertise_addr: self.advertise_addr,
             peers,
+            ipc: self.ipc,
+            handler: self.handler,
+            history: self.history,
+            identity: self.identity,
+            audit: self.audit,
+            event_log: self.event_log,
+            rate_limit: self.rate_limit,
+            shutdown: self.shutdown,
+            idempotency: self.idempotency,
+            handshake_ban: self.handshake_ban,
+            transport: self.transport,
+            heartbeat: self.heartbeat,
+            prewarm: self.prewarm,
+            staleness: self.staleness,
+            max_message_bytes: self.max_message_bytes,
+            policy: self.policy,
+            webhooks: self.webhooks,
+            transforms: self.transforms,
+            logging: self.logging,
+            aliases: self.aliases,
+            tags: self.tags,
             persisted_peers,
+            profile: None,
         }
     }
 }
@@ -267,126 +297,36 @@ pub struct PersistedStaticPeerConfig {
     pub agent_id: AgentId,
     pub addr: PeerAddr,
     pub pubkey: String,
+    #[serde(flatten)]
+    pub tuning: PeerTuningConfig,
 }
 
-pub async fn load_persisted_config(path: &Path) -> Result<PersistedConfig> {
-    let raw = match tokio::fs::read_to_string(path).await {
-        Ok(raw) => raw,
-        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(PersistedConfig::default()),
-        Err(err) => {
-            return Err(err).with_context(|| format!("failed to read config: {}", path.display()));
-        }
-    };
-    let parsed = serde_yaml::from_str::<PersistedConfig>(&raw)
-        .with_context(|| format!("failed to parse config: {}", path.display()))?;
-    Ok(parsed)
-}
-
-pub async fn save_persisted_config(path: &Path, config: &PersistedConfig) -> Result<()> {
-    if let Some(parent) = path.parent()
-        && !parent.exists()
-    {
-        tokio::fs::create_dir_all(parent)
-            .await
-            .with_context(|| format!("failed to create directory: {}", parent.display()))?;
-    }
-
-    let serialized = serde_yaml::to_string(config)
-        .with_context(|| format!("failed to serialize config: {}", path.display()))?;
-    tokio::fs::write(path, serialized)
-        .await
-        .with_context(|| format!("failed to write config: {}", path.display()))?;
-    Ok(())
-}
-
-pub async fn append_static_peer(path: &Path, peer: PersistedStaticPeerConfig) -> Result<()> {
-    let mut config = load_persisted_config(path).await?;
-    config.peers.push(peer);
-    save_persisted_config(path, &config).await
-}
-
-pub async fn resolve_static_peer(
-    agent_id: AgentId,
-    addr: &str,
-    pubkey: String,
-) -> Result<StaticPeerConfig> {
-    let addr = PeerAddr::parse(addr)?;
-    let resolved = addr.resolve_for_config_load().await?;
-    Ok(StaticPeerConfig {
-        agent_id,
-        addr: resolved,
-        pubkey,
-    })
+/// Longest accepted alias name.
+pub const MAX_ALIAS_LEN: usize = 64;
+
+/// Alias names start with a letter and contain only ASCII letters, digits,
+/// `-`, and `_`, so they can never be mistaken for an agent ID.
+pub fn is_valid_alias(name: &str) -> bool {
+    name.len() <= MAX_ALIAS_LEN
+        && name.starts_with(|c: char| c.is_ascii_alphabetic())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
-pub struct KnownPeer {
-    pub agent_id: AgentId,
-    pub addr: SocketAddr,
-    pub pubkey: String,
-    pub last_seen_unix_ms: u64,
-    pub source: KnownPeerSource,
-}
-
-#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
-#[serde(rename_all = "snake_case")]
-pub enum KnownPeerSource {
-    Static,
-    Discovered,
-    Cached,
-}
-
-#[allow(dead_code)]
-#[derive(Debug, Clone, Deserialize)]
-struct LegacyKnownPeer {
-    agent_id: AgentId,
-    addr: SocketAddr,
-    pubkey: String,
-    last_seen_unix_ms: u64,
-}
-
-pub async fn load_known_peers(path: &Path) -> Result<Vec<KnownPeer>> {
-    let raw = match tokio::fs::read_to_string(path).await {
-        Ok(raw) => raw,
-        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
-        Err(err) => {
-            return Err(err)
-                .with_context(|| format!("failed to read known peers: {}", path.display()));
-        }
-    };
-    let peers = match serde_json::from_str::<Vec<KnownPeer>>(&raw) {
-        Ok(peers) => peers,
-        Err(parse_err) => {
-            if serde_json::from_str::<Vec<LegacyKnownPeer>>(&raw).is_ok() {
-                warn!(
-                    path = %path.display(),
-                    "ignoring legacy known_peers cache without source metadata; cache will be rebuilt"
-                );
-                return Ok(Vec::new());
-            }
-            return Err(parse_err)
-                .with_context(|| format!("failed to parse known peers: {}", path.display()));
-        }
-    };
-    Ok(peers)
-}
-
-pub async fn save_known_peers(path: &Path, peers: &[KnownPeer]) -> Result<()> {
-    if let Some(parent) = path.parent()
-        && !parent.exists()
-    {
-        tokio::fs::create_dir_all(parent)
-            .await
-            .with_context(|| format!("failed to create directory: {}", parent.display()))?;
-    }
-
-    let data = serde_json::to_vec(peers).context("failed to encode known peers")?;
-    tokio::fs::write(path, data)
-        .await
-        .with_context(|| format!("failed to write known peers: {}", path.display()))?;
-    Ok(())
+/// Longest accepted peer tag.
+pub const MAX_TAG_LEN: usize = 64;
+
+/// Tags start with a letter or digit and contain only ASCII letters, digits,
+/// `.`, `-`, and `_`.
+pub fn is_valid_tag(tag: &str) -> bool {
+    tag.len() <= MAX_TAG_LEN
+        && tag.starts_with(|c: char| c.is_ascii_alphanumeric())
+        && tag
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'))
 }
 
 #[cfg(test)]
-#[path = "tests.rs"]
+#[path = "tests/mod.rs"]
 mod tests;
@@ -0,0 +1,94 @@
+use std::env;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+
+#[derive(Debug, Clone)]
+pub struct AxonPaths {
+    pub root: PathBuf,
+    pub identity_key: PathBuf,
+    pub identity_pub: PathBuf,
+    pub config: PathBuf,
+    pub known_peers: PathBuf,
+    pub revocations: PathBuf,
+    pub blocklist: PathBuf,
+    pub socket: PathBuf,
+    pub history: PathBuf,
+    pub audit: PathBuf,
+    pub events: PathBuf,
+    pub stats: PathBuf,
+    /// Set by [`AxonPaths::with_profile`].
+    pub profile: Option<String>,
+}
+
+impl AxonPaths {
+    pub fn discover_with_override(override_root: Option<&Path>) -> Result<Self> {
+        if let Some(root) = override_root {
+            return Ok(Self::from_root(root.to_path_buf()));
+        }
+
+        if let Ok(root) = env::var("AXON_ROOT")
+            && !root.trim().is_empty()
+        {
+            return Ok(Self::from_root(PathBuf::from(root)));
+        }
+
+        Self::discover()
+    }
+
+    pub fn discover() -> Result<Self> {
+        let home = env::var("HOME").context("HOME is not set")?;
+        let root = Path::new(&home).join(".axon");
+        Ok(Self::from_root(root))
+    }
+
+    pub fn from_root(root: PathBuf) -> Self {
+        Self {
+            identity_key: root.join("identity.key"),
+            identity_pub: root.join("identity.pub"),
+            config: root.join("config.yaml"),
+            known_peers: root.join("known_peers.json"),
+            revocations: root.join("revocations.json"),
+            blocklist: root.join("blocklist.json"),
+            socket: root.join("axon.sock"),
+            history: root.join("history.sqlite3"),
+            audit: root.join("audit.jsonl"),
+            events: root.join("events.jsonl"),
+            stats: root.join("stats.json"),
+            profile: None,
+            root,
+        }
+    }
+
+    pub fn ensure_root_exists(&self) -> Result<()> {
+        if self.root.exists() {
+            // Reject symlinked root directory (security: IPC.md §2.2)
+            let meta = fs::symlink_metadata(&self.root).with_context(|| {
+                format!(
+                    "failed to read metadata for AXON root: {}",
+                    self.root.display()
+                )
+            })?;
+            if meta.file_type().is_symlink() {
+                anyhow::bail!(
+                    "AXON root directory is a symlink (security violation): {}. \
+                     Remove the symlink and restart.",
+                    self.root.display()
+                );
+            }
+        } else {
+            fs::create_dir_all(&self.root).with_context(|| {
+                format!("failed to create AXON root dir: {}", self.root.display())
+            })?;
+        }
+        fs::set_permissions(&self.root, fs::Permissions::from_mode(0o700)).with_context(|| {
+            format!(
+                "failed to set AXON dir permissions: {}",
+                self.root.display()
+            )
+        })?;
+        Ok(())
+    }
+}
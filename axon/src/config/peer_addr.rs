@@ -0,0 +1,102 @@
+use std::net::{SocketAddr, ToSocketAddrs};
+
+use anyhow::{Context, Result, anyhow};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PeerAddr {
+    Socket(SocketAddr),
+    Host { host: String, port: u16 },
+}
+
+impl PeerAddr {
+    fn resolve_host(host: &str, port: u16) -> Result<SocketAddr> {
+        let addrs: Vec<SocketAddr> = (host, port)
+            .to_socket_addrs()
+            .with_context(|| format!("failed to resolve '{host}:{port}'"))?
+            .collect();
+        if let Some(addr) = addrs.iter().copied().find(SocketAddr::is_ipv4) {
+            return Ok(addr);
+        }
+        addrs
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("resolution returned no addresses for '{host}:{port}'"))
+    }
+
+    pub fn parse(input: &str) -> Result<Self> {
+        if let Ok(addr) = input.parse::<SocketAddr>() {
+            return Ok(Self::Socket(addr));
+        }
+
+        let (host, port) = input
+            .rsplit_once(':')
+            .ok_or_else(|| anyhow!("expected host:port or ip:port"))?;
+        if host.is_empty() {
+            anyhow::bail!("host cannot be empty");
+        }
+
+        let port = port
+            .parse::<u16>()
+            .with_context(|| format!("invalid port '{port}'"))?;
+        Ok(Self::Host {
+            host: host.to_string(),
+            port,
+        })
+    }
+
+    pub fn resolve(&self) -> Result<SocketAddr> {
+        match self {
+            PeerAddr::Socket(addr) => Ok(*addr),
+            PeerAddr::Host { host, port } => Self::resolve_host(host, *port),
+        }
+    }
+
+    pub async fn resolve_for_config_load(&self) -> Result<SocketAddr> {
+        match self {
+            PeerAddr::Socket(addr) => Ok(*addr),
+            PeerAddr::Host { host, port } => {
+                let host_for_lookup = host.clone();
+                let host_for_error = host.clone();
+                let port = *port;
+                tokio::task::spawn_blocking(move || Self::resolve_host(&host_for_lookup, port))
+                    .await
+                    .map_err(|err| {
+                        anyhow!(
+                            "hostname resolution task failed for '{host_for_error}:{port}': {err}"
+                        )
+                    })?
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for PeerAddr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PeerAddr::Socket(addr) => write!(f, "{addr}"),
+            PeerAddr::Host { host, port } => write!(f, "{host}:{port}"),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for PeerAddr {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Self, D::Error> {
+        let raw = String::deserialize(deserializer)?;
+        Self::parse(&raw).map_err(serde::de::Error::custom)
+    }
+}
+
+impl Serialize for PeerAddr {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        match self {
+            PeerAddr::Socket(addr) => serializer.serialize_str(&addr.to_string()),
+            PeerAddr::Host { host, port } => serializer.serialize_str(&format!("{host}:{port}")),
+        }
+    }
+}
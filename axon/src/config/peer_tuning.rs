@@ -0,0 +1,67 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use super::MaxMessageBytesConfig;
+use super::transport::{CongestionControl, initial_window};
+
+/// Connection overrides on one static peer entry (`peers[]` in
+/// `config.yaml`), for peers whose link differs from the rest, such as a WAN
+/// peer among LAN ones.
+///
+/// Unset or zero values keep the daemon default.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, Default, PartialEq, Eq)]
+pub struct PeerTuningConfig {
+    /// Seconds a QUIC handshake when dialing this peer may take.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub handshake_timeout_secs: Option<u64>,
+    /// Seconds without traffic before a dialed connection is closed. QUIC
+    /// uses the lower of both ends' values.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idle_timeout_secs: Option<u64>,
+    /// Longest wait between reconnect attempts.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reconnect_max_backoff_secs: Option<u64>,
+    /// Cap on encoded envelopes sent to or received from this peer, applied
+    /// on top of the per-kind `max_message_bytes` caps.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_message_bytes: Option<u32>,
+    /// Congestion controller for connections dialed to this peer.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub congestion_control: Option<CongestionControl>,
+    /// Initial congestion window, in bytes, for connections dialed to this
+    /// peer.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub initial_window_bytes: Option<u64>,
+}
+
+impl PeerTuningConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn handshake_timeout(&self) -> Option<Duration> {
+        secs(self.handshake_timeout_secs)
+    }
+
+    pub fn idle_timeout(&self) -> Option<Duration> {
+        secs(self.idle_timeout_secs)
+    }
+
+    pub fn reconnect_max_backoff(&self) -> Option<Duration> {
+        secs(self.reconnect_max_backoff_secs)
+    }
+
+    /// Effective cap, clamped like the per-kind caps.
+    pub fn max_message_bytes(&self) -> Option<usize> {
+        MaxMessageBytesConfig::effective(self.max_message_bytes)
+    }
+
+    pub fn initial_window(&self) -> Option<u64> {
+        initial_window(self.initial_window_bytes)
+    }
+}
+
+fn secs(value: Option<u64>) -> Option<Duration> {
+    value.filter(|secs| *secs > 0).map(Duration::from_secs)
+}
@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+
+/// Startup connection pre-warming (`prewarm:` section of `config.yaml`).
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, Default, PartialEq, Eq)]
+pub struct PrewarmConfig {
+    /// Dial every static and cached peer as soon as the transport is up,
+    /// instead of on the first reconnect tick.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    /// Startup dials in flight at once.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_concurrent: Option<usize>,
+}
+
+impl PrewarmConfig {
+    pub const DEFAULT_MAX_CONCURRENT: usize = 16;
+
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.enabled.unwrap_or(false)
+    }
+
+    pub fn max_concurrent(&self) -> usize {
+        self.max_concurrent
+            .filter(|n| *n > 0)
+            .unwrap_or(Self::DEFAULT_MAX_CONCURRENT)
+    }
+}
@@ -0,0 +1,159 @@
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, anyhow};
+use serde::{Deserialize, Serialize};
+
+use super::{AxonPaths, PersistedConfig, PersistedStaticPeerConfig, system_config_path};
+
+/// Selects a profile when `--profile` is not given.
+pub const PROFILE_ENV: &str = "AXON_PROFILE";
+
+/// Top-level keys a profile can replace.
+pub(super) const PROFILE_KEYS: [&str; 4] = ["name", "port", "advertise_addr", "peers"];
+
+/// Longest accepted profile name.
+pub const MAX_PROFILE_NAME_LEN: usize = 64;
+
+/// Overrides in `profiles.<name>` of `config.yaml`, chosen with
+/// `axon --profile <name>`. Unset fields keep the top-level value; every
+/// other setting is shared by all profiles.
+#[derive(Debug, Clone, Deserialize, Serialize, Default, PartialEq, Eq)]
+pub struct ProfileConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub advertise_addr: Option<String>,
+    /// Directory holding `identity.key` and `identity.pub`, relative to the
+    /// state root. Defaults to the profile's own state directory.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub identity_dir: Option<PathBuf>,
+    /// Replaces the top-level `peers` when set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub peers: Option<Vec<PersistedStaticPeerConfig>>,
+}
+
+/// Profile names start with a letter and contain only ASCII letters, digits,
+/// `-`, and `_`, so each maps to a plain directory name.
+pub fn is_valid_profile_name(name: &str) -> bool {
+    name.len() <= MAX_PROFILE_NAME_LEN
+        && name.starts_with(|c: char| c.is_ascii_alphabetic())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+pub(super) fn unknown_profile(name: &str, defined: &[&str]) -> anyhow::Error {
+    if defined.is_empty() {
+        anyhow!("unknown profile '{name}'; config.yaml defines no profiles")
+    } else {
+        anyhow!("unknown profile '{name}' (defined: {})", defined.join(", "))
+    }
+}
+
+impl PersistedConfig {
+    pub fn profile(&self, name: &str) -> Result<&ProfileConfig> {
+        self.profiles.get(name).ok_or_else(|| {
+            let defined: Vec<&str> = self.profiles.keys().map(String::as_str).collect();
+            unknown_profile(name, &defined)
+        })
+    }
+
+    /// The static peers `profile` connects to (the top-level `peers` without
+    /// one).
+    pub fn static_peers(&self, profile: Option<&str>) -> &[PersistedStaticPeerConfig] {
+        profile
+            .and_then(|name| self.profiles.get(name))
+            .and_then(|overrides| overrides.peers.as_deref())
+            .unwrap_or(&self.peers)
+    }
+
+    /// The list new static peers of `profile` are written to. A profile that
+    /// inherits the top-level peers gets its own copy of them first, so
+    /// adding one does not hide the rest.
+    pub fn static_peers_mut(
+        &mut self,
+        profile: Option<&str>,
+    ) -> Result<&mut Vec<PersistedStaticPeerConfig>> {
+        let Some(name) = profile else {
+            return Ok(&mut self.peers);
+        };
+        self.profile(name)?;
+        let inherited = self.peers.clone();
+        let overrides = self.profiles.entry(name.to_string()).or_default();
+        Ok(overrides.peers.get_or_insert(inherited))
+    }
+
+    /// Every static peer list: the top-level one and each profile's own.
+    pub fn all_static_peers_mut(
+        &mut self,
+    ) -> impl Iterator<Item = &mut Vec<PersistedStaticPeerConfig>> {
+        std::iter::once(&mut self.peers).chain(
+            self.profiles
+                .values_mut()
+                .filter_map(|overrides| overrides.peers.as_mut()),
+        )
+    }
+}
+
+impl AxonPaths {
+    /// Paths for `profile`: its socket, caches, and logs under
+    /// `<root>/profiles/<name>/`, so profiles can run side by side, and the
+    /// shared `config.yaml`. The profile must be defined in the user or
+    /// system config.
+    pub fn with_profile(self, profile: &str) -> Result<Self> {
+        if !is_valid_profile_name(profile) {
+            anyhow::bail!(
+                "invalid profile name '{profile}': use letters, digits, '-', and '_', starting with a letter"
+            );
+        }
+        let overrides = find_profile(&self.config, profile)?;
+        let base_root = self.root;
+        let mut paths = Self::from_root(base_root.join("profiles").join(profile));
+        paths.config = self.config;
+        if let Some(dir) = overrides.identity_dir {
+            let dir = base_root.join(dir);
+            paths.identity_key = dir.join("identity.key");
+            paths.identity_pub = dir.join("identity.pub");
+        }
+        paths.profile = Some(profile.to_string());
+        Ok(paths)
+    }
+
+    /// The state root the profile's paths were derived from.
+    pub fn base_root(&self) -> &Path {
+        match &self.profile {
+            Some(_) => self.config.parent().unwrap_or(&self.root),
+            None => &self.root,
+        }
+    }
+}
+
+/// `profile` from the user config, else the system config.
+fn find_profile(user_config: &Path, profile: &str) -> Result<ProfileConfig> {
+    let mut first_err = None;
+    for path in std::iter::once(user_config.to_path_buf()).chain(system_config_path()) {
+        match read_config(&path)?.profile(profile) {
+            Ok(overrides) => return Ok(overrides.clone()),
+            Err(err) => {
+                first_err.get_or_insert(err);
+            }
+        }
+    }
+    Err(first_err.unwrap_or_else(|| anyhow!("unknown profile '{profile}'")))
+}
+
+fn read_config(path: &Path) -> Result<PersistedConfig> {
+    match std::fs::read_to_string(path) {
+        Ok(raw) => serde_yaml::from_str(&raw)
+            .with_context(|| format!("failed to parse config: {}", path.display())),
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(PersistedConfig::default()),
+        Err(err) => Err(err).with_context(|| format!("failed to read config: {}", path.display())),
+    }
+}
+
+#[cfg(test)]
+#[path = "profiles_tests.rs"]
+mod tests;
@@ -0,0 +1,123 @@
+use tempfile::tempdir;
+
+use super::*;
+use crate::config::{ConfigSource, PeerAddr, load_layered};
+use crate::message::AgentId;
+
+fn peer(id: char, addr: &str) -> PersistedStaticPeerConfig {
+    PersistedStaticPeerConfig {
+        agent_id: AgentId::new(format!("ed25519.{}", id.to_string().repeat(32))),
+        addr: PeerAddr::parse(addr).unwrap(),
+        pubkey: "Zm9v".to_string(),
+        tuning: Default::default(),
+    }
+}
+
+fn config_with_work_profile() -> PersistedConfig {
+    let yaml = r#"
+name: home
+port: 7100
+peers:
+  - agent_id: "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa"
+    addr: "10.0.0.1:7100"
+    pubkey: "Zm9v"
+profiles:
+  work:
+    port: 7200
+    identity_dir: keys/work
+  lab:
+    name: lab
+    peers: []
+"#;
+    serde_yaml::from_str(yaml).unwrap()
+}
+
+#[tokio::test]
+async fn profile_overrides_only_what_it_sets() {
+    let dir = tempdir().unwrap();
+    let user = dir.path().join("config.yaml");
+    std::fs::write(
+        &user,
+        serde_yaml::to_string(&config_with_work_profile()).unwrap(),
+    )
+    .unwrap();
+    let load = |profile, vars: &[(&str, &str)]| {
+        let vars: Vec<(String, String)> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        load_layered(None, &user, Some(profile), vars)
+    };
+
+    let work = load("work", &[]).await.unwrap();
+    assert_eq!(work.config.port, Some(7200));
+    assert_eq!(work.config.name.as_deref(), Some("home"));
+    assert_eq!(work.config.peers.len(), 1);
+    assert_eq!(
+        work.provenance["port"],
+        ConfigSource::Profile {
+            name: "work".to_string()
+        }
+    );
+
+    let lab = load("lab", &[("AXON_NAME", "from-env")]).await.unwrap();
+    assert_eq!(lab.config.port, Some(7100));
+    assert_eq!(lab.config.name.as_deref(), Some("from-env"));
+    assert!(lab.config.peers.is_empty());
+
+    let err = load("play", &[]).await.unwrap_err();
+    assert!(err.to_string().contains("defined: lab, work"), "{err}");
+}
+
+#[test]
+fn adding_a_peer_to_an_inheriting_profile_keeps_the_inherited_ones() {
+    let mut config = config_with_work_profile();
+    config
+        .static_peers_mut(Some("work"))
+        .unwrap()
+        .push(peer('b', "10.0.0.2:7100"));
+
+    assert_eq!(config.peers.len(), 1);
+    assert_eq!(config.static_peers(Some("work")).len(), 2);
+    assert!(config.static_peers(Some("lab")).is_empty());
+    assert!(config.static_peers_mut(Some("play")).is_err());
+
+    let removed: usize = config
+        .all_static_peers_mut()
+        .map(|peers| {
+            let before = peers.len();
+            peers.retain(|p| !p.agent_id.as_str().ends_with('a'));
+            before - peers.len()
+        })
+        .sum();
+    assert_eq!(removed, 2);
+}
+
+#[test]
+fn profile_paths_move_runtime_files_but_share_config() {
+    let dir = tempdir().unwrap();
+    let base = AxonPaths::from_root(dir.path().to_path_buf());
+    std::fs::write(
+        &base.config,
+        serde_yaml::to_string(&config_with_work_profile()).unwrap(),
+    )
+    .unwrap();
+
+    let work = base.clone().with_profile("work").unwrap();
+    let profile_root = dir.path().join("profiles/work");
+    assert_eq!(work.root, profile_root);
+    assert_eq!(work.socket, profile_root.join("axon.sock"));
+    assert_eq!(work.known_peers, profile_root.join("known_peers.json"));
+    assert_eq!(work.config, base.config);
+    assert_eq!(work.identity_key, dir.path().join("keys/work/identity.key"));
+    assert_eq!(work.base_root(), dir.path());
+
+    let lab = base.clone().with_profile("lab").unwrap();
+    assert_eq!(
+        lab.identity_key,
+        dir.path().join("profiles/lab/identity.key")
+    );
+
+    assert!(base.clone().with_profile("play").is_err());
+    assert!(base.with_profile("../etc").is_err());
+}
@@ -0,0 +1,70 @@
+use std::collections::HashSet;
+use std::io::ErrorKind;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::message::AgentId;
+
+/// One entry in `revocations.json`: a public key this agent must never
+/// accept again, whatever its config, discovery cache, or enrollment tokens say.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+pub struct RevokedKey {
+    pub agent_id: AgentId,
+    pub pubkey: String,
+    pub revoked_at_unix_ms: u64,
+}
+
+pub async fn load_revocations(path: &Path) -> Result<Vec<RevokedKey>> {
+    let raw = match tokio::fs::read_to_string(path).await {
+        Ok(raw) => raw,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => {
+            return Err(err)
+                .with_context(|| format!("failed to read revocations: {}", path.display()));
+        }
+    };
+    serde_json::from_str(&raw)
+        .with_context(|| format!("failed to parse revocations: {}", path.display()))
+}
+
+pub async fn save_revocations(path: &Path, revoked: &[RevokedKey]) -> Result<()> {
+    if let Some(parent) = path.parent()
+        && !parent.exists()
+    {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .with_context(|| format!("failed to create directory: {}", parent.display()))?;
+    }
+
+    let data = serde_json::to_vec_pretty(revoked).context("failed to encode revocations")?;
+    tokio::fs::write(path, data)
+        .await
+        .with_context(|| format!("failed to write revocations: {}", path.display()))?;
+    Ok(())
+}
+
+/// Append `entry` to the revocation file unless its key is already listed.
+/// Returns `false` when it was.
+pub async fn add_revocation(path: &Path, entry: RevokedKey) -> Result<bool> {
+    let mut revoked = load_revocations(path).await?;
+    if revoked
+        .iter()
+        .any(|existing| existing.pubkey == entry.pubkey)
+    {
+        return Ok(false);
+    }
+    revoked.push(entry);
+    save_revocations(path, &revoked).await?;
+    Ok(true)
+}
+
+/// The revoked public keys, for [`crate::peer_table::PeerTable::set_revoked`].
+pub fn revoked_pubkeys(revoked: &[RevokedKey]) -> HashSet<String> {
+    revoked.iter().map(|entry| entry.pubkey.clone()).collect()
+}
+
+#[cfg(test)]
+#[path = "revocations_tests.rs"]
+mod tests;
@@ -0,0 +1,40 @@
+use super::*;
+use tempfile::tempdir;
+
+fn entry(pubkey: &str) -> RevokedKey {
+    RevokedKey {
+        agent_id: "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa".into(),
+        pubkey: pubkey.to_string(),
+        revoked_at_unix_ms: 123,
+    }
+}
+
+#[tokio::test]
+async fn revocations_empty_when_missing() {
+    let dir = tempdir().expect("temp dir");
+    let loaded = load_revocations(&dir.path().join("missing.json"))
+        .await
+        .expect("load");
+    assert!(loaded.is_empty());
+}
+
+#[tokio::test]
+async fn add_revocation_appends_each_key_once() {
+    let dir = tempdir().expect("temp dir");
+    let path = dir.path().join("nested").join("revocations.json");
+
+    assert!(add_revocation(&path, entry("Zm9v")).await.expect("add"));
+    assert!(!add_revocation(&path, entry("Zm9v")).await.expect("re-add"));
+    assert!(
+        add_revocation(&path, entry("YmFy"))
+            .await
+            .expect("add second")
+    );
+
+    let loaded = load_revocations(&path).await.expect("load");
+    assert_eq!(loaded, vec![entry("Zm9v"), entry("YmFy")]);
+    assert_eq!(
+        revoked_pubkeys(&loaded),
+        HashSet::from(["Zm9v".to_string(), "YmFy".to_string()])
+    );
+}
@@ -0,0 +1,293 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use super::application_kind;
+use crate::message::{AgentId, MessageKind};
+
+/// IPC client connection limits (`ipc:` section of `config.yaml`).
+///
+/// Unset values disable the corresponding limit.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, Default, PartialEq, Eq)]
+pub struct IpcConfig {
+    /// Close clients that send no command for this many seconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idle_timeout_secs: Option<u64>,
+    /// Close clients once their connection is this many seconds old.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_lifetime_secs: Option<u64>,
+}
+
+impl IpcConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn idle_timeout(&self) -> Option<Duration> {
+        self.idle_timeout_secs
+            .filter(|secs| *secs > 0)
+            .map(Duration::from_secs)
+    }
+
+    pub fn max_lifetime(&self) -> Option<Duration> {
+        self.max_lifetime_secs
+            .filter(|secs| *secs > 0)
+            .map(Duration::from_secs)
+    }
+}
+
+/// External handler for inbound requests (`handler:` section of `config.yaml`).
+#[derive(Debug, Clone, Deserialize, Serialize, Default, PartialEq, Eq)]
+pub struct HandlerConfig {
+    /// Executable spawned once per inbound request; unset disables the handler.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exec: Option<PathBuf>,
+    /// Rhai script whose `handle(request)` function is consulted before
+    /// `exec`; unset disables it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub script: Option<PathBuf>,
+    /// Seconds a handler run may take before it is killed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+    /// Maximum runs of each handler (`exec`, `script`) at once.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_concurrent: Option<usize>,
+    /// Seconds a response is reused for an identical request from the same
+    /// peer; unset or 0 disables the cache.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_ttl_secs: Option<u64>,
+    /// Cached responses kept at once; the oldest is evicted first.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_max_entries: Option<usize>,
+}
+
+impl HandlerConfig {
+    pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+    pub const DEFAULT_MAX_CONCURRENT: usize = 4;
+    pub const DEFAULT_CACHE_MAX_ENTRIES: usize = 1024;
+
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn timeout(&self) -> Duration {
+        self.timeout_secs
+            .filter(|secs| *secs > 0)
+            .map(Duration::from_secs)
+            .unwrap_or(Self::DEFAULT_TIMEOUT)
+    }
+
+    pub fn max_concurrent(&self) -> usize {
+        self.max_concurrent
+            .filter(|n| *n > 0)
+            .unwrap_or(Self::DEFAULT_MAX_CONCURRENT)
+    }
+
+    /// How long responses are cached, or `None` when caching is off.
+    pub fn cache_ttl(&self) -> Option<Duration> {
+        self.cache_ttl_secs
+            .filter(|secs| *secs > 0)
+            .map(Duration::from_secs)
+    }
+
+    pub fn cache_max_entries(&self) -> usize {
+        self.cache_max_entries
+            .filter(|n| *n > 0)
+            .unwrap_or(Self::DEFAULT_CACHE_MAX_ENTRIES)
+    }
+}
+
+/// Persisted message history (`history:` section of `config.yaml`).
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, Default, PartialEq, Eq)]
+pub struct HistoryConfig {
+    /// Record sent and received envelopes in `history.sqlite3`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    /// Delete entries older than this many days; `0` keeps everything.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retention_days: Option<u64>,
+}
+
+impl HistoryConfig {
+    pub const DEFAULT_RETENTION_DAYS: u64 = 7;
+
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.enabled.unwrap_or(false)
+    }
+
+    pub fn retention(&self) -> Option<Duration> {
+        match self.retention_days.unwrap_or(Self::DEFAULT_RETENTION_DAYS) {
+            0 => None,
+            days => Some(Duration::from_secs(days * 24 * 60 * 60)),
+        }
+    }
+}
+
+/// Append-only audit log (`audit:` section of `config.yaml`).
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, Default, PartialEq, Eq)]
+pub struct AuditConfig {
+    /// Record message and peer activity in `audit.jsonl`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    /// Rotate `audit.jsonl` once it would grow past this many bytes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_file_bytes: Option<u64>,
+    /// Rotated files kept (`audit.jsonl.1` … `audit.jsonl.N`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_files: Option<usize>,
+}
+
+impl AuditConfig {
+    pub const DEFAULT_MAX_FILE_BYTES: u64 = 10 * 1024 * 1024;
+    pub const DEFAULT_MAX_FILES: usize = 5;
+
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.enabled.unwrap_or(false)
+    }
+
+    pub fn max_file_bytes(&self) -> u64 {
+        self.max_file_bytes
+            .filter(|bytes| *bytes > 0)
+            .unwrap_or(Self::DEFAULT_MAX_FILE_BYTES)
+    }
+
+    pub fn max_files(&self) -> usize {
+        self.max_files
+            .filter(|n| *n > 0)
+            .unwrap_or(Self::DEFAULT_MAX_FILES)
+    }
+}
+
+/// Graceful shutdown (`shutdown:` section of `config.yaml`).
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, Default, PartialEq, Eq)]
+pub struct ShutdownConfig {
+    /// Seconds to wait for in-flight inbound streams before closing
+    /// connections; `0` closes immediately.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub drain_timeout_secs: Option<u64>,
+}
+
+impl ShutdownConfig {
+    pub const DEFAULT_DRAIN_TIMEOUT: Duration = Duration::from_secs(5);
+
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn drain_timeout(&self) -> Duration {
+        self.drain_timeout_secs
+            .map(Duration::from_secs)
+            .unwrap_or(Self::DEFAULT_DRAIN_TIMEOUT)
+    }
+}
+
+/// Liveness heartbeats on connected peer links (`heartbeat:` section of
+/// `config.yaml`).
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, Default, PartialEq, Eq)]
+pub struct HeartbeatConfig {
+    /// Seconds between heartbeats sent to each connected peer; unset or `0`
+    /// disables. Off by default: daemons that predate `heartbeat` forward it
+    /// to their IPC clients as `unknown`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interval_secs: Option<u64>,
+    /// Consecutive intervals without traffic before a peer is `degraded`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub miss_threshold: Option<u32>,
+}
+
+impl HeartbeatConfig {
+    pub const DEFAULT_MISS_THRESHOLD: u32 = 3;
+
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn interval(&self) -> Option<Duration> {
+        self.interval_secs
+            .filter(|secs| *secs > 0)
+            .map(Duration::from_secs)
+    }
+
+    pub fn miss_threshold(&self) -> u32 {
+        self.miss_threshold
+            .filter(|n| *n > 0)
+            .unwrap_or(Self::DEFAULT_MISS_THRESHOLD)
+    }
+}
+
+/// How long unseen peers stay in the peer table, per source (`staleness:`
+/// section of `config.yaml`). Static peers never expire.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, Default, PartialEq, Eq)]
+pub struct StalenessConfig {
+    /// Seconds an mDNS-discovered peer may go unseen; `0` never expires.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub discovered_ttl_secs: Option<u64>,
+    /// Seconds a peer loaded from `known_peers.json` may go unseen; unset or
+    /// `0` never expires.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cached_ttl_secs: Option<u64>,
+}
+
+impl StalenessConfig {
+    pub const DEFAULT_DISCOVERED_TTL: Duration = crate::peer_table::STALE_TIMEOUT;
+
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn discovered_ttl(&self) -> Option<Duration> {
+        match self.discovered_ttl_secs {
+            Some(0) => None,
+            Some(secs) => Some(Duration::from_secs(secs)),
+            None => Some(Self::DEFAULT_DISCOVERED_TTL),
+        }
+    }
+
+    pub fn cached_ttl(&self) -> Option<Duration> {
+        self.cached_ttl_secs
+            .filter(|secs| *secs > 0)
+            .map(Duration::from_secs)
+    }
+}
+
+/// Per-peer authorization (`policy:` section of `config.yaml`).
+///
+/// Peers without an entry may send every kind.
+#[derive(Debug, Clone, Deserialize, Serialize, Default, PartialEq, Eq)]
+pub struct PolicyConfig {
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub peers: BTreeMap<AgentId, PeerPolicyConfig>,
+}
+
+impl PolicyConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, Default, PartialEq, Eq)]
+pub struct PeerPolicyConfig {
+    /// Application kinds (`request`, `response`, `message`, `error`) the peer
+    /// may send; anything else is rejected as `not_authorized`.
+    #[serde(default)]
+    pub allow_kinds: Vec<String>,
+}
+
+impl PeerPolicyConfig {
+    /// Allowed kinds, skipping names that are not application kinds.
+    pub fn kinds(&self) -> impl Iterator<Item = MessageKind> + '_ {
+        self.allow_kinds
+            .iter()
+            .filter_map(|name| application_kind(name))
+    }
+}
@@ -0,0 +1,80 @@
+use super::super::*;
+use tempfile::tempdir;
+
+#[tokio::test]
+async fn known_peers_roundtrip() {
+    let dir = tempdir().expect("temp dir");
+    let path = dir.path().join("known.json");
+    let peers = vec![KnownPeer {
+        agent_id: "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa".into(),
+        addr: "127.0.0.1:7100".parse().expect("addr"),
+        pubkey: "Zm9v".to_string(),
+        last_seen_unix_ms: 123,
+        first_seen_unix_ms: Some(100),
+        source: KnownPeerSource::Discovered,
+    }];
+
+    save_known_peers(&path, &peers).await.expect("save");
+    let loaded = load_known_peers(&path).await.expect("load");
+    assert_eq!(loaded, peers);
+    assert!(!dir.path().join("known.json.tmp").exists());
+}
+
+#[tokio::test]
+async fn known_peers_without_first_seen_still_load() {
+    let dir = tempdir().expect("temp dir");
+    let path = dir.path().join("known.json");
+    std::fs::write(
+        &path,
+        r#"[{"agent_id":"ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa","addr":"127.0.0.1:7100","pubkey":"Zm9v","last_seen_unix_ms":123,"source":"cached"}]"#,
+    )
+    .expect("write known peers");
+
+    let loaded = load_known_peers(&path).await.expect("load");
+    assert_eq!(loaded.len(), 1);
+    assert_eq!(loaded[0].first_seen_unix_ms, None);
+}
+
+#[tokio::test]
+async fn known_peers_empty_when_missing() {
+    let dir = tempdir().expect("temp dir");
+    let loaded = load_known_peers(&dir.path().join("missing.json"))
+        .await
+        .expect("load");
+    assert!(loaded.is_empty());
+}
+
+#[tokio::test]
+async fn legacy_known_peers_cache_is_ignored() {
+    let dir = tempdir().expect("temp dir");
+    let path = dir.path().join("known.json");
+    std::fs::write(
+        &path,
+        r#"[{"agent_id":"ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa","addr":"127.0.0.1:7100","pubkey":"Zm9v","last_seen_unix_ms":123}]"#,
+    )
+    .expect("write legacy known peers");
+
+    let loaded = load_known_peers(&path).await.expect("load");
+    assert!(loaded.is_empty());
+}
+
+#[tokio::test]
+async fn save_known_peers_creates_parent_dir() {
+    let dir = tempdir().expect("temp dir");
+    let path = dir.path().join("nested").join("subdir").join("known.json");
+    let peers = vec![KnownPeer {
+        agent_id: "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa".into(),
+        addr: "127.0.0.1:7100".parse().expect("addr"),
+        pubkey: "Zm9v".to_string(),
+        last_seen_unix_ms: 456,
+        first_seen_unix_ms: None,
+        source: KnownPeerSource::Cached,
+    }];
+
+    save_known_peers(&path, &peers)
+        .await
+        .expect("save should create parent dirs");
+    assert!(path.exists(), "file should exist after save");
+    let loaded = load_known_peers(&path).await.expect("load");
+    assert_eq!(loaded, peers);
+}
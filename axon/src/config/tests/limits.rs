@@ -0,0 +1,149 @@
+use std::time::Duration;
+
+use super::super::*;
+use crate::message::MAX_MESSAGE_SIZE;
+use tempfile::tempdir;
+
+#[tokio::test]
+async fn config_parses_rate_limit_section() {
+    let dir = tempdir().expect("temp dir");
+    let path = dir.path().join("config.yaml");
+    std::fs::write(
+        &path,
+        "rate_limit:\n  messages_per_sec: 50\n  bytes_per_sec: 0\n",
+    )
+    .expect("write");
+    let cfg = Config::load(&path).await.expect("load config");
+    assert_eq!(cfg.rate_limit.messages_per_sec(), Some(50));
+    assert_eq!(cfg.rate_limit.bytes_per_sec(), None, "zero disables");
+    assert!(Config::default().rate_limit.is_default());
+}
+
+#[tokio::test]
+async fn config_parses_idempotency_section() {
+    let dir = tempdir().expect("temp dir");
+    let path = dir.path().join("config.yaml");
+    std::fs::write(&path, "idempotency:\n  window_secs: 0\n  max_entries: 10\n").expect("write");
+    let cfg = Config::load(&path).await.expect("load config");
+    assert_eq!(cfg.idempotency.window(), None, "zero disables the cache");
+    assert_eq!(cfg.idempotency.max_entries(), 10);
+    assert_eq!(
+        IdempotencyConfig::default().window(),
+        Some(IdempotencyConfig::DEFAULT_WINDOW)
+    );
+}
+
+#[tokio::test]
+async fn config_parses_handshake_ban_section() {
+    let dir = tempdir().expect("temp dir");
+    let path = dir.path().join("config.yaml");
+    std::fs::write(
+        &path,
+        "handshake_ban:\n  max_failures: 0\n  ban_secs: 120\n  max_ban_secs: 30\n",
+    )
+    .expect("write");
+    let cfg = Config::load(&path).await.expect("load config");
+    assert_eq!(cfg.handshake_ban.max_failures(), 0, "zero disables bans");
+    assert_eq!(
+        cfg.handshake_ban.window(),
+        HandshakeBanConfig::DEFAULT_WINDOW
+    );
+    assert_eq!(cfg.handshake_ban.ban(), Duration::from_secs(120));
+    assert_eq!(
+        cfg.handshake_ban.max_ban(),
+        Duration::from_secs(120),
+        "cap is never below the first ban"
+    );
+    assert_eq!(
+        HandshakeBanConfig::default().max_failures(),
+        HandshakeBanConfig::DEFAULT_MAX_FAILURES
+    );
+}
+
+#[tokio::test]
+async fn config_parses_prewarm_section() {
+    let dir = tempdir().expect("temp dir");
+    let path = dir.path().join("config.yaml");
+    std::fs::write(&path, "prewarm:\n  enabled: true\n  max_concurrent: 0\n").expect("write");
+    let cfg = Config::load(&path).await.expect("load config");
+    assert!(cfg.prewarm.enabled());
+    assert_eq!(
+        cfg.prewarm.max_concurrent(),
+        PrewarmConfig::DEFAULT_MAX_CONCURRENT,
+        "zero keeps the default"
+    );
+    assert!(!PrewarmConfig::default().enabled());
+}
+
+#[tokio::test]
+async fn config_parses_max_message_bytes_section() {
+    let dir = tempdir().expect("temp dir");
+    let path = dir.path().join("config.yaml");
+    std::fs::write(
+        &path,
+        "max_message_bytes:\n  message: 4096\n  request: 10\n  response: 100000\n",
+    )
+    .expect("write");
+    let cfg = Config::load(&path).await.expect("load config");
+    let caps = cfg.max_message_bytes;
+    assert_eq!(MaxMessageBytesConfig::effective(caps.message), Some(4096));
+    assert_eq!(
+        MaxMessageBytesConfig::effective(caps.request),
+        Some(MaxMessageBytesConfig::MIN_BYTES as usize)
+    );
+    assert_eq!(
+        MaxMessageBytesConfig::effective(caps.response),
+        Some(MAX_MESSAGE_SIZE as usize)
+    );
+    assert_eq!(MaxMessageBytesConfig::effective(caps.error), None);
+    assert_eq!(MaxMessageBytesConfig::effective(Some(0)), None);
+}
+
+#[tokio::test]
+async fn config_parses_per_peer_tuning() {
+    let dir = tempdir().expect("temp dir");
+    let path = dir.path().join("config.yaml");
+    std::fs::write(
+        &path,
+        r#"
+peers:
+  - agent_id: "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa"
+    addr: "127.0.0.1:7100"
+    pubkey: "Zm9v"
+    handshake_timeout_secs: 30
+    idle_timeout_secs: 0
+    reconnect_max_backoff_secs: 300
+    max_message_bytes: 16
+    congestion_control: bbr
+    initial_window_bytes: 65536
+transport:
+  congestion_control: new_reno
+  initial_window_bytes: 0
+"#,
+    )
+    .expect("write config");
+
+    let cfg = Config::load(&path).await.expect("load config");
+    let tuning = cfg.peer_tuning("ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa");
+    assert_eq!(tuning.handshake_timeout(), Some(Duration::from_secs(30)));
+    assert_eq!(tuning.idle_timeout(), None);
+    assert_eq!(
+        tuning.reconnect_max_backoff(),
+        Some(Duration::from_secs(300))
+    );
+    assert_eq!(
+        tuning.max_message_bytes(),
+        Some(MaxMessageBytesConfig::MIN_BYTES as usize)
+    );
+    assert_eq!(tuning.congestion_control, Some(CongestionControl::Bbr));
+    assert_eq!(tuning.initial_window(), Some(65536));
+    assert_eq!(
+        cfg.transport.congestion_control,
+        Some(CongestionControl::NewReno)
+    );
+    assert_eq!(cfg.transport.initial_window(), None, "0 keeps the default");
+    assert!(
+        cfg.peer_tuning("ed25519.bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb")
+            .is_default()
+    );
+}
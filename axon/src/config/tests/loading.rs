@@ -1,4 +1,5 @@
-use super::*;
+use super::super::*;
+use proptest::prelude::*;
 use tempfile::tempdir;
 
 #[tokio::test]
@@ -90,7 +91,7 @@ fn cli_override_takes_precedence() {
         port: Some(8000),
         advertise_addr: None,
         peers: Vec::new(),
-        persisted_peers: Vec::new(),
+        ..Default::default()
     };
     assert_eq!(cfg.effective_port(Some(9999)), 9999);
     assert_eq!(cfg.effective_port(None), 8000);
@@ -129,106 +130,30 @@ async fn config_ignores_unknown_fields() {
 }
 
 #[tokio::test]
-async fn known_peers_roundtrip() {
-    let dir = tempdir().expect("temp dir");
-    let path = dir.path().join("known.json");
-    let peers = vec![KnownPeer {
-        agent_id: "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa".into(),
-        addr: "127.0.0.1:7100".parse().expect("addr"),
-        pubkey: "Zm9v".to_string(),
-        last_seen_unix_ms: 123,
-        source: KnownPeerSource::Discovered,
-    }];
-
-    save_known_peers(&path, &peers).await.expect("save");
-    let loaded = load_known_peers(&path).await.expect("load");
-    assert_eq!(loaded, peers);
-}
-
-#[tokio::test]
-async fn known_peers_empty_when_missing() {
+async fn append_static_peer_persists_yaml_entry() {
     let dir = tempdir().expect("temp dir");
-    let loaded = load_known_peers(&dir.path().join("missing.json"))
-        .await
-        .expect("load");
-    assert!(loaded.is_empty());
-}
+    let path = dir.path().join("config.yaml");
 
-#[tokio::test]
-async fn legacy_known_peers_cache_is_ignored() {
-    let dir = tempdir().expect("temp dir");
-    let path = dir.path().join("known.json");
-    std::fs::write(
+    append_static_peer(
         &path,
-        r#"[{"agent_id":"ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa","addr":"127.0.0.1:7100","pubkey":"Zm9v","last_seen_unix_ms":123}]"#,
+        PersistedStaticPeerConfig {
+            agent_id: "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa".into(),
+            addr: PeerAddr::parse("127.0.0.1:7100").expect("addr"),
+            pubkey: "Zm9v".to_string(),
+            tuning: Default::default(),
+        },
     )
-    .expect("write legacy known peers");
-
-    let loaded = load_known_peers(&path).await.expect("load");
-    assert!(loaded.is_empty());
-}
-
-#[test]
-fn discover_paths_from_root() {
-    let root = PathBuf::from("/tmp/axon-test");
-    let paths = AxonPaths::from_root(root.clone());
-    assert_eq!(paths.identity_key, root.join("identity.key"));
-    assert_eq!(paths.identity_pub, root.join("identity.pub"));
-    assert_eq!(paths.config, root.join("config.yaml"));
-    assert_eq!(paths.known_peers, root.join("known_peers.json"));
-    assert_eq!(paths.socket, root.join("axon.sock"));
-}
-
-#[test]
-fn discover_with_override_uses_override_root() {
-    let root = PathBuf::from("/tmp/axon-override");
-    let paths = AxonPaths::discover_with_override(Some(root.as_path())).expect("discover");
-    assert_eq!(paths.root, root);
-    assert_eq!(paths.socket, PathBuf::from("/tmp/axon-override/axon.sock"));
-}
+    .await
+    .expect("append peer");
 
-#[test]
-fn peer_addr_parse_and_resolve_ipv4_socket() {
-    let addr = PeerAddr::parse("127.0.0.1:7100").expect("parse");
+    let persisted = load_persisted_config(&path).await.expect("load persisted");
+    assert_eq!(persisted.peers.len(), 1);
     assert_eq!(
-        addr.resolve().expect("resolve"),
-        "127.0.0.1:7100".parse().expect("socket addr")
+        persisted.peers[0].agent_id,
+        "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa"
     );
 }
 
-#[test]
-fn peer_addr_parse_hostname_with_port() {
-    let addr = PeerAddr::parse("localhost:7100").expect("parse");
-    let PeerAddr::Host { host, port } = addr else {
-        panic!("expected host variant");
-    };
-    assert_eq!(host, "localhost");
-    assert_eq!(port, 7100);
-}
-
-#[test]
-fn peer_addr_requires_port() {
-    let err = PeerAddr::parse("localhost").expect_err("missing port should fail");
-    assert!(err.to_string().contains("host:port"));
-}
-
-#[test]
-fn ensure_root_creates_and_sets_perms() {
-    let dir = tempdir().expect("temp dir");
-    let root = dir.path().join("axon-subdir");
-    let paths = AxonPaths::from_root(root.clone());
-    paths.ensure_root_exists().expect("ensure root");
-    assert!(root.exists());
-    let mode = fs::metadata(&root).unwrap().permissions().mode();
-    assert_eq!(mode & 0o777, 0o700);
-}
-
-// =========================================================================
-// Property-based tests
-// =========================================================================
-
-use proptest::prelude::*;
-
 proptest! {
     #[test]
     fn effective_port_cli_always_wins(config_port in proptest::option::of(1u16..),
@@ -238,7 +163,7 @@ proptest! {
             port: config_port,
             advertise_addr: None,
             peers: Vec::new(),
-            persisted_peers: Vec::new(),
+            ..Default::default()
         };
         prop_assert_eq!(cfg.effective_port(Some(cli_port)), cli_port);
     }
@@ -250,57 +175,9 @@ proptest! {
             port: config_port,
             advertise_addr: None,
             peers: Vec::new(),
-            persisted_peers: Vec::new(),
+            ..Default::default()
         };
         let expected = config_port.unwrap_or(7100);
         prop_assert_eq!(cfg.effective_port(None), expected);
     }
 }
-
-#[tokio::test]
-async fn append_static_peer_persists_yaml_entry() {
-    let dir = tempdir().expect("temp dir");
-    let path = dir.path().join("config.yaml");
-
-    append_static_peer(
-        &path,
-        PersistedStaticPeerConfig {
-            agent_id: "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa".into(),
-            addr: PeerAddr::parse("127.0.0.1:7100").expect("addr"),
-            pubkey: "Zm9v".to_string(),
-        },
-    )
-    .await
-    .expect("append peer");
-
-    let persisted = load_persisted_config(&path).await.expect("load persisted");
-    assert_eq!(persisted.peers.len(), 1);
-    assert_eq!(
-        persisted.peers[0].agent_id,
-        "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa"
-    );
-}
-
-// =========================================================================
-// Mutation-coverage: save_known_peers creates parent dir
-// =========================================================================
-
-#[tokio::test]
-async fn save_known_peers_creates_parent_dir() {
-    let dir = tempdir().expect("temp dir");
-    let path = dir.path().join("nested").join("subdir").join("known.json");
-    let peers = vec![KnownPeer {
-        agent_id: "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa".into(),
-        addr: "127.0.0.1:7100".parse().expect("addr"),
-        pubkey: "Zm9v".to_string(),
-        last_seen_unix_ms: 456,
-        source: KnownPeerSource::Cached,
-    }];
-
-    save_known_peers(&path, &peers)
-        .await
-        .expect("save should create parent dirs");
-    assert!(path.exists(), "file should exist after save");
-    let loaded = load_known_peers(&path).await.expect("load");
-    assert_eq!(loaded, peers);
-}
@@ -0,0 +1,7 @@
+mod known_peers;
+mod limits;
+mod loading;
+mod names;
+mod paths;
+mod peer_addr;
+mod sections;
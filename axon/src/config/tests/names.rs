@@ -0,0 +1,43 @@
+use super::super::*;
+use tempfile::tempdir;
+
+#[tokio::test]
+async fn config_parses_aliases_and_omits_empty_section() {
+    let dir = tempdir().expect("temp dir");
+    let path = dir.path().join("config.yaml");
+    std::fs::write(
+        &path,
+        "aliases:\n  laptop: ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa\n",
+    )
+    .expect("write");
+    let cfg = Config::load(&path).await.expect("load config");
+    assert_eq!(
+        cfg.aliases.get("laptop").map(AgentId::as_str),
+        Some("ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa")
+    );
+
+    let yaml = serde_yaml::to_string(&PersistedConfig::default()).expect("serialize");
+    assert!(!yaml.contains("aliases"));
+}
+
+#[test]
+fn alias_names_cannot_look_like_agent_ids() {
+    assert!(is_valid_alias("laptop"));
+    assert!(is_valid_alias("build-box_2"));
+    assert!(!is_valid_alias(""));
+    assert!(!is_valid_alias("2box"));
+    assert!(!is_valid_alias("ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa"));
+    assert!(!is_valid_alias("has space"));
+    assert!(!is_valid_alias(&"a".repeat(MAX_ALIAS_LEN + 1)));
+}
+
+#[test]
+fn tags_allow_dots_dashes_and_leading_digits() {
+    assert!(is_valid_tag("prod"));
+    assert!(is_valid_tag("2gpu"));
+    assert!(is_valid_tag("eu-west.1_a"));
+    assert!(!is_valid_tag(""));
+    assert!(!is_valid_tag(".hidden"));
+    assert!(!is_valid_tag("has space"));
+    assert!(!is_valid_tag(&"a".repeat(MAX_TAG_LEN + 1)));
+}
@@ -0,0 +1,37 @@
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::PathBuf;
+
+use super::super::*;
+use tempfile::tempdir;
+
+#[test]
+fn discover_paths_from_root() {
+    let root = PathBuf::from("/tmp/axon-test");
+    let paths = AxonPaths::from_root(root.clone());
+    assert_eq!(paths.identity_key, root.join("identity.key"));
+    assert_eq!(paths.identity_pub, root.join("identity.pub"));
+    assert_eq!(paths.config, root.join("config.yaml"));
+    assert_eq!(paths.known_peers, root.join("known_peers.json"));
+    assert_eq!(paths.revocations, root.join("revocations.json"));
+    assert_eq!(paths.socket, root.join("axon.sock"));
+}
+
+#[test]
+fn discover_with_override_uses_override_root() {
+    let root = PathBuf::from("/tmp/axon-override");
+    let paths = AxonPaths::discover_with_override(Some(root.as_path())).expect("discover");
+    assert_eq!(paths.root, root);
+    assert_eq!(paths.socket, PathBuf::from("/tmp/axon-override/axon.sock"));
+}
+
+#[test]
+fn ensure_root_creates_and_sets_perms() {
+    let dir = tempdir().expect("temp dir");
+    let root = dir.path().join("axon-subdir");
+    let paths = AxonPaths::from_root(root.clone());
+    paths.ensure_root_exists().expect("ensure root");
+    assert!(root.exists());
+    let mode = fs::metadata(&root).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o700);
+}
@@ -0,0 +1,26 @@
+use super::super::*;
+
+#[test]
+fn peer_addr_parse_and_resolve_ipv4_socket() {
+    let addr = PeerAddr::parse("127.0.0.1:7100").expect("parse");
+    assert_eq!(
+        addr.resolve().expect("resolve"),
+        "127.0.0.1:7100".parse().expect("socket addr")
+    );
+}
+
+#[test]
+fn peer_addr_parse_hostname_with_port() {
+    let addr = PeerAddr::parse("localhost:7100").expect("parse");
+    let PeerAddr::Host { host, port } = addr else {
+        panic!("expected host variant");
+    };
+    assert_eq!(host, "localhost");
+    assert_eq!(port, 7100);
+}
+
+#[test]
+fn peer_addr_requires_port() {
+    let err = PeerAddr::parse("localhost").expect_err("missing port should fail");
+    assert!(err.to_string().contains("host:port"));
+}
@@ -0,0 +1,243 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use super::super::*;
+use tempfile::tempdir;
+
+#[tokio::test]
+async fn config_parses_ipc_limits() {
+    let dir = tempdir().expect("temp dir");
+    let path = dir.path().join("config.yaml");
+    std::fs::write(
+        &path,
+        "ipc:\n  idle_timeout_secs: 300\n  max_lifetime_secs: 0\n",
+    )
+    .expect("write");
+    let cfg = Config::load(&path).await.expect("load config");
+    assert_eq!(cfg.ipc.idle_timeout(), Some(Duration::from_secs(300)));
+    assert_eq!(
+        cfg.ipc.max_lifetime(),
+        None,
+        "zero disables the max lifetime"
+    );
+}
+
+#[test]
+fn persisted_config_omits_default_ipc_section() {
+    let yaml = serde_yaml::to_string(&PersistedConfig::default()).expect("serialize");
+    assert!(!yaml.contains("ipc"));
+}
+
+#[tokio::test]
+async fn config_parses_handler_section_with_defaults() {
+    let dir = tempdir().expect("temp dir");
+    let path = dir.path().join("config.yaml");
+    std::fs::write(&path, "handler:\n  exec: /opt/axon/answer.sh\n").expect("write");
+    let cfg = Config::load(&path).await.expect("load config");
+    assert_eq!(cfg.handler.exec, Some(PathBuf::from("/opt/axon/answer.sh")));
+    assert_eq!(cfg.handler.timeout(), HandlerConfig::DEFAULT_TIMEOUT);
+    assert_eq!(
+        cfg.handler.max_concurrent(),
+        HandlerConfig::DEFAULT_MAX_CONCURRENT
+    );
+    assert!(Config::default().handler.exec.is_none());
+    assert_eq!(cfg.handler.cache_ttl(), None);
+
+    std::fs::write(&path, "handler:\n  cache_ttl_secs: 30\n").expect("write");
+    let cfg = Config::load(&path).await.expect("load config");
+    assert_eq!(cfg.handler.cache_ttl(), Some(Duration::from_secs(30)));
+    assert_eq!(
+        cfg.handler.cache_max_entries(),
+        HandlerConfig::DEFAULT_CACHE_MAX_ENTRIES
+    );
+}
+
+#[tokio::test]
+async fn config_parses_history_section() {
+    let dir = tempdir().expect("temp dir");
+    let path = dir.path().join("config.yaml");
+    std::fs::write(&path, "history:\n  enabled: true\n  retention_days: 0\n").expect("write");
+    let cfg = Config::load(&path).await.expect("load config");
+    assert!(cfg.history.enabled());
+    assert_eq!(cfg.history.retention(), None, "zero keeps everything");
+
+    let defaults = HistoryConfig::default();
+    assert!(!defaults.enabled());
+    assert_eq!(
+        defaults.retention(),
+        Some(Duration::from_secs(
+            HistoryConfig::DEFAULT_RETENTION_DAYS * 86_400
+        ))
+    );
+}
+
+#[tokio::test]
+async fn config_parses_audit_section() {
+    let dir = tempdir().expect("temp dir");
+    let path = dir.path().join("config.yaml");
+    std::fs::write(
+        &path,
+        "audit:\n  enabled: true\n  max_file_bytes: 4096\n  max_files: 0\n",
+    )
+    .expect("write");
+    let cfg = Config::load(&path).await.expect("load config");
+    assert!(cfg.audit.enabled());
+    assert_eq!(cfg.audit.max_file_bytes(), 4096);
+    assert_eq!(
+        cfg.audit.max_files(),
+        AuditConfig::DEFAULT_MAX_FILES,
+        "zero falls back to the default"
+    );
+    assert!(!AuditConfig::default().enabled());
+}
+
+#[tokio::test]
+async fn config_parses_event_log_section() {
+    let dir = tempdir().expect("temp dir");
+    let path = dir.path().join("config.yaml");
+    std::fs::write(
+        &path,
+        "event_log:\n  enabled: true\n  max_file_age_secs: 0\n",
+    )
+    .expect("write");
+    let cfg = Config::load(&path).await.expect("load config");
+    assert!(cfg.event_log.enabled());
+    assert_eq!(cfg.event_log.max_file_age(), None, "0 rotates by size only");
+    assert_eq!(
+        EventLogConfig::default().max_file_age(),
+        Some(EventLogConfig::DEFAULT_MAX_FILE_AGE)
+    );
+    assert_eq!(cfg.event_log.max_files(), EventLogConfig::DEFAULT_MAX_FILES);
+}
+
+#[tokio::test]
+async fn config_parses_shutdown_drain_timeout() {
+    let dir = tempdir().expect("temp dir");
+    let path = dir.path().join("config.yaml");
+    std::fs::write(&path, "shutdown:\n  drain_timeout_secs: 0\n").expect("write");
+    let cfg = Config::load(&path).await.expect("load config");
+    assert_eq!(cfg.shutdown.drain_timeout(), Duration::ZERO);
+    assert_eq!(
+        ShutdownConfig::default().drain_timeout(),
+        ShutdownConfig::DEFAULT_DRAIN_TIMEOUT
+    );
+}
+
+#[tokio::test]
+async fn config_parses_heartbeat_section() {
+    let dir = tempdir().expect("temp dir");
+    let path = dir.path().join("config.yaml");
+    std::fs::write(
+        &path,
+        "heartbeat:\n  interval_secs: 2\n  miss_threshold: 5\n",
+    )
+    .expect("write");
+    let cfg = Config::load(&path).await.expect("load config");
+    assert_eq!(cfg.heartbeat.interval(), Some(Duration::from_secs(2)));
+    assert_eq!(cfg.heartbeat.miss_threshold(), 5);
+
+    let disabled = HeartbeatConfig {
+        interval_secs: Some(0),
+        miss_threshold: Some(0),
+    };
+    assert_eq!(disabled.interval(), None, "zero disables heartbeats");
+    assert_eq!(
+        disabled.miss_threshold(),
+        HeartbeatConfig::DEFAULT_MISS_THRESHOLD
+    );
+    assert_eq!(
+        HeartbeatConfig::default().interval(),
+        None,
+        "heartbeats are opt-in"
+    );
+}
+
+#[tokio::test]
+async fn config_parses_staleness_section() {
+    let dir = tempdir().expect("temp dir");
+    let path = dir.path().join("config.yaml");
+    std::fs::write(
+        &path,
+        "staleness:\n  discovered_ttl_secs: 0\n  cached_ttl_secs: 3600\n",
+    )
+    .expect("write");
+    let cfg = Config::load(&path).await.expect("load config");
+    assert_eq!(cfg.staleness.discovered_ttl(), None, "zero never expires");
+    assert_eq!(cfg.staleness.cached_ttl(), Some(Duration::from_secs(3600)));
+
+    let defaults = StalenessConfig::default();
+    assert_eq!(
+        defaults.discovered_ttl(),
+        Some(StalenessConfig::DEFAULT_DISCOVERED_TTL)
+    );
+    assert_eq!(
+        defaults.cached_ttl(),
+        None,
+        "cached peers never expire by default"
+    );
+}
+
+#[tokio::test]
+async fn config_parses_policy_section() {
+    let dir = tempdir().expect("temp dir");
+    let path = dir.path().join("config.yaml");
+    std::fs::write(
+        &path,
+        "policy:\n  peers:\n    ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa:\n      allow_kinds: [message, error, subscribe_topics, query]\n",
+    )
+    .expect("write");
+    let cfg = Config::load(&path).await.expect("load config");
+    let peer = cfg
+        .policy
+        .peers
+        .get("ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa")
+        .expect("peer policy");
+    assert_eq!(
+        peer.kinds().collect::<Vec<_>>(),
+        vec![MessageKind::Message, MessageKind::Error]
+    );
+    assert!(Config::default().policy.is_default());
+}
+
+#[test]
+fn webhook_urls_parse_host_port_and_path() {
+    let url = WebhookUrl::parse("https://hooks.example.com/axon?x=1").unwrap();
+    assert_eq!(
+        url,
+        WebhookUrl {
+            tls: true,
+            host: "hooks.example.com".to_string(),
+            port: 443,
+            path: "/axon?x=1".to_string(),
+        }
+    );
+    let url = WebhookUrl::parse("http://[::1]:8080").unwrap();
+    assert_eq!(
+        (url.host.as_str(), url.port, url.path.as_str()),
+        ("::1", 8080, "/")
+    );
+    assert_eq!(WebhookUrl::parse("http://h?q").unwrap().path, "/?q");
+
+    for bad in [
+        "hooks.example.com/axon",
+        "http://:80/",
+        "http://h:0/",
+        "http://user:pw@h/",
+        "http://h/a b",
+        "http://h/#frag",
+    ] {
+        assert!(WebhookUrl::parse(bad).is_err(), "{bad}");
+    }
+}
+
+#[tokio::test]
+async fn config_parses_identity_section() {
+    let dir = tempdir().expect("temp dir");
+    let paths = AxonPaths::from_root(dir.path().to_path_buf());
+    std::fs::write(&paths.config, "identity:\n  storage: keychain\n").expect("write");
+    let cfg = Config::load(&paths.config).await.expect("load config");
+    assert_eq!(cfg.identity.storage(), IdentityStorage::Keychain);
+    let identity = IdentityConfig::for_paths(&paths).expect("identity config");
+    assert_eq!(identity.storage(), IdentityStorage::Keychain);
+    assert_eq!(IdentityConfig::default().storage(), IdentityStorage::File);
+}
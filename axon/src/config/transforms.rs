@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+
+use crate::message::AgentId;
+
+/// One step of the inbound transformation pipeline (`transforms[]` in
+/// `config.yaml`). Steps run in order on each inbound message that passes
+/// their filters; empty filters match everything.
+#[derive(Debug, Clone, Deserialize, Serialize, Default, PartialEq, Eq)]
+pub struct TransformConfig {
+    /// Application kinds the step applies to (`request`, `response`,
+    /// `message`, `error`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub kinds: Vec<String>,
+    /// Topic patterns (`name`, `prefix.*`, `*`); when set, untopiced
+    /// messages are not matched.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub topics: Vec<String>,
+    /// Sending agents the step applies to.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub peers: Vec<AgentId>,
+    /// Discard matching messages; later steps do not run.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub drop: bool,
+    /// JSON pointers (RFC 6901, e.g. `/auth/token`) into the payload whose
+    /// values are replaced with `"[redacted]"`. Missing paths are skipped.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub redact: Vec<String>,
+    /// Topic to deliver matching messages under.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub set_topic: Option<String>,
+}
+
+impl TransformConfig {
+    /// Text that replaces redacted payload values.
+    pub const REDACTED: &'static str = "[redacted]";
+}
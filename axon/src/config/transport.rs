@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+
+/// QUIC congestion control algorithm, as written in `config.yaml`.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CongestionControl {
+    Cubic,
+    NewReno,
+    Bbr,
+}
+
+/// QUIC congestion control (`transport:` section of `config.yaml`).
+///
+/// Static peers can override both settings on their own entry, e.g. `bbr`
+/// for a WAN peer while LAN peers keep `cubic`.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, Default, PartialEq, Eq)]
+pub struct TransportConfig {
+    /// Controller for connections accepted or dialed; unset is `cubic`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub congestion_control: Option<CongestionControl>,
+    /// Congestion window, in bytes, before the first acknowledgement; unset
+    /// or 0 keeps the controller's default (about 14 KB).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub initial_window_bytes: Option<u64>,
+}
+
+impl TransportConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn initial_window(&self) -> Option<u64> {
+        initial_window(self.initial_window_bytes)
+    }
+}
+
+pub(super) fn initial_window(bytes: Option<u64>) -> Option<u64> {
+    bytes.filter(|bytes| *bytes > 0)
+}
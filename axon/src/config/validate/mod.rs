@@ -0,0 +1,434 @@
+use std::collections::BTreeSet;
+use std::io::ErrorKind;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use base64::{Engine as _, engine::general_purpose::STANDARD};
+use ed25519_dalek::VerifyingKey;
+use serde::Serialize;
+use serde_json::{Value, json};
+
+use super::{
+    LogTarget, MaxMessageBytesConfig, PeerAddr, PersistedConfig, PersistedStaticPeerConfig,
+    application_kind, is_valid_alias, is_valid_profile_name, is_valid_tag,
+};
+use crate::identity::derive_agent_id;
+use crate::message::MAX_MESSAGE_SIZE;
+
+mod schema;
+mod webhooks;
+
+pub use schema::config_schema;
+use webhooks::check_webhooks;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    /// The daemon would reject or ignore the setting.
+    Error,
+    /// Accepted, but probably not what was meant (e.g. a misspelled key).
+    Warning,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ConfigIssue {
+    pub severity: Severity,
+    /// Location in the file, e.g. `peers[0].pubkey`; empty for the whole file.
+    pub path: String,
+    pub message: String,
+}
+
+/// Result of `axon config validate`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigReport {
+    pub config: String,
+    pub exists: bool,
+    /// `true` when there are no errors; warnings do not invalidate.
+    pub valid: bool,
+    pub issues: Vec<ConfigIssue>,
+}
+
+impl ConfigReport {
+    fn push(&mut self, severity: Severity, path: impl Into<String>, message: impl Into<String>) {
+        if severity == Severity::Error {
+            self.valid = false;
+        }
+        self.issues.push(ConfigIssue {
+            severity,
+            path: path.into(),
+            message: message.into(),
+        });
+    }
+}
+
+/// Check `config.yaml` beyond what loading does: unknown keys, static peer
+/// keys and addresses, and alias names. A missing file is valid.
+pub async fn validate_config_file(path: &Path) -> Result<ConfigReport> {
+    let mut report = ConfigReport {
+        config: path.display().to_string(),
+        exists: true,
+        valid: true,
+        issues: Vec::new(),
+    };
+    let raw = match tokio::fs::read_to_string(path).await {
+        Ok(raw) => raw,
+        Err(err) if err.kind() == ErrorKind::NotFound => {
+            report.exists = false;
+            return Ok(report);
+        }
+        Err(err) => {
+            return Err(err).with_context(|| format!("failed to read config: {}", path.display()));
+        }
+    };
+    validate_config_yaml(&raw, &mut report).await;
+    Ok(report)
+}
+
+async fn validate_config_yaml(raw: &str, report: &mut ConfigReport) {
+    let document: Value = match serde_yaml::from_str::<Option<Value>>(raw) {
+        Ok(document) => document.unwrap_or_else(|| json!({})),
+        Err(err) => {
+            report.push(Severity::Error, "", format!("invalid YAML: {err}"));
+            return;
+        }
+    };
+    let mut unknown = Vec::new();
+    unknown_keys(&document, &config_schema(), "", &mut unknown);
+    for key in unknown {
+        report.push(
+            Severity::Warning,
+            key,
+            "unknown key (ignored by the daemon)",
+        );
+    }
+    let config: PersistedConfig = match serde_json::from_value(document) {
+        Ok(config) => config,
+        Err(err) => {
+            report.push(Severity::Error, "", err.to_string());
+            return;
+        }
+    };
+
+    if config.port == Some(0) {
+        report.push(
+            Severity::Error,
+            "port",
+            "port 0 is not valid; QUIC requires a non-zero port",
+        );
+    }
+    if let Some(addr) = &config.advertise_addr
+        && let Err(err) = PeerAddr::parse(addr)
+    {
+        report.push(
+            Severity::Error,
+            "advertise_addr",
+            format!("invalid address '{addr}': {err}"),
+        );
+    }
+    if let Some(exec) = &config.handler.exec
+        && !exec.is_file()
+    {
+        report.push(
+            Severity::Warning,
+            "handler.exec",
+            format!("{} does not exist or is not a file", exec.display()),
+        );
+    }
+    if let Some(script) = &config.handler.script {
+        check_handler_script(report, script);
+    }
+    if cfg!(not(feature = "history")) && config.history.enabled() {
+        report.push(
+            Severity::Error,
+            "history.enabled",
+            "this axon was built without the `history` feature; the daemon will not start",
+        );
+    }
+
+    if config.logging.target() != LogTarget::File {
+        let file_only = [
+            ("logging.path", config.logging.path.is_some()),
+            (
+                "logging.max_file_bytes",
+                config.logging.max_file_bytes.is_some(),
+            ),
+            ("logging.max_files", config.logging.max_files.is_some()),
+        ];
+        for (key, set) in file_only {
+            if set {
+                report.push(
+                    Severity::Warning,
+                    key,
+                    "only used with `target: file`; ignored",
+                );
+            }
+        }
+    }
+
+    for (kind, value) in config.max_message_bytes.entries() {
+        check_message_bytes(report, format!("max_message_bytes.{kind}"), value);
+    }
+
+    let mut peer_lists = vec![("peers".to_string(), &config.peers)];
+    for (name, profile) in &config.profiles {
+        if !is_valid_profile_name(name) {
+            report.push(
+                Severity::Error,
+                format!("profiles.{name}"),
+                "profile names use letters, digits, '-', and '_', starting with a letter",
+            );
+        }
+        if let Some(peers) = &profile.peers {
+            peer_lists.push((format!("profiles.{name}.peers"), peers));
+        }
+    }
+    for (list, peers) in peer_lists {
+        check_static_peers(report, &list, peers).await;
+    }
+
+    for (agent_id, peer) in &config.policy.peers {
+        let at = format!("policy.peers.{agent_id}");
+        if !is_agent_id(agent_id.as_str()) {
+            report.push(
+                Severity::Error,
+                at.clone(),
+                format!("'{agent_id}' is not an agent ID (ed25519.<32 hex>)"),
+            );
+        }
+        for (idx, kind) in peer.allow_kinds.iter().enumerate() {
+            if application_kind(kind).is_none() {
+                report.push(
+                    Severity::Warning,
+                    format!("{at}.allow_kinds[{idx}]"),
+                    format!("'{kind}' is not request, response, message, or error; ignored"),
+                );
+            }
+        }
+    }
+
+    check_webhooks(report, &config.webhooks);
+
+    for (idx, step) in config.transforms.iter().enumerate() {
+        let at = |field: &str| format!("transforms[{idx}].{field}");
+        for (kind_idx, kind) in step.kinds.iter().enumerate() {
+            if application_kind(kind).is_none() {
+                report.push(
+                    Severity::Warning,
+                    at(&format!("kinds[{kind_idx}]")),
+                    format!("'{kind}' is not request, response, message, or error; never matches"),
+                );
+            }
+        }
+        for (peer_idx, agent_id) in step.peers.iter().enumerate() {
+            if !is_agent_id(agent_id.as_str()) {
+                report.push(
+                    Severity::Error,
+                    at(&format!("peers[{peer_idx}]")),
+                    format!("'{agent_id}' is not an agent ID (ed25519.<32 hex>)"),
+                );
+            }
+        }
+        for (pointer_idx, pointer) in step.redact.iter().enumerate() {
+            if !pointer.starts_with('/') {
+                report.push(
+                    Severity::Error,
+                    at(&format!("redact[{pointer_idx}]")),
+                    format!("'{pointer}' is not a JSON pointer (e.g. /auth/token)"),
+                );
+            }
+        }
+        if let Some(topic) = &step.set_topic
+            && !is_topic(topic)
+        {
+            report.push(
+                Severity::Error,
+                at("set_topic"),
+                format!("'{topic}' is not a topic (dot-separated letters, digits, '_', '-'); step is skipped"),
+            );
+        }
+        if step.drop && (!step.redact.is_empty() || step.set_topic.is_some()) {
+            report.push(
+                Severity::Warning,
+                at("drop"),
+                "dropped messages are not redacted or renamed",
+            );
+        }
+    }
+
+    for (name, agent_id) in &config.aliases {
+        let at = format!("aliases.{name}");
+        if !is_valid_alias(name) {
+            report.push(
+                Severity::Error,
+                at.clone(),
+                "alias names start with a letter and use only letters, digits, '-', '_'",
+            );
+        }
+        if !is_agent_id(agent_id.as_str()) {
+            report.push(
+                Severity::Error,
+                at,
+                format!("'{agent_id}' is not an agent ID (ed25519.<32 hex>)"),
+            );
+        }
+    }
+
+    for (agent_id, tags) in &config.tags {
+        let at = format!("tags.{agent_id}");
+        if !is_agent_id(agent_id.as_str()) {
+            report.push(
+                Severity::Error,
+                at.clone(),
+                format!("'{agent_id}' is not an agent ID (ed25519.<32 hex>)"),
+            );
+        }
+        for (idx, tag) in tags.iter().enumerate() {
+            if !is_valid_tag(tag) {
+                report.push(
+                    Severity::Error,
+                    format!("{at}[{idx}]"),
+                    "tags start with a letter or digit and use only letters, digits, '.', '-', '_'",
+                );
+            }
+        }
+    }
+}
+
+/// Agent ID for a base64 Ed25519 public key, or why the key is invalid.
+/// Duplicate, key, address, and size cap checks for one static peer list.
+async fn check_static_peers(
+    report: &mut ConfigReport,
+    list: &str,
+    peers: &[PersistedStaticPeerConfig],
+) {
+    let mut seen = BTreeSet::new();
+    for (idx, peer) in peers.iter().enumerate() {
+        let at = |field: &str| format!("{list}[{idx}].{field}");
+        if !seen.insert(peer.agent_id.as_str()) {
+            report.push(
+                Severity::Warning,
+                at("agent_id"),
+                format!("{} is listed more than once", peer.agent_id),
+            );
+        }
+        match pubkey_agent_id(&peer.pubkey) {
+            Ok(derived) if derived != peer.agent_id.as_str() => report.push(
+                Severity::Error,
+                at("agent_id"),
+                format!("does not match pubkey, which derives {derived}"),
+            ),
+            Ok(_) => {}
+            Err(message) => report.push(Severity::Error, at("pubkey"), message),
+        }
+        if let Err(err) = peer.addr.resolve_for_config_load().await {
+            report.push(
+                Severity::Error,
+                at("addr"),
+                format!("{err:#}; the daemon skips this peer"),
+            );
+        }
+        check_message_bytes(
+            report,
+            at("max_message_bytes"),
+            peer.tuning.max_message_bytes,
+        );
+    }
+}
+
+/// Warn when a byte cap is outside the range the daemon clamps it to.
+#[cfg(feature = "script")]
+fn check_handler_script(report: &mut ConfigReport, script: &Path) {
+    if let Err(err) = super::compile_handler_script(&rhai::Engine::new_raw(), script) {
+        report.push(Severity::Error, "handler.script", format!("{err:#}"));
+    }
+}
+
+#[cfg(not(feature = "script"))]
+fn check_handler_script(report: &mut ConfigReport, _script: &Path) {
+    report.push(
+        Severity::Error,
+        "handler.script",
+        "this axon was built without the `script` feature; the daemon will not start",
+    );
+}
+
+fn check_message_bytes(report: &mut ConfigReport, at: String, value: Option<u32>) {
+    let Some(bytes) = value.filter(|n| *n > 0) else {
+        return;
+    };
+    let effective = MaxMessageBytesConfig::effective(value).unwrap_or_default();
+    if bytes as usize != effective {
+        report.push(
+            Severity::Warning,
+            at,
+            format!(
+                "must be {}-{MAX_MESSAGE_SIZE}; the daemon uses {effective}",
+                MaxMessageBytesConfig::MIN_BYTES
+            ),
+        );
+    }
+}
+
+fn pubkey_agent_id(pubkey: &str) -> Result<String, String> {
+    let bytes = STANDARD
+        .decode(pubkey.trim())
+        .map_err(|_| "not valid base64".to_string())?;
+    let bytes: [u8; 32] = bytes
+        .try_into()
+        .map_err(|bytes: Vec<u8>| format!("must decode to 32 bytes, got {}", bytes.len()))?;
+    let key = VerifyingKey::from_bytes(&bytes)
+        .map_err(|_| "not a valid Ed25519 public key".to_string())?;
+    Ok(derive_agent_id(&key))
+}
+
+pub(crate) fn is_agent_id(input: &str) -> bool {
+    input.strip_prefix("ed25519.").is_some_and(|hex| {
+        hex.len() == 32 && hex.chars().all(|c| matches!(c, '0'..='9' | 'a'..='f'))
+    })
+}
+
+/// Dotted paths of keys in `value` that `schema` does not declare. Only
+/// objects with `properties` are checked; free-form maps (`aliases`) are not.
+/// Same rules as the daemon's topic validation, without wildcards.
+fn is_topic(input: &str) -> bool {
+    (1..=128).contains(&input.len())
+        && input.split('.').all(|segment| {
+            !segment.is_empty()
+                && segment
+                    .chars()
+                    .all(|ch| ch.is_ascii_alphanumeric() || ch == '_' || ch == '-')
+        })
+}
+
+fn unknown_keys(value: &Value, schema: &Value, path: &str, out: &mut Vec<String>) {
+    match value {
+        Value::Object(map) => {
+            let Some(properties) = schema.get("properties").and_then(Value::as_object) else {
+                return;
+            };
+            for (key, child) in map {
+                let child_path = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{path}.{key}")
+                };
+                match properties.get(key) {
+                    Some(child_schema) => unknown_keys(child, child_schema, &child_path, out),
+                    None => out.push(child_path),
+                }
+            }
+        }
+        Value::Array(items) => {
+            if let Some(item_schema) = schema.get("items") {
+                for (idx, item) in items.iter().enumerate() {
+                    unknown_keys(item, item_schema, &format!("{path}[{idx}]"), out);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+#[path = "tests.rs"]
+mod tests;
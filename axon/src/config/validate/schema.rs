@@ -0,0 +1,195 @@
+use serde_json::{Value, json};
+
+use super::webhooks::webhooks_schema;
+use crate::message::MAX_MESSAGE_SIZE;
+
+/// JSON Schema for `config.yaml`, for editor integration
+/// (`axon config schema`). Keep in step with
+/// [`PersistedConfig`](crate::config::PersistedConfig).
+pub fn config_schema() -> Value {
+    let seconds =
+        |description: &str| json!({"type": "integer", "minimum": 0, "description": description});
+    let message_bytes = |description: &str| json!({"type": "integer", "minimum": 0, "maximum": MAX_MESSAGE_SIZE, "description": description});
+    let congestion_control = |description: &str| json!({"type": "string", "enum": ["cubic", "new_reno", "bbr"], "description": description});
+    let section = |description: &str, properties: Value| {
+        json!({
+            "type": "object",
+            "description": description,
+            "additionalProperties": false,
+            "properties": properties,
+        })
+    };
+    let static_peers = json!({
+        "type": "array",
+        "description": "Static peers, connected to without mDNS.",
+        "items": {
+            "type": "object",
+            "additionalProperties": false,
+            "required": ["agent_id", "addr", "pubkey"],
+            "properties": {
+                "agent_id": {"type": "string", "pattern": "^ed25519\\.[0-9a-f]{32}$"},
+                "addr": {"type": "string", "description": "ip:port or host:port; hostnames resolve at load time."},
+                "pubkey": {"type": "string", "description": "Base64 Ed25519 public key (32 bytes)."},
+                "handshake_timeout_secs": seconds("Seconds a QUIC handshake when dialing this peer may take (default 10)."),
+                "idle_timeout_secs": seconds("Idle timeout offered when dialing this peer; QUIC uses the lower of both ends' values."),
+                "reconnect_max_backoff_secs": seconds("Longest wait between reconnect attempts to this peer (default 30)."),
+                "max_message_bytes": message_bytes("Cap on every envelope to or from this peer, on top of the per-kind caps."),
+                "congestion_control": congestion_control("Congestion controller for connections dialed to this peer (default: the transport section's)."),
+                "initial_window_bytes": {"type": "integer", "minimum": 0, "description": "Initial congestion window in bytes for connections dialed to this peer."}
+            }
+        }
+    });
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "AXON config.yaml",
+        "type": "object",
+        "additionalProperties": false,
+        "properties": {
+            "name": {"type": "string", "description": "Human-readable agent name reported by `whoami`."},
+            "port": {"type": "integer", "minimum": 1, "maximum": 65535, "default": 7100, "description": "QUIC listen port."},
+            "advertise_addr": {"type": "string", "description": "host:port or ip:port other agents use to reach this one (`axon identity`)."},
+            "peers": static_peers.clone(),
+            "ipc": section("IPC client connection limits; unset disables a limit.", json!({
+                "idle_timeout_secs": seconds("Close clients that send no command for this many seconds."),
+                "max_lifetime_secs": seconds("Close clients once their connection is this many seconds old."),
+            })),
+            "handler": section("External handler for inbound requests.", json!({
+                "exec": {"type": "string", "description": "Executable spawned once per inbound request; unset disables the handler."},
+                "script": {"type": "string", "description": "Rhai script whose handle(request) is consulted before exec; needs the `script` build feature."},
+                "timeout_secs": seconds("Seconds a handler run may take before it is killed (default 10)."),
+                "max_concurrent": {"type": "integer", "minimum": 1, "description": "Maximum runs of each handler (exec, script) at once (default 4)."},
+                "cache_ttl_secs": seconds("Reuse a response for an identical request from the same peer for this many seconds; 0 disables."),
+                "cache_max_entries": {"type": "integer", "minimum": 1, "description": "Cached responses kept at once (default 1024)."},
+            })),
+            "history": section("Persisted message history.", json!({
+                "enabled": {"type": "boolean", "default": false, "description": "Record sent and received envelopes in history.sqlite3."},
+                "retention_days": {"type": "integer", "minimum": 0, "default": 7, "description": "Delete entries older than this many days; 0 keeps everything."},
+            })),
+            "identity": section("Where the identity seed is kept.", json!({
+                "storage": {"enum": ["file", "keychain"], "default": "file", "description": "file keeps the seed in identity.key; keychain moves it to the macOS Keychain or Linux Secret Service (needs the `keychain` build feature) and uses identity.key while neither is reachable."},
+            })),
+            "audit": section("Append-only audit log.", json!({
+                "enabled": {"type": "boolean", "default": false, "description": "Record message and peer activity in audit.jsonl."},
+                "max_file_bytes": {"type": "integer", "minimum": 0, "description": "Rotate audit.jsonl once it would grow past this many bytes."},
+                "max_files": {"type": "integer", "minimum": 0, "description": "Rotated files kept."},
+            })),
+            "event_log": section("Machine-readable JSON-lines event log.", json!({
+                "enabled": {"type": "boolean", "default": false, "description": "Append sends, receives, connects, and errors to events.jsonl."},
+                "max_file_bytes": {"type": "integer", "minimum": 0, "description": "Rotate events.jsonl once it would grow past this many bytes."},
+                "max_file_age_secs": seconds("Rotate events.jsonl once it is this many seconds old (default 86400); 0 rotates by size only."),
+                "max_files": {"type": "integer", "minimum": 0, "description": "Rotated files kept."},
+            })),
+            "rate_limit": section("Per-peer inbound rate limits; unset or 0 disables a limit.", json!({
+                "messages_per_sec": {"type": "integer", "minimum": 0, "description": "Messages each peer may send per second, with a one-second burst."},
+                "bytes_per_sec": {"type": "integer", "minimum": 0, "description": "Envelope bytes each peer may send per second, with a one-second burst."},
+            })),
+            "shutdown": section("Graceful shutdown.", json!({
+                "drain_timeout_secs": seconds("Seconds to wait for in-flight inbound streams before closing connections; 0 closes immediately."),
+            })),
+            "idempotency": section("Response cache for deduplicating retried requests.", json!({
+                "window_secs": seconds("Seconds a response stays cached; 0 disables the cache."),
+                "max_entries": {"type": "integer", "minimum": 0, "description": "Cached responses kept across all peers."},
+            })),
+            "handshake_ban": section("Temporary bans for source IPs whose inbound TLS handshakes keep failing.", json!({
+                "max_failures": {"type": "integer", "minimum": 0, "default": 10, "description": "Failed handshakes within window_secs that ban the address; 0 disables banning."},
+                "window_secs": seconds("Seconds over which failures are counted (default 60)."),
+                "ban_secs": seconds("First ban length in seconds (default 60); each repeat ban doubles it."),
+                "max_ban_secs": seconds("Longest ban in seconds (default 3600)."),
+            })),
+            "transport": section("QUIC congestion control; static peers can override both settings.", json!({
+                "congestion_control": congestion_control("Controller for connections accepted or dialed (default cubic)."),
+                "initial_window_bytes": {"type": "integer", "minimum": 0, "description": "Congestion window in bytes before the first acknowledgement; unset or 0 keeps the controller's default."},
+            })),
+            "heartbeat": section("Liveness heartbeats on connected peer links.", json!({
+                "interval_secs": {"type": "integer", "minimum": 0, "default": 0, "description": "Seconds between heartbeats sent to each connected peer; 0 (the default) disables. Enable only when every peer understands heartbeat."},
+                "miss_threshold": {"type": "integer", "minimum": 0, "default": 3, "description": "Consecutive intervals without traffic before a peer is degraded."},
+            })),
+            "prewarm": section("Dial known peers as soon as the daemon starts.", json!({
+                "enabled": {"type": "boolean", "default": false, "description": "Dial every static and cached peer at startup instead of on the first reconnect tick."},
+                "max_concurrent": {"type": "integer", "minimum": 0, "default": 16, "description": "Startup dials in flight at once."},
+            })),
+            "staleness": section("How long unseen peers stay in the peer table; static peers never expire.", json!({
+                "discovered_ttl_secs": {"type": "integer", "minimum": 0, "default": 60, "description": "Seconds an mDNS-discovered peer may go unseen before removal; 0 never expires."},
+                "cached_ttl_secs": {"type": "integer", "minimum": 0, "default": 0, "description": "Seconds a peer loaded from known_peers.json may go unseen before removal; 0 never expires."},
+            })),
+            "max_message_bytes": section("Per-kind caps on encoded envelope size, enforced on send and receive; unset or 0 means the 64KB wire limit.", json!({
+                "request": message_bytes("Largest `request` envelope."),
+                "response": message_bytes("Largest `response` envelope."),
+                "message": message_bytes("Largest `message` envelope."),
+                "error": message_bytes("Largest `error` envelope."),
+            })),
+            "policy": section("Per-peer authorization; peers without an entry may send every kind.", json!({
+                "peers": {
+                    "type": "object",
+                    "description": "Policy per agent ID.",
+                    "propertyNames": {"pattern": "^ed25519\\.[0-9a-f]{32}$"},
+                    "additionalProperties": {
+                        "type": "object",
+                        "additionalProperties": false,
+                        "properties": {
+                            "allow_kinds": {
+                                "type": "array",
+                                "description": "Application kinds the peer may send; others are rejected with `not_authorized`.",
+                                "items": {"enum": ["request", "response", "message", "error"]}
+                            }
+                        }
+                    }
+                }
+            })),
+            "webhooks": webhooks_schema(seconds),
+            "transforms": {
+                "type": "array",
+                "description": "Steps run in order on inbound messages before history, webhooks, and IPC delivery; empty filters match everything.",
+                "items": {
+                    "type": "object",
+                    "additionalProperties": false,
+                    "properties": {
+                        "kinds": {"type": "array", "description": "Application kinds the step applies to.", "items": {"enum": ["request", "response", "message", "error"]}},
+                        "topics": {"type": "array", "description": "Topic patterns the step applies to (`name`, `prefix.*`, `*`).", "items": {"type": "string"}},
+                        "peers": {"type": "array", "description": "Sending agents the step applies to.", "items": {"type": "string", "pattern": "^ed25519\\.[0-9a-f]{32}$"}},
+                        "drop": {"type": "boolean", "default": false, "description": "Discard matching messages; later steps do not run."},
+                        "redact": {"type": "array", "description": "JSON pointers into the payload whose values become \"[redacted]\".", "items": {"type": "string", "pattern": "^/"}},
+                        "set_topic": {"type": "string", "pattern": "^[A-Za-z0-9_-]+(\\.[A-Za-z0-9_-]+)*$", "description": "Topic to deliver matching messages under."},
+                    }
+                }
+            },
+            "logging": section("Daemon log output.", json!({
+                "target": {"enum": ["stdout", "journald", "syslog", "file"], "default": "stdout", "description": "Log sink: stdout text, journald fields, RFC 5424 syslog on /dev/log, or a JSON-lines file."},
+                "path": {"type": "string", "description": "Log file for `target: file`; relative to the state root (default axon.log)."},
+                "max_file_bytes": {"type": "integer", "minimum": 0, "description": "Rotate the log file once it would grow past this many bytes."},
+                "max_files": {"type": "integer", "minimum": 0, "description": "Rotated log files kept."},
+            })),
+            "aliases": {
+                "type": "object",
+                "description": "CLI shorthand names for agent IDs (`axon alias`); unused by the daemon.",
+                "propertyNames": {"pattern": "^[A-Za-z][A-Za-z0-9_-]{0,63}$"},
+                "additionalProperties": {"type": "string", "pattern": "^ed25519\\.[0-9a-f]{32}$"}
+            },
+            "tags": {
+                "type": "object",
+                "description": "Free-form labels per peer agent ID (`axon peers tag`), reported and filterable in `peers`.",
+                "propertyNames": {"pattern": "^ed25519\\.[0-9a-f]{32}$"},
+                "additionalProperties": {
+                    "type": "array",
+                    "items": {"type": "string", "pattern": "^[A-Za-z0-9][A-Za-z0-9._-]{0,63}$"}
+                }
+            },
+            "profiles": {
+                "type": "object",
+                "description": "Named overrides chosen with `axon --profile <name>`; other settings are shared.",
+                "propertyNames": {"pattern": "^[A-Za-z][A-Za-z0-9_-]{0,63}$"},
+                "additionalProperties": {
+                    "type": "object",
+                    "additionalProperties": false,
+                    "properties": {
+                        "name": {"type": "string"},
+                        "port": {"type": "integer", "minimum": 1, "maximum": 65535},
+                        "advertise_addr": {"type": "string"},
+                        "identity_dir": {"type": "string", "description": "Directory holding identity.key, relative to the state root (default profiles/<name>)."},
+                        "peers": static_peers
+                    }
+                }
+            }
+        }
+    })
+}
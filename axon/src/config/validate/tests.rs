@@ -0,0 +1,472 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use ed25519_dalek::SigningKey;
+use tempfile::tempdir;
+
+use super::*;
+use crate::config::{
+    AuditConfig, CongestionControl, EventLogConfig, HandlerConfig, HandshakeBanConfig,
+    HeartbeatConfig, HistoryConfig, IdempotencyConfig, IdentityConfig, IdentityStorage, IpcConfig,
+    LogTarget, LoggingConfig, MaxMessageBytesConfig, PeerPolicyConfig, PeerTuningConfig,
+    PersistedStaticPeerConfig, PolicyConfig, PrewarmConfig, ProfileConfig, RateLimitConfig,
+    ShutdownConfig, StalenessConfig, TransformConfig, TransportConfig, WebhookConfig,
+};
+use crate::message::AgentId;
+
+fn peer_key() -> (String, String) {
+    let key = SigningKey::from_bytes(&[7u8; 32]).verifying_key();
+    (derive_agent_id(&key), STANDARD.encode(key.to_bytes()))
+}
+
+async fn validate(yaml: &str) -> ConfigReport {
+    let dir = tempdir().expect("temp dir");
+    let path = dir.path().join("config.yaml");
+    std::fs::write(&path, yaml).expect("write config");
+    validate_config_file(&path).await.expect("validate")
+}
+
+fn issue_at<'a>(report: &'a ConfigReport, path: &str) -> &'a ConfigIssue {
+    report
+        .issues
+        .iter()
+        .find(|issue| issue.path == path)
+        .unwrap_or_else(|| panic!("no issue at {path}: {:?}", report.issues))
+}
+
+#[tokio::test]
+async fn missing_and_empty_configs_are_valid() {
+    let dir = tempdir().expect("temp dir");
+    let report = validate_config_file(&dir.path().join("config.yaml"))
+        .await
+        .expect("validate");
+    assert!(report.valid && !report.exists && report.issues.is_empty());
+
+    let report = validate("").await;
+    assert!(report.valid && report.exists && report.issues.is_empty());
+}
+
+#[tokio::test]
+async fn valid_peer_passes() {
+    let (agent_id, pubkey) = peer_key();
+    let report = validate(&format!(
+        "port: 7100\npeers:\n  - agent_id: {agent_id}\n    addr: 127.0.0.1:7100\n    pubkey: {pubkey}\naliases:\n  laptop: {agent_id}\n"
+    ))
+    .await;
+    assert!(report.valid, "{:?}", report.issues);
+    assert!(report.issues.is_empty(), "{:?}", report.issues);
+}
+
+#[tokio::test]
+async fn unknown_keys_are_warnings() {
+    let report = validate("prot: 7100\nipc:\n  idle_timeout: 5\n").await;
+    assert!(report.valid);
+    assert_eq!(issue_at(&report, "prot").severity, Severity::Warning);
+    assert_eq!(
+        issue_at(&report, "ipc.idle_timeout").severity,
+        Severity::Warning
+    );
+}
+
+#[tokio::test]
+async fn bad_pubkeys_and_mismatched_ids_are_errors() {
+    let (agent_id, pubkey) = peer_key();
+    let report = validate(&format!(
+        "peers:\n  - agent_id: ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa\n    addr: 127.0.0.1:7100\n    pubkey: Zm9v\n  - agent_id: ed25519.bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb\n    addr: 127.0.0.1:7101\n    pubkey: {pubkey}\n  - agent_id: {agent_id}\n    addr: 127.0.0.1:7102\n    pubkey: '%%%'\n"
+    ))
+    .await;
+    assert!(!report.valid);
+    assert!(
+        issue_at(&report, "peers[0].pubkey")
+            .message
+            .contains("32 bytes, got 3")
+    );
+    assert!(
+        issue_at(&report, "peers[1].agent_id")
+            .message
+            .contains(&agent_id)
+    );
+    assert!(
+        issue_at(&report, "peers[2].pubkey")
+            .message
+            .contains("base64")
+    );
+}
+
+#[tokio::test]
+async fn unresolvable_addrs_and_bad_aliases_are_errors() {
+    let (agent_id, pubkey) = peer_key();
+    let report = validate(&format!(
+        "port: 0\npeers:\n  - agent_id: {agent_id}\n    addr: no-such-host.invalid:7100\n    pubkey: {pubkey}\naliases:\n  9lives: {agent_id}\n"
+    ))
+    .await;
+    assert!(!report.valid);
+    assert_eq!(issue_at(&report, "port").severity, Severity::Error);
+    assert!(
+        issue_at(&report, "peers[0].addr")
+            .message
+            .contains("skips this peer")
+    );
+    assert_eq!(
+        issue_at(&report, "aliases.9lives").severity,
+        Severity::Error
+    );
+}
+
+#[tokio::test]
+async fn bad_tags_are_errors() {
+    let (agent_id, _) = peer_key();
+    let report = validate(&format!(
+        "tags:\n  {agent_id}: [prod, \"bad tag\"]\n  laptop: [gpu]\n"
+    ))
+    .await;
+    assert!(!report.valid);
+    assert_eq!(
+        issue_at(&report, &format!("tags.{agent_id}[1]")).severity,
+        Severity::Error
+    );
+    assert_eq!(issue_at(&report, "tags.laptop").severity, Severity::Error);
+}
+
+#[tokio::test]
+async fn syntax_and_type_errors_stop_validation() {
+    let report = validate("peers: [\n").await;
+    assert!(!report.valid);
+    assert!(issue_at(&report, "").message.starts_with("invalid YAML"));
+
+    let report = validate("port: seventy\n").await;
+    assert!(!report.valid);
+    assert_eq!(report.issues.len(), 1);
+}
+
+#[tokio::test]
+async fn out_of_range_message_caps_are_warnings() {
+    let report = validate(
+        "max_message_bytes:
+  request: 100
+  response: 70000
+  message: 4096
+  error: 0
+",
+    )
+    .await;
+    assert!(report.valid, "{:?}", report.issues);
+    assert_eq!(report.issues.len(), 2, "{:?}", report.issues);
+    assert!(
+        issue_at(&report, "max_message_bytes.request")
+            .message
+            .contains("uses 1024")
+    );
+    assert!(
+        issue_at(&report, "max_message_bytes.response")
+            .message
+            .contains("uses 65536")
+    );
+}
+
+#[tokio::test]
+async fn policy_checks_agent_ids_and_kind_names() {
+    let report = validate(
+        "policy:
+  peers:
+    ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa:
+      allow_kinds: [message, query, heartbeat]
+    laptop:
+      allow_kinds: [request]
+",
+    )
+    .await;
+    assert!(!report.valid);
+    assert_eq!(report.issues.len(), 3, "{:?}", report.issues);
+    let base = "policy.peers.ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+    assert_eq!(
+        issue_at(&report, &format!("{base}.allow_kinds[1]")).severity,
+        Severity::Warning
+    );
+    assert_eq!(
+        issue_at(&report, &format!("{base}.allow_kinds[2]")).severity,
+        Severity::Warning
+    );
+    assert_eq!(
+        issue_at(&report, "policy.peers.laptop").severity,
+        Severity::Error
+    );
+}
+
+#[tokio::test]
+async fn webhooks_check_urls_kinds_and_peers() {
+    let report = validate(
+        "webhooks:
+  - url: https://example.com/hook
+    kinds: [message, heartbeat]
+    peers: [laptop]
+  - url: ftp://example.com/
+",
+    )
+    .await;
+    assert!(!report.valid);
+    assert_eq!(report.issues.len(), 3, "{:?}", report.issues);
+    assert_eq!(
+        issue_at(&report, "webhooks[0].kinds[1]").severity,
+        Severity::Warning
+    );
+    assert_eq!(
+        issue_at(&report, "webhooks[0].peers[0]").severity,
+        Severity::Error
+    );
+    assert!(
+        issue_at(&report, "webhooks[1].url")
+            .message
+            .contains("http://")
+    );
+}
+
+#[tokio::test]
+async fn transforms_check_kinds_peers_pointers_and_topics() {
+    let report = validate(
+        "transforms:
+  - kinds: [message, heartbeat]
+    peers: [laptop]
+    redact: [auth.token, /auth/token]
+  - drop: true
+    set_topic: ci.builds
+  - set_topic: ci..builds
+",
+    )
+    .await;
+    assert!(!report.valid);
+    assert_eq!(report.issues.len(), 5, "{:?}", report.issues);
+    assert_eq!(
+        issue_at(&report, "transforms[0].kinds[1]").severity,
+        Severity::Warning
+    );
+    assert_eq!(
+        issue_at(&report, "transforms[0].peers[0]").severity,
+        Severity::Error
+    );
+    assert_eq!(
+        issue_at(&report, "transforms[0].redact[0]").severity,
+        Severity::Error
+    );
+    assert_eq!(
+        issue_at(&report, "transforms[1].drop").severity,
+        Severity::Warning
+    );
+    assert_eq!(
+        issue_at(&report, "transforms[2].set_topic").severity,
+        Severity::Error
+    );
+}
+
+#[tokio::test]
+async fn logging_path_without_file_target_is_a_warning() {
+    let report = validate("logging:\n  target: journald\n  path: axon.log\n  max_files: 3\n").await;
+    assert!(report.valid);
+    assert_eq!(
+        issue_at(&report, "logging.path").severity,
+        Severity::Warning
+    );
+    assert_eq!(
+        issue_at(&report, "logging.max_files").severity,
+        Severity::Warning
+    );
+
+    let report = validate("logging:\n  target: syslogd\n").await;
+    assert!(!report.valid, "unknown targets fail to parse");
+}
+
+#[tokio::test]
+async fn history_needs_the_history_feature() {
+    let report = validate("history:\n  enabled: true\n").await;
+    assert_eq!(
+        report.valid,
+        cfg!(feature = "history"),
+        "{:?}",
+        report.issues
+    );
+}
+
+#[tokio::test]
+async fn handler_script_must_compile_and_define_handle() {
+    let dir = tempdir().expect("temp dir");
+    let good = dir.path().join("good.rhai");
+    std::fs::write(&good, "fn handle(request) { #{ ok: true } }").expect("write script");
+    let bad = dir.path().join("bad.rhai");
+    std::fs::write(&bad, "fn respond(request) { () }").expect("write script");
+
+    let report = validate(&format!("handler:\n  script: {}\n", good.display())).await;
+    assert_eq!(
+        report.valid,
+        cfg!(feature = "script"),
+        "{:?}",
+        report.issues
+    );
+
+    let report = validate(&format!("handler:\n  script: {}\n", bad.display())).await;
+    assert!(!report.valid);
+    assert_eq!(
+        issue_at(&report, "handler.script").severity,
+        Severity::Error
+    );
+}
+
+#[test]
+fn schema_declares_every_config_key() {
+    let (agent_id, pubkey) = peer_key();
+    let config = PersistedConfig {
+        name: Some("alice".to_string()),
+        port: Some(7100),
+        advertise_addr: Some("alice.local:7100".to_string()),
+        peers: vec![PersistedStaticPeerConfig {
+            agent_id: AgentId::new(agent_id.clone()),
+            addr: PeerAddr::parse("127.0.0.1:7100").expect("addr"),
+            pubkey,
+            tuning: PeerTuningConfig {
+                handshake_timeout_secs: Some(1),
+                idle_timeout_secs: Some(1),
+                reconnect_max_backoff_secs: Some(1),
+                max_message_bytes: Some(1),
+                congestion_control: Some(CongestionControl::Bbr),
+                initial_window_bytes: Some(1),
+            },
+        }],
+        ipc: IpcConfig {
+            idle_timeout_secs: Some(1),
+            max_lifetime_secs: Some(1),
+        },
+        handler: HandlerConfig {
+            exec: Some(PathBuf::from("/bin/true")),
+            script: Some(PathBuf::from("handler.rhai")),
+            timeout_secs: Some(1),
+            max_concurrent: Some(1),
+            cache_ttl_secs: Some(1),
+            cache_max_entries: Some(1),
+        },
+        history: HistoryConfig {
+            enabled: Some(true),
+            retention_days: Some(1),
+        },
+        identity: IdentityConfig {
+            storage: Some(IdentityStorage::Keychain),
+        },
+        audit: AuditConfig {
+            enabled: Some(true),
+            max_file_bytes: Some(1),
+            max_files: Some(1),
+        },
+        event_log: EventLogConfig {
+            enabled: Some(true),
+            max_file_bytes: Some(1),
+            max_file_age_secs: Some(1),
+            max_files: Some(1),
+        },
+        rate_limit: RateLimitConfig {
+            messages_per_sec: Some(1),
+            bytes_per_sec: Some(1),
+        },
+        shutdown: ShutdownConfig {
+            drain_timeout_secs: Some(1),
+        },
+        idempotency: IdempotencyConfig {
+            window_secs: Some(1),
+            max_entries: Some(1),
+        },
+        handshake_ban: HandshakeBanConfig {
+            max_failures: Some(1),
+            window_secs: Some(1),
+            ban_secs: Some(1),
+            max_ban_secs: Some(1),
+        },
+        transport: TransportConfig {
+            congestion_control: Some(CongestionControl::NewReno),
+            initial_window_bytes: Some(1),
+        },
+        heartbeat: HeartbeatConfig {
+            interval_secs: Some(1),
+            miss_threshold: Some(1),
+        },
+        prewarm: PrewarmConfig {
+            enabled: Some(true),
+            max_concurrent: Some(1),
+        },
+        staleness: StalenessConfig {
+            discovered_ttl_secs: Some(1),
+            cached_ttl_secs: Some(1),
+        },
+        max_message_bytes: MaxMessageBytesConfig {
+            request: Some(1),
+            response: Some(1),
+            message: Some(1),
+            error: Some(1),
+        },
+        policy: PolicyConfig {
+            peers: BTreeMap::from([(
+                AgentId::new(agent_id.clone()),
+                PeerPolicyConfig {
+                    allow_kinds: vec!["message".to_string()],
+                },
+            )]),
+        },
+        webhooks: vec![WebhookConfig {
+            url: "https://example.com/hook".to_string(),
+            kinds: vec!["message".to_string()],
+            topics: vec!["builds.*".to_string()],
+            peers: vec![AgentId::new(agent_id.clone())],
+            secret: Some("s3cret".to_string()),
+            max_retries: Some(1),
+            connect_timeout_secs: Some(1),
+            timeout_secs: Some(1),
+        }],
+        transforms: vec![TransformConfig {
+            kinds: vec!["message".to_string()],
+            topics: vec!["builds.*".to_string()],
+            peers: vec![AgentId::new(agent_id.clone())],
+            drop: true,
+            redact: vec!["/auth/token".to_string()],
+            set_topic: Some("ci.builds".to_string()),
+        }],
+        logging: LoggingConfig {
+            target: Some(LogTarget::File),
+            path: Some(PathBuf::from("logs/axon.log")),
+            max_file_bytes: Some(1 << 20),
+            max_files: Some(3),
+        },
+        aliases: BTreeMap::from([("laptop".to_string(), AgentId::new(agent_id.clone()))]),
+        tags: BTreeMap::from([(AgentId::new(agent_id.clone()), vec!["prod".to_string()])]),
+        profiles: BTreeMap::from([(
+            "work".to_string(),
+            ProfileConfig {
+                name: Some("alice-work".to_string()),
+                port: Some(7200),
+                advertise_addr: Some("alice.work:7200".to_string()),
+                identity_dir: Some(PathBuf::from("profiles/work")),
+                peers: Some(Vec::new()),
+            },
+        )]),
+    };
+    let value = serde_json::to_value(&config).expect("encode config");
+    let mut unknown = Vec::new();
+    unknown_keys(&value, &config_schema(), "", &mut unknown);
+    assert!(unknown.is_empty(), "schema is missing {unknown:?}");
+}
+
+#[tokio::test]
+async fn profiles_check_names_and_their_peers() {
+    let report = validate(
+        "profiles:
+  work:
+    port: 7200
+    peers:
+      - agent_id: ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa
+        addr: 127.0.0.1:7100
+        pubkey: Zm9v
+  2fast:
+    port: 7300
+",
+    )
+    .await;
+    assert!(!report.valid);
+    assert_eq!(report.issues.len(), 2, "{:?}", report.issues);
+    issue_at(&report, "profiles.2fast");
+    issue_at(&report, "profiles.work.peers[0].pubkey");
+}
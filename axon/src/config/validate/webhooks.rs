@@ -0,0 +1,56 @@
+use serde_json::{Value, json};
+
+use super::{ConfigReport, Severity, is_agent_id};
+use crate::config::{WebhookConfig, WebhookUrl, application_kind};
+
+/// Check each `webhooks` entry: URL scheme and host, kind filters, and
+/// sender agent IDs.
+pub(super) fn check_webhooks(report: &mut ConfigReport, webhooks: &[WebhookConfig]) {
+    for (idx, hook) in webhooks.iter().enumerate() {
+        let at = |field: &str| format!("webhooks[{idx}].{field}");
+        if let Err(message) = WebhookUrl::parse(&hook.url) {
+            report.push(Severity::Error, at("url"), message);
+        }
+        for (kind_idx, kind) in hook.kinds.iter().enumerate() {
+            if application_kind(kind).is_none() {
+                report.push(
+                    Severity::Warning,
+                    at(&format!("kinds[{kind_idx}]")),
+                    format!("'{kind}' is not request, response, message, or error; never matches"),
+                );
+            }
+        }
+        for (peer_idx, agent_id) in hook.peers.iter().enumerate() {
+            if !is_agent_id(agent_id.as_str()) {
+                report.push(
+                    Severity::Error,
+                    at(&format!("peers[{peer_idx}]")),
+                    format!("'{agent_id}' is not an agent ID (ed25519.<32 hex>)"),
+                );
+            }
+        }
+    }
+}
+
+/// Schema for the `webhooks` list; `seconds` builds the timeout fields.
+pub(super) fn webhooks_schema(seconds: impl Fn(&str) -> Value) -> Value {
+    json!({
+        "type": "array",
+        "description": "HTTP endpoints that matching inbound messages are POSTed to; empty filters match everything.",
+        "items": {
+            "type": "object",
+            "additionalProperties": false,
+            "required": ["url"],
+            "properties": {
+                "url": {"type": "string", "pattern": "^https?://", "description": "Endpoint the envelope JSON is POSTed to."},
+                "kinds": {"type": "array", "description": "Application kinds forwarded.", "items": {"enum": ["request", "response", "message", "error"]}},
+                "topics": {"type": "array", "description": "Topic patterns forwarded (`name`, `prefix.*`, `*`).", "items": {"type": "string"}},
+                "peers": {"type": "array", "description": "Sending agents forwarded.", "items": {"type": "string", "pattern": "^ed25519\\.[0-9a-f]{32}$"}},
+                "secret": {"type": "string", "description": "Key for the X-Axon-Signature HMAC-SHA256 of `<X-Axon-Timestamp>.<body>`."},
+                "max_retries": {"type": "integer", "minimum": 0, "default": 3, "description": "Retries after a connection error, timeout, 429, or 5xx."},
+                "connect_timeout_secs": seconds("Seconds a delivery attempt may spend resolving the host and connecting (default 5)."),
+                "timeout_secs": seconds("Seconds a connected delivery attempt may spend on the TLS handshake, sending, and reading the response status, in total (default 10)."),
+            }
+        }
+    })
+}
@@ -0,0 +1,133 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::message::AgentId;
+
+/// One HTTP endpoint that matching inbound messages are POSTed to
+/// (`webhooks[]` in `config.yaml`). Empty filters match everything.
+#[derive(Debug, Clone, Deserialize, Serialize, Default, PartialEq, Eq)]
+pub struct WebhookConfig {
+    /// `http://` or `https://` URL the envelope JSON is POSTed to.
+    pub url: String,
+    /// Application kinds forwarded (`request`, `response`, `message`, `error`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub kinds: Vec<String>,
+    /// Topic patterns (`name`, `prefix.*`, `*`); when set, untopiced
+    /// messages are not forwarded.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub topics: Vec<String>,
+    /// Sending agents whose messages are forwarded.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub peers: Vec<AgentId>,
+    /// Key for the `X-Axon-Signature` HMAC-SHA256 of the body; unset sends
+    /// no signature.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+    /// Retries after a failed delivery (connection error, timeout, 429, or 5xx).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_retries: Option<u32>,
+    /// Seconds a delivery attempt may spend resolving the host and
+    /// connecting.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connect_timeout_secs: Option<u64>,
+    /// Seconds a connected delivery attempt may spend on the TLS handshake,
+    /// sending the request, and reading the response status, in total.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+}
+
+impl WebhookConfig {
+    pub const DEFAULT_MAX_RETRIES: u32 = 3;
+    pub const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+    pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+    pub fn max_retries(&self) -> u32 {
+        self.max_retries.unwrap_or(Self::DEFAULT_MAX_RETRIES)
+    }
+
+    pub fn connect_timeout(&self) -> Duration {
+        self.connect_timeout_secs
+            .filter(|secs| *secs > 0)
+            .map(Duration::from_secs)
+            .unwrap_or(Self::DEFAULT_CONNECT_TIMEOUT)
+    }
+
+    pub fn timeout(&self) -> Duration {
+        self.timeout_secs
+            .filter(|secs| *secs > 0)
+            .map(Duration::from_secs)
+            .unwrap_or(Self::DEFAULT_TIMEOUT)
+    }
+}
+
+/// The parts of a webhook URL needed to send an HTTP/1.1 request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WebhookUrl {
+    pub tls: bool,
+    pub host: String,
+    pub port: u16,
+    /// Path and query, starting with `/`.
+    pub path: String,
+}
+
+impl WebhookUrl {
+    /// Parse `scheme://host[:port][/path]`. Userinfo and fragments are not
+    /// supported; IPv6 hosts are written in brackets.
+    pub fn parse(url: &str) -> Result<Self, String> {
+        let (tls, rest) = if let Some(rest) = url.strip_prefix("https://") {
+            (true, rest)
+        } else if let Some(rest) = url.strip_prefix("http://") {
+            (false, rest)
+        } else {
+            return Err("must start with http:// or https://".to_string());
+        };
+        if url.contains('#') {
+            return Err("fragments are not supported".to_string());
+        }
+        let (authority, path) = match rest.find(['/', '?']) {
+            Some(idx) if rest[idx..].starts_with('/') => (&rest[..idx], rest[idx..].to_string()),
+            Some(idx) => (&rest[..idx], format!("/{}", &rest[idx..])),
+            None => (rest, "/".to_string()),
+        };
+        if authority.contains('@') {
+            return Err("credentials in the URL are not supported".to_string());
+        }
+        let default_port = if tls { 443 } else { 80 };
+        let (host, port) = match authority.strip_prefix('[') {
+            Some(bracketed) => {
+                let (host, after) = bracketed
+                    .split_once(']')
+                    .ok_or_else(|| "unclosed '[' in host".to_string())?;
+                match after.strip_prefix(':') {
+                    Some(port) => (host, parse_port(port)?),
+                    None if after.is_empty() => (host, default_port),
+                    None => return Err(format!("unexpected '{after}' after host")),
+                }
+            }
+            None => match authority.rsplit_once(':') {
+                Some((host, port)) => (host, parse_port(port)?),
+                None => (authority, default_port),
+            },
+        };
+        if host.is_empty() {
+            return Err("missing host".to_string());
+        }
+        if path.chars().any(|c| c.is_ascii_control() || c == ' ') {
+            return Err("path contains spaces or control characters".to_string());
+        }
+        Ok(Self {
+            tls,
+            host: host.to_string(),
+            port,
+            path,
+        })
+    }
+}
+
+fn parse_port(port: &str) -> Result<u16, String> {
+    port.parse::<u16>()
+        .ok()
+        .filter(|port| *port > 0)
+        .ok_or_else(|| format!("invalid port '{port}'"))
+}
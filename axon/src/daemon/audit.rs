@@ -0,0 +1,386 @@
+use std::io::{ErrorKind, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use base64::{Engine as _, engine::general_purpose::STANDARD};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::fs::{File, OpenOptions};
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+use tokio::sync::Mutex;
+use tracing::warn;
+use uuid::Uuid;
+
+use super::reload::StaticPeerChanges;
+use crate::config::AuditConfig;
+use crate::identity::Identity;
+use crate::message::{AgentId, Envelope, MessageKind, now_millis};
+
+/// `prev` of the first record ever written to a log.
+pub(crate) const GENESIS_HASH: &str =
+    "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// Records between signed checkpoints.
+const CHECKPOINT_INTERVAL: u64 = 64;
+
+/// Bytes read from the end of the log on open to find the chain head; far
+/// larger than any single record.
+const TAIL_READ_BYTES: u64 = 64 * 1024;
+
+/// Activity recorded in the audit log.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum AuditEvent {
+    /// Envelope sent via IPC `send`; `reason` is set when delivery failed.
+    Send,
+    /// Envelope received from a peer, including replies to our requests.
+    Receive,
+    PeerAdd,
+    PeerUpdate,
+    PeerRemove,
+    /// A peer completed the mutually authenticated QUIC handshake.
+    AuthAccept,
+    /// A handshake was rejected because the peer's key is not pinned.
+    AuthReject,
+    /// Signature by this agent's key over the hash chain up to this record.
+    Checkpoint,
+}
+
+/// One audit entry, before the timestamp and local agent ID are attached.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub(crate) struct AuditRecord {
+    pub(crate) event: AuditEvent,
+    pub(crate) peer: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) msg_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) kind: Option<MessageKind>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) addr: Option<String>,
+    /// What triggered the event (`add_peer`, `reload`, `stale`, ...) or why it failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) reason: Option<String>,
+}
+
+impl AuditRecord {
+    pub(crate) fn peer(event: AuditEvent, peer: impl Into<String>) -> Self {
+        Self {
+            event,
+            peer: peer.into(),
+            msg_id: None,
+            kind: None,
+            addr: None,
+            reason: None,
+        }
+    }
+
+    /// `send` records the recipient as `peer`; every other event the sender.
+    pub(crate) fn envelope(event: AuditEvent, envelope: &Envelope) -> Self {
+        let peer = match event {
+            AuditEvent::Send => envelope.to.as_ref(),
+            _ => envelope.from.as_ref(),
+        }
+        .map(|id| id.to_string())
+        .unwrap_or_default();
+        Self {
+            msg_id: Some(envelope.id),
+            kind: Some(envelope.kind),
+            ..Self::peer(event, peer)
+        }
+    }
+
+    pub(crate) fn with_addr(mut self, addr: Option<String>) -> Self {
+        self.addr = addr;
+        self
+    }
+
+    pub(crate) fn with_reason(mut self, reason: impl Into<String>) -> Self {
+        self.reason = Some(reason.into());
+        self
+    }
+}
+
+#[derive(Serialize)]
+struct AuditLine<'a> {
+    ts_ms: u64,
+    agent_id: &'a str,
+    #[serde(flatten)]
+    record: &'a AuditRecord,
+    /// Position in the hash chain, counting from 1.
+    seq: u64,
+    /// [`line_hash`] of the previous record, or [`GENESIS_HASH`].
+    prev: &'a str,
+    /// Base64 signature over [`checkpoint_message`]; `checkpoint` records only.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sig: Option<String>,
+}
+
+/// The hash-chain fields of a written record, as read back from the log.
+#[derive(Debug, Deserialize)]
+pub(crate) struct ChainFields {
+    pub(crate) event: AuditEvent,
+    pub(crate) seq: u64,
+    pub(crate) prev: String,
+    #[serde(default)]
+    pub(crate) agent_id: String,
+    #[serde(default)]
+    pub(crate) sig: Option<String>,
+}
+
+/// Hex SHA-256 of one log line, without its trailing newline.
+pub(crate) fn line_hash(line: &[u8]) -> String {
+    Sha256::digest(line)
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+/// Bytes signed by a `checkpoint` record: its own `seq` and `prev`, which
+/// commits every record before it.
+pub(crate) fn checkpoint_message(seq: u64, prev: &str) -> Vec<u8> {
+    format!("axon-audit-checkpoint:v1:{seq}:{prev}").into_bytes()
+}
+
+struct ActiveFile {
+    file: File,
+    size: u64,
+    /// `seq` of the last record written, across rotations.
+    seq: u64,
+    /// Hash of the last record written.
+    prev: String,
+    /// Records written since the last checkpoint.
+    unsigned: u64,
+}
+
+struct Inner {
+    path: PathBuf,
+    identity: Identity,
+    max_file_bytes: u64,
+    max_files: usize,
+    active: Mutex<ActiveFile>,
+}
+
+/// Append-only JSONL log of message and peer activity (`audit.enabled`).
+///
+/// Writes are serialized behind a mutex, so lines appear in timestamp order.
+/// When the next line would push `audit.jsonl` past `max_file_bytes`, the
+/// file is renamed to `audit.jsonl.1` (shifting older files up to
+/// `max_files`) and a fresh one is started.
+///
+/// Records form a hash chain that continues across rotations and restarts:
+/// each carries `seq` and the SHA-256 of the previous line as `prev`. Every
+/// [`CHECKPOINT_INTERVAL`] records, and on [`checkpoint`](Self::checkpoint),
+/// a `checkpoint` record signs the chain head with the identity key, so
+/// edits before it cannot be hidden by recomputing hashes.
+#[derive(Clone)]
+pub(crate) struct AuditLog {
+    inner: Arc<Inner>,
+}
+
+impl AuditLog {
+    /// Open (or create, mode 0600) the audit log for appending, continuing
+    /// the hash chain from its last record.
+    pub(crate) async fn open(
+        path: &Path,
+        identity: &Identity,
+        config: &AuditConfig,
+    ) -> Result<Self> {
+        let mut active = open_active(path).await?;
+        let last = match last_line(path).await? {
+            Some(line) => Some(line),
+            None => last_line(&rotated_path(path, 1)).await?,
+        };
+        if let Some(line) = last {
+            // A record from before hash chaining restarts `seq` at 1 but is
+            // still linked by its hash.
+            let fields = serde_json::from_slice::<ChainFields>(&line).ok();
+            active.seq = fields.as_ref().map_or(0, |fields| fields.seq);
+            active.unsigned = match fields {
+                Some(fields) if fields.event == AuditEvent::Checkpoint => 0,
+                _ => 1,
+            };
+            active.prev = line_hash(&line);
+        }
+        Ok(Self {
+            inner: Arc::new(Inner {
+                path: path.to_path_buf(),
+                identity: identity.clone(),
+                max_file_bytes: config.max_file_bytes(),
+                max_files: config.max_files(),
+                active: Mutex::new(active),
+            }),
+        })
+    }
+
+    /// Sign the chain up to the last record, unless it already ends in a
+    /// checkpoint. Called on shutdown so no tail is left unsigned.
+    pub(crate) async fn checkpoint(&self) {
+        let mut active = self.inner.active.lock().await;
+        if active.unsigned == 0 {
+            return;
+        }
+        if let Err(err) = self.write_checkpoint(&mut active).await {
+            warn!(error = %err, "failed to write audit checkpoint");
+        }
+    }
+
+    /// Append a record. Failures are logged, never returned: auditing must
+    /// not affect delivery.
+    pub(crate) async fn record(&self, record: AuditRecord) {
+        if let Err(err) = self.append(&record).await {
+            warn!(event = ?record.event, error = %err, "failed to write audit record");
+        }
+    }
+
+    /// Record one peer table change per agent ID, tagged with `reason`
+    /// (`discovery`, `stale`, `revoked`, or `reload`).
+    pub(crate) async fn record_peers(&self, event: AuditEvent, ids: &[AgentId], reason: &str) {
+        for id in ids {
+            self.record(AuditRecord::peer(event, id.as_str()).with_reason(reason))
+                .await;
+        }
+    }
+
+    /// Record the peer table changes applied by a config reload.
+    pub(crate) async fn record_reload(&self, changes: &StaticPeerChanges) {
+        self.record_peers(AuditEvent::PeerAdd, &changes.added, "reload")
+            .await;
+        self.record_peers(AuditEvent::PeerRemove, &changes.removed, "reload")
+            .await;
+        self.record_peers(AuditEvent::PeerUpdate, &changes.updated, "reload")
+            .await;
+    }
+
+    async fn append(&self, record: &AuditRecord) -> Result<()> {
+        let mut active = self.inner.active.lock().await;
+        self.write_line(&mut active, record, None).await?;
+        active.unsigned += 1;
+        if active.unsigned >= CHECKPOINT_INTERVAL {
+            self.write_checkpoint(&mut active).await?;
+        }
+        Ok(())
+    }
+
+    async fn write_checkpoint(&self, active: &mut ActiveFile) -> Result<()> {
+        let seq = active.seq + 1;
+        let signature = self
+            .inner
+            .identity
+            .sign(&checkpoint_message(seq, &active.prev));
+        let record = AuditRecord::peer(AuditEvent::Checkpoint, self.inner.identity.agent_id());
+        self.write_line(active, &record, Some(STANDARD.encode(signature.to_bytes())))
+            .await?;
+        active.unsigned = 0;
+        Ok(())
+    }
+
+    async fn write_line(
+        &self,
+        active: &mut ActiveFile,
+        record: &AuditRecord,
+        sig: Option<String>,
+    ) -> Result<()> {
+        let seq = active.seq + 1;
+        let mut line = serde_json::to_vec(&AuditLine {
+            ts_ms: now_millis(),
+            agent_id: self.inner.identity.agent_id(),
+            record,
+            seq,
+            prev: &active.prev,
+            sig,
+        })
+        .context("failed to encode audit record")?;
+        let hash = line_hash(&line);
+        line.push(b'\n');
+
+        if active.size > 0 && active.size + line.len() as u64 > self.inner.max_file_bytes {
+            active.file.flush().await?;
+            rotate(&self.inner.path, self.inner.max_files).await?;
+            let fresh = open_active(&self.inner.path).await?;
+            active.file = fresh.file;
+            active.size = fresh.size;
+        }
+        active.file.write_all(&line).await?;
+        active.file.flush().await?;
+        active.size += line.len() as u64;
+        active.seq = seq;
+        active.prev = hash;
+        Ok(())
+    }
+}
+
+async fn open_active(path: &Path) -> Result<ActiveFile> {
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .mode(0o600)
+        .open(path)
+        .await
+        .with_context(|| format!("failed to open audit log: {}", path.display()))?;
+    let size = file.metadata().await?.len();
+    Ok(ActiveFile {
+        file,
+        size,
+        seq: 0,
+        prev: GENESIS_HASH.to_string(),
+        unsigned: 0,
+    })
+}
+
+/// The last non-empty line of `path`, or `None` when the file is missing or
+/// empty.
+async fn last_line(path: &Path) -> Result<Option<Vec<u8>>> {
+    let mut file = match File::open(path).await {
+        Ok(file) => file,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(err) => {
+            return Err(err).with_context(|| format!("failed to read {}", path.display()));
+        }
+    };
+    let len = file.metadata().await?.len();
+    file.seek(SeekFrom::Start(len.saturating_sub(TAIL_READ_BYTES)))
+        .await?;
+    let mut tail = Vec::new();
+    file.read_to_end(&mut tail).await?;
+    Ok(tail
+        .split(|b| *b == b'\n')
+        .rev()
+        .find(|line| !line.is_empty())
+        .map(<[u8]>::to_vec))
+}
+
+pub(crate) fn rotated_path(path: &Path, n: usize) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".{n}"));
+    PathBuf::from(name)
+}
+
+/// Shift `path.N-1` → `path.N` … `path` → `path.1`, dropping the oldest.
+pub(crate) async fn rotate(path: &Path, max_files: usize) -> Result<()> {
+    let oldest = rotated_path(path, max_files);
+    match tokio::fs::remove_file(&oldest).await {
+        Ok(()) => {}
+        Err(err) if err.kind() == ErrorKind::NotFound => {}
+        Err(err) => {
+            return Err(err).with_context(|| format!("failed to remove {}", oldest.display()));
+        }
+    }
+    for n in (1..max_files).rev() {
+        let from = rotated_path(path, n);
+        match tokio::fs::rename(&from, rotated_path(path, n + 1)).await {
+            Ok(()) => {}
+            Err(err) if err.kind() == ErrorKind::NotFound => {}
+            Err(err) => {
+                return Err(err).with_context(|| format!("failed to rotate {}", from.display()));
+            }
+        }
+    }
+    tokio::fs::rename(path, rotated_path(path, 1))
+        .await
+        .with_context(|| format!("failed to rotate {}", path.display()))
+}
+
+#[cfg(test)]
+#[path = "audit_tests.rs"]
+mod tests;
@@ -0,0 +1,169 @@
+use super::*;
+use serde_json::{Value, json};
+use std::os::unix::fs::PermissionsExt;
+
+const PEER_A: &str = "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+
+pub(super) fn identity(dir: &Path) -> Identity {
+    let paths = crate::config::AxonPaths::from_root(dir.join("state"));
+    paths.ensure_root_exists().unwrap();
+    Identity::load_or_generate(&paths).unwrap()
+}
+
+fn read_lines(path: &Path) -> Vec<Value> {
+    std::fs::read_to_string(path)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn appends_jsonl_records_with_timestamp_and_agent_ids() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("audit.jsonl");
+    let identity = identity(dir.path());
+    let audit = AuditLog::open(&path, &identity, &AuditConfig::default())
+        .await
+        .unwrap();
+    assert_eq!(
+        std::fs::metadata(&path).unwrap().permissions().mode() & 0o777,
+        0o600
+    );
+
+    let sent = Envelope::new(
+        identity.agent_id().to_string(),
+        PEER_A.to_string(),
+        MessageKind::Request,
+        json!({"q": 1}),
+    );
+    audit
+        .record(AuditRecord::envelope(AuditEvent::Send, &sent))
+        .await;
+    audit
+        .record(AuditRecord::peer(AuditEvent::PeerAdd, PEER_A).with_reason("add_peer"))
+        .await;
+
+    let lines = read_lines(&path);
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0]["event"], "send");
+    assert_eq!(lines[0]["agent_id"], identity.agent_id());
+    assert_eq!(lines[0]["peer"], PEER_A, "send records the recipient");
+    assert_eq!(lines[0]["msg_id"], sent.id.to_string());
+    assert_eq!(lines[0]["kind"], "request");
+    assert!(lines[0]["ts_ms"].as_u64().unwrap() > 0);
+    assert!(
+        lines[0].get("payload").is_none(),
+        "payloads are not audited"
+    );
+    assert_eq!(lines[1]["event"], "peer_add");
+    assert_eq!(lines[1]["reason"], "add_peer");
+    assert!(lines[1].get("msg_id").is_none());
+}
+
+#[tokio::test]
+async fn rotates_by_size_and_keeps_max_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("audit.jsonl");
+    let config = AuditConfig {
+        enabled: Some(true),
+        max_file_bytes: Some(1),
+        max_files: Some(2),
+    };
+    let audit = AuditLog::open(&path, &identity(dir.path()), &config)
+        .await
+        .unwrap();
+
+    for reason in ["first", "second", "third", "fourth"] {
+        audit
+            .record(AuditRecord::peer(AuditEvent::PeerRemove, PEER_A).with_reason(reason))
+            .await;
+    }
+
+    let reason = |path: &Path| read_lines(path)[0]["reason"].clone();
+    assert_eq!(reason(&path), "fourth");
+    assert_eq!(reason(&rotated_path(&path, 1)), "third");
+    assert_eq!(reason(&rotated_path(&path, 2)), "second");
+    assert!(!rotated_path(&path, 3).exists(), "oldest file dropped");
+}
+
+#[tokio::test]
+async fn reopen_appends_to_existing_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("audit.jsonl");
+    let identity = identity(dir.path());
+    for _ in 0..2 {
+        let audit = AuditLog::open(&path, &identity, &AuditConfig::default())
+            .await
+            .unwrap();
+        audit
+            .record(AuditRecord::peer(AuditEvent::AuthAccept, PEER_A))
+            .await;
+    }
+    assert_eq!(read_lines(&path).len(), 2);
+}
+
+#[tokio::test]
+async fn records_chain_across_rotation_and_reopen() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("audit.jsonl");
+    let identity = identity(dir.path());
+    let config = AuditConfig {
+        enabled: Some(true),
+        max_file_bytes: Some(1),
+        max_files: Some(3),
+    };
+    AuditLog::open(&path, &identity, &config)
+        .await
+        .unwrap()
+        .record(AuditRecord::peer(AuditEvent::PeerAdd, PEER_A))
+        .await;
+    AuditLog::open(&path, &identity, &config)
+        .await
+        .unwrap()
+        .record(AuditRecord::peer(AuditEvent::PeerRemove, PEER_A))
+        .await;
+
+    let first = std::fs::read_to_string(rotated_path(&path, 1)).unwrap();
+    let first: Value = serde_json::from_str(first.trim_end()).unwrap();
+    let second = read_lines(&path);
+    assert_eq!(first["seq"], 1);
+    assert_eq!(first["prev"], GENESIS_HASH);
+    assert_eq!(second[0]["seq"], 2);
+    let first_raw = std::fs::read_to_string(rotated_path(&path, 1)).unwrap();
+    assert_eq!(
+        second[0]["prev"],
+        line_hash(first_raw.trim_end().as_bytes())
+    );
+}
+
+#[tokio::test]
+async fn checkpoints_sign_every_interval_and_on_demand() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("audit.jsonl");
+    let identity = identity(dir.path());
+    let audit = AuditLog::open(&path, &identity, &AuditConfig::default())
+        .await
+        .unwrap();
+    for _ in 0..CHECKPOINT_INTERVAL {
+        audit
+            .record(AuditRecord::peer(AuditEvent::AuthAccept, PEER_A))
+            .await;
+    }
+    audit.checkpoint().await;
+    let lines = read_lines(&path);
+    assert_eq!(
+        lines.len() as u64,
+        CHECKPOINT_INTERVAL + 1,
+        "no second checkpoint without new records"
+    );
+    let checkpoint = lines.last().unwrap();
+    assert_eq!(checkpoint["event"], "checkpoint");
+    assert!(checkpoint["sig"].as_str().is_some());
+
+    audit
+        .record(AuditRecord::peer(AuditEvent::AuthAccept, PEER_A))
+        .await;
+    audit.checkpoint().await;
+    assert_eq!(read_lines(&path).last().unwrap()["event"], "checkpoint");
+}
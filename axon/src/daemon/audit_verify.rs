@@ -0,0 +1,153 @@
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+use base64::{Engine as _, engine::general_purpose::STANDARD};
+use ed25519_dalek::{Signature, VerifyingKey};
+use serde::Serialize;
+
+use super::audit::{
+    AuditEvent, ChainFields, GENESIS_HASH, checkpoint_message, line_hash, rotated_path,
+};
+use crate::identity::derive_agent_id;
+
+/// Outcome of [`verify_audit_log`]. The log is intact when `problems` is
+/// empty; records after `last_signed_seq` are chained but not yet signed.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AuditVerification {
+    /// Files checked, oldest first.
+    pub files: Vec<String>,
+    /// Hash-chained records checked, including checkpoints.
+    pub records: u64,
+    /// Records written before hash chaining, at the start of the oldest file.
+    pub legacy_records: u64,
+    pub checkpoints: u64,
+    pub first_seq: Option<u64>,
+    pub last_seq: Option<u64>,
+    pub last_signed_seq: Option<u64>,
+    /// Records after the last checkpoint.
+    pub unsigned_tail: u64,
+    /// Whether the oldest record checked is the first one ever written
+    /// (`false` once rotation has dropped older files).
+    pub starts_at_genesis: bool,
+    pub problems: Vec<String>,
+}
+
+impl AuditVerification {
+    pub fn ok(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+/// Check the hash chain and checkpoint signatures of the audit log at `path`
+/// and its rotated files (`path.N` … `path.1`), signed by `key`.
+///
+/// A broken link is reported and checking resumes from the next record, so
+/// one edit yields one problem rather than failing every later record.
+pub fn verify_audit_log(path: &Path, key: &VerifyingKey) -> Result<AuditVerification> {
+    let agent_id = derive_agent_id(key);
+    let mut files = Vec::new();
+    let mut n = 1;
+    while rotated_path(path, n).exists() {
+        files.push(rotated_path(path, n));
+        n += 1;
+    }
+    files.reverse();
+    if path.exists() {
+        files.push(path.to_path_buf());
+    }
+    if files.is_empty() {
+        bail!("no audit log at {} (is audit.enabled set?)", path.display());
+    }
+
+    let mut report = AuditVerification::default();
+    let mut prev: Option<String> = None;
+    for file in &files {
+        let name = file.display().to_string();
+        let contents = std::fs::read(file).with_context(|| format!("failed to read {name}"))?;
+        for (index, line) in contents.split(|b| *b == b'\n').enumerate() {
+            if line.is_empty() {
+                continue;
+            }
+            let at = format!("{name}:{}", index + 1);
+            let hash = line_hash(line);
+            let Ok(fields) = serde_json::from_slice::<ChainFields>(line) else {
+                if report.records == 0 && serde_json::from_slice::<serde_json::Value>(line).is_ok()
+                {
+                    report.legacy_records += 1;
+                } else {
+                    report
+                        .problems
+                        .push(format!("{at}: record is not a hash-chained audit entry"));
+                }
+                prev = Some(hash);
+                continue;
+            };
+            check_record(&mut report, &at, &fields, prev.as_deref(), &agent_id, key);
+            prev = Some(hash);
+        }
+        report.files.push(name);
+    }
+    Ok(report)
+}
+
+fn check_record(
+    report: &mut AuditVerification,
+    at: &str,
+    fields: &ChainFields,
+    expected_prev: Option<&str>,
+    agent_id: &str,
+    key: &VerifyingKey,
+) {
+    match expected_prev {
+        Some(expected) if fields.prev != expected => report.problems.push(format!(
+            "{at}: prev does not match the preceding record (records were edited, inserted, or removed)"
+        )),
+        Some(_) => {}
+        None => report.starts_at_genesis = fields.prev == GENESIS_HASH,
+    }
+    if let Some(last) = report.last_seq
+        && fields.seq != last + 1
+    {
+        report
+            .problems
+            .push(format!("{at}: seq {} follows seq {last}", fields.seq));
+    }
+    if fields.agent_id != agent_id {
+        report.problems.push(format!(
+            "{at}: written by {} rather than {agent_id}",
+            fields.agent_id
+        ));
+    }
+
+    if fields.event == AuditEvent::Checkpoint {
+        let valid = fields
+            .sig
+            .as_deref()
+            .and_then(|sig| STANDARD.decode(sig).ok())
+            .and_then(|bytes| Signature::from_slice(&bytes).ok())
+            .is_some_and(|sig| {
+                key.verify_strict(&checkpoint_message(fields.seq, &fields.prev), &sig)
+                    .is_ok()
+            });
+        if valid {
+            report.checkpoints += 1;
+            report.last_signed_seq = Some(fields.seq);
+            report.unsigned_tail = 0;
+        } else {
+            report
+                .problems
+                .push(format!("{at}: checkpoint signature does not verify"));
+            report.unsigned_tail += 1;
+        }
+    } else {
+        report.unsigned_tail += 1;
+    }
+
+    report.records += 1;
+    report.first_seq.get_or_insert(fields.seq);
+    report.last_seq = Some(fields.seq);
+}
+
+#[cfg(test)]
+#[path = "audit_verify_tests.rs"]
+mod tests;
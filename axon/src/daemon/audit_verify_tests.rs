@@ -0,0 +1,128 @@
+use super::*;
+use crate::config::{AuditConfig, AxonPaths};
+use crate::daemon::audit::{AuditLog, AuditRecord};
+use crate::identity::Identity;
+
+const PEER_A: &str = "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+
+fn identity(dir: &Path) -> Identity {
+    let paths = AxonPaths::from_root(dir.join("state"));
+    paths.ensure_root_exists().unwrap();
+    Identity::load_or_generate(&paths).unwrap()
+}
+
+async fn write_log(path: &Path, identity: &Identity, config: &AuditConfig, records: usize) {
+    let audit = AuditLog::open(path, identity, config).await.unwrap();
+    for _ in 0..records {
+        audit
+            .record(AuditRecord::peer(AuditEvent::PeerAdd, PEER_A).with_reason("add_peer"))
+            .await;
+    }
+    audit.checkpoint().await;
+}
+
+#[tokio::test]
+async fn intact_log_verifies_across_rotated_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("audit.jsonl");
+    let identity = identity(dir.path());
+    let config = AuditConfig {
+        enabled: Some(true),
+        max_file_bytes: Some(4096),
+        max_files: Some(10),
+    };
+    write_log(&path, &identity, &config, 40).await;
+
+    let report = verify_audit_log(&path, &identity.verifying_key()).unwrap();
+    assert!(report.ok(), "{:?}", report.problems);
+    assert!(report.files.len() > 1, "log rotated");
+    assert!(report.starts_at_genesis);
+    assert_eq!(report.records, 41);
+    assert_eq!(report.checkpoints, 1);
+    assert_eq!(report.last_signed_seq, Some(41));
+    assert_eq!(report.unsigned_tail, 0);
+}
+
+#[tokio::test]
+async fn edited_record_breaks_the_chain() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("audit.jsonl");
+    let identity = identity(dir.path());
+    write_log(&path, &identity, &AuditConfig::default(), 3).await;
+
+    let text = std::fs::read_to_string(&path).unwrap();
+    std::fs::write(&path, text.replacen("add_peer", "reload", 1)).unwrap();
+
+    let report = verify_audit_log(&path, &identity.verifying_key()).unwrap();
+    assert_eq!(report.problems.len(), 1, "{:?}", report.problems);
+    assert!(report.problems[0].contains(":2: prev does not match"));
+}
+
+#[tokio::test]
+async fn rehashed_edit_fails_the_checkpoint_signature() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("audit.jsonl");
+    let identity = identity(dir.path());
+    write_log(&path, &identity, &AuditConfig::default(), 1).await;
+
+    // Rewrite the record and fix up the checkpoint's `prev` to match.
+    let text = std::fs::read_to_string(&path).unwrap();
+    let mut lines: Vec<String> = text.lines().map(str::to_string).collect();
+    lines[0] = lines[0].replace("add_peer", "reload");
+    let mut checkpoint: serde_json::Value = serde_json::from_str(&lines[1]).unwrap();
+    checkpoint["prev"] = line_hash(lines[0].as_bytes()).into();
+    lines[1] = checkpoint.to_string();
+    std::fs::write(&path, lines.join("\n") + "\n").unwrap();
+
+    let report = verify_audit_log(&path, &identity.verifying_key()).unwrap();
+    assert_eq!(report.problems.len(), 1, "{:?}", report.problems);
+    assert!(report.problems[0].contains("checkpoint signature does not verify"));
+}
+
+#[tokio::test]
+async fn other_key_and_unsigned_tail_are_reported() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("audit.jsonl");
+    let identity = identity(dir.path());
+    let audit = AuditLog::open(&path, &identity, &AuditConfig::default())
+        .await
+        .unwrap();
+    audit
+        .record(AuditRecord::peer(AuditEvent::PeerAdd, PEER_A))
+        .await;
+
+    let report = verify_audit_log(&path, &identity.verifying_key()).unwrap();
+    assert!(report.ok());
+    assert_eq!(report.unsigned_tail, 1);
+    assert_eq!(report.last_signed_seq, None);
+
+    let other = self::identity(&dir.path().join("other"));
+    let report = verify_audit_log(&path, &other.verifying_key()).unwrap();
+    assert!(
+        report.problems[0].contains("rather than"),
+        "{:?}",
+        report.problems
+    );
+}
+
+#[tokio::test]
+async fn legacy_records_before_the_chain_are_counted_not_failed() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("audit.jsonl");
+    let identity = identity(dir.path());
+    std::fs::write(
+        &path,
+        format!(
+            "{{\"ts_ms\":1,\"agent_id\":\"{}\",\"event\":\"peer_add\",\"peer\":\"{PEER_A}\"}}\n",
+            identity.agent_id()
+        ),
+    )
+    .unwrap();
+    write_log(&path, &identity, &AuditConfig::default(), 1).await;
+
+    let report = verify_audit_log(&path, &identity.verifying_key()).unwrap();
+    assert!(report.ok(), "{:?}", report.problems);
+    assert_eq!(report.legacy_records, 1);
+    assert_eq!(report.records, 2);
+    assert!(!report.starts_at_genesis);
+}
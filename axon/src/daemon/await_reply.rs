@@ -0,0 +1,118 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use serde_json::json;
+use tokio::sync::broadcast;
+use tokio_util::sync::CancellationToken;
+use tracing::warn;
+use uuid::Uuid;
+
+use super::send::DaemonIpcError;
+use crate::ipc::{DaemonReply, IpcErrorCode, IpcSendKind, IpcServer, SendAwait};
+use crate::message::Envelope;
+use crate::transport::REQUEST_TIMEOUT;
+
+/// How long a `send` with `await` waits for the reply, or `None` without one.
+pub(crate) fn reply_wait(
+    kind: IpcSendKind,
+    await_reply: Option<SendAwait>,
+    timeout_secs: Option<u64>,
+) -> Result<Option<Duration>> {
+    let Some(SendAwait::Reply) = await_reply else {
+        return Ok(None);
+    };
+    if matches!(kind, IpcSendKind::Request) {
+        anyhow::bail!(DaemonIpcError::InvalidCommand(
+            "await is only valid for message kind; requests already wait".to_string()
+        ));
+    }
+    match timeout_secs.unwrap_or(REQUEST_TIMEOUT.as_secs()) {
+        0 => anyhow::bail!(DaemonIpcError::InvalidCommand(
+            "timeout_secs must be >= 1".to_string()
+        )),
+        secs => Ok(Some(Duration::from_secs(secs))),
+    }
+}
+
+/// A `send` waiting for the envelope from `to` that `ref`s it. Created
+/// before the message is sent so a fast reply is not missed.
+pub(crate) struct ReplyWaiter {
+    wait: Duration,
+    from: String,
+    delivered: broadcast::Receiver<Arc<Envelope>>,
+}
+
+impl ReplyWaiter {
+    pub(crate) fn new(ipc: &IpcServer, to: &str, wait: Duration) -> Self {
+        Self {
+            wait,
+            from: to.to_string(),
+            delivered: ipc.subscribe_delivered(),
+        }
+    }
+
+    /// Reply to `client_id` once `msg_id` is answered, the wait runs out, or
+    /// `cancel` fires at shutdown, without holding up the command loop.
+    pub(crate) fn spawn(
+        self,
+        ipc: IpcServer,
+        cancel: CancellationToken,
+        client_id: u64,
+        msg_id: Uuid,
+        req_id: Option<String>,
+    ) {
+        tokio::spawn(async move {
+            let reply = tokio::select! {
+                _ = cancel.cancelled() => Ok(Err(IpcErrorCode::ShuttingDown)),
+                reply = tokio::time::timeout(self.wait, self.reply_to(msg_id)) => reply,
+            };
+            let reply = match reply {
+                Ok(Ok(envelope)) => DaemonReply::SendOk {
+                    ok: true,
+                    msg_id,
+                    req_id,
+                    response: Some(envelope),
+                },
+                Ok(Err(code)) => {
+                    DaemonReply::error_with_details(code, json!({ "msg_id": msg_id }), req_id)
+                }
+                Err(_) => DaemonReply::error_with_details(
+                    IpcErrorCode::Timeout,
+                    json!({ "msg_id": msg_id }),
+                    req_id,
+                ),
+            };
+            let _ = ipc.send_reply(client_id, &reply).await;
+        });
+    }
+
+    /// The reply to `msg_id`, or `reply_lost` when envelopes were dropped
+    /// from the buffer before they could be checked (the reply may be among
+    /// them), or `timeout` when the server shuts down first.
+    async fn reply_to(mut self, msg_id: Uuid) -> Result<Envelope, IpcErrorCode> {
+        loop {
+            match self.delivered.recv().await {
+                Ok(envelope)
+                    if envelope.ref_id == Some(msg_id)
+                        && envelope
+                            .from
+                            .as_ref()
+                            .is_some_and(|sender| sender.as_str() == self.from) =>
+                {
+                    return Ok((*envelope).clone());
+                }
+                Ok(_) => {}
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!(%msg_id, skipped, "lost track of inbound envelopes while awaiting a reply");
+                    return Err(IpcErrorCode::ReplyLost);
+                }
+                Err(broadcast::error::RecvError::Closed) => return Err(IpcErrorCode::Timeout),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "await_reply_tests.rs"]
+mod tests;
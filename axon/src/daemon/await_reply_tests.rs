@@ -0,0 +1,61 @@
+use serde_json::json;
+
+use super::*;
+use crate::message::MessageKind;
+
+const LOCAL: &str = "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+const PEER: &str = "ed25519.bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb";
+
+fn waiter(capacity: usize) -> (broadcast::Sender<Arc<Envelope>>, ReplyWaiter) {
+    let (tx, delivered) = broadcast::channel(capacity);
+    let waiter = ReplyWaiter {
+        wait: Duration::from_secs(1),
+        from: PEER.to_string(),
+        delivered,
+    };
+    (tx, waiter)
+}
+
+#[tokio::test]
+async fn reply_to_skips_other_envelopes_until_the_reply() {
+    let sent = Envelope::new(LOCAL, PEER, MessageKind::Message, json!({}));
+    let (tx, waiter) = waiter(4);
+    let unrelated = Envelope::new(PEER, LOCAL, MessageKind::Message, json!({}));
+    let other_sender = Envelope::response_to(&sent, LOCAL, MessageKind::Message, json!({}));
+    let reply = Envelope::response_to(&sent, PEER, MessageKind::Message, json!({}));
+    for envelope in [&unrelated, &other_sender, &reply] {
+        tx.send(Arc::new(envelope.clone())).unwrap();
+    }
+
+    let received = waiter.reply_to(sent.id).await.expect("reply");
+    assert_eq!(received.id, reply.id);
+}
+
+#[tokio::test]
+async fn reply_to_reports_a_lagged_buffer_as_reply_lost() {
+    let sent = Envelope::new(LOCAL, PEER, MessageKind::Message, json!({}));
+    let (tx, waiter) = waiter(2);
+    for _ in 0..3 {
+        let unrelated = Envelope::new(PEER, LOCAL, MessageKind::Message, json!({}));
+        tx.send(Arc::new(unrelated)).unwrap();
+    }
+    let reply = Envelope::response_to(&sent, PEER, MessageKind::Message, json!({}));
+    tx.send(Arc::new(reply)).unwrap();
+
+    assert_eq!(
+        waiter.reply_to(sent.id).await.map(|envelope| envelope.id),
+        Err(IpcErrorCode::ReplyLost)
+    );
+}
+
+#[tokio::test]
+async fn reply_to_times_out_when_the_server_goes_away() {
+    let sent = Envelope::new(LOCAL, PEER, MessageKind::Message, json!({}));
+    let (tx, waiter) = waiter(2);
+    drop(tx);
+
+    assert_eq!(
+        waiter.reply_to(sent.id).await.map(|envelope| envelope.id),
+        Err(IpcErrorCode::Timeout)
+    );
+}
@@ -0,0 +1,108 @@
+use anyhow::Result;
+use tracing::{info, warn};
+
+use super::audit::{AuditEvent, AuditRecord};
+use super::command_handler::DaemonContext;
+use crate::config::{
+    BlockedPeer, add_block, blocked_keys, is_agent_id, load_blocklist, remove_block,
+};
+use crate::ipc::{DaemonReply, IpcErrorCode};
+use crate::message::{AgentId, now_millis};
+
+async fn refresh_transport(ctx: &DaemonContext<'_>) -> Result<()> {
+    let keys = blocked_keys(&load_blocklist(ctx.blocklist_path).await?);
+    ctx.transport.set_blocked_keys(keys);
+    Ok(())
+}
+
+/// Handle IPC `block_peer`: persist the peer to the blocklist, drop it from
+/// the table, and close its connections. `agent_id` must be a well-formed
+/// agent ID other than ours. A peer that is not in the table is blocked by
+/// agent ID alone, which refuses whichever key derives to it.
+pub(crate) async fn block_reply(
+    ctx: &DaemonContext<'_>,
+    agent_id: String,
+    req_id: Option<String>,
+) -> DaemonReply {
+    if !is_agent_id(&agent_id) || agent_id == ctx.local_agent_id.as_str() {
+        return DaemonReply::error(IpcErrorCode::InvalidCommand, req_id);
+    }
+    let pubkey = ctx.peer_table.get(&agent_id).await.map(|peer| peer.pubkey);
+
+    let entry = BlockedPeer {
+        agent_id: AgentId::from(agent_id),
+        pubkey,
+        blocked_at_unix_ms: now_millis(),
+    };
+    let persisted = add_block(ctx.blocklist_path, entry.clone()).await;
+    if let Err(err) = persisted.and(refresh_transport(ctx).await) {
+        warn!(error = %err, "failed to persist block");
+        return DaemonReply::error(IpcErrorCode::InternalError, req_id);
+    }
+
+    let removed = ctx.peer_table.block(entry.refused_key()).await;
+    for peer in &removed {
+        if let Some(audit) = ctx.audit {
+            audit
+                .record(
+                    AuditRecord::peer(AuditEvent::PeerRemove, peer.agent_id.as_str())
+                        .with_reason("blocked"),
+                )
+                .await;
+        }
+    }
+    info!(agent_id = %entry.agent_id, known_key = entry.pubkey.is_some(), "blocked peer");
+
+    DaemonReply::BlockPeer {
+        ok: true,
+        agent_id: entry.agent_id.to_string(),
+        pubkey: entry.pubkey,
+        req_id,
+    }
+}
+
+/// Handle IPC `unblock_peer`: remove the peer from the blocklist so its
+/// handshakes are accepted again. The peer is not re-added here; static
+/// peers return on `reload`, discovered peers on their next announcement.
+pub(crate) async fn unblock_reply(
+    ctx: &DaemonContext<'_>,
+    agent_id: String,
+    req_id: Option<String>,
+) -> DaemonReply {
+    let removed = match remove_block(ctx.blocklist_path, &agent_id).await {
+        Ok(Some(entry)) => entry,
+        Ok(None) => return DaemonReply::error(IpcErrorCode::PeerNotFound, req_id),
+        Err(err) => {
+            warn!(error = %err, "failed to persist unblock");
+            return DaemonReply::error(IpcErrorCode::InternalError, req_id);
+        }
+    };
+    if let Err(err) = refresh_transport(ctx).await {
+        warn!(error = %err, "failed to reload blocklist after unblock");
+        return DaemonReply::error(IpcErrorCode::InternalError, req_id);
+    }
+    ctx.peer_table.unblock(removed.refused_key());
+    info!(agent_id = %removed.agent_id, "unblocked peer");
+
+    DaemonReply::UnblockPeer {
+        ok: true,
+        agent_id: removed.agent_id.to_string(),
+        pubkey: removed.pubkey,
+        req_id,
+    }
+}
+
+/// Handle IPC `blocked`: every entry in the blocklist.
+pub(crate) async fn blocked_reply(ctx: &DaemonContext<'_>, req_id: Option<String>) -> DaemonReply {
+    match load_blocklist(ctx.blocklist_path).await {
+        Ok(blocked) => DaemonReply::Blocked {
+            ok: true,
+            blocked,
+            req_id,
+        },
+        Err(err) => {
+            warn!(error = %err, "failed to read blocklist");
+            DaemonReply::error(IpcErrorCode::InternalError, req_id)
+        }
+    }
+}
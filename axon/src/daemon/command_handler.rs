@@ -1,69 +1,94 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
 use std::sync::atomic::{AtomicU64, Ordering};
-use std::time::{Duration, Instant};
+use std::time::Instant;
 
 use anyhow::Result;
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
 
-use crate::config::resolve_static_peer;
-use crate::ipc::{
-    CommandEvent, DaemonReply, IpcCommand, IpcErrorCode, IpcSendKind, IpcServer, PeerSummary,
-};
-use crate::message::{AgentId, Envelope};
-use crate::peer_table::{ConnectionStatus, PeerSource, PeerTable};
-use crate::peer_token::derive_agent_id_from_pubkey_base64;
-use crate::transport::{QuicTransport, REQUEST_TIMEOUT};
+use super::audit::AuditLog;
+use super::await_reply::{ReplyWaiter, reply_wait};
+use super::blocklist::{block_reply, blocked_reply, unblock_reply};
+use super::event_log::EventLog;
+use super::health::{HealthState, health_reply};
+use super::history::{History, history_reply};
+use super::known_peers::KnownPeersStore;
+use super::peer_tags::PeerTags;
+use super::peers::{add_peer_reply, bans_reply, peers_reply, whois_reply};
+use super::pins::{clear_pin_reply, pins_reply};
+use super::reconnect::ReconnectState;
+use super::reload::ReloadTrigger;
+use super::revocation::revoke_reply;
+use super::send::{handle_send, send_error_reply};
+use super::stats::{lifetime_stats, status_reply};
+use super::supervisor::Supervisor;
+use super::threads::ThreadIndex;
+use super::topics::{TopicTable, publish_reply, subscribe_reply};
+use super::transforms::Transforms;
+use crate::ipc::{CommandEvent, DaemonReply, IpcCommand, IpcErrorCode, IpcServer, LifetimeStats};
+use crate::message::AgentId;
+use crate::peer_table::PeerTable;
+use crate::transport::QuicTransport;
 
+/// Daemon-wide message totals; per-peer traffic lives in the peer table.
 #[derive(Default)]
 pub(crate) struct Counters {
     pub(crate) sent: AtomicU64,
     pub(crate) received: AtomicU64,
 }
 
-#[derive(Debug)]
-pub(crate) enum DaemonIpcError {
-    PeerNotFound,
-    SelfSend,
-    PeerUnreachable,
-    Timeout,
-    InvalidCommand(String),
-}
+impl Counters {
+    pub(crate) fn record_sent(&self) {
+        self.sent.fetch_add(1, Ordering::Relaxed);
+    }
 
-impl std::fmt::Display for DaemonIpcError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            DaemonIpcError::PeerNotFound => write!(f, "peer_not_found"),
-            DaemonIpcError::SelfSend => write!(f, "self_send"),
-            DaemonIpcError::PeerUnreachable => write!(f, "peer_unreachable"),
-            DaemonIpcError::Timeout => write!(f, "timeout"),
-            DaemonIpcError::InvalidCommand(msg) => write!(f, "invalid_command: {msg}"),
-        }
+    pub(crate) fn record_received(&self) {
+        self.received.fetch_add(1, Ordering::Relaxed);
     }
 }
 
-impl std::error::Error for DaemonIpcError {}
-
 pub(crate) struct DaemonContext<'a> {
     pub(crate) ipc: &'a IpcServer,
     pub(crate) peer_table: &'a PeerTable,
     pub(crate) transport: &'a QuicTransport,
     pub(crate) local_agent_id: &'a AgentId,
     pub(crate) counters: &'a Counters,
+    pub(crate) topics: &'a Arc<TopicTable>,
+    pub(crate) threads: &'a ThreadIndex,
+    /// Lifetime totals from previous runs, as loaded at startup.
+    pub(crate) lifetime_baseline: &'a LifetimeStats,
+    pub(crate) history: Option<&'a History>,
+    pub(crate) audit: Option<&'a AuditLog>,
+    pub(crate) events: Option<&'a EventLog>,
+    /// `revocations.json`, appended to by `revoke`.
+    pub(crate) revocations_path: &'a Path,
+    /// `blocklist.json`, edited by `block_peer` and `unblock_peer`.
+    pub(crate) blocklist_path: &'a Path,
+    /// `known_peers.json`, rewritten by `clear_pin`.
+    pub(crate) known_peers: &'a KnownPeersStore,
+    pub(crate) peer_tags: &'a PeerTags,
+    pub(crate) transforms: &'a Transforms,
+    pub(crate) health: &'a HealthState,
+    pub(crate) supervisor: &'a Supervisor,
+    pub(crate) max_ipc_clients: usize,
+    pub(crate) reload_tx: &'a mpsc::Sender<ReloadTrigger>,
+    pub(crate) cancel: &'a CancellationToken,
+    /// Cancelled after the shutdown drain; stops forwarders and reply waiters.
+    pub(crate) forwarding: &'a CancellationToken,
     pub(crate) start: Instant,
 }
 
-pub(crate) fn status_str(status: &ConnectionStatus) -> &'static str {
-    match status {
-        ConnectionStatus::Discovered => "discovered",
-        ConnectionStatus::Connecting => "connecting",
-        ConnectionStatus::Connected => "connected",
-        ConnectionStatus::Disconnected => "disconnected",
-    }
-}
-
-pub(crate) fn source_str(source: &PeerSource) -> &'static str {
-    match source {
-        PeerSource::Static => "static",
-        PeerSource::Discovered => "discovered",
-        PeerSource::Cached => "cached",
+impl DaemonContext<'_> {
+    /// Lifetime totals including this run.
+    pub(crate) fn lifetime(&self) -> LifetimeStats {
+        lifetime_stats(
+            self.lifetime_baseline,
+            self.counters,
+            self.transport.rate_limited_count(),
+            self.start.elapsed(),
+        )
     }
 }
 
@@ -71,90 +96,95 @@ pub(crate) fn source_str(source: &PeerSource) -> &'static str {
 // Command dispatch — directly handles all IPC commands
 // ---------------------------------------------------------------------------
 
-pub(crate) async fn handle_command(cmd: CommandEvent, ctx: &DaemonContext<'_>) -> Result<()> {
+/// Runs in the main loop's set of in-flight sends rather than inline, since a
+/// send can wait on the network for up to its timeout.
+pub(crate) async fn handle_send_command(cmd: CommandEvent, ctx: &DaemonContext<'_>) -> Result<()> {
     let client_id = cmd.client_id;
-
-    let reply = match cmd.command {
-        IpcCommand::Send {
-            to,
-            kind,
-            payload,
-            timeout_secs,
-            ref_id,
-            req_id,
-        } => match handle_send(ctx, to, kind, payload, timeout_secs, ref_id).await {
-            Ok((msg_id, response)) => {
-                let broadcast_envelope = response.clone();
-                let reply = DaemonReply::SendOk {
-                    ok: true,
+    let IpcCommand::Send {
+        to,
+        kind,
+        payload,
+        timeout_secs,
+        ref_id,
+        idempotency_key,
+        thread_id,
+        headers,
+        await_reply,
+        req_id,
+    } = cmd.command
+    else {
+        anyhow::bail!("not a send command");
+    };
+    let result = match reply_wait(kind, await_reply, timeout_secs) {
+        Ok(wait) => {
+            let awaiting = wait.map(|wait| ReplyWaiter::new(ctx.ipc, &to, wait));
+            // The send itself keeps the message delivery timeout.
+            let timeout_secs = timeout_secs.filter(|_| awaiting.is_none());
+            handle_send(
+                ctx,
+                client_id,
+                to,
+                kind,
+                payload,
+                timeout_secs,
+                ref_id,
+                idempotency_key,
+                thread_id,
+                headers,
+            )
+            .await
+            .map(|sent| (sent, awaiting))
+        }
+        Err(e) => Err(e),
+    };
+    let reply = match result {
+        Ok(((msg_id, response), awaiting)) => {
+            if let Some(waiter) = awaiting {
+                // The reply still reaches every client as an inbound event.
+                waiter.spawn(
+                    ctx.ipc.clone(),
+                    ctx.forwarding.clone(),
+                    client_id,
                     msg_id,
                     req_id,
-                    response,
-                };
-                // Send reply first, then broadcast (so sender gets ack before broadcast)
-                ctx.ipc.send_reply(client_id, &reply).await?;
-                if let Some(envelope) = broadcast_envelope {
-                    let _ = ctx.ipc.broadcast_inbound(&envelope).await;
-                }
+                );
                 return Ok(());
             }
-            Err(e) => {
-                let error_code = if let Some(e) = e.downcast_ref::<DaemonIpcError>() {
-                    match e {
-                        DaemonIpcError::PeerNotFound => IpcErrorCode::PeerNotFound,
-                        DaemonIpcError::SelfSend => IpcErrorCode::SelfSend,
-                        DaemonIpcError::PeerUnreachable => IpcErrorCode::PeerUnreachable,
-                        DaemonIpcError::Timeout => IpcErrorCode::Timeout,
-                        DaemonIpcError::InvalidCommand(_) => IpcErrorCode::InvalidCommand,
-                    }
-                } else {
-                    IpcErrorCode::InternalError
-                };
-                DaemonReply::Error {
-                    ok: false,
-                    message: error_code.message(),
-                    error: error_code,
-                    req_id,
-                }
-            }
-        },
-        IpcCommand::Peers { req_id } => {
-            let peers: Vec<PeerSummary> = ctx
-                .peer_table
-                .list()
-                .await
-                .into_iter()
-                .map(|p| PeerSummary {
-                    agent_id: p.agent_id.to_string(),
-                    addr: p.addr.to_string(),
-                    status: status_str(&p.status).to_string(),
-                    rtt_ms: p.rtt_ms,
-                    source: source_str(&p.source).to_string(),
-                })
-                .collect();
-            DaemonReply::Peers {
+            let broadcast_envelope = response.clone();
+            let reply = DaemonReply::SendOk {
                 ok: true,
-                peers,
+                msg_id,
                 req_id,
+                response,
+            };
+            // Send reply first, then broadcast (so sender gets ack before broadcast)
+            ctx.ipc.send_reply(client_id, &reply).await?;
+            if let Some(envelope) = broadcast_envelope {
+                let _ = ctx.ipc.broadcast_inbound(&envelope).await;
             }
+            return Ok(());
         }
-        IpcCommand::Status { req_id } => {
-            let peers_connected = ctx
-                .peer_table
-                .list()
-                .await
-                .iter()
-                .filter(|p| p.status == ConnectionStatus::Connected)
-                .count();
-            DaemonReply::Status {
-                ok: true,
-                uptime_secs: ctx.start.elapsed().as_secs(),
-                peers_connected,
-                messages_sent: ctx.counters.sent.load(Ordering::Relaxed),
-                messages_received: ctx.counters.received.load(Ordering::Relaxed),
-                req_id,
-            }
+        Err(e) => send_error_reply(&e, req_id),
+    };
+    ctx.ipc.send_reply(client_id, &reply).await?;
+    Ok(())
+}
+
+/// `reconnect` is the main loop's backoff state, which it owns mutably and so
+/// is passed per command rather than held in [`DaemonContext`].
+pub(crate) async fn handle_command(
+    cmd: CommandEvent,
+    ctx: &DaemonContext<'_>,
+    reconnect: &HashMap<AgentId, ReconnectState>,
+) -> Result<()> {
+    let client_id = cmd.client_id;
+
+    let reply = match cmd.command {
+        command @ IpcCommand::Send { .. } => {
+            return handle_send_command(CommandEvent { client_id, command }, ctx).await;
         }
+        IpcCommand::Peers { tag, req_id } => peers_reply(ctx, tag, req_id).await,
+        IpcCommand::Status { req_id } => status_reply(ctx, req_id).await,
         IpcCommand::Whoami { req_id } => {
             // Forward to IPC server which has the config info
             ctx.ipc
@@ -168,147 +198,70 @@ pub(crate) async fn handle_command(cmd: CommandEvent, ctx: &DaemonContext<'_>) -
             pubkey,
             addr,
             req_id,
-        } => match derive_agent_id_from_pubkey_base64(&pubkey) {
-            Err(_) => {
-                let error = IpcErrorCode::InvalidCommand;
-                DaemonReply::Error {
-                    ok: false,
-                    message: error.message(),
-                    error,
-                    req_id,
-                }
-            }
-            Ok(agent_id) => {
-                if matches!(agent_id.as_str(), id if id == ctx.local_agent_id.as_str()) {
-                    let error = IpcErrorCode::SelfSend;
-                    DaemonReply::Error {
-                        ok: false,
-                        message: error.message(),
-                        error,
-                        req_id,
-                    }
-                } else if ctx.peer_table.get(agent_id.as_str()).await.is_some() {
-                    let error = IpcErrorCode::InvalidCommand;
-                    DaemonReply::Error {
-                        ok: false,
-                        message: error.message(),
-                        error,
-                        req_id,
-                    }
-                } else {
-                    match resolve_static_peer(agent_id.clone(), &addr, pubkey).await {
-                        Ok(peer) => {
-                            ctx.peer_table.upsert_static(&peer).await;
-                            DaemonReply::AddPeer {
-                                ok: true,
-                                agent_id: agent_id.to_string(),
-                                req_id,
-                            }
-                        }
-                        Err(_) => {
-                            let error = IpcErrorCode::InvalidCommand;
-                            DaemonReply::Error {
-                                ok: false,
-                                message: error.message(),
-                                error,
-                                req_id,
-                            }
-                        }
-                    }
-                }
-            }
-        },
-    };
-
-    ctx.ipc.send_reply(client_id, &reply).await?;
-    Ok(())
-}
-
-// ---------------------------------------------------------------------------
-// Send helper
-// ---------------------------------------------------------------------------
-
-async fn handle_send(
-    ctx: &DaemonContext<'_>,
-    to: String,
-    kind: IpcSendKind,
-    payload: serde_json::Value,
-    timeout_secs: Option<u64>,
-    ref_id: Option<uuid::Uuid>,
-) -> Result<(uuid::Uuid, Option<crate::message::Envelope>)> {
-    if to == ctx.local_agent_id.as_str() {
-        anyhow::bail!(DaemonIpcError::SelfSend);
-    }
-
-    let peer = ctx
-        .peer_table
-        .get(&to)
-        .await
-        .ok_or_else(|| anyhow::anyhow!(DaemonIpcError::PeerNotFound))?;
-
-    let mut envelope = Envelope::new(
-        (*ctx.local_agent_id).clone(),
-        to.clone(),
-        kind.as_message_kind(),
-        payload,
-    );
-    envelope.ref_id = ref_id;
-    envelope
-        .validate()
-        .map_err(|e| anyhow::anyhow!(DaemonIpcError::InvalidCommand(e.to_string())))?;
-
-    let msg_id = envelope.id;
-
-    // Timeout the send (including connection attempt) so IPC clients don't
-    // block indefinitely when the peer is unreachable over UDP/QUIC.
-    let send_timeout = match kind {
-        IpcSendKind::Request => {
-            let secs = timeout_secs.unwrap_or(REQUEST_TIMEOUT.as_secs());
-            if secs == 0 {
-                anyhow::bail!(DaemonIpcError::InvalidCommand(
-                    "timeout_secs must be >= 1".to_string()
-                ));
-            }
-            Duration::from_secs(secs)
+        } => add_peer_reply(ctx, pubkey, addr, req_id).await,
+        IpcCommand::Revoke {
+            pubkey,
+            agent_id,
+            req_id,
+        } => revoke_reply(ctx, pubkey, agent_id, req_id).await,
+        IpcCommand::BlockPeer { agent_id, req_id } => block_reply(ctx, agent_id, req_id).await,
+        IpcCommand::UnblockPeer { agent_id, req_id } => unblock_reply(ctx, agent_id, req_id).await,
+        IpcCommand::Blocked { req_id } => blocked_reply(ctx, req_id).await,
+        IpcCommand::Shutdown { req_id } => {
+            // Acknowledge before cancelling so the client sees the reply.
+            ctx.ipc
+                .send_reply(client_id, &DaemonReply::Shutdown { ok: true, req_id })
+                .await?;
+            tracing::info!("shutdown requested via IPC");
+            ctx.cancel.cancel();
+            return Ok(());
         }
-        IpcSendKind::Message => {
-            if timeout_secs.is_some() {
-                anyhow::bail!(DaemonIpcError::InvalidCommand(
-                    "timeout_secs is only valid for request kind".to_string()
-                ));
+        IpcCommand::Health { req_id } => health_reply(ctx, req_id).await,
+        IpcCommand::Pins { agent_id, req_id } => pins_reply(ctx, agent_id, req_id).await,
+        IpcCommand::ClearPin { agent_id, req_id } => clear_pin_reply(ctx, agent_id, req_id).await,
+        IpcCommand::Bans { req_id } => bans_reply(ctx, req_id),
+        IpcCommand::Whois { agent_id, req_id } => {
+            whois_reply(ctx, &agent_id, reconnect, req_id).await
+        }
+        IpcCommand::Subscribe { topics, req_id } => subscribe_reply(ctx, topics, req_id).await,
+        IpcCommand::Publish {
+            topic,
+            payload,
+            req_id,
+        } => publish_reply(ctx, topic, payload, req_id).await,
+        IpcCommand::Tap { req_id } => {
+            if !ctx.ipc.enable_tap(client_id).await {
+                return Ok(());
             }
-            Duration::from_secs(10)
+            DaemonReply::Tap { ok: true, req_id }
         }
-    };
-    let send_result = tokio::time::timeout(
-        send_timeout,
-        ctx.transport
-            .send_with_timeout(&peer, envelope, send_timeout),
-    )
-    .await;
-
-    match send_result {
-        Err(_elapsed) => {
-            ctx.peer_table.set_disconnected(&to).await;
-            if matches!(kind, IpcSendKind::Request) {
-                anyhow::bail!(DaemonIpcError::Timeout)
-            } else {
-                anyhow::bail!(DaemonIpcError::PeerUnreachable)
+        IpcCommand::OwnReplies { req_id } => {
+            if !ctx.ipc.enable_own_replies(client_id).await {
+                return Ok(());
             }
+            DaemonReply::OwnReplies { ok: true, req_id }
         }
-        Ok(inner) => match inner {
-            Ok(response) => {
-                ctx.counters.sent.fetch_add(1, Ordering::Relaxed);
-                ctx.peer_table.set_connected(&to, None).await;
-                if response.is_some() {
-                    ctx.counters.received.fetch_add(1, Ordering::Relaxed);
+        IpcCommand::History { filter, req_id } => history_reply(ctx, filter, req_id).await,
+        IpcCommand::Reload { req_id } => {
+            // The event loop owns the state a reload mutates; it replies once
+            // the new config has been applied.
+            match ctx
+                .reload_tx
+                .send(ReloadTrigger::Ipc { client_id, req_id })
+                .await
+            {
+                Ok(()) => return Ok(()),
+                Err(mpsc::error::SendError(trigger)) => {
+                    let req_id = match trigger {
+                        ReloadTrigger::Ipc { req_id, .. } => req_id,
+                        ReloadTrigger::Signal => None,
+                    };
+                    DaemonReply::error(IpcErrorCode::InternalError, req_id)
                 }
-                Ok((msg_id, response))
-            }
-            Err(_err) => {
-                ctx.peer_table.set_disconnected(&to).await;
-                anyhow::bail!(DaemonIpcError::PeerUnreachable)
             }
-        },
-    }
+        }
+    };
+
+    ctx.ipc.send_reply(client_id, &reply).await?;
+    Ok(())
 }
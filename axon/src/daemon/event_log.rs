@@ -0,0 +1,196 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use tokio::fs::{File, OpenOptions};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+use tracing::warn;
+use uuid::Uuid;
+
+use super::audit::rotate;
+use crate::config::EventLogConfig;
+use crate::message::{Envelope, MessageKind, now_millis};
+
+/// Activity recorded in the event log.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Event {
+    /// Envelope delivered to a peer via IPC `send`.
+    Send,
+    /// Envelope received from a peer, including replies to our requests.
+    Receive,
+    Connect,
+    Disconnect,
+    /// A send that failed; `reason` says why.
+    Error,
+}
+
+/// One event-log entry, before the timestamp and local agent ID are attached.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub(crate) struct EventRecord {
+    pub(crate) event: Event,
+    pub(crate) peer: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) msg_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) kind: Option<MessageKind>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) topic: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) payload_bytes: Option<usize>,
+    /// Why a peer disconnected or a send failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) reason: Option<String>,
+}
+
+impl EventRecord {
+    pub(crate) fn peer(event: Event, peer: impl Into<String>) -> Self {
+        Self {
+            event,
+            peer: peer.into(),
+            msg_id: None,
+            kind: None,
+            topic: None,
+            payload_bytes: None,
+            reason: None,
+        }
+    }
+
+    /// `send` and `error` record the recipient as `peer`; `receive` the
+    /// sender.
+    pub(crate) fn envelope(event: Event, envelope: &Envelope) -> Self {
+        let peer = match event {
+            Event::Receive => envelope.from.as_ref(),
+            _ => envelope.to.as_ref(),
+        }
+        .map(|id| id.to_string())
+        .unwrap_or_default();
+        Self {
+            msg_id: Some(envelope.id),
+            kind: Some(envelope.kind),
+            topic: envelope.topic.clone(),
+            payload_bytes: Some(envelope.payload.get().len()),
+            ..Self::peer(event, peer)
+        }
+    }
+
+    pub(crate) fn with_reason(mut self, reason: impl Into<String>) -> Self {
+        self.reason = Some(reason.into());
+        self
+    }
+}
+
+#[derive(Serialize)]
+struct EventLine<'a> {
+    ts_ms: u64,
+    agent_id: &'a str,
+    #[serde(flatten)]
+    record: &'a EventRecord,
+}
+
+struct ActiveFile {
+    file: File,
+    size: u64,
+    /// When the current file was started, for age-based rotation.
+    created: SystemTime,
+}
+
+struct Inner {
+    path: PathBuf,
+    agent_id: String,
+    max_file_bytes: u64,
+    max_file_age: Option<Duration>,
+    max_files: usize,
+    active: Mutex<ActiveFile>,
+}
+
+/// JSONL log of message and connection activity for log shippers
+/// (`event_log.enabled`), kept apart from the human-readable tracing output.
+///
+/// The file is rotated to `events.jsonl.1` (shifting older files up to
+/// `max_files`) when the next line would push it past `max_file_bytes`, or
+/// when it is older than `max_file_age`.
+#[derive(Clone)]
+pub(crate) struct EventLog {
+    inner: Arc<Inner>,
+}
+
+impl EventLog {
+    /// Open (or create, mode 0600) the event log for appending.
+    pub(crate) async fn open(path: &Path, agent_id: &str, config: &EventLogConfig) -> Result<Self> {
+        let active = open_active(path).await?;
+        Ok(Self {
+            inner: Arc::new(Inner {
+                path: path.to_path_buf(),
+                agent_id: agent_id.to_string(),
+                max_file_bytes: config.max_file_bytes(),
+                max_file_age: config.max_file_age(),
+                max_files: config.max_files(),
+                active: Mutex::new(active),
+            }),
+        })
+    }
+
+    /// Append a record. Failures are logged, never returned: the event log
+    /// must not affect delivery.
+    pub(crate) async fn record(&self, record: EventRecord) {
+        if let Err(err) = self.append(&record).await {
+            warn!(event = ?record.event, error = %err, "failed to write event log record");
+        }
+    }
+
+    async fn append(&self, record: &EventRecord) -> Result<()> {
+        let mut line = serde_json::to_vec(&EventLine {
+            ts_ms: now_millis(),
+            agent_id: &self.inner.agent_id,
+            record,
+        })
+        .context("failed to encode event record")?;
+        line.push(b'\n');
+
+        let mut active = self.inner.active.lock().await;
+        let too_big = active.size + line.len() as u64 > self.inner.max_file_bytes;
+        let too_old = self
+            .inner
+            .max_file_age
+            .is_some_and(|max_age| active.created.elapsed().is_ok_and(|age| age >= max_age));
+        if active.size > 0 && (too_big || too_old) {
+            active.file.flush().await?;
+            rotate(&self.inner.path, self.inner.max_files).await?;
+            *active = open_active(&self.inner.path).await?;
+        }
+        active.file.write_all(&line).await?;
+        active.file.flush().await?;
+        active.size += line.len() as u64;
+        Ok(())
+    }
+}
+
+async fn open_active(path: &Path) -> Result<ActiveFile> {
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .mode(0o600)
+        .open(path)
+        .await
+        .with_context(|| format!("failed to open event log: {}", path.display()))?;
+    let metadata = file.metadata().await?;
+    // Filesystems without birth times fall back to the last write, so a
+    // reopened file is not rotated early.
+    let created = metadata
+        .created()
+        .or_else(|_| metadata.modified())
+        .unwrap_or_else(|_| SystemTime::now());
+    Ok(ActiveFile {
+        file,
+        size: metadata.len(),
+        created,
+    })
+}
+
+#[cfg(test)]
+#[path = "event_log_tests.rs"]
+mod tests;
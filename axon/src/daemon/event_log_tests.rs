@@ -0,0 +1,84 @@
+use super::*;
+use crate::daemon::audit::rotated_path;
+use serde_json::{Value, json};
+
+const LOCAL: &str = "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+const PEER: &str = "ed25519.bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb";
+
+fn read_lines(path: &Path) -> Vec<Value> {
+    std::fs::read_to_string(path)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn records_envelopes_and_connection_events() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("events.jsonl");
+    let log = EventLog::open(&path, LOCAL, &EventLogConfig::default())
+        .await
+        .unwrap();
+
+    let mut sent = Envelope::new(LOCAL, PEER, MessageKind::Message, json!({"n": 1}));
+    sent.topic = Some("builds.done".to_string());
+    log.record(EventRecord::envelope(Event::Send, &sent)).await;
+    let reply = Envelope::new(PEER, LOCAL, MessageKind::Response, json!({}));
+    log.record(EventRecord::envelope(Event::Receive, &reply))
+        .await;
+    log.record(EventRecord::envelope(Event::Error, &sent).with_reason("timeout"))
+        .await;
+    log.record(EventRecord::peer(Event::Disconnect, PEER).with_reason("idle"))
+        .await;
+
+    let lines = read_lines(&path);
+    assert_eq!(lines.len(), 4);
+    assert_eq!(lines[0]["event"], "send");
+    assert_eq!(lines[0]["agent_id"], LOCAL);
+    assert_eq!(lines[0]["peer"], PEER, "send records the recipient");
+    assert_eq!(lines[0]["msg_id"], sent.id.to_string());
+    assert_eq!(lines[0]["topic"], "builds.done");
+    assert_eq!(lines[0]["payload_bytes"], 7);
+    assert!(lines[0]["ts_ms"].as_u64().unwrap() > 0);
+    assert!(lines[0].get("payload").is_none());
+    assert_eq!(lines[1]["event"], "receive");
+    assert_eq!(lines[1]["peer"], PEER, "receive records the sender");
+    assert_eq!(lines[2]["event"], "error");
+    assert_eq!(lines[2]["reason"], "timeout");
+    assert_eq!(
+        lines[3],
+        json!({"ts_ms": lines[3]["ts_ms"], "agent_id": LOCAL, "event": "disconnect", "peer": PEER, "reason": "idle"})
+    );
+}
+
+#[tokio::test]
+async fn rotates_by_size_and_by_age() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("events.jsonl");
+    let config = EventLogConfig {
+        max_file_bytes: Some(200),
+        max_file_age_secs: Some(60),
+        max_files: Some(2),
+        ..Default::default()
+    };
+    let log = EventLog::open(&path, LOCAL, &config).await.unwrap();
+    let connect = || EventRecord::peer(Event::Connect, PEER);
+
+    for _ in 0..2 {
+        log.record(connect()).await;
+    }
+    assert_eq!(
+        read_lines(&path).len(),
+        1,
+        "second line would pass 200 bytes"
+    );
+    assert_eq!(read_lines(&rotated_path(&path, 1)).len(), 1);
+
+    log.inner.active.lock().await.created -= Duration::from_secs(61);
+    log.record(connect()).await;
+    assert_eq!(read_lines(&path).len(), 1, "rotated by age");
+    assert!(rotated_path(&path, 2).exists());
+    log.record(connect()).await;
+    assert!(!rotated_path(&path, 3).exists(), "only max_files are kept");
+}
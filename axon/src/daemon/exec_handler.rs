@@ -0,0 +1,208 @@
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde_json::{Value, json};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::process::Command;
+use tokio::sync::Semaphore;
+use tracing::{debug, warn};
+
+use super::handler_cache::HandlerCache;
+use crate::config::HandlerConfig;
+use crate::errors::WireErrorCode;
+use crate::message::{Envelope, ErrorPayload, MAX_MESSAGE_SIZE, MessageKind};
+use crate::transport::ResponseHandlerFn;
+
+/// Bytes of handler stderr kept for logs and error messages.
+const STDERR_PREVIEW_BYTES: usize = 512;
+
+/// Answers inbound `request`s by running an external executable: the request
+/// envelope is written to its stdin as one JSON line, and its stdout (a JSON
+/// object) becomes the `response` payload.
+pub(crate) struct ExecHandler {
+    program: PathBuf,
+    timeout: Duration,
+    permits: Arc<Semaphore>,
+    local_agent_id: String,
+    cache: Option<HandlerCache>,
+}
+
+impl ExecHandler {
+    pub(crate) fn new(program: PathBuf, config: &HandlerConfig, local_agent_id: String) -> Self {
+        Self {
+            program,
+            timeout: config.timeout(),
+            permits: Arc::new(Semaphore::new(config.max_concurrent())),
+            local_agent_id,
+            cache: config
+                .cache_ttl()
+                .map(|ttl| HandlerCache::new(ttl, config.cache_max_entries())),
+        }
+    }
+
+    /// Wrap as a transport response handler.
+    pub(crate) fn into_response_handler(self) -> ResponseHandlerFn {
+        let handler = Arc::new(self);
+        Arc::new(move |request: Arc<Envelope>| {
+            let handler = handler.clone();
+            Box::pin(async move { Some(handler.respond(&request).await) })
+        })
+    }
+
+    pub(crate) async fn respond(&self, request: &Envelope) -> Envelope {
+        let cache_key = self.cache.as_ref().and_then(|_| HandlerCache::key(request));
+        let cached = cache_key
+            .as_ref()
+            .and_then(|key| self.cache.as_ref()?.get(key));
+        if let Some(payload) = cached {
+            debug!(msg_id = %request.id, "answered request from handler cache");
+            return self.reply(request, MessageKind::Response, payload);
+        }
+        let payload = match self.permits.clone().try_acquire_owned() {
+            Ok(_permit) => match self.run(request).await {
+                Ok(payload) => {
+                    if let (Some(cache), Some(key)) = (&self.cache, cache_key) {
+                        cache.insert(key, payload.clone());
+                    }
+                    return self.reply(request, MessageKind::Response, payload);
+                }
+                Err(error) => error,
+            },
+            Err(_) => ErrorPayload::new(
+                WireErrorCode::HandlerBusy,
+                "all request handler slots are in use; retry shortly",
+            ),
+        };
+        warn!(
+            msg_id = %request.id,
+            code = %payload.code,
+            message = %payload.message,
+            "request handler failed"
+        );
+        self.reply(request, MessageKind::Error, payload.to_value())
+    }
+
+    fn reply(&self, request: &Envelope, kind: MessageKind, payload: Value) -> Envelope {
+        Envelope::response_to(request, self.local_agent_id.clone(), kind, payload)
+    }
+
+    async fn run(&self, request: &Envelope) -> Result<Value, ErrorPayload> {
+        let mut input = serde_json::to_vec(request).map_err(|err| {
+            ErrorPayload::new(
+                WireErrorCode::HandlerFailed,
+                format!("failed to encode request: {err}"),
+            )
+        })?;
+        input.push(b'\n');
+
+        let mut child = Command::new(&self.program)
+            .env(
+                "AXON_FROM",
+                request.from.as_ref().map(|id| id.as_str()).unwrap_or(""),
+            )
+            .env("AXON_MSG_ID", request.id.to_string())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|err| {
+                ErrorPayload::new(
+                    WireErrorCode::HandlerFailed,
+                    format!("failed to start handler {}: {err}", self.program.display()),
+                )
+            })?;
+
+        let mut stdin = child.stdin.take().expect("piped stdin");
+        let stdout = child.stdout.take().expect("piped stdout");
+        let stderr = child.stderr.take().expect("piped stderr");
+
+        let io = async {
+            // A handler that exits without reading stdin is not an error.
+            let write = async {
+                let _ = stdin.write_all(&input).await;
+                drop(stdin);
+            };
+            let read_stdout = async {
+                let mut buf = Vec::new();
+                stdout
+                    .take(MAX_MESSAGE_SIZE as u64 + 1)
+                    .read_to_end(&mut buf)
+                    .await
+                    .map(|_| buf)
+            };
+            let read_stderr = async {
+                let mut buf = Vec::new();
+                let _ = stderr
+                    .take(STDERR_PREVIEW_BYTES as u64)
+                    .read_to_end(&mut buf)
+                    .await;
+                buf
+            };
+            let ((), stdout, stderr) = tokio::join!(write, read_stdout, read_stderr);
+            let status = child.wait().await;
+            (stdout, stderr, status)
+        };
+
+        let (stdout, stderr, status) =
+            tokio::time::timeout(self.timeout, io).await.map_err(|_| {
+                ErrorPayload::new(
+                    WireErrorCode::HandlerTimeout,
+                    format!(
+                        "request handler did not finish within {}s",
+                        self.timeout.as_secs()
+                    ),
+                )
+            })?;
+
+        let stderr = String::from_utf8_lossy(&stderr).trim().to_string();
+        if !stderr.is_empty() {
+            debug!(msg_id = %request.id, stderr = %stderr, "request handler stderr");
+        }
+
+        let status = status.map_err(|err| {
+            ErrorPayload::new(
+                WireErrorCode::HandlerFailed,
+                format!("failed waiting for handler: {err}"),
+            )
+        })?;
+        if !status.success() {
+            let detail = if stderr.is_empty() {
+                String::new()
+            } else {
+                format!(": {stderr}")
+            };
+            return Err(ErrorPayload::new(
+                WireErrorCode::HandlerFailed,
+                format!("request handler exited with {status}{detail}"),
+            )
+            .with_details(json!({ "exit_code": status.code() })));
+        }
+
+        let stdout = stdout.map_err(|err| {
+            ErrorPayload::new(
+                WireErrorCode::HandlerFailed,
+                format!("failed reading handler output: {err}"),
+            )
+        })?;
+        if stdout.len() > MAX_MESSAGE_SIZE as usize {
+            return Err(ErrorPayload::new(
+                WireErrorCode::HandlerInvalidOutput,
+                format!("handler output exceeds {MAX_MESSAGE_SIZE} bytes"),
+            ));
+        }
+        match serde_json::from_slice::<Value>(&stdout) {
+            Ok(payload @ Value::Object(_)) => Ok(payload),
+            Ok(_) | Err(_) => Err(ErrorPayload::new(
+                WireErrorCode::HandlerInvalidOutput,
+                "handler stdout must be a single JSON object",
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "exec_handler_tests.rs"]
+mod tests;
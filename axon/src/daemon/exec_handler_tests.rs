@@ -0,0 +1,145 @@
+use super::*;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+
+const LOCAL_ID: &str = "ed25519.bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb";
+const PEER_ID: &str = "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+
+fn script(dir: &Path, body: &str) -> PathBuf {
+    let path = dir.join("handler.sh");
+    std::fs::write(&path, format!("#!/bin/sh\n{body}\n")).unwrap();
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    path
+}
+
+fn handler(program: PathBuf, timeout_secs: u64, max_concurrent: usize) -> ExecHandler {
+    let config = HandlerConfig {
+        exec: Some(program.clone()),
+        timeout_secs: Some(timeout_secs),
+        max_concurrent: Some(max_concurrent),
+        ..HandlerConfig::default()
+    };
+    ExecHandler::new(program, &config, LOCAL_ID.to_string())
+}
+
+fn request() -> Envelope {
+    Envelope::new(
+        PEER_ID.to_string(),
+        LOCAL_ID.to_string(),
+        MessageKind::Request,
+        json!({"question": "ping"}),
+    )
+}
+
+fn payload(envelope: &Envelope) -> Value {
+    envelope.payload_value().unwrap()
+}
+
+#[tokio::test]
+async fn stdout_json_becomes_response_payload() {
+    let dir = tempfile::tempdir().unwrap();
+    let program = script(
+        dir.path(),
+        r#"input=$(cat)
+case "$input" in *'"question":"ping"'*) q=ping ;; *) q=missing ;; esac
+printf '{"answer":"%s","from":"%s","id":"%s"}' "$q" "$AXON_FROM" "$AXON_MSG_ID""#,
+    );
+    let request = request();
+
+    let response = handler(program, 5, 1).respond(&request).await;
+
+    assert_eq!(response.kind, MessageKind::Response);
+    assert_eq!(response.ref_id, Some(request.id));
+    assert_eq!(
+        payload(&response),
+        json!({"answer": "ping", "from": PEER_ID, "id": request.id.to_string()})
+    );
+}
+
+#[tokio::test]
+async fn nonzero_exit_maps_to_handler_failed() {
+    let dir = tempfile::tempdir().unwrap();
+    let program = script(dir.path(), "echo 'boom' >&2\nexit 3");
+
+    let response = handler(program, 5, 1).respond(&request()).await;
+
+    assert_eq!(response.kind, MessageKind::Error);
+    let body = payload(&response);
+    assert_eq!(body["code"], "handler_failed");
+    assert_eq!(body["retryable"], false);
+    assert!(body["message"].as_str().unwrap().contains("boom"));
+}
+
+#[tokio::test]
+async fn non_object_output_maps_to_handler_invalid_output() {
+    let dir = tempfile::tempdir().unwrap();
+    let program = script(dir.path(), "echo '[1, 2]'");
+
+    let response = handler(program, 5, 1).respond(&request()).await;
+
+    assert_eq!(response.kind, MessageKind::Error);
+    assert_eq!(payload(&response)["code"], "handler_invalid_output");
+}
+
+#[tokio::test]
+async fn slow_handler_maps_to_retryable_timeout() {
+    let dir = tempfile::tempdir().unwrap();
+    let program = script(dir.path(), "exec sleep 5");
+
+    let response = handler(program, 1, 1).respond(&request()).await;
+
+    assert_eq!(response.kind, MessageKind::Error);
+    let body = payload(&response);
+    assert_eq!(body["code"], "handler_timeout");
+    assert_eq!(body["retryable"], true);
+}
+
+#[tokio::test]
+async fn saturated_handler_reports_busy() {
+    let dir = tempfile::tempdir().unwrap();
+    let program = script(dir.path(), "exec sleep 5");
+    let handler = handler(program, 1, 1);
+    let _permit = handler.permits.clone().try_acquire_owned().unwrap();
+
+    let response = handler.respond(&request()).await;
+
+    let body = payload(&response);
+    assert_eq!(body["code"], "handler_busy");
+    assert_eq!(body["retryable"], true);
+}
+
+#[tokio::test]
+async fn identical_requests_are_answered_from_the_cache() {
+    let dir = tempfile::tempdir().unwrap();
+    let runs = dir.path().join("runs");
+    let program = script(
+        dir.path(),
+        &format!(
+            "echo run >> {}\nprintf '{{\"runs\":%s}}' $(wc -l < {})",
+            runs.display(),
+            runs.display()
+        ),
+    );
+    let config = HandlerConfig {
+        exec: Some(program.clone()),
+        cache_ttl_secs: Some(60),
+        ..HandlerConfig::default()
+    };
+    let handler = ExecHandler::new(program, &config, LOCAL_ID.to_string());
+
+    let first = handler.respond(&request()).await;
+    let repeat = request();
+    let second = handler.respond(&repeat).await;
+
+    assert_eq!(payload(&first), json!({"runs": 1}));
+    assert_eq!(payload(&second), json!({"runs": 1}));
+    assert_eq!(second.ref_id, Some(repeat.id));
+
+    let other = Envelope::new(
+        PEER_ID.to_string(),
+        LOCAL_ID.to_string(),
+        MessageKind::Request,
+        json!({"question": "pong"}),
+    );
+    assert_eq!(payload(&handler.respond(&other).await), json!({"runs": 2}));
+}
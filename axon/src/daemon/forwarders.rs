@@ -0,0 +1,315 @@
+use std::sync::Arc;
+use std::time::Instant;
+
+use anyhow::{Result, bail};
+use tokio::sync::broadcast;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, trace, warn};
+
+use super::audit::{AuditEvent, AuditLog, AuditRecord};
+use super::command_handler::Counters;
+use super::event_log::{Event, EventLog, EventRecord};
+use super::heartbeat::{HeartbeatMonitor, note_peer_software};
+use super::history::History;
+use super::supervisor::Supervisor;
+use super::threads::ThreadIndex;
+use super::topics::{SubscribeTopics, TopicTable, normalize_subscriptions};
+use super::transforms::{Outcome, Transforms};
+use super::webhooks::Webhooks;
+use crate::ipc::{HistoryDirection, IpcServer};
+use crate::message::{Envelope, MessageKind};
+use crate::peer_table::PeerTable;
+use crate::transport::{ConnectionEvent, PairRequest, QuicTransport};
+
+// ---------------------------------------------------------------------------
+// Transport → IPC forwarders
+// ---------------------------------------------------------------------------
+
+/// Forward inbound envelopes from the transport, after the `transforms`
+/// pipeline, to history, matching webhooks, and all IPC clients.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn spawn_inbound_forwarder(
+    supervisor: &Arc<Supervisor>,
+    transport: &QuicTransport,
+    ipc: IpcServer,
+    counters: Arc<Counters>,
+    heartbeats: Arc<HeartbeatMonitor>,
+    topics: Arc<TopicTable>,
+    threads: Arc<ThreadIndex>,
+    peer_table: PeerTable,
+    history: Option<History>,
+    audit: Option<AuditLog>,
+    events: Option<EventLog>,
+    webhooks: Option<Webhooks>,
+    transforms: Arc<Transforms>,
+    cancel: CancellationToken,
+) {
+    let transport = transport.clone();
+    supervisor.spawn("inbound_forwarder", cancel.clone(), move || {
+        forward_inbound(
+            transport.subscribe_inbound(),
+            ipc.clone(),
+            counters.clone(),
+            heartbeats.clone(),
+            topics.clone(),
+            threads.clone(),
+            peer_table.clone(),
+            history.clone(),
+            audit.clone(),
+            events.clone(),
+            webhooks.clone(),
+            transforms.clone(),
+            cancel.clone(),
+        )
+    });
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn forward_inbound(
+    mut inbound_rx: broadcast::Receiver<Arc<Envelope>>,
+    ipc: IpcServer,
+    counters: Arc<Counters>,
+    heartbeats: Arc<HeartbeatMonitor>,
+    topics: Arc<TopicTable>,
+    threads: Arc<ThreadIndex>,
+    peer_table: PeerTable,
+    history: Option<History>,
+    audit: Option<AuditLog>,
+    events: Option<EventLog>,
+    webhooks: Option<Webhooks>,
+    transforms: Arc<Transforms>,
+    cancel: CancellationToken,
+) -> Result<()> {
+    loop {
+        tokio::select! {
+            _ = cancel.cancelled() => break,
+            msg = inbound_rx.recv() => {
+                match msg {
+                    Ok(envelope) => {
+                        let from_id = envelope.from.as_deref().unwrap_or("unknown");
+                        let heartbeat = envelope.kind == MessageKind::Heartbeat;
+                        heartbeats.heard(from_id, heartbeat, Instant::now());
+                        if heartbeat {
+                            // Liveness and version only: not counted, recorded, or delivered.
+                            note_peer_software(&peer_table, &envelope).await;
+                            if peer_table.clear_degraded(from_id).await {
+                                info!(peer = from_id, "peer heartbeats resumed");
+                            }
+                            continue;
+                        }
+                        if envelope.kind == MessageKind::SubscribeTopics {
+                            match envelope
+                                .payload_as::<SubscribeTopics>()
+                                .and_then(|sub| normalize_subscriptions(sub.topics))
+                            {
+                                Ok(set) => {
+                                    debug!(peer = from_id, topics = ?set, "peer topic subscriptions updated");
+                                    topics.set_remote(from_id, set);
+                                }
+                                Err(err) => {
+                                    warn!(peer = from_id, error = %err, "ignoring invalid topic subscriptions");
+                                }
+                            }
+                            continue;
+                        }
+                        if let Some(ref topic) = envelope.topic
+                            && !topics.wants(topic)
+                        {
+                            debug!(msg_id = %envelope.id, from = from_id, topic, "dropping message for unsubscribed topic");
+                            continue;
+                        }
+                        counters.record_received();
+                        peer_table.record_received(from_id, &envelope).await;
+                        threads.record(&envelope);
+                        let payload_bytes = envelope.payload.get().len();
+                        info!(
+                            msg_id = %envelope.id,
+                            from = from_id,
+                            kind = %envelope.kind,
+                            payload_bytes,
+                            "message received"
+                        );
+                        {
+                            let raw = envelope.payload.get();
+                            let truncated = if raw.len() > 256 {
+                                format!("{}…", &raw[..256])
+                            } else {
+                                raw.to_string()
+                            };
+                            debug!(
+                                msg_id = %envelope.id,
+                                from = from_id,
+                                kind = %envelope.kind,
+                                payload = %truncated,
+                                "message received (payload preview)"
+                            );
+                            trace!(
+                                msg_id = %envelope.id,
+                                from = from_id,
+                                kind = %envelope.kind,
+                                payload = raw,
+                                "message received (full payload)"
+                            );
+                        }
+                        if let Some(ref from) = envelope.from {
+                            peer_table.set_connected(from.as_str(), None).await;
+                        }
+                        // The audit and event logs record what the peer sent.
+                        if let Some(ref audit) = audit {
+                            audit.record(AuditRecord::envelope(AuditEvent::Receive, &envelope)).await;
+                        }
+                        if let Some(ref events) = events {
+                            events.record(EventRecord::envelope(Event::Receive, &envelope)).await;
+                        }
+                        let envelope = match transforms.apply(&envelope) {
+                            Outcome::Unchanged => envelope,
+                            Outcome::Changed(transformed) => Arc::new(transformed),
+                            Outcome::Dropped => {
+                                debug!(msg_id = %envelope.id, from = from_id, "dropping message by inbound transform");
+                                continue;
+                            }
+                        };
+                        if let Some(ref history) = history {
+                            history.record(HistoryDirection::In, &envelope).await;
+                        }
+                        if let Some(ref webhooks) = webhooks {
+                            webhooks.dispatch(&envelope);
+                        }
+                        if let Err(err) = ipc.broadcast_inbound(&envelope).await {
+                            warn!(error = %err, "failed broadcasting inbound to IPC clients");
+                        }
+                    }
+                    Err(err) => bail!("inbound subscription closed: {err}"),
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Forward rejected unknown-peer handshakes (TLS verifier) to all IPC clients.
+pub(crate) fn spawn_pair_request_forwarder(
+    supervisor: &Arc<Supervisor>,
+    transport: &QuicTransport,
+    ipc: IpcServer,
+    audit: Option<AuditLog>,
+    cancel: CancellationToken,
+) {
+    let transport = transport.clone();
+    supervisor.spawn("pair_request_forwarder", cancel.clone(), move || {
+        forward_pair_requests(
+            transport.subscribe_pair_requests(),
+            ipc.clone(),
+            audit.clone(),
+            cancel.clone(),
+        )
+    });
+}
+
+async fn forward_pair_requests(
+    mut pair_request_rx: broadcast::Receiver<PairRequest>,
+    ipc: IpcServer,
+    audit: Option<AuditLog>,
+    cancel: CancellationToken,
+) -> Result<()> {
+    loop {
+        tokio::select! {
+            _ = cancel.cancelled() => break,
+            msg = pair_request_rx.recv() => {
+                match msg {
+                    Ok(pair_request) => {
+                        if let Some(ref audit) = audit {
+                            audit
+                                .record(
+                                    AuditRecord::peer(AuditEvent::AuthReject, &pair_request.agent_id)
+                                        .with_addr(pair_request.addr.clone())
+                                        .with_reason("unknown_peer"),
+                                )
+                                .await;
+                        }
+                        if let Err(err) = ipc
+                            .broadcast_pair_request(
+                                &pair_request.agent_id,
+                                &pair_request.pubkey,
+                                pair_request.addr.as_deref(),
+                            )
+                            .await
+                        {
+                            warn!(error = %err, "failed broadcasting pair_request to IPC clients");
+                        }
+                    }
+                    Err(err) => bail!("pair_request subscription closed: {err}"),
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Forward peer connection lifecycle transitions to all IPC clients.
+pub(crate) fn spawn_connection_event_forwarder(
+    supervisor: &Arc<Supervisor>,
+    transport: &QuicTransport,
+    ipc: IpcServer,
+    heartbeats: Arc<HeartbeatMonitor>,
+    audit: Option<AuditLog>,
+    events: Option<EventLog>,
+    cancel: CancellationToken,
+) {
+    let transport = transport.clone();
+    supervisor.spawn("connection_event_forwarder", cancel.clone(), move || {
+        forward_connection_events(
+            transport.subscribe_connection_events(),
+            ipc.clone(),
+            heartbeats.clone(),
+            audit.clone(),
+            events.clone(),
+            cancel.clone(),
+        )
+    });
+}
+
+async fn forward_connection_events(
+    mut connection_event_rx: broadcast::Receiver<ConnectionEvent>,
+    ipc: IpcServer,
+    heartbeats: Arc<HeartbeatMonitor>,
+    audit: Option<AuditLog>,
+    events: Option<EventLog>,
+    cancel: CancellationToken,
+) -> Result<()> {
+    loop {
+        tokio::select! {
+            _ = cancel.cancelled() => break,
+            msg = connection_event_rx.recv() => {
+                let result = match msg {
+                    Ok(ConnectionEvent::Connected { agent_id }) => {
+                        info!(peer_id = %agent_id, "peer connected");
+                        heartbeats.forget(&agent_id);
+                        if let Some(ref audit) = audit {
+                            audit.record(AuditRecord::peer(AuditEvent::AuthAccept, &agent_id)).await;
+                        }
+                        if let Some(ref events) = events {
+                            events.record(EventRecord::peer(Event::Connect, &agent_id)).await;
+                        }
+                        ipc.broadcast_peer_connected(&agent_id).await
+                    }
+                    Ok(ConnectionEvent::Disconnected { agent_id, reason }) => {
+                        info!(peer_id = %agent_id, reason, "peer disconnected");
+                        heartbeats.forget(&agent_id);
+                        if let Some(ref events) = events {
+                            events
+                                .record(EventRecord::peer(Event::Disconnect, &agent_id).with_reason(reason))
+                                .await;
+                        }
+                        ipc.broadcast_peer_disconnected(&agent_id, reason).await
+                    }
+                    Err(err) => bail!("connection event subscription closed: {err}"),
+                };
+                if let Err(err) = result {
+                    warn!(error = %err, "failed broadcasting connection event to IPC clients");
+                }
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,96 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::message::Envelope;
+
+type Key = (String, [u8; 32]);
+
+#[derive(Debug, Default)]
+struct CacheState {
+    entries: HashMap<Key, Value>,
+    /// Keys in insertion order, for expiry and eviction of the oldest entry.
+    order: VecDeque<(Instant, Key)>,
+}
+
+/// Response payloads from `handler.exec`, keyed by (requesting peer, hash of
+/// the request payload). An identical request from the same peer within the
+/// TTL is answered from here without running the handler.
+///
+/// Only successful responses are stored. Concurrent identical requests are
+/// not coalesced: each runs the handler until one response is cached.
+#[derive(Debug)]
+pub(crate) struct HandlerCache {
+    ttl: Duration,
+    max_entries: usize,
+    state: Mutex<CacheState>,
+}
+
+impl HandlerCache {
+    pub(crate) fn new(ttl: Duration, max_entries: usize) -> Self {
+        Self {
+            ttl,
+            max_entries,
+            state: Mutex::default(),
+        }
+    }
+
+    /// Cache key for `request`, or `None` when it has no sender. The payload
+    /// is hashed in canonical form (sorted keys), so field order does not
+    /// matter; headers, topic, and thread are not part of the key.
+    pub(crate) fn key(request: &Envelope) -> Option<Key> {
+        let from = request.from.as_ref()?.to_string();
+        let payload = request.payload_value().ok()?;
+        let digest = Sha256::digest(payload.to_string().as_bytes());
+        Some((from, digest.into()))
+    }
+
+    pub(crate) fn get(&self, key: &Key) -> Option<Value> {
+        self.get_at(key, Instant::now())
+    }
+
+    pub(crate) fn insert(&self, key: Key, payload: Value) {
+        self.insert_at(key, payload, Instant::now());
+    }
+
+    fn get_at(&self, key: &Key, now: Instant) -> Option<Value> {
+        let mut state = self.state.lock().ok()?;
+        self.evict(&mut state, now);
+        state.entries.get(key).cloned()
+    }
+
+    fn insert_at(&self, key: Key, payload: Value, now: Instant) {
+        let Ok(mut state) = self.state.lock() else {
+            return;
+        };
+        self.evict(&mut state, now);
+        if state.entries.remove(&key).is_some() {
+            state.order.retain(|(_, queued)| *queued != key);
+        }
+        while state.entries.len() >= self.max_entries {
+            let Some((_, oldest)) = state.order.pop_front() else {
+                break;
+            };
+            state.entries.remove(&oldest);
+        }
+        state.entries.insert(key.clone(), payload);
+        state.order.push_back((now, key));
+    }
+
+    fn evict(&self, state: &mut CacheState, now: Instant) {
+        while let Some((inserted, key)) = state.order.front() {
+            if now.saturating_duration_since(*inserted) < self.ttl {
+                break;
+            }
+            state.entries.remove(key);
+            state.order.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "handler_cache_tests.rs"]
+mod tests;
@@ -0,0 +1,60 @@
+use super::*;
+use serde_json::json;
+
+use crate::message::MessageKind;
+
+const PEER_A: &str = "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+const PEER_B: &str = "ed25519.bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb";
+const LOCAL: &str = "ed25519.cccccccccccccccccccccccccccccccc";
+
+fn request(from: &str, payload: Value) -> Envelope {
+    Envelope::new(from, LOCAL, MessageKind::Request, payload)
+}
+
+#[test]
+fn key_ignores_field_order_but_not_the_peer() {
+    let a = HandlerCache::key(&request(PEER_A, json!({"q": 1, "domain": "x"}))).unwrap();
+    let reordered: Value = serde_json::from_str(r#"{"domain":"x","q":1}"#).unwrap();
+    assert_eq!(
+        HandlerCache::key(&request(PEER_A, reordered)),
+        Some(a.clone())
+    );
+    assert_ne!(
+        HandlerCache::key(&request(PEER_B, json!({"q": 1, "domain": "x"}))),
+        Some(a.clone())
+    );
+    assert_ne!(
+        HandlerCache::key(&request(PEER_A, json!({"q": 2, "domain": "x"}))),
+        Some(a)
+    );
+}
+
+#[test]
+fn entries_expire_after_ttl() {
+    let cache = HandlerCache::new(Duration::from_secs(30), 16);
+    let key = HandlerCache::key(&request(PEER_A, json!({"q": 1}))).unwrap();
+    let start = Instant::now();
+    cache.insert_at(key.clone(), json!({"answer": 1}), start);
+
+    assert_eq!(
+        cache.get_at(&key, start + Duration::from_secs(29)),
+        Some(json!({"answer": 1}))
+    );
+    assert_eq!(cache.get_at(&key, start + Duration::from_secs(30)), None);
+}
+
+#[test]
+fn oldest_entry_is_evicted_at_capacity() {
+    let cache = HandlerCache::new(Duration::from_secs(30), 2);
+    let keys: Vec<_> = (0..3)
+        .map(|q| HandlerCache::key(&request(PEER_A, json!({"q": q}))).unwrap())
+        .collect();
+    let now = Instant::now();
+    for (answer, key) in keys.iter().enumerate() {
+        cache.insert_at(key.clone(), json!(answer), now);
+    }
+
+    assert_eq!(cache.get_at(&keys[0], now), None);
+    assert_eq!(cache.get_at(&keys[1], now), Some(json!(1)));
+    assert_eq!(cache.get_at(&keys[2], now), Some(json!(2)));
+}
@@ -0,0 +1,73 @@
+use std::sync::Arc;
+
+#[cfg(feature = "script")]
+use anyhow::Context;
+use anyhow::Result;
+use tracing::info;
+
+use super::exec_handler::ExecHandler;
+#[cfg(feature = "script")]
+use super::script_handler::ScriptHandler;
+use crate::config::HandlerConfig;
+use crate::message::Envelope;
+use crate::transport::ResponseHandlerFn;
+
+/// The inbound request handler chain: `embedded` (an `axon::node` handler),
+/// then `handler.script`, then `handler.exec`. Each one passes the requests
+/// it returns `None` for to the next.
+pub(crate) fn response_handler(
+    config: &HandlerConfig,
+    local_agent_id: &str,
+    embedded: Option<ResponseHandlerFn>,
+) -> Result<Option<ResponseHandlerFn>> {
+    let exec_handler = config.exec.clone().map(|program| {
+        info!(handler = %program.display(), "answering inbound requests via handler.exec");
+        ExecHandler::new(program, config, local_agent_id.to_string()).into_response_handler()
+    });
+    let script_handler = load_script_handler(config, local_agent_id)?;
+    Ok([embedded, script_handler, exec_handler]
+        .into_iter()
+        .flatten()
+        .reduce(chain_response_handlers))
+}
+
+#[cfg(feature = "script")]
+fn load_script_handler(
+    config: &HandlerConfig,
+    local_agent_id: &str,
+) -> Result<Option<ResponseHandlerFn>> {
+    let Some(path) = &config.script else {
+        return Ok(None);
+    };
+    let handler = ScriptHandler::load(path, config, local_agent_id.to_string())
+        .context("failed to load handler.script")?;
+    info!(script = %path.display(), "answering inbound requests via handler.script");
+    Ok(Some(handler.into_response_handler()))
+}
+
+#[cfg(not(feature = "script"))]
+fn load_script_handler(
+    config: &HandlerConfig,
+    _local_agent_id: &str,
+) -> Result<Option<ResponseHandlerFn>> {
+    if config.script.is_some() {
+        anyhow::bail!("handler.script requires axon built with the `script` feature");
+    }
+    Ok(None)
+}
+
+/// `first`, falling back to `fallback` for requests it returns `None` for.
+fn chain_response_handlers(
+    first: ResponseHandlerFn,
+    fallback: ResponseHandlerFn,
+) -> ResponseHandlerFn {
+    Arc::new(move |request: Arc<Envelope>| {
+        let (first, fallback) = (first.clone(), fallback.clone());
+        Box::pin(async move {
+            match first(request.clone()).await {
+                Some(response) => Some(response),
+                None => fallback(request).await,
+            }
+        })
+    })
+}
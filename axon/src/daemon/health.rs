@@ -0,0 +1,146 @@
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU8, AtomicU64, Ordering};
+
+use crate::ipc::{
+    DaemonReply, DiscoveryHealth, HealthSubsystems, HistoryHealth, IpcHealth, PersistenceHealth,
+    TasksHealth, TransportHealth,
+};
+use crate::message::now_millis;
+use crate::peer_table::PeerSource;
+
+use super::command_handler::DaemonContext;
+
+const MDNS_DISABLED: u8 = 0;
+const MDNS_RUNNING: u8 = 1;
+const MDNS_FAILED: u8 = 2;
+
+/// Subsystem signals that are not observable from the objects in
+/// `DaemonContext` alone, updated by the tasks that own them.
+pub(crate) struct HealthState {
+    mdns: AtomicU8,
+    /// Unix ms of the last successful `known_peers.json` write; 0 = never.
+    last_persist_ms: AtomicU64,
+    persist_error: Mutex<Option<String>>,
+}
+
+impl HealthState {
+    pub(crate) fn new(mdns_enabled: bool) -> Self {
+        Self {
+            mdns: AtomicU8::new(if mdns_enabled {
+                MDNS_RUNNING
+            } else {
+                MDNS_DISABLED
+            }),
+            last_persist_ms: AtomicU64::new(0),
+            persist_error: Mutex::new(None),
+        }
+    }
+
+    pub(crate) fn set_mdns_running(&self) {
+        self.mdns.store(MDNS_RUNNING, Ordering::Relaxed);
+    }
+
+    pub(crate) fn set_mdns_failed(&self) {
+        self.mdns.store(MDNS_FAILED, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_persist(&self, result: &anyhow::Result<()>) {
+        let error = match result {
+            Ok(()) => {
+                self.last_persist_ms.store(now_millis(), Ordering::Relaxed);
+                None
+            }
+            Err(err) => Some(format!("{err:#}")),
+        };
+        if let Ok(mut slot) = self.persist_error.lock() {
+            *slot = error;
+        }
+    }
+
+    fn mdns_str(&self) -> &'static str {
+        match self.mdns.load(Ordering::Relaxed) {
+            MDNS_RUNNING => "running",
+            MDNS_FAILED => "failed",
+            _ => "disabled",
+        }
+    }
+
+    fn persistence(&self) -> PersistenceHealth {
+        let last_error = self.persist_error.lock().ok().and_then(|slot| slot.clone());
+        let last_write_ms = match self.last_persist_ms.load(Ordering::Relaxed) {
+            0 => None,
+            ms => Some(ms),
+        };
+        PersistenceHealth {
+            ok: last_error.is_none(),
+            last_write_ms,
+            last_error,
+        }
+    }
+}
+
+pub(crate) async fn health_reply(ctx: &DaemonContext<'_>, req_id: Option<String>) -> DaemonReply {
+    let bound_addr = ctx.transport.local_addr().ok().map(|addr| addr.to_string());
+    let transport = TransportHealth {
+        ok: bound_addr.is_some(),
+        bound_addr,
+        connections: ctx.transport.connection_count().await,
+    };
+
+    let mdns = ctx.health.mdns_str();
+    let static_peers = ctx
+        .peer_table
+        .list()
+        .await
+        .iter()
+        .filter(|peer| peer.source == PeerSource::Static)
+        .count();
+    let discovery = DiscoveryHealth {
+        ok: mdns != "failed",
+        mdns,
+        static_peers,
+    };
+
+    let clients = ctx.ipc.client_count().await;
+    let ipc = IpcHealth {
+        ok: clients <= ctx.max_ipc_clients,
+        clients,
+        max_clients: ctx.max_ipc_clients,
+    };
+
+    let tasks = ctx.supervisor.report();
+    let tasks = TasksHealth {
+        ok: tasks.iter().all(|task| task.state != "restarting"),
+        tasks,
+    };
+
+    let subsystems = HealthSubsystems {
+        transport,
+        discovery,
+        ipc,
+        persistence: ctx.health.persistence(),
+        history: HistoryHealth {
+            ok: true,
+            enabled: ctx.history.is_some(),
+        },
+        tasks,
+    };
+    let healthy = subsystems.transport.ok
+        && subsystems.discovery.ok
+        && subsystems.ipc.ok
+        && subsystems.persistence.ok
+        && subsystems.history.ok
+        && subsystems.tasks.ok;
+
+    DaemonReply::Health {
+        ok: true,
+        healthy,
+        uptime_secs: ctx.start.elapsed().as_secs(),
+        subsystems,
+        req_id,
+    }
+}
+
+#[cfg(test)]
+#[path = "health_tests.rs"]
+mod tests;
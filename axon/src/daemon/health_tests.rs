@@ -0,0 +1,29 @@
+use super::*;
+
+#[test]
+fn persistence_tracks_last_write_and_clears_error_on_success() {
+    let health = HealthState::new(true);
+    let initial = health.persistence();
+    assert!(initial.ok);
+    assert_eq!(initial.last_write_ms, None);
+
+    health.record_persist(&Err(anyhow::anyhow!("disk full")));
+    let failed = health.persistence();
+    assert!(!failed.ok);
+    assert_eq!(failed.last_error.as_deref(), Some("disk full"));
+
+    health.record_persist(&Ok(()));
+    let recovered = health.persistence();
+    assert!(recovered.ok);
+    assert!(recovered.last_error.is_none());
+    assert!(recovered.last_write_ms.is_some());
+}
+
+#[test]
+fn mdns_state_reflects_config_and_failure() {
+    assert_eq!(HealthState::new(false).mdns_str(), "disabled");
+    let health = HealthState::new(true);
+    assert_eq!(health.mdns_str(), "running");
+    health.set_mdns_failed();
+    assert_eq!(health.mdns_str(), "failed");
+}
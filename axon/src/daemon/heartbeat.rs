@@ -0,0 +1,143 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde_json::json;
+use tracing::{debug, info, warn};
+
+use crate::message::{AgentId, Envelope, MessageKind};
+use crate::peer_table::{PeerSoftware, PeerTable, VersionCompatibility};
+use crate::transport::QuicTransport;
+
+/// Daemon-level features announced in heartbeats, for peers to check.
+pub(crate) const FEATURES: &[&str] = &["heartbeat", "subscribe_topics", "request_dedup"];
+
+/// This daemon's heartbeat payload.
+pub(crate) fn local_software() -> PeerSoftware {
+    PeerSoftware {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        features: FEATURES.iter().map(|feature| feature.to_string()).collect(),
+    }
+}
+
+/// Record the software a peer reported in a heartbeat, logging when it is
+/// first learned or changes and warning when it is semver-incompatible
+/// with ours. Heartbeats from daemons that predate version reporting carry
+/// no version and are ignored.
+pub(crate) async fn note_peer_software(peer_table: &PeerTable, envelope: &Envelope) {
+    let (Some(from), Ok(software)) = (&envelope.from, envelope.payload_as::<PeerSoftware>()) else {
+        return;
+    };
+    let version = software.version.clone();
+    if !peer_table.set_software(from.as_str(), software).await {
+        return;
+    }
+    let local = env!("CARGO_PKG_VERSION");
+    match VersionCompatibility::of(local, &version) {
+        VersionCompatibility::Older => warn!(
+            peer = %from,
+            version,
+            local,
+            "peer runs an older, incompatible axon version; upgrade it"
+        ),
+        VersionCompatibility::Newer => warn!(
+            peer = %from,
+            version,
+            local,
+            "peer runs a newer, incompatible axon version; upgrade this daemon"
+        ),
+        VersionCompatibility::Compatible | VersionCompatibility::Unknown => {
+            info!(peer = %from, version, "peer software version")
+        }
+    }
+}
+
+/// When each heartbeat-capable peer was last heard from.
+///
+/// A peer is tracked from its first heartbeat until its connection goes away,
+/// so peers that never send heartbeats (older daemons, or `interval_secs: 0`)
+/// are never marked degraded. Any inbound envelope refreshes a tracked peer.
+#[derive(Debug, Default)]
+pub(crate) struct HeartbeatMonitor {
+    last_heard: Mutex<HashMap<AgentId, Instant>>,
+}
+
+impl HeartbeatMonitor {
+    /// Record inbound traffic from `agent_id` at `now`. Only a heartbeat
+    /// starts tracking a peer.
+    pub(crate) fn heard(&self, agent_id: &str, heartbeat: bool, now: Instant) {
+        if let Ok(mut last_heard) = self.last_heard.lock() {
+            if let Some(at) = last_heard.get_mut(agent_id) {
+                *at = now;
+            } else if heartbeat {
+                last_heard.insert(AgentId::from(agent_id), now);
+            }
+        }
+    }
+
+    /// Stop tracking `agent_id` (its connection came or went).
+    pub(crate) fn forget(&self, agent_id: &str) {
+        if let Ok(mut last_heard) = self.last_heard.lock() {
+            last_heard.remove(agent_id);
+        }
+    }
+
+    /// Tracked peers not heard from for longer than `window`.
+    pub(crate) fn overdue(&self, now: Instant, window: Duration) -> Vec<AgentId> {
+        let Ok(last_heard) = self.last_heard.lock() else {
+            return Vec::new();
+        };
+        let mut overdue: Vec<AgentId> = last_heard
+            .iter()
+            .filter(|(_, at)| now.saturating_duration_since(**at) > window)
+            .map(|(id, _)| id.clone())
+            .collect();
+        overdue.sort();
+        overdue
+    }
+}
+
+/// One heartbeat round: send a heartbeat on every live connection and mark
+/// tracked peers that missed `miss_threshold` intervals as degraded.
+pub(crate) async fn heartbeat_tick(
+    monitor: &HeartbeatMonitor,
+    peer_table: &PeerTable,
+    transport: &QuicTransport,
+    local_agent_id: &AgentId,
+    interval: Duration,
+    miss_threshold: u32,
+) {
+    for peer in peer_table.list().await {
+        if !peer.status.is_connected() || !transport.has_connection(&peer.agent_id).await {
+            monitor.forget(&peer.agent_id);
+            continue;
+        }
+        let envelope = Envelope::new(
+            local_agent_id.clone(),
+            peer.agent_id.clone(),
+            MessageKind::Heartbeat,
+            json!(local_software()),
+        );
+        let transport = transport.clone();
+        tokio::spawn(async move {
+            if let Err(err) = transport.send(&peer, envelope).await {
+                debug!(peer = %peer.agent_id, error = %err, "failed to send heartbeat");
+            }
+        });
+    }
+
+    let window = interval.saturating_mul(miss_threshold);
+    for agent_id in monitor.overdue(Instant::now(), window) {
+        if peer_table.mark_degraded(&agent_id).await {
+            warn!(
+                peer = %agent_id,
+                silent_secs = window.as_secs(),
+                "peer missed heartbeats; marking degraded"
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "heartbeat_tests.rs"]
+mod tests;
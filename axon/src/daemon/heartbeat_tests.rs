@@ -0,0 +1,85 @@
+use std::time::{Duration, Instant};
+
+use serde_json::json;
+
+use super::{HeartbeatMonitor, local_software, note_peer_software};
+use crate::message::{AgentId, Envelope, MessageKind};
+use crate::peer_table::PeerTable;
+
+const PEER: &str = "ed25519.a1b2c3d4e5f6a7b8c9d0e1f2a3b4c5d6";
+
+#[test]
+fn peers_are_tracked_only_after_a_heartbeat() {
+    let monitor = HeartbeatMonitor::default();
+    let start = Instant::now();
+    let window = Duration::from_secs(30);
+
+    monitor.heard(PEER, false, start);
+    assert!(
+        monitor
+            .overdue(start + Duration::from_secs(60), window)
+            .is_empty(),
+        "ordinary traffic does not opt a peer into heartbeat tracking"
+    );
+
+    monitor.heard(PEER, true, start);
+    assert!(monitor.overdue(start + window, window).is_empty());
+    assert_eq!(
+        monitor.overdue(start + Duration::from_secs(31), window),
+        vec![AgentId::from(PEER)]
+    );
+}
+
+#[test]
+fn any_traffic_refreshes_a_tracked_peer_until_forgotten() {
+    let monitor = HeartbeatMonitor::default();
+    let start = Instant::now();
+    let window = Duration::from_secs(30);
+
+    monitor.heard(PEER, true, start);
+    monitor.heard(PEER, false, start + Duration::from_secs(20));
+    assert!(
+        monitor
+            .overdue(start + Duration::from_secs(45), window)
+            .is_empty()
+    );
+
+    monitor.forget(PEER);
+    assert!(
+        monitor
+            .overdue(start + Duration::from_secs(120), window)
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn heartbeats_carry_the_daemon_version_onto_the_peer_record() {
+    let table = PeerTable::new();
+    table
+        .upsert_discovered(
+            PEER.into(),
+            "127.0.0.1:7100".parse().unwrap(),
+            "Zm9v".to_string(),
+        )
+        .await;
+    let heartbeat = |payload| {
+        Envelope::new(
+            PEER,
+            "ed25519.ffffffffffffffffffffffffffffffff",
+            MessageKind::Heartbeat,
+            payload,
+        )
+    };
+
+    note_peer_software(&table, &heartbeat(json!({}))).await;
+    assert_eq!(
+        table.get(PEER).await.unwrap().software,
+        None,
+        "pre-version heartbeat"
+    );
+
+    note_peer_software(&table, &heartbeat(json!(local_software()))).await;
+    let software = table.get(PEER).await.unwrap().software.unwrap();
+    assert_eq!(software.version, env!("CARGO_PKG_VERSION"));
+    assert!(software.features.iter().any(|f| f == "subscribe_topics"));
+}
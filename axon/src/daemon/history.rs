@@ -0,0 +1,342 @@
+use std::fs::OpenOptions;
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{Context, Result, anyhow};
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{Connection, Row, params, params_from_iter};
+use serde_json::value::RawValue;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use super::command_handler::DaemonContext;
+use super::supervisor::Supervisor;
+use crate::config::HistoryConfig;
+use crate::ipc::{DaemonReply, HistoryDirection, HistoryEntry, HistoryFilter, IpcErrorCode};
+use crate::message::{AgentId, Envelope, MessageKind, now_millis};
+
+/// Entries returned when a `history` filter sets no `limit`.
+pub(crate) const DEFAULT_QUERY_LIMIT: usize = 100;
+/// Upper bound on entries returned by one `history` command.
+pub(crate) const MAX_QUERY_LIMIT: usize = 1000;
+const PRUNE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+const SCHEMA: &str = "
+PRAGMA journal_mode = WAL;
+CREATE TABLE IF NOT EXISTS envelopes (
+    seq INTEGER PRIMARY KEY AUTOINCREMENT,
+    ts_ms INTEGER NOT NULL,
+    direction TEXT NOT NULL,
+    peer TEXT NOT NULL,
+    id TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    ref_id TEXT,
+    from_id TEXT,
+    to_id TEXT,
+    payload TEXT NOT NULL,
+    topic TEXT,
+    thread_id TEXT,
+    headers TEXT
+);
+CREATE INDEX IF NOT EXISTS envelopes_ts ON envelopes (ts_ms);
+CREATE INDEX IF NOT EXISTS envelopes_peer ON envelopes (peer, seq);
+CREATE INDEX IF NOT EXISTS envelopes_id ON envelopes (id);
+CREATE INDEX IF NOT EXISTS envelopes_ref ON envelopes (ref_id);
+";
+
+/// Columns added after the original schema, as `(name, type)`. Databases
+/// created by older daemons gain them on open.
+const ADDED_COLUMNS: &[(&str, &str)] = &[
+    ("topic", "TEXT"),
+    ("thread_id", "TEXT"),
+    ("headers", "TEXT"),
+];
+
+/// Indexes on [`ADDED_COLUMNS`], created once the columns exist.
+const ADDED_INDEXES: &str = "
+CREATE INDEX IF NOT EXISTS envelopes_thread ON envelopes (thread_id, seq);
+";
+
+/// SQLite-backed record of envelopes sent and received by this daemon.
+///
+/// Statements run on the blocking pool; the connection is shared behind a
+/// mutex, so writes and queries are serialized.
+#[derive(Clone)]
+pub(crate) struct History {
+    conn: Arc<Mutex<Connection>>,
+    retention: Option<Duration>,
+}
+
+impl History {
+    /// Open (or create, mode 0600) the history database and apply retention.
+    pub(crate) async fn open(path: &Path, retention: Option<Duration>) -> Result<Self> {
+        let path = path.to_path_buf();
+        let conn = tokio::task::spawn_blocking(move || -> Result<Connection> {
+            OpenOptions::new()
+                .create(true)
+                .append(true)
+                .mode(0o600)
+                .open(&path)
+                .with_context(|| format!("failed to create {}", path.display()))?;
+            let conn = Connection::open(&path)
+                .with_context(|| format!("failed to open {}", path.display()))?;
+            conn.execute_batch(SCHEMA)
+                .and_then(|()| add_missing_columns(&conn))
+                .with_context(|| format!("failed to initialize {}", path.display()))?;
+            Ok(conn)
+        })
+        .await??;
+
+        let history = Self {
+            conn: Arc::new(Mutex::new(conn)),
+            retention,
+        };
+        history.prune().await?;
+        Ok(history)
+    }
+
+    async fn with_conn<T, F>(&self, f: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Connection) -> Result<T> + Send + 'static,
+    {
+        let conn = self.conn.clone();
+        tokio::task::spawn_blocking(move || {
+            let conn = conn
+                .lock()
+                .map_err(|_| anyhow!("history database lock poisoned"))?;
+            f(&conn)
+        })
+        .await?
+    }
+
+    /// Record an envelope. Failures are logged, never returned: history is
+    /// diagnostic and must not affect delivery.
+    pub(crate) async fn record(&self, direction: HistoryDirection, envelope: &Envelope) {
+        let peer = match direction {
+            HistoryDirection::In => envelope.from.as_ref(),
+            HistoryDirection::Out => envelope.to.as_ref(),
+        }
+        .map(|id| id.to_string())
+        .unwrap_or_default();
+        let row = (
+            now_millis() as i64,
+            direction.as_str(),
+            peer,
+            envelope.id.to_string(),
+            envelope.kind.to_string(),
+            envelope.ref_id.map(|id| id.to_string()),
+            envelope.from.as_ref().map(|id| id.to_string()),
+            envelope.to.as_ref().map(|id| id.to_string()),
+            envelope.payload.get().to_string(),
+            envelope.topic.clone(),
+            envelope.thread_id.clone(),
+            (!envelope.headers.is_empty())
+                .then(|| serde_json::Value::Object(envelope.headers.clone()).to_string()),
+        );
+        let msg_id = envelope.id;
+        let result = self
+            .with_conn(move |conn| {
+                conn.execute(
+                    "INSERT INTO envelopes
+                     (ts_ms, direction, peer, id, kind, ref_id, from_id, to_id, payload, topic,
+                      thread_id, headers)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+                    params![
+                        row.0, row.1, row.2, row.3, row.4, row.5, row.6, row.7, row.8, row.9,
+                        row.10, row.11
+                    ],
+                )?;
+                Ok(())
+            })
+            .await;
+        if let Err(err) = result {
+            warn!(msg_id = %msg_id, error = %err, "failed to record envelope in history");
+        }
+    }
+
+    /// Newest `filter.limit` matching entries, returned oldest first.
+    pub(crate) async fn query(&self, filter: HistoryFilter) -> Result<Vec<HistoryEntry>> {
+        self.with_conn(move |conn| query_entries(conn, &filter))
+            .await
+    }
+
+    /// Delete entries older than the retention window. Returns rows removed.
+    pub(crate) async fn prune(&self) -> Result<usize> {
+        let Some(retention) = self.retention else {
+            return Ok(0);
+        };
+        let cutoff = now_millis().saturating_sub(retention.as_millis() as u64) as i64;
+        self.with_conn(move |conn| {
+            Ok(conn.execute("DELETE FROM envelopes WHERE ts_ms < ?1", params![cutoff])?)
+        })
+        .await
+    }
+}
+
+fn add_missing_columns(conn: &Connection) -> rusqlite::Result<()> {
+    let mut stmt = conn.prepare("PRAGMA table_info(envelopes)")?;
+    let existing = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    for (name, ty) in ADDED_COLUMNS {
+        if !existing.iter().any(|col| col == name) {
+            conn.execute_batch(&format!("ALTER TABLE envelopes ADD COLUMN {name} {ty}"))?;
+        }
+    }
+    conn.execute_batch(ADDED_INDEXES)
+}
+
+fn query_entries(conn: &Connection, filter: &HistoryFilter) -> Result<Vec<HistoryEntry>> {
+    let mut sql = String::from(
+        "SELECT seq, ts_ms, direction, peer, id, kind, ref_id, from_id, to_id, payload, topic,
+                thread_id, headers
+         FROM envelopes WHERE 1 = 1",
+    );
+    let mut args: Vec<SqlValue> = Vec::new();
+    if let Some(ref peer) = filter.peer {
+        sql.push_str(" AND peer = ?");
+        args.push(SqlValue::Text(peer.clone()));
+    }
+    if let Some(kind) = filter.kind {
+        sql.push_str(" AND kind = ?");
+        args.push(SqlValue::Text(kind.to_string()));
+    }
+    if let Some(direction) = filter.direction {
+        sql.push_str(" AND direction = ?");
+        args.push(SqlValue::Text(direction.as_str().to_string()));
+    }
+    if let Some(msg_id) = filter.msg_id {
+        sql.push_str(" AND (id = ? OR ref_id = ?)");
+        args.push(SqlValue::Text(msg_id.to_string()));
+        args.push(SqlValue::Text(msg_id.to_string()));
+    }
+    if let Some(ref thread_id) = filter.thread_id {
+        sql.push_str(" AND thread_id = ?");
+        args.push(SqlValue::Text(thread_id.clone()));
+    }
+    if let Some(since) = filter.since_ms {
+        sql.push_str(" AND ts_ms >= ?");
+        args.push(SqlValue::Integer(since as i64));
+    }
+    if let Some(until) = filter.until_ms {
+        sql.push_str(" AND ts_ms < ?");
+        args.push(SqlValue::Integer(until as i64));
+    }
+    let limit = filter
+        .limit
+        .unwrap_or(DEFAULT_QUERY_LIMIT)
+        .min(MAX_QUERY_LIMIT);
+    sql.push_str(" ORDER BY seq DESC LIMIT ?");
+    args.push(SqlValue::Integer(limit as i64));
+
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(params_from_iter(args), |row| Ok(read_entry(row)))?;
+    let mut entries = Vec::new();
+    for row in rows {
+        match row? {
+            Ok(entry) => entries.push(entry),
+            Err(err) => debug!(error = %err, "skipping unreadable history row"),
+        }
+    }
+    entries.reverse();
+    Ok(entries)
+}
+
+fn read_entry(row: &Row<'_>) -> Result<HistoryEntry> {
+    let direction = match row.get::<_, String>(2)?.as_str() {
+        "in" => HistoryDirection::In,
+        "out" => HistoryDirection::Out,
+        other => anyhow::bail!("unknown direction '{other}'"),
+    };
+    let kind: MessageKind = serde_json::from_value(row.get::<_, String>(5)?.into())?;
+    let ref_id = row
+        .get::<_, Option<String>>(6)?
+        .map(|id| Uuid::parse_str(&id))
+        .transpose()?;
+    Ok(HistoryEntry {
+        seq: row.get::<_, i64>(0)? as u64,
+        ts_ms: row.get::<_, i64>(1)? as u64,
+        direction,
+        peer: row.get(3)?,
+        envelope: Envelope {
+            id: Uuid::parse_str(&row.get::<_, String>(4)?)?,
+            kind,
+            ref_id,
+            payload: RawValue::from_string(row.get(9)?)?,
+            topic: row.get(10)?,
+            thread_id: row.get(11)?,
+            headers: row
+                .get::<_, Option<String>>(12)?
+                .map(|headers| serde_json::from_str(&headers))
+                .transpose()?
+                .unwrap_or_default(),
+            from: row.get::<_, Option<String>>(7)?.map(AgentId::from),
+            to: row.get::<_, Option<String>>(8)?.map(AgentId::from),
+        },
+    })
+}
+
+/// Open the history database and start its pruner when `history.enabled`.
+pub(crate) async fn start_history(
+    path: &Path,
+    config: &HistoryConfig,
+    supervisor: &Arc<Supervisor>,
+    cancel: &CancellationToken,
+) -> Result<Option<History>> {
+    if !config.enabled() {
+        return Ok(None);
+    }
+    let history = History::open(path, config.retention()).await?;
+    let (pruned, prune_cancel) = (history.clone(), cancel.clone());
+    supervisor.spawn("history_pruner", cancel.clone(), move || {
+        prune_history(pruned.clone(), prune_cancel.clone())
+    });
+    info!(path = %path.display(), "recording message history");
+    Ok(Some(history))
+}
+
+/// Periodically apply the retention window until cancelled.
+async fn prune_history(history: History, cancel: CancellationToken) -> Result<()> {
+    let mut interval = tokio::time::interval(PRUNE_INTERVAL);
+    interval.tick().await;
+    loop {
+        tokio::select! {
+            _ = cancel.cancelled() => return Ok(()),
+            _ = interval.tick() => match history.prune().await {
+                Ok(0) => {}
+                Ok(removed) => debug!(removed, "pruned message history"),
+                Err(err) => warn!(error = %err, "failed to prune message history"),
+            },
+        }
+    }
+}
+
+/// Handle IPC `history`: `history_disabled` unless `history.enabled`.
+pub(crate) async fn history_reply(
+    ctx: &DaemonContext<'_>,
+    filter: HistoryFilter,
+    req_id: Option<String>,
+) -> DaemonReply {
+    match ctx.history {
+        None => DaemonReply::error(IpcErrorCode::HistoryDisabled, req_id),
+        Some(history) => match history.query(filter).await {
+            Ok(entries) => DaemonReply::History {
+                ok: true,
+                entries,
+                req_id,
+            },
+            Err(err) => {
+                warn!(error = %err, "history query failed");
+                DaemonReply::error(IpcErrorCode::InternalError, req_id)
+            }
+        },
+    }
+}
+
+#[cfg(test)]
+#[path = "history_tests.rs"]
+mod tests;
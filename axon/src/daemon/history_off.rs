@@ -0,0 +1,44 @@
+//! Message history in a build without the `history` feature. There is never
+//! a [`History`], so `history.enabled` stops the daemon at startup and IPC
+//! `history` always answers `history_disabled`.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::{Result, bail};
+use tokio_util::sync::CancellationToken;
+
+use super::command_handler::DaemonContext;
+use super::supervisor::Supervisor;
+use crate::config::HistoryConfig;
+use crate::ipc::{DaemonReply, HistoryDirection, HistoryFilter, IpcErrorCode};
+use crate::message::Envelope;
+
+#[derive(Clone)]
+pub(crate) enum History {}
+
+impl History {
+    pub(crate) async fn record(&self, _direction: HistoryDirection, _envelope: &Envelope) {
+        match *self {}
+    }
+}
+
+pub(crate) async fn start_history(
+    _path: &Path,
+    config: &HistoryConfig,
+    _supervisor: &Arc<Supervisor>,
+    _cancel: &CancellationToken,
+) -> Result<Option<History>> {
+    if config.enabled() {
+        bail!("history.enabled requires axon built with the `history` feature");
+    }
+    Ok(None)
+}
+
+pub(crate) async fn history_reply(
+    _ctx: &DaemonContext<'_>,
+    _filter: HistoryFilter,
+    req_id: Option<String>,
+) -> DaemonReply {
+    DaemonReply::error(IpcErrorCode::HistoryDisabled, req_id)
+}
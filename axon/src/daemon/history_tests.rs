@@ -0,0 +1,189 @@
+use super::*;
+use serde_json::json;
+use std::os::unix::fs::PermissionsExt;
+
+const LOCAL: &str = "ed25519.bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb";
+const PEER_A: &str = "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+const PEER_C: &str = "ed25519.cccccccccccccccccccccccccccccccc";
+
+fn envelope(from: &str, to: &str, kind: MessageKind) -> Envelope {
+    Envelope::new(from.to_string(), to.to_string(), kind, json!({"n": 1}))
+}
+
+#[tokio::test]
+async fn records_and_filters_envelopes() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("history.sqlite3");
+    let history = History::open(&path, None).await.unwrap();
+    assert_eq!(
+        std::fs::metadata(&path).unwrap().permissions().mode() & 0o777,
+        0o600
+    );
+
+    let request = envelope(LOCAL, PEER_A, MessageKind::Request);
+    let response = Envelope::response_to(
+        &request,
+        PEER_A.to_string(),
+        MessageKind::Response,
+        json!({"ok": 1}),
+    );
+    let other = envelope(PEER_C, LOCAL, MessageKind::Message);
+    history.record(HistoryDirection::Out, &request).await;
+    history.record(HistoryDirection::In, &response).await;
+    history.record(HistoryDirection::In, &other).await;
+
+    let all = history.query(HistoryFilter::default()).await.unwrap();
+    assert_eq!(all.len(), 3);
+    assert_eq!(all[0].envelope, request, "oldest first, round-trips intact");
+    assert_eq!(all[0].direction, HistoryDirection::Out);
+    assert_eq!(all[0].peer, PEER_A);
+
+    let thread = history
+        .query(HistoryFilter {
+            msg_id: Some(request.id),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    let ids: Vec<_> = thread.iter().map(|entry| entry.envelope.id).collect();
+    assert_eq!(ids, vec![request.id, response.id]);
+
+    let from_c = history
+        .query(HistoryFilter {
+            peer: Some(PEER_C.to_string()),
+            direction: Some(HistoryDirection::In),
+            kind: Some(MessageKind::Message),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(from_c.len(), 1);
+    assert_eq!(from_c[0].envelope.id, other.id);
+
+    let newest = history
+        .query(HistoryFilter {
+            limit: Some(1),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(newest.len(), 1);
+    assert_eq!(newest[0].envelope.id, other.id);
+
+    let future = history
+        .query(HistoryFilter {
+            since_ms: Some(now_millis() + 60_000),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert!(future.is_empty());
+}
+
+#[tokio::test]
+async fn entries_survive_reopen() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("history.sqlite3");
+    let mut sent = envelope(LOCAL, PEER_A, MessageKind::Message);
+    sent.headers.insert("trace_id".to_string(), json!("abc123"));
+    {
+        let history = History::open(&path, None).await.unwrap();
+        history.record(HistoryDirection::Out, &sent).await;
+    }
+
+    let history = History::open(&path, None).await.unwrap();
+    let entries = history.query(HistoryFilter::default()).await.unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].envelope.id, sent.id);
+    assert_eq!(entries[0].envelope.headers, sent.headers);
+}
+
+#[tokio::test]
+async fn topic_column_is_added_to_older_databases() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("history.sqlite3");
+    Connection::open(&path)
+        .unwrap()
+        .execute_batch(
+            "CREATE TABLE envelopes (
+                seq INTEGER PRIMARY KEY AUTOINCREMENT, ts_ms INTEGER NOT NULL,
+                direction TEXT NOT NULL, peer TEXT NOT NULL, id TEXT NOT NULL,
+                kind TEXT NOT NULL, ref_id TEXT, from_id TEXT, to_id TEXT,
+                payload TEXT NOT NULL
+            );",
+        )
+        .unwrap();
+
+    let history = History::open(&path, None).await.unwrap();
+    let mut published = envelope(LOCAL, PEER_A, MessageKind::Message);
+    published.topic = Some("builds.main".to_string());
+    history.record(HistoryDirection::Out, &published).await;
+
+    let entries = history.query(HistoryFilter::default()).await.unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].envelope.topic.as_deref(), Some("builds.main"));
+}
+
+#[tokio::test]
+async fn filters_by_thread_id() {
+    let dir = tempfile::tempdir().unwrap();
+    let history = History::open(&dir.path().join("history.sqlite3"), None)
+        .await
+        .unwrap();
+    let mut request = envelope(LOCAL, PEER_A, MessageKind::Request);
+    request.thread_id = Some("t-1".to_string());
+    let reply = Envelope::response_to(&request, PEER_A, MessageKind::Response, json!({}));
+    history.record(HistoryDirection::Out, &request).await;
+    history
+        .record(
+            HistoryDirection::Out,
+            &envelope(LOCAL, PEER_A, MessageKind::Message),
+        )
+        .await;
+    history.record(HistoryDirection::In, &reply).await;
+
+    let entries = history
+        .query(HistoryFilter {
+            thread_id: Some("t-1".to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    let ids: Vec<_> = entries.iter().map(|entry| entry.envelope.id).collect();
+    assert_eq!(ids, vec![request.id, reply.id]);
+    assert_eq!(entries[1].envelope.thread_id.as_deref(), Some("t-1"));
+}
+
+#[tokio::test]
+async fn prune_removes_entries_outside_retention() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("history.sqlite3");
+    let history = History::open(&path, Some(Duration::from_secs(3600)))
+        .await
+        .unwrap();
+    history
+        .record(
+            HistoryDirection::Out,
+            &envelope(LOCAL, PEER_A, MessageKind::Message),
+        )
+        .await;
+    history
+        .with_conn(|conn| {
+            conn.execute("UPDATE envelopes SET ts_ms = ts_ms - 7200000", [])?;
+            Ok(())
+        })
+        .await
+        .unwrap();
+    history
+        .record(
+            HistoryDirection::Out,
+            &envelope(LOCAL, PEER_A, MessageKind::Message),
+        )
+        .await;
+
+    assert_eq!(history.prune().await.unwrap(), 1);
+    assert_eq!(
+        history.query(HistoryFilter::default()).await.unwrap().len(),
+        1
+    );
+}
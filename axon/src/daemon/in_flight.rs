@@ -0,0 +1,43 @@
+use std::future::Future;
+use std::pin::Pin;
+
+use anyhow::Result;
+use futures_util::StreamExt;
+use futures_util::stream::FuturesUnordered;
+
+/// Most IPC `send` commands in progress at once; past it the event loop
+/// stops reading commands until one finishes.
+pub(crate) const MAX_IN_FLIGHT_SENDS: usize = 256;
+
+type SendFuture<'a> = Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>>;
+
+/// IPC `send` commands in progress. Each can wait on the network for up to
+/// its timeout, so the event loop polls them alongside its other work
+/// instead of awaiting them one at a time.
+#[derive(Default)]
+pub(crate) struct InFlightSends<'a> {
+    sends: FuturesUnordered<SendFuture<'a>>,
+}
+
+impl<'a> InFlightSends<'a> {
+    pub(crate) fn has_room(&self) -> bool {
+        self.sends.len() < MAX_IN_FLIGHT_SENDS
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.sends.is_empty()
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.sends.len()
+    }
+
+    pub(crate) fn push(&mut self, send: impl Future<Output = Result<()>> + Send + 'a) {
+        self.sends.push(Box::pin(send));
+    }
+
+    /// The outcome of the next send to finish, or `None` when there are none.
+    pub(crate) async fn next(&mut self) -> Option<Result<()>> {
+        self.sends.next().await
+    }
+}
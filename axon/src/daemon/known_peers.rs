@@ -0,0 +1,76 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use tokio::sync::Mutex;
+
+use super::health::HealthState;
+use crate::config::{KnownPeer, save_known_peers};
+use crate::peer_table::PeerTable;
+
+/// `known_peers.json` writer that skips saves when nothing but
+/// `last_seen_unix_ms` changed, so an idle daemon leaves the file alone.
+/// Writes go through `save_known_peers`, which swaps the file atomically.
+pub(crate) struct KnownPeersStore {
+    path: PathBuf,
+    /// The peers as last loaded or written, in agent ID order.
+    written: Mutex<Vec<KnownPeer>>,
+}
+
+impl KnownPeersStore {
+    pub(crate) fn new(path: PathBuf, mut loaded: Vec<KnownPeer>) -> Self {
+        loaded.sort_by(|a, b| a.agent_id.cmp(&b.agent_id));
+        Self {
+            path,
+            written: Mutex::new(loaded),
+        }
+    }
+
+    /// Write `peers` (in agent ID order) if they differ from the last write.
+    /// Returns whether the file was written.
+    pub(crate) async fn save(&self, peers: Vec<KnownPeer>) -> Result<bool> {
+        let mut written = self.written.lock().await;
+        if same_peers(&written, &peers) {
+            return Ok(false);
+        }
+        save_known_peers(&self.path, &peers).await?;
+        *written = peers;
+        Ok(true)
+    }
+
+    /// Write `peers` unconditionally, refreshing every `last_seen_unix_ms`.
+    pub(crate) async fn save_now(&self, peers: Vec<KnownPeer>) -> Result<()> {
+        let mut written = self.written.lock().await;
+        save_known_peers(&self.path, &peers).await?;
+        *written = peers;
+        Ok(())
+    }
+
+    /// Save the peer table if the peer set changed, recording the outcome of
+    /// any write for `health`.
+    pub(crate) async fn persist(&self, peer_table: &PeerTable, health: &HealthState) -> Result<()> {
+        match self.save(peer_table.to_known_peers().await).await {
+            Ok(false) => Ok(()),
+            result => {
+                let result = result.map(drop);
+                health.record_persist(&result);
+                result
+            }
+        }
+    }
+}
+
+/// Equal apart from `last_seen_unix_ms`, which moves on every save.
+fn same_peers(a: &[KnownPeer], b: &[KnownPeer]) -> bool {
+    a.len() == b.len()
+        && a.iter().zip(b).all(|(a, b)| {
+            a.agent_id == b.agent_id
+                && a.addr == b.addr
+                && a.pubkey == b.pubkey
+                && a.first_seen_unix_ms == b.first_seen_unix_ms
+                && a.source == b.source
+        })
+}
+
+#[cfg(test)]
+#[path = "known_peers_tests.rs"]
+mod tests;
@@ -0,0 +1,106 @@
+use super::*;
+use crate::config::{KnownPeerSource, load_known_peers};
+
+fn peer(id: &str, last_seen_unix_ms: u64) -> KnownPeer {
+    KnownPeer {
+        agent_id: id.into(),
+        addr: "127.0.0.1:7100".parse().unwrap(),
+        pubkey: "Zm9v".to_string(),
+        last_seen_unix_ms,
+        first_seen_unix_ms: Some(100),
+        source: KnownPeerSource::Discovered,
+    }
+}
+
+#[tokio::test]
+async fn unchanged_peers_are_not_rewritten() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("known_peers.json");
+    let store = KnownPeersStore::new(path.clone(), Vec::new());
+
+    assert!(!store.save(Vec::new()).await.unwrap());
+    assert!(!path.exists());
+
+    assert!(store.save(vec![peer("ed25519.aaaa", 1)]).await.unwrap());
+    std::fs::remove_file(&path).unwrap();
+    // Only last_seen moved: the file is left alone.
+    assert!(!store.save(vec![peer("ed25519.aaaa", 2)]).await.unwrap());
+    assert!(!path.exists());
+}
+
+#[tokio::test]
+async fn loaded_peers_count_as_written() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("known_peers.json");
+    let store = KnownPeersStore::new(
+        path.clone(),
+        vec![peer("ed25519.bbbb", 1), peer("ed25519.aaaa", 1)],
+    );
+
+    let sorted = vec![peer("ed25519.aaaa", 5), peer("ed25519.bbbb", 5)];
+    assert!(!store.save(sorted).await.unwrap());
+    assert!(!path.exists());
+}
+
+#[tokio::test]
+async fn changed_peers_are_written() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("known_peers.json");
+    let store = KnownPeersStore::new(path.clone(), vec![peer("ed25519.aaaa", 1)]);
+
+    let mut moved = peer("ed25519.aaaa", 1);
+    moved.addr = "127.0.0.1:7200".parse().unwrap();
+    assert!(store.save(vec![moved.clone()]).await.unwrap());
+    assert_eq!(load_known_peers(&path).await.unwrap(), vec![moved]);
+
+    assert!(store.save(Vec::new()).await.unwrap());
+    assert!(load_known_peers(&path).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn failed_write_is_retried() {
+    let dir = tempfile::tempdir().unwrap();
+    // A directory where the file should be makes the rename fail.
+    let path = dir.path().join("known_peers.json");
+    std::fs::create_dir(&path).unwrap();
+    let store = KnownPeersStore::new(path.clone(), Vec::new());
+    let peers = vec![peer("ed25519.aaaa", 1)];
+
+    assert!(store.save(peers.clone()).await.is_err());
+    std::fs::remove_dir(&path).unwrap();
+    assert!(store.save(peers.clone()).await.unwrap());
+    assert_eq!(load_known_peers(&path).await.unwrap(), peers);
+}
+
+#[tokio::test]
+async fn save_now_writes_even_when_unchanged() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("known_peers.json");
+    let store = KnownPeersStore::new(path.clone(), vec![peer("ed25519.aaaa", 1)]);
+
+    store.save_now(vec![peer("ed25519.aaaa", 9)]).await.unwrap();
+    let loaded = load_known_peers(&path).await.unwrap();
+    assert_eq!(loaded[0].last_seen_unix_ms, 9);
+}
+
+#[tokio::test]
+async fn interrupted_write_leaves_previous_file_intact() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("known_peers.json");
+    let store = KnownPeersStore::new(path.clone(), Vec::new());
+    let peers = vec![peer("ed25519.aaaa", 1)];
+    store.save(peers.clone()).await.unwrap();
+
+    // A crash between writing the temporary file and the rename leaves a
+    // torn `.tmp` behind; the real file still holds the previous write.
+    let tmp = dir.path().join("known_peers.json.tmp");
+    std::fs::write(&tmp, b"[{\"agent_id\":").unwrap();
+    assert_eq!(load_known_peers(&path).await.unwrap(), peers);
+
+    // The next save replaces the leftover and removes it by renaming.
+    let mut more = peers.clone();
+    more.push(peer("ed25519.bbbb", 1));
+    assert!(store.save(more.clone()).await.unwrap());
+    assert_eq!(load_known_peers(&path).await.unwrap(), more);
+    assert!(!tmp.exists());
+}
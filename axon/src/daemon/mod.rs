@@ -1,17 +1,78 @@
+mod audit;
+mod audit_verify;
+mod await_reply;
+mod blocklist;
 pub(crate) mod command_handler;
+mod event_log;
+mod exec_handler;
+mod forwarders;
+mod handler_cache;
+mod handlers;
+mod health;
+mod heartbeat;
+#[cfg(feature = "history")]
+mod history;
+#[cfg(not(feature = "history"))]
+#[path = "history_off.rs"]
+mod history;
+mod in_flight;
+mod known_peers;
 mod lockfile;
+mod options;
 mod peer_events;
+mod peer_tags;
+mod peers;
+mod pins;
+mod quic;
 mod reconnect;
-
-use command_handler::{Counters, DaemonContext, handle_command};
+mod reload;
+mod revocation;
+#[cfg(feature = "script")]
+mod script_handler;
+mod send;
+mod shutdown;
+mod signals;
+mod stats;
+mod supervisor;
+mod systemd;
+mod threads;
+mod topics;
+mod transforms;
+mod webhooks;
+
+use audit::AuditLog;
+pub use audit_verify::{AuditVerification, verify_audit_log};
+use command_handler::{Counters, DaemonContext, handle_command, handle_send_command};
+use event_log::EventLog;
+use forwarders::{
+    spawn_connection_event_forwarder, spawn_inbound_forwarder, spawn_pair_request_forwarder,
+};
+use health::HealthState;
+use heartbeat::{HeartbeatMonitor, heartbeat_tick};
+use history::start_history;
+use in_flight::InFlightSends;
+use known_peers::KnownPeersStore;
 use lockfile::DaemonLock;
-use peer_events::handle_peer_event;
-use reconnect::{ReconnectState, attempt_reconnects, handle_reconnect_outcome, reconnect_channel};
+pub use options::DaemonOptions;
+use peer_events::{StaticDiscovery, on_discovery_event, remove_stale_peers, spawn_mdns_discovery};
+use peer_tags::PeerTags;
+use quic::open_transport;
+use reconnect::{
+    ReconnectState, attempt_reconnects, handle_reconnect_outcome, prewarm_connections,
+    reconnect_channel,
+};
+use reload::{ReloadTrigger, handle_reload, stale_policy};
+use shutdown::shutdown;
+use signals::{spawn_reload_signal_task, spawn_shutdown_signal_task};
+use stats::{load_lifetime_stats, save_lifetime_stats};
+use supervisor::Supervisor;
+use threads::ThreadIndex;
+use topics::{TopicTable, spawn_topic_announcer};
+use transforms::Transforms;
+use webhooks::Webhooks;
 
 use std::collections::HashMap;
-use std::path::PathBuf;
 use std::sync::Arc;
-use std::sync::atomic::Ordering;
 use std::time::{Duration, Instant};
 
 // Hardcoded defaults (previously configurable; see Phase 9).
@@ -23,44 +84,30 @@ const MAX_IPC_CLIENTS: usize = 64;
 const MAX_CLIENT_QUEUE: usize = 1024;
 const RECONNECT_MAX_BACKOFF: Duration = Duration::from_secs(30);
 
-use anyhow::{Context, Result};
+use anyhow::Result;
 use tokio::sync::mpsc;
 use tokio_util::sync::CancellationToken;
-use tracing::{debug, error, info, trace, warn};
+use tracing::{error, info, warn};
 
-use crate::config::{AxonPaths, Config, load_known_peers, save_known_peers};
-use crate::discovery::{run_mdns_discovery, run_static_discovery};
+use crate::config::{
+    Config, blocked_keys, load_blocklist, load_known_peers, load_revocations, revoked_pubkeys,
+};
 use crate::identity::Identity;
-use crate::ipc::IpcServer;
+use crate::ipc::{IpcCommand, IpcServer};
 use crate::message::AgentId;
 use crate::peer_table::PeerTable;
-use crate::transport::QuicTransport;
-
-// ---------------------------------------------------------------------------
-// Configuration
-// ---------------------------------------------------------------------------
-
-#[derive(Debug, Clone, Default)]
-pub struct DaemonOptions {
-    pub port: Option<u16>,
-    pub disable_mdns: bool,
-    pub axon_root: Option<PathBuf>,
-    pub cancel: Option<CancellationToken>,
-}
 
 // ---------------------------------------------------------------------------
 // Daemon entry point
 // ---------------------------------------------------------------------------
 
 pub async fn run_daemon(opts: DaemonOptions) -> Result<()> {
-    let paths = match opts.axon_root {
-        Some(ref root) => AxonPaths::from_root(root.clone()),
-        None => AxonPaths::discover()?,
-    };
+    let paths = opts.paths()?;
     paths.ensure_root_exists()?;
     let mut daemon_lock = DaemonLock::acquire(&paths.root)?;
+    let activated = systemd::take_activated_sockets();
 
-    let config = Config::load(&paths.config).await?;
+    let mut config = Config::load_for(&paths).await?;
     let port = config.effective_port(opts.port);
 
     let identity = Identity::load_or_generate(&paths)?;
@@ -80,36 +127,106 @@ pub async fn run_daemon(opts: DaemonOptions) -> Result<()> {
 
     // --- Cancellation token for structured shutdown ---
     let cancel = opts.cancel.unwrap_or_default();
-    spawn_shutdown_signal_task(cancel.clone());
+    // Forwarders and reply waiters outlive `cancel` so replies arriving
+    // during the shutdown drain still reach IPC clients.
+    let forwarding = CancellationToken::new();
+    let _forwarding_guard = forwarding.clone().drop_guard();
+    if opts.handle_signals {
+        spawn_shutdown_signal_task(cancel.clone());
+    }
+
+    // --- Config reload requests (SIGHUP and IPC `reload`) ---
+    let (reload_tx, mut reload_rx) = mpsc::channel::<ReloadTrigger>(8);
+    if opts.handle_signals {
+        spawn_reload_signal_task(reload_tx.clone(), cancel.clone());
+    }
 
     // --- Peer table ---
+    // Revocations and blocks load first so refused keys never enter the table.
+    let revoked = revoked_pubkeys(&load_revocations(&paths.revocations).await?);
     let peer_table = PeerTable::new();
+    peer_table.set_revoked(revoked.clone()).await;
+    let blocked = blocked_keys(&load_blocklist(&paths.blocklist).await?);
+    peer_table.set_blocked(blocked.clone()).await;
+    peer_table.set_stale_policy(stale_policy(&config.staleness));
     for peer in &config.peers {
         peer_table.upsert_static(peer).await;
     }
-    for peer in load_known_peers(&paths.known_peers).await? {
-        peer_table.upsert_cached(&peer).await;
+    let cached_peers = load_known_peers(&paths.known_peers).await?;
+    for peer in &cached_peers {
+        peer_table.upsert_cached(peer).await;
     }
+    let known_peers = KnownPeersStore::new(paths.known_peers.clone(), cached_peers);
 
     // --- Counters ---
     let counters = Arc::new(Counters::default());
+    let heartbeats = Arc::new(HeartbeatMonitor::default());
+    let topics = Arc::new(TopicTable::default());
+    let threads = Arc::new(ThreadIndex::default());
+    let lifetime_baseline = load_lifetime_stats(&paths.stats).await;
+    if let Err(err) = save_lifetime_stats(&paths.stats, &lifetime_baseline).await {
+        warn!(error = %err, "failed to persist lifetime stats at startup");
+    }
+    let health = Arc::new(HealthState::new(!opts.disable_mdns));
+    let supervisor = Arc::new(Supervisor::default());
+    let peer_tags = PeerTags::default();
+    peer_tags.set(&config.tags);
+
+    // --- Message history ---
+    let history = start_history(&paths.history, &config.history, &supervisor, &cancel).await?;
+
+    // --- Audit log ---
+    let audit = if config.audit.enabled() {
+        let audit = AuditLog::open(&paths.audit, &identity, &config.audit).await?;
+        info!(path = %paths.audit.display(), "writing audit log");
+        Some(audit)
+    } else {
+        None
+    };
+
+    // --- Event log ---
+    let events = if config.event_log.enabled() {
+        let events =
+            EventLog::open(&paths.events, local_agent_id.as_str(), &config.event_log).await?;
+        info!(path = %paths.events.display(), "writing event log");
+        Some(events)
+    } else {
+        None
+    };
+
+    // --- Inbound request handler ---
+    let response_handler = handlers::response_handler(
+        &config.handler,
+        local_agent_id.as_str(),
+        opts.response_handler,
+    )?;
+
+    // --- Webhooks ---
+    let webhooks = Webhooks::new(&config.webhooks);
+    let transforms = Arc::new(Transforms::default());
+    transforms.set(&config.transforms);
 
     // --- Transport ---
-    let bind_addr = format!("0.0.0.0:{port}")
-        .parse()
-        .context("invalid bind address")?;
-    let transport = QuicTransport::bind_cancellable(
-        bind_addr,
+    let transport = open_transport(
+        activated.quic,
+        port,
         &identity,
         cancel.clone(),
-        MAX_CONNECTIONS,
-        KEEPALIVE,
-        IDLE_TIMEOUT,
-        None,
-        INBOUND_READ_TIMEOUT,
+        response_handler,
         peer_table.pubkey_map(),
+        &config,
     )
     .await?;
+    transport.set_revoked_keys(revoked);
+    transport.set_blocked_keys(blocked);
+    let local_port = transport.local_addr()?.port();
+    if local_port != port {
+        warn!(
+            configured = port,
+            bound = local_port,
+            "socket-activated QUIC port differs from configured port"
+        );
+    }
 
     // --- IPC ---
     let start = Instant::now();
@@ -120,131 +237,64 @@ pub async fn run_daemon(opts: DaemonOptions) -> Result<()> {
         version: env!("CARGO_PKG_VERSION").to_string(),
         max_client_queue: MAX_CLIENT_QUEUE,
         uptime_secs: Arc::new(move || start.elapsed().as_secs()),
+        idle_timeout: config.ipc.idle_timeout(),
+        max_lifetime: config.ipc.max_lifetime(),
     };
 
-    let (ipc, mut cmd_rx) =
-        IpcServer::bind(paths.socket.clone(), MAX_IPC_CLIENTS, ipc_config).await?;
-
-    // --- Inbound message forwarder (transport → IPC clients) ---
-    let mut inbound_rx = transport.subscribe_inbound();
-    let ipc_for_inbound = ipc.clone();
-    let counters_for_inbound = counters.clone();
-    let peer_table_for_inbound = peer_table.clone();
-    let cancel_for_inbound = cancel.clone();
-    tokio::spawn(async move {
-        loop {
-            tokio::select! {
-                _ = cancel_for_inbound.cancelled() => break,
-                msg = inbound_rx.recv() => {
-                    match msg {
-                        Ok(envelope) => {
-                            counters_for_inbound.received.fetch_add(1, Ordering::Relaxed);
-                            let from_id = envelope.from.as_deref().unwrap_or("unknown");
-                            let payload_bytes = envelope.payload.get().len();
-                            info!(
-                                msg_id = %envelope.id,
-                                from = from_id,
-                                kind = %envelope.kind,
-                                payload_bytes,
-                                "message received"
-                            );
-                            {
-                                let raw = envelope.payload.get();
-                                let truncated = if raw.len() > 256 {
-                                    format!("{}…", &raw[..256])
-                                } else {
-                                    raw.to_string()
-                                };
-                                debug!(
-                                    msg_id = %envelope.id,
-                                    from = from_id,
-                                    kind = %envelope.kind,
-                                    payload = %truncated,
-                                    "message received (payload preview)"
-                                );
-                                trace!(
-                                    msg_id = %envelope.id,
-                                    from = from_id,
-                                    kind = %envelope.kind,
-                                    payload = raw,
-                                    "message received (full payload)"
-                                );
-                            }
-                            if let Some(ref from) = envelope.from {
-                                peer_table_for_inbound
-                                    .set_connected(from.as_str(), None)
-                                    .await;
-                            }
-                            if let Err(err) = ipc_for_inbound.broadcast_inbound(&envelope).await {
-                                warn!(error = %err, "failed broadcasting inbound to IPC clients");
-                            }
-                        }
-                        Err(err) => {
-                            warn!(error = %err, "inbound subscription closed");
-                            break;
-                        }
-                    }
-                }
-            }
-        }
-    });
-
-    // --- Pair-request forwarder (transport TLS verifier -> IPC clients) ---
-    let mut pair_request_rx = transport.subscribe_pair_requests();
-    let ipc_for_pair_request = ipc.clone();
-    let cancel_for_pair_request = cancel.clone();
-    tokio::spawn(async move {
-        loop {
-            tokio::select! {
-                _ = cancel_for_pair_request.cancelled() => break,
-                msg = pair_request_rx.recv() => {
-                    match msg {
-                        Ok(pair_request) => {
-                            if let Err(err) = ipc_for_pair_request
-                                .broadcast_pair_request(
-                                    &pair_request.agent_id,
-                                    &pair_request.pubkey,
-                                    pair_request.addr.as_deref(),
-                                )
-                                .await
-                            {
-                                warn!(error = %err, "failed broadcasting pair_request to IPC clients");
-                            }
-                        }
-                        Err(err) => {
-                            warn!(error = %err, "pair_request subscription closed");
-                            break;
-                        }
-                    }
-                }
-            }
-        }
-    });
-
-    // --- Discovery ---
-    let (peer_event_tx, mut peer_event_rx) = mpsc::channel(256);
-    {
-        let tx = peer_event_tx.clone();
-        let peers = config.persisted_peers.clone();
-        let cancel_clone = cancel.clone();
-        tokio::spawn(async move {
-            if let Err(err) = run_static_discovery(peers, tx, cancel_clone).await {
-                warn!(error = %err, "static discovery failed");
-            }
-        });
-    }
-    if !opts.disable_mdns {
-        let tx = peer_event_tx.clone();
-        let agent_id = local_agent_id.clone();
-        let pubkey = identity.public_key_base64().to_string();
-        let cancel_clone = cancel.clone();
-        tokio::spawn(async move {
-            if let Err(err) = run_mdns_discovery(agent_id, pubkey, port, tx, cancel_clone).await {
-                warn!(error = %err, "mDNS discovery failed");
-            }
-        });
+    let (ipc, mut cmd_rx) = match activated.ipc {
+        Some(listener) => IpcServer::from_listener(listener, MAX_IPC_CLIENTS, ipc_config)?,
+        None => IpcServer::bind(paths.socket.clone(), MAX_IPC_CLIENTS, ipc_config).await?,
+    };
+    if ipc.socket_path() != paths.socket {
+        warn!(
+            expected = %paths.socket.display(),
+            activated = %ipc.socket_path().display(),
+            "socket-activated IPC path differs from state root; CLI commands will not find it"
+        );
     }
 
+    // --- Transport → IPC forwarders ---
+    spawn_inbound_forwarder(
+        &supervisor,
+        &transport,
+        ipc.clone(),
+        counters.clone(),
+        heartbeats.clone(),
+        topics.clone(),
+        threads.clone(),
+        peer_table.clone(),
+        history.clone(),
+        audit.clone(),
+        events.clone(),
+        webhooks,
+        transforms.clone(),
+        forwarding.clone(),
+    );
+    spawn_pair_request_forwarder(
+        &supervisor,
+        &transport,
+        ipc.clone(),
+        audit.clone(),
+        forwarding.clone(),
+    );
+    spawn_connection_event_forwarder(
+        &supervisor,
+        &transport,
+        ipc.clone(),
+        heartbeats.clone(),
+        audit.clone(),
+        events.clone(),
+        forwarding.clone(),
+    );
+    spawn_topic_announcer(
+        &supervisor,
+        &transport,
+        peer_table.clone(),
+        topics.clone(),
+        local_agent_id.clone(),
+        cancel.clone(),
+    );
+
     // --- Reconnection tracking ---
     let mut reconnect_map = HashMap::<AgentId, ReconnectState>::new();
     for peer in peer_table.list().await {
@@ -254,10 +304,48 @@ pub async fn run_daemon(opts: DaemonOptions) -> Result<()> {
     // --- Reconnect outcome channel ---
     let (reconnect_tx, mut reconnect_rx) = reconnect_channel();
 
+    // --- Connection pre-warming ---
+    if config.prewarm.enabled() {
+        let started = prewarm_connections(
+            &peer_table,
+            &transport,
+            &mut reconnect_map,
+            &cancel,
+            &reconnect_tx,
+            config.prewarm.max_concurrent(),
+        )
+        .await;
+        info!(peers = started, "pre-warming peer connections");
+    }
+
+    // --- Discovery ---
+    let (peer_event_tx, mut peer_event_rx) = mpsc::channel(256);
+    let mut static_discovery = StaticDiscovery::start(
+        &supervisor,
+        config.persisted_peers.clone(),
+        peer_event_tx.clone(),
+        &cancel,
+    );
+    if !opts.disable_mdns {
+        spawn_mdns_discovery(
+            &supervisor,
+            local_agent_id.clone(),
+            identity.public_key_base64().to_string(),
+            local_port,
+            peer_event_tx.clone(),
+            &cancel,
+            health.clone(),
+        );
+    }
+
     // --- Timers ---
     let mut save_interval = tokio::time::interval(Duration::from_secs(60));
     let mut stale_interval = tokio::time::interval(Duration::from_secs(5));
     let mut reconnect_interval = tokio::time::interval(Duration::from_secs(1));
+    let heartbeat_every = config.heartbeat.interval();
+    let heartbeat_misses = config.heartbeat.miss_threshold();
+    let mut heartbeat_interval =
+        tokio::time::interval(heartbeat_every.unwrap_or(Duration::from_secs(3600)));
 
     let ctx = DaemonContext {
         ipc: &ipc,
@@ -265,9 +353,29 @@ pub async fn run_daemon(opts: DaemonOptions) -> Result<()> {
         transport: &transport,
         local_agent_id: &local_agent_id,
         counters: &counters,
+        topics: &topics,
+        threads: &threads,
+        lifetime_baseline: &lifetime_baseline,
+        history: history.as_ref(),
+        audit: audit.as_ref(),
+        events: events.as_ref(),
+        revocations_path: &paths.revocations,
+        blocklist_path: &paths.blocklist,
+        known_peers: &known_peers,
+        peer_tags: &peer_tags,
+        transforms: &transforms,
+        health: &health,
+        supervisor: &supervisor,
+        max_ipc_clients: MAX_IPC_CLIENTS,
+        reload_tx: &reload_tx,
+        cancel: &cancel,
+        forwarding: &forwarding,
         start,
     };
 
+    let mut sends = InFlightSends::default();
+    systemd::notify("READY=1");
+
     // --- Main event loop ---
     loop {
         tokio::select! {
@@ -275,44 +383,55 @@ pub async fn run_daemon(opts: DaemonOptions) -> Result<()> {
                 info!("shutdown signal received");
                 break;
             }
-            maybe_cmd = cmd_rx.recv() => {
-                if let Some(cmd) = maybe_cmd
-                    && let Err(err) = handle_command(cmd, &ctx).await
-                {
-                    error!(error = %err, "failed handling IPC command");
+            maybe_cmd = cmd_rx.recv(), if sends.has_room() => {
+                if let Some(cmd) = maybe_cmd {
+                    if let Err(err) = ipc.tap_command(cmd.client_id, &cmd.command).await {
+                        warn!(error = %err, "failed sending tap event to IPC clients");
+                    }
+                    if matches!(cmd.command, IpcCommand::Send { .. }) {
+                        sends.push(handle_send_command(cmd, &ctx));
+                    } else if let Err(err) = handle_command(cmd, &ctx, &reconnect_map).await {
+                        error!(error = %err, "failed handling IPC command");
+                    }
                 }
             }
-            maybe_event = peer_event_rx.recv() => {
-                if let Some(event) = maybe_event {
-                    handle_peer_event(
-                        event,
-                        &peer_table,
+            Some(result) = sends.next(), if !sends.is_empty() => {
+                if let Err(err) = result {
+                    error!(error = %err, "failed handling IPC send");
+                }
+            }
+            maybe_reload = reload_rx.recv() => {
+                if let Some(trigger) = maybe_reload {
+                    handle_reload(
+                        trigger,
+                        &ctx,
+                        &paths.config,
+                        opts.port,
+                        &mut config,
                         &mut reconnect_map,
+                        &mut static_discovery,
                     ).await;
-                    if let Err(err) = save_known_peers(&paths.known_peers, &peer_table.to_known_peers().await).await {
-                        warn!(error = %err, "failed to persist known peers after discovery event");
-                    }
                 }
             }
-            _ = stale_interval.tick() => {
-                let removed = peer_table.remove_stale(crate::peer_table::STALE_TIMEOUT).await;
-                if !removed.is_empty() {
-                    for id in &removed {
-                        reconnect_map.remove(id);
-                    }
-                    info!(count = removed.len(), "removed stale discovered peers");
-                    if let Err(err) = save_known_peers(&paths.known_peers, &peer_table.to_known_peers().await).await {
-                        warn!(error = %err, "failed to persist known peers after stale cleanup");
-                    }
+            maybe_event = peer_event_rx.recv() => {
+                if let Some(event) = maybe_event {
+                    on_discovery_event(event, &ctx, &mut reconnect_map).await;
                 }
             }
+            _ = stale_interval.tick() => {
+                remove_stale_peers(&ctx, &mut reconnect_map).await;
+            }
             maybe_outcome = reconnect_rx.recv() => {
                 if let Some(outcome) = maybe_outcome {
+                    let max_backoff = config
+                        .peer_tuning(outcome.agent_id.as_str())
+                        .reconnect_max_backoff()
+                        .unwrap_or(RECONNECT_MAX_BACKOFF);
                     handle_reconnect_outcome(
                         outcome,
                         &peer_table,
                         &mut reconnect_map,
-                        RECONNECT_MAX_BACKOFF,
+                        max_backoff,
                     ).await;
                 }
             }
@@ -325,80 +444,34 @@ pub async fn run_daemon(opts: DaemonOptions) -> Result<()> {
                     &reconnect_tx,
                 ).await;
             }
+            _ = heartbeat_interval.tick(), if heartbeat_every.is_some() => {
+                heartbeat_tick(
+                    &heartbeats,
+                    &peer_table,
+                    &transport,
+                    &local_agent_id,
+                    heartbeat_interval.period(),
+                    heartbeat_misses,
+                ).await;
+            }
             _ = save_interval.tick() => {
-                if let Err(err) = save_known_peers(&paths.known_peers, &peer_table.to_known_peers().await).await {
+                if let Err(err) = known_peers.persist(&peer_table, &health).await {
                     warn!(error = %err, "failed to persist known peers");
                 }
+                if let Err(err) = save_lifetime_stats(&paths.stats, &ctx.lifetime()).await {
+                    warn!(error = %err, "failed to persist lifetime stats");
+                }
             }
         }
     }
 
     // --- Shutdown sequence (spec §8) ---
-    info!("shutting down...");
-
-    // Signal all background tasks to stop
-    cancel.cancel();
-    info!("all background tasks signaled for shutdown");
-
-    // Brief drain period for in-flight streams
-    tokio::time::sleep(Duration::from_millis(100)).await;
-
-    transport.close_all().await;
-    if let Err(err) = save_known_peers(&paths.known_peers, &peer_table.to_known_peers().await).await
-    {
-        warn!(error = %err, "failed to save known peers during shutdown");
-    }
-    let cleanup_socket_result = ipc.cleanup_socket();
-    if let Err(err) = daemon_lock.release() {
-        warn!(error = %err, "failed to remove daemon lock file during shutdown");
-    }
-    cleanup_socket_result?;
-    info!("shutdown complete");
-
-    Ok(())
-}
-
-fn spawn_shutdown_signal_task(cancel: CancellationToken) {
-    tokio::spawn(async move {
-        if let Err(err) = wait_for_shutdown_signal(cancel).await {
-            warn!(error = %err, "failed to install shutdown signal handler");
-        }
-    });
-}
-
-#[cfg(unix)]
-async fn wait_for_shutdown_signal(cancel: CancellationToken) -> Result<()> {
-    use tokio::signal::unix::{SignalKind, signal};
-
-    let mut sigterm =
-        signal(SignalKind::terminate()).context("failed to install SIGTERM handler")?;
-    let mut sigint = signal(SignalKind::interrupt()).context("failed to install SIGINT handler")?;
-
-    tokio::select! {
-        _ = cancel.cancelled() => {}
-        _ = sigterm.recv() => {
-            info!("received SIGTERM, initiating graceful shutdown");
-            cancel.cancel();
-        }
-        _ = sigint.recv() => {
-            info!("received SIGINT, initiating graceful shutdown");
-            cancel.cancel();
-        }
-    }
-
-    Ok(())
-}
-
-#[cfg(not(unix))]
-async fn wait_for_shutdown_signal(cancel: CancellationToken) -> Result<()> {
-    tokio::select! {
-        _ = cancel.cancelled() => {}
-        result = tokio::signal::ctrl_c() => {
-            result.context("failed to install Ctrl-C handler")?;
-            info!("received Ctrl-C, initiating graceful shutdown");
-            cancel.cancel();
-        }
-    }
-
-    Ok(())
+    shutdown(
+        &ctx,
+        sends,
+        config.shutdown.drain_timeout(),
+        &paths.stats,
+        &mut daemon_lock,
+    )
+    .await
 }
@@ -0,0 +1,49 @@
+use std::path::PathBuf;
+
+use tokio_util::sync::CancellationToken;
+
+use crate::config::AxonPaths;
+use crate::transport::ResponseHandlerFn;
+
+#[derive(Clone, Default)]
+pub struct DaemonOptions {
+    pub port: Option<u16>,
+    pub disable_mdns: bool,
+    pub axon_root: Option<PathBuf>,
+    /// Profile from `profiles` in config.yaml (`axon --profile`).
+    pub profile: Option<String>,
+    pub cancel: Option<CancellationToken>,
+    /// Install SIGTERM/SIGINT shutdown and SIGHUP reload handlers. Off when
+    /// the daemon is embedded in another program (`axon::node`).
+    pub handle_signals: bool,
+    /// Answers inbound requests ahead of `handler.exec`, which still gets the
+    /// requests it declines (`axon::node` handlers).
+    pub response_handler: Option<ResponseHandlerFn>,
+}
+
+impl std::fmt::Debug for DaemonOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DaemonOptions")
+            .field("port", &self.port)
+            .field("disable_mdns", &self.disable_mdns)
+            .field("axon_root", &self.axon_root)
+            .field("profile", &self.profile)
+            .field("handle_signals", &self.handle_signals)
+            .field("response_handler", &self.response_handler.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+impl DaemonOptions {
+    /// The state directory: `axon_root` or the default, narrowed to `profile`.
+    pub(crate) fn paths(&self) -> anyhow::Result<AxonPaths> {
+        let paths = match self.axon_root {
+            Some(ref root) => AxonPaths::from_root(root.clone()),
+            None => AxonPaths::discover()?,
+        };
+        match self.profile {
+            Some(ref profile) => paths.with_profile(profile),
+            None => Ok(paths),
+        }
+    }
+}
@@ -1,18 +1,33 @@
 use std::collections::HashMap;
+use std::sync::Arc;
 use std::time::Instant;
 
-use tracing::warn;
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
 
+use super::audit::AuditEvent;
+use super::command_handler::DaemonContext;
+use super::health::HealthState;
 use super::reconnect::ReconnectState;
-use crate::discovery::PeerEvent;
+use super::supervisor::Supervisor;
+use crate::config::PersistedStaticPeerConfig;
+use crate::discovery::{PeerEvent, run_mdns_discovery, run_static_discovery};
 use crate::message::AgentId;
 use crate::peer_table::{PeerSource, PeerTable};
 
+/// Peer table membership change caused by a discovery event.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum PeerChange {
+    Added(AgentId),
+    Removed(AgentId),
+}
+
 pub(crate) async fn handle_peer_event(
     event: PeerEvent,
     peer_table: &PeerTable,
     reconnect_state: &mut HashMap<AgentId, ReconnectState>,
-) {
+) -> Option<PeerChange> {
     let now = Instant::now();
 
     match event {
@@ -21,19 +36,23 @@ pub(crate) async fn handle_peer_event(
             addr,
             pubkey,
         } => {
-            if let Some(existing) = peer_table.get(&agent_id).await {
+            if peer_table.is_refused(&pubkey) {
+                return None;
+            }
+            let existing = peer_table.get(&agent_id).await;
+            if let Some(ref existing) = existing {
                 if matches!(existing.source, PeerSource::Static | PeerSource::Cached)
                     && existing.pubkey != pubkey
                 {
                     warn!(
                         peer_id = %agent_id,
                         source = ?existing.source,
-                        "ignoring discovered pubkey change for pinned peer"
+                        "ignoring discovered pubkey change for pinned peer; run `axon pins clear` if it was reinstalled"
                     );
                     reconnect_state
                         .entry(agent_id)
                         .or_insert_with(|| ReconnectState::immediate(now));
-                    return;
+                    return None;
                 }
 
                 if existing.source == PeerSource::Static {
@@ -41,7 +60,7 @@ pub(crate) async fn handle_peer_event(
                         .refresh_static_addr(agent_id.as_str(), addr, &pubkey)
                         .await;
                     reconnect_state.insert(agent_id, ReconnectState::immediate(now));
-                    return;
+                    return None;
                 }
             }
 
@@ -49,20 +68,155 @@ pub(crate) async fn handle_peer_event(
                 .upsert_discovered(agent_id.clone(), addr, pubkey)
                 .await;
 
-            reconnect_state.insert(agent_id, ReconnectState::immediate(now));
+            reconnect_state.insert(agent_id.clone(), ReconnectState::immediate(now));
+            existing.is_none().then_some(PeerChange::Added(agent_id))
         }
         PeerEvent::Lost { agent_id } => {
-            if matches!(
+            let removed = matches!(
                 peer_table.get(&agent_id).await.map(|peer| peer.source),
                 Some(PeerSource::Discovered)
-            ) {
-                peer_table.remove(&agent_id).await;
-            }
+            ) && peer_table.remove(&agent_id).await.is_some();
             reconnect_state.remove(&agent_id);
+            removed.then_some(PeerChange::Removed(agent_id))
         }
     }
 }
 
+/// Apply a discovery event, tell IPC clients and the audit log about any
+/// membership change, and persist the peer table.
+pub(crate) async fn on_discovery_event(
+    event: PeerEvent,
+    ctx: &DaemonContext<'_>,
+    reconnect_state: &mut HashMap<AgentId, ReconnectState>,
+) {
+    let change = handle_peer_event(event, ctx.peer_table, reconnect_state).await;
+    let broadcast = match &change {
+        Some(PeerChange::Added(id)) => match ctx.peer_table.get(id.as_str()).await {
+            Some(peer) => {
+                ctx.ipc
+                    .broadcast_peer_discovered(id.as_str(), &peer.addr.to_string())
+                    .await
+            }
+            None => Ok(()),
+        },
+        Some(PeerChange::Removed(id)) => ctx.ipc.broadcast_peer_lost(id.as_str()).await,
+        None => Ok(()),
+    };
+    if let Err(err) = broadcast {
+        warn!(error = %err, "failed broadcasting discovery event to IPC clients");
+    }
+    if let (Some(audit), Some(change)) = (ctx.audit, change) {
+        let (event, id) = match change {
+            PeerChange::Added(id) => (AuditEvent::PeerAdd, id),
+            PeerChange::Removed(id) => (AuditEvent::PeerRemove, id),
+        };
+        audit.record_peers(event, &[id], "discovery").await;
+    }
+    if let Err(err) = ctx.known_peers.persist(ctx.peer_table, ctx.health).await {
+        warn!(error = %err, "failed to persist known peers after discovery event");
+    }
+}
+
+/// Drop peers that have not been seen within the stale policy.
+pub(crate) async fn remove_stale_peers(
+    ctx: &DaemonContext<'_>,
+    reconnect_state: &mut HashMap<AgentId, ReconnectState>,
+) {
+    let removed = ctx.peer_table.remove_stale().await;
+    if removed.is_empty() {
+        return;
+    }
+    for id in &removed {
+        reconnect_state.remove(id);
+    }
+    if let Some(audit) = ctx.audit {
+        audit
+            .record_peers(AuditEvent::PeerRemove, &removed, "stale")
+            .await;
+    }
+    info!(count = removed.len(), "removed stale peers");
+    if let Err(err) = ctx.known_peers.persist(ctx.peer_table, ctx.health).await {
+        warn!(error = %err, "failed to persist known peers after stale cleanup");
+    }
+}
+
+/// Static peer discovery, run under a child token so it can be restarted on
+/// config reload without affecting the rest of the daemon.
+pub(crate) struct StaticDiscovery {
+    supervisor: Arc<Supervisor>,
+    tx: mpsc::Sender<PeerEvent>,
+    parent: CancellationToken,
+    task: CancellationToken,
+}
+
+impl StaticDiscovery {
+    pub(crate) fn start(
+        supervisor: &Arc<Supervisor>,
+        peers: Vec<PersistedStaticPeerConfig>,
+        tx: mpsc::Sender<PeerEvent>,
+        cancel: &CancellationToken,
+    ) -> Self {
+        let task = spawn_static_discovery(supervisor, peers, tx.clone(), cancel);
+        Self {
+            supervisor: supervisor.clone(),
+            tx,
+            parent: cancel.clone(),
+            task,
+        }
+    }
+
+    /// Restart against a new peer list so hostname peers are re-resolved.
+    pub(crate) fn restart(&mut self, peers: Vec<PersistedStaticPeerConfig>) {
+        self.task.cancel();
+        self.task = spawn_static_discovery(&self.supervisor, peers, self.tx.clone(), &self.parent);
+    }
+}
+
+fn spawn_static_discovery(
+    supervisor: &Arc<Supervisor>,
+    peers: Vec<PersistedStaticPeerConfig>,
+    tx: mpsc::Sender<PeerEvent>,
+    cancel: &CancellationToken,
+) -> CancellationToken {
+    let task_cancel = cancel.child_token();
+    let cancel_clone = task_cancel.clone();
+    supervisor.spawn("static_discovery", task_cancel.clone(), move || {
+        run_static_discovery(peers.clone(), tx.clone(), cancel_clone.clone())
+    });
+    task_cancel
+}
+
+/// Advertise and browse over mDNS, reporting its state to `health`.
+pub(crate) fn spawn_mdns_discovery(
+    supervisor: &Arc<Supervisor>,
+    agent_id: AgentId,
+    pubkey: String,
+    port: u16,
+    tx: mpsc::Sender<PeerEvent>,
+    cancel: &CancellationToken,
+    health: Arc<HealthState>,
+) {
+    let cancel_clone = cancel.clone();
+    supervisor.spawn("mdns_discovery", cancel.clone(), move || {
+        let run = run_mdns_discovery(
+            agent_id.clone(),
+            pubkey.clone(),
+            port,
+            tx.clone(),
+            cancel_clone.clone(),
+        );
+        let health = health.clone();
+        async move {
+            health.set_mdns_running();
+            let result = run.await;
+            if result.is_err() {
+                health.set_mdns_failed();
+            }
+            result
+        }
+    });
+}
+
 #[cfg(test)]
 #[path = "peer_events_tests.rs"]
 mod tests;
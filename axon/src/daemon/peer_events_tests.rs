@@ -20,7 +20,7 @@ async fn lost_discovered_peer_is_removed_from_table() {
     let mut reconnect_state = HashMap::new();
     reconnect_state.insert(agent_id.clone(), ReconnectState::immediate(Instant::now()));
 
-    handle_peer_event(
+    let change = handle_peer_event(
         PeerEvent::Lost {
             agent_id: agent_id.clone(),
         },
@@ -29,6 +29,7 @@ async fn lost_discovered_peer_is_removed_from_table() {
     )
     .await;
 
+    assert_eq!(change, Some(PeerChange::Removed(agent_id.clone())));
     assert!(table.get(agent_id.as_str()).await.is_none());
     assert!(!reconnect_state.contains_key(&agent_id));
 }
@@ -44,7 +45,7 @@ async fn lost_static_peer_is_ignored() {
     table.upsert_static(&cfg).await;
 
     let mut reconnect_state = HashMap::new();
-    handle_peer_event(
+    let change = handle_peer_event(
         PeerEvent::Lost {
             agent_id: cfg.agent_id.clone(),
         },
@@ -53,6 +54,7 @@ async fn lost_static_peer_is_ignored() {
     )
     .await;
 
+    assert_eq!(change, None);
     assert!(table.get(cfg.agent_id.as_str()).await.is_some());
 }
 
@@ -83,3 +85,21 @@ async fn discovered_event_refreshes_static_addr_when_pubkey_matches() {
     assert_eq!(peer.source, PeerSource::Static);
     assert!(reconnect_state.contains_key(&cfg.agent_id));
 }
+
+#[tokio::test]
+async fn discovered_event_reports_only_new_peers_as_added() {
+    let table = PeerTable::new();
+    let agent_id: AgentId = "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa".into();
+    let event = || PeerEvent::Discovered {
+        agent_id: agent_id.clone(),
+        addr: addr("127.0.0.1:7100"),
+        pubkey: "Zm9v".to_string(),
+    };
+
+    let mut reconnect_state = HashMap::new();
+    let first = handle_peer_event(event(), &table, &mut reconnect_state).await;
+    let repeat = handle_peer_event(event(), &table, &mut reconnect_state).await;
+
+    assert_eq!(first, Some(PeerChange::Added(agent_id.clone())));
+    assert_eq!(repeat, None, "re-announcements are not membership changes");
+}
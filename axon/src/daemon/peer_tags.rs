@@ -0,0 +1,27 @@
+use std::collections::BTreeMap;
+use std::sync::RwLock;
+
+use crate::message::AgentId;
+
+/// Peer tags from `config.yaml`, replaced on reload and reported by `peers`.
+#[derive(Debug, Default)]
+pub(crate) struct PeerTags {
+    tags: RwLock<BTreeMap<AgentId, Vec<String>>>,
+}
+
+impl PeerTags {
+    pub(crate) fn set(&self, tags: &BTreeMap<AgentId, Vec<String>>) {
+        if let Ok(mut current) = self.tags.write() {
+            *current = tags.clone();
+        }
+    }
+
+    /// Tags for `agent_id`, empty when it has none.
+    pub(crate) fn get(&self, agent_id: &str) -> Vec<String> {
+        self.tags
+            .read()
+            .ok()
+            .and_then(|tags| tags.get(agent_id).cloned())
+            .unwrap_or_default()
+    }
+}
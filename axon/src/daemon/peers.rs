@@ -0,0 +1,177 @@
+use std::collections::HashMap;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use super::audit::{AuditEvent, AuditRecord};
+use super::command_handler::DaemonContext;
+use super::reconnect::ReconnectState;
+use crate::config::resolve_static_peer;
+use crate::ipc::{BanSummary, DaemonReply, IpcErrorCode, PeerDetail, PeerSummary, ReconnectInfo};
+use crate::message::AgentId;
+use crate::peer_table::{ConnectionStatus, PeerRecord, PeerSource};
+use crate::peer_token::derive_agent_id_from_pubkey_base64;
+
+pub(crate) fn status_str(status: &ConnectionStatus) -> &'static str {
+    match status {
+        ConnectionStatus::Discovered => "discovered",
+        ConnectionStatus::Connecting => "connecting",
+        ConnectionStatus::Connected => "connected",
+        ConnectionStatus::Degraded => "degraded",
+        ConnectionStatus::Disconnected => "disconnected",
+    }
+}
+
+pub(crate) fn source_str(source: &PeerSource) -> &'static str {
+    match source {
+        PeerSource::Static => "static",
+        PeerSource::Discovered => "discovered",
+        PeerSource::Cached => "cached",
+    }
+}
+
+fn peer_detail(
+    ctx: &DaemonContext<'_>,
+    peer: &PeerRecord,
+    reconnect: Option<&ReconnectState>,
+) -> PeerDetail {
+    let last_seen_ms = SystemTime::now()
+        .checked_sub(peer.last_seen.elapsed())
+        .and_then(|at| at.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |at| at.as_millis() as u64);
+    let now = Instant::now();
+    PeerDetail {
+        agent_id: peer.agent_id.to_string(),
+        pubkey: peer.pubkey.clone(),
+        addr: peer.addr.to_string(),
+        status: status_str(&peer.status).to_string(),
+        source: source_str(&peer.source).to_string(),
+        rtt_ms: peer.rtt_ms,
+        last_seen_ms,
+        first_seen_ms: peer.first_seen_unix_ms,
+        messages_sent: peer.traffic.messages_sent,
+        messages_received: peer.traffic.messages_received,
+        reconnect: reconnect.map(|state| ReconnectInfo {
+            in_flight: state.in_flight,
+            next_attempt_in_ms: state
+                .next_attempt_at
+                .saturating_duration_since(now)
+                .as_millis() as u64,
+            backoff_secs: state.current_backoff.as_secs(),
+        }),
+        topics: ctx.topics.remote(peer.agent_id.as_str()),
+        software: peer.software.clone(),
+    }
+}
+
+/// Handle IPC `peers`: the peer table, optionally only peers with `tag`.
+pub(crate) async fn peers_reply(
+    ctx: &DaemonContext<'_>,
+    tag: Option<String>,
+    req_id: Option<String>,
+) -> DaemonReply {
+    let peers: Vec<PeerSummary> = ctx
+        .peer_table
+        .list()
+        .await
+        .into_iter()
+        .map(|p| PeerSummary {
+            tags: ctx.peer_tags.get(p.agent_id.as_str()),
+            agent_id: p.agent_id.to_string(),
+            addr: p.addr.to_string(),
+            status: status_str(&p.status).to_string(),
+            rtt_ms: p.rtt_ms,
+            source: source_str(&p.source).to_string(),
+            first_seen_ms: p.first_seen_unix_ms,
+            expires_in_ms: ctx
+                .peer_table
+                .expires_in(&p)
+                .map(|left| left.as_millis() as u64),
+            traffic: p.traffic,
+            version: p.software.map(|software| software.version),
+        })
+        .filter(|p| tag.as_ref().is_none_or(|tag| p.tags.contains(tag)))
+        .collect();
+    DaemonReply::Peers {
+        ok: true,
+        peers,
+        req_id,
+    }
+}
+
+/// Handle IPC `whois`: one peer's full record, with its reconnect backoff
+/// when the main loop is retrying it.
+pub(crate) async fn whois_reply(
+    ctx: &DaemonContext<'_>,
+    agent_id: &str,
+    reconnect: &HashMap<AgentId, ReconnectState>,
+    req_id: Option<String>,
+) -> DaemonReply {
+    match ctx.peer_table.get(agent_id).await {
+        Some(peer) => DaemonReply::Whois {
+            ok: true,
+            peer: peer_detail(ctx, &peer, reconnect.get(&peer.agent_id)),
+            req_id,
+        },
+        None => DaemonReply::error(IpcErrorCode::PeerNotFound, req_id),
+    }
+}
+
+/// Handle IPC `add_peer`: add a static peer for this run. Our own key, a
+/// peer already in the table, and revoked or blocked keys are refused.
+pub(crate) async fn add_peer_reply(
+    ctx: &DaemonContext<'_>,
+    pubkey: String,
+    addr: String,
+    req_id: Option<String>,
+) -> DaemonReply {
+    match derive_agent_id_from_pubkey_base64(&pubkey) {
+        Err(_) => DaemonReply::error(IpcErrorCode::InvalidCommand, req_id),
+        Ok(agent_id) => {
+            if matches!(agent_id.as_str(), id if id == ctx.local_agent_id.as_str()) {
+                DaemonReply::error(IpcErrorCode::SelfSend, req_id)
+            } else if ctx.peer_table.get(agent_id.as_str()).await.is_some()
+                || ctx.peer_table.is_refused(&pubkey)
+            {
+                DaemonReply::error(IpcErrorCode::InvalidCommand, req_id)
+            } else {
+                match resolve_static_peer(agent_id.clone(), &addr, pubkey).await {
+                    Ok(peer) => {
+                        ctx.peer_table.upsert_static(&peer).await;
+                        if let Some(audit) = ctx.audit {
+                            audit
+                                .record(
+                                    AuditRecord::peer(AuditEvent::PeerAdd, agent_id.as_str())
+                                        .with_addr(Some(peer.addr.to_string()))
+                                        .with_reason("add_peer"),
+                                )
+                                .await;
+                        }
+                        DaemonReply::AddPeer {
+                            ok: true,
+                            agent_id: agent_id.to_string(),
+                            req_id,
+                        }
+                    }
+                    Err(_) => DaemonReply::error(IpcErrorCode::InvalidCommand, req_id),
+                }
+            }
+        }
+    }
+}
+
+/// Handle IPC `bans`: source addresses currently banned for failed handshakes.
+pub(crate) fn bans_reply(ctx: &DaemonContext<'_>, req_id: Option<String>) -> DaemonReply {
+    DaemonReply::Bans {
+        ok: true,
+        bans: ctx
+            .transport
+            .handshake_bans()
+            .into_iter()
+            .map(|ban| BanSummary {
+                addr: ban.addr.to_string(),
+                strikes: ban.strikes,
+                remaining_secs: ban.remaining.as_secs().max(1),
+            })
+            .collect(),
+        req_id,
+    }
+}
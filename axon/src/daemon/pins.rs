@@ -0,0 +1,95 @@
+use tracing::{info, warn};
+
+use super::audit::{AuditEvent, AuditRecord};
+use super::command_handler::DaemonContext;
+use super::peers::source_str;
+use crate::ipc::{DaemonReply, IpcErrorCode, PinSummary};
+use crate::peer_table::{PeerRecord, PeerSource};
+
+fn pin_summary(peer: &PeerRecord) -> PinSummary {
+    PinSummary {
+        agent_id: peer.agent_id.to_string(),
+        pubkey: peer.pubkey.clone(),
+        addr: peer.addr.to_string(),
+        source: source_str(&peer.source).to_string(),
+    }
+}
+
+/// Handle IPC `pins`: every pinned key in the peer table, or only the pin
+/// for `agent_id` (`peer_not_found` when there is none).
+pub(crate) async fn pins_reply(
+    ctx: &DaemonContext<'_>,
+    agent_id: Option<String>,
+    req_id: Option<String>,
+) -> DaemonReply {
+    let pins = match agent_id {
+        Some(agent_id) => match ctx.peer_table.get(&agent_id).await {
+            Some(peer) => vec![pin_summary(&peer)],
+            None => return DaemonReply::error(IpcErrorCode::PeerNotFound, req_id),
+        },
+        None => ctx
+            .peer_table
+            .list()
+            .await
+            .iter()
+            .map(pin_summary)
+            .collect(),
+    };
+    DaemonReply::Pins {
+        ok: true,
+        pins,
+        req_id,
+    }
+}
+
+/// Handle IPC `clear_pin`: drop a discovered or cached peer from the table,
+/// close its connection, and rewrite `known_peers.json` so the old key is
+/// not restored on restart. The next discovery announcement pins afresh.
+/// Static peers come from `config.yaml` and return `invalid_command`.
+pub(crate) async fn clear_pin_reply(
+    ctx: &DaemonContext<'_>,
+    agent_id: String,
+    req_id: Option<String>,
+) -> DaemonReply {
+    match ctx.peer_table.get(&agent_id).await {
+        None => return DaemonReply::error(IpcErrorCode::PeerNotFound, req_id),
+        Some(peer) if peer.source == PeerSource::Static => {
+            return DaemonReply::error(IpcErrorCode::InvalidCommand, req_id);
+        }
+        Some(_) => {}
+    }
+    let Some(peer) = ctx.peer_table.remove(&agent_id).await else {
+        return DaemonReply::error(IpcErrorCode::PeerNotFound, req_id);
+    };
+    ctx.transport
+        .close_connection(peer.agent_id.as_str(), b"unpinned")
+        .await;
+
+    let result = ctx
+        .known_peers
+        .save(ctx.peer_table.to_known_peers().await)
+        .await
+        .map(drop);
+    if let Err(ref err) = result {
+        warn!(error = %err, "failed to persist known peers after clearing pin");
+    }
+    ctx.health.record_persist(&result);
+
+    if let Some(audit) = ctx.audit {
+        audit
+            .record(
+                AuditRecord::peer(AuditEvent::PeerRemove, peer.agent_id.as_str())
+                    .with_reason("clear_pin"),
+            )
+            .await;
+    }
+    info!(agent_id = %peer.agent_id, "cleared pinned peer key");
+
+    DaemonReply::ClearPin {
+        ok: true,
+        agent_id: peer.agent_id.to_string(),
+        pubkey: peer.pubkey,
+        source: source_str(&peer.source).to_string(),
+        req_id,
+    }
+}
@@ -0,0 +1,60 @@
+use std::net::UdpSocket;
+
+use anyhow::{Context, Result};
+use tokio_util::sync::CancellationToken;
+
+use super::reload::configure_transport;
+use super::{IDLE_TIMEOUT, INBOUND_READ_TIMEOUT, KEEPALIVE, MAX_CONNECTIONS};
+use crate::config::Config;
+use crate::identity::Identity;
+use crate::peer_table::PubkeyMap;
+use crate::transport::{QuicTransport, ResponseHandlerFn};
+
+/// The daemon's QUIC transport on the socket-activated UDP socket, or else
+/// bound to `port` on all interfaces, with the startup settings from
+/// `config` applied.
+pub(crate) async fn open_transport(
+    activated: Option<UdpSocket>,
+    port: u16,
+    identity: &Identity,
+    cancel: CancellationToken,
+    response_handler: Option<ResponseHandlerFn>,
+    pubkeys: PubkeyMap,
+    config: &Config,
+) -> Result<QuicTransport> {
+    let transport = match activated {
+        Some(socket) => {
+            QuicTransport::from_socket_cancellable(
+                socket,
+                identity,
+                cancel,
+                MAX_CONNECTIONS,
+                KEEPALIVE,
+                IDLE_TIMEOUT,
+                response_handler,
+                INBOUND_READ_TIMEOUT,
+                pubkeys,
+            )
+            .await?
+        }
+        None => {
+            let bind_addr = format!("0.0.0.0:{port}")
+                .parse()
+                .context("invalid bind address")?;
+            QuicTransport::bind_cancellable(
+                bind_addr,
+                identity,
+                cancel,
+                MAX_CONNECTIONS,
+                KEEPALIVE,
+                IDLE_TIMEOUT,
+                response_handler,
+                INBOUND_READ_TIMEOUT,
+                pubkeys,
+            )
+            .await?
+        }
+    };
+    configure_transport(&transport, config);
+    Ok(transport)
+}
@@ -1,18 +1,16 @@
 use std::collections::HashMap;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use tracing::warn;
 
-use tokio::sync::mpsc;
+use tokio::sync::{Semaphore, mpsc};
 use tokio_util::sync::CancellationToken;
 
 use crate::message::AgentId;
-use crate::peer_table::{ConnectionStatus, PeerTable};
+use crate::peer_table::{ConnectionStatus, PeerRecord, PeerTable};
 use crate::transport::QuicTransport;
 
-/// Timeout for a single reconnection attempt (QUIC handshake to peer).
-const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
-
 #[derive(Debug, Clone)]
 pub(crate) struct ReconnectState {
     pub(crate) next_attempt_at: Instant,
@@ -99,16 +97,16 @@ pub(crate) async fn attempt_reconnects(
         let mut status = peer.status;
         let has_conn = transport.has_connection(&peer.agent_id).await;
 
-        if status == ConnectionStatus::Connected && !has_conn {
+        if status.is_connected() && !has_conn {
             peer_table.set_disconnected(&peer.agent_id).await;
             status = ConnectionStatus::Disconnected;
-        } else if status != ConnectionStatus::Connected && has_conn {
+        } else if !status.is_connected() && has_conn {
             peer_table.set_connected(&peer.agent_id, None).await;
             reconnect_state.remove(&peer.agent_id);
             continue;
         }
 
-        if status != ConnectionStatus::Connected {
+        if !status.is_connected() {
             reconnect_state
                 .entry(peer.agent_id)
                 .or_insert_with(|| ReconnectState::immediate(now));
@@ -136,11 +134,11 @@ pub(crate) async fn attempt_reconnects(
             continue;
         };
 
-        if peer.status == ConnectionStatus::Connected && transport.has_connection(&agent_id).await {
+        if peer.status.is_connected() && transport.has_connection(&agent_id).await {
             reconnect_state.remove(&agent_id);
             continue;
         }
-        if peer.status == ConnectionStatus::Connected {
+        if peer.status.is_connected() {
             peer_table.set_disconnected(&agent_id).await;
         }
 
@@ -149,31 +147,87 @@ pub(crate) async fn attempt_reconnects(
             state.in_flight = true;
         }
 
-        peer_table
-            .set_status(&agent_id, ConnectionStatus::Connecting)
-            .await;
-
-        let transport = transport.clone();
-        let cancel = cancel.clone();
-        let outcome_tx = outcome_tx.clone();
-        tokio::spawn(async move {
-            let connect_result = tokio::select! {
-                _ = cancel.cancelled() => Err(anyhow::anyhow!("cancelled")),
-                result = tokio::time::timeout(
-                    CONNECT_TIMEOUT,
-                    transport.ensure_connection(&peer),
-                ) => match result {
-                    Ok(inner) => inner,
-                    Err(_elapsed) => Err(anyhow::anyhow!("connection attempt timed out")),
-                },
-            };
-
-            let result = connect_result.map(|conn| conn.rtt().as_secs_f64() * 1000.0);
-            let _ = outcome_tx.send(ReconnectOutcome { agent_id, result }).await;
-        });
+        spawn_dial(peer, peer_table, transport, cancel, outcome_tx, None).await;
     }
 }
 
+/// Dial every peer in the table right away, at most `max_concurrent` at a
+/// time, instead of waiting for the reconnect tick. Outcomes are reported
+/// through `outcome_tx` like reconnect attempts; peers are marked in flight
+/// so the tick leaves them alone meanwhile. Returns how many dials started.
+pub(crate) async fn prewarm_connections(
+    peer_table: &PeerTable,
+    transport: &QuicTransport,
+    reconnect_state: &mut HashMap<AgentId, ReconnectState>,
+    cancel: &CancellationToken,
+    outcome_tx: &mpsc::Sender<ReconnectOutcome>,
+    max_concurrent: usize,
+) -> usize {
+    let now = Instant::now();
+    let limit = Arc::new(Semaphore::new(max_concurrent.max(1)));
+    let mut started = 0;
+    for peer in peer_table.list().await {
+        if peer.status.is_connected() || transport.has_connection(&peer.agent_id).await {
+            continue;
+        }
+        reconnect_state
+            .entry(peer.agent_id.clone())
+            .or_insert_with(|| ReconnectState::immediate(now))
+            .in_flight = true;
+        spawn_dial(
+            peer,
+            peer_table,
+            transport,
+            cancel,
+            outcome_tx,
+            Some(limit.clone()),
+        )
+        .await;
+        started += 1;
+    }
+    started
+}
+
+/// Mark `peer` connecting and dial it in a spawned task, bounded by its
+/// handshake timeout, once a `limit` permit (if any) is free.
+async fn spawn_dial(
+    peer: PeerRecord,
+    peer_table: &PeerTable,
+    transport: &QuicTransport,
+    cancel: &CancellationToken,
+    outcome_tx: &mpsc::Sender<ReconnectOutcome>,
+    limit: Option<Arc<Semaphore>>,
+) {
+    let agent_id = peer.agent_id.clone();
+    peer_table
+        .set_status(&agent_id, ConnectionStatus::Connecting)
+        .await;
+
+    let transport = transport.clone();
+    let cancel = cancel.clone();
+    let outcome_tx = outcome_tx.clone();
+    let connect_timeout = transport.handshake_timeout(&agent_id);
+    tokio::spawn(async move {
+        let _permit = match limit {
+            Some(limit) => limit.acquire_owned().await.ok(),
+            None => None,
+        };
+        let connect_result = tokio::select! {
+            _ = cancel.cancelled() => Err(anyhow::anyhow!("cancelled")),
+            result = tokio::time::timeout(
+                connect_timeout,
+                transport.ensure_connection(&peer),
+            ) => match result {
+                Ok(inner) => inner,
+                Err(_elapsed) => Err(anyhow::anyhow!("connection attempt timed out")),
+            },
+        };
+
+        let result = connect_result.map(|conn| conn.rtt().as_secs_f64() * 1000.0);
+        let _ = outcome_tx.send(ReconnectOutcome { agent_id, result }).await;
+    });
+}
+
 #[cfg(test)]
 #[path = "reconnect_tests.rs"]
 mod tests;
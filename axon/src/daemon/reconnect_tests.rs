@@ -136,3 +136,60 @@ async fn handle_reconnect_outcome_failure_clears_in_flight_and_backs_off() {
     let peer = peer_table.get(&agent_id).await.unwrap();
     assert_eq!(peer.status, ConnectionStatus::Disconnected);
 }
+
+#[tokio::test]
+async fn prewarm_dials_every_peer_and_marks_them_in_flight() {
+    use crate::config::AxonPaths;
+    use crate::identity::Identity;
+
+    let dir = tempfile::tempdir().unwrap();
+    let identity = |name: &str| {
+        Identity::load_or_generate(&AxonPaths::from_root(dir.path().join(name))).unwrap()
+    };
+    let (id_a, id_b) = (identity("a"), identity("b"));
+    let table_a = PeerTable::new();
+    let table_b = PeerTable::new();
+    table_b
+        .upsert_discovered(
+            id_a.agent_id().into(),
+            "127.0.0.1:1".parse().unwrap(),
+            id_a.public_key_base64().to_string(),
+        )
+        .await;
+    let localhost = "127.0.0.1:0".parse().unwrap();
+    let transport_b = QuicTransport::bind(localhost, &id_b, 128, table_b.pubkey_map())
+        .await
+        .unwrap();
+    table_a
+        .upsert_discovered(
+            id_b.agent_id().into(),
+            transport_b.local_addr().unwrap(),
+            id_b.public_key_base64().to_string(),
+        )
+        .await;
+    let transport_a = QuicTransport::bind(localhost, &id_a, 128, table_a.pubkey_map())
+        .await
+        .unwrap();
+
+    let mut reconnect_state = HashMap::new();
+    let (outcome_tx, mut outcome_rx) = reconnect_channel();
+    let started = prewarm_connections(
+        &table_a,
+        &transport_a,
+        &mut reconnect_state,
+        &CancellationToken::new(),
+        &outcome_tx,
+        1,
+    )
+    .await;
+
+    assert_eq!(started, 1);
+    assert!(reconnect_state[id_b.agent_id()].in_flight);
+    let outcome = tokio::time::timeout(Duration::from_secs(5), outcome_rx.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(outcome.agent_id.as_str(), id_b.agent_id());
+    assert!(outcome.result.is_ok());
+    assert!(transport_a.has_connection(id_b.agent_id()).await);
+}
@@ -0,0 +1,370 @@
+use std::collections::{HashMap, HashSet};
+use std::net::IpAddr;
+use std::path::Path;
+use std::time::Instant;
+
+use anyhow::Result;
+use tracing::{error, info, warn};
+
+use super::audit::AuditEvent;
+use super::command_handler::DaemonContext;
+use super::peer_events::StaticDiscovery;
+use super::reconnect::ReconnectState;
+use super::revocation::reload_revocations;
+use crate::config::{
+    Config, CongestionControl, HandshakeBanConfig, IdempotencyConfig, MaxMessageBytesConfig,
+    PolicyConfig, RateLimitConfig, StalenessConfig, StaticPeerConfig, TransportConfig,
+};
+use crate::ipc::{DaemonReply, IpcErrorCode, IpcServer};
+use crate::message::AgentId;
+use crate::peer_table::{PeerSource, PeerTable, StalePolicy};
+use crate::transport::{
+    Congestion, CongestionController, HandshakeBanLimit, InboundRateLimit, KindPolicy,
+    MessageSizeLimits, PeerTuning, QuicTransport, ResponseCacheLimit,
+};
+
+/// Why a config reload was requested.
+#[derive(Debug)]
+pub(crate) enum ReloadTrigger {
+    /// SIGHUP; there is no one to reply to.
+    Signal,
+    /// IPC `reload` command; the outcome is replied to `client_id`.
+    Ipc {
+        client_id: u64,
+        req_id: Option<String>,
+    },
+}
+
+/// Handle one reload request: re-read `revocations.json` and `config.yaml`,
+/// apply what can change live, and reply if it came over IPC.
+pub(crate) async fn handle_reload(
+    trigger: ReloadTrigger,
+    ctx: &DaemonContext<'_>,
+    config_path: &Path,
+    cli_port: Option<u16>,
+    config: &mut Config,
+    reconnect_state: &mut HashMap<AgentId, ReconnectState>,
+    static_discovery: &mut StaticDiscovery,
+) {
+    match reload_revocations(ctx.revocations_path, ctx.peer_table, ctx.transport).await {
+        Ok(removed) => {
+            if let Some(audit) = ctx.audit {
+                let ids: Vec<_> = removed.into_iter().map(|peer| peer.agent_id).collect();
+                audit
+                    .record_peers(AuditEvent::PeerRemove, &ids, "revoked")
+                    .await;
+            }
+        }
+        Err(err) => warn!(error = %err, "revocation reload failed; keeping previous list"),
+    }
+    let outcome = reload_config(
+        config_path,
+        cli_port,
+        config,
+        ctx.peer_table,
+        ctx.ipc,
+        ctx.transport,
+        reconnect_state,
+    )
+    .await;
+    match &outcome {
+        Ok(changes) => {
+            if let Some(audit) = ctx.audit {
+                audit.record_reload(changes).await;
+            }
+            static_discovery.restart(config.persisted_peers.clone());
+            ctx.peer_tags.set(&config.tags);
+            ctx.transforms.set(&config.transforms);
+            if let Err(err) = ctx.known_peers.persist(ctx.peer_table, ctx.health).await {
+                warn!(error = %err, "failed to persist known peers after config reload");
+            }
+            info!("config reloaded");
+        }
+        Err(err) => warn!(error = %err, "config reload failed; keeping previous config"),
+    }
+    if let ReloadTrigger::Ipc { client_id, req_id } = trigger
+        && let Err(err) = ctx
+            .ipc
+            .send_reply(client_id, &reload_reply(&outcome, req_id))
+            .await
+    {
+        error!(error = %err, "failed replying to IPC reload command");
+    }
+}
+
+/// Apply the transport settings from `config` at startup. `reload_config`
+/// re-applies the ones that can change live.
+pub(crate) fn configure_transport(transport: &QuicTransport, config: &Config) {
+    transport.set_inbound_rate_limit(inbound_rate_limit(&config.rate_limit));
+    transport.set_message_size_limits(message_size_limits(&config.max_message_bytes));
+    transport.set_kind_policy(kind_policy(&config.policy));
+    transport.set_peer_tuning(peer_tuning(config));
+    transport.set_congestion(congestion(&config.transport));
+    transport.set_response_cache(response_cache_limit(&config.idempotency));
+    transport.set_handshake_bans(handshake_ban_limit(&config.handshake_ban));
+    transport.set_handshake_ban_exempt(handshake_ban_exempt(&config.peers));
+}
+
+/// Peer table changes applied by a reload.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct StaticPeerChanges {
+    pub(crate) added: Vec<AgentId>,
+    pub(crate) removed: Vec<AgentId>,
+    pub(crate) updated: Vec<AgentId>,
+}
+
+/// Reconcile the peer table with a freshly loaded static peer list.
+///
+/// Peers in `next` are inserted, or overwritten when their address or pubkey
+/// differs from the table entry. Peers that were in `previous` but are absent
+/// from `next` are removed if still static. Static peers that never came from
+/// config (e.g. enrolled via `add_peer`) are left alone. Live connections are
+/// not touched; peers that were added or changed get an immediate reconnect.
+pub(crate) async fn apply_static_peers(
+    previous: &[StaticPeerConfig],
+    next: &[StaticPeerConfig],
+    peer_table: &PeerTable,
+    reconnect_state: &mut HashMap<AgentId, ReconnectState>,
+) -> StaticPeerChanges {
+    let now = Instant::now();
+    let mut changes = StaticPeerChanges::default();
+
+    for peer in next {
+        if peer_table.is_refused(&peer.pubkey) {
+            continue;
+        }
+        match peer_table.get(peer.agent_id.as_str()).await {
+            Some(existing)
+                if existing.source == PeerSource::Static
+                    && existing.addr == peer.addr
+                    && existing.pubkey == peer.pubkey => {}
+            Some(_) => {
+                peer_table.upsert_static(peer).await;
+                reconnect_state.insert(peer.agent_id.clone(), ReconnectState::immediate(now));
+                changes.updated.push(peer.agent_id.clone());
+            }
+            None => {
+                peer_table.upsert_static(peer).await;
+                reconnect_state.insert(peer.agent_id.clone(), ReconnectState::immediate(now));
+                changes.added.push(peer.agent_id.clone());
+            }
+        }
+    }
+
+    let retained: HashSet<&str> = next.iter().map(|p| p.agent_id.as_str()).collect();
+    for peer in previous {
+        if retained.contains(peer.agent_id.as_str()) {
+            continue;
+        }
+        let is_static = matches!(
+            peer_table
+                .get(peer.agent_id.as_str())
+                .await
+                .map(|p| p.source),
+            Some(PeerSource::Static)
+        );
+        if is_static {
+            peer_table.remove(peer.agent_id.as_str()).await;
+            reconnect_state.remove(&peer.agent_id);
+            changes.removed.push(peer.agent_id.clone());
+        }
+    }
+
+    info!(
+        added = changes.added.len(),
+        removed = changes.removed.len(),
+        updated = changes.updated.len(),
+        "applied static peer changes from config reload"
+    );
+    changes
+}
+
+/// Re-read `config.yaml` and apply it to the running daemon.
+///
+/// Static peers, IPC client limits, inbound rate limits, message size caps,
+/// the per-peer kind policy, the idempotency window, handshake bans, and
+/// staleness TTLs take effect immediately; congestion control and per-peer
+/// connection overrides apply to connections made after the reload. `port`
+/// and `name` are bound at startup; changes to them are logged and otherwise
+/// ignored until restart. On error `current` is left untouched.
+pub(crate) async fn reload_config(
+    config_path: &Path,
+    cli_port: Option<u16>,
+    current: &mut Config,
+    peer_table: &PeerTable,
+    ipc: &IpcServer,
+    transport: &QuicTransport,
+    reconnect_state: &mut HashMap<AgentId, ReconnectState>,
+) -> Result<StaticPeerChanges> {
+    let next = Config::load_profile(config_path, current.profile.as_deref()).await?;
+
+    if next.effective_port(cli_port) != current.effective_port(cli_port) {
+        warn!("config reload: port change requires a daemon restart; keeping current port");
+    }
+    if next.name != current.name {
+        warn!("config reload: name change requires a daemon restart; keeping current name");
+    }
+    if next.history != current.history {
+        warn!("config reload: history change requires a daemon restart; keeping current history");
+    }
+    if next.audit != current.audit {
+        warn!("config reload: audit change requires a daemon restart; keeping current audit log");
+    }
+    if next.event_log != current.event_log {
+        warn!(
+            "config reload: event_log change requires a daemon restart; keeping current event log"
+        );
+    }
+    if next.heartbeat != current.heartbeat {
+        warn!(
+            "config reload: heartbeat change requires a daemon restart; keeping current interval"
+        );
+    }
+    if next.prewarm != current.prewarm {
+        warn!("config reload: prewarm only applies at daemon startup");
+    }
+    if next.handler != current.handler {
+        warn!("config reload: handler change requires a daemon restart; keeping current handler");
+    }
+
+    let changes =
+        apply_static_peers(&current.peers, &next.peers, peer_table, reconnect_state).await;
+    ipc.set_client_limits(next.ipc.idle_timeout(), next.ipc.max_lifetime())
+        .await;
+    transport.set_inbound_rate_limit(inbound_rate_limit(&next.rate_limit));
+    transport.set_message_size_limits(message_size_limits(&next.max_message_bytes));
+    transport.set_kind_policy(kind_policy(&next.policy));
+    transport.set_peer_tuning(peer_tuning(&next));
+    if next.transport != current.transport {
+        transport.set_congestion(congestion(&next.transport));
+    }
+    if next.idempotency != current.idempotency {
+        transport.set_response_cache(response_cache_limit(&next.idempotency));
+    }
+    if next.handshake_ban != current.handshake_ban {
+        transport.set_handshake_bans(handshake_ban_limit(&next.handshake_ban));
+    }
+    transport.set_handshake_ban_exempt(handshake_ban_exempt(&next.peers));
+    peer_table.set_stale_policy(stale_policy(&next.staleness));
+
+    *current = next;
+    Ok(changes)
+}
+
+pub(crate) fn inbound_rate_limit(config: &RateLimitConfig) -> InboundRateLimit {
+    InboundRateLimit {
+        messages_per_sec: config.messages_per_sec(),
+        bytes_per_sec: config.bytes_per_sec(),
+    }
+}
+
+/// Connection overrides of the static peers that set any.
+pub(crate) fn peer_tuning(config: &Config) -> HashMap<String, PeerTuning> {
+    config
+        .persisted_peers
+        .iter()
+        .filter(|peer| !peer.tuning.is_default())
+        .map(|peer| {
+            let tuning = &peer.tuning;
+            let transport_tuning = PeerTuning {
+                handshake_timeout: tuning.handshake_timeout(),
+                idle_timeout: tuning.idle_timeout(),
+                max_message_bytes: tuning.max_message_bytes(),
+                congestion_controller: tuning.congestion_control.map(congestion_controller),
+                initial_window: tuning.initial_window(),
+            };
+            (peer.agent_id.to_string(), transport_tuning)
+        })
+        .collect()
+}
+
+pub(crate) fn congestion(config: &TransportConfig) -> Congestion {
+    Congestion {
+        controller: config
+            .congestion_control
+            .map(congestion_controller)
+            .unwrap_or_default(),
+        initial_window: config.initial_window(),
+    }
+}
+
+fn congestion_controller(control: CongestionControl) -> CongestionController {
+    match control {
+        CongestionControl::Cubic => CongestionController::Cubic,
+        CongestionControl::NewReno => CongestionController::NewReno,
+        CongestionControl::Bbr => CongestionController::Bbr,
+    }
+}
+
+pub(crate) fn message_size_limits(config: &MaxMessageBytesConfig) -> MessageSizeLimits {
+    MessageSizeLimits {
+        request: MaxMessageBytesConfig::effective(config.request),
+        response: MaxMessageBytesConfig::effective(config.response),
+        message: MaxMessageBytesConfig::effective(config.message),
+        error: MaxMessageBytesConfig::effective(config.error),
+    }
+}
+
+pub(crate) fn kind_policy(config: &PolicyConfig) -> KindPolicy {
+    let mut policy = KindPolicy::default();
+    for (agent_id, peer) in &config.peers {
+        policy.allow(agent_id.as_str(), peer.kinds());
+    }
+    policy
+}
+
+pub(crate) fn response_cache_limit(config: &IdempotencyConfig) -> ResponseCacheLimit {
+    ResponseCacheLimit {
+        window: config.window(),
+        max_entries: config.max_entries(),
+    }
+}
+
+pub(crate) fn handshake_ban_limit(config: &HandshakeBanConfig) -> HandshakeBanLimit {
+    HandshakeBanLimit {
+        max_failures: config.max_failures(),
+        window: config.window(),
+        ban: config.ban(),
+        max_ban: config.max_ban(),
+    }
+}
+
+/// Source IPs of static peers, which handshake bans never apply to.
+pub(crate) fn handshake_ban_exempt(peers: &[StaticPeerConfig]) -> HashSet<IpAddr> {
+    peers
+        .iter()
+        .map(|peer| peer.addr.ip().to_canonical())
+        .collect()
+}
+
+pub(crate) fn stale_policy(config: &StalenessConfig) -> StalePolicy {
+    StalePolicy {
+        discovered: config.discovered_ttl(),
+        cached: config.cached_ttl(),
+    }
+}
+
+/// IPC reply for a `reload` command.
+pub(crate) fn reload_reply(
+    outcome: &Result<StaticPeerChanges>,
+    req_id: Option<String>,
+) -> DaemonReply {
+    match outcome {
+        Ok(changes) => DaemonReply::Reload {
+            ok: true,
+            peers_added: changes.added.len(),
+            peers_removed: changes.removed.len(),
+            peers_updated: changes.updated.len(),
+            req_id,
+        },
+        Err(err) => DaemonReply::error_with_details(
+            IpcErrorCode::ReloadFailed,
+            serde_json::json!({ "reason": format!("{err:#}") }),
+            req_id,
+        ),
+    }
+}
+
+#[cfg(test)]
+#[path = "reload_tests.rs"]
+mod tests;
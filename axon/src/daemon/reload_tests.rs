@@ -0,0 +1,109 @@
+use super::*;
+use crate::config::StaticPeerConfig;
+use crate::peer_table::{PeerSource, PeerTable};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+
+fn addr(value: &str) -> SocketAddr {
+    value.parse().expect("socket addr")
+}
+
+fn static_peer(agent_id: &str, peer_addr: &str, pubkey: &str) -> StaticPeerConfig {
+    StaticPeerConfig {
+        agent_id: agent_id.into(),
+        addr: addr(peer_addr),
+        pubkey: pubkey.to_string(),
+    }
+}
+
+const PEER_A: &str = "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+const PEER_B: &str = "ed25519.bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb";
+const PEER_C: &str = "ed25519.cccccccccccccccccccccccccccccccc";
+
+#[tokio::test]
+async fn reload_adds_removes_and_updates_static_peers() {
+    let table = PeerTable::new();
+    let previous = vec![
+        static_peer(PEER_A, "127.0.0.1:7100", "YQ=="),
+        static_peer(PEER_B, "127.0.0.1:7101", "Yg=="),
+    ];
+    for peer in &previous {
+        table.upsert_static(peer).await;
+    }
+    let next = vec![
+        static_peer(PEER_A, "127.0.0.1:7100", "YQ=="),
+        static_peer(PEER_B, "127.0.0.1:7201", "Yg=="),
+        static_peer(PEER_C, "127.0.0.1:7102", "Yw=="),
+    ];
+
+    let mut reconnect_state = HashMap::new();
+    let changes = apply_static_peers(&previous, &next, &table, &mut reconnect_state).await;
+    assert_eq!(changes.added, vec![AgentId::from(PEER_C)]);
+    assert_eq!(changes.updated, vec![AgentId::from(PEER_B)]);
+    assert!(changes.removed.is_empty());
+    assert_eq!(
+        table.get(PEER_B).await.expect("peer b").addr,
+        addr("127.0.0.1:7201")
+    );
+    assert!(reconnect_state.contains_key(PEER_B));
+    assert!(reconnect_state.contains_key(PEER_C));
+    assert!(!reconnect_state.contains_key(PEER_A));
+
+    let changes = apply_static_peers(&next, &next[..1], &table, &mut reconnect_state).await;
+    assert_eq!(
+        changes.removed,
+        vec![AgentId::from(PEER_B), AgentId::from(PEER_C)]
+    );
+    assert!(table.get(PEER_B).await.is_none());
+    assert!(table.get(PEER_C).await.is_none());
+    assert!(!reconnect_state.contains_key(PEER_B));
+}
+
+#[tokio::test]
+async fn reload_keeps_static_peers_not_sourced_from_config() {
+    let table = PeerTable::new();
+    // Enrolled at runtime via `add_peer`, never part of config.yaml.
+    let enrolled = static_peer(PEER_A, "127.0.0.1:7100", "YQ==");
+    table.upsert_static(&enrolled).await;
+
+    let mut reconnect_state = HashMap::new();
+    let changes = apply_static_peers(&[], &[], &table, &mut reconnect_state).await;
+    assert_eq!(changes, StaticPeerChanges::default());
+    assert!(table.get(PEER_A).await.is_some());
+}
+
+#[tokio::test]
+async fn reload_promotes_discovered_peer_to_static() {
+    let table = PeerTable::new();
+    table
+        .upsert_discovered(PEER_A.into(), addr("127.0.0.1:7100"), "YQ==".to_string())
+        .await;
+
+    let next = vec![static_peer(PEER_A, "127.0.0.1:7100", "YQ==")];
+    let mut reconnect_state = HashMap::new();
+    let changes = apply_static_peers(&[], &next, &table, &mut reconnect_state).await;
+    assert_eq!(changes.updated, vec![AgentId::from(PEER_A)]);
+    assert_eq!(
+        table.get(PEER_A).await.expect("peer a").source,
+        PeerSource::Static
+    );
+}
+
+#[test]
+fn congestion_defaults_to_cubic_and_maps_the_configured_controller() {
+    assert_eq!(
+        congestion(&TransportConfig::default()),
+        Congestion::default()
+    );
+    let configured = TransportConfig {
+        congestion_control: Some(CongestionControl::Bbr),
+        initial_window_bytes: Some(65536),
+    };
+    assert_eq!(
+        congestion(&configured),
+        Congestion {
+            controller: CongestionController::Bbr,
+            initial_window: Some(65536),
+        }
+    );
+}
@@ -0,0 +1,84 @@
+use std::path::Path;
+
+use anyhow::Result;
+use tracing::{info, warn};
+
+use super::audit::{AuditEvent, AuditRecord};
+use super::command_handler::DaemonContext;
+use crate::config::{RevokedKey, add_revocation, load_revocations, revoked_pubkeys};
+use crate::ipc::{DaemonReply, IpcErrorCode};
+use crate::message::{AgentId, now_millis};
+use crate::peer_table::{PeerRecord, PeerTable};
+use crate::peer_token::derive_agent_id_from_pubkey_base64;
+use crate::transport::QuicTransport;
+
+/// Re-read `revocations.json` and apply it to the peer table and the TLS
+/// verifiers. Peers holding a newly revoked key are removed (the transport
+/// closes their connections) and returned so the caller can audit them.
+pub(crate) async fn reload_revocations(
+    path: &Path,
+    peer_table: &PeerTable,
+    transport: &QuicTransport,
+) -> Result<Vec<PeerRecord>> {
+    let keys = revoked_pubkeys(&load_revocations(path).await?);
+    transport.set_revoked_keys(keys.clone());
+    Ok(peer_table.set_revoked(keys).await)
+}
+
+/// Handle IPC `revoke`: persist the key to the revocation file, then drop
+/// any peer holding it and close its connections. Exactly one of `pubkey` and
+/// `agent_id` must be set; `agent_id` must name a peer in the table.
+pub(crate) async fn revoke_reply(
+    ctx: &DaemonContext<'_>,
+    pubkey: Option<String>,
+    agent_id: Option<String>,
+    req_id: Option<String>,
+) -> DaemonReply {
+    let (agent_id, pubkey): (AgentId, String) = match (pubkey, agent_id) {
+        (Some(pubkey), None) => match derive_agent_id_from_pubkey_base64(&pubkey) {
+            Ok(agent_id) => (agent_id, pubkey.trim().to_string()),
+            Err(_) => return DaemonReply::error(IpcErrorCode::InvalidCommand, req_id),
+        },
+        (None, Some(agent_id)) => match ctx.peer_table.get(&agent_id).await {
+            Some(peer) => (peer.agent_id, peer.pubkey),
+            None => return DaemonReply::error(IpcErrorCode::PeerNotFound, req_id),
+        },
+        _ => return DaemonReply::error(IpcErrorCode::InvalidCommand, req_id),
+    };
+    if agent_id == *ctx.local_agent_id {
+        return DaemonReply::error(IpcErrorCode::InvalidCommand, req_id);
+    }
+
+    let entry = RevokedKey {
+        agent_id: agent_id.clone(),
+        pubkey: pubkey.clone(),
+        revoked_at_unix_ms: now_millis(),
+    };
+    if let Err(err) = add_revocation(ctx.revocations_path, entry).await {
+        warn!(error = %err, "failed to persist revocation");
+        return DaemonReply::error(IpcErrorCode::InternalError, req_id);
+    }
+
+    let removed = ctx.peer_table.revoke(&pubkey).await;
+    ctx.transport
+        .set_revoked_keys(ctx.peer_table.revoked_keys());
+    for peer in &removed {
+        if let Some(audit) = ctx.audit {
+            audit
+                .record(
+                    AuditRecord::peer(AuditEvent::PeerRemove, peer.agent_id.as_str())
+                        .with_reason("revoked"),
+                )
+                .await;
+        }
+    }
+    info!(agent_id = %agent_id, removed = removed.len(), "revoked peer key");
+
+    DaemonReply::Revoke {
+        ok: true,
+        agent_id: agent_id.to_string(),
+        pubkey,
+        removed: !removed.is_empty(),
+        req_id,
+    }
+}
@@ -0,0 +1,197 @@
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use rhai::{AST, Dynamic, Engine, EvalAltResult, Scope};
+use serde_json::Value;
+use tokio::sync::Semaphore;
+use tracing::{debug, warn};
+
+use crate::config::{HANDLER_SCRIPT_ENTRY, HandlerConfig, compile_handler_script};
+use crate::errors::WireErrorCode;
+use crate::message::{Envelope, ErrorPayload, MAX_MESSAGE_SIZE, MessageKind};
+use crate::transport::ResponseHandlerFn;
+
+/// Largest string, array, or object map a script may build.
+const MAX_SCRIPT_VALUE: usize = MAX_MESSAGE_SIZE as usize;
+
+/// Answers inbound `request`s with the `handle(request)` function of a Rhai
+/// script. The request envelope is passed as an object map; the function
+/// returns an object map (the `response` payload), `reject(...)` (an `error`
+/// reply), or `()` to leave the request to the next handler.
+pub(crate) struct ScriptHandler {
+    ast: Arc<AST>,
+    timeout: Duration,
+    permits: Arc<Semaphore>,
+    local_agent_id: String,
+}
+
+/// Value returned by the script's `reject(message)` and
+/// `reject(code, message)` functions.
+#[derive(Debug, Clone)]
+struct Rejection {
+    code: String,
+    message: String,
+}
+
+impl ScriptHandler {
+    pub(crate) fn load(
+        path: &Path,
+        config: &HandlerConfig,
+        local_agent_id: String,
+    ) -> Result<Self> {
+        let ast = compile_handler_script(&engine(None), path)?;
+        Ok(Self {
+            ast: Arc::new(ast),
+            timeout: config.timeout(),
+            permits: Arc::new(Semaphore::new(config.max_concurrent())),
+            local_agent_id,
+        })
+    }
+
+    /// Wrap as a transport response handler.
+    pub(crate) fn into_response_handler(self) -> ResponseHandlerFn {
+        let handler = Arc::new(self);
+        Arc::new(move |request: Arc<Envelope>| {
+            let handler = handler.clone();
+            Box::pin(async move { handler.respond(&request).await })
+        })
+    }
+
+    /// The reply to `request`, or `None` when the script passes it on.
+    pub(crate) async fn respond(&self, request: &Envelope) -> Option<Envelope> {
+        let result = match self.permits.clone().try_acquire_owned() {
+            Ok(_permit) => self.run(request).await,
+            Err(_) => Err(ErrorPayload::new(
+                WireErrorCode::HandlerBusy,
+                "all request handler slots are in use; retry shortly",
+            )),
+        };
+        let payload = match result {
+            Ok(Some(payload)) => return Some(self.reply(request, MessageKind::Response, payload)),
+            Ok(None) => {
+                debug!(msg_id = %request.id, "handler script passed on request");
+                return None;
+            }
+            Err(payload) => payload,
+        };
+        if payload.wire_code() != Some(WireErrorCode::Rejected) {
+            warn!(
+                msg_id = %request.id,
+                code = %payload.code,
+                message = %payload.message,
+                "request handler script failed"
+            );
+        }
+        Some(self.reply(request, MessageKind::Error, payload.to_value()))
+    }
+
+    fn reply(&self, request: &Envelope, kind: MessageKind, payload: Value) -> Envelope {
+        Envelope::response_to(request, self.local_agent_id.clone(), kind, payload)
+    }
+
+    async fn run(&self, request: &Envelope) -> Result<Option<Value>, ErrorPayload> {
+        let input = serde_json::to_value(request)
+            .ok()
+            .and_then(|value| rhai::serde::to_dynamic(value).ok())
+            .ok_or_else(|| {
+                ErrorPayload::new(WireErrorCode::HandlerFailed, "failed to encode request")
+            })?;
+        let ast = self.ast.clone();
+        let timeout = self.timeout;
+        let output = tokio::task::spawn_blocking(move || {
+            engine(Some(Instant::now() + timeout)).call_fn::<Dynamic>(
+                &mut Scope::new(),
+                &ast,
+                HANDLER_SCRIPT_ENTRY,
+                (input,),
+            )
+        })
+        .await
+        .map_err(|err| {
+            ErrorPayload::new(
+                WireErrorCode::HandlerFailed,
+                format!("handler script panicked: {err}"),
+            )
+        })?;
+
+        match output {
+            Ok(value) => interpret(value),
+            Err(err) => Err(match *err {
+                EvalAltResult::ErrorTerminated(..) => ErrorPayload::new(
+                    WireErrorCode::HandlerTimeout,
+                    format!(
+                        "request handler did not finish within {}s",
+                        timeout.as_secs()
+                    ),
+                ),
+                err => ErrorPayload::new(
+                    WireErrorCode::HandlerFailed,
+                    format!("handler script error: {err}"),
+                ),
+            }),
+        }
+    }
+}
+
+/// Map what `handle` returned to a response payload, a pass, or an error.
+fn interpret(value: Dynamic) -> Result<Option<Value>, ErrorPayload> {
+    if value.is_unit() {
+        return Ok(None);
+    }
+    if let Some(rejection) = value.clone().try_cast::<Rejection>() {
+        let payload = match WireErrorCode::parse(&rejection.code) {
+            Some(code) => ErrorPayload::new(code, rejection.message),
+            None => ErrorPayload {
+                code: rejection.code,
+                message: rejection.message,
+                retryable: false,
+                details: None,
+            },
+        };
+        return Err(payload);
+    }
+    let invalid = || {
+        ErrorPayload::new(
+            WireErrorCode::HandlerInvalidOutput,
+            format!("{HANDLER_SCRIPT_ENTRY}() must return an object map, (), or reject(...)"),
+        )
+    };
+    if !value.is_map() {
+        return Err(invalid());
+    }
+    match rhai::serde::from_dynamic::<Value>(&value) {
+        Ok(payload @ Value::Object(_)) => Ok(Some(payload)),
+        Ok(_) | Err(_) => Err(invalid()),
+    }
+}
+
+/// A sandboxed engine: no module imports, bounded value sizes, and, with a
+/// `deadline`, terminated once it passes.
+fn engine(deadline: Option<Instant>) -> Engine {
+    let mut engine = Engine::new();
+    engine.set_module_resolver(rhai::module_resolvers::DummyModuleResolver::new());
+    engine.set_max_string_size(MAX_SCRIPT_VALUE);
+    engine.set_max_array_size(MAX_SCRIPT_VALUE);
+    engine.set_max_map_size(MAX_SCRIPT_VALUE);
+    engine.on_print(|text| debug!(output = text, "handler script print"));
+    engine.on_debug(|text, _, pos| debug!(output = text, %pos, "handler script debug"));
+    engine.register_type_with_name::<Rejection>("Rejection");
+    engine.register_fn("reject", |message: &str| Rejection {
+        code: WireErrorCode::Rejected.as_str().to_string(),
+        message: message.to_string(),
+    });
+    engine.register_fn("reject", |code: &str, message: &str| Rejection {
+        code: code.to_string(),
+        message: message.to_string(),
+    });
+    if let Some(deadline) = deadline {
+        engine.on_progress(move |_| (Instant::now() >= deadline).then_some(Dynamic::UNIT));
+    }
+    engine
+}
+
+#[cfg(test)]
+#[path = "script_handler_tests.rs"]
+mod tests;
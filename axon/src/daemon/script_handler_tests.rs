@@ -0,0 +1,127 @@
+use super::*;
+use serde_json::json;
+use std::path::PathBuf;
+
+const LOCAL_ID: &str = "ed25519.bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb";
+const PEER_ID: &str = "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+
+fn handler(dir: &Path, source: &str, timeout_secs: u64) -> ScriptHandler {
+    let path = dir.join("handler.rhai");
+    std::fs::write(&path, source).unwrap();
+    let config = HandlerConfig {
+        script: Some(path.clone()),
+        timeout_secs: Some(timeout_secs),
+        ..HandlerConfig::default()
+    };
+    ScriptHandler::load(&path, &config, LOCAL_ID.to_string()).unwrap()
+}
+
+fn request(payload: Value) -> Envelope {
+    Envelope::new(
+        PEER_ID.to_string(),
+        LOCAL_ID.to_string(),
+        MessageKind::Request,
+        payload,
+    )
+}
+
+fn payload(envelope: &Envelope) -> Value {
+    envelope.payload_value().unwrap()
+}
+
+const ROUTER: &str = r#"
+fn handle(request) {
+    switch request.payload.op {
+        "ping" => #{ answer: "pong", from: request.from },
+        "delete" => reject("deletes are not accepted here"),
+        "admin" => reject("not_authorized", "admin ops need a human"),
+        _ => ()
+    }
+}
+"#;
+
+#[tokio::test]
+async fn map_becomes_response_and_unit_passes_on() {
+    let dir = tempfile::tempdir().unwrap();
+    let handler = handler(dir.path(), ROUTER, 5);
+    let request = request(json!({"op": "ping"}));
+
+    let response = handler.respond(&request).await.expect("answered");
+    assert_eq!(response.kind, MessageKind::Response);
+    assert_eq!(response.ref_id, Some(request.id));
+    assert_eq!(
+        payload(&response),
+        json!({"answer": "pong", "from": PEER_ID})
+    );
+
+    assert!(
+        handler
+            .respond(&self::request(json!({"op": "other"})))
+            .await
+            .is_none()
+    );
+}
+
+#[tokio::test]
+async fn reject_maps_to_error_with_chosen_code() {
+    let dir = tempfile::tempdir().unwrap();
+    let handler = handler(dir.path(), ROUTER, 5);
+
+    let response = handler
+        .respond(&request(json!({"op": "delete"})))
+        .await
+        .unwrap();
+    assert_eq!(response.kind, MessageKind::Error);
+    assert_eq!(payload(&response)["code"], "rejected");
+    assert_eq!(
+        payload(&response)["message"],
+        "deletes are not accepted here"
+    );
+    assert_eq!(payload(&response)["retryable"], false);
+
+    let response = handler
+        .respond(&request(json!({"op": "admin"})))
+        .await
+        .unwrap();
+    assert_eq!(payload(&response)["code"], "not_authorized");
+}
+
+#[tokio::test]
+async fn runaway_and_invalid_scripts_map_to_handler_errors() {
+    let dir = tempfile::tempdir().unwrap();
+    let looping = handler(dir.path(), "fn handle(request) { loop {} }", 1);
+    let response = looping.respond(&request(json!({}))).await.unwrap();
+    assert_eq!(payload(&response)["code"], "handler_timeout");
+
+    let scalar = handler(dir.path(), "fn handle(request) { 42 }", 5);
+    let response = scalar.respond(&request(json!({}))).await.unwrap();
+    assert_eq!(payload(&response)["code"], "handler_invalid_output");
+
+    let throwing = handler(dir.path(), r#"fn handle(request) { throw "boom" }"#, 5);
+    let response = throwing.respond(&request(json!({}))).await.unwrap();
+    assert_eq!(payload(&response)["code"], "handler_failed");
+
+    let importing = handler(
+        dir.path(),
+        r#"fn handle(request) { import "helpers" as helpers; #{} }"#,
+        5,
+    );
+    let response = importing.respond(&request(json!({}))).await.unwrap();
+    assert_eq!(payload(&response)["code"], "handler_failed");
+}
+
+#[test]
+fn load_requires_a_readable_script_with_handle() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("handler.rhai");
+    let config = HandlerConfig::default();
+
+    std::fs::write(&path, "fn answer(request) { #{} }").unwrap();
+    let err = ScriptHandler::load(&path, &config, LOCAL_ID.to_string())
+        .err()
+        .expect("missing handle");
+    assert!(err.to_string().contains("handle(request)"), "{err}");
+
+    let missing = PathBuf::from("/nonexistent/handler.rhai");
+    assert!(ScriptHandler::load(&missing, &config, LOCAL_ID.to_string()).is_err());
+}
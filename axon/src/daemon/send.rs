@@ -0,0 +1,250 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use serde_json::json;
+
+use super::audit::{AuditEvent, AuditRecord};
+use super::command_handler::DaemonContext;
+use super::event_log::{Event, EventRecord};
+use crate::ipc::{DaemonReply, HistoryDirection, IpcErrorCode, IpcSendKind};
+use crate::message::Envelope;
+use crate::transport::REQUEST_TIMEOUT;
+
+#[derive(Debug)]
+pub(crate) enum DaemonIpcError {
+    PeerNotFound,
+    SelfSend,
+    PeerUnreachable,
+    Timeout,
+    InvalidCommand(String),
+}
+
+impl std::fmt::Display for DaemonIpcError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DaemonIpcError::PeerNotFound => write!(f, "peer_not_found"),
+            DaemonIpcError::SelfSend => write!(f, "self_send"),
+            DaemonIpcError::PeerUnreachable => write!(f, "peer_unreachable"),
+            DaemonIpcError::Timeout => write!(f, "timeout"),
+            DaemonIpcError::InvalidCommand(msg) => write!(f, "invalid_command: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for DaemonIpcError {}
+
+/// The IPC error reply for a failed `send`.
+pub(crate) fn send_error_reply(err: &anyhow::Error, req_id: Option<String>) -> DaemonReply {
+    match err.downcast_ref::<DaemonIpcError>() {
+        Some(DaemonIpcError::InvalidCommand(reason)) => DaemonReply::error_with_details(
+            IpcErrorCode::InvalidCommand,
+            json!({ "reason": reason }),
+            req_id,
+        ),
+        Some(e) => {
+            let error_code = match e {
+                DaemonIpcError::PeerNotFound => IpcErrorCode::PeerNotFound,
+                DaemonIpcError::SelfSend => IpcErrorCode::SelfSend,
+                DaemonIpcError::PeerUnreachable => IpcErrorCode::PeerUnreachable,
+                DaemonIpcError::Timeout => IpcErrorCode::Timeout,
+                DaemonIpcError::InvalidCommand(_) => IpcErrorCode::InvalidCommand,
+            };
+            DaemonReply::error(error_code, req_id)
+        }
+        None => DaemonReply::error(IpcErrorCode::InternalError, req_id),
+    }
+}
+
+const MAX_IDEMPOTENCY_KEY_LEN: usize = 256;
+
+/// Build, validate, and deliver one envelope for IPC `send` from
+/// `client_id`; returns its ID and the peer's reply for a request.
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn handle_send(
+    ctx: &DaemonContext<'_>,
+    client_id: u64,
+    to: String,
+    kind: IpcSendKind,
+    payload: serde_json::Value,
+    timeout_secs: Option<u64>,
+    ref_id: Option<uuid::Uuid>,
+    idempotency_key: Option<String>,
+    thread_id: Option<String>,
+    headers: serde_json::Map<String, serde_json::Value>,
+) -> Result<(uuid::Uuid, Option<crate::message::Envelope>)> {
+    if to == ctx.local_agent_id.as_str() {
+        anyhow::bail!(DaemonIpcError::SelfSend);
+    }
+
+    let peer = ctx
+        .peer_table
+        .get(&to)
+        .await
+        .ok_or_else(|| anyhow::anyhow!(DaemonIpcError::PeerNotFound))?;
+
+    let mut envelope = Envelope::new(
+        (*ctx.local_agent_id).clone(),
+        to.clone(),
+        kind.as_message_kind(),
+        payload,
+    );
+    envelope.ref_id = ref_id;
+    envelope.thread_id =
+        thread_id.or_else(|| ref_id.and_then(|ref_id| ctx.threads.thread_of(&ref_id)));
+    envelope.headers = headers;
+    if let Some(key) = idempotency_key {
+        if !matches!(kind, IpcSendKind::Request) {
+            anyhow::bail!(DaemonIpcError::InvalidCommand(
+                "idempotency_key is only valid for request kind".to_string()
+            ));
+        }
+        if key.is_empty() || key.len() > MAX_IDEMPOTENCY_KEY_LEN {
+            anyhow::bail!(DaemonIpcError::InvalidCommand(format!(
+                "idempotency_key must be 1-{MAX_IDEMPOTENCY_KEY_LEN} bytes"
+            )));
+        }
+        envelope.id = Envelope::idempotent_id(ctx.local_agent_id.as_str(), &to, &key);
+    }
+    envelope
+        .validate()
+        .and_then(|()| ctx.transport.check_message_size(&envelope))
+        .map_err(|e| anyhow::anyhow!(DaemonIpcError::InvalidCommand(e.to_string())))?;
+
+    let msg_id = envelope.id;
+    let sent = envelope.clone();
+    let recorded = ctx.history.map(|history| (history, envelope.clone()));
+    let audited = ctx
+        .audit
+        .map(|audit| (audit, AuditRecord::envelope(AuditEvent::Send, &envelope)));
+    let logged = ctx
+        .events
+        .map(|events| (events, EventRecord::envelope(Event::Send, &envelope)));
+
+    // Timeout the send (including connection attempt) so IPC clients don't
+    // block indefinitely when the peer is unreachable over UDP/QUIC.
+    let send_timeout = match kind {
+        IpcSendKind::Request => {
+            let secs = timeout_secs.unwrap_or(REQUEST_TIMEOUT.as_secs());
+            if secs == 0 {
+                anyhow::bail!(DaemonIpcError::InvalidCommand(
+                    "timeout_secs must be >= 1".to_string()
+                ));
+            }
+            Duration::from_secs(secs)
+        }
+        IpcSendKind::Message => {
+            if timeout_secs.is_some() {
+                anyhow::bail!(DaemonIpcError::InvalidCommand(
+                    "timeout_secs is only valid for request kind".to_string()
+                ));
+            }
+            Duration::from_secs(10)
+        }
+    };
+    // Route replies to the sender before transmitting: one can arrive
+    // while the send (or a request's wait for its response) is in flight.
+    ctx.ipc.record_send(msg_id, client_id);
+    let send_result = tokio::time::timeout(
+        send_timeout,
+        ctx.transport
+            .send_with_timeout(&peer, envelope, send_timeout),
+    )
+    .await;
+
+    if let Some((audit, record)) = audited {
+        match &send_result {
+            Ok(Ok(response)) => {
+                audit.record(record).await;
+                if let Some(response) = response {
+                    audit
+                        .record(AuditRecord::envelope(AuditEvent::Receive, response))
+                        .await;
+                }
+            }
+            Ok(Err(_)) => audit.record(record.with_reason("peer_unreachable")).await,
+            Err(_) if matches!(kind, IpcSendKind::Request) => {
+                audit.record(record.with_reason("timeout")).await
+            }
+            Err(_) => audit.record(record.with_reason("peer_unreachable")).await,
+        }
+    }
+
+    if let Some((events, record)) = logged {
+        match &send_result {
+            Ok(Ok(response)) => {
+                events.record(record).await;
+                if let Some(response) = response {
+                    events
+                        .record(EventRecord::envelope(Event::Receive, response))
+                        .await;
+                }
+            }
+            failed => {
+                let reason = match failed {
+                    Err(_) if matches!(kind, IpcSendKind::Request) => "timeout",
+                    _ => "peer_unreachable",
+                };
+                let record = EventRecord {
+                    event: Event::Error,
+                    ..record
+                };
+                events.record(record.with_reason(reason)).await;
+            }
+        }
+    }
+
+    if !matches!(send_result, Ok(Ok(_))) {
+        ctx.ipc.forget_send(msg_id);
+    }
+    match send_result {
+        Err(_elapsed) => {
+            ctx.peer_table.set_disconnected(&to).await;
+            ctx.peer_table.record_error(&to, "timeout").await;
+            if matches!(kind, IpcSendKind::Request) {
+                anyhow::bail!(DaemonIpcError::Timeout)
+            } else {
+                anyhow::bail!(DaemonIpcError::PeerUnreachable)
+            }
+        }
+        Ok(inner) => match inner {
+            Ok(response) => {
+                ctx.counters.record_sent();
+                ctx.peer_table.set_connected(&to, None).await;
+                ctx.peer_table.record_sent(&to, &sent).await;
+                ctx.threads.record(&sent);
+                if let Some(ref response) = response {
+                    ctx.counters.record_received();
+                    ctx.peer_table.record_received(&to, response).await;
+                    ctx.threads.record(response);
+                }
+                if let Some((history, sent)) = recorded {
+                    history.record(HistoryDirection::Out, &sent).await;
+                    if let Some(ref response) = response {
+                        history.record(HistoryDirection::In, response).await;
+                    }
+                }
+                tap_envelope(ctx, HistoryDirection::Out, &sent).await;
+                if let Some(ref response) = response {
+                    tap_envelope(ctx, HistoryDirection::In, response).await;
+                }
+                Ok((msg_id, response))
+            }
+            Err(err) => {
+                ctx.peer_table.set_disconnected(&to).await;
+                ctx.peer_table.record_error(&to, format!("{err:#}")).await;
+                anyhow::bail!(DaemonIpcError::PeerUnreachable)
+            }
+        },
+    }
+}
+
+/// Show a delivered envelope to `tap` clients (`axon record`).
+pub(crate) async fn tap_envelope(
+    ctx: &DaemonContext<'_>,
+    direction: HistoryDirection,
+    envelope: &Envelope,
+) {
+    if let Err(err) = ctx.ipc.tap_envelope(direction, envelope).await {
+        tracing::warn!(error = %err, "failed sending tap event to IPC clients");
+    }
+}
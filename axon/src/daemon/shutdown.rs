@@ -0,0 +1,91 @@
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::Result;
+use tracing::{info, warn};
+
+use super::command_handler::DaemonContext;
+use super::in_flight::InFlightSends;
+use super::lockfile::DaemonLock;
+use super::stats::save_lifetime_stats;
+use super::systemd;
+
+/// The shutdown sequence (spec §8): stop taking new work, drain in-flight
+/// streams and IPC sends for up to `drain_timeout`, close the QUIC endpoint,
+/// stop the forwarders, then persist state and remove the socket and lock
+/// file.
+pub(crate) async fn shutdown(
+    ctx: &DaemonContext<'_>,
+    mut sends: InFlightSends<'_>,
+    drain_timeout: Duration,
+    stats_path: &Path,
+    daemon_lock: &mut DaemonLock,
+) -> Result<()> {
+    info!("shutting down...");
+    systemd::notify("STOPPING=1");
+
+    // Stop taking new work. Forwarders and reply waiters keep running so
+    // replies that arrive during the drain are still delivered.
+    ctx.cancel.cancel();
+    info!("all background tasks signaled for shutdown");
+
+    // Inbound QUIC streams (reads, handler runs, replies) and IPC sends
+    // still waiting on a peer share the drain timeout.
+    let transport = ctx.transport;
+    let in_flight = transport.in_flight_streams();
+    if in_flight > 0 || !sends.is_empty() {
+        info!(
+            in_flight,
+            sends = sends.len(),
+            timeout_secs = drain_timeout.as_secs_f64(),
+            "draining in-flight streams and sends"
+        );
+    }
+    let finish_sends = tokio::time::timeout(drain_timeout, async {
+        while let Some(result) = sends.next().await {
+            if let Err(err) = result {
+                warn!(error = %err, "failed handling IPC send during shutdown");
+            }
+        }
+    });
+    let (drained, sends_finished) = tokio::join!(transport.drain(drain_timeout), finish_sends);
+    if !drained {
+        warn!(
+            remaining = transport.in_flight_streams(),
+            "drain timeout elapsed; closing connections with streams still in flight"
+        );
+    }
+    if sends_finished.is_err() {
+        warn!(
+            remaining = sends.len(),
+            "drain timeout elapsed; abandoning IPC sends still in flight"
+        );
+    }
+
+    transport.shutdown().await;
+    ctx.forwarding.cancel();
+    if let Some(audit) = ctx.audit {
+        audit.checkpoint().await;
+    }
+    // Always written on shutdown so last_seen_unix_ms stays current.
+    let result = ctx
+        .known_peers
+        .save_now(ctx.peer_table.to_known_peers().await)
+        .await;
+    ctx.health.record_persist(&result);
+    if let Err(err) = result {
+        warn!(error = %err, "failed to save known peers during shutdown");
+    }
+    if let Err(err) = save_lifetime_stats(stats_path, &ctx.lifetime()).await {
+        warn!(error = %err, "failed to save lifetime stats during shutdown");
+    }
+    ctx.ipc.shutdown().await;
+    let cleanup_socket_result = ctx.ipc.cleanup_socket();
+    if let Err(err) = daemon_lock.release() {
+        warn!(error = %err, "failed to remove daemon lock file during shutdown");
+    }
+    cleanup_socket_result?;
+    info!("shutdown complete");
+
+    Ok(())
+}
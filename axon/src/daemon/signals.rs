@@ -0,0 +1,89 @@
+use anyhow::{Context, Result};
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+use super::reload::ReloadTrigger;
+
+/// Cancel `cancel` on SIGTERM or SIGINT (Ctrl-C off Unix).
+pub(crate) fn spawn_shutdown_signal_task(cancel: CancellationToken) {
+    tokio::spawn(async move {
+        if let Err(err) = wait_for_shutdown_signal(cancel).await {
+            warn!(error = %err, "failed to install shutdown signal handler");
+        }
+    });
+}
+
+/// Turn each SIGHUP into a config reload request.
+#[cfg(unix)]
+pub(crate) fn spawn_reload_signal_task(
+    reload_tx: mpsc::Sender<ReloadTrigger>,
+    cancel: CancellationToken,
+) {
+    use tokio::signal::unix::{SignalKind, signal};
+
+    tokio::spawn(async move {
+        let mut sighup = match signal(SignalKind::hangup()) {
+            Ok(sighup) => sighup,
+            Err(err) => {
+                warn!(error = %err, "failed to install SIGHUP handler");
+                return;
+            }
+        };
+        loop {
+            tokio::select! {
+                _ = cancel.cancelled() => break,
+                _ = sighup.recv() => {
+                    info!("received SIGHUP, reloading config");
+                    if reload_tx.send(ReloadTrigger::Signal).await.is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+    });
+}
+
+#[cfg(not(unix))]
+pub(crate) fn spawn_reload_signal_task(
+    _reload_tx: mpsc::Sender<ReloadTrigger>,
+    _cancel: CancellationToken,
+) {
+}
+
+#[cfg(unix)]
+async fn wait_for_shutdown_signal(cancel: CancellationToken) -> Result<()> {
+    use tokio::signal::unix::{SignalKind, signal};
+
+    let mut sigterm =
+        signal(SignalKind::terminate()).context("failed to install SIGTERM handler")?;
+    let mut sigint = signal(SignalKind::interrupt()).context("failed to install SIGINT handler")?;
+
+    tokio::select! {
+        _ = cancel.cancelled() => {}
+        _ = sigterm.recv() => {
+            info!("received SIGTERM, initiating graceful shutdown");
+            cancel.cancel();
+        }
+        _ = sigint.recv() => {
+            info!("received SIGINT, initiating graceful shutdown");
+            cancel.cancel();
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(not(unix))]
+async fn wait_for_shutdown_signal(cancel: CancellationToken) -> Result<()> {
+    tokio::select! {
+        _ = cancel.cancelled() => {}
+        result = tokio::signal::ctrl_c() => {
+            result.context("failed to install Ctrl-C handler")?;
+            info!("received Ctrl-C, initiating graceful shutdown");
+            cancel.cancel();
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,89 @@
+use std::io::ErrorKind;
+use std::path::Path;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use tracing::warn;
+
+use crate::ipc::{DaemonReply, LifetimeStats};
+use crate::message::now_millis;
+
+use super::command_handler::{Counters, DaemonContext};
+
+/// Load `stats.json` and count this start. A missing file starts from zero;
+/// an unreadable or corrupt one is logged and replaced rather than blocking
+/// startup.
+pub(crate) async fn load_lifetime_stats(path: &Path) -> LifetimeStats {
+    let mut stats = match tokio::fs::read(path).await {
+        Ok(raw) => serde_json::from_slice(&raw).unwrap_or_else(|err| {
+            warn!(path = %path.display(), error = %err, "ignoring corrupt stats file");
+            LifetimeStats::default()
+        }),
+        Err(err) if err.kind() == ErrorKind::NotFound => LifetimeStats::default(),
+        Err(err) => {
+            warn!(path = %path.display(), error = %err, "failed to read stats file");
+            LifetimeStats::default()
+        }
+    };
+    stats.daemon_starts += 1;
+    if stats.first_started_ms == 0 {
+        stats.first_started_ms = now_millis();
+    }
+    stats
+}
+
+/// Lifetime totals: `baseline` (from previous runs) plus this run so far.
+pub(crate) fn lifetime_stats(
+    baseline: &LifetimeStats,
+    counters: &Counters,
+    rate_limited: u64,
+    uptime: Duration,
+) -> LifetimeStats {
+    LifetimeStats {
+        messages_sent: baseline.messages_sent + counters.sent.load(Ordering::Relaxed),
+        messages_received: baseline.messages_received + counters.received.load(Ordering::Relaxed),
+        messages_rate_limited: baseline.messages_rate_limited + rate_limited,
+        uptime_secs: baseline.uptime_secs + uptime.as_secs(),
+        ..*baseline
+    }
+}
+
+/// Write `stats.json` via a temporary file so a crash never leaves it torn.
+pub(crate) async fn save_lifetime_stats(path: &Path, stats: &LifetimeStats) -> Result<()> {
+    let data = serde_json::to_vec(stats).context("failed to encode stats")?;
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    tokio::fs::write(&tmp, data)
+        .await
+        .with_context(|| format!("failed to write stats: {}", path.display()))?;
+    tokio::fs::rename(&tmp, path)
+        .await
+        .with_context(|| format!("failed to write stats: {}", path.display()))
+}
+
+#[cfg(test)]
+#[path = "stats_tests.rs"]
+mod tests;
+
+/// Handle IPC `status`: uptime, connected peers, and message totals.
+pub(crate) async fn status_reply(ctx: &DaemonContext<'_>, req_id: Option<String>) -> DaemonReply {
+    let peers_connected = ctx
+        .peer_table
+        .list()
+        .await
+        .iter()
+        .filter(|p| p.status.is_connected())
+        .count();
+    DaemonReply::Status {
+        ok: true,
+        uptime_secs: ctx.start.elapsed().as_secs(),
+        peers_connected,
+        messages_sent: ctx.counters.sent.load(Ordering::Relaxed),
+        messages_received: ctx.counters.received.load(Ordering::Relaxed),
+        messages_rate_limited: ctx.transport.rate_limited_count(),
+        lifetime: ctx.lifetime(),
+        degraded: ctx.supervisor.degraded(),
+        req_id,
+    }
+}
@@ -0,0 +1,43 @@
+use super::*;
+
+#[tokio::test]
+async fn first_start_begins_from_zero() {
+    let dir = tempfile::tempdir().unwrap();
+    let stats = load_lifetime_stats(&dir.path().join("stats.json")).await;
+    assert_eq!(stats.daemon_starts, 1);
+    assert_eq!(stats.messages_sent, 0);
+    assert!(stats.first_started_ms > 0);
+}
+
+#[tokio::test]
+async fn totals_accumulate_across_restarts() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("stats.json");
+
+    let baseline = load_lifetime_stats(&path).await;
+    let counters = Counters::default();
+    counters.sent.store(3, Ordering::Relaxed);
+    counters.received.store(2, Ordering::Relaxed);
+    let first_run = lifetime_stats(&baseline, &counters, 1, Duration::from_secs(60));
+    save_lifetime_stats(&path, &first_run).await.unwrap();
+
+    let baseline = load_lifetime_stats(&path).await;
+    assert_eq!(baseline.daemon_starts, 2);
+    assert_eq!(baseline.first_started_ms, first_run.first_started_ms);
+    let counters = Counters::default();
+    counters.sent.store(4, Ordering::Relaxed);
+    let second_run = lifetime_stats(&baseline, &counters, 0, Duration::from_secs(30));
+    assert_eq!(second_run.messages_sent, 7);
+    assert_eq!(second_run.messages_received, 2);
+    assert_eq!(second_run.messages_rate_limited, 1);
+    assert_eq!(second_run.uptime_secs, 90);
+}
+
+#[tokio::test]
+async fn corrupt_file_is_replaced() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("stats.json");
+    std::fs::write(&path, b"not json").unwrap();
+    let stats = load_lifetime_stats(&path).await;
+    assert_eq!(stats.daemon_starts, 1);
+}
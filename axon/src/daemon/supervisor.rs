@@ -0,0 +1,165 @@
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::Result;
+use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+use crate::ipc::TaskHealth;
+
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+/// A task that ran at least this long before failing restarts with the
+/// initial backoff again.
+const STABLE_RUN: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TaskState {
+    Running,
+    /// Failed; waiting out the backoff before the next start.
+    Restarting,
+    /// Returned `Ok(())`: its work is done and it is not restarted.
+    Finished,
+}
+
+impl TaskState {
+    fn as_str(self) -> &'static str {
+        match self {
+            TaskState::Running => "running",
+            TaskState::Restarting => "restarting",
+            TaskState::Finished => "finished",
+        }
+    }
+}
+
+#[derive(Debug)]
+struct TaskEntry {
+    state: TaskState,
+    restarts: u64,
+    last_error: Option<String>,
+}
+
+/// Restarts long-lived daemon tasks that exit with an error or panic.
+///
+/// Each task is a factory producing a fresh future per run. A run that
+/// returns `Err` or panics is restarted after an exponential backoff (1s
+/// doubling to 30s, reset after a run of at least 60s); `Ok(())` ends
+/// supervision. Cancellation ends supervision without counting a failure.
+#[derive(Debug, Default)]
+pub(crate) struct Supervisor {
+    tasks: Mutex<BTreeMap<&'static str, TaskEntry>>,
+}
+
+impl Supervisor {
+    /// Start `name` under supervision until `cancel` fires. Re-spawning an
+    /// existing name (e.g. after a config reload) resets its entry.
+    pub(crate) fn spawn<F, Fut>(
+        self: &Arc<Self>,
+        name: &'static str,
+        cancel: CancellationToken,
+        mut make: F,
+    ) where
+        F: FnMut() -> Fut + Send + 'static,
+        Fut: Future<Output = Result<()>> + Send + 'static,
+    {
+        self.update(name, |entry| {
+            *entry = TaskEntry {
+                state: TaskState::Running,
+                restarts: 0,
+                last_error: None,
+            }
+        });
+        let supervisor = self.clone();
+        // Build the first run before spawning so anything the factory sets
+        // up eagerly (e.g. a broadcast subscription) exists on return.
+        let mut run = make();
+        tokio::spawn(async move {
+            let mut backoff = INITIAL_BACKOFF;
+            loop {
+                let started = Instant::now();
+                let outcome = tokio::spawn(run).await;
+                if cancel.is_cancelled() {
+                    break;
+                }
+                let error = match outcome {
+                    Ok(Ok(())) => {
+                        supervisor.update(name, |entry| entry.state = TaskState::Finished);
+                        break;
+                    }
+                    Ok(Err(err)) => format!("{err:#}"),
+                    Err(err) if err.is_panic() => "panicked".to_string(),
+                    Err(err) => err.to_string(),
+                };
+                if started.elapsed() >= STABLE_RUN {
+                    backoff = INITIAL_BACKOFF;
+                }
+                warn!(
+                    task = name,
+                    error = %error,
+                    retry_in_secs = backoff.as_secs(),
+                    "daemon task exited; restarting"
+                );
+                supervisor.update(name, |entry| {
+                    entry.state = TaskState::Restarting;
+                    entry.last_error = Some(error);
+                });
+                tokio::select! {
+                    _ = cancel.cancelled() => break,
+                    _ = tokio::time::sleep(backoff) => {}
+                }
+                backoff = (backoff * 2).min(MAX_BACKOFF);
+                run = make();
+                supervisor.update(name, |entry| {
+                    entry.state = TaskState::Running;
+                    entry.restarts += 1;
+                });
+                info!(task = name, "daemon task restarted");
+            }
+        });
+    }
+
+    fn update(&self, name: &'static str, f: impl FnOnce(&mut TaskEntry)) {
+        if let Ok(mut tasks) = self.tasks.lock() {
+            f(tasks.entry(name).or_insert(TaskEntry {
+                state: TaskState::Running,
+                restarts: 0,
+                last_error: None,
+            }));
+        }
+    }
+
+    /// Per-task state, sorted by name.
+    pub(crate) fn report(&self) -> Vec<TaskHealth> {
+        let Ok(tasks) = self.tasks.lock() else {
+            return Vec::new();
+        };
+        tasks
+            .iter()
+            .map(|(name, entry)| TaskHealth {
+                name,
+                state: entry.state.as_str(),
+                restarts: entry.restarts,
+                last_error: entry.last_error.clone(),
+            })
+            .collect()
+    }
+
+    /// Names of tasks currently down and waiting to restart.
+    pub(crate) fn degraded(&self) -> Vec<&'static str> {
+        let Ok(tasks) = self.tasks.lock() else {
+            return Vec::new();
+        };
+        tasks
+            .iter()
+            .filter(|(_, entry)| entry.state == TaskState::Restarting)
+            .map(|(name, _)| *name)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+#[path = "supervisor_tests.rs"]
+mod tests;
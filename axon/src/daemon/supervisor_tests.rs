@@ -0,0 +1,112 @@
+use super::*;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use anyhow::bail;
+
+fn task(supervisor: &Supervisor, name: &str) -> Option<TaskHealth> {
+    supervisor
+        .report()
+        .into_iter()
+        .find(|task| task.name == name)
+}
+
+/// Let spawned tasks run without advancing the paused clock.
+async fn settle() {
+    for _ in 0..10 {
+        tokio::task::yield_now().await;
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn failed_task_restarts_with_backoff() {
+    let supervisor = Arc::new(Supervisor::default());
+    let cancel = CancellationToken::new();
+    let runs = Arc::new(AtomicU32::new(0));
+    let counter = runs.clone();
+    let task_cancel = cancel.clone();
+    supervisor.spawn("flaky", cancel.clone(), move || {
+        let run = counter.fetch_add(1, Ordering::SeqCst);
+        let cancel = task_cancel.clone();
+        async move {
+            if run < 2 {
+                bail!("run {run} failed");
+            }
+            cancel.cancelled().await;
+            Ok(())
+        }
+    });
+
+    settle().await;
+    let flaky = task(&supervisor, "flaky").unwrap();
+    assert_eq!(flaky.state, "restarting");
+    assert_eq!(flaky.last_error.as_deref(), Some("run 0 failed"));
+    assert_eq!(supervisor.degraded(), vec!["flaky"]);
+
+    // First backoff is 1s, the second 2s.
+    tokio::time::sleep(Duration::from_millis(1001)).await;
+    settle().await;
+    assert_eq!(runs.load(Ordering::SeqCst), 2);
+    assert_eq!(task(&supervisor, "flaky").unwrap().state, "restarting");
+    tokio::time::sleep(Duration::from_millis(1001)).await;
+    settle().await;
+    assert_eq!(runs.load(Ordering::SeqCst), 2, "second backoff doubled");
+    tokio::time::sleep(Duration::from_millis(1000)).await;
+    settle().await;
+
+    let flaky = task(&supervisor, "flaky").unwrap();
+    assert_eq!(runs.load(Ordering::SeqCst), 3);
+    assert_eq!(flaky.state, "running");
+    assert_eq!(flaky.restarts, 2);
+    assert!(supervisor.degraded().is_empty());
+
+    cancel.cancel();
+    settle().await;
+    assert_eq!(task(&supervisor, "flaky").unwrap().state, "running");
+}
+
+#[tokio::test(start_paused = true)]
+async fn panicking_task_is_restarted() {
+    let supervisor = Arc::new(Supervisor::default());
+    let cancel = CancellationToken::new();
+    let runs = Arc::new(AtomicU32::new(0));
+    let counter = runs.clone();
+    supervisor.spawn("panicky", cancel.clone(), move || {
+        let run = counter.fetch_add(1, Ordering::SeqCst);
+        async move {
+            if run == 0 {
+                panic!("boom");
+            }
+            std::future::pending::<Result<()>>().await
+        }
+    });
+
+    settle().await;
+    assert_eq!(
+        task(&supervisor, "panicky").unwrap().last_error.as_deref(),
+        Some("panicked")
+    );
+    tokio::time::sleep(Duration::from_millis(1001)).await;
+    settle().await;
+    assert_eq!(runs.load(Ordering::SeqCst), 2);
+    assert_eq!(task(&supervisor, "panicky").unwrap().state, "running");
+    cancel.cancel();
+}
+
+#[tokio::test(start_paused = true)]
+async fn completed_task_is_not_restarted() {
+    let supervisor = Arc::new(Supervisor::default());
+    let runs = Arc::new(AtomicU32::new(0));
+    let counter = runs.clone();
+    supervisor.spawn("oneshot", CancellationToken::new(), move || {
+        counter.fetch_add(1, Ordering::SeqCst);
+        async { Ok(()) }
+    });
+
+    settle().await;
+    tokio::time::sleep(Duration::from_secs(60)).await;
+    settle().await;
+    assert_eq!(runs.load(Ordering::SeqCst), 1);
+    let oneshot = task(&supervisor, "oneshot").unwrap();
+    assert_eq!(oneshot.state, "finished");
+    assert!(supervisor.degraded().is_empty());
+}
@@ -0,0 +1,151 @@
+//! systemd integration: socket activation (`sd_listen_fds`) and readiness
+//! notification (`sd_notify`), implemented directly against the documented
+//! environment/datagram protocol so no libsystemd dependency is needed.
+
+use std::io;
+use std::net::UdpSocket;
+use std::os::unix::io::{FromRawFd, RawFd};
+use std::os::unix::net::{UnixDatagram, UnixListener};
+
+use tracing::{debug, info, warn};
+
+/// First file descriptor passed by systemd (`SD_LISTEN_FDS_START`).
+const LISTEN_FDS_START: RawFd = 3;
+
+/// Sockets handed over by systemd socket activation.
+#[derive(Debug, Default)]
+pub(crate) struct ActivatedSockets {
+    /// Unix stream listener for IPC (`ListenStream=` on a path).
+    pub(crate) ipc: Option<UnixListener>,
+    /// UDP socket for QUIC (`ListenDatagram=` on an IP address).
+    pub(crate) quic: Option<UdpSocket>,
+}
+
+/// Number of descriptors passed to this process, per the `sd_listen_fds`
+/// rules: `LISTEN_PID` must name this process and `LISTEN_FDS` must parse.
+fn listen_fds_count(listen_pid: Option<&str>, listen_fds: Option<&str>, pid: u32) -> usize {
+    let Some(listen_pid) = listen_pid.and_then(|v| v.parse::<u32>().ok()) else {
+        return 0;
+    };
+    if listen_pid != pid {
+        return 0;
+    }
+    listen_fds.and_then(|v| v.parse().ok()).unwrap_or(0)
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum SocketKind {
+    UnixStream,
+    InetDatagram,
+    Other,
+}
+
+fn socket_kind(fd: RawFd) -> SocketKind {
+    let mut sock_type: libc::c_int = 0;
+    let mut len = std::mem::size_of::<libc::c_int>() as libc::socklen_t;
+    let result = unsafe {
+        libc::getsockopt(
+            fd,
+            libc::SOL_SOCKET,
+            libc::SO_TYPE,
+            &mut sock_type as *mut _ as *mut libc::c_void,
+            &mut len,
+        )
+    };
+    if result != 0 {
+        return SocketKind::Other;
+    }
+
+    let mut addr: libc::sockaddr_storage = unsafe { std::mem::zeroed() };
+    let mut addr_len = std::mem::size_of::<libc::sockaddr_storage>() as libc::socklen_t;
+    let result = unsafe {
+        libc::getsockname(
+            fd,
+            &mut addr as *mut _ as *mut libc::sockaddr,
+            &mut addr_len,
+        )
+    };
+    if result != 0 {
+        return SocketKind::Other;
+    }
+
+    match (libc::c_int::from(addr.ss_family), sock_type) {
+        (libc::AF_UNIX, libc::SOCK_STREAM) => SocketKind::UnixStream,
+        (libc::AF_INET | libc::AF_INET6, libc::SOCK_DGRAM) => SocketKind::InetDatagram,
+        _ => SocketKind::Other,
+    }
+}
+
+/// Collect sockets passed via systemd socket activation, classified by socket
+/// type. Returns empty when the daemon was not socket-activated. Unexpected or
+/// duplicate descriptors are closed.
+pub(crate) fn take_activated_sockets() -> ActivatedSockets {
+    let count = listen_fds_count(
+        std::env::var("LISTEN_PID").ok().as_deref(),
+        std::env::var("LISTEN_FDS").ok().as_deref(),
+        std::process::id(),
+    );
+    let mut sockets = ActivatedSockets::default();
+    for fd in LISTEN_FDS_START..LISTEN_FDS_START + count as RawFd {
+        // Passed descriptors do not have FD_CLOEXEC set.
+        unsafe {
+            libc::fcntl(fd, libc::F_SETFD, libc::FD_CLOEXEC);
+        }
+        match socket_kind(fd) {
+            SocketKind::UnixStream if sockets.ipc.is_none() => {
+                info!(fd, "using socket-activated IPC listener");
+                sockets.ipc = Some(unsafe { UnixListener::from_raw_fd(fd) });
+            }
+            SocketKind::InetDatagram if sockets.quic.is_none() => {
+                info!(fd, "using socket-activated QUIC socket");
+                sockets.quic = Some(unsafe { UdpSocket::from_raw_fd(fd) });
+            }
+            kind => {
+                warn!(fd, ?kind, "ignoring unexpected socket-activated descriptor");
+                unsafe {
+                    libc::close(fd);
+                }
+            }
+        }
+    }
+    sockets
+}
+
+fn notify_to(socket: &str, state: &str) -> io::Result<()> {
+    let datagram = UnixDatagram::unbound()?;
+    if let Some(name) = socket.strip_prefix('@') {
+        #[cfg(target_os = "linux")]
+        {
+            use std::os::linux::net::SocketAddrExt;
+            let addr = std::os::unix::net::SocketAddr::from_abstract_name(name.as_bytes())?;
+            datagram.send_to_addr(state.as_bytes(), &addr)?;
+            return Ok(());
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            let _ = name;
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "abstract notify sockets are Linux-only",
+            ));
+        }
+    }
+    datagram.send_to(state.as_bytes(), socket)?;
+    Ok(())
+}
+
+/// Send a state update (e.g. `READY=1`) to the service manager. No-op when
+/// `NOTIFY_SOCKET` is unset, i.e. when not running under `Type=notify`.
+pub(crate) fn notify(state: &str) {
+    let Ok(socket) = std::env::var("NOTIFY_SOCKET") else {
+        return;
+    };
+    match notify_to(&socket, state) {
+        Ok(()) => debug!(state, "sent sd_notify"),
+        Err(err) => warn!(error = %err, state, "failed to send sd_notify"),
+    }
+}
+
+#[cfg(test)]
+#[path = "systemd_tests.rs"]
+mod tests;
@@ -0,0 +1,36 @@
+use super::*;
+use std::os::unix::io::AsRawFd;
+
+#[test]
+fn listen_fds_requires_matching_pid() {
+    assert_eq!(listen_fds_count(Some("42"), Some("2"), 42), 2);
+    assert_eq!(listen_fds_count(Some("41"), Some("2"), 42), 0);
+    assert_eq!(listen_fds_count(None, Some("2"), 42), 0);
+    assert_eq!(listen_fds_count(Some("42"), None, 42), 0);
+    assert_eq!(listen_fds_count(Some("42"), Some("x"), 42), 0);
+}
+
+#[test]
+fn socket_kind_classifies_ipc_and_quic_sockets() {
+    let dir = tempfile::tempdir().unwrap();
+    let listener = UnixListener::bind(dir.path().join("axon.sock")).unwrap();
+    let udp = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let tcp = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+
+    assert_eq!(socket_kind(listener.as_raw_fd()), SocketKind::UnixStream);
+    assert_eq!(socket_kind(udp.as_raw_fd()), SocketKind::InetDatagram);
+    assert_eq!(socket_kind(tcp.as_raw_fd()), SocketKind::Other);
+}
+
+#[test]
+fn notify_sends_state_datagram() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("notify.sock");
+    let receiver = UnixDatagram::bind(&path).unwrap();
+
+    notify_to(path.to_str().unwrap(), "READY=1").unwrap();
+
+    let mut buf = [0u8; 64];
+    let n = receiver.recv(&mut buf).unwrap();
+    assert_eq!(&buf[..n], b"READY=1");
+}
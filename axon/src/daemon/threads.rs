@@ -0,0 +1,64 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+use uuid::Uuid;
+
+use crate::message::Envelope;
+
+/// Recent envelopes remembered for `thread_id` inheritance.
+pub(crate) const THREAD_INDEX_CAPACITY: usize = 4096;
+
+/// `thread_id` of recently sent and received envelopes, keyed by message ID,
+/// so a `send` that sets `ref` joins the referenced envelope's thread.
+///
+/// Bounded: the oldest entry is evicted once `capacity` is reached.
+#[derive(Debug)]
+pub(crate) struct ThreadIndex {
+    capacity: usize,
+    entries: Mutex<(HashMap<Uuid, String>, VecDeque<Uuid>)>,
+}
+
+impl Default for ThreadIndex {
+    fn default() -> Self {
+        Self::with_capacity(THREAD_INDEX_CAPACITY)
+    }
+}
+
+impl ThreadIndex {
+    pub(crate) fn with_capacity(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Mutex::new((HashMap::new(), VecDeque::new())),
+        }
+    }
+
+    /// Remember `envelope`'s thread, if it has one.
+    pub(crate) fn record(&self, envelope: &Envelope) {
+        let Some(ref thread_id) = envelope.thread_id else {
+            return;
+        };
+        if let Ok(mut entries) = self.entries.lock() {
+            let (threads, order) = &mut *entries;
+            if threads.insert(envelope.id, thread_id.clone()).is_none() {
+                order.push_back(envelope.id);
+            }
+            while order.len() > self.capacity {
+                if let Some(oldest) = order.pop_front() {
+                    threads.remove(&oldest);
+                }
+            }
+        }
+    }
+
+    /// Thread of a remembered envelope.
+    pub(crate) fn thread_of(&self, msg_id: &Uuid) -> Option<String> {
+        self.entries
+            .lock()
+            .ok()
+            .and_then(|entries| entries.0.get(msg_id).cloned())
+    }
+}
+
+#[cfg(test)]
+#[path = "threads_tests.rs"]
+mod tests;
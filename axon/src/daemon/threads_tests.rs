@@ -0,0 +1,41 @@
+use serde_json::json;
+
+use super::*;
+use crate::message::MessageKind;
+
+const LOCAL: &str = "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+const PEER: &str = "ed25519.bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb";
+
+fn envelope(thread_id: Option<&str>) -> Envelope {
+    let mut envelope = Envelope::new(LOCAL, PEER, MessageKind::Message, json!({}));
+    envelope.thread_id = thread_id.map(str::to_string);
+    envelope
+}
+
+#[test]
+fn remembers_threaded_envelopes_only() {
+    let index = ThreadIndex::default();
+    let threaded = envelope(Some("t-1"));
+    let plain = envelope(None);
+    index.record(&threaded);
+    index.record(&plain);
+
+    assert_eq!(index.thread_of(&threaded.id).as_deref(), Some("t-1"));
+    assert_eq!(index.thread_of(&plain.id), None);
+}
+
+#[test]
+fn evicts_oldest_beyond_capacity() {
+    let index = ThreadIndex::with_capacity(2);
+    let envelopes: Vec<Envelope> = ["a", "b", "c"]
+        .into_iter()
+        .map(|thread| envelope(Some(thread)))
+        .collect();
+    for envelope in &envelopes {
+        index.record(envelope);
+    }
+
+    assert_eq!(index.thread_of(&envelopes[0].id), None);
+    assert_eq!(index.thread_of(&envelopes[1].id).as_deref(), Some("b"));
+    assert_eq!(index.thread_of(&envelopes[2].id).as_deref(), Some("c"));
+}
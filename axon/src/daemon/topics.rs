@@ -0,0 +1,385 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{Result, bail};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tokio::sync::broadcast;
+use tokio::task::JoinSet;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, warn};
+
+use super::audit::{AuditEvent, AuditRecord};
+use super::command_handler::DaemonContext;
+use super::event_log::{Event, EventRecord};
+use super::send::tap_envelope;
+use super::supervisor::Supervisor;
+use crate::ipc::{DaemonReply, HistoryDirection, IpcErrorCode};
+use crate::message::{AgentId, Envelope, MessageKind};
+use crate::peer_table::PeerTable;
+use crate::transport::{ConnectionEvent, QuicTransport};
+
+/// Maximum length of a topic or subscription pattern.
+pub(crate) const MAX_TOPIC_LEN: usize = 128;
+/// Maximum patterns in one subscription set.
+pub(crate) const MAX_SUBSCRIPTIONS: usize = 64;
+/// Per-peer send budget for `publish`, matching fire-and-forget `send`.
+const PUBLISH_TIMEOUT: Duration = Duration::from_secs(10);
+/// Sends of one `subscribe_topics` announce before giving up until the
+/// peer reconnects.
+const ANNOUNCE_ATTEMPTS: u32 = 5;
+/// Delay before the first announce retry; doubles with each attempt.
+const ANNOUNCE_RETRY_DELAY: Duration = Duration::from_millis(200);
+
+/// `subscribe_topics` payload: the sender's complete subscription set.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct SubscribeTopics {
+    pub(crate) topics: Vec<String>,
+}
+
+/// Check a topic: dot-separated segments of `[A-Za-z0-9_-]`. Subscription
+/// patterns may also end in `*` (`"*"` alone, or `"prefix.*"`).
+pub(crate) fn validate_topic(topic: &str, pattern: bool) -> Result<()> {
+    if topic.is_empty() || topic.len() > MAX_TOPIC_LEN {
+        bail!("topic must be 1-{MAX_TOPIC_LEN} bytes");
+    }
+    let segments: Vec<&str> = topic.split('.').collect();
+    for (idx, segment) in segments.iter().enumerate() {
+        let wildcard = *segment == "*" && idx == segments.len() - 1;
+        if wildcard && pattern {
+            continue;
+        }
+        if segment.is_empty()
+            || !segment
+                .chars()
+                .all(|ch| ch.is_ascii_alphanumeric() || ch == '_' || ch == '-')
+        {
+            bail!(
+                "invalid topic '{topic}': use dot-separated [A-Za-z0-9_-] segments{}",
+                if pattern {
+                    ", optionally ending in .*"
+                } else {
+                    ""
+                }
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Validate and deduplicate a subscription set, keeping first-seen order.
+pub(crate) fn normalize_subscriptions(patterns: Vec<String>) -> Result<Vec<String>> {
+    let mut out: Vec<String> = Vec::with_capacity(patterns.len());
+    for pattern in patterns {
+        validate_topic(&pattern, true)?;
+        if !out.contains(&pattern) {
+            out.push(pattern);
+        }
+    }
+    if out.len() > MAX_SUBSCRIPTIONS {
+        bail!("at most {MAX_SUBSCRIPTIONS} topic subscriptions");
+    }
+    Ok(out)
+}
+
+/// Whether subscription `pattern` covers `topic`: an exact match, `*`, or
+/// `prefix.*` for any topic below `prefix`.
+pub(crate) fn topic_matches(pattern: &str, topic: &str) -> bool {
+    if pattern == "*" || pattern == topic {
+        return true;
+    }
+    match pattern.strip_suffix(".*") {
+        Some(prefix) => topic
+            .strip_prefix(prefix)
+            .is_some_and(|rest| rest.starts_with('.')),
+        None => false,
+    }
+}
+
+/// This daemon's topic subscriptions and the latest set announced by each peer.
+#[derive(Debug, Default)]
+pub(crate) struct TopicTable {
+    local: Mutex<Vec<String>>,
+    remote: Mutex<HashMap<AgentId, Vec<String>>>,
+}
+
+impl TopicTable {
+    pub(crate) fn local(&self) -> Vec<String> {
+        self.local
+            .lock()
+            .map(|local| local.clone())
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn set_local(&self, topics: Vec<String>) {
+        if let Ok(mut local) = self.local.lock() {
+            *local = topics;
+        }
+    }
+
+    /// Whether an inbound envelope tagged `topic` is wanted locally.
+    pub(crate) fn wants(&self, topic: &str) -> bool {
+        self.local
+            .lock()
+            .is_ok_and(|local| local.iter().any(|pattern| topic_matches(pattern, topic)))
+    }
+
+    /// Replace `peer`'s subscription set; an empty set removes it.
+    pub(crate) fn set_remote(&self, peer: &str, topics: Vec<String>) {
+        if let Ok(mut remote) = self.remote.lock() {
+            if topics.is_empty() {
+                remote.remove(peer);
+            } else {
+                remote.insert(AgentId::from(peer), topics);
+            }
+        }
+    }
+
+    pub(crate) fn remote(&self, peer: &str) -> Vec<String> {
+        self.remote
+            .lock()
+            .ok()
+            .and_then(|remote| remote.get(peer).cloned())
+            .unwrap_or_default()
+    }
+
+    /// Peers with a subscription matching `topic`, sorted.
+    pub(crate) fn subscribers(&self, topic: &str) -> Vec<AgentId> {
+        let Ok(remote) = self.remote.lock() else {
+            return Vec::new();
+        };
+        let mut peers: Vec<AgentId> = remote
+            .iter()
+            .filter(|(_, patterns)| patterns.iter().any(|p| topic_matches(p, topic)))
+            .map(|(peer, _)| peer.clone())
+            .collect();
+        peers.sort();
+        peers
+    }
+}
+
+/// Send this daemon's subscription set to `agent_id` in the background.
+///
+/// A failed send is retried with backoff while the peer stays connected;
+/// each attempt sends the set current at that moment, so a retry never
+/// overwrites a newer announce. Once the connection is gone the next
+/// `Connected` event announces again.
+pub(crate) fn announce_to(
+    transport: &QuicTransport,
+    peer_table: &PeerTable,
+    topics: &Arc<TopicTable>,
+    local_agent_id: &AgentId,
+    agent_id: AgentId,
+) {
+    let (transport, peer_table, topics) = (transport.clone(), peer_table.clone(), topics.clone());
+    let local_agent_id = local_agent_id.clone();
+    tokio::spawn(async move {
+        let mut delay = ANNOUNCE_RETRY_DELAY;
+        for attempt in 1..=ANNOUNCE_ATTEMPTS {
+            let Some(peer) = peer_table.get(&agent_id).await else {
+                return;
+            };
+            let envelope = Envelope::new(
+                local_agent_id.clone(),
+                agent_id.clone(),
+                MessageKind::SubscribeTopics,
+                json!(SubscribeTopics {
+                    topics: topics.local()
+                }),
+            );
+            let Err(err) = transport.send(&peer, envelope).await else {
+                return;
+            };
+            if attempt == ANNOUNCE_ATTEMPTS || !transport.has_connection(&agent_id).await {
+                warn!(
+                    peer = %agent_id,
+                    error = %err,
+                    "failed to announce topic subscriptions; resending when the peer reconnects"
+                );
+                return;
+            }
+            debug!(peer = %agent_id, attempt, error = %err, "retrying topic subscription announce");
+            tokio::time::sleep(delay).await;
+            delay *= 2;
+        }
+    });
+}
+
+/// Announce the local subscription set to each peer as it connects, so a
+/// peer that restarted (or never heard it) learns it again, and drop a
+/// peer's announced set when its connection ends.
+pub(crate) fn spawn_topic_announcer(
+    supervisor: &Arc<Supervisor>,
+    transport: &QuicTransport,
+    peer_table: PeerTable,
+    topics: Arc<TopicTable>,
+    local_agent_id: AgentId,
+    cancel: CancellationToken,
+) {
+    let transport = transport.clone();
+    supervisor.spawn("topic_announcer", cancel.clone(), move || {
+        announce_on_connect(
+            transport.subscribe_connection_events(),
+            transport.clone(),
+            peer_table.clone(),
+            topics.clone(),
+            local_agent_id.clone(),
+            cancel.clone(),
+        )
+    });
+}
+
+async fn announce_on_connect(
+    mut connection_event_rx: broadcast::Receiver<ConnectionEvent>,
+    transport: QuicTransport,
+    peer_table: PeerTable,
+    topics: Arc<TopicTable>,
+    local_agent_id: AgentId,
+    cancel: CancellationToken,
+) -> Result<()> {
+    loop {
+        tokio::select! {
+            _ = cancel.cancelled() => break,
+            msg = connection_event_rx.recv() => match msg {
+                Ok(ConnectionEvent::Connected { agent_id }) => {
+                    if !topics.local().is_empty() {
+                        announce_to(&transport, &peer_table, &topics, &local_agent_id, agent_id.into());
+                    }
+                }
+                Ok(ConnectionEvent::Disconnected { agent_id, .. }) => {
+                    topics.set_remote(&agent_id, Vec::new());
+                }
+                Err(err) => bail!("connection event subscription closed: {err}"),
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Replace the local subscription set and announce it to connected peers.
+pub(crate) async fn subscribe_reply(
+    ctx: &DaemonContext<'_>,
+    topics: Vec<String>,
+    req_id: Option<String>,
+) -> DaemonReply {
+    let topics = match normalize_subscriptions(topics) {
+        Ok(topics) => topics,
+        Err(err) => {
+            debug!(error = %err, "rejecting subscribe");
+            return DaemonReply::error(IpcErrorCode::InvalidCommand, req_id);
+        }
+    };
+    ctx.topics.set_local(topics.clone());
+    for peer in ctx.peer_table.list().await {
+        if peer.status.is_connected() && ctx.transport.has_connection(&peer.agent_id).await {
+            announce_to(
+                ctx.transport,
+                ctx.peer_table,
+                ctx.topics,
+                ctx.local_agent_id,
+                peer.agent_id,
+            );
+        }
+    }
+    DaemonReply::Subscribe {
+        ok: true,
+        topics,
+        req_id,
+    }
+}
+
+/// Send `payload` as a `message` tagged `topic` to every peer subscribed to it.
+pub(crate) async fn publish_reply(
+    ctx: &DaemonContext<'_>,
+    topic: String,
+    payload: serde_json::Value,
+    req_id: Option<String>,
+) -> DaemonReply {
+    if let Err(err) = validate_topic(&topic, false) {
+        debug!(error = %err, "rejecting publish");
+        return DaemonReply::error(IpcErrorCode::InvalidCommand, req_id);
+    }
+    if !payload.is_object() {
+        return DaemonReply::error(IpcErrorCode::InvalidCommand, req_id);
+    }
+
+    let mut failed = Vec::new();
+    let mut sends = JoinSet::new();
+    for agent_id in ctx.topics.subscribers(&topic) {
+        let Some(peer) = ctx.peer_table.get(&agent_id).await else {
+            failed.push(agent_id.to_string());
+            continue;
+        };
+        let mut envelope = Envelope::new(
+            ctx.local_agent_id.clone(),
+            agent_id.clone(),
+            MessageKind::Message,
+            payload.clone(),
+        );
+        envelope.topic = Some(topic.clone());
+        let transport = ctx.transport.clone();
+        sends.spawn(async move {
+            let sent = tokio::time::timeout(
+                PUBLISH_TIMEOUT,
+                transport.send_with_timeout(&peer, envelope.clone(), PUBLISH_TIMEOUT),
+            )
+            .await;
+            (agent_id, envelope, matches!(sent, Ok(Ok(_))))
+        });
+    }
+
+    let mut delivered = Vec::new();
+    while let Some(joined) = sends.join_next().await {
+        let Ok((to, envelope, ok)) = joined else {
+            continue;
+        };
+        let record = AuditRecord::envelope(AuditEvent::Send, &envelope);
+        if ok {
+            ctx.counters.record_sent();
+            ctx.peer_table.set_connected(&to, None).await;
+            ctx.peer_table.record_sent(&to, &envelope).await;
+            if let Some(history) = ctx.history {
+                history.record(HistoryDirection::Out, &envelope).await;
+            }
+            if let Some(audit) = ctx.audit {
+                audit.record(record).await;
+            }
+            if let Some(events) = ctx.events {
+                events
+                    .record(EventRecord::envelope(Event::Send, &envelope))
+                    .await;
+            }
+            tap_envelope(ctx, HistoryDirection::Out, &envelope).await;
+            delivered.push(to.to_string());
+        } else {
+            ctx.peer_table.set_disconnected(&to).await;
+            ctx.peer_table.record_error(&to, "publish failed").await;
+            if let Some(audit) = ctx.audit {
+                audit.record(record.with_reason("peer_unreachable")).await;
+            }
+            if let Some(events) = ctx.events {
+                events
+                    .record(
+                        EventRecord::envelope(Event::Error, &envelope)
+                            .with_reason("peer_unreachable"),
+                    )
+                    .await;
+            }
+            failed.push(to.to_string());
+        }
+    }
+    delivered.sort();
+    failed.sort();
+    DaemonReply::Publish {
+        ok: true,
+        topic,
+        delivered,
+        failed,
+        req_id,
+    }
+}
+
+#[cfg(test)]
+#[path = "topics_tests.rs"]
+mod tests;
@@ -0,0 +1,70 @@
+use super::*;
+
+const PEER_A: &str = "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+const PEER_B: &str = "ed25519.bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb";
+
+fn topics(list: &[&str]) -> Vec<String> {
+    list.iter().map(|t| t.to_string()).collect()
+}
+
+#[test]
+fn validate_topic_accepts_dotted_segments() {
+    for topic in ["builds", "builds.main", "a-b_c.D9"] {
+        assert!(validate_topic(topic, false).is_ok(), "{topic}");
+    }
+    for topic in ["", ".builds", "builds.", "a..b", "a b", "builds.*", "*"] {
+        assert!(validate_topic(topic, false).is_err(), "{topic}");
+    }
+    assert!(validate_topic(&"a".repeat(MAX_TOPIC_LEN + 1), false).is_err());
+}
+
+#[test]
+fn validate_pattern_allows_only_trailing_wildcard() {
+    for pattern in ["*", "builds.*", "builds.main"] {
+        assert!(validate_topic(pattern, true).is_ok(), "{pattern}");
+    }
+    for pattern in ["*.main", "builds.*.x", "build*", ".*"] {
+        assert!(validate_topic(pattern, true).is_err(), "{pattern}");
+    }
+}
+
+#[test]
+fn topic_matches_exact_prefix_and_star() {
+    assert!(topic_matches("builds.main", "builds.main"));
+    assert!(topic_matches("*", "anything.at.all"));
+    assert!(topic_matches("builds.*", "builds.main"));
+    assert!(topic_matches("builds.*", "builds.main.linux"));
+    assert!(!topic_matches("builds.*", "builds"));
+    assert!(!topic_matches("builds.*", "buildsx.main"));
+    assert!(!topic_matches("builds.main", "builds.main.linux"));
+}
+
+#[test]
+fn normalize_subscriptions_dedupes_and_limits() {
+    assert_eq!(
+        normalize_subscriptions(topics(&["a", "b.*", "a"])).unwrap(),
+        topics(&["a", "b.*"])
+    );
+    assert!(normalize_subscriptions(topics(&["ok", "not ok"])).is_err());
+    let too_many: Vec<String> = (0..=MAX_SUBSCRIPTIONS).map(|i| format!("t{i}")).collect();
+    assert!(normalize_subscriptions(too_many).is_err());
+}
+
+#[test]
+fn topic_table_tracks_local_and_remote_sets() {
+    let table = TopicTable::default();
+    assert!(!table.wants("builds.main"));
+    table.set_local(topics(&["builds.*"]));
+    assert!(table.wants("builds.main"));
+    assert!(!table.wants("deploys.prod"));
+
+    table.set_remote(PEER_B, topics(&["deploys.*"]));
+    table.set_remote(PEER_A, topics(&["*"]));
+    assert_eq!(table.subscribers("deploys.prod"), vec![PEER_A, PEER_B]);
+    assert_eq!(table.subscribers("builds.main"), vec![PEER_A]);
+    assert_eq!(table.remote(PEER_B), topics(&["deploys.*"]));
+
+    table.set_remote(PEER_A, Vec::new());
+    assert_eq!(table.subscribers("builds.main"), Vec::<AgentId>::new());
+    assert!(table.remote(PEER_A).is_empty());
+}
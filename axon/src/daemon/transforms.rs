@@ -0,0 +1,155 @@
+use std::sync::{Arc, RwLock};
+
+use serde_json::Value;
+use tracing::warn;
+
+use super::topics::{topic_matches, validate_topic};
+use crate::config::{TransformConfig, application_kind};
+use crate::message::{AgentId, Envelope, MessageKind};
+
+/// The `transforms` pipeline from `config.yaml`, replaced on reload and run
+/// on each inbound message before it is recorded in history, sent to
+/// webhooks, or delivered to IPC clients.
+#[derive(Debug, Default)]
+pub(crate) struct Transforms {
+    steps: RwLock<Arc<[Transform]>>,
+}
+
+/// What the pipeline made of one envelope.
+#[derive(Debug, PartialEq)]
+pub(crate) enum Outcome {
+    Unchanged,
+    Changed(Envelope),
+    Dropped,
+}
+
+impl Transforms {
+    /// Replace the pipeline, skipping steps with an invalid `set_topic`.
+    pub(crate) fn set(&self, configs: &[TransformConfig]) {
+        let steps: Vec<_> = configs
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, config)| match Transform::new(config) {
+                Ok(step) => Some(step),
+                Err(err) => {
+                    warn!(step = idx, error = %err, "skipping invalid inbound transform");
+                    None
+                }
+            })
+            .collect();
+        match self.steps.write() {
+            Ok(mut current) => *current = steps.into(),
+            Err(poisoned) => *poisoned.into_inner() = steps.into(),
+        }
+    }
+
+    /// Run every step that matches `envelope`, in order. A step sees the
+    /// envelope as left by the steps before it.
+    pub(crate) fn apply(&self, envelope: &Envelope) -> Outcome {
+        let steps = match self.steps.read() {
+            Ok(steps) => steps.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        };
+        let mut changed: Option<Envelope> = None;
+        for step in steps.iter() {
+            let current = changed.as_ref().unwrap_or(envelope);
+            if !step.matches(current) {
+                continue;
+            }
+            if step.drop {
+                return Outcome::Dropped;
+            }
+            if let Some(next) = step.rewrite(current) {
+                changed = Some(next);
+            }
+        }
+        match changed {
+            Some(envelope) => Outcome::Changed(envelope),
+            None => Outcome::Unchanged,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct Transform {
+    /// `None` matches every kind.
+    kinds: Option<Vec<MessageKind>>,
+    topics: Vec<String>,
+    peers: Vec<AgentId>,
+    drop: bool,
+    redact: Vec<String>,
+    set_topic: Option<String>,
+}
+
+impl Transform {
+    pub(crate) fn new(config: &TransformConfig) -> anyhow::Result<Self> {
+        if let Some(topic) = &config.set_topic {
+            validate_topic(topic, false)?;
+        }
+        Ok(Self {
+            kinds: (!config.kinds.is_empty()).then(|| {
+                config
+                    .kinds
+                    .iter()
+                    .filter_map(|kind| application_kind(kind))
+                    .collect()
+            }),
+            topics: config.topics.clone(),
+            peers: config.peers.clone(),
+            drop: config.drop,
+            redact: config.redact.clone(),
+            set_topic: config.set_topic.clone(),
+        })
+    }
+
+    fn matches(&self, envelope: &Envelope) -> bool {
+        let kind = self
+            .kinds
+            .as_ref()
+            .is_none_or(|kinds| kinds.contains(&envelope.kind));
+        let topic = self.topics.is_empty()
+            || envelope.topic.as_deref().is_some_and(|topic| {
+                self.topics
+                    .iter()
+                    .any(|pattern| topic_matches(pattern, topic))
+            });
+        let peer = self.peers.is_empty()
+            || envelope
+                .from
+                .as_ref()
+                .is_some_and(|from| self.peers.contains(from));
+        kind && topic && peer
+    }
+
+    /// `envelope` with this step's redactions and topic applied, or `None`
+    /// when neither changes anything.
+    fn rewrite(&self, envelope: &Envelope) -> Option<Envelope> {
+        let mut next: Option<Envelope> = None;
+        if !self.redact.is_empty()
+            && let Ok(mut payload) = envelope.payload_value()
+        {
+            let mut redacted = false;
+            for pointer in &self.redact {
+                if let Some(value) = payload.pointer_mut(pointer) {
+                    *value = Value::from(TransformConfig::REDACTED);
+                    redacted = true;
+                }
+            }
+            if redacted {
+                let mut copy = envelope.clone();
+                copy.payload = Envelope::raw_json(&payload);
+                next = Some(copy);
+            }
+        }
+        if let Some(topic) = &self.set_topic
+            && envelope.topic.as_ref() != Some(topic)
+        {
+            next.get_or_insert_with(|| envelope.clone()).topic = Some(topic.clone());
+        }
+        next
+    }
+}
+
+#[cfg(test)]
+#[path = "transforms_tests.rs"]
+mod tests;
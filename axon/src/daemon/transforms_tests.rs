@@ -0,0 +1,82 @@
+use serde_json::json;
+
+use super::*;
+
+const A: &str = "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+const B: &str = "ed25519.bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb";
+
+fn message(topic: Option<&str>) -> Envelope {
+    let mut envelope = Envelope::new(
+        A,
+        B,
+        MessageKind::Message,
+        json!({"build": 7, "auth": {"token": "s3cret"}}),
+    );
+    envelope.topic = topic.map(str::to_string);
+    envelope
+}
+
+fn pipeline(configs: &[TransformConfig]) -> Transforms {
+    let transforms = Transforms::default();
+    transforms.set(configs);
+    transforms
+}
+
+#[test]
+fn steps_redact_and_rename_in_order() {
+    let transforms = pipeline(&[
+        TransformConfig {
+            topics: vec!["builds.*".to_string()],
+            redact: vec!["/auth/token".to_string(), "/missing".to_string()],
+            set_topic: Some("ci.builds".to_string()),
+            ..Default::default()
+        },
+        // Sees the topic set by the first step.
+        TransformConfig {
+            topics: vec!["ci.builds".to_string()],
+            redact: vec!["/build".to_string()],
+            ..Default::default()
+        },
+    ]);
+
+    let Outcome::Changed(changed) = transforms.apply(&message(Some("builds.main"))) else {
+        panic!("expected a changed envelope");
+    };
+    assert_eq!(changed.topic.as_deref(), Some("ci.builds"));
+    assert_eq!(
+        changed.payload_value().unwrap(),
+        json!({"build": "[redacted]", "auth": {"token": "[redacted]"}})
+    );
+
+    assert_eq!(transforms.apply(&message(None)), Outcome::Unchanged);
+}
+
+#[test]
+fn drop_stops_the_pipeline_for_matching_senders() {
+    let transforms = pipeline(&[TransformConfig {
+        kinds: vec!["message".to_string()],
+        peers: vec![A.into()],
+        drop: true,
+        ..Default::default()
+    }]);
+    assert_eq!(transforms.apply(&message(None)), Outcome::Dropped);
+
+    let mut other = message(None);
+    other.from = Some(B.into());
+    assert_eq!(transforms.apply(&other), Outcome::Unchanged);
+}
+
+#[test]
+fn invalid_topics_are_skipped_and_reload_replaces_steps() {
+    let transforms = pipeline(&[TransformConfig {
+        set_topic: Some("not a topic".to_string()),
+        ..Default::default()
+    }]);
+    assert_eq!(transforms.apply(&message(None)), Outcome::Unchanged);
+
+    transforms.set(&[TransformConfig {
+        drop: true,
+        ..Default::default()
+    }]);
+    assert_eq!(transforms.apply(&message(None)), Outcome::Dropped);
+}
@@ -0,0 +1,369 @@
+use std::io::{self, BufRead, BufReader, ErrorKind, Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result, anyhow, bail};
+use ring::hmac;
+use rustls::pki_types::ServerName;
+use rustls::{ClientConfig, ClientConnection, StreamOwned};
+use rustls_platform_verifier::BuilderVerifierExt;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tracing::{debug, info, warn};
+
+use super::topics::topic_matches;
+use crate::config::{WebhookConfig, WebhookUrl, application_kind};
+use crate::message::{AgentId, Envelope, MessageKind, now_millis};
+
+/// Deliveries (including their retries) in progress across all webhooks;
+/// messages matched beyond this are dropped with a warning.
+const MAX_IN_FLIGHT: usize = 64;
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+/// Longest HTTP status line read from an endpoint.
+const MAX_STATUS_LINE: u64 = 1024;
+
+/// POSTs matching inbound envelopes to the `webhooks` endpoints.
+#[derive(Clone)]
+pub(crate) struct Webhooks {
+    hooks: Arc<[Arc<Webhook>]>,
+    permits: Arc<Semaphore>,
+}
+
+impl Webhooks {
+    /// Dispatcher for `configs`, skipping entries with an invalid URL; `None`
+    /// when nothing is left.
+    pub(crate) fn new(configs: &[WebhookConfig]) -> Option<Self> {
+        let hooks: Vec<_> = configs
+            .iter()
+            .filter_map(|config| match Webhook::new(config) {
+                Ok(hook) => Some(Arc::new(hook)),
+                Err(err) => {
+                    warn!(url = %config.url, error = %err, "skipping webhook with invalid url");
+                    None
+                }
+            })
+            .collect();
+        if hooks.is_empty() {
+            return None;
+        }
+        info!(
+            count = hooks.len(),
+            "forwarding inbound messages to webhooks"
+        );
+        Some(Self {
+            hooks: hooks.into(),
+            permits: Arc::new(Semaphore::new(MAX_IN_FLIGHT)),
+        })
+    }
+
+    /// Start a background delivery of `envelope` to every matching webhook.
+    pub(crate) fn dispatch(&self, envelope: &Envelope) {
+        let mut body: Option<Arc<[u8]>> = None;
+        for hook in self.hooks.iter().filter(|hook| hook.matches(envelope)) {
+            let body = match &body {
+                Some(body) => body.clone(),
+                None => match serde_json::to_vec(envelope) {
+                    Ok(encoded) => body.insert(encoded.into()).clone(),
+                    Err(err) => {
+                        warn!(msg_id = %envelope.id, error = %err, "failed to encode envelope for webhooks");
+                        return;
+                    }
+                },
+            };
+            let Ok(permit) = self.permits.clone().try_acquire_owned() else {
+                warn!(msg_id = %envelope.id, url = %hook.url_text, "too many webhook deliveries in flight; dropping");
+                continue;
+            };
+            let (hook, msg_id) = (hook.clone(), envelope.id.to_string());
+            tokio::spawn(async move {
+                hook.deliver(&msg_id, body, permit).await;
+            });
+        }
+    }
+}
+
+pub(crate) struct Webhook {
+    url_text: String,
+    url: WebhookUrl,
+    /// `None` matches every kind.
+    kinds: Option<Vec<MessageKind>>,
+    topics: Vec<String>,
+    peers: Vec<AgentId>,
+    key: Option<hmac::Key>,
+    max_retries: u32,
+    connect_timeout: Duration,
+    timeout: Duration,
+}
+
+impl Webhook {
+    pub(crate) fn new(config: &WebhookConfig) -> Result<Self> {
+        let url = WebhookUrl::parse(&config.url).map_err(|err| anyhow!(err))?;
+        Ok(Self {
+            url_text: config.url.clone(),
+            url,
+            kinds: (!config.kinds.is_empty()).then(|| {
+                config
+                    .kinds
+                    .iter()
+                    .filter_map(|kind| application_kind(kind))
+                    .collect()
+            }),
+            topics: config.topics.clone(),
+            peers: config.peers.clone(),
+            key: config
+                .secret
+                .as_ref()
+                .map(|secret| hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes())),
+            max_retries: config.max_retries(),
+            connect_timeout: config.connect_timeout(),
+            timeout: config.timeout(),
+        })
+    }
+
+    pub(crate) fn matches(&self, envelope: &Envelope) -> bool {
+        let kind = self
+            .kinds
+            .as_ref()
+            .is_none_or(|kinds| kinds.contains(&envelope.kind));
+        let topic = self.topics.is_empty()
+            || envelope.topic.as_deref().is_some_and(|topic| {
+                self.topics
+                    .iter()
+                    .any(|pattern| topic_matches(pattern, topic))
+            });
+        let peer = self.peers.is_empty()
+            || envelope
+                .from
+                .as_ref()
+                .is_some_and(|from| self.peers.contains(from));
+        kind && topic && peer
+    }
+
+    /// POST `body`, retrying with backoff; `true` once the endpoint answers
+    /// 2xx. Holds `_permit` until done.
+    pub(crate) async fn deliver(
+        &self,
+        msg_id: &str,
+        body: Arc<[u8]>,
+        _permit: OwnedSemaphorePermit,
+    ) -> bool {
+        let mut backoff = INITIAL_BACKOFF;
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            // Each attempt is signed with a fresh timestamp.
+            let request = self.request(msg_id, &body, now_millis() / 1000);
+            let error = match self.attempt(request).await {
+                Ok(status) if (200..300).contains(&status) => {
+                    debug!(msg_id, url = %self.url_text, status, attempt, "webhook delivered");
+                    return true;
+                }
+                Ok(status) if status != 429 && status < 500 => {
+                    warn!(msg_id, url = %self.url_text, status, "webhook rejected message");
+                    return false;
+                }
+                Ok(status) => format!("HTTP {status}"),
+                Err(err) => format!("{err:#}"),
+            };
+            if attempt > self.max_retries {
+                warn!(msg_id, url = %self.url_text, attempts = attempt, error, "webhook delivery failed");
+                return false;
+            }
+            debug!(msg_id, url = %self.url_text, attempt, error, "webhook delivery failed; retrying");
+            tokio::time::sleep(backoff).await;
+            backoff = (backoff * 2).min(MAX_BACKOFF);
+        }
+    }
+
+    /// One delivery attempt: resolve and connect within `connect_timeout`,
+    /// then finish the exchange within `timeout`. Returns the status.
+    async fn attempt(&self, request: Vec<u8>) -> Result<u16> {
+        let connect_deadline = Instant::now() + self.connect_timeout;
+        let host = (self.url.host.as_str(), self.url.port);
+        let addrs: Vec<SocketAddr> =
+            tokio::time::timeout(self.connect_timeout, tokio::net::lookup_host(host))
+                .await
+                .map_err(|_| anyhow!("timed out resolving {}", self.url.host))?
+                .with_context(|| format!("failed to resolve {}", self.url.host))?
+                .collect();
+        let (url, timeout) = (self.url.clone(), self.timeout);
+        tokio::task::spawn_blocking(move || post(&url, addrs, &request, connect_deadline, timeout))
+            .await
+            .map_err(|err| anyhow!("webhook task failed: {err}"))?
+    }
+
+    /// The full HTTP/1.1 request for one delivery attempt at `timestamp`
+    /// (unix seconds).
+    fn request(&self, msg_id: &str, body: &[u8], timestamp: u64) -> Vec<u8> {
+        let url = &self.url;
+        let default_port = if url.tls { 443 } else { 80 };
+        let host = if url.host.contains(':') {
+            format!("[{}]", url.host)
+        } else {
+            url.host.clone()
+        };
+        let mut head = format!("POST {} HTTP/1.1\r\nHost: {host}", url.path);
+        if url.port != default_port {
+            head.push_str(&format!(":{}", url.port));
+        }
+        head.push_str(&format!(
+            "\r\nUser-Agent: axon/{}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\nX-Axon-Msg-Id: {msg_id}\r\n",
+            env!("CARGO_PKG_VERSION"),
+            body.len()
+        ));
+        if let Some(key) = &self.key {
+            head.push_str(&format!(
+                "X-Axon-Timestamp: {timestamp}\r\nX-Axon-Signature: sha256={}\r\n",
+                signature(key, timestamp, body)
+            ));
+        }
+        head.push_str("\r\n");
+        let mut request = head.into_bytes();
+        request.extend_from_slice(body);
+        request
+    }
+}
+
+/// Lowercase hex HMAC-SHA256 of `<timestamp>.<body>`. Covering the
+/// timestamp lets receivers reject replayed deliveries.
+pub(crate) fn signature(key: &hmac::Key, timestamp: u64, body: &[u8]) -> String {
+    let mut context = hmac::Context::with_key(key);
+    context.update(format!("{timestamp}.").as_bytes());
+    context.update(body);
+    context
+        .sign()
+        .as_ref()
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+/// Send `request` to the first of `addrs` that accepts by `connect_deadline`
+/// and return the response status. Blocking; the TLS handshake, the write,
+/// and the status line together get `timeout` once connected.
+fn post(
+    url: &WebhookUrl,
+    addrs: Vec<SocketAddr>,
+    request: &[u8],
+    connect_deadline: Instant,
+    timeout: Duration,
+) -> Result<u16> {
+    let tcp = connect_any(addrs, connect_deadline)
+        .with_context(|| format!("failed to connect to {}", url.host))?;
+    let tcp = Deadline {
+        tcp,
+        at: Instant::now() + timeout,
+    };
+    if url.tls {
+        let server_name = ServerName::try_from(url.host.clone())
+            .map_err(|err| anyhow!("invalid TLS server name {}: {err}", url.host))?;
+        let connection = ClientConnection::new(tls_config()?, server_name)?;
+        exchange(StreamOwned::new(connection, tcp), request)
+    } else {
+        exchange(tcp, request)
+    }
+}
+
+/// Connect to the first of `addrs` that accepts, so a host resolving to an
+/// unreachable address (often IPv6) still gets its other addresses tried
+/// with whatever is left before `deadline`.
+fn connect_any(
+    addrs: impl IntoIterator<Item = SocketAddr>,
+    deadline: Instant,
+) -> Result<TcpStream> {
+    let mut last_err = None;
+    for addr in addrs {
+        let Some(left) = time_left(deadline) else {
+            return Err(anyhow!("timed out connecting").context(format!("{addr}")));
+        };
+        match TcpStream::connect_timeout(&addr, left) {
+            Ok(tcp) => return Ok(tcp),
+            Err(err) => last_err = Some(anyhow!(err).context(format!("{addr}"))),
+        }
+    }
+    Err(last_err.unwrap_or_else(|| anyhow!("no addresses")))
+}
+
+/// A connection whose reads and writes fail once `at` passes, so an endpoint
+/// trickling bytes cannot stretch an attempt past its timeout.
+struct Deadline {
+    tcp: TcpStream,
+    at: Instant,
+}
+
+impl Deadline {
+    /// The time left, or a `TimedOut` error once there is none.
+    fn left(&self) -> io::Result<Duration> {
+        time_left(self.at).ok_or_else(|| io::Error::new(ErrorKind::TimedOut, "timed out"))
+    }
+}
+
+impl Read for Deadline {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.tcp.set_read_timeout(Some(self.left()?))?;
+        self.tcp.read(buf).map_err(timed_out)
+    }
+}
+
+impl Write for Deadline {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.tcp.set_write_timeout(Some(self.left()?))?;
+        self.tcp.write(buf).map_err(timed_out)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.tcp.flush()
+    }
+}
+
+/// Time until `deadline`, or `None` once it has passed.
+fn time_left(deadline: Instant) -> Option<Duration> {
+    Some(deadline.saturating_duration_since(Instant::now())).filter(|left| !left.is_zero())
+}
+
+/// Socket timeouts surface as `WouldBlock` on Unix; report them as timeouts.
+fn timed_out(err: io::Error) -> io::Error {
+    match err.kind() {
+        ErrorKind::WouldBlock => io::Error::new(ErrorKind::TimedOut, "timed out"),
+        _ => err,
+    }
+}
+
+fn exchange(mut stream: impl Read + Write, request: &[u8]) -> Result<u16> {
+    stream
+        .write_all(request)
+        .context("failed to send request")?;
+    stream.flush()?;
+    let mut line = Vec::new();
+    BufReader::new(stream)
+        .take(MAX_STATUS_LINE)
+        .read_until(b'\n', &mut line)
+        .context("failed to read response")?;
+    let line = String::from_utf8_lossy(&line);
+    let mut parts = line.split_whitespace();
+    match (parts.next(), parts.next().map(str::parse::<u16>)) {
+        (Some(version), Some(Ok(status))) if version.starts_with("HTTP/1.") => Ok(status),
+        _ if line.is_empty() => bail!("connection closed before a response"),
+        _ => bail!("invalid HTTP status line: {}", line.trim_end()),
+    }
+}
+
+/// Client config trusting the platform's certificate store.
+fn tls_config() -> Result<Arc<ClientConfig>> {
+    static CONFIG: OnceLock<Result<Arc<ClientConfig>, String>> = OnceLock::new();
+    CONFIG
+        .get_or_init(|| {
+            ClientConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+                .with_safe_default_protocol_versions()
+                .and_then(|builder| builder.with_platform_verifier())
+                .map(|builder| Arc::new(builder.with_no_client_auth()))
+                .map_err(|err| format!("failed to load platform TLS roots: {err}"))
+        })
+        .clone()
+        .map_err(|err| anyhow!(err))
+}
+
+#[cfg(test)]
+#[path = "webhooks_tests.rs"]
+mod tests;
@@ -0,0 +1,224 @@
+use std::net::TcpListener;
+use std::sync::mpsc;
+use std::thread;
+
+use serde_json::json;
+
+use super::*;
+
+const A: &str = "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+const B: &str = "ed25519.bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb";
+
+struct Received {
+    head: String,
+    body: Vec<u8>,
+}
+
+/// Answer one connection per entry of `statuses`, reporting each request.
+fn serve(statuses: Vec<u16>) -> (String, mpsc::Receiver<Received>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!(
+        "http://{}/hooks/axon?src=test",
+        listener.local_addr().unwrap()
+    );
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        for status in statuses {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut head = String::new();
+            let mut length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if let Some(value) = line.strip_prefix("Content-Length: ") {
+                    length = value.trim().parse().unwrap();
+                }
+                if line == "\r\n" {
+                    break;
+                }
+                head.push_str(&line);
+            }
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+            let reply = format!("HTTP/1.1 {status} Whatever\r\nContent-Length: 0\r\n\r\n");
+            reader.get_mut().write_all(reply.as_bytes()).unwrap();
+            tx.send(Received { head, body }).unwrap();
+        }
+    });
+    (url, rx)
+}
+
+fn config(url: &str) -> WebhookConfig {
+    WebhookConfig {
+        url: url.to_string(),
+        ..Default::default()
+    }
+}
+
+fn permit() -> OwnedSemaphorePermit {
+    Arc::new(Semaphore::new(1)).try_acquire_owned().unwrap()
+}
+
+fn message(topic: Option<&str>) -> Envelope {
+    let mut envelope = Envelope::new(A, B, MessageKind::Message, json!({"n": 1}));
+    envelope.topic = topic.map(str::to_string);
+    envelope
+}
+
+#[test]
+fn filters_combine_kind_topic_and_peer() {
+    let hook = Webhook::new(&WebhookConfig {
+        kinds: vec!["message".to_string()],
+        topics: vec!["builds.*".to_string()],
+        peers: vec![AgentId::from(A)],
+        ..config("http://localhost/")
+    })
+    .unwrap();
+    assert!(hook.matches(&message(Some("builds.done"))));
+    assert!(!hook.matches(&message(Some("deploys.done"))));
+    assert!(!hook.matches(&message(None)), "topic filter needs a topic");
+
+    let mut request = message(Some("builds.done"));
+    request.kind = MessageKind::Request;
+    assert!(!hook.matches(&request));
+    let mut stranger = message(Some("builds.done"));
+    stranger.from = Some(AgentId::from(B));
+    assert!(!hook.matches(&stranger));
+
+    let unfiltered = Webhook::new(&config("http://localhost/")).unwrap();
+    assert!(unfiltered.matches(&request));
+    let unknown_kind = Webhook::new(&WebhookConfig {
+        kinds: vec!["heartbeat".to_string()],
+        ..config("http://localhost/")
+    })
+    .unwrap();
+    assert!(
+        !unknown_kind.matches(&request),
+        "invalid kinds match nothing"
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn delivery_is_signed_and_retried_after_server_errors() {
+    let (url, requests) = serve(vec![503, 200]);
+    let hook = Webhook::new(&WebhookConfig {
+        secret: Some("s3cret".to_string()),
+        ..config(&url)
+    })
+    .unwrap();
+    let envelope = message(None);
+    let body: Arc<[u8]> = serde_json::to_vec(&envelope).unwrap().into();
+
+    assert!(
+        hook.deliver(&envelope.id.to_string(), body.clone(), permit())
+            .await
+    );
+    let first = requests.recv().unwrap();
+    let second = requests.recv().unwrap();
+    assert_eq!(first.body, second.body);
+    assert_eq!(second.body, body.as_ref());
+    assert!(
+        second
+            .head
+            .starts_with("POST /hooks/axon?src=test HTTP/1.1\r\n")
+    );
+    assert!(second.head.contains("Content-Type: application/json\r\n"));
+    assert!(
+        second
+            .head
+            .contains(&format!("X-Axon-Msg-Id: {}\r\n", envelope.id))
+    );
+    let timestamp: u64 = second
+        .head
+        .lines()
+        .find_map(|line| line.strip_prefix("X-Axon-Timestamp: "))
+        .expect("timestamp header")
+        .parse()
+        .unwrap();
+    assert!(timestamp.abs_diff(now_millis() / 1000) < 60);
+    let key = hmac::Key::new(hmac::HMAC_SHA256, b"s3cret");
+    let expected = format!(
+        "X-Axon-Signature: sha256={}\r\n",
+        signature(&key, timestamp, &body)
+    );
+    assert!(second.head.contains(&expected), "{}", second.head);
+    assert_ne!(
+        signature(&key, timestamp, &body),
+        signature(&key, timestamp + 1, &body),
+        "the timestamp is signed"
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn client_errors_are_not_retried() {
+    let (url, requests) = serve(vec![400]);
+    let hook = Webhook::new(&config(&url)).unwrap();
+    assert!(!hook.deliver("id", Arc::from(&b"{}"[..]), permit()).await);
+    assert!(requests.recv().is_ok());
+    assert!(requests.recv().is_err(), "only one attempt");
+}
+
+#[tokio::test(start_paused = true)]
+async fn retries_stop_at_max_retries() {
+    let (url, requests) = serve(vec![500, 500]);
+    let hook = Webhook::new(&WebhookConfig {
+        max_retries: Some(1),
+        ..config(&url)
+    })
+    .unwrap();
+    assert!(!hook.deliver("id", Arc::from(&b"{}"[..]), permit()).await);
+    assert_eq!(requests.iter().count(), 2);
+}
+
+#[test]
+fn connect_falls_through_to_a_later_address() {
+    let closed = TcpListener::bind("127.0.0.1:0").unwrap();
+    let closed_addr = closed.local_addr().unwrap();
+    drop(closed);
+    let open = TcpListener::bind("127.0.0.1:0").unwrap();
+    let open_addr = open.local_addr().unwrap();
+    let deadline = Instant::now() + Duration::from_secs(2);
+    let tcp = connect_any([closed_addr, open_addr], deadline).unwrap();
+    assert_eq!(tcp.peer_addr().unwrap(), open_addr);
+    let err = connect_any([closed_addr], deadline).unwrap_err();
+    assert!(
+        format!("{err:#}").contains(&closed_addr.to_string()),
+        "{err:#}"
+    );
+    let err = connect_any([open_addr], Instant::now()).unwrap_err();
+    assert!(format!("{err:#}").contains("timed out"), "{err:#}");
+}
+
+#[test]
+fn a_trickling_endpoint_cannot_outlast_the_timeout() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        // Each byte would reset a per-read timeout.
+        while stream.write_all(b"H").is_ok() {
+            thread::sleep(Duration::from_millis(100));
+        }
+    });
+    let url = WebhookUrl::parse(&format!("http://{addr}/")).unwrap();
+    let started = Instant::now();
+    let connect_deadline = started + Duration::from_secs(2);
+    let err = post(
+        &url,
+        vec![addr],
+        b"POST",
+        connect_deadline,
+        Duration::from_millis(500),
+    )
+    .unwrap_err();
+    assert!(format!("{err:#}").contains("timed out"), "{err:#}");
+    assert!(started.elapsed() < Duration::from_secs(2));
+}
+
+#[test]
+fn invalid_urls_are_skipped() {
+    assert!(Webhooks::new(&[config("ftp://example.com/")]).is_none());
+    let webhooks = Webhooks::new(&[config("ftp://example.com/"), config("http://localhost:9/")]);
+    assert_eq!(webhooks.unwrap().hooks.len(), 1);
+}
@@ -198,6 +198,30 @@ pub async fn run_mdns_discovery(
     Ok(())
 }
 
+/// Browse for `timeout` and report whether an advertisement carrying
+/// `agent_id` resolves, i.e. whether that agent is visible to mDNS peers on
+/// this network.
+pub async fn mdns_advertisement_visible(agent_id: &str, timeout: Duration) -> Result<bool> {
+    let mdns = ServiceDaemon::new().context("failed to start mDNS daemon")?;
+    let receiver = mdns
+        .browse(SERVICE_TYPE)
+        .context("failed to start mDNS browse")?;
+    let seen = tokio::time::timeout(timeout, async {
+        while let Ok(event) = receiver.recv_async().await {
+            if let ServiceEvent::ServiceResolved(info) = event
+                && info.get_property_val_str("agent_id") == Some(agent_id)
+            {
+                return true;
+            }
+        }
+        false
+    })
+    .await
+    .unwrap_or(false);
+    let _ = mdns.shutdown();
+    Ok(seen)
+}
+
 fn parse_resolved_service(
     local_agent_id: &str,
     info: &ServiceInfo,
@@ -8,11 +8,13 @@ async fn static_discovery_emits_all_peers() {
             agent_id: "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa".into(),
             addr: PeerAddr::Socket("127.0.0.1:7100".parse().expect("addr")),
             pubkey: "Zm9v".to_string(),
+            tuning: Default::default(),
         },
         PersistedStaticPeerConfig {
             agent_id: "ed25519.bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb".into(),
             addr: PeerAddr::Socket("127.0.0.1:7101".parse().expect("addr")),
             pubkey: "YmFy".to_string(),
+            tuning: Default::default(),
         },
     ];
 
@@ -46,6 +48,7 @@ async fn static_discovery_stays_alive() {
         agent_id: "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa".into(),
         addr: PeerAddr::Socket("127.0.0.1:7100".parse().expect("addr")),
         pubkey: "Zm9v".to_string(),
+        tuning: Default::default(),
     }];
 
     let (tx, mut rx) = mpsc::channel(8);
@@ -0,0 +1,203 @@
+//! Error registry: every code AXON reports on the wire (`error` envelope
+//! payloads), over IPC (`"ok": false` replies), and as a CLI exit status,
+//! with whether retrying can succeed. `spec/MESSAGE_TYPES.md` ("Error
+//! Payloads") and `spec/IPC.md` ("Error Codes") document the same tables.
+
+use serde::{Deserialize, Serialize};
+
+// ---------------------------------------------------------------------------
+// CLI exit codes (README "Exit codes")
+// ---------------------------------------------------------------------------
+
+/// Local or runtime failure after argument parsing.
+pub const EXIT_RUNTIME: u8 = 1;
+/// Usage error, a daemon failure without a dedicated code, or a remote
+/// `error` envelope.
+pub const EXIT_FAILURE_REPLY: u8 = 2;
+pub const EXIT_PEER_NOT_FOUND: u8 = 3;
+pub const EXIT_TIMEOUT: u8 = 4;
+pub const EXIT_PEER_UNREACHABLE: u8 = 5;
+/// Not permitted to connect to the daemon socket.
+pub const EXIT_IPC_AUTH: u8 = 6;
+/// No daemon is listening on the socket.
+pub const EXIT_DAEMON_NOT_RUNNING: u8 = 7;
+
+// ---------------------------------------------------------------------------
+// Wire error codes
+// ---------------------------------------------------------------------------
+
+/// Codes the daemon puts in the payload of `error` envelopes it sends.
+/// Applications may use codes of their own; those parse as `None` in
+/// [`WireErrorCode::parse`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WireErrorCode {
+    Unhandled,
+    UnknownKind,
+    InvalidEnvelope,
+    RateLimited,
+    MessageTooLarge,
+    NotAuthorized,
+    HandlerBusy,
+    HandlerTimeout,
+    HandlerFailed,
+    HandlerInvalidOutput,
+    Rejected,
+}
+
+impl WireErrorCode {
+    pub const ALL: [Self; 11] = [
+        Self::Unhandled,
+        Self::UnknownKind,
+        Self::InvalidEnvelope,
+        Self::RateLimited,
+        Self::MessageTooLarge,
+        Self::NotAuthorized,
+        Self::HandlerBusy,
+        Self::HandlerTimeout,
+        Self::HandlerFailed,
+        Self::HandlerInvalidOutput,
+        Self::Rejected,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Unhandled => "unhandled",
+            Self::UnknownKind => "unknown_kind",
+            Self::InvalidEnvelope => "invalid_envelope",
+            Self::RateLimited => "rate_limited",
+            Self::MessageTooLarge => "message_too_large",
+            Self::NotAuthorized => "not_authorized",
+            Self::HandlerBusy => "handler_busy",
+            Self::HandlerTimeout => "handler_timeout",
+            Self::HandlerFailed => "handler_failed",
+            Self::HandlerInvalidOutput => "handler_invalid_output",
+            Self::Rejected => "rejected",
+        }
+    }
+
+    /// The registered code named `code`, if any.
+    pub fn parse(code: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|known| known.as_str() == code)
+    }
+
+    /// Whether the same request may succeed if sent again later.
+    pub fn retryable(self) -> bool {
+        matches!(
+            self,
+            Self::RateLimited | Self::HandlerBusy | Self::HandlerTimeout
+        )
+    }
+
+    /// CLI exit status for a `request` answered with this code.
+    pub fn exit_code(self) -> u8 {
+        EXIT_FAILURE_REPLY
+    }
+}
+
+impl std::fmt::Display for WireErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// IPC error codes
+// ---------------------------------------------------------------------------
+
+/// IPC error codes returned in error responses.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum IpcErrorCode {
+    InvalidCommand,
+    CommandTooLarge,
+    PeerNotFound,
+    SelfSend,
+    PeerUnreachable,
+    Timeout,
+    ReloadFailed,
+    HistoryDisabled,
+    ReplyLost,
+    ShuttingDown,
+    InternalError,
+}
+
+impl std::fmt::Display for IpcErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl IpcErrorCode {
+    pub const ALL: [Self; 11] = [
+        Self::InvalidCommand,
+        Self::CommandTooLarge,
+        Self::PeerNotFound,
+        Self::SelfSend,
+        Self::PeerUnreachable,
+        Self::Timeout,
+        Self::ReloadFailed,
+        Self::HistoryDisabled,
+        Self::ReplyLost,
+        Self::ShuttingDown,
+        Self::InternalError,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::InvalidCommand => "invalid_command",
+            Self::CommandTooLarge => "command_too_large",
+            Self::PeerNotFound => "peer_not_found",
+            Self::SelfSend => "self_send",
+            Self::PeerUnreachable => "peer_unreachable",
+            Self::Timeout => "timeout",
+            Self::ReloadFailed => "reload_failed",
+            Self::HistoryDisabled => "history_disabled",
+            Self::ReplyLost => "reply_lost",
+            Self::ShuttingDown => "shutting_down",
+            Self::InternalError => "internal_error",
+        }
+    }
+
+    /// Human-readable explanation of the error code.
+    pub fn message(&self) -> &'static str {
+        match self {
+            Self::InvalidCommand => "malformed command, unknown cmd, or invalid field value",
+            Self::CommandTooLarge => "IPC command exceeds 64KB limit",
+            Self::PeerNotFound => "target agent_id not in peer table",
+            Self::SelfSend => "cannot send messages to self",
+            Self::PeerUnreachable => "peer known but connection failed",
+            Self::Timeout => "request timed out waiting for peer response",
+            Self::ReloadFailed => "config.yaml could not be loaded; previous config kept",
+            Self::HistoryDisabled => {
+                "message history is disabled; set history.enabled: true in config.yaml"
+            }
+            Self::ReplyLost => {
+                "inbound envelopes arrived too fast to check while awaiting the reply; \
+                 it may have been missed (see inbound events)"
+            }
+            Self::ShuttingDown => "daemon shut down before the awaited reply arrived",
+            Self::InternalError => "unexpected daemon error",
+        }
+    }
+
+    /// Whether the same command may succeed if sent again later without
+    /// changes. A peer that is down or slow can recover; the others need a
+    /// different command, config, or peer table.
+    pub fn retryable(self) -> bool {
+        matches!(self, Self::PeerUnreachable | Self::Timeout)
+    }
+
+    /// CLI exit status for a daemon reply carrying this code.
+    pub fn exit_code(self) -> u8 {
+        match self {
+            Self::PeerNotFound => EXIT_PEER_NOT_FOUND,
+            Self::Timeout => EXIT_TIMEOUT,
+            Self::PeerUnreachable => EXIT_PEER_UNREACHABLE,
+            _ => EXIT_FAILURE_REPLY,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,62 @@
+use serde_json::json;
+
+use super::*;
+use crate::message::ErrorPayload;
+
+#[test]
+fn codes_serialize_as_their_registry_names() {
+    for code in WireErrorCode::ALL {
+        assert_eq!(serde_json::to_value(code).unwrap(), json!(code.as_str()));
+        assert_eq!(WireErrorCode::parse(code.as_str()), Some(code));
+    }
+    for code in IpcErrorCode::ALL {
+        assert_eq!(serde_json::to_value(code).unwrap(), json!(code.as_str()));
+        assert_eq!(code.to_string(), code.as_str());
+    }
+    assert_eq!(WireErrorCode::parse("app_specific"), None);
+}
+
+#[test]
+fn only_transient_failures_are_retryable() {
+    let wire: Vec<_> = WireErrorCode::ALL
+        .into_iter()
+        .filter(|code| code.retryable())
+        .collect();
+    assert_eq!(
+        wire,
+        [
+            WireErrorCode::RateLimited,
+            WireErrorCode::HandlerBusy,
+            WireErrorCode::HandlerTimeout
+        ]
+    );
+    let ipc: Vec<_> = IpcErrorCode::ALL
+        .into_iter()
+        .filter(|code| code.retryable())
+        .collect();
+    assert_eq!(ipc, [IpcErrorCode::PeerUnreachable, IpcErrorCode::Timeout]);
+}
+
+#[test]
+fn exit_codes_follow_the_readme_table() {
+    assert_eq!(IpcErrorCode::PeerNotFound.exit_code(), 3);
+    assert_eq!(IpcErrorCode::Timeout.exit_code(), 4);
+    assert_eq!(IpcErrorCode::PeerUnreachable.exit_code(), 5);
+    assert_eq!(IpcErrorCode::InvalidCommand.exit_code(), 2);
+    assert_eq!(WireErrorCode::RateLimited.exit_code(), 2);
+}
+
+#[test]
+fn error_payloads_take_retryable_from_the_registry() {
+    let payload = ErrorPayload::new(WireErrorCode::HandlerBusy, "busy");
+    assert_eq!(
+        payload.to_value(),
+        json!({"code": "handler_busy", "message": "busy", "retryable": true})
+    );
+    assert_eq!(payload.wire_code(), Some(WireErrorCode::HandlerBusy));
+
+    let payload = ErrorPayload::new(WireErrorCode::HandlerFailed, "exited")
+        .with_details(json!({"exit_code": 3}));
+    assert_eq!(payload.to_value()["details"], json!({"exit_code": 3}));
+    assert!(!payload.retryable);
+}
@@ -0,0 +1,201 @@
+use std::num::NonZeroU32;
+
+use anyhow::{Context, Result, anyhow, bail, ensure};
+use base64::{Engine as _, engine::general_purpose::STANDARD};
+use ed25519_dalek::SigningKey;
+use ring::aead::{Aad, CHACHA20_POLY1305, LessSafeKey, NONCE_LEN, Nonce, UnboundKey};
+use ring::pbkdf2;
+use serde::{Deserialize, Serialize};
+
+use super::Identity;
+
+/// `format` value identifying an identity backup file.
+pub const BACKUP_FORMAT: &str = "axon-identity-backup";
+const BACKUP_VERSION: u32 = 1;
+const KDF_PBKDF2_SHA256: &str = "pbkdf2-hmac-sha256";
+const PBKDF2_ITERATIONS: u32 = 600_000;
+/// Highest iteration count accepted from a backup file, so a crafted one
+/// cannot pin a CPU before the passphrase check can fail.
+const MAX_PBKDF2_ITERATIONS: u32 = 10 * PBKDF2_ITERATIONS;
+const SALT_LEN: usize = 16;
+
+/// On-disk backup: JSON with the agent ID in the clear and the 32-byte seed
+/// either base64-encoded or sealed with a passphrase-derived key.
+#[derive(Debug, Serialize, Deserialize)]
+struct BackupFile {
+    format: String,
+    version: u32,
+    agent_id: String,
+    #[serde(flatten)]
+    key: BackupKey,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "encryption", rename_all = "snake_case")]
+enum BackupKey {
+    None {
+        seed: String,
+    },
+    /// ChaCha20-Poly1305 over the seed, keyed by PBKDF2-HMAC-SHA256 of the
+    /// passphrase; the agent ID is the associated data.
+    Passphrase {
+        kdf: String,
+        iterations: u32,
+        salt: String,
+        nonce: String,
+        ciphertext: String,
+    },
+}
+
+impl Identity {
+    /// Serialize the keypair as a backup file, sealed when `passphrase` is set.
+    pub fn export_backup(&self, passphrase: Option<&str>) -> Result<String> {
+        self.export_backup_with(passphrase, PBKDF2_ITERATIONS)
+    }
+
+    fn export_backup_with(&self, passphrase: Option<&str>, iterations: u32) -> Result<String> {
+        let seed = self.signing_key.to_bytes();
+        let key = match passphrase {
+            None => BackupKey::None {
+                seed: STANDARD.encode(seed),
+            },
+            Some(passphrase) => {
+                let mut salt = [0u8; SALT_LEN];
+                let mut nonce = [0u8; NONCE_LEN];
+                getrandom::getrandom(&mut salt)
+                    .and_then(|()| getrandom::getrandom(&mut nonce))
+                    .map_err(|err| anyhow!("failed to gather randomness: {err}"))?;
+                let mut sealed = seed.to_vec();
+                sealing_key(passphrase, &salt, iterations)?
+                    .seal_in_place_append_tag(
+                        Nonce::assume_unique_for_key(nonce),
+                        Aad::from(self.agent_id.as_bytes()),
+                        &mut sealed,
+                    )
+                    .map_err(|_| anyhow!("failed to encrypt identity backup"))?;
+                BackupKey::Passphrase {
+                    kdf: KDF_PBKDF2_SHA256.to_string(),
+                    iterations,
+                    salt: STANDARD.encode(salt),
+                    nonce: STANDARD.encode(nonce),
+                    ciphertext: STANDARD.encode(sealed),
+                }
+            }
+        };
+        let file = BackupFile {
+            format: BACKUP_FORMAT.to_string(),
+            version: BACKUP_VERSION,
+            agent_id: self.agent_id.clone(),
+            key,
+        };
+        serde_json::to_string_pretty(&file).context("failed to encode identity backup")
+    }
+
+    /// Restore a keypair from [`Identity::export_backup`] output. The derived
+    /// agent ID must match the one recorded in the backup.
+    pub fn from_backup(text: &str, passphrase: Option<&str>) -> Result<Self> {
+        let file = parse_backup(text)?;
+        let seed = match file.key {
+            BackupKey::None { seed } => STANDARD
+                .decode(seed.trim())
+                .context("invalid identity backup: seed is not base64")?,
+            BackupKey::Passphrase {
+                kdf,
+                iterations,
+                salt,
+                nonce,
+                ciphertext,
+            } => {
+                ensure!(
+                    kdf == KDF_PBKDF2_SHA256,
+                    "unsupported identity backup kdf '{kdf}'"
+                );
+                let passphrase = passphrase.ok_or_else(|| {
+                    anyhow!("identity backup is encrypted; a passphrase is required")
+                })?;
+                let salt = STANDARD
+                    .decode(salt)
+                    .context("invalid identity backup: salt is not base64")?;
+                let nonce: [u8; NONCE_LEN] = STANDARD
+                    .decode(nonce)
+                    .ok()
+                    .and_then(|nonce| nonce.try_into().ok())
+                    .ok_or_else(|| anyhow!("invalid identity backup: malformed nonce"))?;
+                let mut sealed = STANDARD
+                    .decode(ciphertext)
+                    .context("invalid identity backup: ciphertext is not base64")?;
+                sealing_key(passphrase, &salt, iterations)?
+                    .open_in_place(
+                        Nonce::assume_unique_for_key(nonce),
+                        Aad::from(file.agent_id.as_bytes()),
+                        &mut sealed,
+                    )
+                    .map_err(|_| anyhow!("wrong passphrase or corrupted identity backup"))?
+                    .to_vec()
+            }
+        };
+        let seed: [u8; 32] = seed.try_into().map_err(|seed: Vec<u8>| {
+            anyhow!(
+                "invalid identity backup: expected a 32-byte seed, got {} bytes",
+                seed.len()
+            )
+        })?;
+        let identity = Identity::from_signing_key(SigningKey::from_bytes(&seed));
+        if identity.agent_id != file.agent_id {
+            bail!(
+                "identity backup is corrupt: key derives {} but the backup records {}",
+                identity.agent_id,
+                file.agent_id
+            );
+        }
+        Ok(identity)
+    }
+
+    /// Whether a backup needs a passphrase to restore.
+    pub fn backup_is_encrypted(text: &str) -> Result<bool> {
+        Ok(matches!(
+            parse_backup(text)?.key,
+            BackupKey::Passphrase { .. }
+        ))
+    }
+}
+
+fn parse_backup(text: &str) -> Result<BackupFile> {
+    let file: BackupFile = serde_json::from_str(text).context("not an AXON identity backup")?;
+    ensure!(
+        file.format == BACKUP_FORMAT,
+        "not an AXON identity backup (format '{}')",
+        file.format
+    );
+    ensure!(
+        file.version == BACKUP_VERSION,
+        "unsupported identity backup version {}",
+        file.version
+    );
+    Ok(file)
+}
+
+fn sealing_key(passphrase: &str, salt: &[u8], iterations: u32) -> Result<LessSafeKey> {
+    let iterations = NonZeroU32::new(iterations)
+        .ok_or_else(|| anyhow!("invalid identity backup: zero kdf iterations"))?;
+    ensure!(
+        iterations.get() <= MAX_PBKDF2_ITERATIONS,
+        "invalid identity backup: {iterations} kdf iterations exceed the limit of \
+         {MAX_PBKDF2_ITERATIONS}"
+    );
+    let mut key = [0u8; 32];
+    pbkdf2::derive(
+        pbkdf2::PBKDF2_HMAC_SHA256,
+        iterations,
+        salt,
+        passphrase.as_bytes(),
+        &mut key,
+    );
+    let key = UnboundKey::new(&CHACHA20_POLY1305, &key)
+        .map_err(|_| anyhow!("failed to build identity backup key"))?;
+    Ok(LessSafeKey::new(key))
+}
+
+#[cfg(test)]
+#[path = "backup_tests.rs"]
+mod tests;
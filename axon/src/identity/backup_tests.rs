@@ -0,0 +1,78 @@
+use super::*;
+
+/// Low iteration count so debug-build tests stay fast.
+const TEST_ITERATIONS: u32 = 1_000;
+
+fn identity() -> Identity {
+    Identity::from_signing_key(SigningKey::from_bytes(&[9u8; 32]))
+}
+
+#[test]
+fn plain_backup_roundtrips() {
+    let original = identity();
+    let backup = original.export_backup(None).expect("export");
+    assert!(!Identity::backup_is_encrypted(&backup).expect("parse"));
+
+    let restored = Identity::from_backup(&backup, None).expect("import");
+    assert_eq!(restored.agent_id(), original.agent_id());
+    assert_eq!(restored.public_key_base64(), original.public_key_base64());
+}
+
+#[test]
+fn encrypted_backup_roundtrips_and_hides_seed() {
+    let original = identity();
+    let backup = original
+        .export_backup_with(Some("correct horse"), TEST_ITERATIONS)
+        .expect("export");
+    assert!(Identity::backup_is_encrypted(&backup).expect("parse"));
+    assert!(!backup.contains(&STANDARD.encode([9u8; 32])));
+
+    let restored = Identity::from_backup(&backup, Some("correct horse")).expect("import");
+    assert_eq!(restored.agent_id(), original.agent_id());
+}
+
+#[test]
+fn encrypted_backup_rejects_wrong_or_missing_passphrase() {
+    let backup = identity()
+        .export_backup_with(Some("correct horse"), TEST_ITERATIONS)
+        .expect("export");
+
+    let err = Identity::from_backup(&backup, Some("battery staple")).unwrap_err();
+    assert!(err.to_string().contains("wrong passphrase"));
+    let err = Identity::from_backup(&backup, None).unwrap_err();
+    assert!(err.to_string().contains("passphrase is required"));
+}
+
+#[test]
+fn encrypted_backup_rejects_excessive_kdf_iterations() {
+    let backup = identity()
+        .export_backup_with(Some("correct horse"), TEST_ITERATIONS)
+        .expect("export");
+    let crafted = backup.replace(
+        &format!("\"iterations\": {TEST_ITERATIONS}"),
+        &format!("\"iterations\": {}", u32::MAX),
+    );
+    assert_ne!(crafted, backup);
+
+    let started = std::time::Instant::now();
+    let err = Identity::from_backup(&crafted, Some("correct horse")).unwrap_err();
+    assert!(err.to_string().contains("exceed the limit"), "{err}");
+    assert!(started.elapsed() < std::time::Duration::from_secs(1));
+}
+
+#[test]
+fn backup_with_mismatched_agent_id_is_rejected() {
+    let backup = identity().export_backup(None).expect("export");
+    let tampered = backup.replace(
+        identity().agent_id(),
+        "ed25519.00000000000000000000000000000000",
+    );
+    let err = Identity::from_backup(&tampered, None).unwrap_err();
+    assert!(err.to_string().contains("corrupt"));
+}
+
+#[test]
+fn non_backup_json_is_rejected() {
+    let err = Identity::from_backup(r#"{"format":"other","version":1}"#, None).unwrap_err();
+    assert!(err.to_string().contains("not an AXON identity backup"));
+}
@@ -0,0 +1,87 @@
+//! The seed in the OS keychain (`identity.storage: keychain`). Each identity
+//! has its own entry: service `axon`, account the `identity.key` path it
+//! replaces. Without the `keychain` feature every keychain is unavailable.
+
+use crate::config::AxonPaths;
+
+pub(super) enum KeychainError {
+    /// No keychain to talk to; the caller uses `identity.key` instead.
+    Unavailable(String),
+    /// The keychain is there but refused or failed, e.g. while locked.
+    #[cfg_attr(not(feature = "keychain"), allow(dead_code))]
+    Failed(anyhow::Error),
+}
+
+#[cfg(feature = "keychain")]
+pub(super) use os::{load, store};
+
+#[cfg(feature = "keychain")]
+mod os {
+    use anyhow::anyhow;
+    use base64::{Engine as _, engine::general_purpose::STANDARD};
+
+    use super::{AxonPaths, KeychainError};
+
+    const SERVICE: &str = "axon";
+
+    /// The seed stored for `paths`, or `None` when there is no entry yet.
+    pub(in crate::identity) fn load(paths: &AxonPaths) -> Result<Option<[u8; 32]>, KeychainError> {
+        let account = account(paths);
+        let text = match entry(&account)?.get_password() {
+            Ok(text) => text,
+            Err(keyring::Error::NoEntry) => return Ok(None),
+            Err(err) => return Err(classify(&account, err)),
+        };
+        let seed = STANDARD
+            .decode(text.trim())
+            .ok()
+            .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+            .ok_or_else(|| {
+                KeychainError::Failed(anyhow!(
+                    "keychain entry {SERVICE}/{account} is not a base64 32-byte seed"
+                ))
+            })?;
+        Ok(Some(seed))
+    }
+
+    /// Store `seed` for `paths`, replacing any earlier entry.
+    pub(in crate::identity) fn store(
+        paths: &AxonPaths,
+        seed: &[u8; 32],
+    ) -> Result<(), KeychainError> {
+        let account = account(paths);
+        entry(&account)?
+            .set_password(&STANDARD.encode(seed))
+            .map_err(|err| classify(&account, err))
+    }
+
+    fn account(paths: &AxonPaths) -> String {
+        paths.identity_key.display().to_string()
+    }
+
+    fn entry(account: &str) -> Result<keyring::Entry, KeychainError> {
+        keyring::Entry::new(SERVICE, account).map_err(|err| classify(account, err))
+    }
+
+    fn classify(account: &str, err: keyring::Error) -> KeychainError {
+        match err {
+            keyring::Error::PlatformFailure(err) => KeychainError::Unavailable(err.to_string()),
+            err => KeychainError::Failed(anyhow!("keychain entry {SERVICE}/{account}: {err}")),
+        }
+    }
+}
+
+#[cfg(not(feature = "keychain"))]
+pub(super) fn load(_paths: &AxonPaths) -> Result<Option<[u8; 32]>, KeychainError> {
+    Err(unsupported())
+}
+
+#[cfg(not(feature = "keychain"))]
+pub(super) fn store(_paths: &AxonPaths, _seed: &[u8; 32]) -> Result<(), KeychainError> {
+    Err(unsupported())
+}
+
+#[cfg(not(feature = "keychain"))]
+fn unsupported() -> KeychainError {
+    KeychainError::Unavailable("axon was built without the `keychain` feature".to_string())
+}
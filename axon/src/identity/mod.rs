@@ -1,14 +1,24 @@
 use std::fs;
-use std::os::unix::fs::PermissionsExt;
+use std::io::{ErrorKind, Write};
+use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
 use std::path::Path;
 
-use anyhow::{Context, Result, anyhow};
+use anyhow::{Context, Result, anyhow, bail};
 use base64::{Engine as _, engine::general_purpose::STANDARD};
-use ed25519_dalek::{SigningKey, VerifyingKey};
+use ed25519_dalek::{Signature, Signer, SigningKey, VerifyingKey};
 use rcgen::{CertificateParams, DistinguishedName, DnType, KeyPair, PKCS_ED25519};
 use sha2::{Digest, Sha256};
+use tracing::warn;
 
-use crate::config::AxonPaths;
+use crate::config::{AxonPaths, IdentityConfig, IdentityStorage};
+
+mod backup;
+mod keychain;
+mod ssh;
+
+use keychain::KeychainError;
+
+pub use backup::BACKUP_FORMAT;
 
 #[derive(Debug, Clone)]
 pub struct Identity {
@@ -24,46 +34,71 @@ pub struct QuicCertificate {
 }
 
 impl Identity {
+    /// Load the identity of `paths`, generating one on first use, from where
+    /// `identity.storage` keeps it (see [`IdentityConfig::for_paths`]).
     pub fn load_or_generate(paths: &AxonPaths) -> Result<Self> {
         paths.ensure_root_exists()?;
 
-        let signing_key = if paths.identity_key.exists() {
-            let raw = fs::read(&paths.identity_key)
-                .with_context(|| format!("failed to read {}", paths.identity_key.display()))?;
-            let text = std::str::from_utf8(&raw).map_err(|_| {
-                anyhow!(
-                    "invalid identity.key format at {}: expected base64 text containing a 32-byte seed; \
-                     non-text key data is unsupported. \
-                     Remove identity.key and identity.pub from this root to re-initialize identity.",
-                    paths.identity_key.display()
-                )
-            })?;
-            let seed = decode_seed_from_base64_text(text, &paths.identity_key)?;
-            SigningKey::from_bytes(&seed)
-        } else {
-            let mut seed = [0u8; 32];
-            getrandom::getrandom(&mut seed)
-                .map_err(|err| anyhow!("failed to gather randomness: {err}"))?;
-            let key = SigningKey::from_bytes(&seed);
-            write_seed_as_base64(&paths.identity_key, &seed)?;
-            key
+        let seed = match IdentityConfig::for_paths(paths)?.storage() {
+            IdentityStorage::File => load_or_generate_file_seed(paths)?,
+            IdentityStorage::Keychain => load_or_generate_keychain_seed(paths)?,
         };
 
+        let identity = Self::from_signing_key(SigningKey::from_bytes(&seed));
+        identity.write_public_key(paths)?;
+        Ok(identity)
+    }
+
+    /// Whether `paths` already holds an identity: `identity.key`, or a
+    /// keychain entry when `identity.storage` is `keychain`.
+    pub fn exists(paths: &AxonPaths) -> Result<bool> {
+        if paths.identity_key.exists() {
+            return Ok(true);
+        }
+        if IdentityConfig::for_paths(paths)?.storage() == IdentityStorage::File {
+            return Ok(false);
+        }
+        match keychain::load(paths) {
+            Ok(seed) => Ok(seed.is_some()),
+            Err(KeychainError::Unavailable(_)) => Ok(false),
+            Err(KeychainError::Failed(err)) => Err(err),
+        }
+    }
+
+    fn from_signing_key(signing_key: SigningKey) -> Self {
         let verifying = signing_key.verifying_key();
-        let pubkey_b64 = STANDARD.encode(verifying.to_bytes());
-        fs::write(&paths.identity_pub, &pubkey_b64).with_context(|| {
+        Self {
+            agent_id: derive_agent_id(&verifying),
+            public_key_base64: STANDARD.encode(verifying.to_bytes()),
+            signing_key,
+        }
+    }
+
+    /// Write this keypair to `identity.key` (mode 0600), or the keychain, and
+    /// `identity.pub`, replacing any existing identity in the state root.
+    pub fn install(&self, paths: &AxonPaths) -> Result<()> {
+        paths.ensure_root_exists()?;
+        let seed = self.signing_key.to_bytes();
+        match IdentityConfig::for_paths(paths)?.storage() {
+            IdentityStorage::File => write_seed_as_base64(&paths.identity_key, &seed)?,
+            IdentityStorage::Keychain => match keychain::store(paths, &seed) {
+                Ok(()) => remove_seed_file(paths)?,
+                Err(KeychainError::Unavailable(reason)) => {
+                    warn_file_fallback(paths, &reason);
+                    write_seed_as_base64(&paths.identity_key, &seed)?;
+                }
+                Err(KeychainError::Failed(err)) => return Err(err),
+            },
+        }
+        self.write_public_key(paths)
+    }
+
+    fn write_public_key(&self, paths: &AxonPaths) -> Result<()> {
+        fs::write(&paths.identity_pub, &self.public_key_base64).with_context(|| {
             format!(
                 "failed to write public key: {}",
                 paths.identity_pub.display()
             )
-        })?;
-
-        let agent_id = derive_agent_id(&verifying);
-
-        Ok(Self {
-            signing_key,
-            agent_id,
-            public_key_base64: pubkey_b64,
         })
     }
 
@@ -79,6 +114,11 @@ impl Identity {
         self.signing_key.verifying_key()
     }
 
+    /// Ed25519 signature over `message` with this agent's key.
+    pub fn sign(&self, message: &[u8]) -> Signature {
+        self.signing_key.sign(message)
+    }
+
     pub fn make_quic_certificate(&self) -> Result<QuicCertificate> {
         let seed = self.signing_key.to_bytes();
         let public_key = self.signing_key.verifying_key().to_bytes();
@@ -107,6 +147,98 @@ impl Identity {
     }
 }
 
+fn load_or_generate_file_seed(paths: &AxonPaths) -> Result<[u8; 32]> {
+    if paths.identity_key.exists() {
+        return read_seed_file(&paths.identity_key);
+    }
+    let seed = random_seed()?;
+    write_seed_as_base64(&paths.identity_key, &seed)?;
+    Ok(seed)
+}
+
+/// The keychain seed. A seed still in `identity.key` moves into the keychain
+/// on first use; while no keychain is reachable the file is used instead,
+/// but a keychain that refuses access (such as a locked one) is an error,
+/// so the seed is never written to disk behind the user's back.
+fn load_or_generate_keychain_seed(paths: &AxonPaths) -> Result<[u8; 32]> {
+    let reason = match keychain::load(paths) {
+        Ok(Some(seed)) => {
+            if paths.identity_key.exists() && read_seed_file(&paths.identity_key)? != seed {
+                bail!(
+                    "{} and its keychain entry hold different identities; \
+                     move identity.key aside to use the keychain one",
+                    paths.identity_key.display()
+                );
+            }
+            return Ok(seed);
+        }
+        Ok(None) => {
+            let on_disk = paths.identity_key.exists();
+            let seed = if on_disk {
+                read_seed_file(&paths.identity_key)?
+            } else {
+                random_seed()?
+            };
+            match keychain::store(paths, &seed) {
+                Ok(()) => {
+                    if keychain::load(paths).ok().flatten() != Some(seed) {
+                        bail!(
+                            "keychain did not return the seed just stored for {}",
+                            paths.identity_key.display()
+                        );
+                    }
+                    if on_disk {
+                        remove_seed_file(paths)?;
+                    }
+                    return Ok(seed);
+                }
+                Err(KeychainError::Unavailable(reason)) => reason,
+                Err(KeychainError::Failed(err)) => return Err(err),
+            }
+        }
+        Err(KeychainError::Unavailable(reason)) => reason,
+        Err(KeychainError::Failed(err)) => return Err(err),
+    };
+    warn_file_fallback(paths, &reason);
+    load_or_generate_file_seed(paths)
+}
+
+fn warn_file_fallback(paths: &AxonPaths, reason: &str) {
+    warn!(
+        path = %paths.identity_key.display(),
+        reason,
+        "identity.storage is keychain but no keychain is available; using identity.key"
+    );
+}
+
+fn remove_seed_file(paths: &AxonPaths) -> Result<()> {
+    match fs::remove_file(&paths.identity_key) {
+        Err(err) if err.kind() != ErrorKind::NotFound => {
+            Err(err).with_context(|| format!("failed to remove {}", paths.identity_key.display()))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn random_seed() -> Result<[u8; 32]> {
+    let mut seed = [0u8; 32];
+    getrandom::getrandom(&mut seed).map_err(|err| anyhow!("failed to gather randomness: {err}"))?;
+    Ok(seed)
+}
+
+fn read_seed_file(path: &Path) -> Result<[u8; 32]> {
+    let raw = fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+    let text = std::str::from_utf8(&raw).map_err(|_| {
+        anyhow!(
+            "invalid identity.key format at {}: expected base64 text containing a 32-byte seed; \
+             non-text key data is unsupported. \
+             Remove identity.key and identity.pub from this root to re-initialize identity.",
+            path.display()
+        )
+    })?;
+    decode_seed_from_base64_text(text, path)
+}
+
 fn decode_seed_from_base64_text(text: &str, path: &Path) -> Result<[u8; 32]> {
     let bytes = STANDARD.decode(text.trim()).map_err(|err| {
         anyhow!(
@@ -128,7 +260,14 @@ fn decode_seed_from_base64_text(text: &str, path: &Path) -> Result<[u8; 32]> {
 
 fn write_seed_as_base64(path: &Path, seed: &[u8; 32]) -> Result<()> {
     let key_b64 = STANDARD.encode(seed);
-    fs::write(path, &key_b64)
+    // Create with 0600 so the key is never readable by others, even briefly.
+    fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(path)
+        .and_then(|mut file| file.write_all(key_b64.as_bytes()))
         .with_context(|| format!("failed to write private key: {}", path.display()))?;
     fs::set_permissions(path, fs::Permissions::from_mode(0o600))
         .with_context(|| format!("failed to set key permissions: {}", path.display()))?;
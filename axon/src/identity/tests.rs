@@ -102,3 +102,19 @@ fn legacy_raw_key_is_rejected() {
     let msg = result.unwrap_err().to_string();
     assert!(msg.contains("invalid identity.key"));
 }
+
+#[cfg(not(feature = "keychain"))]
+#[test]
+fn keychain_storage_falls_back_to_identity_key_without_the_feature() {
+    let dir = tempdir().expect("tempdir");
+    let paths = AxonPaths::from_root(PathBuf::from(dir.path()));
+    paths.ensure_root_exists().expect("root");
+    fs::write(&paths.config, "identity:\n  storage: keychain\n").expect("write config");
+    assert!(!Identity::exists(&paths).expect("exists"));
+
+    let first = Identity::load_or_generate(&paths).expect("first load");
+    assert!(paths.identity_key.exists());
+    assert!(Identity::exists(&paths).expect("exists"));
+    let second = Identity::load_or_generate(&paths).expect("second load");
+    assert_eq!(first.agent_id(), second.agent_id());
+}
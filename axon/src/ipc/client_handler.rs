@@ -1,23 +1,33 @@
 use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::{Context, Result};
 use serde_json::Value;
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::net::UnixStream;
 use tokio::sync::{mpsc, oneshot};
+use tokio::time::Instant;
 use tokio_util::sync::CancellationToken;
 
 use super::protocol::{CommandEvent, DaemonReply, IpcCommand, IpcErrorCode, MAX_IPC_LINE_LENGTH};
 
+/// Per-client connection limits enforced by the read loop.
+#[derive(Debug, Clone, Copy, Default)]
+pub(super) struct ClientLimits {
+    pub(super) idle_timeout: Option<Duration>,
+    pub(super) max_lifetime: Option<Duration>,
+}
+
+async fn sleep_until_deadline(deadline: Option<Instant>) {
+    match deadline {
+        Some(deadline) => tokio::time::sleep_until(deadline).await,
+        None => std::future::pending().await,
+    }
+}
+
 fn build_error_line(error: IpcErrorCode, req_id: Option<String>) -> Arc<str> {
     Arc::from(
-        serde_json::to_string(&DaemonReply::Error {
-            ok: false,
-            message: error.message(),
-            error,
-            req_id,
-        })
-        .expect("IPC error serialization"),
+        serde_json::to_string(&DaemonReply::error(error, req_id)).expect("IPC error serialization"),
     )
 }
 
@@ -44,6 +54,7 @@ pub(super) async fn handle_client(
     mut out_rx: mpsc::Receiver<Arc<str>>,
     cmd_tx: mpsc::Sender<CommandEvent>,
     cancel: CancellationToken,
+    limits: ClientLimits,
 ) -> Result<()> {
     #[derive(Clone, Copy)]
     enum WriterCloseMode {
@@ -95,6 +106,8 @@ pub(super) async fn handle_client(
     let mut reader = BufReader::new(read_half);
     let mut buf = Vec::with_capacity(MAX_IPC_LINE_LENGTH + 1);
     let mut writer_close_mode = WriterCloseMode::Immediate;
+    let lifetime_deadline = limits.max_lifetime.map(|d| Instant::now() + d);
+    let mut idle_deadline = limits.idle_timeout.map(|d| Instant::now() + d);
     loop {
         if cancel.is_cancelled() {
             break;
@@ -102,10 +115,21 @@ pub(super) async fn handle_client(
         buf.clear();
         let mut found_newline = false;
         let mut exceeded = false;
+        let mut expired = false;
 
         loop {
             let available = tokio::select! {
                 _ = cancel.cancelled() => break,
+                _ = sleep_until_deadline(idle_deadline) => {
+                    tracing::debug!(client_id, "closing idle IPC client");
+                    expired = true;
+                    break;
+                }
+                _ = sleep_until_deadline(lifetime_deadline) => {
+                    tracing::debug!(client_id, "closing IPC client at max lifetime");
+                    expired = true;
+                    break;
+                }
                 read_result = reader.fill_buf() => read_result.context("failed reading IPC")?,
             };
             if available.is_empty() {
@@ -142,9 +166,10 @@ pub(super) async fn handle_client(
             break; // Close connection — can't reliably find next command boundary
         }
 
-        if !found_newline {
-            break; // EOF
+        if expired || !found_newline {
+            break; // Limit reached or EOF
         }
+        idle_deadline = limits.idle_timeout.map(|d| Instant::now() + d);
         let line = match std::str::from_utf8(&buf) {
             Ok(s) => s,
             Err(_) => {
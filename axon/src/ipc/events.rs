@@ -0,0 +1,64 @@
+//! Peer and pairing events pushed to every connected IPC client.
+
+use std::sync::Arc;
+
+use anyhow::Result;
+
+use super::protocol::DaemonReply;
+use super::server::IpcServer;
+
+impl IpcServer {
+    pub async fn broadcast_pair_request(
+        &self,
+        agent_id: &str,
+        pubkey: &str,
+        addr: Option<&str>,
+    ) -> Result<()> {
+        let event = DaemonReply::PairRequestEvent {
+            event: "pair_request",
+            agent_id: agent_id.to_string(),
+            pubkey: pubkey.to_string(),
+            addr: addr.map(str::to_string),
+        };
+        let line: Arc<str> = Arc::from(serde_json::to_string(&event)?);
+        self.broadcast_line(line).await
+    }
+
+    pub async fn broadcast_peer_connected(&self, agent_id: &str) -> Result<()> {
+        let event = DaemonReply::PeerConnectedEvent {
+            event: "connected",
+            agent_id: agent_id.to_string(),
+        };
+        let line: Arc<str> = Arc::from(serde_json::to_string(&event)?);
+        self.broadcast_line(line).await
+    }
+
+    pub async fn broadcast_peer_disconnected(&self, agent_id: &str, reason: &str) -> Result<()> {
+        let event = DaemonReply::PeerDisconnectedEvent {
+            event: "disconnected",
+            agent_id: agent_id.to_string(),
+            reason: reason.to_string(),
+        };
+        let line: Arc<str> = Arc::from(serde_json::to_string(&event)?);
+        self.broadcast_line(line).await
+    }
+
+    pub async fn broadcast_peer_discovered(&self, agent_id: &str, addr: &str) -> Result<()> {
+        let event = DaemonReply::PeerDiscoveredEvent {
+            event: "discovered",
+            agent_id: agent_id.to_string(),
+            addr: addr.to_string(),
+        };
+        let line: Arc<str> = Arc::from(serde_json::to_string(&event)?);
+        self.broadcast_line(line).await
+    }
+
+    pub async fn broadcast_peer_lost(&self, agent_id: &str) -> Result<()> {
+        let event = DaemonReply::PeerLostEvent {
+            event: "lost",
+            agent_id: agent_id.to_string(),
+        };
+        let line: Arc<str> = Arc::from(serde_json::to_string(&event)?);
+        self.broadcast_line(line).await
+    }
+}
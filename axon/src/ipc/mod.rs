@@ -1,10 +1,16 @@
 mod auth;
 mod client_handler;
+mod events;
 mod protocol;
+mod recording;
+mod reply_routes;
 mod server;
 
 pub use protocol::{
-    CommandEvent, DaemonReply, IpcCommand, IpcErrorCode, IpcSendKind, MAX_IPC_LINE_LENGTH,
-    PeerSummary, WhoamiInfo,
+    BanSummary, CommandEvent, DaemonReply, DiscoveryHealth, HealthSubsystems, HistoryDirection,
+    HistoryEntry, HistoryFilter, HistoryHealth, IpcCommand, IpcErrorCode, IpcHealth, IpcSendKind,
+    LifetimeStats, MAX_IPC_LINE_LENGTH, PeerDetail, PeerSummary, PersistenceHealth, PinSummary,
+    ReconnectInfo, SendAwait, TaskHealth, TasksHealth, TransportHealth, WhoamiInfo,
 };
+pub use recording::{RECORDING_FORMAT_VERSION, parse_recording, recording_entry};
 pub use server::{IpcServer, IpcServerConfig};
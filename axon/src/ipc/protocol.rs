@@ -1,229 +0,0 @@
-use serde::{Deserialize, Serialize};
-use serde_json::Value;
-use uuid::Uuid;
-
-use crate::message::{Envelope, MessageKind};
-
-/// Maximum length of a single IPC command line (64 KB).
-pub const MAX_IPC_LINE_LENGTH: usize = 64 * 1024;
-
-// ---------------------------------------------------------------------------
-// IPC protocol types
-// ---------------------------------------------------------------------------
-
-/// Client-to-daemon IPC command, deserialized from line-delimited JSON.
-/// Tagged by the `cmd` field (e.g., `{"cmd": "send", ...}`).
-#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
-#[serde(rename_all = "snake_case")]
-pub enum IpcSendKind {
-    Request,
-    Message,
-}
-
-impl IpcSendKind {
-    pub fn as_message_kind(self) -> MessageKind {
-        match self {
-            IpcSendKind::Request => MessageKind::Request,
-            IpcSendKind::Message => MessageKind::Message,
-        }
-    }
-}
-
-#[derive(Debug, Clone, Deserialize)]
-#[serde(tag = "cmd", rename_all = "snake_case")]
-pub enum IpcCommand {
-    Send {
-        to: String,
-        kind: IpcSendKind,
-        payload: Value,
-        #[serde(default)]
-        timeout_secs: Option<u64>,
-        #[serde(default, rename = "ref")]
-        ref_id: Option<Uuid>,
-        #[serde(default)]
-        req_id: Option<String>,
-    },
-    Peers {
-        #[serde(default)]
-        req_id: Option<String>,
-    },
-    Status {
-        #[serde(default)]
-        req_id: Option<String>,
-    },
-    Whoami {
-        #[serde(default)]
-        req_id: Option<String>,
-    },
-    AddPeer {
-        pubkey: String,
-        addr: String,
-        #[serde(default)]
-        req_id: Option<String>,
-    },
-}
-
-impl IpcCommand {
-    pub fn req_id(&self) -> Option<&str> {
-        match self {
-            IpcCommand::Send { req_id, .. }
-            | IpcCommand::Peers { req_id, .. }
-            | IpcCommand::Status { req_id, .. }
-            | IpcCommand::Whoami { req_id, .. }
-            | IpcCommand::AddPeer { req_id, .. } => req_id.as_deref(),
-        }
-    }
-
-    pub fn cmd_name(&self) -> &'static str {
-        match self {
-            IpcCommand::Send { .. } => "send",
-            IpcCommand::Peers { .. } => "peers",
-            IpcCommand::Status { .. } => "status",
-            IpcCommand::Whoami { .. } => "whoami",
-            IpcCommand::AddPeer { .. } => "add_peer",
-        }
-    }
-}
-
-/// A parsed IPC command paired with the originating client's connection ID.
-#[derive(Debug, Clone)]
-pub struct CommandEvent {
-    pub client_id: u64,
-    pub command: IpcCommand,
-}
-
-/// Summary of a connected or known peer, returned by the `peers` command.
-#[derive(Debug, Clone, Serialize)]
-pub struct PeerSummary {
-    pub agent_id: String,
-    pub addr: String,
-    pub status: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub rtt_ms: Option<f64>,
-    pub source: String,
-}
-
-/// Daemon identity information returned by the `whoami` command.
-#[derive(Debug, Clone, Serialize)]
-pub struct WhoamiInfo {
-    pub agent_id: String,
-    pub public_key: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub name: Option<String>,
-    pub version: String,
-    pub uptime_secs: u64,
-}
-
-// ---------------------------------------------------------------------------
-// Error codes
-// ---------------------------------------------------------------------------
-
-/// IPC error codes returned in error responses.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
-#[serde(rename_all = "snake_case")]
-pub enum IpcErrorCode {
-    InvalidCommand,
-    CommandTooLarge,
-    PeerNotFound,
-    SelfSend,
-    PeerUnreachable,
-    Timeout,
-    InternalError,
-}
-
-impl std::fmt::Display for IpcErrorCode {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            IpcErrorCode::InvalidCommand => write!(f, "invalid_command"),
-            IpcErrorCode::CommandTooLarge => write!(f, "command_too_large"),
-            IpcErrorCode::PeerNotFound => write!(f, "peer_not_found"),
-            IpcErrorCode::SelfSend => write!(f, "self_send"),
-            IpcErrorCode::PeerUnreachable => write!(f, "peer_unreachable"),
-            IpcErrorCode::Timeout => write!(f, "timeout"),
-            IpcErrorCode::InternalError => write!(f, "internal_error"),
-        }
-    }
-}
-
-impl IpcErrorCode {
-    /// Human-readable explanation of the error code.
-    pub fn message(&self) -> &'static str {
-        match self {
-            IpcErrorCode::InvalidCommand => {
-                "malformed command, unknown cmd, or invalid field value"
-            }
-            IpcErrorCode::CommandTooLarge => "IPC command exceeds 64KB limit",
-            IpcErrorCode::PeerNotFound => "target agent_id not in peer table",
-            IpcErrorCode::SelfSend => "cannot send messages to self",
-            IpcErrorCode::PeerUnreachable => "peer known but connection failed",
-            IpcErrorCode::Timeout => "request timed out waiting for peer response",
-            IpcErrorCode::InternalError => "unexpected daemon error",
-        }
-    }
-}
-
-// ---------------------------------------------------------------------------
-// Daemon replies
-// ---------------------------------------------------------------------------
-
-/// Daemon-to-client IPC response, serialized as line-delimited JSON.
-/// Uses `#[serde(untagged)]` — variants are distinguished by their field shapes.
-#[derive(Debug, Clone, Serialize)]
-#[serde(untagged)]
-pub enum DaemonReply {
-    SendOk {
-        ok: bool,
-        msg_id: Uuid,
-        #[serde(skip_serializing_if = "Option::is_none")]
-        req_id: Option<String>,
-        #[serde(skip_serializing_if = "Option::is_none")]
-        response: Option<Envelope>,
-    },
-    Peers {
-        ok: bool,
-        peers: Vec<PeerSummary>,
-        #[serde(skip_serializing_if = "Option::is_none")]
-        req_id: Option<String>,
-    },
-    Status {
-        ok: bool,
-        uptime_secs: u64,
-        peers_connected: usize,
-        messages_sent: u64,
-        messages_received: u64,
-        #[serde(skip_serializing_if = "Option::is_none")]
-        req_id: Option<String>,
-    },
-    Error {
-        ok: bool,
-        error: IpcErrorCode,
-        message: &'static str,
-        #[serde(skip_serializing_if = "Option::is_none")]
-        req_id: Option<String>,
-    },
-    InboundEvent {
-        event: &'static str, // always "inbound"
-        from: String,
-        envelope: Envelope,
-    },
-    PairRequestEvent {
-        event: &'static str, // always "pair_request"
-        agent_id: String,
-        pubkey: String,
-        #[serde(skip_serializing_if = "Option::is_none")]
-        addr: Option<String>,
-    },
-    Whoami {
-        ok: bool,
-        #[serde(flatten)]
-        info: WhoamiInfo,
-        #[serde(skip_serializing_if = "Option::is_none")]
-        req_id: Option<String>,
-    },
-    AddPeer {
-        ok: bool,
-        agent_id: String,
-        #[serde(skip_serializing_if = "Option::is_none")]
-        req_id: Option<String>,
-    },
-}
@@ -0,0 +1,249 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use uuid::Uuid;
+
+pub use crate::errors::IpcErrorCode;
+use crate::message::MessageKind;
+
+mod reply;
+mod summaries;
+
+pub use reply::DaemonReply;
+pub use summaries::{
+    BanSummary, DiscoveryHealth, HealthSubsystems, HistoryDirection, HistoryEntry, HistoryFilter,
+    HistoryHealth, IpcHealth, LifetimeStats, PeerDetail, PeerSummary, PersistenceHealth,
+    PinSummary, ReconnectInfo, TaskHealth, TasksHealth, TransportHealth, WhoamiInfo,
+};
+
+/// Maximum length of a single IPC command line (64 KB).
+pub const MAX_IPC_LINE_LENGTH: usize = 64 * 1024;
+
+// ---------------------------------------------------------------------------
+// IPC commands
+// ---------------------------------------------------------------------------
+
+/// Client-to-daemon IPC command, deserialized from line-delimited JSON.
+/// Tagged by the `cmd` field (e.g., `{"cmd": "send", ...}`).
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum IpcSendKind {
+    Request,
+    Message,
+}
+
+/// What a `send` waits for before replying, beyond delivery.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SendAwait {
+    /// An inbound envelope from the recipient whose `ref` is the sent message.
+    Reply,
+}
+
+impl IpcSendKind {
+    pub fn as_message_kind(self) -> MessageKind {
+        match self {
+            IpcSendKind::Request => MessageKind::Request,
+            IpcSendKind::Message => MessageKind::Message,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(tag = "cmd", rename_all = "snake_case")]
+pub enum IpcCommand {
+    Send {
+        to: String,
+        kind: IpcSendKind,
+        payload: Value,
+        #[serde(default)]
+        timeout_secs: Option<u64>,
+        #[serde(default, rename = "ref")]
+        ref_id: Option<Uuid>,
+        #[serde(default)]
+        idempotency_key: Option<String>,
+        /// Conversation thread; inherited from the `ref`'d envelope when omitted.
+        #[serde(default)]
+        thread_id: Option<String>,
+        /// Application metadata copied into the envelope `headers`.
+        #[serde(default)]
+        headers: Map<String, Value>,
+        /// For `kind=message`, reply only once the recipient answers.
+        #[serde(default, rename = "await", skip_serializing_if = "Option::is_none")]
+        await_reply: Option<SendAwait>,
+        #[serde(default)]
+        req_id: Option<String>,
+    },
+    Peers {
+        /// Only peers carrying this tag.
+        #[serde(default)]
+        tag: Option<String>,
+        #[serde(default)]
+        req_id: Option<String>,
+    },
+    Status {
+        #[serde(default)]
+        req_id: Option<String>,
+    },
+    Whoami {
+        #[serde(default)]
+        req_id: Option<String>,
+    },
+    AddPeer {
+        pubkey: String,
+        addr: String,
+        #[serde(default)]
+        req_id: Option<String>,
+    },
+    /// Revoke a peer by `pubkey` or by the `agent_id` of a known peer.
+    Revoke {
+        #[serde(default)]
+        pubkey: Option<String>,
+        #[serde(default)]
+        agent_id: Option<String>,
+        #[serde(default)]
+        req_id: Option<String>,
+    },
+    /// Block a peer by agent ID, known or not: drop it, close its
+    /// connections, and refuse its key until `unblock_peer`.
+    BlockPeer {
+        agent_id: String,
+        #[serde(default)]
+        req_id: Option<String>,
+    },
+    UnblockPeer {
+        agent_id: String,
+        #[serde(default)]
+        req_id: Option<String>,
+    },
+    /// List blocked peers.
+    Blocked {
+        #[serde(default)]
+        req_id: Option<String>,
+    },
+    /// List pinned peer public keys, or only the pin for `agent_id`.
+    Pins {
+        #[serde(default)]
+        agent_id: Option<String>,
+        #[serde(default)]
+        req_id: Option<String>,
+    },
+    /// Forget the pinned key of a discovered or cached peer so the next
+    /// discovery announcement pins whatever key it carries.
+    ClearPin {
+        agent_id: String,
+        #[serde(default)]
+        req_id: Option<String>,
+    },
+    /// List source addresses banned for repeated handshake failures.
+    Bans {
+        #[serde(default)]
+        req_id: Option<String>,
+    },
+    Reload {
+        #[serde(default)]
+        req_id: Option<String>,
+    },
+    Shutdown {
+        #[serde(default)]
+        req_id: Option<String>,
+    },
+    History {
+        #[serde(default)]
+        filter: HistoryFilter,
+        #[serde(default)]
+        req_id: Option<String>,
+    },
+    Health {
+        #[serde(default)]
+        req_id: Option<String>,
+    },
+    Whois {
+        agent_id: String,
+        #[serde(default)]
+        req_id: Option<String>,
+    },
+    Subscribe {
+        topics: Vec<String>,
+        #[serde(default)]
+        req_id: Option<String>,
+    },
+    Publish {
+        topic: String,
+        payload: Value,
+        #[serde(default)]
+        req_id: Option<String>,
+    },
+    /// Also receive `tap` events for this connection: other clients'
+    /// commands and every envelope sent, or received in reply, via IPC.
+    Tap {
+        #[serde(default)]
+        req_id: Option<String>,
+    },
+    /// Stop receiving `inbound` events for replies to envelopes that other
+    /// clients sent via `send`.
+    OwnReplies {
+        #[serde(default)]
+        req_id: Option<String>,
+    },
+}
+
+impl IpcCommand {
+    pub fn req_id(&self) -> Option<&str> {
+        match self {
+            IpcCommand::Send { req_id, .. }
+            | IpcCommand::Peers { req_id, .. }
+            | IpcCommand::Status { req_id, .. }
+            | IpcCommand::Whoami { req_id, .. }
+            | IpcCommand::AddPeer { req_id, .. }
+            | IpcCommand::Revoke { req_id, .. }
+            | IpcCommand::BlockPeer { req_id, .. }
+            | IpcCommand::UnblockPeer { req_id, .. }
+            | IpcCommand::Blocked { req_id }
+            | IpcCommand::Pins { req_id, .. }
+            | IpcCommand::ClearPin { req_id, .. }
+            | IpcCommand::Bans { req_id, .. }
+            | IpcCommand::Reload { req_id, .. }
+            | IpcCommand::Shutdown { req_id, .. }
+            | IpcCommand::History { req_id, .. }
+            | IpcCommand::Health { req_id, .. }
+            | IpcCommand::Whois { req_id, .. }
+            | IpcCommand::Subscribe { req_id, .. }
+            | IpcCommand::Publish { req_id, .. }
+            | IpcCommand::Tap { req_id }
+            | IpcCommand::OwnReplies { req_id } => req_id.as_deref(),
+        }
+    }
+
+    pub fn cmd_name(&self) -> &'static str {
+        match self {
+            IpcCommand::Send { .. } => "send",
+            IpcCommand::Peers { .. } => "peers",
+            IpcCommand::Status { .. } => "status",
+            IpcCommand::Whoami { .. } => "whoami",
+            IpcCommand::AddPeer { .. } => "add_peer",
+            IpcCommand::Revoke { .. } => "revoke",
+            IpcCommand::BlockPeer { .. } => "block_peer",
+            IpcCommand::UnblockPeer { .. } => "unblock_peer",
+            IpcCommand::Blocked { .. } => "blocked",
+            IpcCommand::Pins { .. } => "pins",
+            IpcCommand::ClearPin { .. } => "clear_pin",
+            IpcCommand::Bans { .. } => "bans",
+            IpcCommand::Reload { .. } => "reload",
+            IpcCommand::Shutdown { .. } => "shutdown",
+            IpcCommand::History { .. } => "history",
+            IpcCommand::Health { .. } => "health",
+            IpcCommand::Whois { .. } => "whois",
+            IpcCommand::Subscribe { .. } => "subscribe",
+            IpcCommand::Publish { .. } => "publish",
+            IpcCommand::Tap { .. } => "tap",
+            IpcCommand::OwnReplies { .. } => "own_replies",
+        }
+    }
+}
+
+/// A parsed IPC command paired with the originating client's connection ID.
+#[derive(Debug, Clone)]
+pub struct CommandEvent {
+    pub client_id: u64,
+    pub command: IpcCommand,
+}
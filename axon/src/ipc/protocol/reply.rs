@@ -0,0 +1,248 @@
+use serde::Serialize;
+use serde_json::Value;
+use uuid::Uuid;
+
+use super::summaries::{
+    BanSummary, HealthSubsystems, HistoryDirection, HistoryEntry, LifetimeStats, PeerDetail,
+    PeerSummary, PinSummary, WhoamiInfo,
+};
+use super::{IpcCommand, IpcErrorCode};
+use crate::config::BlockedPeer;
+use crate::message::Envelope;
+
+/// Daemon-to-client IPC response, serialized as line-delimited JSON.
+/// Uses `#[serde(untagged)]` — variants are distinguished by their field shapes.
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum DaemonReply {
+    SendOk {
+        ok: bool,
+        msg_id: Uuid,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        req_id: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        response: Option<Envelope>,
+    },
+    Peers {
+        ok: bool,
+        peers: Vec<PeerSummary>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        req_id: Option<String>,
+    },
+    Status {
+        ok: bool,
+        uptime_secs: u64,
+        peers_connected: usize,
+        messages_sent: u64,
+        messages_received: u64,
+        /// Inbound messages rejected by `rate_limit` since startup.
+        messages_rate_limited: u64,
+        lifetime: LifetimeStats,
+        /// Supervised tasks that exited and are waiting to restart.
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        degraded: Vec<&'static str>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        req_id: Option<String>,
+    },
+    Error {
+        ok: bool,
+        error: IpcErrorCode,
+        message: &'static str,
+        /// From the error registry: whether resending the same command can
+        /// succeed.
+        retryable: bool,
+        /// Specifics of this failure, such as the rejected field.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        details: Option<Value>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        req_id: Option<String>,
+    },
+    InboundEvent {
+        event: &'static str, // always "inbound"
+        from: String,
+        envelope: Envelope,
+    },
+    PairRequestEvent {
+        event: &'static str, // always "pair_request"
+        agent_id: String,
+        pubkey: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        addr: Option<String>,
+    },
+    PeerConnectedEvent {
+        event: &'static str, // always "connected"
+        agent_id: String,
+    },
+    PeerDisconnectedEvent {
+        event: &'static str, // always "disconnected"
+        agent_id: String,
+        reason: String,
+    },
+    PeerDiscoveredEvent {
+        event: &'static str, // always "discovered"
+        agent_id: String,
+        addr: String,
+    },
+    PeerLostEvent {
+        event: &'static str, // always "lost"
+        agent_id: String,
+    },
+    TapCommandEvent {
+        event: &'static str, // always "tap"
+        client_id: u64,
+        command: IpcCommand,
+    },
+    TapEnvelopeEvent {
+        event: &'static str, // always "tap"
+        direction: HistoryDirection,
+        envelope: Envelope,
+    },
+    Tap {
+        ok: bool,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        req_id: Option<String>,
+    },
+    OwnReplies {
+        ok: bool,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        req_id: Option<String>,
+    },
+    Whoami {
+        ok: bool,
+        #[serde(flatten)]
+        info: WhoamiInfo,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        req_id: Option<String>,
+    },
+    AddPeer {
+        ok: bool,
+        agent_id: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        req_id: Option<String>,
+    },
+    Revoke {
+        ok: bool,
+        agent_id: String,
+        pubkey: String,
+        /// Whether the peer table held the key (and so was updated).
+        removed: bool,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        req_id: Option<String>,
+    },
+    BlockPeer {
+        ok: bool,
+        agent_id: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pubkey: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        req_id: Option<String>,
+    },
+    UnblockPeer {
+        ok: bool,
+        agent_id: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pubkey: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        req_id: Option<String>,
+    },
+    Blocked {
+        ok: bool,
+        blocked: Vec<BlockedPeer>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        req_id: Option<String>,
+    },
+    Pins {
+        ok: bool,
+        pins: Vec<PinSummary>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        req_id: Option<String>,
+    },
+    ClearPin {
+        ok: bool,
+        agent_id: String,
+        /// The key that was pinned before it was cleared.
+        pubkey: String,
+        source: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        req_id: Option<String>,
+    },
+    Bans {
+        ok: bool,
+        bans: Vec<BanSummary>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        req_id: Option<String>,
+    },
+    Shutdown {
+        ok: bool,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        req_id: Option<String>,
+    },
+    Health {
+        ok: bool,
+        healthy: bool,
+        uptime_secs: u64,
+        subsystems: HealthSubsystems,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        req_id: Option<String>,
+    },
+    History {
+        ok: bool,
+        entries: Vec<HistoryEntry>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        req_id: Option<String>,
+    },
+    Whois {
+        ok: bool,
+        peer: PeerDetail,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        req_id: Option<String>,
+    },
+    Subscribe {
+        ok: bool,
+        topics: Vec<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        req_id: Option<String>,
+    },
+    Publish {
+        ok: bool,
+        topic: String,
+        /// Subscribed peers that accepted the message.
+        delivered: Vec<String>,
+        /// Subscribed peers the message could not be sent to.
+        failed: Vec<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        req_id: Option<String>,
+    },
+    Reload {
+        ok: bool,
+        peers_added: usize,
+        peers_removed: usize,
+        peers_updated: usize,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        req_id: Option<String>,
+    },
+}
+
+impl DaemonReply {
+    /// `ok: false` reply for `error`, with its registry message and
+    /// `retryable` flag.
+    pub fn error(error: IpcErrorCode, req_id: Option<String>) -> Self {
+        Self::error_reply(error, None, req_id)
+    }
+
+    /// [`DaemonReply::error`] with `details` attached.
+    pub fn error_with_details(error: IpcErrorCode, details: Value, req_id: Option<String>) -> Self {
+        Self::error_reply(error, Some(details), req_id)
+    }
+
+    fn error_reply(error: IpcErrorCode, details: Option<Value>, req_id: Option<String>) -> Self {
+        DaemonReply::Error {
+            ok: false,
+            message: error.message(),
+            retryable: error.retryable(),
+            error,
+            details,
+            req_id,
+        }
+    }
+}
@@ -0,0 +1,236 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::message::{Envelope, MessageKind};
+use crate::peer_table::{PeerSoftware, PeerTraffic};
+
+/// Summary of a connected or known peer, returned by the `peers` command.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PeerSummary {
+    pub agent_id: String,
+    pub addr: String,
+    pub status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rtt_ms: Option<f64>,
+    pub source: String,
+    /// Unix ms when this daemon first learned of the peer.
+    pub first_seen_ms: u64,
+    /// Milliseconds until the peer is dropped unless seen again; absent for
+    /// peers that never expire (`staleness` in `config.yaml`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_in_ms: Option<u64>,
+    pub traffic: PeerTraffic,
+    /// Daemon version the peer reported in its heartbeats.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Labels from `config.yaml` `tags`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+/// A pinned peer public key, returned by the `pins` command.
+#[derive(Debug, Clone, Serialize)]
+pub struct PinSummary {
+    pub agent_id: String,
+    pub pubkey: String,
+    pub addr: String,
+    /// `static` (config.yaml), `discovered`, or `cached` (known_peers.json).
+    pub source: String,
+}
+
+/// A source address banned for repeated handshake failures, returned by the
+/// `bans` command.
+#[derive(Debug, Clone, Serialize)]
+pub struct BanSummary {
+    pub addr: String,
+    /// Bans this address has earned, including the current one.
+    pub strikes: u32,
+    pub remaining_secs: u64,
+}
+
+/// Everything the daemon knows about one peer, returned by the `whois` command.
+#[derive(Debug, Clone, Serialize)]
+pub struct PeerDetail {
+    pub agent_id: String,
+    /// Pinned base64 Ed25519 public key; the TLS certificate must match it.
+    pub pubkey: String,
+    pub addr: String,
+    pub status: String,
+    pub source: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rtt_ms: Option<f64>,
+    /// Unix ms of the last discovery sighting or successful contact.
+    pub last_seen_ms: u64,
+    /// Unix ms when this daemon first learned of the peer.
+    pub first_seen_ms: u64,
+    /// Messages sent to / received from this peer since the daemon started.
+    pub messages_sent: u64,
+    pub messages_received: u64,
+    /// Present while the daemon is trying to (re)connect to the peer.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reconnect: Option<ReconnectInfo>,
+    /// Topic patterns the peer has subscribed to.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub topics: Vec<String>,
+    /// Daemon version and features the peer reported in its heartbeats.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub software: Option<PeerSoftware>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReconnectInfo {
+    pub in_flight: bool,
+    /// Milliseconds until the next attempt; 0 when one is due or running.
+    pub next_attempt_in_ms: u64,
+    /// Wait applied after the next failed attempt.
+    pub backoff_secs: u64,
+}
+
+/// Daemon identity information returned by the `whoami` command.
+#[derive(Debug, Clone, Serialize)]
+pub struct WhoamiInfo {
+    pub agent_id: String,
+    pub public_key: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub version: String,
+    pub uptime_secs: u64,
+}
+
+/// Per-subsystem readiness returned by the `health` command.
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthSubsystems {
+    pub transport: TransportHealth,
+    pub discovery: DiscoveryHealth,
+    pub ipc: IpcHealth,
+    pub persistence: PersistenceHealth,
+    pub history: HistoryHealth,
+    pub tasks: TasksHealth,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TransportHealth {
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bound_addr: Option<String>,
+    pub connections: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DiscoveryHealth {
+    pub ok: bool,
+    /// `running`, `disabled`, or `failed`.
+    pub mdns: &'static str,
+    pub static_peers: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct IpcHealth {
+    pub ok: bool,
+    pub clients: usize,
+    pub max_clients: usize,
+}
+
+/// Totals accumulated across daemon restarts, persisted to `stats.json`.
+///
+/// Counters include the current run; the top-level `status` counters cover
+/// only the time since this daemon started.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LifetimeStats {
+    pub messages_sent: u64,
+    pub messages_received: u64,
+    pub messages_rate_limited: u64,
+    pub uptime_secs: u64,
+    pub daemon_starts: u64,
+    /// Unix ms of the first recorded start.
+    pub first_started_ms: u64,
+}
+
+/// `known_peers.json` writes.
+#[derive(Debug, Clone, Serialize)]
+pub struct PersistenceHealth {
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_write_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HistoryHealth {
+    pub ok: bool,
+    pub enabled: bool,
+}
+
+/// Supervised background tasks (forwarders, discovery, history pruning).
+#[derive(Debug, Clone, Serialize)]
+pub struct TasksHealth {
+    pub ok: bool,
+    pub tasks: Vec<TaskHealth>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskHealth {
+    pub name: &'static str,
+    /// `running`, `restarting`, or `finished`.
+    pub state: &'static str,
+    pub restarts: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+}
+
+/// Whether a recorded envelope was sent or received by this daemon.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HistoryDirection {
+    In,
+    Out,
+}
+
+impl HistoryDirection {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            HistoryDirection::In => "in",
+            HistoryDirection::Out => "out",
+        }
+    }
+}
+
+/// Filter for the `history` command. Set fields are combined with AND.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub struct HistoryFilter {
+    /// Remote agent: recipient of sent envelopes, sender of received ones.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub peer: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<MessageKind>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub direction: Option<HistoryDirection>,
+    /// Matches an envelope's `id` or its `ref`, so a request and its reply
+    /// are returned together.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub msg_id: Option<Uuid>,
+    /// Only envelopes in this conversation thread.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thread_id: Option<String>,
+    /// Inclusive lower bound on the recorded time (Unix ms).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub since_ms: Option<u64>,
+    /// Exclusive upper bound on the recorded time (Unix ms).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub until_ms: Option<u64>,
+    /// Return at most this many of the newest matches.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+}
+
+/// One recorded envelope, returned by the `history` command.
+#[derive(Debug, Clone, Serialize)]
+pub struct HistoryEntry {
+    pub seq: u64,
+    pub ts_ms: u64,
+    pub direction: HistoryDirection,
+    pub peer: String,
+    pub envelope: Envelope,
+}
@@ -0,0 +1,130 @@
+//! Traffic recording: `tap` fan-out to recording clients, and the
+//! `axon record` file format that `axon replay` reads back.
+
+use std::sync::Arc;
+
+use anyhow::{Context, Result, bail};
+use serde_json::{Value, json};
+
+use super::protocol::{DaemonReply, HistoryDirection, IpcCommand};
+use super::server::IpcServer;
+use crate::message::Envelope;
+
+/// Recording format version, written as `axonrec` in the header line.
+pub const RECORDING_FORMAT_VERSION: u64 = 1;
+
+impl IpcServer {
+    /// Start sending `tap` events to `client_id`; false if it has gone.
+    pub async fn enable_tap(&self, client_id: u64) -> bool {
+        match self.clients.lock().await.get_mut(&client_id) {
+            Some(client) => {
+                client.tap = true;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Show `command` from `client_id` to tapping clients other than itself.
+    pub async fn tap_command(&self, client_id: u64, command: &IpcCommand) -> Result<()> {
+        self.tap_line(Some(client_id), || DaemonReply::TapCommandEvent {
+            event: "tap",
+            client_id,
+            command: command.clone(),
+        })
+        .await
+    }
+
+    /// Show an envelope sent via IPC, or received in reply, to tapping
+    /// clients.
+    pub async fn tap_envelope(
+        &self,
+        direction: HistoryDirection,
+        envelope: &Envelope,
+    ) -> Result<()> {
+        self.tap_line(None, || DaemonReply::TapEnvelopeEvent {
+            event: "tap",
+            direction,
+            envelope: envelope.clone(),
+        })
+        .await
+    }
+
+    /// Like `broadcast_line`, for tapping clients
+    /// only; `event` is not built when there are none.
+    async fn tap_line(
+        &self,
+        except: Option<u64>,
+        event: impl FnOnce() -> DaemonReply,
+    ) -> Result<()> {
+        let mut clients = self.clients.lock().await;
+        let mut taps = clients
+            .iter()
+            .filter(|(client_id, client)| client.tap && Some(**client_id) != except)
+            .peekable();
+        if taps.peek().is_none() {
+            return Ok(());
+        }
+        let line: Arc<str> = Arc::from(serde_json::to_string(&event())?);
+        let disconnected: Vec<u64> = taps
+            .filter(|(_, client)| client.tx.try_send(line.clone()).is_err())
+            .map(|(client_id, _)| *client_id)
+            .collect();
+        for client_id in disconnected {
+            if let Some(client) = clients.remove(&client_id) {
+                client.cancel.cancel();
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Recording entry for an IPC event seen by a tapping connection, stamped
+/// `t_ms` after the recording started. Replies to our own commands are not
+/// events and never reach this.
+pub fn recording_entry(event: &Value, t_ms: u64) -> Value {
+    match event.get("event").and_then(Value::as_str) {
+        Some("tap") if event.get("command").is_some() => json!({
+            "t_ms": t_ms,
+            "type": "command",
+            "client_id": event["client_id"],
+            "command": event["command"],
+        }),
+        Some("tap") => json!({
+            "t_ms": t_ms,
+            "type": if event["direction"] == "out" { "outbound" } else { "inbound" },
+            "envelope": event["envelope"],
+        }),
+        Some("inbound") => json!({
+            "t_ms": t_ms,
+            "type": "inbound",
+            "envelope": event["envelope"],
+        }),
+        _ => json!({"t_ms": t_ms, "type": "event", "event": event}),
+    }
+}
+
+/// Parse a recording into its header and entries.
+pub fn parse_recording(text: &str) -> Result<(Value, Vec<Value>)> {
+    let mut lines = text.lines().filter(|line| !line.trim().is_empty());
+    let header: Value = lines
+        .next()
+        .context("recording is empty")
+        .and_then(|line| serde_json::from_str(line).context("invalid recording header"))?;
+    match header.get("axonrec").and_then(Value::as_u64) {
+        Some(RECORDING_FORMAT_VERSION) => {}
+        Some(version) => bail!("unsupported recording version {version}"),
+        None => bail!("not an axon recording (missing axonrec header)"),
+    }
+    let entries = lines
+        .enumerate()
+        .map(|(n, line)| {
+            serde_json::from_str(line).with_context(|| format!("invalid recording entry {}", n + 1))
+        })
+        .collect::<Result<_>>()?;
+    Ok((header, entries))
+}
+
+#[cfg(test)]
+#[path = "recording_tests.rs"]
+mod tests;
@@ -0,0 +1,63 @@
+use serde_json::json;
+
+use super::{parse_recording, recording_entry};
+
+const PEER: &str = "ed25519.bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb";
+
+#[test]
+fn entries_classify_tap_and_connection_events() {
+    let command = json!({"cmd": "send", "to": PEER, "kind": "request", "payload": {}});
+    assert_eq!(
+        recording_entry(
+            &json!({"event": "tap", "client_id": 3, "command": command}),
+            12
+        ),
+        json!({"t_ms": 12, "type": "command", "client_id": 3, "command": command})
+    );
+    let envelope = json!({"id": "6f1c1a4e-2d3b-4c5a-9e8f-0a1b2c3d4e5f", "kind": "response"});
+    assert_eq!(
+        recording_entry(
+            &json!({"event": "tap", "direction": "out", "envelope": envelope}),
+            1
+        )["type"],
+        "outbound"
+    );
+    assert_eq!(
+        recording_entry(
+            &json!({"event": "tap", "direction": "in", "envelope": envelope}),
+            1
+        )["type"],
+        "inbound"
+    );
+    assert_eq!(
+        recording_entry(
+            &json!({"event": "inbound", "from": PEER, "envelope": envelope}),
+            5
+        ),
+        json!({"t_ms": 5, "type": "inbound", "envelope": envelope})
+    );
+    let connected = json!({"event": "connected", "agent_id": PEER});
+    assert_eq!(
+        recording_entry(&connected, 7),
+        json!({"t_ms": 7, "type": "event", "event": connected})
+    );
+}
+
+#[test]
+fn parse_recording_requires_a_known_header() {
+    let text =
+        "{\"axonrec\":1,\"agent_id\":null,\"started_ms\":1}\n\n{\"t_ms\":0,\"type\":\"event\"}\n";
+    let (header, entries) = parse_recording(text).unwrap();
+    assert_eq!(header["started_ms"], 1);
+    assert_eq!(entries.len(), 1);
+
+    for (text, error) in [
+        ("", "recording is empty"),
+        ("{\"t_ms\":0}\n", "missing axonrec header"),
+        ("{\"axonrec\":2}\n", "unsupported recording version 2"),
+        ("{\"axonrec\":1}\nnot json\n", "invalid recording entry 1"),
+    ] {
+        let err = parse_recording(text).unwrap_err().to_string();
+        assert!(err.contains(error), "{err}");
+    }
+}
@@ -0,0 +1,65 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+use uuid::Uuid;
+
+/// Recent `send` envelopes remembered for reply routing.
+pub(crate) const REPLY_ROUTES_CAPACITY: usize = 4096;
+
+/// IPC client that sent each recent envelope via `send`, keyed by message
+/// ID, so an inbound envelope whose `ref` names one can be routed to it.
+///
+/// Bounded: the oldest entry is evicted once `capacity` is reached.
+#[derive(Debug)]
+pub(crate) struct ReplyRoutes {
+    capacity: usize,
+    entries: Mutex<(HashMap<Uuid, u64>, VecDeque<Uuid>)>,
+}
+
+impl Default for ReplyRoutes {
+    fn default() -> Self {
+        Self::with_capacity(REPLY_ROUTES_CAPACITY)
+    }
+}
+
+impl ReplyRoutes {
+    pub(crate) fn with_capacity(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Mutex::new((HashMap::new(), VecDeque::new())),
+        }
+    }
+
+    /// Remember that `client_id` sent `msg_id`.
+    pub(crate) fn record(&self, msg_id: Uuid, client_id: u64) {
+        if let Ok(mut entries) = self.entries.lock() {
+            let (senders, order) = &mut *entries;
+            if senders.insert(msg_id, client_id).is_none() {
+                order.push_back(msg_id);
+            }
+            while order.len() > self.capacity {
+                if let Some(oldest) = order.pop_front() {
+                    senders.remove(&oldest);
+                }
+            }
+        }
+    }
+
+    /// Forget `msg_id`, e.g. when its send failed.
+    pub(crate) fn forget(&self, msg_id: &Uuid) {
+        if let Ok(mut entries) = self.entries.lock() {
+            let (senders, order) = &mut *entries;
+            if senders.remove(msg_id).is_some() {
+                order.retain(|id| id != msg_id);
+            }
+        }
+    }
+
+    /// Client that sent a remembered envelope.
+    pub(crate) fn sender_of(&self, msg_id: &Uuid) -> Option<u64> {
+        self.entries
+            .lock()
+            .ok()
+            .and_then(|entries| entries.0.get(msg_id).copied())
+    }
+}
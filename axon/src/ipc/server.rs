@@ -4,21 +4,31 @@ use std::os::unix::fs::{FileTypeExt, PermissionsExt};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
 
 use anyhow::{Context, Result};
 use tokio::net::UnixListener;
-use tokio::sync::{Mutex, mpsc};
+use tokio::sync::{Mutex, broadcast, mpsc};
 use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
 
 use super::auth;
-use super::client_handler::handle_client;
+use super::client_handler::{ClientLimits, handle_client};
 use super::protocol::{CommandEvent, DaemonReply, IpcCommand, IpcErrorCode, WhoamiInfo};
+use super::reply_routes::ReplyRoutes;
 use crate::message::Envelope;
 
+/// Inbound envelopes buffered for each `send` awaiting a reply.
+const DELIVERED_CAPACITY: usize = 256;
+
 #[derive(Clone)]
-struct ClientHandle {
-    tx: mpsc::Sender<Arc<str>>,
-    cancel: CancellationToken,
+pub(super) struct ClientHandle {
+    pub(super) tx: mpsc::Sender<Arc<str>>,
+    pub(super) cancel: CancellationToken,
+    /// Set by the `tap` command.
+    pub(super) tap: bool,
+    /// Set by the `own_replies` command.
+    own_replies: bool,
 }
 
 pub struct IpcServerConfig {
@@ -28,6 +38,10 @@ pub struct IpcServerConfig {
     pub version: String,
     pub max_client_queue: usize,
     pub uptime_secs: Arc<dyn Fn() -> u64 + Send + Sync>,
+    /// Close clients that send no command within this window (`None` = never).
+    pub idle_timeout: Option<Duration>,
+    /// Close clients whose connection is older than this (`None` = never).
+    pub max_lifetime: Option<Duration>,
 }
 
 impl Default for IpcServerConfig {
@@ -39,6 +53,8 @@ impl Default for IpcServerConfig {
             version: env!("CARGO_PKG_VERSION").to_string(),
             max_client_queue: 1024,
             uptime_secs: Arc::new(|| 0),
+            idle_timeout: None,
+            max_lifetime: None,
         }
     }
 }
@@ -49,11 +65,21 @@ impl Default for IpcServerConfig {
 pub struct IpcServer {
     socket_path: PathBuf,
     max_clients: usize,
-    clients: Arc<Mutex<HashMap<u64, ClientHandle>>>,
+    pub(super) clients: Arc<Mutex<HashMap<u64, ClientHandle>>>,
     next_client_id: Arc<AtomicU64>,
     owner_uid: u32,
     max_client_queue: usize,
     config: Arc<IpcServerConfig>,
+    limits: Arc<Mutex<ClientLimits>>,
+    /// False when the listener was inherited (socket activation); the socket
+    /// file then belongs to the service manager and is left in place.
+    owns_socket_file: bool,
+    /// Stops the accept loop; see [`IpcServer::shutdown`].
+    accept_cancel: CancellationToken,
+    /// Which client sent recent `send` envelopes, for `own_replies`.
+    reply_routes: Arc<ReplyRoutes>,
+    /// Inbound envelopes as broadcast to clients, for `send` with `await`.
+    delivered: broadcast::Sender<Arc<Envelope>>,
 }
 
 impl IpcServer {
@@ -106,8 +132,55 @@ impl IpcServer {
                 )
             })?;
 
+        Ok(Self::serve(
+            listener,
+            socket_path,
+            max_clients,
+            config,
+            true,
+        ))
+    }
+
+    /// Serve IPC on an already-listening socket, e.g. one passed in by systemd
+    /// socket activation. The socket file is not removed by `cleanup_socket`.
+    pub fn from_listener(
+        listener: std::os::unix::net::UnixListener,
+        max_clients: usize,
+        config: IpcServerConfig,
+    ) -> Result<(Self, mpsc::Receiver<CommandEvent>)> {
+        let socket_path = listener
+            .local_addr()
+            .context("failed to read inherited IPC socket address")?
+            .as_pathname()
+            .map(Path::to_path_buf)
+            .context("inherited IPC socket is not bound to a filesystem path")?;
+        listener
+            .set_nonblocking(true)
+            .context("failed to set inherited IPC socket non-blocking")?;
+        let listener =
+            UnixListener::from_std(listener).context("failed to register inherited IPC socket")?;
+        Ok(Self::serve(
+            listener,
+            socket_path,
+            max_clients,
+            config,
+            false,
+        ))
+    }
+
+    fn serve(
+        listener: UnixListener,
+        socket_path: PathBuf,
+        max_clients: usize,
+        config: IpcServerConfig,
+        owns_socket_file: bool,
+    ) -> (Self, mpsc::Receiver<CommandEvent>) {
         let owner_uid = unsafe { libc::getuid() };
         let max_client_queue = config.max_client_queue;
+        let limits = ClientLimits {
+            idle_timeout: config.idle_timeout,
+            max_lifetime: config.max_lifetime,
+        };
 
         let server = Self {
             socket_path,
@@ -117,12 +190,17 @@ impl IpcServer {
             owner_uid,
             max_client_queue,
             config: Arc::new(config),
+            limits: Arc::new(Mutex::new(limits)),
+            owns_socket_file,
+            accept_cancel: CancellationToken::new(),
+            reply_routes: Arc::new(ReplyRoutes::default()),
+            delivered: broadcast::channel(DELIVERED_CAPACITY).0,
         };
 
         let (cmd_tx, cmd_rx) = mpsc::channel(256);
         server.start_accept_loop(listener, cmd_tx);
 
-        Ok((server, cmd_rx))
+        (server, cmd_rx)
     }
 
     pub async fn send_reply(&self, client_id: u64, reply: &DaemonReply) -> Result<()> {
@@ -145,6 +223,8 @@ impl IpcServer {
         Ok(())
     }
 
+    /// Broadcast an inbound envelope. A reply to an envelope a client sent
+    /// via `send` skips other clients that sent `own_replies`.
     pub async fn broadcast_inbound(&self, envelope: &Envelope) -> Result<()> {
         let event = DaemonReply::InboundEvent {
             event: "inbound",
@@ -156,23 +236,49 @@ impl IpcServer {
             envelope: envelope.clone(),
         };
         let line: Arc<str> = Arc::from(serde_json::to_string(&event)?);
-        self.broadcast_line(line).await
+        if self.delivered.receiver_count() > 0 {
+            let _ = self.delivered.send(Arc::new(envelope.clone()));
+        }
+        match envelope
+            .ref_id
+            .and_then(|ref_id| self.reply_routes.sender_of(&ref_id))
+        {
+            Some(sender) => {
+                self.broadcast_line_to(line, |client_id, client| {
+                    client_id == sender || !client.own_replies
+                })
+                .await
+            }
+            None => self.broadcast_line(line).await,
+        }
     }
 
-    pub async fn broadcast_pair_request(
-        &self,
-        agent_id: &str,
-        pubkey: &str,
-        addr: Option<&str>,
-    ) -> Result<()> {
-        let event = DaemonReply::PairRequestEvent {
-            event: "pair_request",
-            agent_id: agent_id.to_string(),
-            pubkey: pubkey.to_string(),
-            addr: addr.map(str::to_string),
-        };
-        let line: Arc<str> = Arc::from(serde_json::to_string(&event)?);
-        self.broadcast_line(line).await
+    /// Envelopes passed to [`broadcast_inbound`](Self::broadcast_inbound)
+    /// from now on.
+    pub fn subscribe_delivered(&self) -> broadcast::Receiver<Arc<Envelope>> {
+        self.delivered.subscribe()
+    }
+
+    /// Remember that `client_id` sent `msg_id` via `send`.
+    pub fn record_send(&self, msg_id: Uuid, client_id: u64) {
+        self.reply_routes.record(msg_id, client_id);
+    }
+
+    /// Undo [`record_send`](Self::record_send) for a send that failed.
+    pub fn forget_send(&self, msg_id: Uuid) {
+        self.reply_routes.forget(&msg_id);
+    }
+
+    /// Stop sending `client_id` replies to other clients' sends; false if it
+    /// has gone.
+    pub async fn enable_own_replies(&self, client_id: u64) -> bool {
+        match self.clients.lock().await.get_mut(&client_id) {
+            Some(client) => {
+                client.own_replies = true;
+                true
+            }
+            None => false,
+        }
     }
 
     pub async fn handle_command(&self, event: CommandEvent) -> Result<DaemonReply> {
@@ -188,12 +294,10 @@ impl IpcServer {
                 },
                 req_id,
             }),
-            _ => Ok(DaemonReply::Error {
-                ok: false,
-                error: IpcErrorCode::InternalError,
-                message: IpcErrorCode::InternalError.message(),
-                req_id: event.command.req_id().map(|s| s.to_string()),
-            }),
+            _ => Ok(DaemonReply::error(
+                IpcErrorCode::InternalError,
+                event.command.req_id().map(|s| s.to_string()),
+            )),
         }
     }
 
@@ -205,6 +309,28 @@ impl IpcServer {
         }
     }
 
+    /// Stop accepting connections and close every client. Clients see EOF,
+    /// which matters when the daemon runs inside a longer-lived process.
+    pub async fn shutdown(&self) {
+        self.accept_cancel.cancel();
+        for (_, client) in self.clients.lock().await.drain() {
+            client.cancel.cancel();
+        }
+    }
+
+    /// Replace the idle/lifetime limits applied to clients accepted from now
+    /// on. Already-connected clients keep the limits they were accepted with.
+    pub async fn set_client_limits(
+        &self,
+        idle_timeout: Option<Duration>,
+        max_lifetime: Option<Duration>,
+    ) {
+        *self.limits.lock().await = ClientLimits {
+            idle_timeout,
+            max_lifetime,
+        };
+    }
+
     pub async fn client_count(&self) -> usize {
         self.clients.lock().await.len()
     }
@@ -214,7 +340,7 @@ impl IpcServer {
     }
 
     pub fn cleanup_socket(&self) -> Result<()> {
-        if self.socket_path.exists() {
+        if self.owns_socket_file && self.socket_path.exists() {
             fs::remove_file(&self.socket_path).with_context(|| {
                 format!(
                     "failed to remove socket file: {}",
@@ -225,10 +351,23 @@ impl IpcServer {
         Ok(())
     }
 
-    async fn broadcast_line(&self, line: Arc<str>) -> Result<()> {
+    pub(super) async fn broadcast_line(&self, line: Arc<str>) -> Result<()> {
+        self.broadcast_line_to(line, |_, _| true).await
+    }
+
+    /// Like [`broadcast_line`](Self::broadcast_line), for the clients `keep`
+    /// accepts.
+    async fn broadcast_line_to(
+        &self,
+        line: Arc<str>,
+        keep: impl Fn(u64, &ClientHandle) -> bool,
+    ) -> Result<()> {
         let mut clients = self.clients.lock().await;
         let mut disconnected = Vec::new();
         for (client_id, client) in clients.iter() {
+            if !keep(*client_id, client) {
+                continue;
+            }
             if client.tx.try_send(line.clone()).is_err() {
                 disconnected.push(*client_id);
             }
@@ -247,13 +386,22 @@ impl IpcServer {
         let max_clients = self.max_clients;
         let owner_uid = self.owner_uid;
         let max_client_queue = self.max_client_queue;
+        let limits = self.limits.clone();
+        let accept_cancel = self.accept_cancel.clone();
 
         tokio::spawn(async move {
             loop {
-                let (socket, _) = match listener.accept().await {
+                let accepted = tokio::select! {
+                    _ = accept_cancel.cancelled() => break,
+                    accepted = listener.accept() => accepted,
+                };
+                let (socket, _) = match accepted {
                     Ok(v) => v,
                     Err(err) => {
+                        // Back off briefly so persistent failures (e.g. EMFILE)
+                        // don't spin the accept loop.
                         tracing::warn!(error = %err, "failed to accept IPC connection");
+                        tokio::time::sleep(Duration::from_millis(100)).await;
                         continue;
                     }
                 };
@@ -297,11 +445,14 @@ impl IpcServer {
                     ClientHandle {
                         tx: out_tx.clone(),
                         cancel: cancel.clone(),
+                        tap: false,
+                        own_replies: false,
                     },
                 );
 
                 let clients_for_remove = clients.clone();
                 let cmd_tx_for_client = cmd_tx.clone();
+                let client_limits = *limits.lock().await;
 
                 tokio::spawn(async move {
                     let _ = handle_client(
@@ -311,6 +462,7 @@ impl IpcServer {
                         out_rx,
                         cmd_tx_for_client,
                         cancel.clone(),
+                        client_limits,
                     )
                     .await;
                     if let Some(client) = clients_for_remove.lock().await.remove(&client_id) {
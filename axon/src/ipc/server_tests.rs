@@ -8,6 +8,7 @@ use tokio::sync::{Mutex, mpsc};
 use tokio_util::sync::CancellationToken;
 
 use super::*;
+use crate::ipc::HistoryDirection;
 use crate::message::MessageKind;
 
 fn test_server_with_clients(clients: HashMap<u64, mpsc::Sender<Arc<str>>>) -> IpcServer {
@@ -19,6 +20,8 @@ fn test_server_with_clients(clients: HashMap<u64, mpsc::Sender<Arc<str>>>) -> Ip
                 ClientHandle {
                     tx,
                     cancel: CancellationToken::new(),
+                    tap: false,
+                    own_replies: false,
                 },
             )
         })
@@ -31,6 +34,11 @@ fn test_server_with_clients(clients: HashMap<u64, mpsc::Sender<Arc<str>>>) -> Ip
         owner_uid: 0,
         max_client_queue: 8,
         config: Arc::new(IpcServerConfig::default()),
+        limits: Arc::new(Mutex::new(ClientLimits::default())),
+        owns_socket_file: true,
+        accept_cancel: CancellationToken::new(),
+        reply_routes: Arc::new(ReplyRoutes::default()),
+        delivered: broadcast::channel(DELIVERED_CAPACITY).0,
     }
 }
 
@@ -86,6 +94,8 @@ async fn close_client_cancels_client_handle() {
         ClientHandle {
             tx,
             cancel: cancel.clone(),
+            tap: false,
+            own_replies: false,
         },
     );
     let server = IpcServer {
@@ -96,6 +106,11 @@ async fn close_client_cancels_client_handle() {
         owner_uid: 0,
         max_client_queue: 8,
         config: Arc::new(IpcServerConfig::default()),
+        limits: Arc::new(Mutex::new(ClientLimits::default())),
+        owns_socket_file: true,
+        accept_cancel: CancellationToken::new(),
+        reply_routes: Arc::new(ReplyRoutes::default()),
+        delivered: broadcast::channel(DELIVERED_CAPACITY).0,
     };
 
     server.close_client(7).await;
@@ -134,3 +149,188 @@ async fn broadcast_pair_request_reaches_connected_clients() {
     assert!(line_b.contains("\"event\":\"pair_request\""));
     assert!(line_b.contains("\"agent_id\":\"ed25519.cccccccccccccccccccccccccccccccc\""));
 }
+
+#[tokio::test]
+async fn broadcast_peer_lifecycle_events_reach_connected_clients() {
+    let (tx, mut rx) = mpsc::channel::<Arc<str>>(8);
+
+    let mut clients = HashMap::new();
+    clients.insert(1, tx);
+    let server = test_server_with_clients(clients);
+
+    server
+        .broadcast_peer_connected("ed25519.cccccccccccccccccccccccccccccccc")
+        .await
+        .expect("connected broadcast");
+    server
+        .broadcast_peer_disconnected("ed25519.cccccccccccccccccccccccccccccccc", "idle_timeout")
+        .await
+        .expect("disconnected broadcast");
+
+    let connected: serde_json::Value =
+        serde_json::from_str(&rx.recv().await.expect("connected event")).unwrap();
+    assert_eq!(
+        connected,
+        json!({"event": "connected", "agent_id": "ed25519.cccccccccccccccccccccccccccccccc"})
+    );
+
+    let disconnected: serde_json::Value =
+        serde_json::from_str(&rx.recv().await.expect("disconnected event")).unwrap();
+    assert_eq!(
+        disconnected,
+        json!({
+            "event": "disconnected",
+            "agent_id": "ed25519.cccccccccccccccccccccccccccccccc",
+            "reason": "idle_timeout"
+        })
+    );
+}
+
+#[tokio::test]
+async fn broadcast_peer_discovery_events_reach_connected_clients() {
+    let (tx, mut rx) = mpsc::channel::<Arc<str>>(8);
+
+    let mut clients = HashMap::new();
+    clients.insert(1, tx);
+    let server = test_server_with_clients(clients);
+
+    server
+        .broadcast_peer_discovered("ed25519.cccccccccccccccccccccccccccccccc", "10.0.0.5:7100")
+        .await
+        .expect("discovered broadcast");
+    server
+        .broadcast_peer_lost("ed25519.cccccccccccccccccccccccccccccccc")
+        .await
+        .expect("lost broadcast");
+
+    let discovered: serde_json::Value =
+        serde_json::from_str(&rx.recv().await.expect("discovered event")).unwrap();
+    assert_eq!(
+        discovered,
+        json!({
+            "event": "discovered",
+            "agent_id": "ed25519.cccccccccccccccccccccccccccccccc",
+            "addr": "10.0.0.5:7100"
+        })
+    );
+    let lost: serde_json::Value =
+        serde_json::from_str(&rx.recv().await.expect("lost event")).unwrap();
+    assert_eq!(
+        lost,
+        json!({"event": "lost", "agent_id": "ed25519.cccccccccccccccccccccccccccccccc"})
+    );
+}
+
+#[tokio::test]
+async fn tap_events_reach_only_tapping_clients() {
+    let (tap_tx, mut tap_rx) = mpsc::channel::<Arc<str>>(8);
+    let (plain_tx, mut plain_rx) = mpsc::channel::<Arc<str>>(8);
+    let server = test_server_with_clients(HashMap::from([(1, tap_tx), (2, plain_tx)]));
+    assert!(server.enable_tap(1).await);
+    assert!(!server.enable_tap(9).await);
+
+    let command = IpcCommand::Status { req_id: None };
+    server.tap_command(2, &command).await.unwrap();
+    server.tap_command(1, &command).await.unwrap();
+    let envelope = Envelope::new(
+        "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+        "ed25519.bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb",
+        MessageKind::Message,
+        json!({}),
+    );
+    server
+        .tap_envelope(HistoryDirection::Out, &envelope)
+        .await
+        .unwrap();
+
+    let event: serde_json::Value = serde_json::from_str(&tap_rx.recv().await.unwrap()).unwrap();
+    assert_eq!(
+        event,
+        json!({"event": "tap", "client_id": 2, "command": {"cmd": "status", "req_id": null}})
+    );
+    let event: serde_json::Value = serde_json::from_str(&tap_rx.recv().await.unwrap()).unwrap();
+    assert_eq!(event["direction"], "out", "own commands are not echoed");
+    assert_eq!(event["envelope"]["id"], envelope.id.to_string());
+    assert!(plain_rx.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn replies_skip_own_replies_clients_that_did_not_send() {
+    let (sender_tx, mut sender_rx) = mpsc::channel::<Arc<str>>(8);
+    let (own_tx, mut own_rx) = mpsc::channel::<Arc<str>>(8);
+    let (plain_tx, mut plain_rx) = mpsc::channel::<Arc<str>>(8);
+    let server =
+        test_server_with_clients(HashMap::from([(1, sender_tx), (2, own_tx), (3, plain_tx)]));
+    assert!(server.enable_own_replies(2).await);
+    assert!(!server.enable_own_replies(9).await);
+
+    let sent = Envelope::new(
+        "ed25519.bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb",
+        "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+        MessageKind::Message,
+        json!({}),
+    );
+    server.record_send(sent.id, 1);
+    let mut reply = Envelope::new(
+        "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+        "ed25519.bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb",
+        MessageKind::Message,
+        json!({"done": true}),
+    );
+    reply.ref_id = Some(sent.id);
+    server.broadcast_inbound(&reply).await.unwrap();
+
+    assert!(
+        sender_rx
+            .recv()
+            .await
+            .unwrap()
+            .contains(&reply.id.to_string())
+    );
+    assert!(
+        plain_rx
+            .recv()
+            .await
+            .unwrap()
+            .contains(&reply.id.to_string())
+    );
+    assert!(own_rx.try_recv().is_err(), "reply to another client's send");
+
+    let unrelated = Envelope::new(
+        "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+        "ed25519.bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb",
+        MessageKind::Message,
+        json!({}),
+    );
+    server.broadcast_inbound(&unrelated).await.unwrap();
+    assert!(
+        own_rx
+            .recv()
+            .await
+            .unwrap()
+            .contains(&unrelated.id.to_string())
+    );
+}
+
+#[tokio::test]
+async fn forgotten_sends_are_no_longer_routed() {
+    let (sender_tx, mut sender_rx) = mpsc::channel::<Arc<str>>(8);
+    let (own_tx, mut own_rx) = mpsc::channel::<Arc<str>>(8);
+    let server = test_server_with_clients(HashMap::from([(1, sender_tx), (2, own_tx)]));
+    assert!(server.enable_own_replies(2).await);
+
+    let msg_id = uuid::Uuid::new_v4();
+    server.record_send(msg_id, 1);
+    server.forget_send(msg_id);
+    let mut reply = Envelope::new(
+        "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+        "ed25519.bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb",
+        MessageKind::Message,
+        json!({}),
+    );
+    reply.ref_id = Some(msg_id);
+    server.broadcast_inbound(&reply).await.unwrap();
+
+    assert!(sender_rx.recv().await.is_some());
+    assert!(own_rx.recv().await.is_some(), "no sender left to route to");
+}
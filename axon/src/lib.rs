@@ -1,9 +1,18 @@
+// `config_schema` has more top-level keys than `json!` expands at the
+// default limit.
+#![recursion_limit = "256"]
+
+pub mod client;
 pub mod config;
 pub mod daemon;
 pub mod discovery;
+pub mod errors;
 pub mod identity;
 pub mod ipc;
 pub mod message;
+pub mod node;
 pub mod peer_table;
 pub mod peer_token;
+#[cfg(any(test, feature = "sim"))]
+pub mod sim;
 pub mod transport;
@@ -7,12 +7,12 @@ mod app;
 #[tokio::main]
 async fn main() -> ExitCode {
     let cli = app::run::Cli::parse();
-    app::run::init_tracing(cli.verbose, cli.quiet);
+    app::run::init_tracing(cli.verbose, cli.quiet, cli.stdout_is_protocol());
     match app::run::run(cli).await {
         Ok(code) => code,
         Err(err) => {
             eprintln!("Error: {err:#}");
-            ExitCode::from(1)
+            app::cli::ipc_client::error_exit_code(&err)
         }
     }
 }
@@ -3,8 +3,9 @@ use std::time::{SystemTime, UNIX_EPOCH};
 
 use anyhow::{Result, bail};
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
 use serde_json::value::RawValue;
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
 use uuid::Uuid;
 
 /// AXON message kind — determines stream mapping.
@@ -13,6 +14,10 @@ use uuid::Uuid;
 /// - `Response` → bidirectional stream (reply to a `Request`)
 /// - `Message` → unidirectional stream (fire-and-forget)
 /// - `Error` → bidirectional stream (error reply to a `Request`)
+/// - `Heartbeat` → unidirectional stream (daemon liveness probe, never
+///   delivered to IPC clients)
+/// - `SubscribeTopics` → unidirectional stream (the sender's topic
+///   subscription set, never delivered to IPC clients)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum MessageKind {
@@ -20,6 +25,8 @@ pub enum MessageKind {
     Response,
     Message,
     Error,
+    Heartbeat,
+    SubscribeTopics,
     #[serde(other)]
     Unknown,
 }
@@ -32,6 +39,18 @@ impl MessageKind {
     pub fn is_response(self) -> bool {
         matches!(self, MessageKind::Response | MessageKind::Error)
     }
+
+    /// One of the four application kinds, as opposed to daemon-level or
+    /// unknown kinds.
+    pub fn is_application(self) -> bool {
+        matches!(
+            self,
+            MessageKind::Request
+                | MessageKind::Response
+                | MessageKind::Message
+                | MessageKind::Error
+        )
+    }
 }
 
 impl fmt::Display for MessageKind {
@@ -41,6 +60,8 @@ impl fmt::Display for MessageKind {
             MessageKind::Response => "response",
             MessageKind::Message => "message",
             MessageKind::Error => "error",
+            MessageKind::Heartbeat => "heartbeat",
+            MessageKind::SubscribeTopics => "subscribe_topics",
             MessageKind::Unknown => "unknown",
         };
         f.write_str(s)
@@ -137,9 +158,9 @@ impl<'de> serde::Deserialize<'de> for AgentId {
 
 /// AXON wire envelope — the top-level JSON object for every QUIC message.
 ///
-/// The wire format carries only `id`, `kind`, `payload`, and optionally `ref`.
-/// The `from` and `to` fields are populated by the daemon layer (not on wire)
-/// for IPC client consumption.
+/// The wire format carries only `id`, `kind`, `payload`, and optionally `ref`,
+/// `topic`, `thread_id`, and `headers` (DEC-014). The `from` and `to` fields
+/// are populated by the daemon layer (not on wire) for IPC client consumption.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Envelope {
     pub id: Uuid,
@@ -147,6 +168,16 @@ pub struct Envelope {
     #[serde(rename = "ref", default, skip_serializing_if = "Option::is_none")]
     pub ref_id: Option<Uuid>,
     pub payload: Box<RawValue>,
+    /// Pub/sub topic for `message` envelopes sent via `publish`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub topic: Option<String>,
+    /// Conversation this envelope belongs to; copied onto replies.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thread_id: Option<String>,
+    /// Application metadata (trace IDs, tenant IDs, ...), delivered as sent
+    /// and never interpreted by the daemon. Not copied onto replies.
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    pub headers: Map<String, Value>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub from: Option<AgentId>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -159,6 +190,9 @@ impl PartialEq for Envelope {
             && self.kind == other.kind
             && self.ref_id == other.ref_id
             && self.payload.get() == other.payload.get()
+            && self.topic == other.topic
+            && self.thread_id == other.thread_id
+            && self.headers == other.headers
             && self.from == other.from
             && self.to == other.to
     }
@@ -192,11 +226,32 @@ impl Envelope {
             kind,
             ref_id: None,
             payload: Self::raw_json(&payload),
+            topic: None,
+            thread_id: None,
+            headers: Map::new(),
             from: Some(from.into()),
             to: Some(to.into()),
         }
     }
 
+    /// Deterministic message ID for a request sent with an idempotency key.
+    ///
+    /// Retries with the same key from `from` to `to` reuse the ID, which lets
+    /// the receiver answer duplicates from its response cache. The ID is a
+    /// well-formed UUID v4 built from SHA-256 bytes, so the wire format is
+    /// unchanged.
+    pub fn idempotent_id(from: &str, to: &str, key: &str) -> Uuid {
+        let mut hasher = Sha256::new();
+        for part in ["axon-idempotency", from, to, key] {
+            hasher.update(part.as_bytes());
+            hasher.update([0]);
+        }
+        let digest = hasher.finalize();
+        let mut bytes = [0u8; 16];
+        bytes.copy_from_slice(&digest[..16]);
+        uuid::Builder::from_random_bytes(bytes).into_uuid()
+    }
+
     pub fn response_to(
         request: &Envelope,
         from: impl Into<AgentId>,
@@ -208,6 +263,9 @@ impl Envelope {
             kind,
             ref_id: Some(request.id),
             payload: Self::raw_json(&payload),
+            topic: None,
+            thread_id: request.thread_id.clone(),
+            headers: Map::new(),
             from: Some(from.into()),
             to: request.from.clone(),
         }
@@ -223,13 +281,18 @@ impl Envelope {
         if !self.payload.get().trim_start().starts_with('{') {
             bail!("payload must be a JSON object");
         }
+        if let Some(ref thread_id) = self.thread_id
+            && (thread_id.is_empty() || thread_id.len() > MAX_THREAD_ID_LEN)
+        {
+            bail!("thread_id must be 1-{MAX_THREAD_ID_LEN} bytes");
+        }
         Ok(())
     }
 
     /// Serialize for QUIC wire transport.
     ///
     /// The wire format carries only `id`, `kind`, `payload`, and optional
-    /// `ref`; daemon-local routing fields (`from`, `to`) are stripped.
+    /// `ref`/`topic`/`thread_id`/`headers`; daemon-local routing fields (`from`, `to`) are stripped.
     pub fn wire_encode(&self) -> Result<Vec<u8>> {
         let mut wire = self.clone();
         wire.from = None;
@@ -240,6 +303,9 @@ impl Envelope {
 
 pub const MAX_MESSAGE_SIZE: u32 = 65536;
 
+/// Longest accepted `thread_id`, in bytes.
+pub const MAX_THREAD_ID_LEN: usize = 128;
+
 pub fn encode(envelope: &Envelope) -> Result<Vec<u8>> {
     let json = serde_json::to_vec(envelope)?;
     if json.len() > MAX_MESSAGE_SIZE as usize {
@@ -36,6 +36,33 @@ fn response_links_request_id() {
     assert_eq!(resp.to, req.from);
 }
 
+#[test]
+fn response_inherits_thread_id() {
+    let mut req = Envelope::new(agent_a(), agent_b(), MessageKind::Request, json!({}));
+    req.thread_id = Some("t-1".to_string());
+    let resp = Envelope::response_to(&req, agent_b(), MessageKind::Response, json!({}));
+    assert_eq!(resp.thread_id.as_deref(), Some("t-1"));
+}
+
+#[test]
+fn response_does_not_copy_headers() {
+    let mut req = Envelope::new(agent_a(), agent_b(), MessageKind::Request, json!({}));
+    req.headers.insert("trace_id".to_string(), json!("abc123"));
+    let resp = Envelope::response_to(&req, agent_b(), MessageKind::Response, json!({}));
+    assert!(resp.headers.is_empty());
+}
+
+#[test]
+fn validation_bounds_thread_id_length() {
+    let mut env = Envelope::new(agent_a(), agent_b(), MessageKind::Message, json!({}));
+    env.thread_id = Some("t".repeat(MAX_THREAD_ID_LEN));
+    assert!(env.validate().is_ok());
+    env.thread_id = Some("t".repeat(MAX_THREAD_ID_LEN + 1));
+    assert!(env.validate().is_err());
+    env.thread_id = Some(String::new());
+    assert!(env.validate().is_err());
+}
+
 #[test]
 fn envelope_new_sets_defaults() {
     let env = Envelope::new(agent_a(), agent_b(), MessageKind::Request, json!({}));
@@ -128,6 +155,8 @@ fn message_kind_display() {
     assert_eq!(MessageKind::Response.to_string(), "response");
     assert_eq!(MessageKind::Message.to_string(), "message");
     assert_eq!(MessageKind::Error.to_string(), "error");
+    assert_eq!(MessageKind::Heartbeat.to_string(), "heartbeat");
+    assert_eq!(MessageKind::SubscribeTopics.to_string(), "subscribe_topics");
     assert_eq!(MessageKind::Unknown.to_string(), "unknown");
 }
 
@@ -138,6 +167,8 @@ fn kind_serde_roundtrip() {
         MessageKind::Response,
         MessageKind::Message,
         MessageKind::Error,
+        MessageKind::Heartbeat,
+        MessageKind::SubscribeTopics,
     ] {
         let json = serde_json::to_string(&kind).unwrap();
         let back: MessageKind = serde_json::from_str(&json).unwrap();
@@ -159,6 +190,8 @@ const ALL_KINDS: &[MessageKind] = &[
     MessageKind::Response,
     MessageKind::Message,
     MessageKind::Error,
+    MessageKind::Heartbeat,
+    MessageKind::SubscribeTopics,
     MessageKind::Unknown,
 ];
 
@@ -168,8 +201,14 @@ proptest! {
         kind_idx in 0..ALL_KINDS.len(),
     ) {
         let kind = ALL_KINDS[kind_idx];
-        // Message and Unknown are neither request nor response
-        if kind != MessageKind::Message && kind != MessageKind::Unknown {
+        // Message, Heartbeat, SubscribeTopics, and Unknown are neither request nor response
+        if !matches!(
+            kind,
+            MessageKind::Message
+                | MessageKind::Heartbeat
+                | MessageKind::SubscribeTopics
+                | MessageKind::Unknown
+        ) {
             prop_assert_ne!(kind.expects_response(), kind.is_response(),
                 "kind {:?} must be exactly one of request or response", kind);
         }
@@ -232,3 +271,21 @@ fn encode_accepts_exactly_max_size() {
         "encode must accept exactly MAX_MESSAGE_SIZE"
     );
 }
+
+#[test]
+fn idempotent_id_is_stable_valid_v4() {
+    let id = Envelope::idempotent_id(&agent_a(), &agent_b(), "job-42");
+    assert_eq!(
+        id,
+        Envelope::idempotent_id(&agent_a(), &agent_b(), "job-42")
+    );
+    assert_eq!(id.get_version(), Some(uuid::Version::Random));
+    assert_ne!(
+        id,
+        Envelope::idempotent_id(&agent_a(), &agent_b(), "job-43")
+    );
+    assert_ne!(
+        id,
+        Envelope::idempotent_id(&agent_b(), &agent_a(), "job-42")
+    );
+}
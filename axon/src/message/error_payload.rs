@@ -0,0 +1,62 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{Envelope, MessageKind};
+use crate::errors::WireErrorCode;
+
+/// Payload of an `error` envelope (`spec/MESSAGE_TYPES.md`, "Error codes").
+/// Unknown fields are ignored; a missing `retryable` reads as `false`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErrorPayload {
+    pub code: String,
+    pub message: String,
+    #[serde(default)]
+    pub retryable: bool,
+    /// Structured specifics of the failure, when the sender has any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<Value>,
+}
+
+impl ErrorPayload {
+    /// A daemon error with `retryable` taken from the registry.
+    pub fn new(code: WireErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code: code.as_str().to_string(),
+            message: message.into(),
+            retryable: code.retryable(),
+            details: None,
+        }
+    }
+
+    pub fn with_details(mut self, details: Value) -> Self {
+        self.details = Some(details);
+        self
+    }
+
+    /// The registered code, or `None` for application-defined codes.
+    pub fn wire_code(&self) -> Option<WireErrorCode> {
+        WireErrorCode::parse(&self.code)
+    }
+
+    /// The payload as JSON, for building an `error` envelope.
+    pub fn to_value(&self) -> Value {
+        serde_json::to_value(self).expect("error payload serialization")
+    }
+
+    /// The error payload of `envelope`, or `None` when it is not an `error`
+    /// or its payload lacks `code` and `message`.
+    pub fn from_envelope(envelope: &Envelope) -> Option<Self> {
+        if envelope.kind != MessageKind::Error {
+            return None;
+        }
+        serde_json::from_str(envelope.payload.get()).ok()
+    }
+}
+
+impl std::fmt::Display for ErrorPayload {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.code, self.message)
+    }
+}
+
+impl std::error::Error for ErrorPayload {}
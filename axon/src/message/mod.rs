@@ -1,3 +1,7 @@
 mod envelope;
+mod error_payload;
 
-pub use envelope::{AgentId, Envelope, MAX_MESSAGE_SIZE, MessageKind, decode, encode, now_millis};
+pub use envelope::{
+    AgentId, Envelope, MAX_MESSAGE_SIZE, MAX_THREAD_ID_LEN, MessageKind, decode, encode, now_millis,
+};
+pub use error_payload::ErrorPayload;
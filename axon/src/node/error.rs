@@ -0,0 +1,53 @@
+use anyhow::Result;
+use serde_json::Value;
+
+use crate::ipc::IpcErrorCode;
+
+/// A daemon reply with `ok: false`. Reaches callers inside the
+/// `anyhow::Error` of an [`AxonNode`](super::AxonNode) method, so it can be
+/// recovered with `downcast_ref`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NodeError {
+    pub code: IpcErrorCode,
+    pub message: String,
+    /// Whether resending the same call can succeed.
+    pub retryable: bool,
+    pub details: Option<Value>,
+}
+
+impl std::fmt::Display for NodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.code, self.message)
+    }
+}
+
+impl std::error::Error for NodeError {}
+
+/// `reply` unchanged when `ok` is true, else its error as a [`NodeError`].
+pub(super) fn check_reply(reply: Value) -> Result<Value> {
+    if reply.get("ok") == Some(&Value::Bool(true)) {
+        return Ok(reply);
+    }
+    let code = reply
+        .get("error")
+        .cloned()
+        .and_then(|code| serde_json::from_value(code).ok())
+        .unwrap_or(IpcErrorCode::InternalError);
+    let message = reply
+        .get("message")
+        .and_then(Value::as_str)
+        .unwrap_or("daemon reported an error")
+        .to_string();
+    let retryable = reply
+        .get("retryable")
+        .and_then(Value::as_bool)
+        .unwrap_or_else(|| code.retryable());
+    let details = reply.get("details").cloned();
+    Err(NodeError {
+        code,
+        message,
+        retryable,
+        details,
+    }
+    .into())
+}
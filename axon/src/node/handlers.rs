@@ -0,0 +1,190 @@
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use serde::Deserialize;
+use serde_json::Value;
+use tracing::warn;
+
+use crate::errors::WireErrorCode;
+use crate::message::{Envelope, ErrorPayload, MessageKind};
+use crate::transport::ResponseHandlerFn;
+
+/// An `error` reply from a request handler. Sent as the `code`, `message`,
+/// `retryable`, `details` payload every AXON error uses.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HandlerError {
+    pub code: String,
+    pub message: String,
+    pub retryable: bool,
+    pub details: Option<Value>,
+}
+
+impl HandlerError {
+    pub fn new(code: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            code: code.into(),
+            message: message.into(),
+            retryable: false,
+            details: None,
+        }
+    }
+
+    /// Mark the error as worth retrying.
+    pub fn retryable(mut self) -> Self {
+        self.retryable = true;
+        self
+    }
+
+    /// Attach structured specifics for the caller.
+    pub fn with_details(mut self, details: Value) -> Self {
+        self.details = Some(details);
+        self
+    }
+
+    fn into_payload(self) -> Value {
+        ErrorPayload {
+            code: self.code,
+            message: self.message,
+            retryable: self.retryable,
+            details: self.details,
+        }
+        .to_value()
+    }
+}
+
+impl std::fmt::Display for HandlerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.code, self.message)
+    }
+}
+
+impl std::error::Error for HandlerError {}
+
+type RequestFuture = Pin<Box<dyn Future<Output = Result<Value, HandlerError>> + Send>>;
+type RequestHandler = Arc<dyn Fn(Envelope) -> RequestFuture + Send + Sync>;
+type MessageHandler =
+    Arc<dyn Fn(Envelope) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync>;
+
+/// Handlers registered on an [`AxonNodeBuilder`](super::AxonNodeBuilder).
+#[derive(Clone, Default)]
+pub(super) struct Handlers {
+    requests: Option<RequestHandler>,
+    domains: BTreeMap<String, RequestHandler>,
+    messages: Vec<MessageHandler>,
+}
+
+impl std::fmt::Debug for Handlers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Handlers")
+            .field("requests", &self.requests.is_some())
+            .field("domains", &self.domains.keys().collect::<Vec<_>>())
+            .field("messages", &self.messages.len())
+            .finish()
+    }
+}
+
+impl Handlers {
+    pub(super) fn on_request<F, Fut>(&mut self, domain: Option<String>, handler: F)
+    where
+        F: Fn(Envelope) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<Value, HandlerError>> + Send + 'static,
+    {
+        let handler: RequestHandler = Arc::new(move |request| Box::pin(handler(request)));
+        match domain {
+            Some(domain) => {
+                self.domains.insert(domain, handler);
+            }
+            None => self.requests = Some(handler),
+        }
+    }
+
+    pub(super) fn on_message<F, Fut>(&mut self, handler: F)
+    where
+        F: Fn(Envelope) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.messages
+            .push(Arc::new(move |message| Box::pin(handler(message))));
+    }
+
+    pub(super) fn has_message_handlers(&self) -> bool {
+        !self.messages.is_empty()
+    }
+
+    /// The handler for `request`: the most specific domain handler matching
+    /// its payload `domain`, else the catch-all one.
+    fn route(&self, request: &Envelope) -> Option<&RequestHandler> {
+        #[derive(Deserialize)]
+        struct Domain {
+            domain: String,
+        }
+        serde_json::from_str::<Domain>(request.payload.get())
+            .ok()
+            .and_then(|payload| {
+                self.domains
+                    .iter()
+                    .filter(|(pattern, _)| domain_matches(pattern, &payload.domain))
+                    .max_by_key(|(pattern, _)| pattern.len())
+                    .map(|(_, handler)| handler)
+            })
+            .or(self.requests.as_ref())
+    }
+
+    /// The daemon's response handler, or `None` without request handlers.
+    /// Requests no handler matches go on to `handler.exec` or the default
+    /// `unhandled` error.
+    pub(super) fn response_handler(&self) -> Option<ResponseHandlerFn> {
+        if self.requests.is_none() && self.domains.is_empty() {
+            return None;
+        }
+        let handlers = Arc::new(self.clone());
+        Some(Arc::new(move |request: Arc<Envelope>| {
+            let handler = handlers.route(&request).cloned();
+            Box::pin(async move {
+                let handler = handler?;
+                let request = Arc::unwrap_or_clone(request);
+                // The transport fills in `to` with the local agent ID.
+                let responder = request.to.clone()?;
+                // A panicking handler becomes an error reply, not a dropped
+                // stream.
+                let result = tokio::spawn(handler(request.clone()))
+                    .await
+                    .unwrap_or_else(|_| {
+                        Err(HandlerError::new(
+                            WireErrorCode::HandlerFailed.as_str(),
+                            "request handler panicked",
+                        ))
+                    });
+                let (kind, payload) = match result {
+                    Ok(payload) => (MessageKind::Response, payload),
+                    Err(error) => {
+                        warn!(msg_id = %request.id, code = %error.code, message = %error.message, "request handler failed");
+                        (MessageKind::Error, error.into_payload())
+                    }
+                };
+                Some(Envelope::response_to(&request, responder, kind, payload))
+            })
+        }))
+    }
+
+    /// Run every message handler on `message`, each in its own task.
+    pub(super) fn dispatch_message(&self, message: &Envelope) {
+        for handler in &self.messages {
+            tokio::spawn(handler(message.clone()));
+        }
+    }
+}
+
+/// Whether `domain` is `pattern` or below it: `family` matches
+/// `family.calendar`, but not `familyoffice`.
+pub(super) fn domain_matches(pattern: &str, domain: &str) -> bool {
+    domain
+        .strip_prefix(pattern)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+}
+
+#[cfg(test)]
+#[path = "handlers_tests.rs"]
+mod tests;
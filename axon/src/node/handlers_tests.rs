@@ -0,0 +1,84 @@
+use serde_json::json;
+
+use super::*;
+
+const LOCAL: &str = "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+const PEER: &str = "ed25519.bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb";
+
+fn request(payload: Value) -> Arc<Envelope> {
+    let mut envelope = Envelope::new(PEER, LOCAL, MessageKind::Request, payload);
+    envelope.from = Some(PEER.into());
+    envelope.to = Some(LOCAL.into());
+    Arc::new(envelope)
+}
+
+fn answer(name: &'static str) -> impl Fn(Envelope) -> RequestFuture + Send + Sync + 'static {
+    move |_| Box::pin(async move { Ok(json!({"by": name})) })
+}
+
+#[test]
+fn domains_match_themselves_and_their_subdomains() {
+    assert!(domain_matches("family", "family"));
+    assert!(domain_matches("family", "family.calendar"));
+    assert!(!domain_matches("family", "familyoffice"));
+    assert!(!domain_matches("family.calendar", "family"));
+}
+
+#[tokio::test]
+async fn requests_route_to_the_most_specific_domain() {
+    let mut handlers = Handlers::default();
+    handlers.on_request(Some("family".to_string()), answer("family"));
+    handlers.on_request(Some("family.calendar".to_string()), answer("calendar"));
+    let respond = handlers.response_handler().unwrap();
+
+    let reply = respond(request(json!({"domain": "family.calendar.week"})))
+        .await
+        .unwrap();
+    assert_eq!(reply.kind, MessageKind::Response);
+    assert_eq!(reply.payload.get(), r#"{"by":"calendar"}"#);
+    assert_eq!(reply.from.unwrap().as_str(), LOCAL);
+
+    let reply = respond(request(json!({"domain": "family.school"})))
+        .await
+        .unwrap();
+    assert_eq!(reply.payload.get(), r#"{"by":"family"}"#);
+
+    // Nothing matches and there is no catch-all: the daemon falls back.
+    assert!(respond(request(json!({"domain": "work"}))).await.is_none());
+    assert!(respond(request(json!("plain text"))).await.is_none());
+
+    handlers.on_request(None, answer("any"));
+    let respond = handlers.response_handler().unwrap();
+    let reply = respond(request(json!({"domain": "work"}))).await.unwrap();
+    assert_eq!(reply.payload.get(), r#"{"by":"any"}"#);
+}
+
+#[tokio::test]
+async fn handler_errors_and_panics_become_error_replies() {
+    let mut handlers = Handlers::default();
+    handlers.on_request(Some("busy".to_string()), |_| async {
+        Err(HandlerError::new("busy", "try later").retryable())
+    });
+    handlers.on_request(None, |_| async { panic!("boom") });
+    let respond = handlers.response_handler().unwrap();
+
+    let reply = respond(request(json!({"domain": "busy"}))).await.unwrap();
+    assert_eq!(reply.kind, MessageKind::Error);
+    let payload: Value = serde_json::from_str(reply.payload.get()).unwrap();
+    assert_eq!(
+        payload,
+        json!({"code": "busy", "message": "try later", "retryable": true})
+    );
+
+    let reply = respond(request(json!({}))).await.unwrap();
+    assert_eq!(reply.kind, MessageKind::Error);
+    assert!(reply.payload.get().contains("handler_failed"));
+}
+
+#[test]
+fn no_request_handlers_means_no_response_handler() {
+    let mut handlers = Handlers::default();
+    handlers.on_message(|_| async {});
+    assert!(handlers.response_handler().is_none());
+    assert!(handlers.has_message_handlers());
+}
@@ -0,0 +1,317 @@
+//! Embed an AXON agent in a Rust program.
+//!
+//! [`AxonNode::builder`] starts the daemon in a task of the caller's tokio
+//! runtime and returns a handle that talks to it over its IPC socket, so an
+//! embedded node behaves exactly like one started with `axon daemon`: the
+//! same `config.yaml`, state root, and socket, which the CLI can also use.
+//!
+//! ```no_run
+//! # async fn demo() -> anyhow::Result<()> {
+//! use axon::node::AxonNode;
+//! use serde_json::json;
+//!
+//! let node = AxonNode::builder().port(7100).spawn().await?;
+//! let mut inbound = node.subscribe_inbound().await?;
+//! let reply = node.send_request("ed25519.…", json!({"question": "status?"})).await?;
+//! println!("{}", reply.payload);
+//! while let Some(envelope) = inbound.next().await {
+//!     println!("{} sent {}", envelope.from.unwrap(), envelope.payload);
+//! }
+//! node.shutdown().await?;
+//! # Ok(())
+//! # }
+//! ```
+
+mod error;
+mod handlers;
+mod query;
+
+use std::future::Future;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::{Context, Result, anyhow, bail};
+use serde_json::{Value, json};
+use tokio::task::JoinHandle;
+use tokio::time::{Instant, sleep, timeout};
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+use crate::config::AxonPaths;
+use crate::daemon::{DaemonOptions, run_daemon};
+use crate::ipc::PeerSummary;
+use crate::message::{Envelope, MessageKind};
+
+use crate::client::{InboundEnvelopes, IpcClient};
+pub use error::NodeError;
+use error::check_reply;
+pub use handlers::HandlerError;
+use handlers::Handlers;
+pub use query::QueryError;
+
+/// How long `spawn` waits for the IPC socket and `shutdown` for the daemon
+/// to exit.
+pub const NODE_STARTUP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Options for an embedded node. Unset options fall back to `config.yaml`
+/// in the state root, as they do for `axon daemon`.
+#[derive(Debug, Clone, Default)]
+pub struct AxonNodeBuilder {
+    port: Option<u16>,
+    state_root: Option<PathBuf>,
+    profile: Option<String>,
+    disable_mdns: bool,
+    handlers: Handlers,
+}
+
+impl AxonNodeBuilder {
+    /// QUIC listen port (`axon daemon --port`).
+    pub fn port(mut self, port: u16) -> Self {
+        self.port = Some(port);
+        self
+    }
+
+    /// State root holding the identity, socket, and `config.yaml`. Defaults
+    /// to `AXON_ROOT` or `~/.axon`.
+    pub fn state_root(mut self, root: impl Into<PathBuf>) -> Self {
+        self.state_root = Some(root.into());
+        self
+    }
+
+    /// Profile from `profiles` in `config.yaml` (`axon --profile`).
+    pub fn profile(mut self, profile: impl Into<String>) -> Self {
+        self.profile = Some(profile.into());
+        self
+    }
+
+    /// Skip mDNS discovery; only static and enrolled peers are reachable.
+    pub fn disable_mdns(mut self) -> Self {
+        self.disable_mdns = true;
+        self
+    }
+
+    /// Answer inbound requests no [`on_domain`](Self::on_domain) handler
+    /// claims. The returned value becomes the `response` payload and a
+    /// [`HandlerError`] an `error` reply. Without any request handler the
+    /// daemon falls back to `handler.exec`, then to the `unhandled` error.
+    pub fn on_request<F, Fut>(mut self, handler: F) -> Self
+    where
+        F: Fn(Envelope) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<Value, HandlerError>> + Send + 'static,
+    {
+        self.handlers.on_request(None, handler);
+        self
+    }
+
+    /// Answer inbound requests whose payload `domain` is `domain` or below
+    /// it (`family` covers `family.calendar`). The most specific registered
+    /// domain wins.
+    pub fn on_domain<F, Fut>(mut self, domain: impl Into<String>, handler: F) -> Self
+    where
+        F: Fn(Envelope) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<Value, HandlerError>> + Send + 'static,
+    {
+        self.handlers.on_request(Some(domain.into()), handler);
+        self
+    }
+
+    /// Run `handler` on every inbound fire-and-forget `message`.
+    pub fn on_message<F, Fut>(mut self, handler: F) -> Self
+    where
+        F: Fn(Envelope) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.handlers.on_message(handler);
+        self
+    }
+
+    /// Start the daemon and wait until its IPC socket accepts connections.
+    /// Fails if the daemon exits first, e.g. because another daemon holds
+    /// the state root.
+    pub async fn spawn(self) -> Result<AxonNode> {
+        let paths = match &self.state_root {
+            Some(root) => AxonPaths::from_root(root.clone()),
+            None => AxonPaths::discover()?,
+        };
+        let paths = match &self.profile {
+            Some(profile) => paths.with_profile(profile)?,
+            None => paths,
+        };
+
+        let cancel = CancellationToken::new();
+        let mut task = tokio::spawn(run_daemon(DaemonOptions {
+            port: self.port,
+            disable_mdns: self.disable_mdns,
+            axon_root: Some(paths.base_root().to_path_buf()),
+            profile: self.profile,
+            cancel: Some(cancel.clone()),
+            handle_signals: false,
+            response_handler: self.handlers.response_handler(),
+        }));
+
+        let deadline = Instant::now() + NODE_STARTUP_TIMEOUT;
+        let client = loop {
+            if task.is_finished() {
+                return Err(match (&mut task).await {
+                    Ok(Err(err)) => err.context("daemon exited during startup"),
+                    _ => anyhow!("daemon exited during startup"),
+                });
+            }
+            if let Ok(client) = IpcClient::connect(&paths.socket).await {
+                break client;
+            }
+            if Instant::now() >= deadline {
+                cancel.cancel();
+                bail!(
+                    "IPC socket {} did not appear within {}s",
+                    paths.socket.display(),
+                    NODE_STARTUP_TIMEOUT.as_secs()
+                );
+            }
+            sleep(Duration::from_millis(50)).await;
+        };
+
+        let whoami = check_reply(client.command(json!({"cmd": "whoami"})).await?)?;
+        let field = |name: &str| {
+            whoami[name]
+                .as_str()
+                .map(str::to_string)
+                .with_context(|| format!("whoami reply has no {name}"))
+        };
+        let node = AxonNode {
+            agent_id: field("agent_id")?,
+            public_key: field("public_key")?,
+            paths,
+            cancel,
+            task: Some(task),
+        };
+
+        if self.handlers.has_message_handlers() {
+            let mut inbound = node.subscribe_inbound().await?;
+            let handlers = self.handlers;
+            tokio::spawn(async move {
+                while let Some(envelope) = inbound.next().await {
+                    if envelope.kind == MessageKind::Message {
+                        handlers.dispatch_message(&envelope);
+                    }
+                }
+            });
+        }
+        Ok(node)
+    }
+}
+
+/// Handle to a running embedded node. Dropping it stops the daemon without
+/// waiting; [`AxonNode::shutdown`] waits for a clean exit.
+#[derive(Debug)]
+pub struct AxonNode {
+    agent_id: String,
+    public_key: String,
+    paths: AxonPaths,
+    cancel: CancellationToken,
+    task: Option<JoinHandle<Result<()>>>,
+}
+
+impl AxonNode {
+    pub fn builder() -> AxonNodeBuilder {
+        AxonNodeBuilder::default()
+    }
+
+    pub fn agent_id(&self) -> &str {
+        &self.agent_id
+    }
+
+    /// Base64 Ed25519 public key, which peers enroll with.
+    pub fn public_key(&self) -> &str {
+        &self.public_key
+    }
+
+    /// Paths of the node's state root, shared with the `axon` CLI.
+    pub fn paths(&self) -> &AxonPaths {
+        &self.paths
+    }
+
+    /// Send a request and wait for the peer's `response` or `error`
+    /// envelope.
+    pub async fn send_request(&self, to: &str, payload: Value) -> Result<Envelope> {
+        let reply = self
+            .command(json!({"cmd": "send", "to": to, "kind": "request", "payload": payload}))
+            .await?;
+        let response = reply
+            .get("response")
+            .cloned()
+            .context("daemon reply has no response")?;
+        serde_json::from_value(response).context("invalid response envelope")
+    }
+
+    /// Send a fire-and-forget message. Returns its message ID.
+    pub async fn notify(&self, to: &str, payload: Value) -> Result<Uuid> {
+        let reply = self
+            .command(json!({"cmd": "send", "to": to, "kind": "message", "payload": payload}))
+            .await?;
+        serde_json::from_value(reply["msg_id"].clone()).context("daemon reply has no msg_id")
+    }
+
+    /// Known peers and their connection status.
+    pub async fn peers(&self) -> Result<Vec<PeerSummary>> {
+        let reply = self.command(json!({"cmd": "peers"})).await?;
+        serde_json::from_value(reply["peers"].clone()).context("invalid peers reply")
+    }
+
+    /// Enroll a peer by public key and address (`axon connect`). Returns its
+    /// agent ID.
+    pub async fn add_peer(&self, pubkey: &str, addr: &str) -> Result<String> {
+        let reply = self
+            .command(json!({"cmd": "add_peer", "pubkey": pubkey, "addr": addr}))
+            .await?;
+        reply["agent_id"]
+            .as_str()
+            .map(str::to_string)
+            .context("add_peer reply has no agent_id")
+    }
+
+    /// Envelopes peers send to this node from now on, as a
+    /// `futures_core::Stream`. Ends when the node stops; each subscriber gets
+    /// every envelope, and one more than `EVENT_BUFFER` behind is ended.
+    pub async fn subscribe_inbound(&self) -> Result<InboundEnvelopes> {
+        let client = IpcClient::connect(&self.paths.socket).await?;
+        let inbound = client.inbound();
+        // Every IPC client receives `inbound` events. The round trip makes
+        // sure the daemon has registered this one before returning.
+        check_reply(client.command(json!({"cmd": "status"})).await?)?;
+        Ok(inbound.owning(client))
+    }
+
+    /// Stop the daemon and wait for it to close connections and flush its
+    /// state. Returns the daemon's error if it had failed.
+    pub async fn shutdown(mut self) -> Result<()> {
+        self.cancel.cancel();
+        let Some(task) = self.task.take() else {
+            return Ok(());
+        };
+        timeout(NODE_STARTUP_TIMEOUT, task)
+            .await
+            .with_context(|| {
+                format!(
+                    "daemon did not exit within {}s",
+                    NODE_STARTUP_TIMEOUT.as_secs()
+                )
+            })?
+            .context("daemon task panicked")?
+    }
+
+    async fn command(&self, command: Value) -> Result<Value> {
+        let client = IpcClient::connect(&self.paths.socket).await?;
+        check_reply(client.command(command).await?)
+    }
+}
+
+impl Drop for AxonNode {
+    fn drop(&mut self) {
+        self.cancel.cancel();
+    }
+}
+
+#[cfg(test)]
+#[path = "tests.rs"]
+mod tests;
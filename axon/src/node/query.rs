@@ -0,0 +1,113 @@
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use super::{AxonNode, NodeError};
+use crate::message::{Envelope, ErrorPayload, MAX_MESSAGE_SIZE, MessageKind};
+
+/// Why [`AxonNode::query`] produced no typed response.
+#[derive(Debug)]
+pub enum QueryError {
+    /// The request could not be encoded as JSON.
+    Encode(serde_json::Error),
+    /// The request envelope would exceed the wire limit.
+    TooLarge { size: usize, limit: usize },
+    /// The daemon rejected the send: unknown or unreachable peer, timeout.
+    Node(NodeError),
+    /// The daemon could not be reached, or its reply was malformed.
+    Ipc(anyhow::Error),
+    /// The peer answered with an `error` envelope.
+    Remote(ErrorPayload),
+    /// The `response` payload does not match the expected type.
+    Decode(serde_json::Error),
+}
+
+impl std::fmt::Display for QueryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Encode(err) => write!(f, "failed to encode request: {err}"),
+            Self::TooLarge { size, limit } => {
+                write!(f, "request of {size} bytes exceeds the {limit}-byte limit")
+            }
+            Self::Node(err) => write!(f, "daemon rejected request: {err}"),
+            Self::Ipc(err) => write!(f, "{err:#}"),
+            Self::Remote(err) => write!(f, "peer replied with error {err}"),
+            Self::Decode(err) => write!(f, "unexpected response payload: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for QueryError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Encode(err) | Self::Decode(err) => Some(err),
+            Self::Node(err) => Some(err),
+            Self::Remote(err) => Some(err),
+            Self::Ipc(err) => Some(err.as_ref()),
+            Self::TooLarge { .. } => None,
+        }
+    }
+}
+
+impl AxonNode {
+    /// Send `request` as a `request` envelope and decode the peer's
+    /// `response` payload as `Resp`. An `error` reply becomes
+    /// [`QueryError::Remote`].
+    pub async fn query<Req, Resp>(&self, to: &str, request: &Req) -> Result<Resp, QueryError>
+    where
+        Req: Serialize + ?Sized,
+        Resp: DeserializeOwned,
+    {
+        let payload = serde_json::to_value(request).map_err(QueryError::Encode)?;
+        check_wire_size(&Envelope::new(
+            self.agent_id(),
+            to,
+            MessageKind::Request,
+            payload.clone(),
+        ))?;
+        let reply = self.send_request(to, payload).await.map_err(|err| {
+            match err.downcast::<NodeError>() {
+                Ok(err) => QueryError::Node(err),
+                Err(err) => QueryError::Ipc(err),
+            }
+        })?;
+        decode_reply(&reply)
+    }
+}
+
+/// Reject `envelope` when its wire encoding exceeds `MAX_MESSAGE_SIZE`, before
+/// the daemon or the peer would.
+pub(super) fn check_wire_size(envelope: &Envelope) -> Result<(), QueryError> {
+    let mut wire = envelope.clone();
+    wire.from = None;
+    wire.to = None;
+    let size = serde_json::to_vec(&wire).map_err(QueryError::Encode)?.len();
+    let limit = MAX_MESSAGE_SIZE as usize;
+    if size > limit {
+        return Err(QueryError::TooLarge { size, limit });
+    }
+    Ok(())
+}
+
+/// The typed payload of a `response`, or the reason there is none.
+pub(super) fn decode_reply<Resp: DeserializeOwned>(reply: &Envelope) -> Result<Resp, QueryError> {
+    match reply.kind {
+        MessageKind::Response => {
+            serde_json::from_str(reply.payload.get()).map_err(QueryError::Decode)
+        }
+        MessageKind::Error => Err(QueryError::Remote(
+            ErrorPayload::from_envelope(reply).unwrap_or_else(|| ErrorPayload {
+                code: "invalid_error".to_string(),
+                message: format!("malformed error payload: {}", reply.payload.get()),
+                retryable: false,
+                details: None,
+            }),
+        )),
+        kind => Err(QueryError::Ipc(anyhow::anyhow!(
+            "reply has kind {kind}, expected response or error"
+        ))),
+    }
+}
+
+#[cfg(test)]
+#[path = "query_tests.rs"]
+mod tests;
@@ -0,0 +1,62 @@
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+use super::*;
+
+const LOCAL: &str = "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+const PEER: &str = "ed25519.bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb";
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Sum {
+    sum: i64,
+}
+
+fn reply(kind: MessageKind, payload: Value) -> Envelope {
+    let request = Envelope::new(LOCAL, PEER, MessageKind::Request, json!({}));
+    Envelope::response_to(&request, PEER, kind, payload)
+}
+
+#[test]
+fn responses_decode_and_errors_map_to_variants() {
+    let sum: Sum = decode_reply(&reply(MessageKind::Response, json!({"sum": 5}))).unwrap();
+    assert_eq!(sum, Sum { sum: 5 });
+
+    let err = decode_reply::<Sum>(&reply(MessageKind::Response, json!({"total": 5})));
+    assert!(matches!(err, Err(QueryError::Decode(_))));
+
+    let err = decode_reply::<Sum>(&reply(
+        MessageKind::Error,
+        json!({"code": "rate_limited", "message": "slow down", "retryable": true}),
+    ));
+    match err {
+        Err(QueryError::Remote(payload)) => assert_eq!(
+            payload,
+            ErrorPayload {
+                code: "rate_limited".to_string(),
+                message: "slow down".to_string(),
+                retryable: true,
+                details: None,
+            }
+        ),
+        other => panic!("expected Remote, got {other:?}"),
+    }
+
+    let err = decode_reply::<Sum>(&reply(MessageKind::Error, json!("oops")));
+    assert!(matches!(err, Err(QueryError::Remote(p)) if p.code == "invalid_error"));
+}
+
+#[test]
+fn oversized_requests_are_rejected_before_sending() {
+    let fits = Envelope::new(LOCAL, PEER, MessageKind::Request, json!({"x": "y"}));
+    assert!(check_wire_size(&fits).is_ok());
+
+    let big = "x".repeat(MAX_MESSAGE_SIZE as usize);
+    let too_big = Envelope::new(LOCAL, PEER, MessageKind::Request, json!({"x": big}));
+    match check_wire_size(&too_big) {
+        Err(QueryError::TooLarge { size, limit }) => {
+            assert!(size > limit);
+            assert_eq!(limit, MAX_MESSAGE_SIZE as usize);
+        }
+        other => panic!("expected TooLarge, got {other:?}"),
+    }
+}
@@ -0,0 +1,59 @@
+use serde_json::json;
+use tempfile::tempdir;
+
+use super::error::check_reply;
+use super::*;
+use crate::ipc::IpcErrorCode;
+
+#[test]
+fn failed_replies_become_node_errors() {
+    let ok = json!({"ok": true, "peers": []});
+    assert_eq!(check_reply(ok.clone()).unwrap(), ok);
+
+    let err = check_reply(json!({
+        "ok": false,
+        "error": "peer_not_found",
+        "message": "unknown peer"
+    }))
+    .unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<NodeError>(),
+        Some(&NodeError {
+            code: IpcErrorCode::PeerNotFound,
+            message: "unknown peer".to_string(),
+            retryable: false,
+            details: None,
+        })
+    );
+
+    let err = check_reply(json!({
+        "ok": false,
+        "error": "invalid_command",
+        "message": "bad field",
+        "retryable": false,
+        "details": {"reason": "payload must be an object"}
+    }))
+    .unwrap_err();
+    let err = err.downcast_ref::<NodeError>().unwrap();
+    assert_eq!(
+        err.details,
+        Some(json!({"reason": "payload must be an object"}))
+    );
+
+    let err = check_reply(json!({"ok": false, "error": "from_the_future"})).unwrap_err();
+    let err = err.downcast_ref::<NodeError>().unwrap();
+    assert_eq!(err.code, IpcErrorCode::InternalError);
+}
+
+#[tokio::test]
+async fn spawn_fails_for_an_undefined_profile() {
+    let dir = tempdir().unwrap();
+    let err = AxonNode::builder()
+        .state_root(dir.path())
+        .profile("work")
+        .disable_mdns()
+        .spawn()
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("unknown profile 'work'"), "{err}");
+}
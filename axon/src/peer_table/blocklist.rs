@@ -0,0 +1,54 @@
+use std::collections::HashSet;
+
+use super::{PeerRecord, PeerTable};
+use crate::peer_token::derive_agent_id_from_pubkey_base64;
+
+impl PeerTable {
+    /// Whether `pubkey` is on the blocklist, directly or through the agent ID
+    /// it derives to.
+    pub fn is_blocked(&self, pubkey: &str) -> bool {
+        let blocked = match self.blocked.read() {
+            Ok(blocked) => blocked,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        blocked.contains(pubkey)
+            || derive_agent_id_from_pubkey_base64(pubkey)
+                .is_ok_and(|agent_id| blocked.contains(agent_id.as_str()))
+    }
+
+    /// Whether `pubkey` is revoked or blocked; such keys never enter the
+    /// table and are refused at the TLS handshake.
+    pub fn is_refused(&self, pubkey: &str) -> bool {
+        self.is_revoked(pubkey) || self.is_blocked(pubkey)
+    }
+
+    /// Replace the blocklist and drop every peer holding a blocked key.
+    /// Returns the removed peers.
+    pub async fn set_blocked(&self, keys: HashSet<String>) -> Vec<PeerRecord> {
+        match self.blocked.write() {
+            Ok(mut blocked) => *blocked = keys,
+            Err(poisoned) => *poisoned.into_inner() = keys,
+        }
+        self.remove_refused().await
+    }
+
+    /// Add `key` (a public key, or an agent ID whose key is not known) to
+    /// the blocklist and drop any peer it matches. Returns the removed peers.
+    pub async fn block(&self, key: &str) -> Vec<PeerRecord> {
+        match self.blocked.write() {
+            Ok(mut blocked) => blocked.insert(key.to_string()),
+            Err(poisoned) => poisoned.into_inner().insert(key.to_string()),
+        };
+        self.remove_refused().await
+    }
+
+    /// Take `key` off the blocklist. The peer is not re-added; discovery,
+    /// a config reload, or `add_peer` bring it back. Returns `false` when it
+    /// was not blocked.
+    pub fn unblock(&self, key: &str) -> bool {
+        match self.blocked.write() {
+            Ok(mut blocked) => blocked.remove(key),
+            Err(poisoned) => poisoned.into_inner().remove(key),
+        }
+    }
+}
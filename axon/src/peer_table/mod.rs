@@ -1,15 +1,25 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::net::SocketAddr;
 use std::sync::{Arc, RwLock as StdRwLock, RwLockWriteGuard as StdRwLockWriteGuard};
 use std::time::{Duration, Instant};
 
-use serde::{Deserialize, Serialize};
 use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
 
 use crate::config::{KnownPeer, KnownPeerSource, StaticPeerConfig};
 use crate::message::AgentId;
 
+mod blocklist;
+mod record;
+mod software;
+mod stale;
+mod traffic;
+
+pub use record::{ConnectionStatus, PeerRecord, PeerSource};
+pub use software::{PeerSoftware, VersionCompatibility};
+pub use stale::StalePolicy;
+pub use traffic::PeerTraffic;
+
 /// Sync-safe pubkey map shared with TLS verifiers.
 ///
 /// Uses `std::sync::RwLock` (not `tokio::sync`) because rustls verifier
@@ -17,72 +27,24 @@ use crate::message::AgentId;
 /// peer public keys — updated automatically by `PeerTable` mutations.
 pub type PubkeyMap = Arc<StdRwLock<HashMap<String, String>>>;
 
+/// Sync-safe set of base64 public keys, shared with TLS verifiers for the
+/// same reason as [`PubkeyMap`]. Holds the revoked keys, and separately the
+/// blocked ones.
+pub type RevokedKeys = Arc<StdRwLock<HashSet<String>>>;
+
 pub const STALE_TIMEOUT: Duration = Duration::from_secs(60);
 
 fn canonical_agent_id(agent_id: &str) -> AgentId {
     AgentId::from(agent_id.to_ascii_lowercase())
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(rename_all = "snake_case")]
-pub enum PeerSource {
-    Static,
-    Discovered,
-    Cached,
-}
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(rename_all = "snake_case")]
-pub enum ConnectionStatus {
-    Discovered,
-    Connecting,
-    Connected,
-    Disconnected,
-}
-
-#[derive(Debug, Clone)]
-pub struct PeerRecord {
-    pub agent_id: AgentId,
-    pub addr: SocketAddr,
-    pub pubkey: String,
-    pub source: PeerSource,
-    pub status: ConnectionStatus,
-    pub rtt_ms: Option<f64>,
-    pub last_seen: Instant,
-}
-
-impl PeerRecord {
-    pub fn from_static(cfg: &StaticPeerConfig) -> Self {
-        let agent_id = canonical_agent_id(cfg.agent_id.as_str());
-        Self {
-            agent_id,
-            addr: cfg.addr,
-            pubkey: cfg.pubkey.clone(),
-            source: PeerSource::Static,
-            status: ConnectionStatus::Discovered,
-            rtt_ms: None,
-            last_seen: Instant::now(),
-        }
-    }
-
-    pub fn from_cached(peer: &KnownPeer) -> Self {
-        let agent_id = canonical_agent_id(peer.agent_id.as_str());
-        Self {
-            agent_id,
-            addr: peer.addr,
-            pubkey: peer.pubkey.clone(),
-            source: PeerSource::Cached,
-            status: ConnectionStatus::Discovered,
-            rtt_ms: None,
-            last_seen: Instant::now(),
-        }
-    }
-}
-
 #[derive(Debug, Clone)]
 pub struct PeerTable {
     inner: Arc<RwLock<HashMap<AgentId, PeerRecord>>>,
     pubkeys: PubkeyMap,
+    revoked: RevokedKeys,
+    blocked: RevokedKeys,
+    stale_policy: Arc<StdRwLock<StalePolicy>>,
 }
 
 impl Default for PeerTable {
@@ -96,14 +58,97 @@ impl PeerTable {
         Self {
             inner: Arc::new(RwLock::new(HashMap::new())),
             pubkeys: Arc::new(StdRwLock::new(HashMap::new())),
+            revoked: Arc::new(StdRwLock::new(HashSet::new())),
+            blocked: Arc::new(StdRwLock::new(HashSet::new())),
+            stale_policy: Arc::new(StdRwLock::new(StalePolicy::default())),
+        }
+    }
+
+    pub fn stale_policy(&self) -> StalePolicy {
+        match self.stale_policy.read() {
+            Ok(policy) => *policy,
+            Err(poisoned) => *poisoned.into_inner(),
         }
     }
 
+    /// Replace the TTLs applied by subsequent [`remove_stale`](Self::remove_stale) calls.
+    pub fn set_stale_policy(&self, policy: StalePolicy) {
+        match self.stale_policy.write() {
+            Ok(mut current) => *current = policy,
+            Err(poisoned) => *poisoned.into_inner() = policy,
+        }
+    }
+
+    /// Time left before `peer` expires unless it is seen again; `None` when
+    /// its source never expires.
+    pub fn expires_in(&self, peer: &PeerRecord) -> Option<Duration> {
+        let ttl = self.stale_policy().ttl(peer.source)?;
+        Some(ttl.saturating_sub(peer.last_seen.elapsed()))
+    }
+
     /// Returns the sync-safe pubkey map for sharing with TLS verifiers.
     pub fn pubkey_map(&self) -> PubkeyMap {
         self.pubkeys.clone()
     }
 
+    /// Whether `pubkey` is on the revocation list.
+    pub fn is_revoked(&self, pubkey: &str) -> bool {
+        match self.revoked.read() {
+            Ok(revoked) => revoked.contains(pubkey),
+            Err(poisoned) => poisoned.into_inner().contains(pubkey),
+        }
+    }
+
+    /// Snapshot of the revoked public keys.
+    pub fn revoked_keys(&self) -> HashSet<String> {
+        match self.revoked.read() {
+            Ok(revoked) => revoked.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        }
+    }
+
+    /// Replace the revocation list and drop every peer holding a revoked key.
+    /// Returns the removed peers.
+    pub async fn set_revoked(&self, keys: HashSet<String>) -> Vec<PeerRecord> {
+        match self.revoked.write() {
+            Ok(mut revoked) => *revoked = keys,
+            Err(poisoned) => *poisoned.into_inner() = keys,
+        }
+        self.remove_refused().await
+    }
+
+    /// Add `pubkey` to the revocation list and drop any peer holding it.
+    /// Returns the removed peers.
+    pub async fn revoke(&self, pubkey: &str) -> Vec<PeerRecord> {
+        match self.revoked.write() {
+            Ok(mut revoked) => revoked.insert(pubkey.to_string()),
+            Err(poisoned) => poisoned.into_inner().insert(pubkey.to_string()),
+        };
+        self.remove_refused().await
+    }
+
+    /// Drop every peer whose key is revoked or blocked.
+    async fn remove_refused(&self) -> Vec<PeerRecord> {
+        let mut table = self.inner.write().await;
+        let refused: Vec<AgentId> = table
+            .values()
+            .filter(|p| self.is_refused(&p.pubkey))
+            .map(|p| p.agent_id.clone())
+            .collect();
+        let removed: Vec<PeerRecord> = refused.iter().filter_map(|id| table.remove(id)).collect();
+        if !removed.is_empty() {
+            let mut map = self.pubkeys_write_guard("remove_refused");
+            for peer in &removed {
+                map.remove(peer.agent_id.as_str());
+                info!(
+                    agent_id = peer.agent_id.as_str(),
+                    "removed peer with revoked or blocked key"
+                );
+            }
+        }
+        removed
+    }
+
     fn pubkeys_write_guard(
         &self,
         operation: &'static str,
@@ -142,7 +187,13 @@ impl PeerTable {
 
     pub async fn upsert_discovered(&self, agent_id: AgentId, addr: SocketAddr, pubkey: String) {
         let agent_id = canonical_agent_id(agent_id.as_str());
+        // Checked under the table lock so a concurrent `revoke` or `block`
+        // cannot miss a peer inserted between the check and the insert.
         let mut table = self.inner.write().await;
+        if self.is_refused(&pubkey) {
+            debug!(agent_id = agent_id.as_str(), %addr, "skipping discovered peer; key is revoked or blocked");
+            return;
+        }
         // O1: block insertion when a static peer already occupies the address
         let static_conflict = table
             .values()
@@ -170,6 +221,9 @@ impl PeerTable {
                 status: ConnectionStatus::Discovered,
                 rtt_ms: None,
                 last_seen: Instant::now(),
+                first_seen_unix_ms: crate::message::now_millis(),
+                traffic: PeerTraffic::default(),
+                software: None,
             });
         let mut map = self.pubkeys_write_guard("upsert_discovered");
         for id in &evicted {
@@ -182,6 +236,13 @@ impl PeerTable {
     pub async fn upsert_static(&self, cfg: &StaticPeerConfig) {
         let agent_id = canonical_agent_id(cfg.agent_id.as_str());
         let mut table = self.inner.write().await;
+        if self.is_refused(&cfg.pubkey) {
+            warn!(
+                agent_id = agent_id.as_str(),
+                "skipping static peer; key is revoked or blocked"
+            );
+            return;
+        }
         // O1: static peers are authoritative; evict any peer at the same address
         let evicted: Vec<AgentId> = table
             .values()
@@ -191,7 +252,12 @@ impl PeerTable {
         for id in &evicted {
             table.remove(id.as_str());
         }
-        table.insert(agent_id.clone(), PeerRecord::from_static(cfg));
+        let mut record = PeerRecord::from_static(cfg);
+        if let Some(existing) = table.get(agent_id.as_str()) {
+            record.first_seen_unix_ms = existing.first_seen_unix_ms;
+            record.traffic = existing.traffic.clone();
+        }
+        table.insert(agent_id.clone(), record);
         let mut map = self.pubkeys_write_guard("upsert_static");
         for id in &evicted {
             map.remove(id.as_str());
@@ -229,6 +295,13 @@ impl PeerTable {
     pub async fn upsert_cached(&self, peer: &KnownPeer) {
         let agent_id = canonical_agent_id(peer.agent_id.as_str());
         let mut table = self.inner.write().await;
+        if self.is_refused(&peer.pubkey) {
+            debug!(
+                agent_id = agent_id.as_str(),
+                "skipping cached peer; key is revoked or blocked"
+            );
+            return;
+        }
         // O1: block insertion when a static peer already occupies the address
         let static_conflict = table.values().any(|p| {
             p.addr == peer.addr && p.agent_id != agent_id && p.source == PeerSource::Static
@@ -282,12 +355,14 @@ impl PeerTable {
         }
     }
 
+    /// A successful connection counts as seeing the peer.
     pub async fn set_connected(&self, agent_id: &str, rtt_ms: Option<f64>) {
         let agent_id = canonical_agent_id(agent_id);
         let mut table = self.inner.write().await;
         if let Some(peer) = table.get_mut(agent_id.as_str()) {
             peer.status = ConnectionStatus::Connected;
             peer.rtt_ms = rtt_ms;
+            peer.last_seen = Instant::now();
         }
     }
 
@@ -300,6 +375,43 @@ impl PeerTable {
         }
     }
 
+    /// Mark a connected peer as degraded. Returns `true` on a transition.
+    pub async fn mark_degraded(&self, agent_id: &str) -> bool {
+        self.transition(
+            agent_id,
+            ConnectionStatus::Connected,
+            ConnectionStatus::Degraded,
+        )
+        .await
+    }
+
+    /// Return a degraded peer to connected. Returns `true` on a transition.
+    pub async fn clear_degraded(&self, agent_id: &str) -> bool {
+        self.transition(
+            agent_id,
+            ConnectionStatus::Degraded,
+            ConnectionStatus::Connected,
+        )
+        .await
+    }
+
+    async fn transition(
+        &self,
+        agent_id: &str,
+        from: ConnectionStatus,
+        to: ConnectionStatus,
+    ) -> bool {
+        let agent_id = canonical_agent_id(agent_id);
+        let mut table = self.inner.write().await;
+        match table.get_mut(agent_id.as_str()) {
+            Some(peer) if peer.status == from => {
+                peer.status = to;
+                true
+            }
+            _ => false,
+        }
+    }
+
     pub async fn set_rtt(&self, agent_id: &str, rtt_ms: f64) {
         let agent_id = canonical_agent_id(agent_id);
         let mut table = self.inner.write().await;
@@ -316,12 +428,19 @@ impl PeerTable {
         }
     }
 
-    pub async fn remove_stale(&self, ttl: Duration) -> Vec<AgentId> {
+    /// Remove peers unseen for longer than the [`StalePolicy`] TTL of their
+    /// source. Returns the removed agent IDs.
+    pub async fn remove_stale(&self) -> Vec<AgentId> {
+        let policy = self.stale_policy();
         let mut table = self.inner.write().await;
         let now = Instant::now();
         let stale: Vec<AgentId> = table
             .values()
-            .filter(|p| p.source == PeerSource::Discovered && now.duration_since(p.last_seen) > ttl)
+            .filter(|p| {
+                policy
+                    .ttl(p.source)
+                    .is_some_and(|ttl| now.duration_since(p.last_seen) > ttl)
+            })
             .map(|p| p.agent_id.clone())
             .collect();
         for id in &stale {
@@ -345,9 +464,10 @@ impl PeerTable {
             .collect()
     }
 
+    /// Non-static peers in agent ID order, ready for `known_peers.json`.
     pub async fn to_known_peers(&self) -> Vec<KnownPeer> {
         let table = self.inner.read().await;
-        table
+        let mut peers: Vec<KnownPeer> = table
             .values()
             .filter(|peer| peer.source != PeerSource::Static)
             .map(|peer| KnownPeer {
@@ -355,13 +475,16 @@ impl PeerTable {
                 addr: peer.addr,
                 pubkey: peer.pubkey.clone(),
                 last_seen_unix_ms: crate::message::now_millis(),
+                first_seen_unix_ms: Some(peer.first_seen_unix_ms),
                 source: match peer.source {
                     PeerSource::Static => KnownPeerSource::Static,
                     PeerSource::Discovered => KnownPeerSource::Discovered,
                     PeerSource::Cached => KnownPeerSource::Cached,
                 },
             })
-            .collect()
+            .collect();
+        peers.sort_by(|a, b| a.agent_id.cmp(&b.agent_id));
+        peers
     }
 }
 
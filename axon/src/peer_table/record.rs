@@ -0,0 +1,86 @@
+use std::net::SocketAddr;
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+
+use super::{PeerSoftware, PeerTraffic, canonical_agent_id};
+use crate::config::{KnownPeer, StaticPeerConfig};
+use crate::message::{AgentId, now_millis};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PeerSource {
+    Static,
+    Discovered,
+    Cached,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConnectionStatus {
+    Discovered,
+    Connecting,
+    Connected,
+    /// Connected, but the peer has missed heartbeats.
+    Degraded,
+    Disconnected,
+}
+
+impl ConnectionStatus {
+    /// Whether the peer has a live connection, healthy or not.
+    pub fn is_connected(self) -> bool {
+        matches!(self, Self::Connected | Self::Degraded)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PeerRecord {
+    pub agent_id: AgentId,
+    pub addr: SocketAddr,
+    pub pubkey: String,
+    pub source: PeerSource,
+    pub status: ConnectionStatus,
+    pub rtt_ms: Option<f64>,
+    pub last_seen: Instant,
+    /// Unix ms when this daemon first learned of the peer; carried across
+    /// restarts through `known_peers.json`.
+    pub first_seen_unix_ms: u64,
+    pub traffic: PeerTraffic,
+    /// Daemon version and features from the peer's latest heartbeat; kept
+    /// across reconnects.
+    pub software: Option<PeerSoftware>,
+}
+
+impl PeerRecord {
+    pub fn from_static(cfg: &StaticPeerConfig) -> Self {
+        let agent_id = canonical_agent_id(cfg.agent_id.as_str());
+        Self {
+            agent_id,
+            addr: cfg.addr,
+            pubkey: cfg.pubkey.clone(),
+            source: PeerSource::Static,
+            status: ConnectionStatus::Discovered,
+            rtt_ms: None,
+            last_seen: Instant::now(),
+            first_seen_unix_ms: now_millis(),
+            traffic: PeerTraffic::default(),
+            software: None,
+        }
+    }
+
+    pub fn from_cached(peer: &KnownPeer) -> Self {
+        let agent_id = canonical_agent_id(peer.agent_id.as_str());
+        Self {
+            agent_id,
+            addr: peer.addr,
+            pubkey: peer.pubkey.clone(),
+            source: PeerSource::Cached,
+            status: ConnectionStatus::Discovered,
+            rtt_ms: None,
+            last_seen: Instant::now(),
+            first_seen_unix_ms: peer.first_seen_unix_ms.unwrap_or_else(now_millis),
+            traffic: PeerTraffic::default(),
+            software: None,
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use serde::{Deserialize, Serialize};
+
+use super::{PeerTable, canonical_agent_id};
+
+/// The daemon software a peer reports in its `heartbeat` payload.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PeerSoftware {
+    /// Semantic version of the peer's daemon.
+    pub version: String,
+    /// Daemon-level features the peer supports.
+    #[serde(default)]
+    pub features: Vec<String>,
+}
+
+/// How a peer's version relates to ours under semver: a different major
+/// version, or a different minor version while the major is 0, is
+/// incompatible.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VersionCompatibility {
+    Compatible,
+    /// Incompatible and older than ours.
+    Older,
+    /// Incompatible and newer than ours.
+    Newer,
+    /// Either version is not `major.minor.patch`.
+    Unknown,
+}
+
+impl VersionCompatibility {
+    pub fn of(local: &str, remote: &str) -> Self {
+        let (Some(local), Some(remote)) = (parse_version(local), parse_version(remote)) else {
+            return Self::Unknown;
+        };
+        let series =
+            |(major, minor, _): (u64, u64, u64)| (major, if major == 0 { minor } else { 0 });
+        match series(remote).cmp(&series(local)) {
+            std::cmp::Ordering::Equal => Self::Compatible,
+            std::cmp::Ordering::Less => Self::Older,
+            std::cmp::Ordering::Greater => Self::Newer,
+        }
+    }
+
+    pub fn is_compatible(self) -> bool {
+        matches!(self, Self::Compatible | Self::Unknown)
+    }
+}
+
+/// `major.minor.patch`, ignoring any pre-release or build suffix.
+fn parse_version(version: &str) -> Option<(u64, u64, u64)> {
+    let core = version.split(['-', '+']).next()?;
+    let mut parts = core.split('.').map(|part| part.parse::<u64>().ok());
+    let version = (parts.next()??, parts.next()??, parts.next()??);
+    parts.next().is_none().then_some(version)
+}
+
+impl PeerTable {
+    /// Record what `agent_id` reported about its software. Returns `true`
+    /// when it differs from what was known.
+    pub async fn set_software(&self, agent_id: &str, software: PeerSoftware) -> bool {
+        let agent_id = canonical_agent_id(agent_id);
+        let mut table = self.inner.write().await;
+        match table.get_mut(agent_id.as_str()) {
+            Some(peer) if peer.software.as_ref() != Some(&software) => {
+                peer.software = Some(software);
+                true
+            }
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use std::time::Duration;
+
+use super::{PeerSource, STALE_TIMEOUT};
+
+/// How long peers of each source may go unseen before
+/// [`PeerTable::remove_stale`](super::PeerTable::remove_stale) drops them;
+/// `None` never expires. Static peers never expire.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StalePolicy {
+    pub discovered: Option<Duration>,
+    pub cached: Option<Duration>,
+}
+
+impl Default for StalePolicy {
+    fn default() -> Self {
+        Self {
+            discovered: Some(STALE_TIMEOUT),
+            cached: None,
+        }
+    }
+}
+
+impl StalePolicy {
+    pub fn ttl(&self, source: PeerSource) -> Option<Duration> {
+        match source {
+            PeerSource::Static => None,
+            PeerSource::Discovered => self.discovered,
+            PeerSource::Cached => self.cached,
+        }
+    }
+}
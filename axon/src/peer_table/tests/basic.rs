@@ -16,6 +16,7 @@ fn make_known_peer(id: &str) -> KnownPeer {
         addr: "127.0.0.1:7100".parse().expect("addr"),
         pubkey: "Zm9v".to_string(),
         last_seen_unix_ms: 12345,
+        first_seen_unix_ms: None,
         source: KnownPeerSource::Cached,
     }
 }
@@ -106,7 +107,7 @@ async fn stale_cleanup_removes_discovered_only() {
         }
     }
 
-    let removed = table.remove_stale(Duration::from_secs(60)).await;
+    let removed = table.remove_stale().await;
     assert_eq!(
         removed,
         vec![AgentId::from("bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb")]
@@ -138,7 +139,7 @@ async fn stale_cleanup_keeps_cached_peers() {
         }
     }
 
-    let removed = table.remove_stale(Duration::from_secs(60)).await;
+    let removed = table.remove_stale().await;
     assert!(removed.is_empty());
     assert!(table.get(id).await.is_some());
 }
@@ -179,6 +180,21 @@ async fn status_transitions() {
     assert_eq!(peer.status, ConnectionStatus::Connected);
     assert_eq!(peer.rtt_ms, Some(0.7));
 
+    assert!(
+        !table.clear_degraded(id).await,
+        "only degraded peers recover"
+    );
+    assert!(table.mark_degraded(id).await);
+    assert!(!table.mark_degraded(id).await, "already degraded");
+    let peer = table.get(id).await.unwrap();
+    assert_eq!(peer.status, ConnectionStatus::Degraded);
+    assert!(peer.status.is_connected());
+    assert!(table.clear_degraded(id).await);
+    assert_eq!(
+        table.get(id).await.unwrap().status,
+        ConnectionStatus::Connected
+    );
+
     table.set_disconnected(id).await;
     let peer = table.get(id).await.unwrap();
     assert_eq!(peer.status, ConnectionStatus::Disconnected);
@@ -353,6 +369,7 @@ async fn to_known_peers_skips_static_peers() {
             addr: "127.0.0.1:7102".parse().expect("addr"),
             pubkey: "Zm9v".to_string(),
             last_seen_unix_ms: 12345,
+            first_seen_unix_ms: None,
             source: KnownPeerSource::Cached,
         })
         .await;
@@ -363,3 +380,79 @@ async fn to_known_peers_skips_static_peers() {
     assert!(ids.contains("ed25519.bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb"));
     assert!(ids.contains("ed25519.cccccccccccccccccccccccccccccccc"));
 }
+
+#[tokio::test]
+async fn first_seen_survives_cache_roundtrip_and_static_refresh() {
+    let table = PeerTable::new();
+    let mut cached = make_known_peer("ed25519.bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb");
+    cached.first_seen_unix_ms = Some(777);
+    table.upsert_cached(&cached).await;
+    table
+        .upsert_discovered(cached.agent_id.clone(), cached.addr, cached.pubkey.clone())
+        .await;
+
+    let known = table.to_known_peers().await;
+    assert_eq!(known[0].first_seen_unix_ms, Some(777));
+
+    let statics = PeerTable::new();
+    let cfg = make_static_cfg("ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa");
+    statics.upsert_static(&cfg).await;
+    let first = statics.get(cfg.agent_id.as_str()).await.unwrap();
+    statics.upsert_static(&cfg).await;
+    let again = statics.get(cfg.agent_id.as_str()).await.unwrap();
+    assert_eq!(again.first_seen_unix_ms, first.first_seen_unix_ms);
+}
+
+#[tokio::test]
+async fn revoked_keys_are_never_inserted() {
+    let table = PeerTable::new();
+    table.revoke("Zm9v").await;
+
+    table
+        .upsert_static(&make_static_cfg("ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa"))
+        .await;
+    table
+        .upsert_cached(&make_known_peer("ed25519.bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb"))
+        .await;
+    table
+        .upsert_discovered(
+            "ed25519.cccccccccccccccccccccccccccccccc".into(),
+            "127.0.0.1:7102".parse().unwrap(),
+            "Zm9v".to_string(),
+        )
+        .await;
+
+    assert!(table.list().await.is_empty());
+    assert!(table.pubkey_map().read().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn revoke_removes_peers_holding_the_key() {
+    let table = PeerTable::new();
+    table
+        .upsert_static(&make_static_cfg("ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa"))
+        .await;
+    table
+        .upsert_discovered(
+            "ed25519.bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb".into(),
+            "127.0.0.1:7101".parse().unwrap(),
+            "YmFy".to_string(),
+        )
+        .await;
+
+    let removed = table.revoke("Zm9v").await;
+    assert_eq!(removed.len(), 1);
+    assert_eq!(
+        removed[0].agent_id,
+        "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa"
+    );
+    assert!(table.is_revoked("Zm9v"));
+    assert!(!table.is_revoked("YmFy"));
+    assert_eq!(table.list().await.len(), 1);
+
+    // Replacing the list lifts the old revocation.
+    let removed = table.set_revoked(["YmFy".to_string()].into()).await;
+    assert_eq!(removed.len(), 1);
+    assert!(!table.is_revoked("Zm9v"));
+    assert!(table.list().await.is_empty());
+}
@@ -0,0 +1,63 @@
+use super::super::*;
+
+const BLOCKED: &str = "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+
+fn static_peer() -> StaticPeerConfig {
+    StaticPeerConfig {
+        agent_id: BLOCKED.into(),
+        addr: "127.0.0.1:7100".parse().expect("addr"),
+        pubkey: "Zm9v".to_string(),
+    }
+}
+
+#[tokio::test]
+async fn blocked_peers_are_removed_and_kept_out_until_unblocked() {
+    let table = PeerTable::new();
+    table.upsert_static(&static_peer()).await;
+    table.set_revoked(["YmFy".to_string()].into()).await;
+
+    let removed = table.block("Zm9v").await;
+    assert_eq!(removed.len(), 1);
+    assert!(table.is_blocked("Zm9v"));
+    assert!(table.is_refused("Zm9v"));
+    assert!(!table.is_revoked("Zm9v"));
+    assert!(!table.is_blocked("YmFy"));
+    assert!(table.is_refused("YmFy"));
+
+    table.upsert_static(&static_peer()).await;
+    table
+        .upsert_discovered(
+            BLOCKED.into(),
+            "127.0.0.1:7100".parse().unwrap(),
+            "Zm9v".to_string(),
+        )
+        .await;
+    assert!(table.list().await.is_empty());
+
+    assert!(table.unblock("Zm9v"));
+    assert!(!table.unblock("Zm9v"));
+    table.upsert_static(&static_peer()).await;
+    assert_eq!(table.list().await.len(), 1);
+}
+
+#[tokio::test]
+async fn blocked_agent_id_refuses_the_key_it_derives_from() {
+    let pubkey = "BwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwc=";
+    let agent_id = crate::peer_token::derive_agent_id_from_pubkey_base64(pubkey).expect("id");
+    let table = PeerTable::new();
+    table
+        .upsert_discovered(
+            agent_id.clone(),
+            "127.0.0.1:7100".parse().unwrap(),
+            pubkey.to_string(),
+        )
+        .await;
+
+    let removed = table.block(agent_id.as_str()).await;
+    assert_eq!(removed.len(), 1);
+    assert!(table.is_blocked(pubkey));
+    assert!(!table.is_blocked("Zm9v"));
+
+    assert!(table.unblock(agent_id.as_str()));
+    assert!(!table.is_blocked(pubkey));
+}
@@ -14,6 +14,7 @@ async fn upsert_discovered_evicts_stale_cached_at_same_addr() {
             addr,
             pubkey: "b2xk".to_string(),
             last_seen_unix_ms: 1000,
+            first_seen_unix_ms: None,
             source: KnownPeerSource::Cached,
         })
         .await;
@@ -82,6 +83,7 @@ async fn upsert_cached_evicts_stale_discovered_at_same_addr() {
             addr,
             pubkey: "bmV3".to_string(),
             last_seen_unix_ms: 2000,
+            first_seen_unix_ms: None,
             source: KnownPeerSource::Cached,
         })
         .await;
@@ -131,7 +133,7 @@ async fn stale_cleanup_boundary_exactly_at_ttl() {
         }
     }
 
-    let removed = table.remove_stale(ttl).await;
+    let removed = table.remove_stale().await;
     assert!(
         !removed.contains(&AgentId::from(id)),
         "peer within TTL should NOT be removed"
@@ -163,6 +165,9 @@ async fn evict_stale_at_addr_evicts_all_matching_peers() {
                     status: ConnectionStatus::Discovered,
                     rtt_ms: None,
                     last_seen: Instant::now(),
+                    first_seen_unix_ms: 0,
+                    traffic: Default::default(),
+                    software: None,
                 },
             );
         }
@@ -244,6 +249,7 @@ async fn upsert_cached_blocked_by_static_at_same_addr() {
             addr,
             pubkey: "bmV3".to_string(),
             last_seen_unix_ms: 5000,
+            first_seen_unix_ms: None,
             source: KnownPeerSource::Cached,
         })
         .await;
@@ -272,6 +278,7 @@ async fn upsert_static_evicts_all_at_same_addr() {
             addr,
             pubkey: "key_b".to_string(),
             last_seen_unix_ms: 1000,
+            first_seen_unix_ms: None,
             source: KnownPeerSource::Cached,
         })
         .await;
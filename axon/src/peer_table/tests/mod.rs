@@ -1,3 +1,7 @@
 mod basic;
+mod blocklist;
 mod eviction;
 mod proptest;
+mod software;
+mod stale;
+mod traffic;
@@ -23,6 +23,7 @@ fn arb_peer_op() -> impl Strategy<Value = PeerOp> {
                 ConnectionStatus::Discovered,
                 ConnectionStatus::Connecting,
                 ConnectionStatus::Connected,
+                ConnectionStatus::Degraded,
                 ConnectionStatus::Disconnected,
             ])
         )
@@ -0,0 +1,55 @@
+use super::super::*;
+
+const ID: &str = "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+
+#[test]
+fn version_compatibility_follows_semver() {
+    use VersionCompatibility::*;
+    for (local, remote, expected) in [
+        ("0.7.2", "0.7.0", Compatible),
+        ("0.7.2", "0.7.9-rc.1", Compatible),
+        ("0.7.2", "0.6.4", Older),
+        ("0.7.2", "0.8.0", Newer),
+        ("1.2.0", "1.9.3", Compatible),
+        ("1.2.0", "0.9.0", Older),
+        ("1.2.0", "2.0.0", Newer),
+        ("0.7.2", "dev", Unknown),
+        ("0.7.2", "0.7", Unknown),
+    ] {
+        assert_eq!(
+            VersionCompatibility::of(local, remote),
+            expected,
+            "{local} vs {remote}"
+        );
+    }
+    assert!(Unknown.is_compatible());
+    assert!(!Older.is_compatible());
+}
+
+#[tokio::test]
+async fn set_software_reports_changes_only() {
+    let table = PeerTable::new();
+    let software = |version: &str| PeerSoftware {
+        version: version.to_string(),
+        features: vec!["heartbeat".to_string()],
+    };
+    assert!(
+        !table.set_software(ID, software("0.7.2")).await,
+        "unknown peer"
+    );
+
+    table
+        .upsert_discovered(
+            ID.into(),
+            "127.0.0.1:7100".parse().unwrap(),
+            "Zm9v".to_string(),
+        )
+        .await;
+    assert!(table.set_software(ID, software("0.7.2")).await);
+    assert!(!table.set_software(ID, software("0.7.2")).await);
+    assert!(table.set_software(ID, software("0.7.3")).await);
+    assert_eq!(
+        table.get(ID).await.unwrap().software,
+        Some(software("0.7.3"))
+    );
+}
@@ -0,0 +1,63 @@
+use super::super::*;
+use crate::config::KnownPeerSource;
+use std::time::Duration;
+
+fn make_known_peer(id: &str) -> KnownPeer {
+    KnownPeer {
+        agent_id: id.into(),
+        addr: "127.0.0.1:7100".parse().expect("addr"),
+        pubkey: "Zm9v".to_string(),
+        last_seen_unix_ms: 12345,
+        first_seen_unix_ms: None,
+        source: KnownPeerSource::Cached,
+    }
+}
+
+#[tokio::test]
+async fn stale_policy_applies_per_source() {
+    let table = PeerTable::new();
+    table.set_stale_policy(StalePolicy {
+        discovered: None,
+        cached: Some(Duration::from_secs(30)),
+    });
+    let cached = "cccccccccccccccccccccccccccccccc";
+    let discovered = "dddddddddddddddddddddddddddddddd";
+    table.upsert_cached(&make_known_peer(cached)).await;
+    table
+        .upsert_discovered(
+            discovered.into(),
+            "127.0.0.1:7101".parse().unwrap(),
+            "YmFy".to_string(),
+        )
+        .await;
+    {
+        let mut inner = table.inner.write().await;
+        for peer in inner.values_mut() {
+            peer.last_seen = Instant::now() - Duration::from_secs(120);
+        }
+    }
+
+    let peer = table.get(discovered).await.unwrap();
+    assert_eq!(table.expires_in(&peer), None);
+    let peer = table.get(cached).await.unwrap();
+    assert_eq!(table.expires_in(&peer), Some(Duration::ZERO));
+
+    let removed = table.remove_stale().await;
+    assert_eq!(removed, vec![AgentId::from(cached)]);
+    assert!(table.get(discovered).await.is_some());
+}
+
+#[tokio::test]
+async fn connecting_refreshes_last_seen() {
+    let table = PeerTable::new();
+    let id = "cccccccccccccccccccccccccccccccc";
+    table.upsert_cached(&make_known_peer(id)).await;
+    {
+        let mut inner = table.inner.write().await;
+        inner.get_mut(id).unwrap().last_seen = Instant::now() - Duration::from_secs(120);
+    }
+
+    table.set_connected(id, None).await;
+    let peer = table.get(id).await.unwrap();
+    assert!(peer.last_seen.elapsed() < Duration::from_secs(1));
+}
@@ -0,0 +1,61 @@
+use super::super::*;
+use crate::message::{Envelope, MessageKind};
+use serde_json::json;
+
+const ID: &str = "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+
+fn envelope() -> Envelope {
+    Envelope::new(
+        "ed25519.bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb",
+        ID,
+        MessageKind::Message,
+        json!({"n": 1}),
+    )
+}
+
+#[tokio::test]
+async fn traffic_counts_messages_bytes_and_errors() {
+    let table = PeerTable::new();
+    table
+        .upsert_discovered(
+            ID.into(),
+            "127.0.0.1:7100".parse().unwrap(),
+            "Zm9v".to_string(),
+        )
+        .await;
+    let envelope = envelope();
+    let wire_len = envelope.wire_encode().unwrap().len() as u64;
+
+    table.record_sent(ID, &envelope).await;
+    table.record_sent(ID, &envelope).await;
+    table.record_received(ID, &envelope).await;
+    table.record_error(ID, "timeout").await;
+
+    let traffic = table.get(ID).await.unwrap().traffic;
+    assert_eq!(traffic.messages_sent, 2);
+    assert_eq!(traffic.messages_received, 1);
+    assert_eq!(traffic.bytes_sent, 2 * wire_len);
+    assert_eq!(traffic.bytes_received, wire_len);
+    assert_eq!(traffic.errors, 1);
+    assert_eq!(traffic.last_error.as_deref(), Some("timeout"));
+    assert!(traffic.last_message_at_ms.is_some());
+}
+
+#[tokio::test]
+async fn traffic_survives_static_refresh_and_ignores_unknown_peers() {
+    let table = PeerTable::new();
+    let cfg = StaticPeerConfig {
+        agent_id: ID.into(),
+        addr: "127.0.0.1:7100".parse().unwrap(),
+        pubkey: "Zm9v".to_string(),
+    };
+    table.upsert_static(&cfg).await;
+    table.record_sent(ID, &envelope()).await;
+    table
+        .record_sent("ed25519.cccccccccccccccccccccccccccccccc", &envelope())
+        .await;
+
+    table.upsert_static(&cfg).await;
+    assert_eq!(table.get(ID).await.unwrap().traffic.messages_sent, 1);
+    assert_eq!(table.list().await.len(), 1);
+}
@@ -0,0 +1,57 @@
+use serde::{Deserialize, Serialize};
+
+use super::{PeerTable, canonical_agent_id};
+use crate::message::{Envelope, now_millis};
+
+/// Traffic with one peer while it has been in the peer table, reported by
+/// `peers` and `whois`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PeerTraffic {
+    pub messages_sent: u64,
+    pub messages_received: u64,
+    /// Wire-encoded envelope bytes.
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+    /// Sends to the peer that failed or timed out.
+    pub errors: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    /// Unix ms of the last envelope sent or received.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_message_at_ms: Option<u64>,
+}
+
+fn wire_len(envelope: &Envelope) -> u64 {
+    envelope.wire_encode().map_or(0, |bytes| bytes.len() as u64)
+}
+
+impl PeerTable {
+    pub async fn record_sent(&self, agent_id: &str, envelope: &Envelope) {
+        let agent_id = canonical_agent_id(agent_id);
+        let mut table = self.inner.write().await;
+        if let Some(peer) = table.get_mut(agent_id.as_str()) {
+            peer.traffic.messages_sent += 1;
+            peer.traffic.bytes_sent += wire_len(envelope);
+            peer.traffic.last_message_at_ms = Some(now_millis());
+        }
+    }
+
+    pub async fn record_received(&self, agent_id: &str, envelope: &Envelope) {
+        let agent_id = canonical_agent_id(agent_id);
+        let mut table = self.inner.write().await;
+        if let Some(peer) = table.get_mut(agent_id.as_str()) {
+            peer.traffic.messages_received += 1;
+            peer.traffic.bytes_received += wire_len(envelope);
+            peer.traffic.last_message_at_ms = Some(now_millis());
+        }
+    }
+
+    pub async fn record_error(&self, agent_id: &str, error: impl Into<String>) {
+        let agent_id = canonical_agent_id(agent_id);
+        let mut table = self.inner.write().await;
+        if let Some(peer) = table.get_mut(agent_id.as_str()) {
+            peer.traffic.errors += 1;
+            peer.traffic.last_error = Some(error.into());
+        }
+    }
+}
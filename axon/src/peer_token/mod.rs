@@ -1,19 +1,54 @@
-use anyhow::{Context, Result, anyhow};
+use anyhow::{Context, Result, anyhow, bail};
 use base64::{
     Engine as _,
     engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD},
 };
+use ed25519_dalek::{Signature, VerifyingKey};
 use sha2::{Digest, Sha256};
 
+use crate::identity::Identity;
 use crate::message::AgentId;
 
 const SCHEME_PREFIX: &str = "axon://";
+/// Domain separator for token signatures, so they cannot be replayed as
+/// signatures over anything else.
+const SIGNATURE_CONTEXT: &str = "axon-peer-token-v1";
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct DecodedPeerToken {
     pub pubkey: String,
     pub addr: String,
     pub agent_id: AgentId,
+    /// Unix seconds after which the token must not be used.
+    pub expires_at: Option<u64>,
+    /// Whether the token carried a valid signature by `pubkey`.
+    pub signed: bool,
+}
+
+impl DecodedPeerToken {
+    pub fn ensure_unexpired(&self, now_unix_secs: u64) -> Result<()> {
+        match self.expires_at {
+            Some(expires_at) if now_unix_secs >= expires_at => {
+                bail!(
+                    "peer token expired at {expires_at} (unix seconds); ask the peer for a new one"
+                )
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Refuse an unsigned token. Stripping the query from a signed token
+    /// leaves a valid unsigned one, so accepting it would skip both the
+    /// expiry and tamper checks.
+    pub fn ensure_signed(&self) -> Result<()> {
+        if !self.signed {
+            bail!(
+                "peer token is unsigned, so its address and expiry cannot be checked; \
+                 ask the peer for a token from a current `axon identity`"
+            );
+        }
+        Ok(())
+    }
 }
 
 pub fn encode(pubkey_base64: &str, addr: &str) -> Result<String> {
@@ -26,11 +61,29 @@ pub fn encode(pubkey_base64: &str, addr: &str) -> Result<String> {
     Ok(format!("{SCHEME_PREFIX}{key_url}@{}", addr.trim()))
 }
 
+/// Encode a token for `identity`, signed by it and optionally expiring at
+/// `expires_at` (unix seconds):
+/// `axon://<pubkey>@<addr>?exp=<unix_secs>&sig=<base64url>`.
+pub fn encode_signed(identity: &Identity, addr: &str, expires_at: Option<u64>) -> Result<String> {
+    let unsigned = encode(identity.public_key_base64(), addr)?;
+    let body = &unsigned[SCHEME_PREFIX.len()..];
+    let signature = identity.sign(&signed_message(body, expires_at));
+    let sig = URL_SAFE_NO_PAD.encode(signature.to_bytes());
+    Ok(match expires_at {
+        Some(exp) => format!("{unsigned}?exp={exp}&sig={sig}"),
+        None => format!("{unsigned}?sig={sig}"),
+    })
+}
+
 pub fn decode(token: &str) -> Result<DecodedPeerToken> {
     let rest = token
         .strip_prefix(SCHEME_PREFIX)
         .ok_or_else(|| anyhow!("peer token must start with '{SCHEME_PREFIX}'"))?;
-    let (pubkey_url, addr_raw) = rest
+    let (body, query) = match rest.split_once('?') {
+        Some((body, query)) => (body, Some(query)),
+        None => (rest, None),
+    };
+    let (pubkey_url, addr_raw) = body
         .split_once('@')
         .ok_or_else(|| anyhow!("peer token must contain '@' between pubkey and addr"))?;
     if pubkey_url.is_empty() {
@@ -43,13 +96,67 @@ pub fn decode(token: &str) -> Result<DecodedPeerToken> {
         .context("peer token pubkey is not valid base64url")?;
     let (pubkey, agent_id) = decode_pubkey_bytes(&key_bytes)?;
 
+    let (expires_at, signed) = match query {
+        None => (None, false),
+        Some(query) => {
+            let (expires_at, signature) = parse_query(query)?;
+            let key: [u8; 32] = key_bytes.as_slice().try_into().expect("length checked");
+            VerifyingKey::from_bytes(&key)
+                .and_then(|key| key.verify_strict(&signed_message(body, expires_at), &signature))
+                .map_err(|_| anyhow!("peer token signature is invalid; the token was altered"))?;
+            (expires_at, true)
+        }
+    };
+
     Ok(DecodedPeerToken {
         pubkey,
         addr: addr_raw.trim().to_string(),
         agent_id,
+        expires_at,
+        signed,
     })
 }
 
+/// Bytes signed for a token: context, `<pubkey>@<addr>`, and expiry.
+fn signed_message(body: &str, expires_at: Option<u64>) -> Vec<u8> {
+    let exp = expires_at.map(|exp| exp.to_string()).unwrap_or_default();
+    format!("{SIGNATURE_CONTEXT}\n{body}\n{exp}").into_bytes()
+}
+
+/// `exp` and `sig` from a token query. An expiry is only trusted when signed,
+/// so `sig` is required and other parameters are rejected.
+fn parse_query(query: &str) -> Result<(Option<u64>, Signature)> {
+    let mut expires_at = None;
+    let mut signature = None;
+    for param in query.split('&') {
+        let (key, value) = param
+            .split_once('=')
+            .ok_or_else(|| anyhow!("peer token parameter '{param}' must be key=value"))?;
+        match key {
+            "exp" if expires_at.is_none() => {
+                expires_at = Some(
+                    value
+                        .parse::<u64>()
+                        .with_context(|| format!("peer token has invalid exp '{value}'"))?,
+                );
+            }
+            "sig" if signature.is_none() => {
+                let bytes = URL_SAFE_NO_PAD
+                    .decode(value)
+                    .context("peer token sig is not valid base64url")?;
+                signature = Some(
+                    Signature::from_slice(&bytes)
+                        .map_err(|_| anyhow!("peer token sig must decode to 64 bytes"))?,
+                );
+            }
+            "exp" | "sig" => bail!("peer token repeats parameter '{key}'"),
+            _ => bail!("peer token has unknown parameter '{key}'"),
+        }
+    }
+    let signature = signature.ok_or_else(|| anyhow!("peer token parameters require a sig"))?;
+    Ok((expires_at, signature))
+}
+
 pub fn derive_agent_id_from_pubkey_base64(pubkey_base64: &str) -> Result<AgentId> {
     let key_bytes = STANDARD
         .decode(pubkey_base64.trim())
@@ -45,3 +45,95 @@ fn derive_agent_id_from_base64_rejects_wrong_length() {
     let err = derive_agent_id_from_pubkey_base64(&pubkey).expect_err("short key");
     assert!(err.to_string().contains("32 bytes"));
 }
+
+fn new_identity() -> (tempfile::TempDir, Identity) {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let paths = crate::config::AxonPaths::from_root(dir.path().to_path_buf());
+    let identity = Identity::load_or_generate(&paths).expect("identity");
+    (dir, identity)
+}
+
+#[test]
+fn signed_roundtrip_carries_expiry() {
+    let (_dir, identity) = new_identity();
+    let token = encode_signed(&identity, "127.0.0.1:7100", Some(1_900_000_000)).expect("encode");
+    assert!(token.contains("?exp=1900000000&sig="));
+
+    let decoded = decode(&token).expect("decode");
+    assert!(decoded.signed);
+    assert_eq!(decoded.expires_at, Some(1_900_000_000));
+    assert_eq!(decoded.agent_id.as_str(), identity.agent_id());
+    assert_eq!(decoded.addr, "127.0.0.1:7100");
+
+    let unexpiring =
+        decode(&encode_signed(&identity, "127.0.0.1:7100", None).expect("encode")).expect("decode");
+    assert!(unexpiring.signed);
+    assert_eq!(unexpiring.expires_at, None);
+    assert!(unexpiring.ensure_unexpired(u64::MAX).is_ok());
+}
+
+#[test]
+fn unsigned_tokens_still_decode() {
+    let token = encode(&STANDARD.encode([7u8; 32]), "127.0.0.1:7100").expect("encode");
+    let decoded = decode(&token).expect("decode");
+    assert!(!decoded.signed);
+    assert_eq!(decoded.expires_at, None);
+    let err = decoded.ensure_signed().unwrap_err();
+    assert!(err.to_string().contains("unsigned"), "{err}");
+}
+
+#[test]
+fn stripping_the_query_leaves_an_unsigned_token() {
+    let (_dir, identity) = new_identity();
+    let token = encode_signed(&identity, "127.0.0.1:7100", Some(1_000)).expect("encode");
+    let decoded = decode(&token).expect("decode");
+    assert!(decoded.ensure_signed().is_ok());
+
+    let (stripped, _query) = token.split_once('?').expect("query");
+    let decoded = decode(stripped).expect("decode");
+    assert!(decoded.ensure_signed().is_err());
+    assert!(decoded.ensure_unexpired(u64::MAX).is_ok(), "expiry is gone");
+}
+
+#[test]
+fn expiry_is_checked_against_now() {
+    let (_dir, identity) = new_identity();
+    let token = encode_signed(&identity, "127.0.0.1:7100", Some(1_000)).expect("encode");
+    let decoded = decode(&token).expect("decode");
+    assert!(decoded.ensure_unexpired(999).is_ok());
+    let err = decoded.ensure_unexpired(1_000).unwrap_err();
+    assert!(err.to_string().contains("expired"), "{err}");
+}
+
+#[test]
+fn tampering_breaks_the_signature() {
+    let (_dir, identity) = new_identity();
+    let token = encode_signed(&identity, "127.0.0.1:7100", Some(1_000)).expect("encode");
+
+    for tampered in [
+        token.replace("127.0.0.1:7100", "10.0.0.9:7100"),
+        token.replace("exp=1000", "exp=9999999999"),
+    ] {
+        let err = decode(&tampered).unwrap_err();
+        assert!(err.to_string().contains("signature is invalid"), "{err}");
+    }
+
+    let (_other_dir, other) = new_identity();
+    let foreign_key = URL_SAFE_NO_PAD.encode(other.verifying_key().to_bytes());
+    let own_key = URL_SAFE_NO_PAD.encode(identity.verifying_key().to_bytes());
+    let err = decode(&token.replace(&own_key, &foreign_key)).unwrap_err();
+    assert!(err.to_string().contains("signature is invalid"), "{err}");
+}
+
+#[test]
+fn query_requires_signature_and_known_parameters() {
+    let (_dir, identity) = new_identity();
+    let unsigned = encode(identity.public_key_base64(), "127.0.0.1:7100").expect("encode");
+
+    let err = decode(&format!("{unsigned}?exp=1000")).unwrap_err();
+    assert!(err.to_string().contains("require a sig"), "{err}");
+
+    let signed = encode_signed(&identity, "127.0.0.1:7100", None).expect("encode");
+    let err = decode(&format!("{signed}&via=chat")).unwrap_err();
+    assert!(err.to_string().contains("unknown parameter 'via'"), "{err}");
+}
@@ -0,0 +1,317 @@
+//! Deterministic multi-node network simulation (`sim` feature).
+//!
+//! [`run`] drives a scenario on a single-threaded tokio runtime whose clock
+//! starts paused: timers fire in virtual time, so a scenario spanning
+//! minutes of latency, timeouts, and reconnect backoff finishes in
+//! milliseconds. Nodes are [`MemoryTransport`]s on one seeded
+//! [`MemoryNetwork`], so code written against [`Transport`] runs unchanged.
+//! The same seed and scenario produce the same [`Trace`] on every run.
+//!
+//! ```
+//! use std::time::Duration;
+//! use axon::message::MessageKind;
+//! use axon::sim::{self, Fault, TraceKind};
+//! use serde_json::json;
+//!
+//! let trace = sim::run(7, |sim| async move {
+//!     let a = sim.add_node("a");
+//!     let b = sim.add_node("b");
+//!     sim.schedule(Duration::from_secs(5), Fault::partition(&a, &b));
+//!     a.send(&b, MessageKind::Message, json!({"n": 1})).await.unwrap();
+//!     sim.run_for(Duration::from_secs(10)).await;
+//!     assert!(a.send(&b, MessageKind::Message, json!({"n": 2})).await.is_err());
+//!     sim.trace()
+//! });
+//! assert!(matches!(trace.last().unwrap().kind, TraceKind::Disconnected { .. }));
+//! ```
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+use anyhow::Result;
+use serde_json::Value;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::time::Instant;
+
+use crate::message::{AgentId, Envelope, MessageKind};
+use crate::transport::{
+    ConnectionEvent, LinkConditions, MemoryNetwork, MemoryTransport, ResponseHandlerFn, Transport,
+};
+
+/// Run `scenario` on a fresh single-threaded runtime with a paused clock
+/// and a network seeded with `seed`, returning its output.
+pub fn run<F, Fut>(seed: u64, scenario: F) -> Fut::Output
+where
+    F: FnOnce(Simulation) -> Fut,
+    Fut: Future,
+{
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .start_paused(true)
+        .build()
+        .expect("failed to build simulation runtime");
+    runtime.block_on(async move { scenario(Simulation::new(seed)).await })
+}
+
+/// A change to the network, applied now or at a virtual time.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Fault {
+    /// Set the conditions for envelopes from one agent to another.
+    Link {
+        from: String,
+        to: String,
+        conditions: LinkConditions,
+    },
+    /// Drop everything between two agents and disconnect them.
+    Partition(String, String),
+    /// Restore the default conditions between two agents.
+    Heal(String, String),
+}
+
+impl Fault {
+    pub fn link(from: &SimNode, to: &SimNode, conditions: LinkConditions) -> Self {
+        Self::Link {
+            from: from.agent_id().to_string(),
+            to: to.agent_id().to_string(),
+            conditions,
+        }
+    }
+
+    pub fn partition(a: &SimNode, b: &SimNode) -> Self {
+        Self::Partition(a.agent_id().to_string(), b.agent_id().to_string())
+    }
+
+    pub fn heal(a: &SimNode, b: &SimNode) -> Self {
+        Self::Heal(a.agent_id().to_string(), b.agent_id().to_string())
+    }
+}
+
+/// Everything the simulated nodes observed, in order.
+pub type Trace = Vec<TraceEvent>;
+
+/// One observation, at virtual time `at` since the simulation started.
+/// Nodes are named as they were added.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraceEvent {
+    pub at: Duration,
+    pub node: String,
+    pub kind: TraceKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TraceKind {
+    /// A message or request reached the node's inbound subscribers.
+    /// Responses go straight back to the sender's `send` and are not
+    /// traced. Message IDs are random, so they are left out.
+    Received {
+        from: String,
+        kind: MessageKind,
+        payload: String,
+    },
+    Connected {
+        peer: String,
+    },
+    Disconnected {
+        peer: String,
+        reason: &'static str,
+    },
+    /// A fault was applied; `node` is empty.
+    Fault(Fault),
+}
+
+/// A simulated network of named nodes. Cloning shares the simulation.
+#[derive(Clone)]
+pub struct Simulation {
+    network: MemoryNetwork,
+    started: Instant,
+    /// Agent ID → node name.
+    names: Arc<Mutex<HashMap<String, String>>>,
+    trace: Arc<Mutex<Trace>>,
+}
+
+impl Simulation {
+    /// A simulation starting now. Use inside a runtime whose clock is
+    /// paused (`#[tokio::test(start_paused = true)]`); [`run`] sets one up.
+    pub fn new(seed: u64) -> Self {
+        Self {
+            network: MemoryNetwork::with_seed(seed),
+            started: Instant::now(),
+            names: Arc::default(),
+            trace: Arc::default(),
+        }
+    }
+
+    pub fn network(&self) -> &MemoryNetwork {
+        &self.network
+    }
+
+    /// Add a node that answers requests with the default `unhandled` error.
+    pub fn add_node(&self, name: &str) -> SimNode {
+        self.attach(name, None)
+    }
+
+    /// Add a node whose requests go to `handler`.
+    pub fn add_node_with_handler(&self, name: &str, handler: ResponseHandlerFn) -> SimNode {
+        self.attach(name, Some(handler))
+    }
+
+    fn attach(&self, name: &str, handler: Option<ResponseHandlerFn>) -> SimNode {
+        let agent_id = {
+            let mut names = lock(&self.names);
+            let agent_id = format!("ed25519.{:032x}", names.len() + 1);
+            names.insert(agent_id.clone(), name.to_string());
+            agent_id
+        };
+        let transport = self.network.attach(AgentId::from(agent_id), handler);
+        self.spawn_recorder(name, &transport);
+        SimNode {
+            name: name.to_string(),
+            transport,
+        }
+    }
+
+    /// Trace what `transport` receives until the simulation ends.
+    fn spawn_recorder(&self, name: &str, transport: &MemoryTransport) {
+        let mut inbound = transport.subscribe_inbound();
+        let mut events = transport.subscribe_connection_events();
+        let (sim, node) = (self.clone(), name.to_string());
+        tokio::spawn(async move {
+            loop {
+                let kind = tokio::select! {
+                    biased;
+                    event = events.recv() => match event {
+                        Ok(ConnectionEvent::Connected { agent_id }) => TraceKind::Connected {
+                            peer: sim.name_of(&agent_id),
+                        },
+                        Ok(ConnectionEvent::Disconnected { agent_id, reason }) => {
+                            TraceKind::Disconnected {
+                                peer: sim.name_of(&agent_id),
+                                reason,
+                            }
+                        }
+                        Err(RecvError::Lagged(_)) => continue,
+                        Err(RecvError::Closed) => return,
+                    },
+                    envelope = inbound.recv() => match envelope {
+                        Ok(envelope) => received(&sim, &envelope),
+                        Err(RecvError::Lagged(_)) => continue,
+                        Err(RecvError::Closed) => return,
+                    },
+                };
+                sim.record(&node, kind);
+            }
+        });
+    }
+
+    fn name_of(&self, agent_id: &str) -> String {
+        lock(&self.names)
+            .get(agent_id)
+            .cloned()
+            .unwrap_or_else(|| agent_id.to_string())
+    }
+
+    fn record(&self, node: &str, kind: TraceKind) {
+        lock(&self.trace).push(TraceEvent {
+            at: self.elapsed(),
+            node: node.to_string(),
+            kind,
+        });
+    }
+
+    /// Virtual time since the simulation started.
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// Let `duration` of virtual time pass while the nodes run.
+    pub async fn run_for(&self, duration: Duration) {
+        tokio::time::sleep(duration).await;
+    }
+
+    /// Apply `fault` now.
+    pub fn apply(&self, fault: Fault) {
+        match &fault {
+            Fault::Link {
+                from,
+                to,
+                conditions,
+            } => self.network.set_link(from, to, *conditions),
+            Fault::Partition(a, b) => self.network.partition(a, b),
+            Fault::Heal(a, b) => self.network.heal(a, b),
+        }
+        self.record("", TraceKind::Fault(fault));
+    }
+
+    /// Apply `fault` once `after` of virtual time has passed from now.
+    pub fn schedule(&self, after: Duration, fault: Fault) {
+        let sim = self.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(after).await;
+            sim.apply(fault);
+        });
+    }
+
+    /// Everything traced so far.
+    pub fn trace(&self) -> Trace {
+        lock(&self.trace).clone()
+    }
+}
+
+fn received(sim: &Simulation, envelope: &Envelope) -> TraceKind {
+    let from = envelope
+        .from
+        .as_ref()
+        .map(|from| sim.name_of(from.as_str()))
+        .unwrap_or_default();
+    TraceKind::Received {
+        from,
+        kind: envelope.kind,
+        payload: envelope.payload.get().to_string(),
+    }
+}
+
+/// One node of a [`Simulation`].
+#[derive(Clone)]
+pub struct SimNode {
+    name: String,
+    transport: MemoryTransport,
+}
+
+impl SimNode {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn agent_id(&self) -> &str {
+        self.transport.local_agent_id()
+    }
+
+    /// The node's transport, for code under test that takes a
+    /// [`Transport`].
+    pub fn transport(&self) -> &MemoryTransport {
+        &self.transport
+    }
+
+    /// Send `payload` to `to`; requests return the reply.
+    pub async fn send(
+        &self,
+        to: &SimNode,
+        kind: MessageKind,
+        payload: Value,
+    ) -> Result<Option<Envelope>> {
+        let envelope = Envelope::new(self.agent_id(), to.agent_id(), kind, payload);
+        self.transport
+            .send(&to.transport.peer_record(), envelope)
+            .await
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+#[cfg(test)]
+#[path = "tests.rs"]
+mod tests;
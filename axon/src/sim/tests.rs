@@ -0,0 +1,152 @@
+use serde_json::json;
+
+use super::*;
+
+fn echo_handler() -> ResponseHandlerFn {
+    Arc::new(|request: Arc<Envelope>| {
+        Box::pin(async move {
+            let to = request.to.clone()?;
+            Some(Envelope::response_to(
+                &request,
+                to,
+                MessageKind::Response,
+                json!({"echo": request.payload.get()}),
+            ))
+        })
+    })
+}
+
+/// `count` messages from `a` to `b`, one every 100ms, over a lossy link.
+fn lossy_run(seed: u64, count: u64) -> Trace {
+    run(seed, |sim| async move {
+        let a = sim.add_node("a");
+        let b = sim.add_node("b");
+        sim.apply(Fault::link(
+            &a,
+            &b,
+            LinkConditions {
+                latency: Duration::from_millis(30),
+                loss: 0.5,
+            },
+        ));
+        for n in 0..count {
+            a.send(&b, MessageKind::Message, json!({"n": n}))
+                .await
+                .unwrap();
+            sim.run_for(Duration::from_millis(70)).await;
+        }
+        sim.trace()
+    })
+}
+
+#[test]
+fn same_seed_gives_the_same_trace() {
+    let trace = lossy_run(42, 40);
+    assert_eq!(trace, lossy_run(42, 40));
+
+    let received: Vec<_> = trace
+        .iter()
+        .filter(|event| matches!(event.kind, TraceKind::Received { .. }))
+        .collect();
+    assert!(
+        (5..35).contains(&received.len()),
+        "about half of 40 messages get through: {}",
+        received.len()
+    );
+    for event in received {
+        assert_eq!(event.node, "b");
+        assert_eq!(
+            event.at.as_millis() % 100,
+            30,
+            "virtual time advances exactly by the link latency"
+        );
+    }
+}
+
+#[test]
+fn scheduled_partition_and_heal_disconnect_and_reconnect() {
+    let trace = run(1, |sim| async move {
+        let a = sim.add_node("a");
+        let b = sim.add_node_with_handler("b", echo_handler());
+        sim.schedule(Duration::from_secs(60), Fault::partition(&a, &b));
+        sim.schedule(Duration::from_secs(120), Fault::heal(&a, &b));
+
+        let reply = a
+            .send(&b, MessageKind::Request, json!({"q": 1}))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(reply.kind, MessageKind::Response);
+
+        sim.run_for(Duration::from_secs(90)).await;
+        let err = a
+            .send(&b, MessageKind::Request, json!({"q": 2}))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("down"), "{err}");
+
+        sim.run_for(Duration::from_secs(60)).await;
+        a.send(&b, MessageKind::Message, json!({"q": 3}))
+            .await
+            .unwrap();
+        sim.run_for(Duration::from_millis(1)).await;
+        assert_eq!(sim.elapsed(), Duration::from_millis(150_001));
+        sim.trace()
+    });
+
+    let seen: Vec<_> = trace
+        .iter()
+        .map(|event| (event.at.as_secs(), event.node.as_str(), &event.kind))
+        .collect();
+    let a_id = format!("ed25519.{:032x}", 1);
+    let b_id = format!("ed25519.{:032x}", 2);
+    assert_eq!(
+        seen,
+        vec![
+            (0, "a", &TraceKind::Connected { peer: "b".into() }),
+            (0, "b", &TraceKind::Connected { peer: "a".into() }),
+            (
+                0,
+                "b",
+                &TraceKind::Received {
+                    from: "a".into(),
+                    kind: MessageKind::Request,
+                    payload: r#"{"q":1}"#.into(),
+                }
+            ),
+            (
+                60,
+                "",
+                &TraceKind::Fault(Fault::Partition(a_id.clone(), b_id.clone()))
+            ),
+            (
+                60,
+                "a",
+                &TraceKind::Disconnected {
+                    peer: "b".into(),
+                    reason: "partitioned",
+                }
+            ),
+            (
+                60,
+                "b",
+                &TraceKind::Disconnected {
+                    peer: "a".into(),
+                    reason: "partitioned",
+                }
+            ),
+            (120, "", &TraceKind::Fault(Fault::Heal(a_id, b_id))),
+            (150, "a", &TraceKind::Connected { peer: "b".into() }),
+            (150, "b", &TraceKind::Connected { peer: "a".into() }),
+            (
+                150,
+                "b",
+                &TraceKind::Received {
+                    from: "a".into(),
+                    kind: MessageKind::Message,
+                    payload: r#"{"q":3}"#.into(),
+                }
+            ),
+        ]
+    );
+}
@@ -0,0 +1,64 @@
+use std::sync::Arc;
+
+use quinn::congestion::{BbrConfig, ControllerFactory, CubicConfig, NewRenoConfig};
+
+/// QUIC congestion control algorithm.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CongestionController {
+    /// Loss-based; quinn's default.
+    #[default]
+    Cubic,
+    NewReno,
+    /// Model-based; keeps throughput up on lossy long-haul links.
+    Bbr,
+}
+
+/// Congestion control for QUIC connections. Applies to the data this end
+/// sends; each side of a connection runs its own controller.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Congestion {
+    pub controller: CongestionController,
+    /// Congestion window, in bytes, before any acknowledgement arrives.
+    /// `None` keeps the algorithm's default.
+    pub initial_window: Option<u64>,
+}
+
+impl Congestion {
+    /// `self` with any per-peer override applied on top.
+    pub(crate) fn with_overrides(
+        self,
+        controller: Option<CongestionController>,
+        initial_window: Option<u64>,
+    ) -> Self {
+        Self {
+            controller: controller.unwrap_or(self.controller),
+            initial_window: initial_window.or(self.initial_window),
+        }
+    }
+
+    pub(crate) fn factory(&self) -> Arc<dyn ControllerFactory + Send + Sync + 'static> {
+        match self.controller {
+            CongestionController::Cubic => {
+                let mut config = CubicConfig::default();
+                if let Some(window) = self.initial_window {
+                    config.initial_window(window);
+                }
+                Arc::new(config)
+            }
+            CongestionController::NewReno => {
+                let mut config = NewRenoConfig::default();
+                if let Some(window) = self.initial_window {
+                    config.initial_window(window);
+                }
+                Arc::new(config)
+            }
+            CongestionController::Bbr => {
+                let mut config = BbrConfig::default();
+                if let Some(window) = self.initial_window {
+                    config.initial_window(window);
+                }
+                Arc::new(config)
+            }
+        }
+    }
+}
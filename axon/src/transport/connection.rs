@@ -2,139 +2,27 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
 
-use anyhow::{Context, Result, anyhow, bail};
+use anyhow::{Context, Result, anyhow};
 use base64::{Engine as _, engine::general_purpose::STANDARD};
 use rustls::pki_types::CertificateDer;
-use serde_json::json;
 use tokio::sync::OwnedSemaphorePermit;
 use tokio::sync::{RwLock, broadcast};
-use tokio::time::timeout;
 use tokio_util::sync::CancellationToken;
+use tokio_util::task::TaskTracker;
 use tracing::{debug, warn};
-use uuid::Uuid;
 
-use crate::message::{Envelope, MessageKind};
+use crate::message::Envelope;
 
-use super::MAX_MESSAGE_SIZE_USIZE;
+use super::ConnectionEvent;
+use super::inbound::{ConnectionContext, handle_bidi_stream, handle_uni_stream};
+use super::kind_policy::PolicyGate;
 use super::quic_transport::ResponseHandlerFn;
+use super::rate_limit::InboundLimiter;
+use super::response_cache::ResponseCache;
+use super::revocation::RevocationGate;
+use super::size_limit::SizeLimiter;
 use super::tls::{derive_agent_id_from_pubkey_bytes, extract_ed25519_pubkey_from_cert_der};
 
-// ---------------------------------------------------------------------------
-// Framing helpers — length-delimited read/write on QUIC streams
-// ---------------------------------------------------------------------------
-
-pub(crate) async fn write_framed(stream: &mut quinn::SendStream, bytes: &[u8]) -> Result<()> {
-    if bytes.len() > MAX_MESSAGE_SIZE_USIZE {
-        return Err(anyhow!("message too large for framing"));
-    }
-
-    stream
-        .write_all(bytes)
-        .await
-        .context("failed to write frame body")?;
-    Ok(())
-}
-
-pub(crate) async fn read_framed(stream: &mut quinn::RecvStream) -> Result<Vec<u8>> {
-    let buf = stream
-        .read_to_end(MAX_MESSAGE_SIZE_USIZE)
-        .await
-        .context("failed to read frame body")?;
-    Ok(buf)
-}
-
-pub(crate) async fn send_unidirectional(
-    connection: &quinn::Connection,
-    envelope: Envelope,
-) -> Result<()> {
-    let bytes = envelope
-        .wire_encode()
-        .context("failed to serialize envelope for wire")?;
-
-    let mut stream = connection
-        .open_uni()
-        .await
-        .context("failed to open uni stream")?;
-    write_framed(&mut stream, &bytes).await?;
-    stream.finish().context("failed to finish uni stream")?;
-    Ok(())
-}
-
-pub(crate) async fn send_request(
-    connection: &quinn::Connection,
-    envelope: Envelope,
-    local_agent_id: &str,
-    request_timeout: Duration,
-) -> Result<Envelope> {
-    let bytes = envelope
-        .wire_encode()
-        .context("failed to serialize request for wire")?;
-
-    let (mut send, mut recv) = connection
-        .open_bi()
-        .await
-        .context("failed to open bidi stream")?;
-    write_framed(&mut send, &bytes).await?;
-    send.finish().context("failed to finish request stream")?;
-
-    let timeout_label = if request_timeout.as_millis() < 1000 {
-        format!("{}ms", request_timeout.as_millis())
-    } else {
-        format!("{}s", request_timeout.as_secs())
-    };
-    let response_bytes = timeout(request_timeout, read_framed(&mut recv))
-        .await
-        .with_context(|| format!("request timed out after {timeout_label}"))??;
-    let mut response = serde_json::from_slice::<Envelope>(&response_bytes)
-        .context("failed to decode response envelope")?;
-    response
-        .validate()
-        .context("response envelope failed validation")?;
-    validate_bidi_response(&response, envelope.id)?;
-    let peer_id = derive_peer_id_from_connection(connection)?;
-    overwrite_authenticated_identity(&mut response, &peer_id, local_agent_id);
-    Ok(response)
-}
-
-fn validate_bidi_response(response: &Envelope, request_id: Uuid) -> Result<()> {
-    if !response.kind.is_response() {
-        bail!(
-            "bidirectional reply must use response|error kind, got {}",
-            response.kind
-        );
-    }
-    if response.ref_id != Some(request_id) {
-        bail!(
-            "bidirectional reply ref {:?} does not match request {}",
-            response.ref_id,
-            request_id
-        );
-    }
-    Ok(())
-}
-
-// ---------------------------------------------------------------------------
-// Default error response for unhandled bidi requests
-// ---------------------------------------------------------------------------
-
-/// Default response for unhandled bidi requests when no response handler is
-/// registered (or the handler returns `None`).
-pub fn default_error_response(request: &Envelope, local_agent_id: &str) -> Envelope {
-    Envelope::response_to(
-        request,
-        local_agent_id.to_string(),
-        MessageKind::Error,
-        json!({
-            "code": "unhandled",
-            "message": format!(
-                "no application handler registered for request '{}'",
-                request.id
-            ),
-            "retryable": false,
-        }),
-    )
-}
-
 // ---------------------------------------------------------------------------
 // Peer public-key extraction
 // ---------------------------------------------------------------------------
@@ -157,7 +45,7 @@ pub(crate) fn extract_peer_pubkey_base64_from_connection(
     Ok(STANDARD.encode(key))
 }
 
-fn derive_peer_id_from_connection(connection: &quinn::Connection) -> Result<String> {
+pub(crate) fn derive_peer_id_from_connection(connection: &quinn::Connection) -> Result<String> {
     let peer_cert_pubkey_b64 = extract_peer_pubkey_base64_from_connection(connection)?;
     let pubkey_bytes = STANDARD
         .decode(&peer_cert_pubkey_b64)
@@ -165,176 +53,66 @@ fn derive_peer_id_from_connection(connection: &quinn::Connection) -> Result<Stri
     Ok(derive_agent_id_from_pubkey_bytes(&pubkey_bytes))
 }
 
-fn overwrite_authenticated_identity(envelope: &mut Envelope, peer_id: &str, local_agent_id: &str) {
-    envelope.from = Some(peer_id.into());
-    envelope.to = Some(local_agent_id.into());
-}
-
-// ---------------------------------------------------------------------------
-// Connection context — shared state for stream handlers
-// ---------------------------------------------------------------------------
-
-#[derive(Clone)]
-struct ConnectionContext {
-    connection: quinn::Connection,
-    local_agent_id: String,
-    inbound_tx: broadcast::Sender<Arc<Envelope>>,
-    connections: Arc<RwLock<HashMap<String, quinn::Connection>>>,
-    response_handler: Option<ResponseHandlerFn>,
-    inbound_read_timeout: Duration,
-}
-
-// ---------------------------------------------------------------------------
-// Unidirectional stream handler
-// ---------------------------------------------------------------------------
-
-async fn handle_uni_stream(ctx: &ConnectionContext, peer_id: &str, mut recv: quinn::RecvStream) {
-    match timeout(ctx.inbound_read_timeout, read_framed(&mut recv)).await {
-        Ok(Ok(bytes)) => match serde_json::from_slice::<Envelope>(&bytes) {
-            Ok(mut envelope) => {
-                overwrite_authenticated_identity(&mut envelope, peer_id, &ctx.local_agent_id);
-                if envelope.kind.expects_response() {
-                    debug!("dropping request kind on uni stream");
-                } else if let Err(err) = envelope.validate() {
-                    debug!(error = %err, "dropping invalid uni envelope");
-                } else {
-                    let _ = ctx.inbound_tx.send(Arc::new(envelope));
-                }
-            }
-            Err(err) => {
-                debug!(error = %err, "dropping malformed uni envelope");
-            }
-        },
-        Ok(Err(err)) => {
-            warn!(error = %err, peer = peer_id, "failed reading uni stream");
-        }
-        Err(_) => {
-            warn!(peer = peer_id, "uni stream read timed out");
-        }
-    }
-}
-
-// ---------------------------------------------------------------------------
-// Bidirectional stream handler
-// ---------------------------------------------------------------------------
-
-/// Handle an authenticated bidi request.
-async fn handle_authenticated_bidi(
-    ctx: &ConnectionContext,
-    request: Envelope,
-    mut send: quinn::SendStream,
-) {
-    if request.kind == MessageKind::Unknown {
-        let response = Envelope::response_to(
-            &request,
-            ctx.local_agent_id.clone(),
-            MessageKind::Error,
-            json!({
-                "code": "unknown_kind",
-                "message": "unknown message kind on bidirectional stream",
-                "retryable": false,
-            }),
-        );
-        send_response(&mut send, &response).await;
-    } else if !request.kind.expects_response() {
-        // Fire-and-forget kind on a bidi stream — accept it gracefully
-        if let Err(err) = request.validate() {
-            debug!(error = %err, "dropping invalid bidi fire-and-forget envelope");
-        } else {
-            let _ = ctx.inbound_tx.send(Arc::new(request));
-        }
-        let _ = send.finish();
-    } else if let Err(err) = request.validate() {
-        let response = Envelope::response_to(
-            &request,
-            ctx.local_agent_id.clone(),
-            MessageKind::Error,
-            json!({
-                "code": "invalid_envelope",
-                "message": format!("envelope validation failed: {err}"),
-                "retryable": false,
-            }),
-        );
-        send_response(&mut send, &response).await;
-    } else {
-        let request_arc = Arc::new(request.clone());
-        let _ = ctx.inbound_tx.send(request_arc.clone());
-        let response = if let Some(ref handler) = ctx.response_handler {
-            match handler(request_arc).await {
-                Some(resp) => resp,
-                None => default_error_response(&request, &ctx.local_agent_id),
-            }
-        } else {
-            default_error_response(&request, &ctx.local_agent_id)
-        };
-        send_response(&mut send, &response).await;
-    }
-}
-
-async fn handle_bidi_stream(
-    ctx: &ConnectionContext,
+pub(crate) fn overwrite_authenticated_identity(
+    envelope: &mut Envelope,
     peer_id: &str,
-    send: quinn::SendStream,
-    mut recv: quinn::RecvStream,
+    local_agent_id: &str,
 ) {
-    let mut request = match timeout(ctx.inbound_read_timeout, read_framed(&mut recv)).await {
-        Ok(Ok(bytes)) => match serde_json::from_slice::<Envelope>(&bytes) {
-            Ok(r) => r,
-            Err(err) => {
-                debug!(error = %err, "dropping malformed bidi envelope");
-                return;
-            }
-        },
-        Ok(Err(err)) => {
-            warn!(error = %err, peer = peer_id, "failed reading bidi stream");
-            return;
-        }
-        Err(_) => {
-            warn!(peer = peer_id, "bidi stream read timed out");
-            return;
-        }
-    };
-
-    overwrite_authenticated_identity(&mut request, peer_id, &ctx.local_agent_id);
-    handle_authenticated_bidi(ctx, request, send).await;
+    envelope.from = Some(peer_id.into());
+    envelope.to = Some(local_agent_id.into());
 }
 
 // ---------------------------------------------------------------------------
-// Helpers
+// Connection registry
 // ---------------------------------------------------------------------------
 
-async fn send_response(send: &mut quinn::SendStream, response: &Envelope) {
-    if let Ok(response_bytes) = response.wire_encode()
-        && write_framed(send, &response_bytes).await.is_ok()
-    {
-        let _ = send.finish();
-    }
-}
-
+/// Register `connection` as the current connection for `peer_id`.
+///
+/// Returns the connection's stable ID and whether the peer had no entry
+/// before. An outbound connection is inserted by `ensure_connection` ahead of
+/// its loop, which announces it there; otherwise a simultaneous inbound dial
+/// that lands in between would leave neither side announcing the peer.
 async fn register_connection(
     connections: &Arc<RwLock<HashMap<String, quinn::Connection>>>,
     peer_id: &str,
     connection: &quinn::Connection,
-) -> usize {
+) -> (usize, bool) {
     let stable_id = connection.stable_id();
-    connections
+    let previous = connections
         .write()
         .await
         .insert(peer_id.to_string(), connection.clone());
-    stable_id
+    let newly_connected = previous.is_none();
+    (stable_id, newly_connected)
 }
 
+/// Remove the entry for `peer_id` if it still refers to `stable_id`.
+/// Returns `true` when the entry was removed.
 async fn unregister_connection_if_current(
     connections: &Arc<RwLock<HashMap<String, quinn::Connection>>>,
     peer_id: &str,
     stable_id: usize,
-) {
+) -> bool {
     let mut conns = connections.write().await;
     if conns
         .get(peer_id)
         .is_some_and(|c| c.stable_id() == stable_id)
     {
         conns.remove(peer_id);
+        return true;
+    }
+    false
+}
+
+/// Stable, snake_case reason string for a terminated connection.
+pub(crate) fn disconnect_reason(err: &quinn::ConnectionError) -> &'static str {
+    match err {
+        quinn::ConnectionError::ApplicationClosed(_)
+        | quinn::ConnectionError::ConnectionClosed(_) => "closed_by_peer",
+        quinn::ConnectionError::LocallyClosed => "closed_locally",
+        quinn::ConnectionError::TimedOut => "idle_timeout",
+        quinn::ConnectionError::Reset => "reset",
+        _ => "transport_error",
     }
 }
 
@@ -347,14 +125,23 @@ pub(crate) async fn run_connection(
     connection: quinn::Connection,
     local_agent_id: String,
     inbound_tx: broadcast::Sender<Arc<Envelope>>,
+    connection_event_tx: broadcast::Sender<ConnectionEvent>,
     connections: Arc<RwLock<HashMap<String, quinn::Connection>>>,
     cancel: CancellationToken,
     response_handler: Option<ResponseHandlerFn>,
     inbound_read_timeout: Duration,
+    inbound_limiter: Arc<InboundLimiter>,
+    response_cache: Arc<ResponseCache>,
+    size_limiter: Arc<SizeLimiter>,
+    policy_gate: Arc<PolicyGate>,
+    revocation: Arc<RevocationGate>,
+    stream_tasks: TaskTracker,
     _connection_permit: Option<OwnedSemaphorePermit>,
 ) {
-    let peer_id = match derive_peer_id_from_connection(&connection) {
-        Ok(peer_id) => peer_id,
+    let (peer_id, peer_pubkey) = match derive_peer_id_from_connection(&connection)
+        .and_then(|id| Ok((id, extract_peer_pubkey_base64_from_connection(&connection)?)))
+    {
+        Ok(peer) => peer,
         Err(err) => {
             warn!(error = %err, "failed to derive peer id from TLS identity");
             return;
@@ -368,48 +155,70 @@ pub(crate) async fn run_connection(
         connections,
         response_handler,
         inbound_read_timeout,
+        inbound_limiter,
+        response_cache,
+        size_limiter,
+        policy_gate,
     });
 
-    let my_stable_id = register_connection(&ctx.connections, &peer_id, &ctx.connection).await;
+    let (my_stable_id, newly_connected) =
+        register_connection(&ctx.connections, &peer_id, &ctx.connection).await;
+    if newly_connected {
+        let _ = connection_event_tx.send(ConnectionEvent::Connected {
+            agent_id: peer_id.clone(),
+        });
+    }
 
-    loop {
+    let mut revocations = revocation.subscribe();
+    let mut draining = false;
+    let reason = loop {
+        if let Some(refusal) = revocation.refusal(&peer_pubkey) {
+            connection.close(0u32.into(), refusal.as_bytes());
+            break refusal;
+        }
         tokio::select! {
-            _ = cancel.cancelled() => {
-                debug!("connection loop shutting down via cancellation");
-                break;
+            _ = cancel.cancelled(), if !draining => {
+                // Take no new requests, but keep reading messages (replies to
+                // our own sends among them) until shutdown closes the link.
+                debug!("connection loop draining via cancellation");
+                draining = true;
             }
+            _ = revocations.changed() => {}
             uni = connection.accept_uni() => {
                 match uni {
                     Ok(recv) => {
                         let ctx = ctx.clone();
                         let peer_id = peer_id.clone();
-                        tokio::spawn(async move {
+                        stream_tasks.spawn(async move {
                             handle_uni_stream(&ctx, &peer_id, recv).await;
                         });
                     }
-                    Err(_) => break,
+                    Err(_) if cancel.is_cancelled() => break "shutdown",
+                    Err(err) => break disconnect_reason(&err),
                 }
             }
-            bi = connection.accept_bi() => {
+            bi = connection.accept_bi(), if !draining => {
                 match bi {
                     Ok((send, recv)) => {
                         let ctx = ctx.clone();
                         let peer_id = peer_id.clone();
-                        tokio::spawn(async move {
+                        stream_tasks.spawn(async move {
                             handle_bidi_stream(&ctx, &peer_id, send, recv).await;
                         });
                     }
-                    Err(_) => break,
+                    Err(_) if cancel.is_cancelled() => break "shutdown",
+                    Err(err) => break disconnect_reason(&err),
                 }
             }
         }
-    }
+    };
 
     // Only remove our entry if we're still the registered connection.
     // Another connection loop (from a simultaneous dial) may have replaced us.
-    unregister_connection_if_current(&ctx.connections, &peer_id, my_stable_id).await;
+    if unregister_connection_if_current(&ctx.connections, &peer_id, my_stable_id).await {
+        let _ = connection_event_tx.send(ConnectionEvent::Disconnected {
+            agent_id: peer_id,
+            reason,
+        });
+    }
 }
-
-#[cfg(test)]
-#[path = "connection_tests.rs"]
-mod tests;
@@ -0,0 +1,73 @@
+use crate::errors::WireErrorCode;
+use crate::message::{Envelope, ErrorPayload, MessageKind};
+
+// ---------------------------------------------------------------------------
+// Default error response for unhandled bidi requests
+// ---------------------------------------------------------------------------
+
+/// Default response for unhandled bidi requests when no response handler is
+/// registered (or the handler returns `None`).
+pub fn default_error_response(request: &Envelope, local_agent_id: &str) -> Envelope {
+    Envelope::response_to(
+        request,
+        local_agent_id.to_string(),
+        MessageKind::Error,
+        ErrorPayload::new(
+            WireErrorCode::Unhandled,
+            format!(
+                "no application handler registered for request '{}'",
+                request.id
+            ),
+        )
+        .to_value(),
+    )
+}
+
+/// Reply to a request rejected by the per-peer inbound rate limit.
+pub(crate) fn rate_limited_response(request: &Envelope, local_agent_id: &str) -> Envelope {
+    Envelope::response_to(
+        request,
+        local_agent_id.to_string(),
+        MessageKind::Error,
+        ErrorPayload::new(
+            WireErrorCode::RateLimited,
+            "inbound rate limit exceeded for this peer; retry after a short backoff",
+        )
+        .to_value(),
+    )
+}
+
+/// Reply to a request from a peer whose kind policy does not allow requests.
+pub(crate) fn not_authorized_response(request: &Envelope, local_agent_id: &str) -> Envelope {
+    Envelope::response_to(
+        request,
+        local_agent_id.to_string(),
+        MessageKind::Error,
+        ErrorPayload::new(
+            WireErrorCode::NotAuthorized,
+            format!(
+                "this peer is not allowed to send '{}' envelopes",
+                request.kind
+            ),
+        )
+        .to_value(),
+    )
+}
+
+/// Reply to a request whose envelope exceeds the size cap for its kind.
+pub(crate) fn too_large_response(
+    request: &Envelope,
+    local_agent_id: &str,
+    reason: String,
+) -> Envelope {
+    Envelope::response_to(
+        request,
+        local_agent_id.to_string(),
+        MessageKind::Error,
+        ErrorPayload::new(WireErrorCode::MessageTooLarge, reason).to_value(),
+    )
+}
+
+#[cfg(test)]
+#[path = "error_replies_tests.rs"]
+mod tests;
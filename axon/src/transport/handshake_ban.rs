@@ -0,0 +1,198 @@
+use std::collections::{HashMap, HashSet};
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Addresses tracked at once; failures from new addresses beyond this are not
+/// counted until older entries expire.
+const MAX_TRACKED_ADDRS: usize = 4096;
+
+/// When repeated inbound handshake failures from one IP address earn it a
+/// temporary ban. `max_failures == 0` disables banning.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HandshakeBanLimit {
+    /// Failures within `window` that trigger a ban.
+    pub max_failures: u32,
+    pub window: Duration,
+    /// Length of the first ban; each further ban of the same address doubles
+    /// it, up to `max_ban`.
+    pub ban: Duration,
+    pub max_ban: Duration,
+}
+
+/// A currently banned address, as reported by IPC `bans`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HandshakeBan {
+    pub addr: IpAddr,
+    /// Bans this address has earned, including the current one.
+    pub strikes: u32,
+    pub remaining: Duration,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct AddrState {
+    failures: u32,
+    window_start: Instant,
+    strikes: u32,
+    banned_until: Option<Instant>,
+    last_failure: Instant,
+}
+
+#[derive(Debug, Default)]
+struct BanState {
+    limit: HandshakeBanLimit,
+    addrs: HashMap<IpAddr, AddrState>,
+    /// Addresses of static peers, never counted or banned.
+    exempt: HashSet<IpAddr>,
+}
+
+impl BanState {
+    /// Drop addresses that are not banned and have been quiet for longer than
+    /// the longest ban, so their strikes are forgiven.
+    fn prune(&mut self, now: Instant) {
+        let forget_after = self.limit.max_ban.max(self.limit.window);
+        self.addrs.retain(|_, state| {
+            state.banned_until.is_some_and(|until| until > now)
+                || now.saturating_duration_since(state.last_failure) < forget_after
+        });
+    }
+}
+
+/// Inbound TLS handshake failures per source IP, with exponentially growing
+/// temporary bans for addresses that keep failing.
+///
+/// Keyed by IP rather than socket address so a client cannot evade a ban by
+/// changing its source port. Callers only count failures from validated
+/// source addresses, so a spoofed address cannot get a real peer banned.
+#[derive(Debug, Default)]
+pub(crate) struct HandshakeBans {
+    state: Mutex<BanState>,
+}
+
+impl HandshakeBans {
+    /// Replace the limits. Existing failure counts and bans are dropped.
+    pub(crate) fn set_limit(&self, limit: HandshakeBanLimit) {
+        if let Ok(mut state) = self.state.lock() {
+            *state = BanState {
+                limit,
+                exempt: std::mem::take(&mut state.exempt),
+                ..BanState::default()
+            };
+        }
+    }
+
+    /// Replace the addresses that are never banned (static peers), lifting
+    /// any current ban on them.
+    pub(crate) fn set_exempt(&self, addrs: HashSet<IpAddr>) {
+        if let Ok(mut state) = self.state.lock() {
+            state.addrs.retain(|addr, _| !addrs.contains(addr));
+            state.exempt = addrs;
+        }
+    }
+
+    /// Whether failures are counted at all.
+    pub(crate) fn enabled(&self) -> bool {
+        self.state
+            .lock()
+            .is_ok_and(|state| state.limit.max_failures > 0)
+    }
+
+    pub(crate) fn is_banned(&self, addr: IpAddr) -> bool {
+        self.is_banned_at(addr, Instant::now())
+    }
+
+    fn is_banned_at(&self, addr: IpAddr, now: Instant) -> bool {
+        let Ok(state) = self.state.lock() else {
+            return false;
+        };
+        state
+            .addrs
+            .get(&addr)
+            .and_then(|entry| entry.banned_until)
+            .is_some_and(|until| until > now)
+    }
+
+    /// Count a failed handshake from `addr`. Returns the ban length when this
+    /// failure starts a new ban.
+    pub(crate) fn record_failure(&self, addr: IpAddr) -> Option<Duration> {
+        self.record_failure_at(addr, Instant::now())
+    }
+
+    fn record_failure_at(&self, addr: IpAddr, now: Instant) -> Option<Duration> {
+        let Ok(mut state) = self.state.lock() else {
+            return None;
+        };
+        let limit = state.limit;
+        if limit.max_failures == 0 || state.exempt.contains(&addr) {
+            return None;
+        }
+        if !state.addrs.contains_key(&addr) && state.addrs.len() >= MAX_TRACKED_ADDRS {
+            state.prune(now);
+            if state.addrs.len() >= MAX_TRACKED_ADDRS {
+                return None;
+            }
+        }
+        let entry = state.addrs.entry(addr).or_insert(AddrState {
+            failures: 0,
+            window_start: now,
+            strikes: 0,
+            banned_until: None,
+            last_failure: now,
+        });
+        if now.saturating_duration_since(entry.window_start) >= limit.window {
+            entry.failures = 0;
+            entry.window_start = now;
+        }
+        entry.failures += 1;
+        entry.last_failure = now;
+        if entry.failures < limit.max_failures {
+            return None;
+        }
+        entry.strikes += 1;
+        entry.failures = 0;
+        entry.window_start = now;
+        let ban = limit
+            .ban
+            .saturating_mul(1u32 << (entry.strikes - 1).min(31))
+            .min(limit.max_ban);
+        entry.banned_until = Some(now + ban);
+        Some(ban)
+    }
+
+    /// Forget `addr` after a successful handshake.
+    pub(crate) fn record_success(&self, addr: IpAddr) {
+        if let Ok(mut state) = self.state.lock() {
+            state.addrs.remove(&addr);
+        }
+    }
+
+    /// Currently banned addresses, longest remaining ban first.
+    pub(crate) fn bans(&self) -> Vec<HandshakeBan> {
+        self.bans_at(Instant::now())
+    }
+
+    fn bans_at(&self, now: Instant) -> Vec<HandshakeBan> {
+        let Ok(mut state) = self.state.lock() else {
+            return Vec::new();
+        };
+        state.prune(now);
+        let mut bans: Vec<HandshakeBan> = state
+            .addrs
+            .iter()
+            .filter_map(|(addr, entry)| {
+                let until = entry.banned_until.filter(|until| *until > now)?;
+                Some(HandshakeBan {
+                    addr: *addr,
+                    strikes: entry.strikes,
+                    remaining: until - now,
+                })
+            })
+            .collect();
+        bans.sort_by(|a, b| b.remaining.cmp(&a.remaining).then(a.addr.cmp(&b.addr)));
+        bans
+    }
+}
+
+#[cfg(test)]
+#[path = "handshake_ban_tests.rs"]
+mod tests;
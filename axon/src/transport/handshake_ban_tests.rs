@@ -0,0 +1,106 @@
+use super::*;
+
+const ADDR_A: IpAddr = IpAddr::V4(std::net::Ipv4Addr::new(192, 0, 2, 1));
+const ADDR_B: IpAddr = IpAddr::V4(std::net::Ipv4Addr::new(192, 0, 2, 2));
+
+fn bans(max_failures: u32) -> HandshakeBans {
+    let bans = HandshakeBans::default();
+    bans.set_limit(HandshakeBanLimit {
+        max_failures,
+        window: Duration::from_secs(60),
+        ban: Duration::from_secs(10),
+        max_ban: Duration::from_secs(25),
+    });
+    bans
+}
+
+#[test]
+fn disabled_by_default() {
+    let bans = HandshakeBans::default();
+    let now = Instant::now();
+    assert!((0..100).all(|_| bans.record_failure_at(ADDR_A, now).is_none()));
+    assert!(!bans.is_banned_at(ADDR_A, now));
+}
+
+#[test]
+fn static_peer_addresses_are_never_banned() {
+    let bans = bans(2);
+    let start = Instant::now();
+    assert_eq!(bans.record_failure_at(ADDR_A, start), None);
+    assert!(bans.record_failure_at(ADDR_A, start).is_some());
+    assert!(bans.is_banned_at(ADDR_A, start));
+
+    bans.set_exempt(HashSet::from([ADDR_A]));
+    assert!(!bans.is_banned_at(ADDR_A, start), "exempting lifts the ban");
+    assert!((0..10).all(|_| bans.record_failure_at(ADDR_A, start).is_none()));
+    assert!(!bans.is_banned_at(ADDR_A, start));
+    assert!(bans.bans_at(start).is_empty());
+
+    bans.set_limit(HandshakeBanLimit {
+        max_failures: 1,
+        ..HandshakeBanLimit::default()
+    });
+    assert_eq!(
+        bans.record_failure_at(ADDR_A, start),
+        None,
+        "kept on reload"
+    );
+    assert!(bans.record_failure_at(ADDR_B, start).is_some());
+}
+
+#[test]
+fn repeated_failures_ban_only_that_address() {
+    let bans = bans(3);
+    let start = Instant::now();
+    assert_eq!(bans.record_failure_at(ADDR_A, start), None);
+    assert_eq!(bans.record_failure_at(ADDR_A, start), None);
+    assert_eq!(
+        bans.record_failure_at(ADDR_A, start),
+        Some(Duration::from_secs(10))
+    );
+    assert!(bans.is_banned_at(ADDR_A, start));
+    assert!(!bans.is_banned_at(ADDR_B, start));
+    assert!(!bans.is_banned_at(ADDR_A, start + Duration::from_secs(10)));
+
+    let listed = bans.bans_at(start + Duration::from_secs(4));
+    assert_eq!(
+        listed,
+        vec![HandshakeBan {
+            addr: ADDR_A,
+            strikes: 1,
+            remaining: Duration::from_secs(6),
+        }]
+    );
+}
+
+#[test]
+fn failures_outside_the_window_do_not_accumulate() {
+    let bans = bans(2);
+    let start = Instant::now();
+    assert_eq!(bans.record_failure_at(ADDR_A, start), None);
+    assert_eq!(
+        bans.record_failure_at(ADDR_A, start + Duration::from_secs(61)),
+        None
+    );
+}
+
+#[test]
+fn repeat_bans_double_up_to_the_cap() {
+    let bans = bans(1);
+    let mut now = Instant::now();
+    let mut lengths = Vec::new();
+    for _ in 0..4 {
+        lengths.push(bans.record_failure_at(ADDR_A, now).unwrap().as_secs());
+        now += Duration::from_secs(30);
+    }
+    assert_eq!(lengths, [10, 20, 25, 25]);
+}
+
+#[test]
+fn success_forgets_the_address() {
+    let bans = bans(2);
+    let now = Instant::now();
+    assert_eq!(bans.record_failure_at(ADDR_A, now), None);
+    bans.record_success(ADDR_A);
+    assert_eq!(bans.record_failure_at(ADDR_A, now), None);
+}
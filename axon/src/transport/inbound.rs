@@ -0,0 +1,262 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::{RwLock, broadcast};
+use tokio::time::timeout;
+use tracing::{debug, warn};
+
+use crate::errors::WireErrorCode;
+use crate::message::{Envelope, ErrorPayload, MessageKind};
+
+use super::connection::overwrite_authenticated_identity;
+use super::error_replies::{
+    default_error_response, not_authorized_response, rate_limited_response, too_large_response,
+};
+use super::kind_policy::PolicyGate;
+use super::outbound::{read_framed, write_framed};
+use super::quic_transport::ResponseHandlerFn;
+use super::rate_limit::InboundLimiter;
+use super::response_cache::ResponseCache;
+use super::size_limit::SizeLimiter;
+
+// ---------------------------------------------------------------------------
+// Connection context — shared state for stream handlers
+// ---------------------------------------------------------------------------
+
+#[derive(Clone)]
+pub(crate) struct ConnectionContext {
+    pub(crate) connection: quinn::Connection,
+    pub(crate) local_agent_id: String,
+    pub(crate) inbound_tx: broadcast::Sender<Arc<Envelope>>,
+    pub(crate) connections: Arc<RwLock<HashMap<String, quinn::Connection>>>,
+    pub(crate) response_handler: Option<ResponseHandlerFn>,
+    pub(crate) inbound_read_timeout: Duration,
+    pub(crate) inbound_limiter: Arc<InboundLimiter>,
+    pub(crate) response_cache: Arc<ResponseCache>,
+    pub(crate) size_limiter: Arc<SizeLimiter>,
+    pub(crate) policy_gate: Arc<PolicyGate>,
+}
+
+// ---------------------------------------------------------------------------
+// Unidirectional stream handler
+// ---------------------------------------------------------------------------
+
+pub(crate) async fn handle_uni_stream(
+    ctx: &ConnectionContext,
+    peer_id: &str,
+    mut recv: quinn::RecvStream,
+) {
+    let limits = ctx.size_limiter.limits_for(peer_id);
+    match timeout(
+        ctx.inbound_read_timeout,
+        read_framed(&mut recv, limits.largest()),
+    )
+    .await
+    {
+        Ok(Ok(bytes)) => match serde_json::from_slice::<Envelope>(&bytes) {
+            Ok(mut envelope) => {
+                overwrite_authenticated_identity(&mut envelope, peer_id, &ctx.local_agent_id);
+                if !ctx.inbound_limiter.check(peer_id, bytes.len()) {
+                    debug!(peer = peer_id, msg_id = %envelope.id, "dropping rate-limited uni envelope");
+                } else if let Err(err) = limits.check(envelope.kind, bytes.len()) {
+                    debug!(peer = peer_id, error = %err, "dropping oversized uni envelope");
+                } else if !ctx.policy_gate.policy().allows(peer_id, envelope.kind) {
+                    debug!(peer = peer_id, kind = %envelope.kind, "dropping unauthorized uni envelope");
+                } else if envelope.kind.expects_response() {
+                    debug!("dropping request kind on uni stream");
+                } else if let Err(err) = envelope.validate() {
+                    debug!(error = %err, "dropping invalid uni envelope");
+                } else {
+                    let _ = ctx.inbound_tx.send(Arc::new(envelope));
+                }
+            }
+            Err(err) => {
+                debug!(error = %err, "dropping malformed uni envelope");
+            }
+        },
+        Ok(Err(err)) => {
+            warn!(error = %err, peer = peer_id, "failed reading uni stream");
+        }
+        Err(_) => {
+            warn!(peer = peer_id, "uni stream read timed out");
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Bidirectional stream handler
+// ---------------------------------------------------------------------------
+
+/// Handle an authenticated bidi request.
+async fn handle_authenticated_bidi(
+    ctx: &ConnectionContext,
+    request: Envelope,
+    mut send: quinn::SendStream,
+) {
+    if request.kind == MessageKind::Unknown {
+        let response = Envelope::response_to(
+            &request,
+            ctx.local_agent_id.clone(),
+            MessageKind::Error,
+            ErrorPayload::new(
+                WireErrorCode::UnknownKind,
+                "unknown message kind on bidirectional stream",
+            )
+            .to_value(),
+        );
+        send_response(&mut send, &response).await;
+    } else if !request.kind.expects_response() {
+        // Fire-and-forget kind on a bidi stream — accept it gracefully
+        if let Err(err) = request.validate() {
+            debug!(error = %err, "dropping invalid bidi fire-and-forget envelope");
+        } else {
+            let _ = ctx.inbound_tx.send(Arc::new(request));
+        }
+        let _ = send.finish();
+    } else if let Err(err) = request.validate() {
+        let response = Envelope::response_to(
+            &request,
+            ctx.local_agent_id.clone(),
+            MessageKind::Error,
+            ErrorPayload::new(
+                WireErrorCode::InvalidEnvelope,
+                format!("envelope validation failed: {err}"),
+            )
+            .to_value(),
+        );
+        send_response(&mut send, &response).await;
+    } else {
+        let peer_id = request.from.as_ref().map(|id| id.to_string());
+        let response = match peer_id.and_then(|peer| ctx.response_cache.slot(&peer, request.id)) {
+            Some((slot, first)) => {
+                if !first {
+                    debug!(msg_id = %request.id, "answering duplicate request from response cache");
+                }
+                slot.get_or_init(|| answer_request(ctx, &request, first))
+                    .await
+                    .clone()
+            }
+            None => answer_request(ctx, &request, true).await,
+        };
+        let response = fit_response(ctx, &request, response);
+        send_response(&mut send, &response).await;
+    }
+}
+
+/// Run the response handler for `request`, forwarding it to IPC subscribers
+/// unless it is a duplicate of a request they already saw.
+async fn answer_request(ctx: &ConnectionContext, request: &Envelope, broadcast: bool) -> Envelope {
+    let request_arc = Arc::new(request.clone());
+    if broadcast {
+        let _ = ctx.inbound_tx.send(request_arc.clone());
+    }
+    if let Some(ref handler) = ctx.response_handler
+        && let Some(response) = handler(request_arc).await
+    {
+        return response;
+    }
+    default_error_response(request, &ctx.local_agent_id)
+}
+
+/// `response`, or a `message_too_large` error in its place when it exceeds
+/// the cap for its kind and peer.
+fn fit_response(ctx: &ConnectionContext, request: &Envelope, response: Envelope) -> Envelope {
+    let len = match response.wire_encode() {
+        Ok(bytes) => bytes.len(),
+        Err(_) => return response,
+    };
+    let peer_id = request.from.as_ref().map_or("", |id| id.as_str());
+    match ctx
+        .size_limiter
+        .limits_for(peer_id)
+        .check(response.kind, len)
+    {
+        Ok(()) => response,
+        Err(err) => {
+            warn!(msg_id = %request.id, error = %err, "replacing oversized response");
+            too_large_response(request, &ctx.local_agent_id, format!("response {err}"))
+        }
+    }
+}
+
+pub(crate) async fn handle_bidi_stream(
+    ctx: &ConnectionContext,
+    peer_id: &str,
+    mut send: quinn::SendStream,
+    mut recv: quinn::RecvStream,
+) {
+    let limits = ctx.size_limiter.limits_for(peer_id);
+    let (mut request, size) = match timeout(
+        ctx.inbound_read_timeout,
+        read_framed(&mut recv, limits.largest()),
+    )
+    .await
+    {
+        Ok(Ok(bytes)) => match serde_json::from_slice::<Envelope>(&bytes) {
+            Ok(r) => (r, bytes.len()),
+            Err(err) => {
+                debug!(error = %err, "dropping malformed bidi envelope");
+                return;
+            }
+        },
+        Ok(Err(err)) => {
+            warn!(error = %err, peer = peer_id, "failed reading bidi stream");
+            return;
+        }
+        Err(_) => {
+            warn!(peer = peer_id, "bidi stream read timed out");
+            return;
+        }
+    };
+
+    overwrite_authenticated_identity(&mut request, peer_id, &ctx.local_agent_id);
+    if !ctx.inbound_limiter.check(peer_id, size) {
+        debug!(peer = peer_id, msg_id = %request.id, "rejecting rate-limited bidi envelope");
+        if request.kind.expects_response() {
+            send_response(
+                &mut send,
+                &rate_limited_response(&request, &ctx.local_agent_id),
+            )
+            .await;
+        } else {
+            let _ = send.finish();
+        }
+        return;
+    }
+    if let Err(err) = limits.check(request.kind, size) {
+        debug!(peer = peer_id, msg_id = %request.id, error = %err, "rejecting oversized bidi envelope");
+        if request.kind.expects_response() {
+            send_response(
+                &mut send,
+                &too_large_response(&request, &ctx.local_agent_id, err.to_string()),
+            )
+            .await;
+        } else {
+            let _ = send.finish();
+        }
+        return;
+    }
+    if !ctx.policy_gate.policy().allows(peer_id, request.kind) {
+        debug!(peer = peer_id, msg_id = %request.id, kind = %request.kind, "rejecting unauthorized bidi envelope");
+        if request.kind.expects_response() {
+            send_response(
+                &mut send,
+                &not_authorized_response(&request, &ctx.local_agent_id),
+            )
+            .await;
+        } else {
+            let _ = send.finish();
+        }
+        return;
+    }
+    handle_authenticated_bidi(ctx, request, send).await;
+}
+
+async fn send_response(send: &mut quinn::SendStream, response: &Envelope) {
+    if let Ok(response_bytes) = response.wire_encode()
+        && write_framed(send, &response_bytes).await.is_ok()
+    {
+        let _ = send.finish();
+    }
+}
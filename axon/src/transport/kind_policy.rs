@@ -0,0 +1,56 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+use crate::message::MessageKind;
+
+/// Application kinds each listed peer may send. Peers without an entry may
+/// send any kind; daemon-level kinds are never restricted.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KindPolicy {
+    peers: HashMap<String, HashSet<MessageKind>>,
+}
+
+impl KindPolicy {
+    /// Restrict `peer` to `kinds`. An empty set blocks every application kind.
+    pub fn allow(&mut self, peer: impl Into<String>, kinds: impl IntoIterator<Item = MessageKind>) {
+        self.peers.insert(peer.into(), kinds.into_iter().collect());
+    }
+
+    pub fn allows(&self, peer: &str, kind: MessageKind) -> bool {
+        if !kind.is_application() {
+            return true;
+        }
+        self.peers
+            .get(peer)
+            .is_none_or(|allowed| allowed.contains(&kind))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.peers.is_empty()
+    }
+}
+
+/// Current policy, shared by all connections and replaced on reload.
+#[derive(Debug, Default)]
+pub(crate) struct PolicyGate {
+    policy: Mutex<Arc<KindPolicy>>,
+}
+
+impl PolicyGate {
+    pub(crate) fn set_policy(&self, policy: KindPolicy) {
+        if let Ok(mut current) = self.policy.lock() {
+            *current = Arc::new(policy);
+        }
+    }
+
+    pub(crate) fn policy(&self) -> Arc<KindPolicy> {
+        self.policy
+            .lock()
+            .map(|policy| policy.clone())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+#[path = "kind_policy_tests.rs"]
+mod tests;
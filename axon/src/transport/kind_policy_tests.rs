@@ -0,0 +1,49 @@
+use super::*;
+
+const PEER: &str = "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+const OTHER: &str = "ed25519.bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb";
+
+#[test]
+fn unlisted_peers_may_send_any_kind() {
+    let mut policy = KindPolicy::default();
+    assert!(policy.allows(PEER, MessageKind::Request));
+    policy.allow(PEER, [MessageKind::Message]);
+    for kind in [
+        MessageKind::Request,
+        MessageKind::Response,
+        MessageKind::Message,
+        MessageKind::Error,
+    ] {
+        assert!(policy.allows(OTHER, kind), "{kind}");
+    }
+}
+
+#[test]
+fn listed_peers_are_limited_to_allowed_application_kinds() {
+    let mut policy = KindPolicy::default();
+    policy.allow(PEER, [MessageKind::Message, MessageKind::Error]);
+    assert!(policy.allows(PEER, MessageKind::Message));
+    assert!(policy.allows(PEER, MessageKind::Error));
+    assert!(!policy.allows(PEER, MessageKind::Request));
+    assert!(!policy.allows(PEER, MessageKind::Response));
+}
+
+#[test]
+fn daemon_kinds_are_never_restricted() {
+    let mut policy = KindPolicy::default();
+    policy.allow(PEER, []);
+    assert!(!policy.allows(PEER, MessageKind::Message));
+    assert!(policy.allows(PEER, MessageKind::Heartbeat));
+    assert!(policy.allows(PEER, MessageKind::SubscribeTopics));
+    assert!(policy.allows(PEER, MessageKind::Unknown));
+}
+
+#[test]
+fn gate_replaces_policy() {
+    let gate = PolicyGate::default();
+    assert!(gate.policy().is_empty());
+    let mut policy = KindPolicy::default();
+    policy.allow(PEER, [MessageKind::Message]);
+    gate.set_policy(policy.clone());
+    assert_eq!(*gate.policy(), policy);
+}
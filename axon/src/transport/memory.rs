@@ -0,0 +1,329 @@
+use std::collections::{HashMap, HashSet};
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use anyhow::{Result, anyhow};
+use tokio::sync::broadcast;
+
+use super::error_replies::default_error_response;
+use super::{ConnectionEvent, MessageSizeLimits, REQUEST_TIMEOUT, ResponseHandlerFn, Transport};
+use crate::message::{AgentId, Envelope};
+use crate::peer_table::{ConnectionStatus, PeerRecord, PeerSource};
+
+/// Delay and drop rate for envelopes travelling one way between two agents.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct LinkConditions {
+    pub latency: Duration,
+    /// Fraction of envelopes dropped, from 0.0 (none) to 1.0 (all).
+    pub loss: f64,
+}
+
+impl LinkConditions {
+    /// A link that drops everything.
+    pub const DOWN: Self = Self {
+        latency: Duration::ZERO,
+        loss: 1.0,
+    };
+}
+
+/// In-process network that [`MemoryTransport`]s attach to. Cloning shares
+/// the network. Drops are drawn from a seeded generator, so a test with the
+/// same seed and send order loses the same envelopes every run; latency uses
+/// tokio timers, which `tokio::time::pause` makes instant.
+#[derive(Clone)]
+pub struct MemoryNetwork {
+    inner: Arc<Mutex<Network>>,
+}
+
+struct Network {
+    agents: HashMap<String, Agent>,
+    default_link: LinkConditions,
+    links: HashMap<(String, String), LinkConditions>,
+    rng: u64,
+}
+
+#[derive(Clone)]
+struct Agent {
+    inbound_tx: broadcast::Sender<Arc<Envelope>>,
+    connection_event_tx: broadcast::Sender<ConnectionEvent>,
+    response_handler: Option<ResponseHandlerFn>,
+    connected: Arc<Mutex<HashSet<String>>>,
+}
+
+impl Default for MemoryNetwork {
+    fn default() -> Self {
+        Self::with_seed(0)
+    }
+}
+
+impl MemoryNetwork {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A network whose dropped envelopes are chosen by `seed`.
+    pub fn with_seed(seed: u64) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(Network {
+                agents: HashMap::new(),
+                default_link: LinkConditions::default(),
+                links: HashMap::new(),
+                rng: seed,
+            })),
+        }
+    }
+
+    /// Join the network as `agent_id`. Requests sent to it go to
+    /// `response_handler`, or get the default `unhandled` error. Attaching
+    /// an ID again replaces the earlier agent.
+    pub fn attach(
+        &self,
+        agent_id: impl Into<AgentId>,
+        response_handler: Option<ResponseHandlerFn>,
+    ) -> MemoryTransport {
+        let agent_id = agent_id.into();
+        let agent = Agent {
+            inbound_tx: broadcast::channel(1024).0,
+            connection_event_tx: broadcast::channel(256).0,
+            response_handler,
+            connected: Arc::default(),
+        };
+        self.lock()
+            .agents
+            .insert(agent_id.to_string(), agent.clone());
+        MemoryTransport {
+            network: self.clone(),
+            local_agent_id: agent_id,
+            agent,
+        }
+    }
+
+    /// Leave the network. Agents connected to `agent_id` see it disconnect.
+    pub fn detach(&self, agent_id: &str) {
+        let Some(agent) = self.lock().agents.remove(agent_id) else {
+            return;
+        };
+        for peer in lock(&agent.connected).drain() {
+            self.disconnect_from(&peer, agent_id, "detached");
+        }
+    }
+
+    /// Conditions for links without their own.
+    pub fn set_default_link(&self, conditions: LinkConditions) {
+        self.lock().default_link = conditions;
+    }
+
+    /// Conditions for envelopes from `from` to `to`; the reverse direction
+    /// is unaffected.
+    pub fn set_link(&self, from: &str, to: &str, conditions: LinkConditions) {
+        self.lock()
+            .links
+            .insert((from.to_string(), to.to_string()), conditions);
+    }
+
+    /// Drop everything between `a` and `b` in both directions and
+    /// disconnect them.
+    pub fn partition(&self, a: &str, b: &str) {
+        self.set_link(a, b, LinkConditions::DOWN);
+        self.set_link(b, a, LinkConditions::DOWN);
+        self.disconnect_from(a, b, "partitioned");
+        self.disconnect_from(b, a, "partitioned");
+    }
+
+    /// Restore the default conditions between `a` and `b`. They reconnect
+    /// on their next exchange.
+    pub fn heal(&self, a: &str, b: &str) {
+        let mut network = self.lock();
+        network.links.remove(&(a.to_string(), b.to_string()));
+        network.links.remove(&(b.to_string(), a.to_string()));
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Network> {
+        lock(&self.inner)
+    }
+
+    fn agent(&self, agent_id: &str) -> Option<Agent> {
+        self.lock().agents.get(agent_id).cloned()
+    }
+
+    fn link(&self, from: &str, to: &str) -> LinkConditions {
+        let network = self.lock();
+        network
+            .links
+            .get(&(from.to_string(), to.to_string()))
+            .copied()
+            .unwrap_or(network.default_link)
+    }
+
+    /// Carry one envelope over the link: wait out its latency, then report
+    /// whether it survived.
+    async fn traverse(&self, from: &str, to: &str) -> bool {
+        let link = self.link(from, to);
+        if !link.latency.is_zero() {
+            tokio::time::sleep(link.latency).await;
+        }
+        link.loss <= 0.0 || self.next_unit() >= link.loss
+    }
+
+    /// Next value in [0, 1) from the network's splitmix64 sequence.
+    fn next_unit(&self) -> f64 {
+        let mut network = self.lock();
+        network.rng = network.rng.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = network.rng;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+        (z >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    fn connect(&self, a: &str, b: &str) {
+        for (local, peer) in [(a, b), (b, a)] {
+            if let Some(agent) = self.agent(local)
+                && lock(&agent.connected).insert(peer.to_string())
+            {
+                let _ = agent.connection_event_tx.send(ConnectionEvent::Connected {
+                    agent_id: peer.to_string(),
+                });
+            }
+        }
+    }
+
+    fn disconnect_from(&self, local: &str, peer: &str, reason: &'static str) {
+        if let Some(agent) = self.agent(local)
+            && lock(&agent.connected).remove(peer)
+        {
+            let _ = agent
+                .connection_event_tx
+                .send(ConnectionEvent::Disconnected {
+                    agent_id: peer.to_string(),
+                    reason,
+                });
+        }
+    }
+}
+
+/// One agent on a [`MemoryNetwork`]. Delivers envelopes the way
+/// [`QuicTransport`](super::QuicTransport) does: the receiver sees `from`
+/// and `to` set by the transport, inbound messages and requests are
+/// broadcast to subscribers, and requests are answered by the receiver's
+/// response handler.
+#[derive(Clone)]
+pub struct MemoryTransport {
+    network: MemoryNetwork,
+    local_agent_id: AgentId,
+    agent: Agent,
+}
+
+impl MemoryTransport {
+    pub fn network(&self) -> &MemoryNetwork {
+        &self.network
+    }
+
+    /// A peer record other agents can `send` to.
+    pub fn peer_record(&self) -> PeerRecord {
+        PeerRecord {
+            agent_id: self.local_agent_id.clone(),
+            addr: SocketAddr::from((Ipv4Addr::LOCALHOST, 0)),
+            pubkey: String::new(),
+            source: PeerSource::Static,
+            status: ConnectionStatus::Discovered,
+            rtt_ms: None,
+            last_seen: Instant::now(),
+            first_seen_unix_ms: 0,
+            traffic: Default::default(),
+            software: None,
+        }
+    }
+
+    async fn deliver(
+        &self,
+        peer: &PeerRecord,
+        mut envelope: Envelope,
+        request_timeout: Duration,
+    ) -> Result<Option<Envelope>> {
+        let local = self.local_agent_id.as_str();
+        let remote = peer.agent_id.as_str();
+        let bytes = envelope.wire_encode()?;
+        MessageSizeLimits::default().check(envelope.kind, bytes.len())?;
+        let target = self
+            .network
+            .agent(remote)
+            .ok_or_else(|| anyhow!("no agent {remote} on the memory network"))?;
+        if self.network.link(local, remote).loss >= 1.0 {
+            return Err(anyhow!("memory link to {remote} is down"));
+        }
+        self.network.connect(local, remote);
+
+        envelope.from = Some(self.local_agent_id.clone());
+        envelope.to = Some(peer.agent_id.clone());
+        if !envelope.kind.expects_response() {
+            if self.network.traverse(local, remote).await {
+                let _ = target.inbound_tx.send(Arc::new(envelope));
+            }
+            return Ok(None);
+        }
+
+        let exchange = async {
+            if !self.network.traverse(local, remote).await {
+                return std::future::pending().await;
+            }
+            let request = Arc::new(envelope);
+            let _ = target.inbound_tx.send(request.clone());
+            let answer = match &target.response_handler {
+                Some(handler) => handler(request.clone()).await,
+                None => None,
+            };
+            let mut response = answer.unwrap_or_else(|| default_error_response(&request, remote));
+            if !self.network.traverse(remote, local).await {
+                return std::future::pending().await;
+            }
+            response.from = Some(peer.agent_id.clone());
+            response.to = Some(self.local_agent_id.clone());
+            response
+        };
+        let response = tokio::time::timeout(request_timeout, exchange)
+            .await
+            .map_err(|_| anyhow!("request timed out after {request_timeout:?}"))?;
+        Ok(Some(response))
+    }
+}
+
+impl Transport for MemoryTransport {
+    fn local_agent_id(&self) -> &str {
+        self.local_agent_id.as_str()
+    }
+
+    fn subscribe_inbound(&self) -> broadcast::Receiver<Arc<Envelope>> {
+        self.agent.inbound_tx.subscribe()
+    }
+
+    fn subscribe_connection_events(&self) -> broadcast::Receiver<ConnectionEvent> {
+        self.agent.connection_event_tx.subscribe()
+    }
+
+    async fn has_connection(&self, agent_id: &str) -> bool {
+        lock(&self.agent.connected).contains(agent_id)
+    }
+
+    async fn send_with_timeout(
+        &self,
+        peer: &PeerRecord,
+        envelope: Envelope,
+        request_timeout: Duration,
+    ) -> Result<Option<Envelope>> {
+        self.deliver(peer, envelope, request_timeout).await
+    }
+
+    async fn send(&self, peer: &PeerRecord, envelope: Envelope) -> Result<Option<Envelope>> {
+        self.deliver(peer, envelope, REQUEST_TIMEOUT).await
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+#[cfg(test)]
+#[path = "memory_tests.rs"]
+mod tests;
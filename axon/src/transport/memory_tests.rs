@@ -0,0 +1,169 @@
+use serde_json::json;
+
+use super::*;
+use crate::message::MessageKind;
+
+const A: &str = "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+const B: &str = "ed25519.bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb";
+
+fn echo_handler() -> ResponseHandlerFn {
+    Arc::new(|request: Arc<Envelope>| {
+        Box::pin(async move {
+            let payload: serde_json::Value = serde_json::from_str(request.payload.get()).ok()?;
+            let to = request.to.clone()?;
+            Some(Envelope::response_to(
+                &request,
+                to,
+                MessageKind::Response,
+                json!({"echo": payload}),
+            ))
+        })
+    })
+}
+
+#[tokio::test]
+async fn messages_and_requests_reach_the_peer() {
+    let network = MemoryNetwork::new();
+    let a = network.attach(A, None);
+    let b = network.attach(B, Some(echo_handler()));
+    let mut b_inbound = b.subscribe_inbound();
+    let mut a_events = a.subscribe_connection_events();
+
+    let sent = a
+        .send(
+            &b.peer_record(),
+            Envelope::new("spoofed", B, MessageKind::Message, json!({"n": 1})),
+        )
+        .await
+        .unwrap();
+    assert!(sent.is_none());
+    let message = b_inbound.recv().await.unwrap();
+    assert_eq!(message.from.as_ref().unwrap().as_str(), A);
+    assert_eq!(message.to.as_ref().unwrap().as_str(), B);
+    assert_eq!(
+        a_events.recv().await.unwrap(),
+        ConnectionEvent::Connected {
+            agent_id: B.to_string()
+        }
+    );
+    assert!(b.has_connection(A).await);
+
+    let request = Envelope::new(A, B, MessageKind::Request, json!({"q": "ping"}));
+    let response = a
+        .send(&b.peer_record(), request.clone())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(response.ref_id, Some(request.id));
+    assert_eq!(response.from.as_ref().unwrap().as_str(), B);
+    assert_eq!(response.payload.get(), r#"{"echo":{"q":"ping"}}"#);
+    assert_eq!(b_inbound.recv().await.unwrap().id, request.id);
+
+    let unhandled = b
+        .send(
+            &a.peer_record(),
+            Envelope::new(B, A, MessageKind::Request, json!({})),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(unhandled.kind, MessageKind::Error);
+    assert!(unhandled.payload.get().contains("unhandled"));
+}
+
+#[tokio::test(start_paused = true)]
+async fn latency_applies_in_each_direction() {
+    let network = MemoryNetwork::new();
+    network.set_default_link(LinkConditions {
+        latency: Duration::from_millis(40),
+        loss: 0.0,
+    });
+    let a = network.attach(A, None);
+    let b = network.attach(B, Some(echo_handler()));
+
+    let started = tokio::time::Instant::now();
+    a.send(
+        &b.peer_record(),
+        Envelope::new(A, B, MessageKind::Request, json!({})),
+    )
+    .await
+    .unwrap();
+    assert_eq!(started.elapsed(), Duration::from_millis(80));
+}
+
+#[tokio::test(start_paused = true)]
+async fn lost_replies_time_out_the_request() {
+    let network = MemoryNetwork::new();
+    let a = network.attach(A, None);
+    let b = network.attach(B, Some(echo_handler()));
+    network.set_link(B, A, LinkConditions::DOWN);
+
+    let err = a
+        .send_with_timeout(
+            &b.peer_record(),
+            Envelope::new(A, B, MessageKind::Request, json!({})),
+            Duration::from_secs(2),
+        )
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("timed out"), "error: {err}");
+}
+
+#[tokio::test]
+async fn loss_is_reproducible_for_a_seed() {
+    async fn delivered(seed: u64) -> Vec<u64> {
+        let network = MemoryNetwork::with_seed(seed);
+        network.set_default_link(LinkConditions {
+            latency: Duration::ZERO,
+            loss: 0.5,
+        });
+        let a = network.attach(A, None);
+        let b = network.attach(B, None);
+        let mut inbound = b.subscribe_inbound();
+        for n in 0..32u64 {
+            let message = Envelope::new(A, B, MessageKind::Message, json!({"n": n}));
+            a.send(&b.peer_record(), message).await.unwrap();
+        }
+        let mut received = Vec::new();
+        while let Ok(envelope) = inbound.try_recv() {
+            let payload: serde_json::Value = serde_json::from_str(envelope.payload.get()).unwrap();
+            received.push(payload["n"].as_u64().unwrap());
+        }
+        received
+    }
+
+    let first = delivered(7).await;
+    assert_eq!(first, delivered(7).await);
+    assert!(!first.is_empty() && first.len() < 32, "{first:?}");
+}
+
+#[tokio::test]
+async fn partitions_disconnect_until_healed() {
+    let network = MemoryNetwork::new();
+    let a = network.attach(A, None);
+    let b = network.attach(B, None);
+    let message = || Envelope::new(A, B, MessageKind::Message, json!({}));
+    a.send(&b.peer_record(), message()).await.unwrap();
+
+    let mut b_events = b.subscribe_connection_events();
+    network.partition(A, B);
+    assert_eq!(
+        b_events.recv().await.unwrap(),
+        ConnectionEvent::Disconnected {
+            agent_id: A.to_string(),
+            reason: "partitioned"
+        }
+    );
+    assert!(!a.has_connection(B).await);
+    let err = a.send(&b.peer_record(), message()).await.unwrap_err();
+    assert!(err.to_string().contains("down"), "error: {err}");
+
+    network.heal(A, B);
+    a.send(&b.peer_record(), message()).await.unwrap();
+    assert!(a.has_connection(B).await);
+
+    network.detach(B);
+    let err = a.send(&b.peer_record(), message()).await.unwrap_err();
+    assert!(err.to_string().contains("no agent"), "error: {err}");
+    assert!(!a.has_connection(B).await);
+}
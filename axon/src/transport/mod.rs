@@ -1,10 +1,29 @@
+mod congestion;
 mod connection;
+mod error_replies;
+mod handshake_ban;
+mod inbound;
+mod kind_policy;
+#[cfg(any(test, feature = "test-util"))]
+mod memory;
+mod outbound;
+mod peer_tuning;
 mod quic_transport;
+mod rate_limit;
+mod response_cache;
+mod revocation;
+mod size_limit;
 mod tls;
 
+use std::future::Future;
+use std::sync::Arc;
 use std::time::Duration;
 
-use crate::message::MAX_MESSAGE_SIZE;
+use anyhow::Result;
+use tokio::sync::broadcast;
+
+use crate::message::{Envelope, MAX_MESSAGE_SIZE};
+use crate::peer_table::PeerRecord;
 
 pub const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
 
@@ -17,6 +36,91 @@ pub struct PairRequest {
     pub addr: Option<String>,
 }
 
-pub use connection::default_error_response;
+/// Peer connection lifecycle transition observed by the transport.
+///
+/// `Connected` fires when a peer gains its first live connection;
+/// `Disconnected` fires when the last registered connection for a peer ends.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConnectionEvent {
+    Connected {
+        agent_id: String,
+    },
+    Disconnected {
+        agent_id: String,
+        reason: &'static str,
+    },
+}
+
+/// Envelope delivery between agents: the part of a transport that
+/// application code sends and subscribes through. Implemented by
+/// [`QuicTransport`] and, with the `test-util` feature, by
+/// [`MemoryTransport`] for tests that should not open sockets.
+pub trait Transport: Clone + Send + Sync + 'static {
+    fn local_agent_id(&self) -> &str;
+
+    /// Inbound messages and requests, with `from` set by the transport.
+    fn subscribe_inbound(&self) -> broadcast::Receiver<Arc<Envelope>>;
+
+    fn subscribe_connection_events(&self) -> broadcast::Receiver<ConnectionEvent>;
+
+    fn has_connection(&self, agent_id: &str) -> impl Future<Output = bool> + Send;
+
+    /// Deliver `envelope`; requests wait up to [`REQUEST_TIMEOUT`] for the
+    /// peer's `response` or `error`.
+    fn send(
+        &self,
+        peer: &PeerRecord,
+        envelope: Envelope,
+    ) -> impl Future<Output = Result<Option<Envelope>>> + Send;
+
+    fn send_with_timeout(
+        &self,
+        peer: &PeerRecord,
+        envelope: Envelope,
+        request_timeout: Duration,
+    ) -> impl Future<Output = Result<Option<Envelope>>> + Send;
+}
+
+impl Transport for QuicTransport {
+    fn local_agent_id(&self) -> &str {
+        QuicTransport::local_agent_id(self)
+    }
+
+    fn subscribe_inbound(&self) -> broadcast::Receiver<Arc<Envelope>> {
+        QuicTransport::subscribe_inbound(self)
+    }
+
+    fn subscribe_connection_events(&self) -> broadcast::Receiver<ConnectionEvent> {
+        QuicTransport::subscribe_connection_events(self)
+    }
+
+    async fn has_connection(&self, agent_id: &str) -> bool {
+        QuicTransport::has_connection(self, agent_id).await
+    }
+
+    async fn send(&self, peer: &PeerRecord, envelope: Envelope) -> Result<Option<Envelope>> {
+        QuicTransport::send(self, peer, envelope).await
+    }
+
+    async fn send_with_timeout(
+        &self,
+        peer: &PeerRecord,
+        envelope: Envelope,
+        request_timeout: Duration,
+    ) -> Result<Option<Envelope>> {
+        QuicTransport::send_with_timeout(self, peer, envelope, request_timeout).await
+    }
+}
+
+pub use congestion::{Congestion, CongestionController};
+pub use error_replies::default_error_response;
+pub use handshake_ban::{HandshakeBan, HandshakeBanLimit};
+pub use kind_policy::KindPolicy;
+#[cfg(any(test, feature = "test-util"))]
+pub use memory::{LinkConditions, MemoryNetwork, MemoryTransport};
+pub use peer_tuning::{DEFAULT_HANDSHAKE_TIMEOUT, PeerTuning};
 pub use quic_transport::{QuicTransport, ResponseHandlerFn};
+pub use rate_limit::InboundRateLimit;
+pub use response_cache::ResponseCacheLimit;
+pub use size_limit::MessageSizeLimits;
 pub use tls::extract_ed25519_pubkey_from_cert_der;
@@ -0,0 +1,117 @@
+use std::time::Duration;
+
+use anyhow::{Context, Result, anyhow, bail};
+use tokio::time::timeout;
+use uuid::Uuid;
+
+use crate::message::Envelope;
+
+use super::MAX_MESSAGE_SIZE_USIZE;
+use super::connection::{derive_peer_id_from_connection, overwrite_authenticated_identity};
+use super::size_limit::MessageSizeLimits;
+
+// ---------------------------------------------------------------------------
+// Framing helpers — length-delimited read/write on QUIC streams
+// ---------------------------------------------------------------------------
+
+pub(crate) async fn write_framed(stream: &mut quinn::SendStream, bytes: &[u8]) -> Result<()> {
+    if bytes.len() > MAX_MESSAGE_SIZE_USIZE {
+        return Err(anyhow!("message too large for framing"));
+    }
+
+    stream
+        .write_all(bytes)
+        .await
+        .context("failed to write frame body")?;
+    Ok(())
+}
+
+/// Read one frame of at most `max_len` bytes.
+pub(crate) async fn read_framed(stream: &mut quinn::RecvStream, max_len: usize) -> Result<Vec<u8>> {
+    let buf = stream
+        .read_to_end(max_len.min(MAX_MESSAGE_SIZE_USIZE))
+        .await
+        .context("failed to read frame body")?;
+    Ok(buf)
+}
+
+// ---------------------------------------------------------------------------
+// Outbound streams — one envelope per stream
+// ---------------------------------------------------------------------------
+
+pub(crate) async fn send_unidirectional(
+    connection: &quinn::Connection,
+    envelope: Envelope,
+    limits: MessageSizeLimits,
+) -> Result<()> {
+    let bytes = envelope
+        .wire_encode()
+        .context("failed to serialize envelope for wire")?;
+    limits.check(envelope.kind, bytes.len())?;
+
+    let mut stream = connection
+        .open_uni()
+        .await
+        .context("failed to open uni stream")?;
+    write_framed(&mut stream, &bytes).await?;
+    stream.finish().context("failed to finish uni stream")?;
+    Ok(())
+}
+
+pub(crate) async fn send_request(
+    connection: &quinn::Connection,
+    envelope: Envelope,
+    local_agent_id: &str,
+    request_timeout: Duration,
+    limits: MessageSizeLimits,
+) -> Result<Envelope> {
+    let bytes = envelope
+        .wire_encode()
+        .context("failed to serialize request for wire")?;
+    limits.check(envelope.kind, bytes.len())?;
+
+    let (mut send, mut recv) = connection
+        .open_bi()
+        .await
+        .context("failed to open bidi stream")?;
+    write_framed(&mut send, &bytes).await?;
+    send.finish().context("failed to finish request stream")?;
+
+    let timeout_label = if request_timeout.as_millis() < 1000 {
+        format!("{}ms", request_timeout.as_millis())
+    } else {
+        format!("{}s", request_timeout.as_secs())
+    };
+    let response_bytes = timeout(request_timeout, read_framed(&mut recv, limits.largest()))
+        .await
+        .with_context(|| format!("request timed out after {timeout_label}"))??;
+    let mut response = serde_json::from_slice::<Envelope>(&response_bytes)
+        .context("failed to decode response envelope")?;
+    limits
+        .check(response.kind, response_bytes.len())
+        .context("response envelope rejected")?;
+    response
+        .validate()
+        .context("response envelope failed validation")?;
+    validate_bidi_response(&response, envelope.id)?;
+    let peer_id = derive_peer_id_from_connection(connection)?;
+    overwrite_authenticated_identity(&mut response, &peer_id, local_agent_id);
+    Ok(response)
+}
+
+fn validate_bidi_response(response: &Envelope, request_id: Uuid) -> Result<()> {
+    if !response.kind.is_response() {
+        bail!(
+            "bidirectional reply must use response|error kind, got {}",
+            response.kind
+        );
+    }
+    if response.ref_id != Some(request_id) {
+        bail!(
+            "bidirectional reply ref {:?} does not match request {}",
+            response.ref_id,
+            request_id
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,117 @@
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use super::congestion::{Congestion, CongestionController};
+use super::tls::transport_config;
+
+/// Handshake timeout for peers without their own.
+pub const DEFAULT_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Connection overrides for one peer. `None` keeps the transport default.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PeerTuning {
+    pub handshake_timeout: Option<Duration>,
+    /// Idle timeout offered when dialing the peer.
+    pub idle_timeout: Option<Duration>,
+    /// Cap on encoded envelopes of every kind to and from the peer.
+    pub max_message_bytes: Option<usize>,
+    /// Congestion controller for connections dialed to the peer.
+    pub congestion_controller: Option<CongestionController>,
+    /// Initial congestion window, in bytes, for connections dialed to the
+    /// peer.
+    pub initial_window: Option<u64>,
+}
+
+impl PeerTuning {
+    /// Whether dialing the peer needs a transport config of its own.
+    fn overrides_transport(&self) -> bool {
+        self.idle_timeout.is_some()
+            || self.congestion_controller.is_some()
+            || self.initial_window.is_some()
+    }
+}
+
+/// Current per-peer overrides and congestion control, replaced on reload,
+/// and the client config that dials with them.
+pub(crate) struct PeerTunings {
+    tunings: RwLock<HashMap<String, PeerTuning>>,
+    congestion: RwLock<Congestion>,
+    client_config: quinn::ClientConfig,
+    keepalive: Duration,
+    idle_timeout: Duration,
+}
+
+impl PeerTunings {
+    pub(crate) fn new(
+        client_config: quinn::ClientConfig,
+        keepalive: Duration,
+        idle_timeout: Duration,
+    ) -> Self {
+        Self {
+            tunings: RwLock::new(HashMap::new()),
+            congestion: RwLock::new(Congestion::default()),
+            client_config,
+            keepalive,
+            idle_timeout,
+        }
+    }
+
+    pub(crate) fn set(&self, tunings: HashMap<String, PeerTuning>) {
+        match self.tunings.write() {
+            Ok(mut current) => *current = tunings,
+            Err(poisoned) => *poisoned.into_inner() = tunings,
+        }
+    }
+
+    pub(crate) fn get(&self, agent_id: &str) -> PeerTuning {
+        let tunings = match self.tunings.read() {
+            Ok(tunings) => tunings,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        tunings.get(agent_id).copied().unwrap_or_default()
+    }
+
+    /// Replace the daemon-wide congestion control, returning the transport
+    /// config accepted connections should now use.
+    pub(crate) fn set_congestion(&self, congestion: Congestion) -> quinn::TransportConfig {
+        match self.congestion.write() {
+            Ok(mut current) => *current = congestion,
+            Err(poisoned) => *poisoned.into_inner() = congestion,
+        }
+        transport_config(self.keepalive, self.idle_timeout, congestion)
+    }
+
+    fn congestion(&self) -> Congestion {
+        match self.congestion.read() {
+            Ok(congestion) => *congestion,
+            Err(poisoned) => *poisoned.into_inner(),
+        }
+    }
+
+    pub(crate) fn handshake_timeout(&self, agent_id: &str) -> Duration {
+        self.get(agent_id)
+            .handshake_timeout
+            .unwrap_or(DEFAULT_HANDSHAKE_TIMEOUT)
+    }
+
+    /// Client config for dialing `agent_id`, or `None` to use the endpoint
+    /// default.
+    pub(crate) fn client_config(&self, agent_id: &str) -> Option<quinn::ClientConfig> {
+        let tuning = self.get(agent_id);
+        let congestion = self.congestion();
+        if !tuning.overrides_transport() && congestion == Congestion::default() {
+            return None;
+        }
+        let congestion =
+            congestion.with_overrides(tuning.congestion_controller, tuning.initial_window);
+        let idle_timeout = tuning.idle_timeout.unwrap_or(self.idle_timeout);
+        let mut config = self.client_config.clone();
+        config.transport_config(Arc::new(transport_config(
+            self.keepalive,
+            idle_timeout,
+            congestion,
+        )));
+        Some(config)
+    }
+}
@@ -0,0 +1,118 @@
+use std::collections::HashSet;
+use std::future::IntoFuture;
+use std::net::IpAddr;
+
+use tracing::{debug, info, warn};
+
+use super::super::handshake_ban::{HandshakeBan, HandshakeBanLimit};
+use super::super::tls::with_handshake_remote_addr;
+use super::QuicTransport;
+
+/// What the accept loop does with an incoming connection before any
+/// handshake work.
+enum Admission {
+    /// The source address is banned for failed handshakes.
+    Refuse,
+    /// Bans are enabled and the source address is unproven: answer with a
+    /// Retry so a spoofed Initial cannot get a real peer banned.
+    Retry,
+    /// Run the handshake. `validated` says whether a failure may count
+    /// toward a ban.
+    Handshake { validated: bool },
+}
+
+impl QuicTransport {
+    /// Temporarily ban source IPs whose inbound handshakes keep failing.
+    pub fn set_handshake_bans(&self, limit: HandshakeBanLimit) {
+        self.handshake_bans.set_limit(limit);
+    }
+
+    /// Source IPs never banned for handshake failures (static peers').
+    pub fn set_handshake_ban_exempt(&self, addrs: HashSet<IpAddr>) {
+        self.handshake_bans.set_exempt(addrs);
+    }
+
+    /// Source IPs currently banned for repeated handshake failures.
+    pub fn handshake_bans(&self) -> Vec<HandshakeBan> {
+        self.handshake_bans.bans()
+    }
+
+    /// Accept inbound connections until cancelled or the endpoint closes.
+    pub(super) fn spawn_accept_loop(&self) {
+        let transport = self.clone();
+        tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    _ = transport.cancel.cancelled() => {
+                        info!("accept loop shutting down");
+                        break;
+                    }
+                    maybe_conn = transport.endpoint.accept() => {
+                        let Some(incoming) = maybe_conn else { break };
+                        transport.accept(incoming).await;
+                    }
+                }
+            }
+        });
+    }
+
+    fn admission(&self, incoming: &quinn::Incoming, remote_ip: IpAddr) -> Admission {
+        if self.handshake_bans.is_banned(remote_ip) {
+            return Admission::Refuse;
+        }
+        // A failure only counts toward a ban once the client has proven it
+        // owns its source address (a Retry round trip).
+        let validated = incoming.remote_address_validated();
+        if !validated && self.handshake_bans.enabled() && incoming.may_retry() {
+            return Admission::Retry;
+        }
+        Admission::Handshake { validated }
+    }
+
+    async fn accept(&self, incoming: quinn::Incoming) {
+        let remote_addr = incoming.remote_address();
+        let remote_ip = remote_addr.ip().to_canonical();
+        let validated = match self.admission(&incoming, remote_ip) {
+            Admission::Refuse => {
+                debug!(remote = %remote_addr, "refusing inbound QUIC connection from banned address");
+                incoming.refuse();
+                return;
+            }
+            Admission::Retry => {
+                if let Err(err) = incoming.retry() {
+                    debug!(remote = %remote_addr, error = %err, "failed to send QUIC retry");
+                }
+                return;
+            }
+            Admission::Handshake { validated } => validated,
+        };
+        match with_handshake_remote_addr(remote_addr, incoming.into_future()).await {
+            Ok(connection) => {
+                self.handshake_bans.record_success(remote_ip);
+                let Ok(permit) = self.connection_semaphore.clone().try_acquire_owned() else {
+                    warn!(
+                        max = self.max_connections,
+                        "rejecting inbound QUIC connection: connection limit reached"
+                    );
+                    connection.close(0u32.into(), b"connection limit reached");
+                    return;
+                };
+                debug!(remote = ?connection.remote_address(), "accepted inbound QUIC connection");
+                self.spawn_connection_loop(connection, Some(permit));
+            }
+            Err(err) => {
+                warn!(error = %err, "failed to accept QUIC connection");
+                if !validated {
+                    return;
+                }
+                if let Some(ban) = self.handshake_bans.record_failure(remote_ip) {
+                    warn!(
+                        remote = %remote_ip,
+                        ban_secs = ban.as_secs(),
+                        "banning address after repeated handshake failures"
+                    );
+                }
+            }
+        }
+    }
+}
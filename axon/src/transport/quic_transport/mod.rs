@@ -0,0 +1,419 @@
+mod accept;
+mod settings;
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result, anyhow};
+use tokio::sync::{Mutex, OwnedSemaphorePermit, RwLock, Semaphore, broadcast};
+use tokio_util::sync::CancellationToken;
+use tokio_util::task::TaskTracker;
+use tracing::warn;
+
+use crate::identity::Identity;
+use crate::message::{AgentId, Envelope};
+use crate::peer_table::{PeerRecord, PubkeyMap};
+use crate::transport::{ConnectionEvent, PairRequest};
+
+use super::REQUEST_TIMEOUT;
+use super::connection::run_connection;
+use super::handshake_ban::HandshakeBans;
+use super::kind_policy::PolicyGate;
+use super::outbound::{send_request, send_unidirectional};
+use super::peer_tuning::PeerTunings;
+use super::rate_limit::InboundLimiter;
+use super::response_cache::ResponseCache;
+use super::revocation::RevocationGate;
+use super::size_limit::SizeLimiter;
+use super::tls::{BuiltEndpoint, build_endpoint, with_handshake_remote_addr};
+
+/// Upper bound on waiting for closed connections to drain at shutdown.
+/// Draining lasts three probe timeouts, about 3s for a connection closed
+/// mid-handshake (no RTT sample yet).
+const SHUTDOWN_DRAIN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Optional callback to produce a response for a bidirectional request.
+/// If `None` is returned, the default error response is used.
+pub type ResponseHandlerFn = Arc<
+    dyn Fn(Arc<Envelope>) -> Pin<Box<dyn Future<Output = Option<Envelope>> + Send>> + Send + Sync,
+>;
+
+#[derive(Clone)]
+pub struct QuicTransport {
+    endpoint: quinn::Endpoint,
+    /// Server config for accepted connections, kept to swap in congestion
+    /// control changes.
+    server_config: quinn::ServerConfig,
+    local_agent_id: AgentId,
+    max_connections: usize,
+    connections: Arc<RwLock<HashMap<String, quinn::Connection>>>,
+    /// Per-peer lock to prevent concurrent connection attempts to the same peer.
+    connecting_locks: Arc<RwLock<HashMap<String, Arc<Mutex<()>>>>>,
+    inbound_tx: broadcast::Sender<Arc<Envelope>>,
+    pair_request_tx: broadcast::Sender<PairRequest>,
+    connection_event_tx: broadcast::Sender<ConnectionEvent>,
+    connection_semaphore: Arc<Semaphore>,
+    cancel: CancellationToken,
+    response_handler: Option<ResponseHandlerFn>,
+    inbound_read_timeout: Duration,
+    inbound_limiter: Arc<InboundLimiter>,
+    response_cache: Arc<ResponseCache>,
+    size_limiter: Arc<SizeLimiter>,
+    peer_tunings: Arc<PeerTunings>,
+    policy_gate: Arc<PolicyGate>,
+    revocation: Arc<RevocationGate>,
+    handshake_bans: Arc<HandshakeBans>,
+    /// Inbound stream handlers (reads, response handler runs, replies).
+    stream_tasks: TaskTracker,
+}
+
+impl QuicTransport {
+    pub async fn bind(
+        bind_addr: SocketAddr,
+        identity: &Identity,
+        max_connections: usize,
+        pubkey_map: PubkeyMap,
+    ) -> Result<Self> {
+        Self::bind_cancellable(
+            bind_addr,
+            identity,
+            CancellationToken::new(),
+            max_connections,
+            Duration::from_secs(15),
+            Duration::from_secs(60),
+            None,
+            Duration::from_secs(10),
+            pubkey_map,
+        )
+        .await
+    }
+
+    /// Cancelling `cancel` stops accepting connections and new requests;
+    /// messages are still read until [`shutdown`](Self::shutdown) closes the
+    /// connections.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn bind_cancellable(
+        bind_addr: SocketAddr,
+        identity: &Identity,
+        cancel: CancellationToken,
+        max_connections: usize,
+        keepalive: Duration,
+        idle_timeout: Duration,
+        response_handler: Option<ResponseHandlerFn>,
+        inbound_read_timeout: Duration,
+        pubkey_map: PubkeyMap,
+    ) -> Result<Self> {
+        let socket = std::net::UdpSocket::bind(bind_addr)
+            .with_context(|| format!("failed to bind QUIC endpoint on {bind_addr}"))?;
+        Self::from_socket_cancellable(
+            socket,
+            identity,
+            cancel,
+            max_connections,
+            keepalive,
+            idle_timeout,
+            response_handler,
+            inbound_read_timeout,
+            pubkey_map,
+        )
+        .await
+    }
+
+    /// Like [`bind_cancellable`](Self::bind_cancellable), but serves QUIC on an
+    /// already-bound UDP socket (e.g. one passed in by systemd socket activation).
+    #[allow(clippy::too_many_arguments)]
+    pub async fn from_socket_cancellable(
+        socket: std::net::UdpSocket,
+        identity: &Identity,
+        cancel: CancellationToken,
+        max_connections: usize,
+        keepalive: Duration,
+        idle_timeout: Duration,
+        response_handler: Option<ResponseHandlerFn>,
+        inbound_read_timeout: Duration,
+        pubkey_map: PubkeyMap,
+    ) -> Result<Self> {
+        let cert = identity.make_quic_certificate()?;
+        let revocation = Arc::new(RevocationGate::default());
+        let BuiltEndpoint {
+            endpoint,
+            server_config,
+            client_config,
+            inbound_tx,
+            pair_request_tx,
+        } = build_endpoint(
+            socket,
+            &cert,
+            pubkey_map,
+            revocation.keys(),
+            keepalive,
+            idle_timeout,
+        )?;
+        let (connection_event_tx, _) = broadcast::channel(512);
+
+        let transport = Self {
+            endpoint,
+            server_config,
+            local_agent_id: AgentId::from(identity.agent_id()),
+            max_connections,
+            connections: Arc::new(RwLock::new(HashMap::new())),
+            connecting_locks: Arc::new(RwLock::new(HashMap::new())),
+            inbound_tx,
+            pair_request_tx,
+            connection_event_tx,
+            connection_semaphore: Arc::new(Semaphore::new(max_connections)),
+            cancel,
+            response_handler,
+            inbound_read_timeout,
+            inbound_limiter: Arc::new(InboundLimiter::default()),
+            response_cache: Arc::new(ResponseCache::default()),
+            size_limiter: Arc::new(SizeLimiter::default()),
+            peer_tunings: Arc::new(PeerTunings::new(client_config, keepalive, idle_timeout)),
+            policy_gate: Arc::new(PolicyGate::default()),
+            revocation,
+            handshake_bans: Arc::new(HandshakeBans::default()),
+            stream_tasks: TaskTracker::new(),
+        };
+        transport.spawn_accept_loop();
+        Ok(transport)
+    }
+
+    pub fn local_agent_id(&self) -> &str {
+        self.local_agent_id.as_str()
+    }
+
+    pub fn subscribe_inbound(&self) -> broadcast::Receiver<Arc<Envelope>> {
+        self.inbound_tx.subscribe()
+    }
+
+    pub fn subscribe_pair_requests(&self) -> broadcast::Receiver<PairRequest> {
+        self.pair_request_tx.subscribe()
+    }
+
+    pub fn subscribe_connection_events(&self) -> broadcast::Receiver<ConnectionEvent> {
+        self.connection_event_tx.subscribe()
+    }
+
+    pub async fn has_connection(&self, agent_id: &str) -> bool {
+        self.connections.read().await.contains_key(agent_id)
+    }
+
+    pub async fn ensure_connection(&self, peer: &PeerRecord) -> Result<quinn::Connection> {
+        // Fast path: already connected.
+        if let Some(existing) = self
+            .connections
+            .read()
+            .await
+            .get(peer.agent_id.as_str())
+            .cloned()
+        {
+            return Ok(existing);
+        }
+
+        // Acquire per-peer lock to prevent duplicate concurrent connection attempts.
+        let peer_lock = {
+            let mut locks = self.connecting_locks.write().await;
+            locks
+                .entry(peer.agent_id.to_string())
+                .or_insert_with(|| Arc::new(Mutex::new(())))
+                .clone()
+        };
+        let _guard = peer_lock.lock().await;
+
+        // Re-check after acquiring the lock — another task may have connected.
+        if let Some(existing) = self
+            .connections
+            .read()
+            .await
+            .get(peer.agent_id.as_str())
+            .cloned()
+        {
+            return Ok(existing);
+        }
+
+        let connection_permit = self
+            .connection_semaphore
+            .clone()
+            .try_acquire_owned()
+            .map_err(|_| anyhow!("connection limit reached"))?;
+
+        let connecting = match self.peer_tunings.client_config(&peer.agent_id) {
+            Some(config) => self
+                .endpoint
+                .connect_with(config, peer.addr, &peer.agent_id),
+            None => self.endpoint.connect(peer.addr, &peer.agent_id),
+        }
+        .with_context(|| format!("failed to begin QUIC connect to {}", peer.addr))?;
+        let remote_addr = connecting.remote_address();
+
+        let handshake_timeout = self.peer_tunings.handshake_timeout(&peer.agent_id);
+        let connection = tokio::time::timeout(
+            handshake_timeout,
+            with_handshake_remote_addr(remote_addr, connecting),
+        )
+        .await
+        .map_err(|_| {
+            anyhow!(
+                "QUIC handshake with {} timed out after {}s",
+                peer.addr,
+                handshake_timeout.as_secs()
+            )
+        })?
+        .with_context(|| format!("QUIC handshake failed with {}", peer.addr))?;
+
+        let previous = self
+            .connections
+            .write()
+            .await
+            .insert(peer.agent_id.to_string(), connection.clone());
+        if previous.is_none() {
+            let _ = self.connection_event_tx.send(ConnectionEvent::Connected {
+                agent_id: peer.agent_id.to_string(),
+            });
+        }
+        self.spawn_connection_loop(connection.clone(), Some(connection_permit));
+
+        Ok(connection)
+    }
+
+    pub async fn send(&self, peer: &PeerRecord, envelope: Envelope) -> Result<Option<Envelope>> {
+        self.send_with_timeout(peer, envelope, REQUEST_TIMEOUT)
+            .await
+    }
+
+    pub async fn send_with_timeout(
+        &self,
+        peer: &PeerRecord,
+        envelope: Envelope,
+        request_timeout: Duration,
+    ) -> Result<Option<Envelope>> {
+        let connection = self.ensure_connection(peer).await?;
+
+        if envelope.kind.expects_response() {
+            let response = send_request(
+                &connection,
+                envelope,
+                self.local_agent_id.as_str(),
+                request_timeout,
+                self.size_limiter.limits_for(&peer.agent_id),
+            )
+            .await?;
+            Ok(Some(response))
+        } else {
+            send_unidirectional(
+                &connection,
+                envelope,
+                self.size_limiter.limits_for(&peer.agent_id),
+            )
+            .await?;
+            Ok(None)
+        }
+    }
+
+    /// Inbound streams whose handlers have not finished yet.
+    pub fn in_flight_streams(&self) -> usize {
+        self.stream_tasks.len()
+    }
+
+    /// Wait up to `timeout` for in-flight inbound streams to finish so their
+    /// replies are written before connections are closed. Returns `true` once
+    /// drained, `false` if the timeout elapsed first.
+    pub async fn drain(&self, timeout: Duration) -> bool {
+        self.stream_tasks.close();
+        tokio::time::timeout(timeout, self.stream_tasks.wait())
+            .await
+            .is_ok()
+    }
+
+    pub async fn connection_count(&self) -> usize {
+        self.connections.read().await.len()
+    }
+
+    /// Close any live connection to `agent_id`. Returns whether one was open.
+    pub async fn close_connection(&self, agent_id: &str, reason: &[u8]) -> bool {
+        match self.connections.read().await.get(agent_id) {
+            Some(connection) => {
+                connection.close(0u32.into(), reason);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub async fn close_all(&self) {
+        for connection in self.connections.read().await.values() {
+            connection.close(0u32.into(), b"shutdown");
+        }
+    }
+
+    /// Close every connection and the endpoint, then wait for closed
+    /// connections to finish draining. quinn releases the UDP socket only
+    /// once they have, so a restart on the same port can bind on return.
+    pub async fn shutdown(&self) {
+        self.close_all().await;
+        self.endpoint.close(0u32.into(), b"shutdown");
+        if tokio::time::timeout(SHUTDOWN_DRAIN_TIMEOUT, self.endpoint.wait_idle())
+            .await
+            .is_err()
+        {
+            warn!(
+                open = self.endpoint.open_connections(),
+                "QUIC connections still draining at shutdown"
+            );
+        }
+    }
+
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        self.endpoint
+            .local_addr()
+            .context("failed to get local address")
+    }
+
+    fn spawn_connection_loop(
+        &self,
+        connection: quinn::Connection,
+        connection_permit: Option<OwnedSemaphorePermit>,
+    ) {
+        let inbound_tx = self.inbound_tx.clone();
+        let connection_event_tx = self.connection_event_tx.clone();
+        let local_id = self.local_agent_id.clone();
+        let connections = self.connections.clone();
+        let cancel = self.cancel.clone();
+        let response_handler = self.response_handler.clone();
+        let inbound_read_timeout = self.inbound_read_timeout;
+        let inbound_limiter = self.inbound_limiter.clone();
+        let response_cache = self.response_cache.clone();
+        let size_limiter = self.size_limiter.clone();
+        let policy_gate = self.policy_gate.clone();
+        let revocation = self.revocation.clone();
+        let stream_tasks = self.stream_tasks.clone();
+
+        tokio::spawn(async move {
+            run_connection(
+                connection,
+                local_id.to_string(),
+                inbound_tx,
+                connection_event_tx,
+                connections,
+                cancel,
+                response_handler,
+                inbound_read_timeout,
+                inbound_limiter,
+                response_cache,
+                size_limiter,
+                policy_gate,
+                revocation,
+                stream_tasks,
+                connection_permit,
+            )
+            .await;
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests;
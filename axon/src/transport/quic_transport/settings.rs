@@ -0,0 +1,90 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+
+use super::super::congestion::Congestion;
+use super::super::kind_policy::KindPolicy;
+use super::super::peer_tuning::PeerTuning;
+use super::super::rate_limit::InboundRateLimit;
+use super::super::response_cache::ResponseCacheLimit;
+use super::super::size_limit::MessageSizeLimits;
+use super::QuicTransport;
+use crate::message::Envelope;
+
+/// Runtime settings applied from config at startup and on reload.
+impl QuicTransport {
+    /// Apply per-peer inbound rate limits to all current and future connections.
+    pub fn set_inbound_rate_limit(&self, limit: InboundRateLimit) {
+        self.inbound_limiter.set_limit(limit);
+    }
+
+    /// Keep responses to inbound requests for deduplicating retries.
+    pub fn set_response_cache(&self, limit: ResponseCacheLimit) {
+        self.response_cache.set_limit(limit);
+    }
+
+    /// Cap encoded envelope size per kind, for sends and inbound streams.
+    pub fn set_message_size_limits(&self, limits: MessageSizeLimits) {
+        self.size_limiter.set_limits(limits);
+    }
+
+    /// Check that `envelope` fits the size cap for its kind and recipient
+    /// before sending.
+    pub fn check_message_size(&self, envelope: &Envelope) -> Result<()> {
+        let bytes = envelope.wire_encode()?;
+        let limits = match &envelope.to {
+            Some(to) => self.size_limiter.limits_for(to.as_str()),
+            None => self.size_limiter.limits(),
+        };
+        limits.check(envelope.kind, bytes.len())
+    }
+
+    /// Replace the per-peer connection overrides. Handshake and idle
+    /// timeouts apply to the next dial; size caps apply at once.
+    pub fn set_peer_tuning(&self, tunings: HashMap<String, PeerTuning>) {
+        self.size_limiter.set_peer_caps(
+            tunings
+                .iter()
+                .filter_map(|(id, tuning)| Some((id.clone(), tuning.max_message_bytes?)))
+                .collect(),
+        );
+        self.peer_tunings.set(tunings);
+    }
+
+    /// Replace the daemon-wide congestion control. Applies to connections
+    /// accepted or dialed from now on; per-peer overrides still win.
+    pub fn set_congestion(&self, congestion: Congestion) {
+        let mut server_config = self.server_config.clone();
+        server_config.transport = Arc::new(self.peer_tunings.set_congestion(congestion));
+        self.endpoint.set_server_config(Some(server_config));
+    }
+
+    /// How long dialing `agent_id` may spend in the QUIC handshake.
+    pub fn handshake_timeout(&self, agent_id: &str) -> Duration {
+        self.peer_tunings.handshake_timeout(agent_id)
+    }
+
+    /// Restrict which application kinds each peer may send to this agent.
+    pub fn set_kind_policy(&self, policy: KindPolicy) {
+        self.policy_gate.set_policy(policy);
+    }
+
+    /// Refuse TLS handshakes from peers whose base64 public key is in `keys`,
+    /// whether or not the key is pinned, and close their open connections.
+    pub fn set_revoked_keys(&self, keys: HashSet<String>) {
+        self.revocation.set_keys(keys);
+    }
+
+    /// Refuse blocked peers' public keys the same way as revoked ones;
+    /// their connections close with reason `blocked`.
+    pub fn set_blocked_keys(&self, keys: HashSet<String>) {
+        self.revocation.set_blocked(keys);
+    }
+
+    /// Inbound messages rejected by the rate limit since startup.
+    pub fn rate_limited_count(&self) -> u64 {
+        self.inbound_limiter.rejected()
+    }
+}
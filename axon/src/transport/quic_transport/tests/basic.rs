@@ -4,7 +4,9 @@ use crate::config::AxonPaths;
 use crate::identity::Identity;
 use crate::message::{Envelope, MessageKind};
 use crate::peer_table::PeerTable;
+use crate::transport::{Congestion, CongestionController, PeerTuning};
 use serde_json::json;
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::time::Duration;
 use tempfile::tempdir;
@@ -45,6 +47,44 @@ async fn two_peers_connect() {
     assert!(pair.transport_a.has_connection(pair.id_b.agent_id()).await);
 }
 
+#[tokio::test]
+async fn peers_exchange_messages_with_configured_congestion_control() {
+    let pair = make_transport_pair().await;
+    pair.transport_b.set_congestion(Congestion {
+        controller: CongestionController::Bbr,
+        initial_window: Some(64 * 1024),
+    });
+    pair.transport_a.set_congestion(Congestion {
+        controller: CongestionController::Bbr,
+        initial_window: None,
+    });
+    pair.transport_a.set_peer_tuning(HashMap::from([(
+        pair.id_b.agent_id().to_string(),
+        PeerTuning {
+            congestion_controller: Some(CongestionController::NewReno),
+            initial_window: Some(32 * 1024),
+            ..PeerTuning::default()
+        },
+    )]));
+    let mut rx_b = pair.transport_b.subscribe_inbound();
+    let peer_b = peer_record(&pair.id_b, pair.transport_b.local_addr().unwrap());
+
+    for n in 0..3 {
+        let notify = Envelope::new(
+            pair.id_a.agent_id().to_string(),
+            pair.id_b.agent_id().to_string(),
+            MessageKind::Message,
+            json!({"n": n, "data": "x".repeat(16 * 1024)}),
+        );
+        pair.transport_a.send(&peer_b, notify).await.expect("send");
+        let received = tokio::time::timeout(Duration::from_secs(5), rx_b.recv())
+            .await
+            .expect("timeout waiting for inbound")
+            .expect("recv");
+        assert_eq!(received.payload_value().unwrap()["n"], n);
+    }
+}
+
 #[tokio::test]
 async fn send_notify_unidirectional() {
     let pair = make_transport_pair().await;
@@ -1,5 +1,6 @@
 use super::fixtures::{make_transport_pair, peer_record, wait_for_registered_connection};
 use crate::message::{Envelope, MessageKind};
+use crate::transport::ConnectionEvent;
 use crate::transport::connection::run_connection;
 use serde_json::json;
 use std::collections::HashMap;
@@ -7,6 +8,7 @@ use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::{RwLock, broadcast};
 use tokio_util::sync::CancellationToken;
+use tokio_util::task::TaskTracker;
 
 #[tokio::test]
 async fn simultaneous_dial_both_sides_can_message() {
@@ -98,6 +100,7 @@ async fn superseded_connection_shutdown_preserves_newer_entry() {
 
     let shared_connections = Arc::new(RwLock::new(HashMap::new()));
     let (inbound_tx, _inbound_rx) = broadcast::channel(16);
+    let (connection_event_tx, mut connection_event_rx) = broadcast::channel(16);
     let cancel1 = CancellationToken::new();
     let cancel2 = CancellationToken::new();
 
@@ -105,10 +108,17 @@ async fn superseded_connection_shutdown_preserves_newer_entry() {
         conn1.clone(),
         pair.id_a.agent_id().to_string(),
         inbound_tx.clone(),
+        connection_event_tx.clone(),
         shared_connections.clone(),
         cancel1.clone(),
         None,
         Duration::from_secs(10),
+        Arc::default(),
+        Arc::default(),
+        Arc::default(),
+        Arc::default(),
+        Arc::default(),
+        TaskTracker::new(),
         None,
     ));
     wait_for_registered_connection(&shared_connections, pair.id_b.agent_id(), conn1.stable_id())
@@ -118,16 +128,25 @@ async fn superseded_connection_shutdown_preserves_newer_entry() {
         conn2.clone(),
         pair.id_a.agent_id().to_string(),
         inbound_tx,
+        connection_event_tx,
         shared_connections.clone(),
         cancel2.clone(),
         None,
         Duration::from_secs(10),
+        Arc::default(),
+        Arc::default(),
+        Arc::default(),
+        Arc::default(),
+        Arc::default(),
+        TaskTracker::new(),
         None,
     ));
     wait_for_registered_connection(&shared_connections, pair.id_b.agent_id(), conn2.stable_id())
         .await;
 
+    // A cancelled loop drains until its connection closes.
     cancel1.cancel();
+    conn1.close(0u32.into(), b"shutdown");
     tokio::time::timeout(Duration::from_secs(5), task1)
         .await
         .expect("task1 join timeout")
@@ -143,10 +162,78 @@ async fn superseded_connection_shutdown_preserves_newer_entry() {
         Some(conn2.stable_id()),
         "superseded loop must not remove newer connection entry"
     );
+    assert_eq!(
+        connection_event_rx.try_recv().expect("connected event"),
+        ConnectionEvent::Connected {
+            agent_id: pair.id_b.agent_id().to_string()
+        }
+    );
+    assert!(
+        connection_event_rx.try_recv().is_err(),
+        "superseding and superseded loops must not emit extra lifecycle events"
+    );
 
     cancel2.cancel();
+    conn2.close(0u32.into(), b"shutdown");
     tokio::time::timeout(Duration::from_secs(5), task2)
         .await
         .expect("task2 join timeout")
         .expect("task2 should exit cleanly");
+    assert_eq!(
+        connection_event_rx.try_recv().expect("disconnected event"),
+        ConnectionEvent::Disconnected {
+            agent_id: pair.id_b.agent_id().to_string(),
+            reason: "shutdown"
+        }
+    );
+}
+
+#[tokio::test]
+async fn connection_events_report_connect_and_peer_close() {
+    let pair = make_transport_pair().await;
+    let addr_b = pair.transport_b.local_addr().expect("local_addr b");
+    let peer_b = peer_record(&pair.id_b, addr_b);
+
+    let mut events_a = pair.transport_a.subscribe_connection_events();
+    let mut events_b = pair.transport_b.subscribe_connection_events();
+
+    pair.transport_a
+        .ensure_connection(&peer_b)
+        .await
+        .expect("A→B connect");
+
+    let connected = tokio::time::timeout(Duration::from_secs(5), events_a.recv())
+        .await
+        .expect("timeout waiting for connected")
+        .expect("connected event");
+    assert_eq!(
+        connected,
+        ConnectionEvent::Connected {
+            agent_id: pair.id_b.agent_id().to_string()
+        }
+    );
+    let accepted = tokio::time::timeout(Duration::from_secs(5), events_b.recv())
+        .await
+        .expect("timeout waiting for inbound connected")
+        .expect("inbound connected event");
+    assert_eq!(
+        accepted,
+        ConnectionEvent::Connected {
+            agent_id: pair.id_a.agent_id().to_string()
+        }
+    );
+
+    pair.transport_b.close_all().await;
+
+    let disconnected = tokio::time::timeout(Duration::from_secs(5), events_a.recv())
+        .await
+        .expect("timeout waiting for disconnected")
+        .expect("disconnected event");
+    assert_eq!(
+        disconnected,
+        ConnectionEvent::Disconnected {
+            agent_id: pair.id_b.agent_id().to_string(),
+            reason: "closed_by_peer"
+        }
+    );
 }
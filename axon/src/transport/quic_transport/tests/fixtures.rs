@@ -102,6 +102,9 @@ pub(super) fn peer_record(identity: &Identity, addr: SocketAddr) -> PeerRecord {
         status: ConnectionStatus::Discovered,
         rtt_ms: None,
         last_seen: std::time::Instant::now(),
+        first_seen_unix_ms: 0,
+        traffic: Default::default(),
+        software: None,
     }
 }
 
@@ -0,0 +1,236 @@
+use super::fixtures::{make_transport_pair, make_transport_pair_with_options, peer_record};
+use crate::message::{Envelope, MessageKind};
+use crate::transport::{ResponseHandlerFn, default_error_response};
+use serde_json::json;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+#[tokio::test]
+async fn send_request_bidirectional_default_error() {
+    let pair = make_transport_pair().await;
+    let addr_b = pair.transport_b.local_addr().expect("local_addr b");
+    let peer_b = peer_record(&pair.id_b, addr_b);
+
+    let request = Envelope::new(
+        pair.id_a.agent_id().to_string(),
+        pair.id_b.agent_id().to_string(),
+        MessageKind::Request,
+        json!({"question": "test?"}),
+    );
+
+    let result = pair
+        .transport_a
+        .send(&peer_b, request.clone())
+        .await
+        .expect("send");
+    let response = result.expect("expected response");
+    assert_eq!(response.kind, MessageKind::Error);
+    assert_eq!(response.ref_id, Some(request.id));
+}
+
+#[tokio::test]
+async fn send_request_rejects_invalid_bidirectional_reply() {
+    let handler: ResponseHandlerFn = Arc::new(|request| {
+        Box::pin(async move {
+            Some(Envelope::new(
+                request.to.as_deref().unwrap().to_string(),
+                request.from.as_deref().unwrap().to_string(),
+                MessageKind::Message,
+                json!({"unexpected": true}),
+            ))
+        })
+    });
+    let pair = make_transport_pair_with_options(128, 128, Some(handler)).await;
+    let addr_b = pair.transport_b.local_addr().expect("local_addr b");
+    let peer_b = peer_record(&pair.id_b, addr_b);
+
+    let request = Envelope::new(
+        pair.id_a.agent_id().to_string(),
+        pair.id_b.agent_id().to_string(),
+        MessageKind::Request,
+        json!({"question": "test?"}),
+    );
+
+    let err = pair
+        .transport_a
+        .send(&peer_b, request)
+        .await
+        .expect_err("invalid bidirectional reply should be rejected");
+    assert!(
+        err.to_string()
+            .contains("bidirectional reply must use response|error kind")
+    );
+}
+
+#[tokio::test]
+async fn send_with_timeout_honors_custom_request_timeout() {
+    let handler: ResponseHandlerFn = Arc::new(|request| {
+        Box::pin(async move {
+            tokio::time::sleep(Duration::from_millis(150)).await;
+            Some(default_error_response(
+                &request,
+                request.to.as_deref().unwrap(),
+            ))
+        })
+    });
+    let pair = make_transport_pair_with_options(128, 128, Some(handler)).await;
+    let addr_b = pair.transport_b.local_addr().expect("local_addr b");
+    let peer_b = peer_record(&pair.id_b, addr_b);
+
+    let request = Envelope::new(
+        pair.id_a.agent_id().to_string(),
+        pair.id_b.agent_id().to_string(),
+        MessageKind::Request,
+        json!({"question": "test?"}),
+    );
+
+    let err = pair
+        .transport_a
+        .send_with_timeout(&peer_b, request, Duration::from_millis(50))
+        .await
+        .expect_err("custom request timeout should be enforced");
+    assert!(err.to_string().contains("request timed out after 50ms"));
+}
+
+#[tokio::test]
+async fn inbound_rate_limit_rejects_requests_with_retryable_error() {
+    let pair = make_transport_pair().await;
+    pair.transport_b
+        .set_inbound_rate_limit(crate::transport::InboundRateLimit {
+            messages_per_sec: Some(1),
+            bytes_per_sec: None,
+        });
+    let addr_b = pair.transport_b.local_addr().expect("local_addr b");
+    let peer_b = peer_record(&pair.id_b, addr_b);
+    let request = || {
+        Envelope::new(
+            pair.id_a.agent_id().to_string(),
+            pair.id_b.agent_id().to_string(),
+            MessageKind::Request,
+            json!({"question": "test?"}),
+        )
+    };
+
+    let first = pair
+        .transport_a
+        .send(&peer_b, request())
+        .await
+        .expect("send")
+        .expect("response");
+    assert_eq!(first.payload_value().unwrap()["code"], json!("unhandled"));
+
+    let second = pair
+        .transport_a
+        .send(&peer_b, request())
+        .await
+        .expect("send")
+        .expect("response");
+    assert_eq!(second.kind, MessageKind::Error);
+    let payload = second.payload_value().unwrap();
+    assert_eq!(payload["code"], json!("rate_limited"));
+    assert_eq!(payload["retryable"], json!(true));
+    assert_eq!(pair.transport_b.rate_limited_count(), 1);
+}
+
+#[tokio::test]
+async fn drain_waits_for_in_flight_request_handlers() {
+    let handler: ResponseHandlerFn = Arc::new(|request| {
+        Box::pin(async move {
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            Some(Envelope::response_to(
+                &request,
+                request.to.as_deref().unwrap().to_string(),
+                MessageKind::Response,
+                json!({"slow": true}),
+            ))
+        })
+    });
+    let pair = make_transport_pair_with_options(128, 128, Some(handler)).await;
+    let addr_b = pair.transport_b.local_addr().expect("local_addr b");
+    let peer_b = peer_record(&pair.id_b, addr_b);
+    let request = Envelope::new(
+        pair.id_a.agent_id().to_string(),
+        pair.id_b.agent_id().to_string(),
+        MessageKind::Request,
+        json!({"question": "slow?"}),
+    );
+
+    let transport_a = pair.transport_a.clone();
+    let pending = tokio::spawn(async move { transport_a.send(&peer_b, request).await });
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while pair.transport_b.in_flight_streams() == 0 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("request never reached B");
+
+    assert!(
+        !pair.transport_b.drain(Duration::from_millis(10)).await,
+        "handler still running"
+    );
+    assert!(pair.transport_b.drain(Duration::from_secs(5)).await);
+    assert_eq!(pair.transport_b.in_flight_streams(), 0);
+    pair.transport_b.close_all().await;
+
+    let response = pending
+        .await
+        .expect("join")
+        .expect("send")
+        .expect("response");
+    assert_eq!(response.kind, MessageKind::Response);
+}
+
+#[tokio::test]
+async fn duplicate_request_answered_from_response_cache() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let handler_calls = calls.clone();
+    let handler: ResponseHandlerFn = Arc::new(move |request| {
+        let calls = handler_calls.clone();
+        Box::pin(async move {
+            let n = calls.fetch_add(1, Ordering::SeqCst) + 1;
+            Some(Envelope::response_to(
+                &request,
+                request.to.as_deref().unwrap().to_string(),
+                MessageKind::Response,
+                json!({"run": n}),
+            ))
+        })
+    });
+    let pair = make_transport_pair_with_options(128, 128, Some(handler)).await;
+    pair.transport_b
+        .set_response_cache(crate::transport::ResponseCacheLimit {
+            window: Some(Duration::from_secs(60)),
+            max_entries: 16,
+        });
+    let mut inbound_b = pair.transport_b.subscribe_inbound();
+    let addr_b = pair.transport_b.local_addr().expect("local_addr b");
+    let peer_b = peer_record(&pair.id_b, addr_b);
+    let request = Envelope::new(
+        pair.id_a.agent_id().to_string(),
+        pair.id_b.agent_id().to_string(),
+        MessageKind::Request,
+        json!({"task": "delegate"}),
+    );
+
+    let mut responses = Vec::new();
+    for _ in 0..2 {
+        responses.push(
+            pair.transport_a
+                .send(&peer_b, request.clone())
+                .await
+                .expect("send")
+                .expect("response"),
+        );
+    }
+    assert_eq!(calls.load(Ordering::SeqCst), 1, "handler ran once");
+    assert_eq!(responses[0].id, responses[1].id, "same cached response");
+    assert_eq!(responses[1].payload_value().unwrap()["run"], json!(1));
+
+    assert_eq!(inbound_b.recv().await.expect("inbound").id, request.id);
+    assert!(
+        inbound_b.try_recv().is_err(),
+        "duplicate not forwarded to IPC"
+    );
+}
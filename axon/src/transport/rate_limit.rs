@@ -0,0 +1,135 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
+
+/// Per-peer inbound limits. `None` disables the corresponding limit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct InboundRateLimit {
+    pub messages_per_sec: Option<u32>,
+    pub bytes_per_sec: Option<u64>,
+}
+
+impl InboundRateLimit {
+    fn is_unlimited(&self) -> bool {
+        self.messages_per_sec.is_none() && self.bytes_per_sec.is_none()
+    }
+}
+
+/// Token bucket holding at most one second of `rate`.
+///
+/// A message larger than the whole bucket is admitted once the bucket is full
+/// and leaves it in debt, so oversized messages are slowed rather than
+/// rejected forever.
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl Bucket {
+    fn full(rate: f64, now: Instant) -> Self {
+        Self {
+            tokens: rate,
+            updated: now,
+        }
+    }
+
+    fn refill(&mut self, rate: f64, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * rate).min(rate);
+        self.updated = now;
+    }
+
+    fn admits(&self, rate: f64, amount: f64) -> bool {
+        self.tokens >= amount.min(rate)
+    }
+}
+
+#[derive(Debug)]
+struct PeerBuckets {
+    messages: Bucket,
+    bytes: Bucket,
+}
+
+#[derive(Debug, Default)]
+struct LimiterState {
+    limit: InboundRateLimit,
+    peers: HashMap<String, PeerBuckets>,
+}
+
+/// Inbound message and byte rate limits, tracked per authenticated peer
+/// across all of that peer's connections.
+#[derive(Debug, Default)]
+pub(crate) struct InboundLimiter {
+    state: Mutex<LimiterState>,
+    rejected: AtomicU64,
+}
+
+impl InboundLimiter {
+    /// Replace the limits. Existing buckets restart full.
+    pub(crate) fn set_limit(&self, limit: InboundRateLimit) {
+        if let Ok(mut state) = self.state.lock() {
+            state.limit = limit;
+            state.peers.clear();
+        }
+    }
+
+    /// Account one inbound message of `bytes` from `peer_id`. Returns `false`
+    /// (and counts a rejection) when either limit is exhausted; nothing is
+    /// consumed in that case.
+    pub(crate) fn check(&self, peer_id: &str, bytes: usize) -> bool {
+        self.check_at(peer_id, bytes, Instant::now())
+    }
+
+    fn check_at(&self, peer_id: &str, bytes: usize, now: Instant) -> bool {
+        let Ok(mut state) = self.state.lock() else {
+            return true;
+        };
+        let limit = state.limit;
+        if limit.is_unlimited() {
+            return true;
+        }
+        let message_rate = limit.messages_per_sec.map(f64::from);
+        let byte_rate = limit.bytes_per_sec.map(|rate| rate as f64);
+
+        let buckets = state
+            .peers
+            .entry(peer_id.to_string())
+            .or_insert_with(|| PeerBuckets {
+                messages: Bucket::full(message_rate.unwrap_or(0.0), now),
+                bytes: Bucket::full(byte_rate.unwrap_or(0.0), now),
+            });
+        let bytes = bytes as f64;
+        let mut admitted = true;
+        if let Some(rate) = message_rate {
+            buckets.messages.refill(rate, now);
+            admitted &= buckets.messages.admits(rate, 1.0);
+        }
+        if let Some(rate) = byte_rate {
+            buckets.bytes.refill(rate, now);
+            admitted &= buckets.bytes.admits(rate, bytes);
+        }
+
+        if admitted {
+            if message_rate.is_some() {
+                buckets.messages.tokens -= 1.0;
+            }
+            if byte_rate.is_some() {
+                buckets.bytes.tokens -= bytes;
+            }
+        } else {
+            self.rejected.fetch_add(1, Ordering::Relaxed);
+        }
+        admitted
+    }
+
+    /// Messages rejected since startup.
+    pub(crate) fn rejected(&self) -> u64 {
+        self.rejected.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+#[path = "rate_limit_tests.rs"]
+mod tests;
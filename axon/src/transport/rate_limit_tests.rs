@@ -0,0 +1,64 @@
+use super::*;
+use std::time::Duration;
+
+const PEER_A: &str = "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+const PEER_B: &str = "ed25519.bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb";
+
+fn limiter(limit: InboundRateLimit) -> InboundLimiter {
+    let limiter = InboundLimiter::default();
+    limiter.set_limit(limit);
+    limiter
+}
+
+#[test]
+fn unlimited_by_default() {
+    let limiter = InboundLimiter::default();
+    let now = Instant::now();
+    assert!((0..10_000).all(|_| limiter.check_at(PEER_A, 1 << 20, now)));
+    assert_eq!(limiter.rejected(), 0);
+}
+
+#[test]
+fn message_rate_is_per_peer_and_refills() {
+    let limiter = limiter(InboundRateLimit {
+        messages_per_sec: Some(2),
+        bytes_per_sec: None,
+    });
+    let start = Instant::now();
+    assert!(limiter.check_at(PEER_A, 10, start));
+    assert!(limiter.check_at(PEER_A, 10, start));
+    assert!(!limiter.check_at(PEER_A, 10, start), "burst exhausted");
+    assert!(
+        limiter.check_at(PEER_B, 10, start),
+        "other peers unaffected"
+    );
+    assert!(limiter.check_at(PEER_A, 10, start + Duration::from_millis(500)));
+    assert_eq!(limiter.rejected(), 1);
+}
+
+#[test]
+fn byte_rate_admits_oversized_message_then_holds_debt() {
+    let limiter = limiter(InboundRateLimit {
+        messages_per_sec: None,
+        bytes_per_sec: Some(1000),
+    });
+    let start = Instant::now();
+    assert!(limiter.check_at(PEER_A, 3000, start), "full bucket admits");
+    assert!(!limiter.check_at(PEER_A, 1, start + Duration::from_secs(1)));
+    assert!(limiter.check_at(PEER_A, 1, start + Duration::from_secs(3)));
+}
+
+#[test]
+fn rejected_message_consumes_nothing() {
+    let limiter = limiter(InboundRateLimit {
+        messages_per_sec: Some(2),
+        bytes_per_sec: Some(100),
+    });
+    let now = Instant::now();
+    assert!(limiter.check_at(PEER_A, 60, now));
+    assert!(!limiter.check_at(PEER_A, 60, now), "byte budget exhausted");
+    assert!(
+        limiter.check_at(PEER_A, 40, now),
+        "rejection did not spend a message token"
+    );
+}
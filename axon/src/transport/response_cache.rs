@@ -0,0 +1,91 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use tokio::sync::OnceCell;
+use uuid::Uuid;
+
+use crate::message::Envelope;
+
+/// How long and how many request responses are kept for deduplication.
+/// A `None` window disables the cache.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResponseCacheLimit {
+    pub window: Option<Duration>,
+    pub max_entries: usize,
+}
+
+type Key = (String, Uuid);
+type Slot = Arc<OnceCell<Envelope>>;
+
+#[derive(Debug, Default)]
+struct CacheState {
+    limit: ResponseCacheLimit,
+    slots: HashMap<Key, Slot>,
+    /// Keys in insertion order, for expiry and eviction of the oldest entry.
+    order: VecDeque<(Instant, Key)>,
+}
+
+impl CacheState {
+    fn evict(&mut self, now: Instant, window: Duration) {
+        while let Some((inserted, key)) = self.order.front() {
+            let expired = now.saturating_duration_since(*inserted) >= window;
+            if !expired && self.order.len() < self.limit.max_entries {
+                break;
+            }
+            self.slots.remove(key);
+            self.order.pop_front();
+        }
+    }
+}
+
+/// Responses to inbound requests, keyed by (authenticated peer, request ID).
+///
+/// A sender retrying with the same idempotency key reuses the same request
+/// ID, so a duplicate within the window receives the first response instead
+/// of running the request again. Concurrent duplicates share one slot and
+/// wait for the first handler to finish.
+#[derive(Debug, Default)]
+pub(crate) struct ResponseCache {
+    state: Mutex<CacheState>,
+}
+
+impl ResponseCache {
+    /// Replace the window and capacity. Cached responses are dropped.
+    pub(crate) fn set_limit(&self, limit: ResponseCacheLimit) {
+        if let Ok(mut state) = self.state.lock() {
+            *state = CacheState {
+                limit,
+                ..CacheState::default()
+            };
+        }
+    }
+
+    /// Slot for `(peer_id, id)` and whether it was just created. Returns
+    /// `None` when the cache is disabled.
+    pub(crate) fn slot(&self, peer_id: &str, id: Uuid) -> Option<(Slot, bool)> {
+        self.slot_at(peer_id, id, Instant::now())
+    }
+
+    fn slot_at(&self, peer_id: &str, id: Uuid, now: Instant) -> Option<(Slot, bool)> {
+        let mut state = self.state.lock().ok()?;
+        let window = state.limit.window?;
+        if state.limit.max_entries == 0 {
+            return None;
+        }
+        state.evict(now, window);
+
+        let key = (peer_id.to_string(), id);
+        if let Some(slot) = state.slots.get(&key) {
+            return Some((slot.clone(), false));
+        }
+        let slot = Slot::default();
+        state.slots.insert(key.clone(), slot.clone());
+        state.order.push_back((now, key));
+        Some((slot, true))
+    }
+}
+
+#[cfg(test)]
+#[path = "response_cache_tests.rs"]
+mod tests;
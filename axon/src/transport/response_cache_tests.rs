@@ -0,0 +1,85 @@
+use super::*;
+use serde_json::json;
+
+use crate::message::MessageKind;
+
+const PEER_A: &str = "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+const PEER_B: &str = "ed25519.bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb";
+
+fn cache(window_secs: u64, max_entries: usize) -> ResponseCache {
+    let cache = ResponseCache::default();
+    cache.set_limit(ResponseCacheLimit {
+        window: Some(Duration::from_secs(window_secs)),
+        max_entries,
+    });
+    cache
+}
+
+fn response() -> Envelope {
+    Envelope::new(PEER_B, PEER_A, MessageKind::Response, json!({"ok": true}))
+}
+
+#[test]
+fn disabled_by_default() {
+    let cache = ResponseCache::default();
+    assert!(cache.slot(PEER_A, Uuid::new_v4()).is_none());
+}
+
+#[tokio::test]
+async fn duplicate_shares_first_slot() {
+    let cache = cache(60, 16);
+    let id = Uuid::new_v4();
+    let (slot, first) = cache.slot(PEER_A, id).unwrap();
+    assert!(first);
+    let cached = slot.get_or_init(|| async { response() }).await.clone();
+
+    let (again, first) = cache.slot(PEER_A, id).unwrap();
+    assert!(!first);
+    assert_eq!(again.get().map(|r| r.id), Some(cached.id));
+}
+
+#[test]
+fn keyed_per_peer() {
+    let cache = cache(60, 16);
+    let id = Uuid::new_v4();
+    assert!(cache.slot(PEER_A, id).unwrap().1);
+    assert!(
+        cache.slot(PEER_B, id).unwrap().1,
+        "same ID from another peer is a new request"
+    );
+}
+
+#[test]
+fn entries_expire_after_window() {
+    let cache = cache(60, 16);
+    let id = Uuid::new_v4();
+    let start = Instant::now();
+    assert!(cache.slot_at(PEER_A, id, start).unwrap().1);
+    assert!(
+        !cache
+            .slot_at(PEER_A, id, start + Duration::from_secs(59))
+            .unwrap()
+            .1
+    );
+    assert!(
+        cache
+            .slot_at(PEER_A, id, start + Duration::from_secs(60))
+            .unwrap()
+            .1
+    );
+}
+
+#[test]
+fn oldest_entry_evicted_at_capacity() {
+    let cache = cache(60, 2);
+    let ids: Vec<Uuid> = (0..3).map(|_| Uuid::new_v4()).collect();
+    let now = Instant::now();
+    for id in &ids {
+        cache.slot_at(PEER_A, *id, now);
+    }
+    assert!(!cache.slot_at(PEER_A, ids[2], now).unwrap().1);
+    assert!(
+        cache.slot_at(PEER_A, ids[0], now).unwrap().1,
+        "oldest entry evicted"
+    );
+}
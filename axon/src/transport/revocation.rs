@@ -0,0 +1,98 @@
+use std::collections::HashSet;
+use std::sync::Mutex;
+
+use tokio::sync::watch;
+
+use crate::peer_table::RevokedKeys;
+use crate::peer_token::derive_agent_id_from_pubkey_base64;
+
+/// Refused public keys, shared by the TLS verifiers and every connection
+/// loop: revoked keys and blocked peers' keys (or agent IDs, for peers
+/// blocked before their key was known). Replacing either set wakes
+/// the loops so connections that were already established with a
+/// now-refused key are closed.
+#[derive(Debug)]
+pub(crate) struct RevocationGate {
+    /// Union of `revoked` and `blocked`, read by the verifiers.
+    keys: RevokedKeys,
+    revoked: Mutex<HashSet<String>>,
+    blocked: Mutex<HashSet<String>>,
+    changed: watch::Sender<()>,
+}
+
+impl Default for RevocationGate {
+    fn default() -> Self {
+        Self {
+            keys: RevokedKeys::default(),
+            revoked: Mutex::default(),
+            blocked: Mutex::default(),
+            changed: watch::Sender::new(()),
+        }
+    }
+}
+
+impl RevocationGate {
+    /// The shared set, for the TLS verifiers.
+    pub(crate) fn keys(&self) -> RevokedKeys {
+        self.keys.clone()
+    }
+
+    pub(crate) fn set_keys(&self, keys: HashSet<String>) {
+        replace(&self.revoked, keys);
+        self.publish();
+    }
+
+    pub(crate) fn set_blocked(&self, keys: HashSet<String>) {
+        replace(&self.blocked, keys);
+        self.publish();
+    }
+
+    /// Why `pubkey` is refused (`"blocked"` or `"revoked"`), or `None`.
+    pub(crate) fn refusal(&self, pubkey: &str) -> Option<&'static str> {
+        let blocked_id = derive_agent_id_from_pubkey_base64(pubkey)
+            .is_ok_and(|agent_id| contains(&self.blocked, agent_id.as_str()));
+        if blocked_id || contains(&self.blocked, pubkey) {
+            Some("blocked")
+        } else if contains(&self.revoked, pubkey) {
+            Some("revoked")
+        } else {
+            None
+        }
+    }
+
+    /// Notified each time either set is replaced.
+    pub(crate) fn subscribe(&self) -> watch::Receiver<()> {
+        self.changed.subscribe()
+    }
+
+    fn publish(&self) {
+        let mut keys = snapshot(&self.revoked);
+        keys.extend(snapshot(&self.blocked));
+        match self.keys.write() {
+            Ok(mut refused) => *refused = keys,
+            Err(poisoned) => *poisoned.into_inner() = keys,
+        }
+        self.changed.send_replace(());
+    }
+}
+
+fn replace(set: &Mutex<HashSet<String>>, keys: HashSet<String>) {
+    match set.lock() {
+        Ok(mut current) => *current = keys,
+        Err(poisoned) => *poisoned.into_inner() = keys,
+    }
+}
+
+fn snapshot(set: &Mutex<HashSet<String>>) -> HashSet<String> {
+    match set.lock() {
+        Ok(current) => current.clone(),
+        Err(poisoned) => poisoned.into_inner().clone(),
+    }
+}
+
+fn contains(set: &Mutex<HashSet<String>>, pubkey: &str) -> bool {
+    match set.lock() {
+        Ok(current) => current.contains(pubkey),
+        Err(poisoned) => poisoned.into_inner().contains(pubkey),
+    }
+}
@@ -0,0 +1,111 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use anyhow::{Result, bail};
+
+use super::MAX_MESSAGE_SIZE_USIZE;
+use crate::message::MessageKind;
+
+/// Largest encoded envelope accepted per application kind, in bytes. `None`
+/// means the 64KB wire limit, which also bounds any larger value.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MessageSizeLimits {
+    pub request: Option<usize>,
+    pub response: Option<usize>,
+    pub message: Option<usize>,
+    pub error: Option<usize>,
+}
+
+impl MessageSizeLimits {
+    /// Cap for `kind`. Daemon-level and unknown kinds get the largest cap.
+    pub fn limit(&self, kind: MessageKind) -> usize {
+        let cap = match kind {
+            MessageKind::Request => self.request,
+            MessageKind::Response => self.response,
+            MessageKind::Message => self.message,
+            MessageKind::Error => self.error,
+            _ => return self.largest(),
+        };
+        cap.map_or(MAX_MESSAGE_SIZE_USIZE, |cap| {
+            cap.min(MAX_MESSAGE_SIZE_USIZE)
+        })
+    }
+
+    /// The most an inbound stream may carry before its kind is known.
+    pub fn largest(&self) -> usize {
+        [
+            MessageKind::Request,
+            MessageKind::Response,
+            MessageKind::Message,
+            MessageKind::Error,
+        ]
+        .into_iter()
+        .map(|kind| self.limit(kind))
+        .max()
+        .unwrap_or(MAX_MESSAGE_SIZE_USIZE)
+    }
+
+    /// These limits with every kind held to at most `cap` bytes.
+    pub fn capped(&self, cap: usize) -> Self {
+        let cap_kind = |kind| Some(self.limit(kind).min(cap));
+        Self {
+            request: cap_kind(MessageKind::Request),
+            response: cap_kind(MessageKind::Response),
+            message: cap_kind(MessageKind::Message),
+            error: cap_kind(MessageKind::Error),
+        }
+    }
+
+    /// Reject an encoded `kind` envelope of `len` bytes that exceeds its cap.
+    pub fn check(&self, kind: MessageKind, len: usize) -> Result<()> {
+        let limit = self.limit(kind);
+        if len > limit {
+            bail!("{kind} envelope is {len} bytes, over the {limit}-byte limit for its kind");
+        }
+        Ok(())
+    }
+}
+
+/// Current size caps, shared by all connections and replaced on reload.
+/// Peers with their own cap get the per-kind caps held to it.
+#[derive(Debug, Default)]
+pub(crate) struct SizeLimiter {
+    limits: Mutex<MessageSizeLimits>,
+    peer_caps: Mutex<HashMap<String, usize>>,
+}
+
+impl SizeLimiter {
+    pub(crate) fn set_limits(&self, limits: MessageSizeLimits) {
+        if let Ok(mut current) = self.limits.lock() {
+            *current = limits;
+        }
+    }
+
+    pub(crate) fn limits(&self) -> MessageSizeLimits {
+        self.limits.lock().map(|limits| *limits).unwrap_or_default()
+    }
+
+    pub(crate) fn set_peer_caps(&self, caps: HashMap<String, usize>) {
+        if let Ok(mut current) = self.peer_caps.lock() {
+            *current = caps;
+        }
+    }
+
+    /// Caps for traffic with `peer_id`.
+    pub(crate) fn limits_for(&self, peer_id: &str) -> MessageSizeLimits {
+        let limits = self.limits();
+        let cap = self
+            .peer_caps
+            .lock()
+            .ok()
+            .and_then(|caps| caps.get(peer_id).copied());
+        match cap {
+            Some(cap) => limits.capped(cap),
+            None => limits,
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "size_limit_tests.rs"]
+mod tests;
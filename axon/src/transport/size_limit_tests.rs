@@ -0,0 +1,78 @@
+use std::collections::HashMap;
+
+use super::*;
+
+#[test]
+fn unset_caps_use_the_wire_limit() {
+    let limits = MessageSizeLimits::default();
+    for kind in [
+        MessageKind::Request,
+        MessageKind::Response,
+        MessageKind::Message,
+        MessageKind::Error,
+        MessageKind::Heartbeat,
+    ] {
+        assert_eq!(limits.limit(kind), MAX_MESSAGE_SIZE_USIZE, "{kind}");
+    }
+    assert!(
+        limits
+            .check(MessageKind::Message, MAX_MESSAGE_SIZE_USIZE)
+            .is_ok()
+    );
+}
+
+#[test]
+fn caps_apply_per_kind_and_clamp_to_wire_limit() {
+    let limits = MessageSizeLimits {
+        message: Some(4096),
+        response: Some(1 << 20),
+        ..Default::default()
+    };
+    assert_eq!(limits.limit(MessageKind::Message), 4096);
+    assert_eq!(limits.limit(MessageKind::Response), MAX_MESSAGE_SIZE_USIZE);
+    assert!(limits.check(MessageKind::Message, 4096).is_ok());
+    let err = limits.check(MessageKind::Message, 4097).unwrap_err();
+    assert!(err.to_string().contains("4096-byte limit"), "{err}");
+    assert!(limits.check(MessageKind::Request, 4097).is_ok());
+}
+
+#[test]
+fn daemon_kinds_and_read_buffer_use_the_largest_cap() {
+    let limits = MessageSizeLimits {
+        request: Some(2048),
+        response: Some(8192),
+        message: Some(1024),
+        error: Some(1024),
+    };
+    assert_eq!(limits.largest(), 8192);
+    assert_eq!(limits.limit(MessageKind::SubscribeTopics), 8192);
+    assert_eq!(limits.limit(MessageKind::Unknown), 8192);
+}
+
+#[test]
+fn limiter_replaces_limits() {
+    let limiter = SizeLimiter::default();
+    assert_eq!(limiter.limits(), MessageSizeLimits::default());
+    let limits = MessageSizeLimits {
+        request: Some(2048),
+        ..Default::default()
+    };
+    limiter.set_limits(limits);
+    assert_eq!(limiter.limits(), limits);
+}
+
+#[test]
+fn peer_cap_holds_every_kind_to_it() {
+    let limiter = SizeLimiter::default();
+    limiter.set_limits(MessageSizeLimits {
+        message: Some(1024),
+        ..Default::default()
+    });
+    limiter.set_peer_caps(HashMap::from([("wan".to_string(), 4096)]));
+
+    let wan = limiter.limits_for("wan");
+    assert_eq!(wan.limit(MessageKind::Message), 1024);
+    assert_eq!(wan.limit(MessageKind::Request), 4096);
+    assert_eq!(wan.largest(), 4096);
+    assert_eq!(limiter.limits_for("lan"), limiter.limits());
+}
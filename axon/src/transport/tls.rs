@@ -18,9 +18,11 @@ use x509_parser::prelude::*;
 
 use crate::identity::QuicCertificate;
 use crate::message::Envelope;
-use crate::peer_table::PubkeyMap;
+use crate::peer_table::{PubkeyMap, RevokedKeys};
 use crate::transport::PairRequest;
 
+use super::congestion::Congestion;
+
 static CRYPTO_PROVIDER: OnceLock<()> = OnceLock::new();
 tokio::task_local! {
     static HANDSHAKE_REMOTE_ADDR: SocketAddr;
@@ -71,17 +73,25 @@ fn current_handshake_remote_addr() -> Option<SocketAddr> {
     HANDSHAKE_REMOTE_ADDR.try_with(|addr| *addr).ok()
 }
 
+/// A bound endpoint and the channels its verifiers and connections feed.
+pub(crate) struct BuiltEndpoint {
+    pub(crate) endpoint: quinn::Endpoint,
+    /// The endpoint's server config, replaced when congestion control changes.
+    pub(crate) server_config: quinn::ServerConfig,
+    /// The endpoint's default client config, for dials that override it.
+    pub(crate) client_config: quinn::ClientConfig,
+    pub(crate) inbound_tx: broadcast::Sender<Arc<Envelope>>,
+    pub(crate) pair_request_tx: broadcast::Sender<PairRequest>,
+}
+
 pub(crate) fn build_endpoint(
-    bind_addr: SocketAddr,
+    socket: std::net::UdpSocket,
     cert: &QuicCertificate,
     expected_pubkeys: PubkeyMap,
+    revoked: RevokedKeys,
     keepalive: Duration,
     idle_timeout: Duration,
-) -> Result<(
-    quinn::Endpoint,
-    broadcast::Sender<Arc<Envelope>>,
-    broadcast::Sender<PairRequest>,
-)> {
+) -> Result<BuiltEndpoint> {
     ensure_crypto_provider();
 
     let cert_chain = vec![CertificateDer::from(cert.cert_der.clone())];
@@ -94,6 +104,7 @@ pub(crate) fn build_endpoint(
         .context("failed to extract certificate subject for mTLS")?;
     let mtls_verifier = PeerClientCertVerifier {
         expected_pubkeys: expected_pubkeys.clone(),
+        revoked: revoked.clone(),
         roots: vec![DistinguishedName::from(subject_dn)],
         pair_request_tx: pair_request_tx.clone(),
         pair_request_seen: pair_request_seen.clone(),
@@ -105,30 +116,35 @@ pub(crate) fn build_endpoint(
         .context("failed to build rustls server config")?;
     rustls_server.alpn_protocols = vec![b"axon/1".to_vec()];
     rustls_server.max_early_data_size = 0;
+    // Session resumption would skip the verifiers, letting a peer revoked
+    // after its first handshake back in. Every connection verifies in full.
+    rustls_server.session_storage = Arc::new(rustls::server::NoServerSessionStorage {});
+    rustls_server.send_tls13_tickets = 0;
 
     let quic_server_config = QuicServerConfig::try_from(rustls_server)
         .context("failed to build QUIC server config from rustls")?;
     let mut server_config = quinn::ServerConfig::with_crypto(Arc::new(quic_server_config));
 
-    let transport_config = Arc::new({
-        let mut config = quinn::TransportConfig::default();
-        config.keep_alive_interval(Some(keepalive));
-        config.max_concurrent_bidi_streams(8u32.into());
-        config.max_concurrent_uni_streams(16u32.into());
-        if let Ok(idle) = quinn::IdleTimeout::try_from(idle_timeout) {
-            config.max_idle_timeout(Some(idle));
-        }
-        config
-    });
+    let transport_config = Arc::new(transport_config(
+        keepalive,
+        idle_timeout,
+        Congestion::default(),
+    ));
     server_config.transport = transport_config.clone();
 
-    let mut endpoint = quinn::Endpoint::server(server_config, bind_addr)
-        .with_context(|| format!("failed to bind QUIC endpoint on {bind_addr}"))?;
+    let mut endpoint = quinn::Endpoint::new(
+        quinn::EndpointConfig::default(),
+        Some(server_config.clone()),
+        socket,
+        Arc::new(quinn::TokioRuntime),
+    )
+    .context("failed to create QUIC endpoint")?;
 
     let mut rustls_client = rustls::ClientConfig::builder()
         .dangerous()
         .with_custom_certificate_verifier(Arc::new(PeerCertVerifier {
             expected_pubkeys,
+            revoked,
             pair_request_tx: pair_request_tx.clone(),
             pair_request_seen,
         }))
@@ -136,15 +152,39 @@ pub(crate) fn build_endpoint(
         .context("failed to configure client mTLS certificate")?;
     rustls_client.alpn_protocols = vec![b"axon/1".to_vec()];
     rustls_client.enable_early_data = false;
+    rustls_client.resumption = rustls::client::Resumption::disabled();
 
     let quic_client_config = QuicClientConfig::try_from(rustls_client)
         .context("failed to build QUIC client config from rustls")?;
     let mut client_config = quinn::ClientConfig::new(Arc::new(quic_client_config));
     client_config.transport_config(transport_config);
-    endpoint.set_default_client_config(client_config);
+    endpoint.set_default_client_config(client_config.clone());
 
     let (inbound_tx, _) = broadcast::channel(512);
-    Ok((endpoint, inbound_tx, pair_request_tx))
+    Ok(BuiltEndpoint {
+        endpoint,
+        server_config,
+        client_config,
+        inbound_tx,
+        pair_request_tx,
+    })
+}
+
+/// QUIC transport settings shared by accepted and dialed connections.
+pub(crate) fn transport_config(
+    keepalive: Duration,
+    idle_timeout: Duration,
+    congestion: Congestion,
+) -> quinn::TransportConfig {
+    let mut config = quinn::TransportConfig::default();
+    config.congestion_controller_factory(congestion.factory());
+    config.keep_alive_interval(Some(keepalive));
+    config.max_concurrent_bidi_streams(8u32.into());
+    config.max_concurrent_uni_streams(16u32.into());
+    if let Ok(idle) = quinn::IdleTimeout::try_from(idle_timeout) {
+        config.max_idle_timeout(Some(idle));
+    }
+    config
 }
 
 // ---------------------------------------------------------------------------
@@ -154,6 +194,7 @@ pub(crate) fn build_endpoint(
 #[derive(Debug)]
 struct PeerCertVerifier {
     expected_pubkeys: PubkeyMap,
+    revoked: RevokedKeys,
     pair_request_tx: broadcast::Sender<PairRequest>,
     pair_request_seen: Arc<Mutex<HashMap<String, Instant>>>,
 }
@@ -161,11 +202,33 @@ struct PeerCertVerifier {
 #[derive(Debug)]
 struct PeerClientCertVerifier {
     expected_pubkeys: PubkeyMap,
+    revoked: RevokedKeys,
     roots: Vec<DistinguishedName>,
     pair_request_tx: broadcast::Sender<PairRequest>,
     pair_request_seen: Arc<Mutex<HashMap<String, Instant>>>,
 }
 
+/// Reject a revoked or blocked key before any pinning or pair_request
+/// handling, so such a peer is refused even if it is still pinned or
+/// discovered. A peer blocked before its key was known is listed by agent ID.
+fn reject_revoked(
+    revoked: &RevokedKeys,
+    agent_id: &str,
+    pubkey: &str,
+) -> std::result::Result<(), rustls::Error> {
+    // std::sync required: rustls verifier callbacks are synchronous
+    let revoked = revoked
+        .read()
+        .map_err(|_| rustls::Error::General("revocation list lock poisoned".to_string()))?;
+    if revoked.contains(pubkey) || revoked.contains(agent_id) {
+        warn!(agent_id = %agent_id, "rejected peer with revoked or blocked key during TLS verification");
+        return Err(rustls::Error::General(format!(
+            "rejecting peer {agent_id}: its public key is revoked or blocked"
+        )));
+    }
+    Ok(())
+}
+
 const PAIR_REQUEST_LOG_WINDOW: Duration = Duration::from_secs(30);
 
 fn maybe_emit_pair_request(
@@ -228,6 +291,7 @@ impl ServerCertVerifier for PeerCertVerifier {
                 "server cert public key does not match expected agent_id".to_string(),
             ));
         }
+        reject_revoked(&self.revoked, &expected_agent_id, &cert_key_b64)?;
 
         // std::sync required: rustls verifier callbacks are synchronous
         let expected = self
@@ -308,6 +372,7 @@ impl ClientCertVerifier for PeerClientCertVerifier {
             })?;
         let cert_pubkey_b64 = STANDARD.encode(cert_key);
         let agent_id = derive_agent_id_from_pubkey_bytes(&cert_key);
+        reject_revoked(&self.revoked, &agent_id, &cert_pubkey_b64)?;
 
         // std::sync required: rustls verifier callbacks are synchronous
         let expected = self
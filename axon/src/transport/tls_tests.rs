@@ -1,7 +1,7 @@
 use super::*;
 use crate::config::AxonPaths;
 use crate::identity::Identity;
-use crate::peer_table::PeerTable;
+use crate::peer_table::{PeerTable, RevokedKeys};
 use rustls::SignatureScheme;
 use rustls::client::danger::ServerCertVerifier;
 use rustls::server::danger::ClientCertVerifier;
@@ -33,6 +33,7 @@ fn make_test_verifier() -> PeerCertVerifier {
     let (pair_request_tx, _) = broadcast::channel(8);
     PeerCertVerifier {
         expected_pubkeys: PeerTable::new().pubkey_map(),
+        revoked: RevokedKeys::default(),
         pair_request_tx,
         pair_request_seen: Arc::new(Mutex::new(HashMap::new())),
     }
@@ -42,6 +43,7 @@ fn make_test_client_verifier() -> PeerClientCertVerifier {
     let (pair_request_tx, _) = broadcast::channel(8);
     PeerClientCertVerifier {
         expected_pubkeys: PeerTable::new().pubkey_map(),
+        revoked: RevokedKeys::default(),
         roots: vec![],
         pair_request_tx,
         pair_request_seen: Arc::new(Mutex::new(HashMap::new())),
@@ -134,6 +136,7 @@ fn server_verifier_accepts_known_peer() {
 
     let verifier = PeerCertVerifier {
         expected_pubkeys: pubkey_map,
+        revoked: RevokedKeys::default(),
         pair_request_tx: broadcast::channel(8).0,
         pair_request_seen: Arc::new(Mutex::new(HashMap::new())),
     };
@@ -171,6 +174,7 @@ fn server_verifier_accepts_uppercase_expected_agent_id() {
 
     let verifier = PeerCertVerifier {
         expected_pubkeys: pubkey_map,
+        revoked: RevokedKeys::default(),
         pair_request_tx: broadcast::channel(8).0,
         pair_request_seen: Arc::new(Mutex::new(HashMap::new())),
     };
@@ -207,6 +211,7 @@ fn server_verifier_rejects_pubkey_mismatch() {
 
     let verifier = PeerCertVerifier {
         expected_pubkeys: pubkey_map,
+        revoked: RevokedKeys::default(),
         pair_request_tx: broadcast::channel(8).0,
         pair_request_seen: Arc::new(Mutex::new(HashMap::new())),
     };
@@ -259,6 +264,7 @@ fn client_verifier_accepts_uppercase_peer_table_key() {
 
     let verifier = PeerClientCertVerifier {
         expected_pubkeys: pubkey_map,
+        revoked: RevokedKeys::default(),
         roots: vec![],
         pair_request_tx: broadcast::channel(8).0,
         pair_request_seen: Arc::new(Mutex::new(HashMap::new())),
@@ -296,6 +302,7 @@ fn unknown_peer_pair_request_is_rate_limited() {
     let (pair_request_tx, mut pair_request_rx) = broadcast::channel(8);
     let verifier = PeerCertVerifier {
         expected_pubkeys: PeerTable::new().pubkey_map(),
+        revoked: RevokedKeys::default(),
         pair_request_tx,
         pair_request_seen: Arc::new(Mutex::new(HashMap::new())),
     };
@@ -334,6 +341,7 @@ async fn unknown_peer_pair_request_includes_remote_addr_from_handshake_context()
     let (pair_request_tx, mut pair_request_rx) = broadcast::channel(8);
     let verifier = PeerCertVerifier {
         expected_pubkeys: PeerTable::new().pubkey_map(),
+        revoked: RevokedKeys::default(),
         pair_request_tx,
         pair_request_seen: Arc::new(Mutex::new(HashMap::new())),
     };
@@ -354,3 +362,59 @@ async fn unknown_peer_pair_request_includes_remote_addr_from_handshake_context()
     assert_eq!(first.agent_id, agent_id_string);
     assert_eq!(first.addr.as_deref(), Some("127.0.0.1:7444"));
 }
+
+#[test]
+fn verifiers_reject_revoked_key_even_when_pinned() {
+    ensure_crypto_provider();
+    let dir = tempdir().expect("tempdir");
+    let paths = AxonPaths::from_root(PathBuf::from(dir.path()));
+    let identity = Identity::load_or_generate(&paths).expect("identity");
+    let cert = identity.make_quic_certificate().expect("cert");
+    let cert_der = CertificateDer::from(cert.cert_der);
+    let agent_id_string = identity.agent_id().to_string();
+    let server_name = ServerName::try_from(agent_id_string.as_str()).unwrap();
+
+    let pubkey_map = Arc::new(StdRwLock::new(HashMap::new()));
+    pubkey_map.write().unwrap().insert(
+        agent_id_string.clone(),
+        identity.public_key_base64().to_string(),
+    );
+    let revoked = RevokedKeys::default();
+    revoked
+        .write()
+        .unwrap()
+        .insert(identity.public_key_base64().to_string());
+
+    let (pair_request_tx, mut pair_request_rx) = broadcast::channel(8);
+    let server_verifier = PeerCertVerifier {
+        expected_pubkeys: pubkey_map.clone(),
+        revoked: revoked.clone(),
+        pair_request_tx: pair_request_tx.clone(),
+        pair_request_seen: Arc::new(Mutex::new(HashMap::new())),
+    };
+    let client_verifier = PeerClientCertVerifier {
+        expected_pubkeys: pubkey_map,
+        revoked,
+        roots: vec![],
+        pair_request_tx,
+        pair_request_seen: Arc::new(Mutex::new(HashMap::new())),
+    };
+
+    let server = server_verifier.verify_server_cert(
+        &cert_der,
+        &[],
+        &server_name,
+        &[],
+        rustls::pki_types::UnixTime::now(),
+    );
+    let client =
+        client_verifier.verify_client_cert(&cert_der, &[], rustls::pki_types::UnixTime::now());
+    for result in [server.err(), client.err()] {
+        let err = result.expect("revoked key must be rejected");
+        assert!(err.to_string().contains("revoked"), "got: {err}");
+    }
+    assert!(
+        pair_request_rx.try_recv().is_err(),
+        "revoked peers must not raise pair_request events"
+    );
+}
@@ -34,6 +34,9 @@ async fn concurrent_ipc_flood() {
                             peers_connected: 0,
                             messages_sent: 0,
                             messages_received: 0,
+                            messages_rate_limited: 0,
+                            lifetime: Default::default(),
+                            degraded: Vec::new(),
                             req_id: None,
                         },
                     )
@@ -123,6 +126,9 @@ async fn ipc_malformed_input_resilience() {
                             peers_connected: 0,
                             messages_sent: 0,
                             messages_received: 0,
+                            messages_rate_limited: 0,
+                            lifetime: Default::default(),
+                            degraded: Vec::new(),
                             req_id: None,
                         },
                     )
@@ -198,6 +204,9 @@ async fn ipc_client_disconnect_under_load() {
                             peers_connected: 0,
                             messages_sent: 0,
                             messages_received: 0,
+                            messages_rate_limited: 0,
+                            lifetime: Default::default(),
+                            degraded: Vec::new(),
                             req_id: None,
                         },
                     )
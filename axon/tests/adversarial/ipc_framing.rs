@@ -80,6 +80,9 @@ async fn ipc_partial_data_then_eof() {
                             peers_connected: 0,
                             messages_sent: 0,
                             messages_received: 0,
+                            messages_rate_limited: 0,
+                            lifetime: Default::default(),
+                            degraded: Vec::new(),
                             req_id: None,
                         },
                     )
@@ -143,6 +146,9 @@ async fn ipc_invalid_utf8_closes_cleanly() {
                             peers_connected: 0,
                             messages_sent: 0,
                             messages_received: 0,
+                            messages_rate_limited: 0,
+                            lifetime: Default::default(),
+                            degraded: Vec::new(),
                             req_id: None,
                         },
                     )
@@ -14,6 +14,9 @@ fn envelope_validation_edge_cases() {
         kind: MessageKind::Request,
         ref_id: None,
         payload: Envelope::raw_json(&json!({})),
+        topic: None,
+        thread_id: None,
+        headers: serde_json::Map::new(),
         from: Some("ed25519.A1B2C3D4E5F6A7B8A1B2C3D4E5F6A7B8".into()),
         to: Some(agent_b().into()),
     };
@@ -25,6 +28,9 @@ fn envelope_validation_edge_cases() {
         kind: MessageKind::Request,
         ref_id: None,
         payload: Envelope::raw_json(&json!({})),
+        topic: None,
+        thread_id: None,
+        headers: serde_json::Map::new(),
         from: Some(agent_a().into()),
         to: Some(agent_b().into()),
     };
@@ -36,6 +42,9 @@ fn envelope_validation_edge_cases() {
         kind: MessageKind::Message,
         ref_id: None,
         payload: Envelope::raw_json(&json!({})),
+        topic: None,
+        thread_id: None,
+        headers: serde_json::Map::new(),
         from: None,
         to: None,
     };
@@ -182,6 +191,7 @@ async fn known_peers_corruption_resilience() {
         addr: "10.0.0.1:7100".parse().unwrap(),
         pubkey: "Zm9v".to_string(),
         last_seen_unix_ms: 1000,
+        first_seen_unix_ms: None,
         source: axon::config::KnownPeerSource::Discovered,
     }];
     save_known_peers(&path, &valid).await.unwrap();
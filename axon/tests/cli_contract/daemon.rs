@@ -0,0 +1,241 @@
+use super::*;
+
+#[test]
+fn service_install_systemd_writes_units_and_refuses_overwrite() {
+    let bin = axon_bin();
+    let root = tempdir().expect("tempdir");
+    let unit_dir = root.path().join("units");
+    let install = |extra: &[&str]| {
+        let mut cmd = Command::new(&bin);
+        cmd.args([
+            "--state-root",
+            root.path().to_str().expect("utf8 path"),
+            "service",
+            "install",
+            "--systemd",
+            "--socket-activation",
+            "--unit-dir",
+            unit_dir.to_str().expect("utf8 path"),
+        ]);
+        cmd.args(extra);
+        run_command(&mut cmd)
+    };
+
+    let output = install(&[]);
+    assert!(output.status.success(), "{output:?}");
+    let service = std::fs::read_to_string(unit_dir.join("axon.service")).expect("service unit");
+    assert!(service.contains("Type=notify"));
+    let socket = std::fs::read_to_string(unit_dir.join("axon.socket")).expect("socket unit");
+    assert!(socket.contains(&format!(
+        "ListenStream={}",
+        root.path().join("axon.sock").display()
+    )));
+
+    let output = install(&[]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--force"));
+
+    let output = install(&["--force"]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains(
+        "Next: systemctl --user daemon-reload && systemctl --user enable --now axon.socket"
+    ));
+
+    let service = |sub: &str| {
+        run_command(Command::new(&bin).args([
+            "service",
+            sub,
+            "--systemd",
+            "--unit-dir",
+            unit_dir.to_str().expect("utf8 path"),
+        ]))
+    };
+    let output = service("status");
+    assert!(output.status.success(), "{output:?}");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Manager: systemd (user)"), "{stdout}");
+    assert!(stdout.contains("axon.socket"), "{stdout}");
+
+    let output = service("uninstall");
+    assert!(output.status.success(), "{output:?}");
+    assert!(!unit_dir.join("axon.service").exists());
+    assert!(!unit_dir.join("axon.socket").exists());
+    let stdout = String::from_utf8_lossy(&service("status").stdout).into_owned();
+    assert!(stdout.contains("Installed: no"), "{stdout}");
+}
+
+#[test]
+fn service_install_launchd_prints_plist_and_rejects_socket_activation() {
+    let bin = axon_bin();
+    let root = tempdir().expect("tempdir");
+    let root_str = root.path().to_str().expect("utf8 path");
+
+    let output = run_command(Command::new(&bin).args([
+        "--state-root",
+        root_str,
+        "service",
+        "install",
+        "--launchd",
+        "--print",
+    ]));
+    assert!(output.status.success(), "{output:?}");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("# com.github.hwbehrens.axon.plist"),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains(&format!("<string>{root_str}</string>")),
+        "{stdout}"
+    );
+
+    let output = run_command(Command::new(&bin).args([
+        "service",
+        "install",
+        "--launchd",
+        "--socket-activation",
+        "--print",
+    ]));
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn stop_without_running_daemon_reports_not_running() {
+    let bin = axon_bin();
+    let root = tempdir().expect("tempdir");
+    let output = run_command(Command::new(&bin).args([
+        "--state-root",
+        root.path().to_str().expect("utf8 path"),
+        "stop",
+    ]));
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("daemon not running"));
+}
+
+#[test]
+fn daemon_self_test_passes_and_cleans_up() {
+    let bin = axon_bin();
+    let tmp = tempdir().expect("tempdir");
+    let output = run_command(
+        Command::new(&bin)
+            .args(["--quiet", "daemon", "--self-test"])
+            .env("TMPDIR", tmp.path()),
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "self-test failed:\n{stdout}");
+    assert!(stdout.contains("Self-test: ✓ PASS"), "{stdout}");
+    for step in [
+        "request_response",
+        "request_error",
+        "message_inbound",
+        "ipc_shutdown",
+    ] {
+        assert!(
+            stdout.contains(&format!("✓ {step}:")),
+            "missing {step}:\n{stdout}"
+        );
+    }
+    assert_eq!(
+        fs::read_dir(tmp.path()).expect("read tmp").count(),
+        0,
+        "temporary state root left behind"
+    );
+}
+
+#[test]
+fn daemon_not_running_returns_exit_code_seven() {
+    let bin = axon_bin();
+    let root = tempdir().expect("tempdir");
+
+    let output = run_command(Command::new(&bin).args([
+        "--state-root",
+        root.path().to_str().expect("utf8 path"),
+        "status",
+    ]));
+    assert_eq!(output.status.code(), Some(7));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Is the daemon running?"));
+}
+
+#[test]
+fn daemon_error_line_is_reported_instead_of_generic_eof_error() {
+    let bin = axon_bin();
+    let root = tempdir().expect("tempdir");
+    let Some(server) = require_socket_server(
+        root.path(),
+        json!({
+            "ok": false,
+            "error": "command_too_large",
+            "message": "IPC command exceeds 64KB limit"
+        }),
+    ) else {
+        return;
+    };
+
+    let output = run_command(Command::new(&bin).args([
+        "--state-root",
+        root.path().to_str().expect("utf8 path"),
+        "status",
+    ]));
+    assert_eq!(output.status.code(), Some(2));
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("\"error\": \"command_too_large\""));
+    assert!(stdout.contains("IPC command exceeds 64KB limit"));
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        !stderr.contains("daemon closed connection without a command response"),
+        "CLI should surface daemon error response before EOF"
+    );
+
+    let command = server.join().expect("server thread");
+    assert_eq!(command["cmd"], "status");
+}
+
+#[test]
+fn reset_clears_state_and_keeps_config() {
+    let root = tempdir().expect("tempdir");
+    let root_str = root.path().to_str().expect("utf8 path");
+    fs::write(root.path().join("known_peers.json"), "[]").expect("write known peers");
+    fs::write(root.path().join("identity.key"), "seed").expect("write key");
+    fs::write(root.path().join("config.yaml"), "port: 7100\n").expect("write config");
+
+    let refused = run_command(
+        Command::new(axon_bin())
+            .args(["--state-root", root_str, "reset", "--keep-identity"])
+            .stdin(Stdio::null()),
+    );
+    assert_eq!(refused.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&refused.stderr).contains("--yes"));
+    assert!(root.path().join("known_peers.json").exists());
+
+    let output = run_command(Command::new(axon_bin()).args([
+        "--state-root",
+        root_str,
+        "reset",
+        "--keep-identity",
+        "--yes",
+    ]));
+    assert!(output.status.success(), "{output:?}");
+    assert!(!root.path().join("known_peers.json").exists());
+    assert!(root.path().join("identity.key").exists());
+    assert!(root.path().join("config.yaml").exists());
+}
+
+#[test]
+fn reset_refuses_while_daemon_is_running() {
+    let root = tempdir().expect("tempdir");
+    let root_str = root.path().to_str().expect("utf8 path");
+    // The test process itself stands in for a live daemon.
+    fs::write(
+        root.path().join("daemon.pid"),
+        std::process::id().to_string(),
+    )
+    .expect("write pidfile");
+
+    let output =
+        run_command(Command::new(axon_bin()).args(["--state-root", root_str, "reset", "--yes"]));
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("daemon is running"));
+    assert!(root.path().join("daemon.pid").exists());
+}
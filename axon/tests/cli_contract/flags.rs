@@ -0,0 +1,77 @@
+use super::*;
+
+#[test]
+fn version_flags_print_version_and_exit_zero() {
+    let bin = axon_bin();
+    let expected = env!("CARGO_PKG_VERSION");
+
+    let long = run_command(Command::new(&bin).arg("--version"));
+    assert!(long.status.success());
+    assert!(String::from_utf8_lossy(&long.stdout).contains(expected));
+
+    let short = run_command(Command::new(&bin).arg("-V"));
+    assert!(short.status.success());
+    assert!(String::from_utf8_lossy(&short.stdout).contains(expected));
+}
+
+#[test]
+fn verbosity_flags_are_listed_in_help() {
+    let bin = axon_bin();
+    let output = run_command(Command::new(&bin).arg("--help"));
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("--verbose"));
+    assert!(stdout.contains("-v"));
+    assert!(stdout.contains("--quiet"));
+    assert!(stdout.contains("-q"));
+}
+
+#[test]
+fn root_flag_aliases_route_client_commands_to_same_socket_contract() {
+    let bin = axon_bin();
+    for flag in ["--state-root", "--state", "--root"] {
+        let root = tempdir().expect("tempdir");
+        let Some(server) = require_socket_server(
+            root.path(),
+            json!({
+                "ok": true,
+                "uptime_secs": 1,
+                "peers_connected": 0,
+                "messages_sent": 0,
+                "messages_received": 0
+            }),
+        ) else {
+            return;
+        };
+
+        let output = run_command(Command::new(&bin).args([
+            flag,
+            root.path().to_str().expect("utf8 path"),
+            "status",
+        ]));
+        assert!(output.status.success(), "{flag} should succeed");
+
+        let command = server.join().expect("server thread");
+        assert_eq!(command["cmd"], "status");
+    }
+}
+
+#[test]
+fn axon_root_env_routes_client_command_without_flag() {
+    let bin = axon_bin();
+    let root = tempdir().expect("tempdir");
+    let Some(server) = require_socket_server(root.path(), json!({"ok": true, "peers": []})) else {
+        return;
+    };
+
+    let output = run_command(
+        Command::new(&bin)
+            .arg("peers")
+            .env("AXON_ROOT", root.path().to_str().expect("utf8 path")),
+    );
+    assert!(output.status.success());
+
+    let command = server.join().expect("server thread");
+    assert_eq!(command["cmd"], "peers");
+}
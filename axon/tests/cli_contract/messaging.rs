@@ -0,0 +1,483 @@
+use super::*;
+
+#[test]
+fn command_reply_ignores_unsolicited_event_lines() {
+    let bin = axon_bin();
+    let root = tempdir().expect("tempdir");
+    let Some(server) = require_socket_server_with_replies(
+        root.path(),
+        vec![
+            json!({
+                "event": "pair_request",
+                "agent_id": VALID_AGENT_ID,
+                "pubkey": "Zm9v",
+                "addr": "127.0.0.1:7100"
+            }),
+            json!({
+                "ok": true,
+                "uptime_secs": 7,
+                "peers_connected": 0,
+                "messages_sent": 1,
+                "messages_received": 2
+            }),
+        ],
+    ) else {
+        return;
+    };
+
+    let output = run_command(Command::new(&bin).args([
+        "--state-root",
+        root.path().to_str().expect("utf8 path"),
+        "status",
+    ]));
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Uptime: 7s"));
+    assert!(!stdout.contains("pair_request"));
+
+    let command = server.join().expect("server thread");
+    assert_eq!(command["cmd"], "status");
+}
+
+#[test]
+fn invalid_agent_id_rejected_at_cli_boundary() {
+    let bin = axon_bin();
+    let output = run_command(Command::new(&bin).args(["request", "banana", "hello"]));
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("invalid agent_id"));
+}
+
+#[test]
+fn send_subcommand_is_rejected() {
+    let bin = axon_bin();
+    let output = run_command(Command::new(&bin).args(["send", VALID_AGENT_ID, "hello"]));
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("unrecognized subcommand"));
+}
+
+#[test]
+fn notify_json_mode_rejects_invalid_payload() {
+    let bin = axon_bin();
+    let output =
+        run_command(Command::new(&bin).args(["notify", "--json", VALID_AGENT_ID, "{\"x\":"]));
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("invalid JSON for --json payload"));
+}
+
+#[test]
+fn notify_default_sends_literal_string_payload() {
+    let bin = axon_bin();
+    let root = tempdir().expect("tempdir");
+    let Some(server) = require_socket_server(root.path(), json!({"ok": true, "msg_id": "x"}))
+    else {
+        return;
+    };
+
+    let output = run_command(Command::new(&bin).args([
+        "--state-root",
+        root.path().to_str().expect("utf8 path"),
+        "notify",
+        VALID_AGENT_ID,
+        "{\"x\":",
+    ]));
+    assert!(output.status.success());
+
+    let command = server.join().expect("server thread");
+    assert_eq!(command["cmd"], "send");
+    assert_eq!(command["kind"], "message");
+    assert_eq!(command["payload"]["data"], "{\"x\":");
+}
+
+#[test]
+fn request_reads_structured_payload_from_stdin() {
+    let bin = axon_bin();
+    let root = tempdir().expect("tempdir");
+    let Some(server) = require_socket_server(root.path(), json!({"ok": true, "msg_id": "x"}))
+    else {
+        return;
+    };
+
+    let mut child = Command::new(&bin)
+        .args([
+            "--state-root",
+            root.path().to_str().expect("utf8 path"),
+            "request",
+            VALID_AGENT_ID,
+            "--payload",
+            "-",
+        ])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("spawn axon request");
+    child
+        .stdin
+        .take()
+        .expect("stdin")
+        .write_all(br#"{"task": "build", "args": ["--release"]}"#)
+        .expect("write stdin");
+    let output = child.wait_with_output().expect("wait for request");
+    assert!(output.status.success(), "{output:?}");
+
+    let command = server.join().expect("server thread");
+    assert_eq!(command["kind"], "request");
+    assert_eq!(
+        command["payload"],
+        json!({"task": "build", "args": ["--release"]})
+    );
+}
+
+#[test]
+fn notify_rejects_oversized_payload_file() {
+    let bin = axon_bin();
+    let root = tempdir().expect("tempdir");
+    let path = root.path().join("payload.json");
+    fs::write(&path, json!({"blob": "x".repeat(70_000)}).to_string()).expect("write payload");
+
+    let output = run_command(Command::new(&bin).args([
+        "--state-root",
+        root.path().to_str().expect("utf8 path"),
+        "notify",
+        VALID_AGENT_ID,
+        "--payload-file",
+        path.to_str().expect("utf8 path"),
+    ]));
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("65536-byte message limit"), "{stderr}");
+}
+
+#[test]
+fn cancel_sends_message_referencing_request() {
+    let bin = axon_bin();
+    let root = tempdir().expect("tempdir");
+    let Some(server) = require_socket_server(root.path(), json!({"ok": true, "msg_id": "x"}))
+    else {
+        return;
+    };
+    let request_id = "6f1c1a4e-2d3b-4c5a-9e8f-0a1b2c3d4e5f";
+
+    let output = run_command(Command::new(&bin).args([
+        "--state-root",
+        root.path().to_str().expect("utf8 path"),
+        "cancel",
+        VALID_AGENT_ID,
+        "--ref",
+        request_id,
+        "--reason",
+        "no longer needed",
+    ]));
+    assert!(output.status.success());
+
+    let command = server.join().expect("server thread");
+    assert_eq!(command["cmd"], "send");
+    assert_eq!(command["kind"], "message");
+    assert_eq!(command["ref"], request_id);
+    assert_eq!(
+        command["payload"],
+        json!({"cancel": true, "reason": "no longer needed"})
+    );
+}
+
+#[test]
+fn cancel_rejects_invalid_ref() {
+    let bin = axon_bin();
+    let output =
+        run_command(Command::new(&bin).args(["cancel", VALID_AGENT_ID, "--ref", "not-a-uuid"]));
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("--ref"));
+}
+
+#[test]
+fn alias_set_then_request_sends_to_aliased_agent() {
+    let bin = axon_bin();
+    let root = tempdir().expect("tempdir");
+    let root_arg = root.path().to_str().expect("utf8 path");
+
+    let output = run_command(Command::new(&bin).args([
+        "--state-root",
+        root_arg,
+        "alias",
+        "set",
+        VALID_AGENT_ID_UPPER,
+        "laptop",
+    ]));
+    assert!(output.status.success());
+    let config = fs::read_to_string(root.path().join("config.yaml")).expect("read config");
+    assert!(config.contains(&format!("laptop: {VALID_AGENT_ID}")));
+
+    let Some(server) = require_socket_server(root.path(), json!({"ok": true, "msg_id": "x"}))
+    else {
+        return;
+    };
+    let output = run_command(Command::new(&bin).args([
+        "--state-root",
+        root_arg,
+        "request",
+        "laptop",
+        "hello",
+    ]));
+    assert!(output.status.success());
+    let command = server.join().expect("server thread");
+    assert_eq!(command["to"], VALID_AGENT_ID);
+}
+
+#[test]
+fn unknown_alias_is_rejected_as_usage_error() {
+    let bin = axon_bin();
+    let root = tempdir().expect("tempdir");
+    let output = run_command(Command::new(&bin).args([
+        "--state-root",
+        root.path().to_str().expect("utf8 path"),
+        "notify",
+        "desktop",
+        "hello",
+    ]));
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("invalid agent_id 'desktop'"));
+}
+
+#[test]
+fn uppercase_agent_id_is_canonicalized_before_request() {
+    let bin = axon_bin();
+    let root = tempdir().expect("tempdir");
+    let Some(server) = require_socket_server(root.path(), json!({"ok": true, "msg_id": "x"}))
+    else {
+        return;
+    };
+
+    let output = run_command(Command::new(&bin).args([
+        "--state-root",
+        root.path().to_str().expect("utf8 path"),
+        "request",
+        VALID_AGENT_ID_UPPER,
+        "hello",
+    ]));
+    assert!(output.status.success());
+
+    let command = server.join().expect("server thread");
+    assert_eq!(command["cmd"], "send");
+    assert_eq!(command["to"], VALID_AGENT_ID);
+}
+
+#[test]
+fn request_peer_not_found_returns_exit_code_three() {
+    let bin = axon_bin();
+    let root = tempdir().expect("tempdir");
+    let Some(server) = require_socket_server(
+        root.path(),
+        json!({"ok": false, "error": "peer_not_found", "message": "peer_not_found"}),
+    ) else {
+        return;
+    };
+
+    let output = run_command(Command::new(&bin).args([
+        "--state-root",
+        root.path().to_str().expect("utf8 path"),
+        "request",
+        VALID_AGENT_ID,
+        "hello",
+    ]));
+    assert_eq!(output.status.code(), Some(3));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("\"ok\": false"));
+
+    let command = server.join().expect("server thread");
+    assert_eq!(command["cmd"], "send");
+    assert_eq!(command["to"], VALID_AGENT_ID);
+}
+
+#[test]
+fn request_remote_error_envelope_returns_exit_code_two() {
+    let bin = axon_bin();
+    let root = tempdir().expect("tempdir");
+    let Some(server) = require_socket_server(
+        root.path(),
+        json!({
+            "ok": true,
+            "msg_id": "550e8400-e29b-41d4-a716-446655440000",
+            "response": {"kind": "error", "payload": {"code": "oops"}}
+        }),
+    ) else {
+        return;
+    };
+
+    let output = run_command(Command::new(&bin).args([
+        "--state-root",
+        root.path().to_str().expect("utf8 path"),
+        "request",
+        VALID_AGENT_ID,
+        "hello",
+    ]));
+    assert_eq!(output.status.code(), Some(2));
+    let command = server.join().expect("server thread");
+    assert_eq!(command["cmd"], "send");
+}
+
+#[test]
+fn request_timeout_daemon_error_returns_exit_code_four() {
+    let bin = axon_bin();
+    let root = tempdir().expect("tempdir");
+    let Some(server) = require_socket_server(
+        root.path(),
+        json!({
+            "ok": false,
+            "error": "timeout",
+            "message": "request timed out waiting for peer response"
+        }),
+    ) else {
+        return;
+    };
+
+    let output = run_command(Command::new(&bin).args([
+        "--state-root",
+        root.path().to_str().expect("utf8 path"),
+        "request",
+        "--timeout",
+        "7",
+        VALID_AGENT_ID,
+        "hello",
+    ]));
+    assert_eq!(output.status.code(), Some(4));
+    let command = server.join().expect("server thread");
+    assert_eq!(command["timeout_secs"], 7);
+}
+
+#[test]
+fn quiet_request_prints_nothing_and_reports_through_exit_code() {
+    let bin = axon_bin();
+    let root = tempdir().expect("tempdir");
+    let Some(server) = require_socket_server(
+        root.path(),
+        json!({"ok": false, "error": "peer_unreachable", "message": "no session"}),
+    ) else {
+        return;
+    };
+
+    let output = run_command(Command::new(&bin).args([
+        "--state-root",
+        root.path().to_str().expect("utf8 path"),
+        "-q",
+        "request",
+        VALID_AGENT_ID,
+        "hello",
+    ]));
+    server.join().expect("server thread");
+    assert_eq!(output.status.code(), Some(5));
+    assert!(output.stdout.is_empty(), "{output:?}");
+}
+
+#[test]
+fn notify_stdin_streams_ndjson_over_one_connection() {
+    let bin = axon_bin();
+    let root = tempdir().expect("tempdir");
+    let Some(server) = spawn_per_line_reply_server(
+        root.path(),
+        vec![
+            json!({"ok": true, "msg_id": "550e8400-e29b-41d4-a716-446655440000"}),
+            json!({"ok": false, "error": "peer_unreachable", "message": "not connected"}),
+        ],
+    ) else {
+        return;
+    };
+
+    let mut child = Command::new(&bin)
+        .args([
+            "--state-root",
+            root.path().to_str().expect("utf8 path"),
+            "notify",
+            VALID_AGENT_ID,
+            "--stdin",
+        ])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("spawn axon notify");
+    child
+        .stdin
+        .take()
+        .expect("stdin")
+        .write_all(b"{\"event\":\"a\"}\n\nnot json\n{\"event\":\"b\"}\n")
+        .expect("write stdin");
+    let output = child.wait_with_output().expect("wait for notify");
+    let commands = server.join().expect("join server");
+
+    assert_eq!(output.status.code(), Some(2), "{output:?}");
+    let results: Vec<Value> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("result line"))
+        .collect();
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[0]["line"], 1);
+    assert_eq!(results[0]["ok"], true);
+    assert_eq!(results[1]["line"], 3);
+    assert_eq!(results[1]["error"], "invalid_payload");
+    assert_eq!(results[2]["line"], 4);
+    assert_eq!(results[2]["error"], "peer_unreachable");
+
+    assert_eq!(commands.len(), 2);
+    assert_eq!(commands[0]["kind"], "message");
+    assert_eq!(commands[0]["payload"], json!({"event": "a"}));
+    assert_eq!(commands[1]["payload"], json!({"event": "b"}));
+}
+
+#[test]
+fn publish_sends_topic_and_wrapped_payload() {
+    let bin = axon_bin();
+    let root = tempdir().expect("tempdir");
+    let Some(server) = require_socket_server(
+        root.path(),
+        json!({"ok": true, "topic": "builds.main", "delivered": [VALID_AGENT_ID], "failed": []}),
+    ) else {
+        return;
+    };
+    let output = run_command(Command::new(&bin).args([
+        "--state-root",
+        root.path().to_str().expect("utf8 path"),
+        "publish",
+        "builds.main",
+        "--json",
+        r#"{"status":"green"}"#,
+    ]));
+    let command = server.join().expect("server thread");
+
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(
+        command,
+        json!({"cmd": "publish", "topic": "builds.main", "payload": {"data": {"status": "green"}}})
+    );
+    let reply: Value = serde_json::from_slice(&output.stdout).expect("reply JSON");
+    assert_eq!(reply["delivered"], json!([VALID_AGENT_ID]));
+}
+
+#[test]
+fn subscribe_sends_topic_list() {
+    let bin = axon_bin();
+    let root = tempdir().expect("tempdir");
+    let Some(server) = require_socket_server(
+        root.path(),
+        json!({"ok": true, "topics": ["builds.*", "deploys.prod"]}),
+    ) else {
+        return;
+    };
+    let output = run_command(Command::new(&bin).args([
+        "--state-root",
+        root.path().to_str().expect("utf8 path"),
+        "subscribe",
+        "builds.*",
+        "deploys.prod",
+    ]));
+    let command = server.join().expect("server thread");
+
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(
+        command,
+        json!({"cmd": "subscribe", "topics": ["builds.*", "deploys.prod"]})
+    );
+}
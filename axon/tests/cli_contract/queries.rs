@@ -0,0 +1,445 @@
+use super::*;
+
+#[test]
+fn reload_sends_reload_command_and_reports_failure_exit_code() {
+    let bin = axon_bin();
+    let root = tempdir().expect("tempdir");
+    let Some(server) = require_socket_server(
+        root.path(),
+        json!({
+            "ok": false,
+            "error": "reload_failed",
+            "message": "config.yaml could not be loaded; previous config kept"
+        }),
+    ) else {
+        return;
+    };
+
+    let output = run_command(Command::new(&bin).args([
+        "--state-root",
+        root.path().to_str().expect("utf8 path"),
+        "reload",
+    ]));
+    assert_eq!(output.status.code(), Some(2));
+
+    let command = server.join().expect("server thread");
+    assert_eq!(command["cmd"], "reload");
+}
+
+#[test]
+fn bans_lists_banned_addresses() {
+    let bin = axon_bin();
+    let root = tempdir().expect("tempdir");
+    let Some(server) = require_socket_server(
+        root.path(),
+        json!({
+            "ok": true,
+            "bans": [{"addr": "192.0.2.7", "strikes": 3, "remaining_secs": 240}]
+        }),
+    ) else {
+        return;
+    };
+
+    let output = run_command(Command::new(&bin).args([
+        "--state-root",
+        root.path().to_str().expect("utf8 path"),
+        "bans",
+    ]));
+    assert_eq!(output.status.code(), Some(0));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("192.0.2.7"), "{stdout}");
+    assert!(stdout.contains("240s"), "{stdout}");
+
+    let command = server.join().expect("server thread");
+    assert_eq!(command["cmd"], "bans");
+}
+
+#[test]
+fn pins_show_asks_daemon_for_one_peer() {
+    let bin = axon_bin();
+    let root = tempdir().expect("tempdir");
+    let agent_id = "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+    let Some(server) = require_socket_server(
+        root.path(),
+        json!({
+            "ok": true,
+            "pins": [{"agent_id": agent_id, "pubkey": "Zm9v", "addr": "192.0.2.7:7100", "source": "discovered"}]
+        }),
+    ) else {
+        return;
+    };
+
+    let output = run_command(Command::new(&bin).args([
+        "--state-root",
+        root.path().to_str().expect("utf8 path"),
+        "pins",
+        "show",
+        agent_id,
+    ]));
+    assert_eq!(output.status.code(), Some(0));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("discovered"), "{stdout}");
+
+    let command = server.join().expect("server thread");
+    assert_eq!(command["cmd"], "pins");
+    assert_eq!(command["agent_id"], agent_id);
+}
+
+#[test]
+fn pins_clear_edits_known_peers_when_daemon_is_stopped() {
+    let root = tempdir().expect("tempdir");
+    let root_str = root.path().to_str().expect("utf8 path");
+    let cached = "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+    let configured = "ed25519.bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb";
+    fs::write(
+        root.path().join("known_peers.json"),
+        json!([{
+            "agent_id": cached,
+            "addr": "192.0.2.7:7100",
+            "pubkey": "Zm9v",
+            "last_seen_unix_ms": 0,
+            "source": "cached"
+        }])
+        .to_string(),
+    )
+    .expect("write known peers");
+    fs::write(
+        root.path().join("config.yaml"),
+        format!("peers:\n  - agent_id: {configured}\n    addr: 192.0.2.8:7100\n    pubkey: YmFy\n"),
+    )
+    .expect("write config");
+
+    let list =
+        run_command(Command::new(axon_bin()).args(["--state-root", root_str, "pins", "list"]));
+    assert!(list.status.success(), "{list:?}");
+    let stdout = String::from_utf8_lossy(&list.stdout);
+    assert!(
+        stdout.contains(cached) && stdout.contains(configured),
+        "{stdout}"
+    );
+
+    let refused = run_command(Command::new(axon_bin()).args([
+        "--state-root",
+        root_str,
+        "pins",
+        "clear",
+        configured,
+    ]));
+    assert_eq!(refused.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&refused.stderr).contains("config.yaml"));
+
+    let cleared = run_command(Command::new(axon_bin()).args([
+        "--state-root",
+        root_str,
+        "pins",
+        "clear",
+        cached,
+    ]));
+    assert!(cleared.status.success(), "{cleared:?}");
+    let known = fs::read_to_string(root.path().join("known_peers.json")).expect("read known peers");
+    assert!(!known.contains(cached), "{known}");
+
+    let missing = run_command(Command::new(axon_bin()).args([
+        "--state-root",
+        root_str,
+        "pins",
+        "show",
+        cached,
+    ]));
+    assert_eq!(missing.status.code(), Some(3));
+}
+
+#[test]
+fn health_exits_two_when_daemon_reports_unhealthy() {
+    let bin = axon_bin();
+    let root = tempdir().expect("tempdir");
+    let Some(server) = require_socket_server(
+        root.path(),
+        json!({
+            "ok": true,
+            "healthy": false,
+            "uptime_secs": 1,
+            "subsystems": {
+                "transport": {"ok": true, "bound_addr": "0.0.0.0:7100", "connections": 0},
+                "discovery": {"ok": false, "mdns": "failed", "static_peers": 0},
+                "ipc": {"ok": true, "clients": 1, "max_clients": 64},
+                "persistence": {"ok": true},
+                "history": {"ok": true, "enabled": false}
+            }
+        }),
+    ) else {
+        return;
+    };
+
+    let output = run_command(Command::new(&bin).args([
+        "--state-root",
+        root.path().to_str().expect("utf8 path"),
+        "health",
+    ]));
+    assert_eq!(output.status.code(), Some(2));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("✗ discovery: mdns failed"));
+
+    let command = server.join().expect("server thread");
+    assert_eq!(command["cmd"], "health");
+}
+
+#[test]
+fn history_sends_filter_and_reports_disabled_exit_code() {
+    let bin = axon_bin();
+    let root = tempdir().expect("tempdir");
+    let Some(server) = require_socket_server(
+        root.path(),
+        json!({
+            "ok": false,
+            "error": "history_disabled",
+            "message": "message history is disabled; set history.enabled: true in config.yaml"
+        }),
+    ) else {
+        return;
+    };
+
+    let output = run_command(Command::new(&bin).args([
+        "--state-root",
+        root.path().to_str().expect("utf8 path"),
+        "history",
+        "--peer",
+        "ED25519.AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA",
+        "--direction",
+        "in",
+        "--limit",
+        "5",
+    ]));
+    assert_eq!(output.status.code(), Some(2));
+
+    let command = server.join().expect("server thread");
+    assert_eq!(command["cmd"], "history");
+    assert_eq!(
+        command["filter"],
+        json!({
+            "peer": "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+            "direction": "in",
+            "limit": 5
+        })
+    );
+}
+
+#[test]
+fn peers_tag_filter_is_sent_to_daemon() {
+    let bin = axon_bin();
+    let root = tempdir().expect("tempdir");
+    let Some(server) = require_socket_server(
+        root.path(),
+        json!({"ok": true, "peers": [{"agent_id": VALID_AGENT_ID, "addr": "127.0.0.1:7100", "status": "connected", "source": "static", "tags": ["prod", "gpu"]}]}),
+    ) else {
+        return;
+    };
+
+    let output = run_command(Command::new(&bin).args([
+        "--state-root",
+        root.path().to_str().expect("utf8 path"),
+        "peers",
+        "--tag",
+        "prod",
+    ]));
+    assert!(output.status.success(), "{output:?}");
+    assert!(String::from_utf8_lossy(&output.stdout).contains("prod,gpu"));
+
+    let command = server.join().expect("server thread");
+    assert_eq!(command["cmd"], "peers");
+    assert_eq!(command["tag"], "prod");
+}
+
+#[test]
+fn peers_tag_and_untag_edit_config() {
+    let root = tempdir().expect("tempdir");
+    let root_str = root.path().to_str().expect("utf8 path");
+    let config = root.path().join("config.yaml");
+    fs::write(&config, format!("aliases:\n  laptop: {VALID_AGENT_ID}\n")).expect("write config");
+
+    let tagged = run_command(Command::new(axon_bin()).args([
+        "--state-root",
+        root_str,
+        "peers",
+        "tag",
+        "laptop",
+        "prod",
+        "gpu",
+    ]));
+    assert!(tagged.status.success(), "{tagged:?}");
+    let yaml = fs::read_to_string(&config).expect("read config");
+    assert!(
+        yaml.contains(VALID_AGENT_ID) && yaml.contains("gpu"),
+        "{yaml}"
+    );
+
+    let invalid = run_command(Command::new(axon_bin()).args([
+        "--state-root",
+        root_str,
+        "peers",
+        "tag",
+        VALID_AGENT_ID,
+        "bad tag",
+    ]));
+    assert_eq!(invalid.status.code(), Some(2));
+
+    let untagged = run_command(Command::new(axon_bin()).args([
+        "--state-root",
+        root_str,
+        "peers",
+        "untag",
+        VALID_AGENT_ID,
+    ]));
+    assert!(untagged.status.success(), "{untagged:?}");
+    let yaml = fs::read_to_string(&config).expect("read config");
+    assert!(!yaml.contains("tags"), "{yaml}");
+}
+
+#[test]
+fn whoami_uses_ipc_while_identity_is_local() {
+    let bin = axon_bin();
+    let root = tempdir().expect("tempdir");
+    let root_str = root.path().to_str().expect("utf8 path");
+
+    let Some(whoami_server) = require_socket_server(
+        root.path(),
+        json!({
+            "ok": true,
+            "agent_id": "ed25519.bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb",
+            "public_key": "Zm9v",
+            "version": env!("CARGO_PKG_VERSION"),
+            "uptime_secs": 1
+        }),
+    ) else {
+        return;
+    };
+
+    let whoami = run_command(Command::new(&bin).args(["--state-root", root_str, "whoami"]));
+    assert!(whoami.status.success());
+    assert!(
+        String::from_utf8_lossy(&whoami.stdout)
+            .contains("ed25519.bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb")
+    );
+    let whoami_cmd = whoami_server.join().expect("server thread");
+    assert_eq!(whoami_cmd["cmd"], "whoami");
+
+    assert!(
+        !root.path().join("identity.key").exists(),
+        "whoami should not require local identity files"
+    );
+
+    let identity = run_command(Command::new(&bin).args(["--state-root", root_str, "identity"]));
+    assert!(identity.status.success());
+    assert!(root.path().join("identity.key").exists());
+    assert!(root.path().join("identity.pub").exists());
+}
+
+#[test]
+fn output_yaml_prints_versioned_status_document() {
+    let bin = axon_bin();
+    let root = tempdir().expect("tempdir");
+    let Some(server) = require_socket_server(
+        root.path(),
+        json!({"ok": true, "uptime_secs": 3, "peers_connected": 0, "messages_sent": 1, "messages_received": 2, "messages_rate_limited": 0, "internal_only": true}),
+    ) else {
+        return;
+    };
+
+    let output = run_command(Command::new(&bin).args([
+        "--state-root",
+        root.path().to_str().expect("utf8 path"),
+        "status",
+        "--output",
+        "yaml",
+    ]));
+    server.join().expect("server thread");
+    assert!(output.status.success(), "{output:?}");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.starts_with("schema: axon.status\nversion: 1\nuptime_secs: 3\n"),
+        "{stdout}"
+    );
+    assert!(!stdout.contains("internal_only"), "{stdout}");
+}
+
+#[test]
+fn output_flag_is_rejected_where_unsupported() {
+    let bin = axon_bin();
+    for args in [
+        vec!["--output", "json", "notify", VALID_AGENT_ID, "hi"],
+        vec!["peers", "--json", "--output", "yaml"],
+    ] {
+        let output = run_command(Command::new(&bin).args(&args));
+        assert_eq!(output.status.code(), Some(2), "{args:?}");
+        assert!(
+            String::from_utf8_lossy(&output.stderr).contains("--output"),
+            "{args:?}"
+        );
+    }
+}
+
+#[test]
+fn whois_resolves_alias_and_renders_peer_record() {
+    let bin = axon_bin();
+    let root = tempdir().expect("tempdir");
+    let root_arg = root.path().to_str().expect("utf8 path");
+    fs::write(
+        root.path().join("config.yaml"),
+        format!("aliases:\n  laptop: {VALID_AGENT_ID}\n"),
+    )
+    .expect("write config");
+
+    let Some(server) = require_socket_server(
+        root.path(),
+        json!({"ok": true, "peer": {
+            "agent_id": VALID_AGENT_ID,
+            "pubkey": "Zm9v",
+            "addr": "10.0.0.2:7100",
+            "status": "connected",
+            "source": "static",
+            "rtt_ms": 0.8,
+            "first_seen_ms": 1771100000000u64,
+            "last_seen_ms": 1771108000000u64,
+            "messages_sent": 4,
+            "messages_received": 1
+        }}),
+    ) else {
+        return;
+    };
+    let output =
+        run_command(Command::new(&bin).args(["--state-root", root_arg, "whois", "laptop"]));
+    let command = server.join().expect("server thread");
+
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(command, json!({"cmd": "whois", "agent_id": VALID_AGENT_ID}));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains(&format!("Agent ID: {VALID_AGENT_ID} (laptop)")),
+        "{stdout}"
+    );
+    assert!(stdout.contains("RTT: 0.80 ms"), "{stdout}");
+    assert!(stdout.contains("Reconnect: -"), "{stdout}");
+}
+
+#[test]
+fn whois_unknown_peer_exits_3() {
+    let bin = axon_bin();
+    let root = tempdir().expect("tempdir");
+    let Some(server) = require_socket_server(
+        root.path(),
+        json!({"ok": false, "error": "peer_not_found", "message": "peer not found"}),
+    ) else {
+        return;
+    };
+    let output = run_command(Command::new(&bin).args([
+        "--state-root",
+        root.path().to_str().expect("utf8 path"),
+        "whois",
+        VALID_AGENT_ID,
+    ]));
+    server.join().expect("server thread");
+
+    assert_eq!(output.status.code(), Some(3));
+    let reply: Value = serde_json::from_slice(&output.stdout).expect("error JSON");
+    assert_eq!(reply["error"], "peer_not_found");
+}
@@ -0,0 +1,272 @@
+use super::*;
+
+#[test]
+fn watch_prints_matching_inbound_events_until_daemon_closes() {
+    let bin = axon_bin();
+    let root = tempdir().expect("tempdir");
+    let socket_path = root.path().join("axon.sock");
+    let listener = match UnixListener::bind(&socket_path) {
+        Ok(listener) => listener,
+        Err(err) if err.kind() == ErrorKind::PermissionDenied => {
+            eprintln!(
+                "skipping socket-dependent test: unix socket bind not permitted in this environment"
+            );
+            return;
+        }
+        Err(err) => panic!("failed to start unix socket server: {err}"),
+    };
+    let server = std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().expect("accept connection");
+        let events = [
+            json!({"event": "connected", "agent_id": VALID_AGENT_ID}),
+            json!({"event": "inbound", "from": VALID_AGENT_ID, "envelope": {
+                "id": "6f1c1a4e-2d3b-4c5a-9e8f-0a1b2c3d4e5f", "kind": "request", "payload": {"q": 1}
+            }}),
+            json!({"event": "inbound", "from": VALID_AGENT_ID, "envelope": {
+                "id": "0b9a8c7d-6e5f-4a3b-8c2d-1e0f9a8b7c6d", "kind": "message", "payload": {"m": 2}
+            }}),
+        ];
+        for event in events {
+            let line = serde_json::to_string(&event).expect("serialize event");
+            stream.write_all(line.as_bytes()).expect("write event");
+            stream.write_all(b"\n").expect("write newline");
+        }
+    });
+
+    let output = run_command(Command::new(&bin).args([
+        "--state-root",
+        root.path().to_str().expect("utf8 path"),
+        "watch",
+        "--kind",
+        "message",
+        "--json",
+    ]));
+    server.join().expect("server thread");
+
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let printed: Vec<Value> = stdout
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect();
+    assert_eq!(printed.len(), 1, "only the message event passes: {stdout}");
+    assert_eq!(printed[0]["envelope"]["payload"], json!({"m": 2}));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("daemon closed the IPC connection"));
+}
+
+#[test]
+fn bench_reports_latency_and_error_counts() {
+    let bin = axon_bin();
+    let root = tempdir().expect("tempdir");
+    let ok = json!({"ok": true, "msg_id": "550e8400-e29b-41d4-a716-446655440000", "response": {"kind": "response", "payload": {}}});
+    let Some(server) = spawn_per_line_reply_server(
+        root.path(),
+        vec![
+            ok.clone(),
+            json!({"ok": false, "error": "timeout", "message": "timed out"}),
+            ok,
+        ],
+    ) else {
+        return;
+    };
+
+    let output = run_command(Command::new(&bin).args([
+        "--state-root",
+        root.path().to_str().expect("utf8 path"),
+        "bench",
+        VALID_AGENT_ID,
+        "--count",
+        "3",
+        "--size",
+        "16",
+        "--json",
+    ]));
+    let commands = server.join().expect("join server");
+
+    assert_eq!(commands.len(), 3);
+    assert_eq!(commands[0]["cmd"], "send");
+    assert_eq!(commands[0]["kind"], "request");
+    assert_eq!(commands[0]["to"], VALID_AGENT_ID);
+    assert_eq!(commands[0]["payload"]["message"], "x".repeat(16));
+
+    assert_eq!(output.status.code(), Some(2));
+    let report: Value = serde_json::from_slice(&output.stdout).expect("bench json");
+    assert_eq!(report["sent"], 3);
+    assert_eq!(report["ok"], 2);
+    assert_eq!(report["errors"]["timeout"], 1);
+    assert!(report["latency_ms"]["p99"].as_f64().is_some());
+}
+
+#[test]
+fn shell_runs_piped_commands_over_one_connection() {
+    let bin = axon_bin();
+    let root = tempdir().expect("tempdir");
+    let Some(server) = spawn_per_line_reply_server(
+        root.path(),
+        vec![
+            json!({"ok": true, "peers": []}),
+            json!({"ok": true, "msg_id": "550e8400-e29b-41d4-a716-446655440000", "response": {"kind": "response", "payload": {"text": "pong"}}}),
+        ],
+    ) else {
+        return;
+    };
+
+    let mut child = Command::new(&bin)
+        .args([
+            "--state-root",
+            root.path().to_str().expect("utf8 path"),
+            "shell",
+        ])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("spawn axon shell");
+    child
+        .stdin
+        .take()
+        .expect("stdin")
+        .write_all(format!("peers\nbogus\nsend {VALID_AGENT_ID} hi there\nquit\n").as_bytes())
+        .expect("write stdin");
+    let output = child.wait_with_output().expect("wait for shell");
+    let commands = server.join().expect("join server");
+
+    assert!(output.status.success(), "{output:?}");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("unknown command 'bogus'"), "{stdout}");
+    assert!(stdout.contains(r#"{"text":"pong"}"#), "{stdout}");
+    assert_eq!(commands[0]["cmd"], "peers");
+    assert_eq!(commands[1]["cmd"], "send");
+    assert_eq!(commands[1]["kind"], "request");
+    assert_eq!(commands[1]["to"], VALID_AGENT_ID);
+    assert_eq!(commands[1]["payload"], json!({"message": "hi there"}));
+}
+
+#[test]
+fn top_once_prints_one_frame_from_status_and_peers() {
+    let bin = axon_bin();
+    let root = tempdir().expect("tempdir");
+    let Some(server) = spawn_per_line_reply_server(
+        root.path(),
+        vec![
+            json!({"ok": true, "uptime_secs": 42, "peers_connected": 1, "messages_sent": 7, "messages_received": 3, "messages_rate_limited": 0}),
+            json!({"ok": true, "peers": [{"agent_id": VALID_AGENT_ID, "addr": "127.0.0.1:7100", "status": "connected", "rtt_ms": 0.5, "source": "static"}]}),
+        ],
+    ) else {
+        return;
+    };
+
+    let output = run_command(Command::new(&bin).args([
+        "--state-root",
+        root.path().to_str().expect("utf8 path"),
+        "top",
+        "--once",
+    ]));
+    let commands = server.join().expect("join server");
+
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(
+        commands,
+        vec![json!({"cmd": "status"}), json!({"cmd": "peers"})]
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.starts_with("axon top  uptime 42s  peers connected 1"),
+        "{stdout}"
+    );
+    assert!(stdout.contains(VALID_AGENT_ID), "{stdout}");
+    assert!(stdout.contains("sent 7 (-)  received 3 (-)"), "{stdout}");
+    assert!(!stdout.contains('\x1b'), "{stdout}");
+}
+
+#[test]
+fn mcp_answers_json_rpc_on_stdout_only() {
+    let bin = axon_bin();
+    let root = tempdir().expect("tempdir");
+    let Some(server) = require_socket_server(root.path(), json!({"ok": true, "peers": []})) else {
+        return;
+    };
+
+    let mut child = Command::new(&bin)
+        .args([
+            "--state-root",
+            root.path().to_str().expect("utf8 path"),
+            "-v",
+            "mcp",
+        ])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("spawn axon mcp");
+    let requests = [
+        json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {"protocolVersion": "2025-06-18"}}),
+        json!({"jsonrpc": "2.0", "method": "notifications/initialized"}),
+        json!({"jsonrpc": "2.0", "id": 2, "method": "tools/call", "params": {"name": "list_peers"}}),
+    ];
+    {
+        let mut stdin = child.stdin.take().expect("stdin");
+        for request in requests {
+            writeln!(stdin, "{request}").expect("write request");
+        }
+    }
+    let output = child.wait_with_output().expect("wait for axon mcp");
+    assert!(output.status.success(), "{output:?}");
+
+    let mut replies: Vec<Value> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("stdout carries only JSON-RPC"))
+        .collect();
+    replies.sort_by_key(|reply| reply["id"].as_u64());
+    assert_eq!(replies.len(), 2, "{replies:?}");
+    assert_eq!(replies[0]["result"]["serverInfo"]["name"], "axon");
+    assert_eq!(
+        replies[1]["result"]["structuredContent"]["peers"],
+        json!([])
+    );
+    assert_eq!(server.join().expect("server thread")["cmd"], "peers");
+}
+
+#[test]
+fn replay_reissues_recorded_commands_only() {
+    let bin = axon_bin();
+    let root = tempdir().expect("tempdir");
+    let recording = root.path().join("session.axonrec");
+    let lines = [
+        json!({"axonrec": 1, "agent_id": VALID_AGENT_ID, "started_ms": 1}),
+        json!({"t_ms": 0, "type": "command", "client_id": 4, "command": {"cmd": "status", "req_id": "r-9"}}),
+        json!({"t_ms": 1, "type": "inbound", "envelope": {"kind": "message"}}),
+        json!({"t_ms": 2, "type": "command", "client_id": 4, "command": {"cmd": "shutdown", "req_id": null}}),
+    ];
+    let text: String = lines.iter().map(|line| format!("{line}\n")).collect();
+    fs::write(&recording, text).expect("write recording");
+    let Some(server) =
+        spawn_per_line_reply_server(root.path(), vec![json!({"ok": true, "peers_connected": 0})])
+    else {
+        return;
+    };
+
+    let output = run_command(Command::new(&bin).args([
+        "--state-root",
+        root.path().to_str().expect("utf8 path"),
+        "replay",
+        recording.to_str().expect("utf8 path"),
+        "--speed",
+        "0",
+    ]));
+    assert!(output.status.success(), "{output:?}");
+    let reply: Value = serde_json::from_slice(&output.stdout).expect("one JSON line");
+    assert_eq!(reply["cmd"], "status");
+    assert_eq!(reply["reply"]["ok"], true);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("replayed 1 commands, skipped 1; 1 recorded envelopes not injected"),
+        "{stderr}"
+    );
+    assert_eq!(
+        server.join().expect("server thread"),
+        vec![json!({"cmd": "status"})],
+        "the recorded req_id is dropped"
+    );
+}
@@ -180,8 +180,13 @@ fn connect_writes_config_and_sends_add_peer_ipc() {
         return;
     };
 
-    let output =
-        run_command(Command::new(&bin).args(["--state-root", root_str, "connect", &token]));
+    let output = run_command(Command::new(&bin).args([
+        "--state-root",
+        root_str,
+        "connect",
+        "--allow-unsigned",
+        &token,
+    ]));
     assert!(output.status.success());
     let stdout = String::from_utf8_lossy(&output.stdout);
     assert!(stdout.contains("Added peer"));
@@ -211,8 +216,13 @@ fn connect_returns_error_when_hotload_fails_after_config_write() {
         return;
     };
 
-    let output =
-        run_command(Command::new(&bin).args(["--state-root", root_str, "connect", &token]));
+    let output = run_command(Command::new(&bin).args([
+        "--state-root",
+        root_str,
+        "connect",
+        "--allow-unsigned",
+        &token,
+    ]));
     assert_eq!(output.status.code(), Some(1));
     let stderr = String::from_utf8_lossy(&output.stderr);
     assert!(stderr.contains("peer saved to"));
@@ -224,3 +234,252 @@ fn connect_returns_error_when_hotload_fails_after_config_write() {
     let command = server.join().expect("server thread");
     assert_eq!(command["cmd"], "add_peer");
 }
+
+#[test]
+fn connect_rejects_expired_tampered_and_stripped_tokens() {
+    let bin = axon_bin();
+    let issuer = tempdir().expect("tempdir");
+    let root = tempdir().expect("tempdir");
+    let issuer_str = issuer.path().to_str().expect("utf8 path");
+    let root_str = root.path().to_str().expect("utf8 path");
+    let issue = |args: &[&str]| {
+        let output = run_command(
+            Command::new(&bin)
+                .args(["--state-root", issuer_str, "identity", "--json"])
+                .args(["--addr", "127.0.0.1:7730"])
+                .args(args),
+        );
+        assert!(output.status.success(), "{output:?}");
+        serde_json::from_slice::<Value>(&output.stdout).expect("identity json")
+    };
+    let connect = |token: &str| {
+        run_command(Command::new(&bin).args(["--state-root", root_str, "connect", token]))
+    };
+
+    let expired = issue(&["--expires", "0s"]);
+    assert!(expired["expires_at"].as_u64().is_some(), "{expired}");
+    let output = connect(expired["uri"].as_str().expect("uri"));
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("expired"));
+
+    let fresh = issue(&["--expires", "1h"]);
+    let tampered = fresh["uri"]
+        .as_str()
+        .expect("uri")
+        .replace("127.0.0.1:7730", "127.0.0.1:7731");
+    let output = connect(&tampered);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("signature is invalid"));
+
+    let (stripped, _query) = expired["uri"]
+        .as_str()
+        .expect("uri")
+        .split_once('?')
+        .expect("query");
+    let output = connect(stripped);
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("--allow-unsigned"), "{stderr}");
+    assert!(stderr.contains("unsigned"), "{stderr}");
+
+    assert!(!root.path().join("config.yaml").exists());
+}
+
+#[test]
+fn revoke_removes_peer_and_blocks_reconnecting_its_token() {
+    let bin = axon_bin();
+    let issuer = tempdir().expect("tempdir");
+    let root = tempdir().expect("tempdir");
+    let issuer_str = issuer.path().to_str().expect("utf8 path");
+    let root_str = root.path().to_str().expect("utf8 path");
+    let output = run_command(Command::new(&bin).args([
+        "--state-root",
+        issuer_str,
+        "identity",
+        "--json",
+        "--addr",
+        "127.0.0.1:7732",
+    ]));
+    assert!(output.status.success(), "{output:?}");
+    let identity: Value = serde_json::from_slice(&output.stdout).expect("identity json");
+    let agent_id = identity["agent_id"].as_str().expect("agent_id");
+    let uri = identity["uri"].as_str().expect("uri");
+    let connect =
+        || run_command(Command::new(&bin).args(["--state-root", root_str, "connect", uri]));
+
+    assert!(connect().status.success());
+    let output =
+        run_command(Command::new(&bin).args(["--state-root", root_str, "revoke", agent_id]));
+    assert!(output.status.success(), "{output:?}");
+    assert!(String::from_utf8_lossy(&output.stdout).contains(agent_id));
+
+    let config = fs::read_to_string(root.path().join("config.yaml")).expect("config");
+    assert!(!config.contains(agent_id), "{config}");
+    let revocations: Value = serde_json::from_str(
+        &fs::read_to_string(root.path().join("revocations.json")).expect("revocations"),
+    )
+    .expect("revocations json");
+    assert_eq!(revocations[0]["agent_id"], agent_id);
+    assert_eq!(revocations[0]["pubkey"], identity["public_key"]);
+
+    let output = connect();
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("revoked"));
+}
+
+#[test]
+fn block_and_unblock_keep_the_peer_in_config() {
+    let bin = axon_bin();
+    let issuer = tempdir().expect("tempdir");
+    let root = tempdir().expect("tempdir");
+    let issuer_str = issuer.path().to_str().expect("utf8 path");
+    let root_str = root.path().to_str().expect("utf8 path");
+    let output = run_command(Command::new(&bin).args([
+        "--state-root",
+        issuer_str,
+        "identity",
+        "--json",
+        "--addr",
+        "127.0.0.1:7733",
+    ]));
+    assert!(output.status.success(), "{output:?}");
+    let identity: Value = serde_json::from_slice(&output.stdout).expect("identity json");
+    let agent_id = identity["agent_id"].as_str().expect("agent_id");
+    let uri = identity["uri"].as_str().expect("uri");
+    let axon = |args: &[&str]| {
+        run_command(
+            Command::new(&bin)
+                .args(["--state-root", root_str])
+                .args(args),
+        )
+    };
+
+    assert!(axon(&["connect", uri]).status.success());
+    let output = axon(&["block", agent_id]);
+    assert!(output.status.success(), "{output:?}");
+    let output = axon(&["blocked", "--json"]);
+    let listed: Value = serde_json::from_slice(&output.stdout).expect("blocked json");
+    assert_eq!(listed["blocked"][0]["agent_id"], agent_id);
+    assert_eq!(listed["blocked"][0]["pubkey"], identity["public_key"]);
+    let config = fs::read_to_string(root.path().join("config.yaml")).expect("config");
+    assert!(config.contains(agent_id), "{config}");
+
+    assert!(axon(&["unblock", agent_id]).status.success());
+    let output = axon(&["unblock", agent_id]);
+    assert_eq!(output.status.code(), Some(3), "{output:?}");
+    let output = axon(&["blocked"]);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("No blocked peers."));
+}
+
+#[test]
+fn audit_verify_reports_missing_and_corrupt_logs() {
+    let bin = axon_bin();
+    let root = tempdir().expect("tempdir");
+    let root_str = root.path().to_str().expect("utf8 path");
+    let output = run_command(Command::new(&bin).args(["--state-root", root_str, "identity"]));
+    assert!(output.status.success(), "{output:?}");
+    let verify =
+        || run_command(Command::new(&bin).args(["--state-root", root_str, "audit", "verify"]));
+
+    let output = verify();
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("no audit log"));
+
+    fs::write(
+        root.path().join("audit.jsonl"),
+        "{\"event\":\"send\"}\nnot json\n",
+    )
+    .expect("write");
+    let output = verify();
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("failed verification"), "{stdout}");
+    assert!(stdout.contains("audit.jsonl:2"), "{stdout}");
+}
+
+#[test]
+fn config_validate_reports_errors_and_exits_2() {
+    let bin = axon_bin();
+    let root = tempdir().expect("tempdir");
+    let root_str = root.path().to_str().expect("utf8 path");
+    fs::write(
+        root.path().join("config.yaml"),
+        "prot: 7100\npeers:\n  - agent_id: ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa\n    addr: 127.0.0.1:7100\n    pubkey: Zm9v\n",
+    )
+    .expect("write config");
+
+    let output =
+        run_command(Command::new(&bin).args(["--state-root", root_str, "config", "validate"]));
+    assert_eq!(output.status.code(), Some(2));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("error: peers[0].pubkey:"), "{stdout}");
+    assert!(stdout.contains("warning: prot: unknown key"), "{stdout}");
+
+    let output = run_command(Command::new(&bin).args([
+        "--state-root",
+        root_str,
+        "config",
+        "validate",
+        "--json",
+    ]));
+    assert_eq!(output.status.code(), Some(2));
+    let report: Value = serde_json::from_slice(&output.stdout).expect("report JSON");
+    assert_eq!(report["valid"], json!(false));
+    assert_eq!(report["issues"].as_array().expect("issues").len(), 2);
+}
+
+#[test]
+fn config_validate_accepts_missing_file_and_schema_prints() {
+    let bin = axon_bin();
+    let root = tempdir().expect("tempdir");
+    let root_str = root.path().to_str().expect("utf8 path");
+
+    let output =
+        run_command(Command::new(&bin).args(["--state-root", root_str, "config", "validate"]));
+    assert_eq!(output.status.code(), Some(0));
+
+    let output =
+        run_command(Command::new(&bin).args(["--state-root", root_str, "config", "schema"]));
+    assert_eq!(output.status.code(), Some(0));
+    let schema: Value = serde_json::from_slice(&output.stdout).expect("schema JSON");
+    assert_eq!(schema["properties"]["port"]["type"], json!("integer"));
+}
+
+#[test]
+fn config_show_merges_layers_and_reports_provenance() {
+    let bin = axon_bin();
+    let root = tempdir().expect("tempdir");
+    let root_str = root.path().to_str().expect("utf8 path");
+    let system = root.path().join("system.yaml");
+    fs::write(&system, "name: fleet\nport: 7000\n").expect("write system config");
+    fs::write(root.path().join("config.yaml"), "port: 7100\n").expect("write config");
+
+    let output = run_command(
+        Command::new(&bin)
+            .args([
+                "--state-root",
+                root_str,
+                "config",
+                "show",
+                "--provenance",
+                "--json",
+            ])
+            .env("AXON_SYSTEM_CONFIG", &system)
+            .env("AXON_HISTORY__ENABLED", "true"),
+    );
+    assert_eq!(output.status.code(), Some(0));
+    let shown: Value = serde_json::from_slice(&output.stdout).expect("show JSON");
+    assert_eq!(shown["config"]["name"], json!("fleet"));
+    assert_eq!(shown["config"]["port"], json!(7100));
+    assert_eq!(shown["config"]["history"]["enabled"], json!(true));
+    assert_eq!(shown["provenance"]["name"]["layer"], json!("system"));
+    assert_eq!(shown["provenance"]["port"]["layer"], json!("user"));
+    assert_eq!(
+        shown["provenance"]["history.enabled"],
+        json!({"layer": "env", "var": "AXON_HISTORY__ENABLED"})
+    );
+    assert_eq!(
+        shown["provenance"]["advertise_addr"]["layer"],
+        json!("default")
+    );
+}
@@ -232,3 +232,94 @@ async fn concurrent_sends_from_multiple_ipc_clients() {
     td.daemon_a.shutdown().await;
     td.daemon_b.shutdown().await;
 }
+
+/// `publish` reaches only peers whose announced subscriptions match the
+/// topic, and the delivered envelope carries the topic.
+#[tokio::test]
+async fn publish_fans_out_to_subscribed_peers_only() {
+    let td = setup_connected_pair().await;
+
+    let stream = UnixStream::connect(&td.daemon_b.paths.socket)
+        .await
+        .unwrap();
+    let (read, _write) = stream.into_split();
+    let mut reader = BufReader::new(read);
+    // B only announces to peers it has a connection to; wait for it so the
+    // subscribe below is announced rather than left to the connect event.
+    assert!(
+        wait_for_peer_connected(
+            &td.daemon_b.paths.socket,
+            td.id_a.agent_id(),
+            Duration::from_secs(10)
+        )
+        .await,
+        "daemon B did not connect to A"
+    );
+
+    let reply = ipc_command(
+        &td.daemon_b.paths.socket,
+        json!({"cmd": "subscribe", "topics": ["builds.*"]}),
+    )
+    .await
+    .unwrap();
+    assert_eq!(reply, json!({"ok": true, "topics": ["builds.*"]}));
+
+    // Wait for A to learn B's subscription, allowing for announce retries.
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(10);
+    loop {
+        let whois = ipc_command(
+            &td.daemon_a.paths.socket,
+            json!({"cmd": "whois", "agent_id": td.id_b.agent_id()}),
+        )
+        .await
+        .unwrap();
+        if whois["peer"]["topics"] == json!(["builds.*"]) {
+            break;
+        }
+        assert!(
+            tokio::time::Instant::now() < deadline,
+            "A never saw B's subscription: {whois}"
+        );
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+
+    let unmatched = ipc_command(
+        &td.daemon_a.paths.socket,
+        json!({"cmd": "publish", "topic": "deploys.prod", "payload": {"n": 0}}),
+    )
+    .await
+    .unwrap();
+    assert_eq!(unmatched["delivered"], json!([]));
+
+    let matched = ipc_command(
+        &td.daemon_a.paths.socket,
+        json!({"cmd": "publish", "topic": "builds.main", "payload": {"n": 1}}),
+    )
+    .await
+    .unwrap();
+    assert_eq!(matched["ok"], json!(true));
+    assert_eq!(matched["delivered"], json!([td.id_b.agent_id()]));
+    assert_eq!(matched["failed"], json!([]));
+
+    let mut line = String::new();
+    timeout(Duration::from_secs(5), reader.read_line(&mut line))
+        .await
+        .unwrap()
+        .unwrap();
+    let inbound: Value = serde_json::from_str(line.trim()).unwrap();
+    assert_eq!(inbound["event"], json!("inbound"));
+    assert_eq!(inbound["envelope"]["kind"], "message");
+    assert_eq!(inbound["envelope"]["topic"], "builds.main");
+    assert_eq!(inbound["envelope"]["payload"], json!({"n": 1}));
+
+    let invalid = ipc_command(
+        &td.daemon_a.paths.socket,
+        json!({"cmd": "publish", "topic": "builds.*", "payload": {}}),
+    )
+    .await
+    .unwrap();
+    assert_eq!(invalid["error"], "invalid_command");
+
+    td.daemon_a.shutdown().await;
+    td.daemon_b.shutdown().await;
+}
@@ -313,3 +313,77 @@ async fn shutdown_with_connected_ipc_clients() {
 
     daemon_a.shutdown().await;
 }
+
+/// reload IPC command applies config.yaml peer changes without restart and
+/// keeps the previous config when the file is invalid.
+#[tokio::test]
+async fn reload_applies_config_peer_changes() {
+    let daemon_root = tempdir().unwrap();
+    let peer_root = tempdir().unwrap();
+
+    let peer_paths = AxonPaths::from_root(PathBuf::from(peer_root.path()));
+    peer_paths.ensure_root_exists().unwrap();
+    let peer_id = Identity::load_or_generate(&peer_paths).unwrap();
+
+    let port = pick_free_port();
+    let daemon = spawn_daemon(daemon_root.path(), port, vec![]);
+    assert!(wait_for_socket(&daemon.paths, Duration::from_secs(5)).await);
+
+    let peer = StaticPeerConfig {
+        agent_id: peer_id.agent_id().into(),
+        addr: format!("127.0.0.1:{}", pick_free_port()).parse().unwrap(),
+        pubkey: peer_id.public_key_base64().to_string(),
+    };
+    let with_peer = Config {
+        port: Some(port),
+        peers: vec![peer],
+        ..Default::default()
+    };
+    std::fs::write(
+        &daemon.paths.config,
+        serde_yaml::to_string(&with_peer).unwrap(),
+    )
+    .unwrap();
+
+    let reply = ipc_command(
+        &daemon.paths.socket,
+        json!({"cmd": "reload", "req_id": "r1"}),
+    )
+    .await
+    .unwrap();
+    assert_eq!(reply["ok"], json!(true));
+    assert_eq!(reply["peers_added"], json!(1));
+    assert_eq!(reply["req_id"], json!("r1"));
+
+    let peers = ipc_command(&daemon.paths.socket, json!({"cmd": "peers"}))
+        .await
+        .unwrap();
+    assert!(
+        peers["peers"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|entry| entry["agent_id"].as_str() == Some(peer_id.agent_id()))
+    );
+
+    std::fs::write(&daemon.paths.config, "peers: [not: valid").unwrap();
+    let reply = ipc_command(&daemon.paths.socket, json!({"cmd": "reload"}))
+        .await
+        .unwrap();
+    assert_eq!(reply["ok"], json!(false));
+    assert_eq!(reply["error"], json!("reload_failed"));
+
+    std::fs::write(&daemon.paths.config, format!("port: {port}\n")).unwrap();
+    let reply = ipc_command(&daemon.paths.socket, json!({"cmd": "reload"}))
+        .await
+        .unwrap();
+    assert_eq!(reply["ok"], json!(true));
+    assert_eq!(reply["peers_removed"], json!(1));
+
+    let peers = ipc_command(&daemon.paths.socket, json!({"cmd": "peers"}))
+        .await
+        .unwrap();
+    assert!(peers["peers"].as_array().unwrap().is_empty());
+
+    daemon.shutdown().await;
+}
@@ -10,9 +10,14 @@
 use std::path::PathBuf;
 use std::time::Duration;
 
-use axon::config::{AxonPaths, Config, StaticPeerConfig};
+use axon::config::{
+    AxonPaths, Config, HandshakeBanConfig, HeartbeatConfig, KnownPeer, KnownPeerSource,
+    MaxMessageBytesConfig, PeerPolicyConfig, PolicyConfig, StaticPeerConfig, load_known_peers,
+    save_known_peers,
+};
 use axon::daemon::{DaemonOptions, run_daemon};
 use axon::identity::Identity;
+use axon::transport::ResponseHandlerFn;
 use serde_json::{Value, json};
 use tempfile::tempdir;
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
@@ -22,6 +27,12 @@ use tokio_util::sync::CancellationToken;
 
 mod broadcast;
 mod connection;
+mod messaging;
+mod node;
+mod peer_state;
+mod policy;
+mod routing;
+mod trust;
 
 // =========================================================================
 // Helpers
@@ -49,6 +60,17 @@ pub(crate) fn spawn_daemon_with_config(
     dir: &std::path::Path,
     port: u16,
     config: Config,
+) -> DaemonHandle {
+    spawn_daemon_with_handler(dir, port, config, None)
+}
+
+/// Like [`spawn_daemon_with_config`], answering inbound requests with
+/// `response_handler` ahead of the configured handlers.
+pub(crate) fn spawn_daemon_with_handler(
+    dir: &std::path::Path,
+    port: u16,
+    config: Config,
+    response_handler: Option<ResponseHandlerFn>,
 ) -> DaemonHandle {
     let cancel = CancellationToken::new();
     let paths = AxonPaths::from_root(PathBuf::from(dir));
@@ -61,7 +83,10 @@ pub(crate) fn spawn_daemon_with_config(
         port: Some(port),
         disable_mdns: true,
         axon_root: Some(PathBuf::from(dir)),
+        profile: None,
         cancel: Some(cancel.clone()),
+        handle_signals: true,
+        response_handler,
     };
 
     let handle = tokio::spawn(async move { run_daemon(opts).await });
@@ -112,15 +137,14 @@ pub(crate) async fn wait_for_peer_connected(
         if tokio::time::Instant::now() >= deadline {
             return false;
         }
-        if let Ok(resp) = ipc_command(socket_path, json!({"cmd": "peers"})).await {
-            if let Some(peers) = resp["peers"].as_array() {
-                if peers.iter().any(|p| {
-                    p["agent_id"].as_str() == Some(peer_agent_id)
-                        && p["status"].as_str() == Some("connected")
-                }) {
-                    return true;
-                }
-            }
+        if let Ok(resp) = ipc_command(socket_path, json!({"cmd": "peers"})).await
+            && let Some(peers) = resp["peers"].as_array()
+            && peers.iter().any(|p| {
+                p["agent_id"].as_str() == Some(peer_agent_id)
+                    && p["status"].as_str() == Some("connected")
+            })
+        {
+            return true;
         }
         tokio::time::sleep(Duration::from_millis(200)).await;
     }
@@ -161,6 +185,14 @@ pub(crate) struct TwoDaemons {
 }
 
 pub(crate) async fn setup_connected_pair() -> TwoDaemons {
+    setup_connected_pair_with(|_, _| None).await
+}
+
+/// Like [`setup_connected_pair`], with B answering requests through the
+/// handler `handler_b` builds from A's identity and B's paths.
+pub(crate) async fn setup_connected_pair_with(
+    handler_b: impl FnOnce(&Identity, &AxonPaths) -> Option<ResponseHandlerFn>,
+) -> TwoDaemons {
     let dir_a = tempdir().unwrap();
     let dir_b = tempdir().unwrap();
 
@@ -187,7 +219,16 @@ pub(crate) async fn setup_connected_pair() -> TwoDaemons {
     }];
 
     let daemon_a = spawn_daemon(dir_a.path(), port_a, peers_for_a);
-    let daemon_b = spawn_daemon(dir_b.path(), port_b, peers_for_b);
+    let daemon_b = spawn_daemon_with_handler(
+        dir_b.path(),
+        port_b,
+        Config {
+            port: Some(port_b),
+            peers: peers_for_b,
+            ..Default::default()
+        },
+        handler_b(&id_a, &paths_b),
+    );
 
     assert!(wait_for_socket(&daemon_a.paths, Duration::from_secs(5)).await);
     assert!(wait_for_socket(&daemon_b.paths, Duration::from_secs(5)).await);
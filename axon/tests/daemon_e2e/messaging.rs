@@ -0,0 +1,506 @@
+use super::*;
+
+/// A daemon with `handler.exec` answers inbound requests with the script's
+/// stdout instead of the default `unhandled` error.
+#[tokio::test]
+async fn handler_exec_answers_inbound_requests() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir_a = tempdir().unwrap();
+    let dir_b = tempdir().unwrap();
+    let paths_a = AxonPaths::from_root(PathBuf::from(dir_a.path()));
+    paths_a.ensure_root_exists().unwrap();
+    let id_a = Identity::load_or_generate(&paths_a).unwrap();
+    let paths_b = AxonPaths::from_root(PathBuf::from(dir_b.path()));
+    paths_b.ensure_root_exists().unwrap();
+    let id_b = Identity::load_or_generate(&paths_b).unwrap();
+
+    let script = dir_b.path().join("answer.sh");
+    std::fs::write(
+        &script,
+        "#!/bin/sh\ncat >/dev/null\nprintf '{\"answered_for\":\"%s\"}' \"$AXON_FROM\"\n",
+    )
+    .unwrap();
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    let port_a = pick_free_port();
+    let port_b = pick_free_port();
+    let daemon_a = spawn_daemon(
+        dir_a.path(),
+        port_a,
+        vec![StaticPeerConfig {
+            agent_id: id_b.agent_id().into(),
+            addr: format!("127.0.0.1:{port_b}").parse().unwrap(),
+            pubkey: id_b.public_key_base64().to_string(),
+        }],
+    );
+    let mut config_b = Config {
+        port: Some(port_b),
+        peers: vec![StaticPeerConfig {
+            agent_id: id_a.agent_id().into(),
+            addr: format!("127.0.0.1:{port_a}").parse().unwrap(),
+            pubkey: id_a.public_key_base64().to_string(),
+        }],
+        ..Default::default()
+    };
+    config_b.handler.exec = Some(script);
+    let daemon_b = spawn_daemon_with_config(dir_b.path(), port_b, config_b);
+
+    assert!(wait_for_socket(&daemon_a.paths, Duration::from_secs(5)).await);
+    assert!(wait_for_socket(&daemon_b.paths, Duration::from_secs(5)).await);
+    assert!(
+        wait_for_peer_connected(
+            &daemon_a.paths.socket,
+            id_b.agent_id(),
+            Duration::from_secs(10)
+        )
+        .await,
+        "daemon A did not connect to B"
+    );
+
+    let stream = UnixStream::connect(&daemon_a.paths.socket).await.unwrap();
+    let (read, mut write) = stream.into_split();
+    let mut reader = BufReader::new(read);
+    let cmd = json!({
+        "cmd": "send",
+        "to": id_b.agent_id(),
+        "kind": "request",
+        "payload": {"question": "who am i"}
+    });
+    write
+        .write_all(format!("{cmd}\n").as_bytes())
+        .await
+        .unwrap();
+
+    let mut ack = String::new();
+    timeout(Duration::from_secs(5), reader.read_line(&mut ack))
+        .await
+        .unwrap()
+        .unwrap();
+    let ack: Value = serde_json::from_str(ack.trim()).unwrap();
+    assert_eq!(ack["ok"], json!(true));
+
+    let mut inbound = String::new();
+    timeout(Duration::from_secs(10), reader.read_line(&mut inbound))
+        .await
+        .unwrap()
+        .unwrap();
+    let inbound: Value = serde_json::from_str(inbound.trim()).unwrap();
+    assert_eq!(inbound["event"], json!("inbound"));
+    assert_eq!(inbound["envelope"]["kind"], json!("response"));
+    assert_eq!(inbound["envelope"]["ref"], ack["msg_id"]);
+    assert_eq!(
+        inbound["envelope"]["payload"]["answered_for"],
+        json!(id_a.agent_id())
+    );
+
+    daemon_a.shutdown().await;
+    daemon_b.shutdown().await;
+}
+
+/// With `history.enabled`, sent requests and their responses are queryable
+/// through the `history` command, and disabled daemons say so.
+#[cfg(feature = "history")]
+#[tokio::test]
+async fn history_records_sent_requests_and_responses() {
+    let dir_a = tempdir().unwrap();
+    let dir_b = tempdir().unwrap();
+    let paths_a = AxonPaths::from_root(PathBuf::from(dir_a.path()));
+    paths_a.ensure_root_exists().unwrap();
+    let id_a = Identity::load_or_generate(&paths_a).unwrap();
+    let paths_b = AxonPaths::from_root(PathBuf::from(dir_b.path()));
+    paths_b.ensure_root_exists().unwrap();
+    let id_b = Identity::load_or_generate(&paths_b).unwrap();
+
+    let port_a = pick_free_port();
+    let port_b = pick_free_port();
+    let mut config_a = Config {
+        port: Some(port_a),
+        peers: vec![StaticPeerConfig {
+            agent_id: id_b.agent_id().into(),
+            addr: format!("127.0.0.1:{port_b}").parse().unwrap(),
+            pubkey: id_b.public_key_base64().to_string(),
+        }],
+        ..Default::default()
+    };
+    config_a.history.enabled = Some(true);
+    let daemon_a = spawn_daemon_with_config(dir_a.path(), port_a, config_a);
+    let daemon_b = spawn_daemon(
+        dir_b.path(),
+        port_b,
+        vec![StaticPeerConfig {
+            agent_id: id_a.agent_id().into(),
+            addr: format!("127.0.0.1:{port_a}").parse().unwrap(),
+            pubkey: id_a.public_key_base64().to_string(),
+        }],
+    );
+    assert!(wait_for_socket(&daemon_a.paths, Duration::from_secs(5)).await);
+    assert!(wait_for_socket(&daemon_b.paths, Duration::from_secs(5)).await);
+    assert!(
+        wait_for_peer_connected(
+            &daemon_a.paths.socket,
+            id_b.agent_id(),
+            Duration::from_secs(10)
+        )
+        .await,
+        "daemon A did not connect to B"
+    );
+
+    let ack = ipc_command(
+        &daemon_a.paths.socket,
+        json!({"cmd": "send", "to": id_b.agent_id(), "kind": "request", "payload": {"q": 1}}),
+    )
+    .await
+    .unwrap();
+    assert_eq!(ack["ok"], json!(true));
+
+    let reply = ipc_command(
+        &daemon_a.paths.socket,
+        json!({"cmd": "history", "filter": {"msg_id": ack["msg_id"]}, "req_id": "h1"}),
+    )
+    .await
+    .unwrap();
+    assert_eq!(reply["ok"], json!(true));
+    assert_eq!(reply["req_id"], json!("h1"));
+    let entries = reply["entries"].as_array().unwrap();
+    assert_eq!(entries.len(), 2, "request and its reply: {reply}");
+    assert_eq!(entries[0]["direction"], json!("out"));
+    assert_eq!(entries[0]["peer"], json!(id_b.agent_id()));
+    assert_eq!(entries[0]["envelope"]["payload"], json!({"q": 1}));
+    assert_eq!(entries[1]["direction"], json!("in"));
+    assert_eq!(entries[1]["envelope"]["ref"], ack["msg_id"]);
+    assert!(paths_a.history.exists());
+
+    let disabled = ipc_command(&daemon_b.paths.socket, json!({"cmd": "history"}))
+        .await
+        .unwrap();
+    assert_eq!(disabled["error"], json!("history_disabled"));
+
+    daemon_a.shutdown().await;
+    daemon_b.shutdown().await;
+}
+
+/// A request's `thread_id` comes back on its reply, a later `send` that
+/// `ref`s it joins the same thread, and `history` (with the `history`
+/// feature) filters by thread.
+#[tokio::test]
+async fn thread_id_follows_replies_and_refd_messages() {
+    let dir_a = tempdir().unwrap();
+    let dir_b = tempdir().unwrap();
+    let paths_a = AxonPaths::from_root(PathBuf::from(dir_a.path()));
+    paths_a.ensure_root_exists().unwrap();
+    let id_a = Identity::load_or_generate(&paths_a).unwrap();
+    let paths_b = AxonPaths::from_root(PathBuf::from(dir_b.path()));
+    paths_b.ensure_root_exists().unwrap();
+    let id_b = Identity::load_or_generate(&paths_b).unwrap();
+
+    let port_a = pick_free_port();
+    let port_b = pick_free_port();
+    let mut config_a = Config {
+        port: Some(port_a),
+        peers: vec![StaticPeerConfig {
+            agent_id: id_b.agent_id().into(),
+            addr: format!("127.0.0.1:{port_b}").parse().unwrap(),
+            pubkey: id_b.public_key_base64().to_string(),
+        }],
+        ..Default::default()
+    };
+    config_a.history.enabled = Some(cfg!(feature = "history"));
+    let daemon_a = spawn_daemon_with_config(dir_a.path(), port_a, config_a);
+    let daemon_b = spawn_daemon(
+        dir_b.path(),
+        port_b,
+        vec![StaticPeerConfig {
+            agent_id: id_a.agent_id().into(),
+            addr: format!("127.0.0.1:{port_a}").parse().unwrap(),
+            pubkey: id_a.public_key_base64().to_string(),
+        }],
+    );
+    assert!(wait_for_socket(&daemon_a.paths, Duration::from_secs(5)).await);
+    assert!(wait_for_socket(&daemon_b.paths, Duration::from_secs(5)).await);
+    assert!(
+        wait_for_peer_connected(
+            &daemon_a.paths.socket,
+            id_b.agent_id(),
+            Duration::from_secs(10)
+        )
+        .await,
+        "daemon A did not connect to B"
+    );
+
+    let stream = UnixStream::connect(&daemon_b.paths.socket).await.unwrap();
+    let (read, _write) = stream.into_split();
+    let mut reader = BufReader::new(read);
+
+    let ack = ipc_command(
+        &daemon_a.paths.socket,
+        json!({
+            "cmd": "send",
+            "to": id_b.agent_id(),
+            "kind": "request",
+            "payload": {"q": 1},
+            "thread_id": "t-1"
+        }),
+    )
+    .await
+    .unwrap();
+    assert_eq!(ack["ok"], json!(true), "{ack}");
+    assert_eq!(ack["response"]["thread_id"], json!("t-1"), "{ack}");
+
+    let followup = ipc_command(
+        &daemon_a.paths.socket,
+        json!({
+            "cmd": "send",
+            "to": id_b.agent_id(),
+            "kind": "message",
+            "ref": ack["msg_id"],
+            "payload": {"done": true}
+        }),
+    )
+    .await
+    .unwrap();
+    assert_eq!(followup["ok"], json!(true), "{followup}");
+
+    let mut received = Vec::new();
+    while received.len() < 2 {
+        let mut line = String::new();
+        timeout(Duration::from_secs(5), reader.read_line(&mut line))
+            .await
+            .unwrap()
+            .unwrap();
+        let event: Value = serde_json::from_str(line.trim()).unwrap();
+        if event["event"] == json!("inbound") {
+            received.push(event);
+        }
+    }
+    assert_eq!(received[1]["envelope"]["ref"], ack["msg_id"]);
+    assert_eq!(received[1]["envelope"]["thread_id"], json!("t-1"));
+
+    if cfg!(feature = "history") {
+        let reply = ipc_command(
+            &daemon_a.paths.socket,
+            json!({"cmd": "history", "filter": {"thread_id": "t-1"}}),
+        )
+        .await
+        .unwrap();
+        let kinds: Vec<&Value> = reply["entries"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|entry| &entry["envelope"]["kind"])
+            .collect();
+        assert_eq!(
+            kinds,
+            [&json!("request"), &json!("error"), &json!("message")]
+        );
+    }
+
+    daemon_a.shutdown().await;
+    daemon_b.shutdown().await;
+}
+
+/// Envelope headers set on `send` reach the receiver's IPC clients as sent.
+#[tokio::test]
+async fn send_headers_are_delivered_unchanged() {
+    let td = setup_connected_pair().await;
+    let stream = UnixStream::connect(&td.daemon_b.paths.socket)
+        .await
+        .unwrap();
+    let (read, _write) = stream.into_split();
+    let mut reader = BufReader::new(read);
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let headers = json!({"trace_id": "abc123", "tenant": {"id": 7}});
+    let ack = ipc_command(
+        &td.daemon_a.paths.socket,
+        json!({
+            "cmd": "send",
+            "to": td.id_b.agent_id(),
+            "kind": "message",
+            "payload": {},
+            "headers": headers
+        }),
+    )
+    .await
+    .unwrap();
+    assert_eq!(ack["ok"], json!(true), "{ack}");
+
+    loop {
+        let mut line = String::new();
+        timeout(Duration::from_secs(5), reader.read_line(&mut line))
+            .await
+            .unwrap()
+            .unwrap();
+        let event: Value = serde_json::from_str(line.trim()).unwrap();
+        if event["event"] == json!("inbound") {
+            assert_eq!(event["envelope"]["headers"], headers);
+            break;
+        }
+    }
+
+    td.daemon_a.shutdown().await;
+    td.daemon_b.shutdown().await;
+}
+
+/// A message sent with `await: "reply"` is answered with the recipient's
+/// `ref`ing reply, or a timeout error when none arrives.
+#[tokio::test]
+async fn send_await_reply_returns_the_refing_message() {
+    let td = setup_connected_pair().await;
+    let stream = UnixStream::connect(&td.daemon_b.paths.socket)
+        .await
+        .unwrap();
+    let (read, _write) = stream.into_split();
+    let mut reader = BufReader::new(read);
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let mut client_a = UnixStream::connect(&td.daemon_a.paths.socket)
+        .await
+        .unwrap();
+    let command = json!({
+        "cmd": "send",
+        "to": td.id_b.agent_id(),
+        "kind": "message",
+        "payload": {"task": "summarize"},
+        "await": "reply",
+        "timeout_secs": 10,
+        "req_id": "r-1"
+    });
+    client_a
+        .write_all(format!("{command}\n").as_bytes())
+        .await
+        .unwrap();
+    // The reply is also broadcast as an inbound event, so skip events.
+    let awaiting = tokio::spawn(async move {
+        let mut reader = BufReader::new(client_a);
+        loop {
+            let mut line = String::new();
+            timeout(Duration::from_secs(15), reader.read_line(&mut line))
+                .await
+                .unwrap()
+                .unwrap();
+            let reply: Value = serde_json::from_str(line.trim()).unwrap();
+            if reply.get("event").is_none() {
+                return reply;
+            }
+        }
+    });
+
+    let sent = loop {
+        let mut line = String::new();
+        timeout(Duration::from_secs(5), reader.read_line(&mut line))
+            .await
+            .unwrap()
+            .unwrap();
+        let event: Value = serde_json::from_str(line.trim()).unwrap();
+        if event["event"] == json!("inbound") {
+            break event["envelope"].clone();
+        }
+    };
+    // Another daemon command runs while A's client is still waiting.
+    let status = ipc_command(&td.daemon_a.paths.socket, json!({"cmd": "status"}))
+        .await
+        .unwrap();
+    assert_eq!(status["ok"], json!(true), "{status}");
+
+    let answer = ipc_command(
+        &td.daemon_b.paths.socket,
+        json!({
+            "cmd": "send",
+            "to": td.id_a.agent_id(),
+            "kind": "message",
+            "ref": sent["id"],
+            "payload": {"summary": "done"}
+        }),
+    )
+    .await
+    .unwrap();
+    assert_eq!(answer["ok"], json!(true), "{answer}");
+
+    let reply = awaiting.await.unwrap();
+    assert_eq!(reply["ok"], json!(true), "{reply}");
+    assert_eq!(reply["req_id"], json!("r-1"));
+    assert_eq!(reply["msg_id"], sent["id"]);
+    assert_eq!(reply["response"]["ref"], sent["id"]);
+    assert_eq!(reply["response"]["payload"], json!({"summary": "done"}));
+
+    let unanswered = ipc_command_timeout(
+        &td.daemon_a.paths.socket,
+        json!({
+            "cmd": "send",
+            "to": td.id_b.agent_id(),
+            "kind": "message",
+            "payload": {},
+            "await": "reply",
+            "timeout_secs": 1
+        }),
+        Duration::from_secs(5),
+    )
+    .await
+    .unwrap();
+    assert_eq!(unanswered["ok"], json!(false), "{unanswered}");
+    assert_eq!(unanswered["error"], json!("timeout"));
+    assert!(unanswered["details"]["msg_id"].is_string(), "{unanswered}");
+
+    let request = ipc_command(
+        &td.daemon_a.paths.socket,
+        json!({
+            "cmd": "send",
+            "to": td.id_b.agent_id(),
+            "kind": "request",
+            "payload": {},
+            "await": "reply"
+        }),
+    )
+    .await
+    .unwrap();
+    assert_eq!(request["error"], json!("invalid_command"), "{request}");
+
+    td.daemon_a.shutdown().await;
+    td.daemon_b.shutdown().await;
+}
+
+/// A `send` still awaiting its reply at shutdown is answered with
+/// `shutting_down` instead of the connection just closing.
+#[tokio::test]
+async fn send_await_reply_is_answered_at_shutdown() {
+    let td = setup_connected_pair().await;
+    let mut client = UnixStream::connect(&td.daemon_a.paths.socket)
+        .await
+        .unwrap();
+    let command = json!({
+        "cmd": "send",
+        "to": td.id_b.agent_id(),
+        "kind": "message",
+        "payload": {},
+        "await": "reply",
+        "timeout_secs": 30,
+        "req_id": "r-1"
+    });
+    client
+        .write_all(format!("{command}\n").as_bytes())
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    td.daemon_a.shutdown().await;
+
+    // Connection events from the shutdown may come first.
+    let mut reader = BufReader::new(client);
+    let reply = loop {
+        let mut line = String::new();
+        timeout(Duration::from_secs(5), reader.read_line(&mut line))
+            .await
+            .unwrap()
+            .unwrap();
+        let reply: Value = serde_json::from_str(line.trim()).unwrap();
+        if reply.get("event").is_none() {
+            break reply;
+        }
+    };
+    assert_eq!(reply["ok"], json!(false), "{reply}");
+    assert_eq!(reply["error"], json!("shutting_down"));
+    assert_eq!(reply["req_id"], json!("r-1"));
+    assert!(reply["details"]["msg_id"].is_string(), "{reply}");
+
+    td.daemon_b.shutdown().await;
+}
@@ -0,0 +1,170 @@
+use axon::message::MessageKind;
+use axon::node::{AxonNode, AxonNodeBuilder, HandlerError, QueryError};
+
+use super::*;
+
+async fn wait_until_connected(node: &AxonNode, peer: &str) {
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(10);
+    while tokio::time::Instant::now() < deadline {
+        let peers = node.peers().await.unwrap();
+        if peers
+            .iter()
+            .any(|p| p.agent_id == peer && p.status == "connected")
+        {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    panic!("{} never connected to {peer}", node.agent_id());
+}
+
+/// Spawn `a` and a plain node, enrolled with each other and connected.
+async fn connected_pair(
+    a: AxonNodeBuilder,
+    dirs: (&std::path::Path, &std::path::Path),
+) -> (AxonNode, AxonNode) {
+    let (port_a, port_b) = (pick_free_port(), pick_free_port());
+    let a = a
+        .state_root(dirs.0)
+        .port(port_a)
+        .disable_mdns()
+        .spawn()
+        .await
+        .unwrap();
+    let b = AxonNode::builder()
+        .state_root(dirs.1)
+        .port(port_b)
+        .disable_mdns()
+        .spawn()
+        .await
+        .unwrap();
+
+    let enrolled = a
+        .add_peer(b.public_key(), &format!("127.0.0.1:{port_b}"))
+        .await
+        .unwrap();
+    assert_eq!(enrolled, b.agent_id());
+    b.add_peer(a.public_key(), &format!("127.0.0.1:{port_a}"))
+        .await
+        .unwrap();
+    wait_until_connected(&b, a.agent_id()).await;
+    (a, b)
+}
+
+/// Two embedded nodes enroll each other, exchange a message and a request,
+/// and shut down cleanly.
+#[tokio::test]
+async fn embedded_nodes_exchange_messages_and_requests() {
+    let (dir_a, dir_b) = (tempdir().unwrap(), tempdir().unwrap());
+    let (a, b) = connected_pair(AxonNode::builder(), (dir_a.path(), dir_b.path())).await;
+
+    let mut inbound = a.subscribe_inbound().await.unwrap();
+    let msg_id = b.notify(a.agent_id(), json!({"hello": "a"})).await.unwrap();
+    let received = timeout(Duration::from_secs(5), inbound.next())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(received.id, msg_id);
+    assert_eq!(received.payload.get(), r#"{"hello":"a"}"#);
+    assert_eq!(received.from.unwrap().as_str(), b.agent_id());
+
+    let reply = b.send_request(a.agent_id(), json!({})).await.unwrap();
+    assert!(reply.kind.is_response(), "got {}", reply.kind);
+    let request = timeout(Duration::from_secs(5), inbound.next())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(reply.ref_id, Some(request.id));
+
+    let err = b.notify("ed25519.unknown", json!({})).await.unwrap_err();
+    assert!(
+        err.downcast_ref::<axon::node::NodeError>().is_some(),
+        "{err}"
+    );
+
+    let socket = a.paths().socket.clone();
+    a.shutdown().await.unwrap();
+    b.shutdown().await.unwrap();
+    assert!(!socket.exists());
+    assert!(
+        timeout(Duration::from_secs(5), inbound.next())
+            .await
+            .unwrap()
+            .is_none()
+    );
+}
+
+/// Registered handlers answer requests by domain and see inbound messages.
+#[tokio::test]
+async fn embedded_handlers_answer_requests_and_messages() {
+    let (dir_a, dir_b) = (tempdir().unwrap(), tempdir().unwrap());
+    let (seen_tx, mut seen_rx) = tokio::sync::mpsc::unbounded_channel();
+    let a = AxonNode::builder()
+        .on_domain("math", |request| async move {
+            let payload: Value = serde_json::from_str(request.payload.get()).unwrap();
+            let sum: i64 = payload["add"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_i64)
+                .sum();
+            Ok(json!({"sum": sum}))
+        })
+        .on_request(|_| async { Err(HandlerError::new("unsupported", "only math here")) })
+        .on_message(move |message| {
+            let seen_tx = seen_tx.clone();
+            async move {
+                let _ = seen_tx.send(message.payload.get().to_string());
+            }
+        });
+    let (a, b) = connected_pair(a, (dir_a.path(), dir_b.path())).await;
+
+    let reply = b
+        .send_request(a.agent_id(), json!({"domain": "math", "add": [2, 3]}))
+        .await
+        .unwrap();
+    assert_eq!(reply.kind, MessageKind::Response);
+    assert_eq!(reply.payload.get(), r#"{"sum":5}"#);
+
+    let reply = b
+        .send_request(a.agent_id(), json!({"domain": "poetry"}))
+        .await
+        .unwrap();
+    assert_eq!(reply.kind, MessageKind::Error);
+    assert!(reply.payload.get().contains("unsupported"));
+
+    #[derive(serde::Serialize)]
+    struct Add<'a> {
+        domain: &'a str,
+        add: Vec<i64>,
+    }
+    #[derive(serde::Deserialize)]
+    struct Sum {
+        sum: i64,
+    }
+    let sum: Sum = b
+        .query(
+            a.agent_id(),
+            &Add {
+                domain: "math",
+                add: vec![4, 5],
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(sum.sum, 9);
+    match b.query::<_, Sum>(a.agent_id(), &json!({})).await {
+        Err(QueryError::Remote(error)) => assert_eq!(error.code, "unsupported"),
+        other => panic!("expected a remote error, got {:?}", other.err()),
+    }
+
+    b.notify(a.agent_id(), json!({"ping": 1})).await.unwrap();
+    let seen = timeout(Duration::from_secs(5), seen_rx.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(seen, r#"{"ping":1}"#);
+
+    a.shutdown().await.unwrap();
+    b.shutdown().await.unwrap();
+}
@@ -0,0 +1,405 @@
+use super::*;
+
+/// whois returns the full peer record, per-peer counters, and reconnect
+/// state for a peer that is down.
+#[tokio::test]
+async fn whois_reports_peer_record_counters_and_reconnect_state() {
+    let dir_a = tempdir().unwrap();
+    let dir_b = tempdir().unwrap();
+    let dir_c = tempdir().unwrap();
+    let paths_a = AxonPaths::from_root(PathBuf::from(dir_a.path()));
+    paths_a.ensure_root_exists().unwrap();
+    let id_a = Identity::load_or_generate(&paths_a).unwrap();
+    let paths_b = AxonPaths::from_root(PathBuf::from(dir_b.path()));
+    paths_b.ensure_root_exists().unwrap();
+    let id_b = Identity::load_or_generate(&paths_b).unwrap();
+    // C is configured on A but never started.
+    let paths_c = AxonPaths::from_root(PathBuf::from(dir_c.path()));
+    paths_c.ensure_root_exists().unwrap();
+    let id_c = Identity::load_or_generate(&paths_c).unwrap();
+
+    let port_a = pick_free_port();
+    let port_b = pick_free_port();
+    let port_c = pick_free_port();
+    let daemon_a = spawn_daemon(
+        dir_a.path(),
+        port_a,
+        vec![
+            StaticPeerConfig {
+                agent_id: id_b.agent_id().into(),
+                addr: format!("127.0.0.1:{port_b}").parse().unwrap(),
+                pubkey: id_b.public_key_base64().to_string(),
+            },
+            StaticPeerConfig {
+                agent_id: id_c.agent_id().into(),
+                addr: format!("127.0.0.1:{port_c}").parse().unwrap(),
+                pubkey: id_c.public_key_base64().to_string(),
+            },
+        ],
+    );
+    let daemon_b = spawn_daemon(
+        dir_b.path(),
+        port_b,
+        vec![StaticPeerConfig {
+            agent_id: id_a.agent_id().into(),
+            addr: format!("127.0.0.1:{port_a}").parse().unwrap(),
+            pubkey: id_a.public_key_base64().to_string(),
+        }],
+    );
+    assert!(wait_for_socket(&daemon_a.paths, Duration::from_secs(5)).await);
+    assert!(wait_for_socket(&daemon_b.paths, Duration::from_secs(5)).await);
+    assert!(
+        wait_for_peer_connected(
+            &daemon_a.paths.socket,
+            id_b.agent_id(),
+            Duration::from_secs(10)
+        )
+        .await,
+        "daemon A did not connect to B"
+    );
+
+    let ack = ipc_command(
+        &daemon_a.paths.socket,
+        json!({"cmd": "send", "to": id_b.agent_id(), "kind": "request", "payload": {"q": 1}}),
+    )
+    .await
+    .unwrap();
+    assert_eq!(ack["ok"], json!(true));
+
+    let reply = ipc_command(
+        &daemon_a.paths.socket,
+        json!({"cmd": "whois", "agent_id": id_b.agent_id(), "req_id": "w"}),
+    )
+    .await
+    .unwrap();
+    assert_eq!(reply["ok"], json!(true), "{reply}");
+    assert_eq!(reply["req_id"], json!("w"));
+    let peer = &reply["peer"];
+    assert_eq!(peer["agent_id"], json!(id_b.agent_id()));
+    assert_eq!(peer["pubkey"], json!(id_b.public_key_base64()));
+    assert_eq!(peer["addr"], json!(format!("127.0.0.1:{port_b}")));
+    assert_eq!(peer["source"], json!("static"));
+    assert_eq!(peer["status"], json!("connected"));
+    assert_eq!(peer["messages_sent"], json!(1));
+    assert_eq!(peer["messages_received"], json!(1));
+    assert!(peer["last_seen_ms"].as_u64().unwrap() > 0);
+    assert!(peer.get("reconnect").is_none(), "{peer}");
+
+    let peers = ipc_command(&daemon_a.paths.socket, json!({"cmd": "peers"}))
+        .await
+        .unwrap();
+    let listed = peers["peers"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|p| p["agent_id"] == json!(id_b.agent_id()))
+        .expect("peer B listed");
+    let traffic = &listed["traffic"];
+    assert_eq!(traffic["messages_sent"], json!(1), "{traffic}");
+    assert_eq!(traffic["messages_received"], json!(1), "{traffic}");
+    assert!(traffic["bytes_sent"].as_u64().unwrap() > 0, "{traffic}");
+    assert!(traffic["bytes_received"].as_u64().unwrap() > 0, "{traffic}");
+    assert_eq!(traffic["errors"], json!(0), "{traffic}");
+    assert!(
+        traffic["last_message_at_ms"].as_u64().unwrap() > 0,
+        "{traffic}"
+    );
+
+    let down = ipc_command(
+        &daemon_a.paths.socket,
+        json!({"cmd": "whois", "agent_id": id_c.agent_id()}),
+    )
+    .await
+    .unwrap();
+    assert_eq!(down["peer"]["messages_sent"], json!(0));
+    assert_ne!(down["peer"]["status"], json!("connected"));
+    assert!(down["peer"]["reconnect"]["backoff_secs"].is_u64(), "{down}");
+
+    let unknown = ipc_command(
+        &daemon_a.paths.socket,
+        json!({"cmd": "whois", "agent_id": "ed25519.00000000000000000000000000000000"}),
+    )
+    .await
+    .unwrap();
+    assert_eq!(unknown["error"], json!("peer_not_found"));
+
+    daemon_a.shutdown().await;
+    daemon_b.shutdown().await;
+}
+
+/// A peer that goes quiet for longer than `miss_threshold` heartbeat intervals
+/// is reported `degraded` while its connection stays up, and returns to
+/// `connected` on its next heartbeat. Heartbeats are not counted as messages.
+#[tokio::test]
+async fn missed_heartbeats_mark_peer_degraded_until_next_heartbeat() {
+    let dir_a = tempdir().unwrap();
+    let dir_b = tempdir().unwrap();
+    let paths_a = AxonPaths::from_root(PathBuf::from(dir_a.path()));
+    paths_a.ensure_root_exists().unwrap();
+    let id_a = Identity::load_or_generate(&paths_a).unwrap();
+    let paths_b = AxonPaths::from_root(PathBuf::from(dir_b.path()));
+    paths_b.ensure_root_exists().unwrap();
+    let id_b = Identity::load_or_generate(&paths_b).unwrap();
+
+    let port_a = pick_free_port();
+    let port_b = pick_free_port();
+    // A expects traffic every second; B only heartbeats every 3 seconds.
+    let daemon_a = spawn_daemon_with_config(
+        dir_a.path(),
+        port_a,
+        Config {
+            port: Some(port_a),
+            peers: vec![StaticPeerConfig {
+                agent_id: id_b.agent_id().into(),
+                addr: format!("127.0.0.1:{port_b}").parse().unwrap(),
+                pubkey: id_b.public_key_base64().to_string(),
+            }],
+            heartbeat: HeartbeatConfig {
+                interval_secs: Some(1),
+                miss_threshold: Some(1),
+            },
+            ..Default::default()
+        },
+    );
+    let daemon_b = spawn_daemon_with_config(
+        dir_b.path(),
+        port_b,
+        Config {
+            port: Some(port_b),
+            peers: vec![StaticPeerConfig {
+                agent_id: id_a.agent_id().into(),
+                addr: format!("127.0.0.1:{port_a}").parse().unwrap(),
+                pubkey: id_a.public_key_base64().to_string(),
+            }],
+            heartbeat: HeartbeatConfig {
+                interval_secs: Some(3),
+                miss_threshold: None,
+            },
+            ..Default::default()
+        },
+    );
+    assert!(wait_for_socket(&daemon_a.paths, Duration::from_secs(5)).await);
+    assert!(wait_for_socket(&daemon_b.paths, Duration::from_secs(5)).await);
+    assert!(
+        wait_for_peer_connected(
+            &daemon_a.paths.socket,
+            id_b.agent_id(),
+            Duration::from_secs(10)
+        )
+        .await,
+        "daemon A did not connect to B"
+    );
+
+    let socket_a = daemon_a.paths.socket.clone();
+    assert!(
+        wait_for_peer_status(&socket_a, id_b.agent_id(), "degraded").await,
+        "B was never degraded"
+    );
+    assert!(
+        wait_for_peer_status(&socket_a, id_b.agent_id(), "connected").await,
+        "B did not recover"
+    );
+
+    for daemon in [&daemon_a, &daemon_b] {
+        let status = ipc_command(&daemon.paths.socket, json!({"cmd": "status"}))
+            .await
+            .unwrap();
+        assert_eq!(status["peers_connected"], json!(1), "{status}");
+        assert_eq!(status["messages_received"], json!(0), "{status}");
+    }
+
+    daemon_a.shutdown().await;
+    daemon_b.shutdown().await;
+}
+
+async fn wait_for_peer_status(socket: &std::path::Path, agent_id: &str, status: &str) -> bool {
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(10);
+    while tokio::time::Instant::now() < deadline {
+        if let Ok(reply) = ipc_command(socket, json!({"cmd": "whois", "agent_id": agent_id})).await
+            && reply["peer"]["status"] == json!(status)
+        {
+            return true;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    false
+}
+
+/// health reports every subsystem ready on a freshly started daemon.
+#[tokio::test]
+async fn health_reports_ready_subsystems() {
+    let dir = tempdir().unwrap();
+    let port = pick_free_port();
+    let daemon = spawn_daemon(dir.path(), port, vec![]);
+    assert!(wait_for_socket(&daemon.paths, Duration::from_secs(5)).await);
+
+    let reply = ipc_command(
+        &daemon.paths.socket,
+        json!({"cmd": "health", "req_id": "h"}),
+    )
+    .await
+    .unwrap();
+    assert_eq!(reply["ok"], json!(true));
+    assert_eq!(reply["healthy"], json!(true), "{reply}");
+    assert_eq!(reply["req_id"], json!("h"));
+    let subsystems = &reply["subsystems"];
+    assert_eq!(
+        subsystems["transport"]["bound_addr"],
+        json!(format!("0.0.0.0:{port}"))
+    );
+    assert_eq!(subsystems["discovery"]["mdns"], json!("disabled"));
+    assert_eq!(subsystems["ipc"]["clients"], json!(1));
+    assert_eq!(subsystems["history"]["enabled"], json!(false));
+
+    daemon.shutdown().await;
+}
+
+/// `clear_pin` forgets a cached peer's key, in the table and on disk, and
+/// refuses static peers, which belong to config.yaml.
+#[tokio::test]
+async fn clear_pin_forgets_cached_peer_but_not_static() {
+    let dir_a = tempdir().unwrap();
+    let dir_b = tempdir().unwrap();
+    let dir_c = tempdir().unwrap();
+    let ids: Vec<Identity> = [dir_b.path(), dir_c.path()]
+        .iter()
+        .map(|dir| {
+            let paths = AxonPaths::from_root(PathBuf::from(dir));
+            paths.ensure_root_exists().unwrap();
+            Identity::load_or_generate(&paths).unwrap()
+        })
+        .collect();
+    let (id_b, id_c) = (&ids[0], &ids[1]);
+
+    let paths_a = AxonPaths::from_root(PathBuf::from(dir_a.path()));
+    paths_a.ensure_root_exists().unwrap();
+    save_known_peers(
+        &paths_a.known_peers,
+        &[KnownPeer {
+            agent_id: id_b.agent_id().into(),
+            addr: format!("127.0.0.1:{}", pick_free_port()).parse().unwrap(),
+            pubkey: id_b.public_key_base64().to_string(),
+            last_seen_unix_ms: 0,
+            first_seen_unix_ms: None,
+            source: KnownPeerSource::Cached,
+        }],
+    )
+    .await
+    .unwrap();
+
+    let port_a = pick_free_port();
+    let daemon_a = spawn_daemon(
+        dir_a.path(),
+        port_a,
+        vec![StaticPeerConfig {
+            agent_id: id_c.agent_id().into(),
+            addr: format!("127.0.0.1:{}", pick_free_port()).parse().unwrap(),
+            pubkey: id_c.public_key_base64().to_string(),
+        }],
+    );
+    assert!(wait_for_socket(&daemon_a.paths, Duration::from_secs(5)).await);
+    let socket = &daemon_a.paths.socket;
+
+    let reply = ipc_command(socket, json!({"cmd": "pins"})).await.unwrap();
+    assert_eq!(reply["pins"].as_array().unwrap().len(), 2, "{reply}");
+    let reply = ipc_command(socket, json!({"cmd": "pins", "agent_id": id_b.agent_id()}))
+        .await
+        .unwrap();
+    assert_eq!(reply["pins"][0]["source"], json!("cached"), "{reply}");
+    assert_eq!(
+        reply["pins"][0]["pubkey"],
+        json!(id_b.public_key_base64()),
+        "{reply}"
+    );
+
+    let reply = ipc_command(
+        socket,
+        json!({"cmd": "clear_pin", "agent_id": id_c.agent_id()}),
+    )
+    .await
+    .unwrap();
+    assert_eq!(reply["error"], json!("invalid_command"), "{reply}");
+
+    let reply = ipc_command(
+        socket,
+        json!({"cmd": "clear_pin", "agent_id": id_b.agent_id()}),
+    )
+    .await
+    .unwrap();
+    assert_eq!(reply["ok"], json!(true), "{reply}");
+    assert_eq!(reply["source"], json!("cached"), "{reply}");
+
+    let reply = ipc_command(socket, json!({"cmd": "pins", "agent_id": id_b.agent_id()}))
+        .await
+        .unwrap();
+    assert_eq!(reply["error"], json!("peer_not_found"), "{reply}");
+    let known = load_known_peers(&daemon_a.paths.known_peers).await.unwrap();
+    assert!(
+        known
+            .iter()
+            .all(|peer| peer.agent_id.as_str() != id_b.agent_id()),
+        "{known:?}"
+    );
+
+    daemon_a.shutdown().await;
+}
+
+/// `peers` reports tags from config.yaml, filters by `tag`, and picks up
+/// tag changes on reload.
+#[tokio::test]
+async fn peers_reports_and_filters_config_tags() {
+    let dir_a = tempdir().unwrap();
+    let dir_b = tempdir().unwrap();
+    let paths_b = AxonPaths::from_root(PathBuf::from(dir_b.path()));
+    paths_b.ensure_root_exists().unwrap();
+    let id_b = Identity::load_or_generate(&paths_b).unwrap();
+    let agent_b: axon::message::AgentId = id_b.agent_id().into();
+
+    let port_a = pick_free_port();
+    let peer_b = StaticPeerConfig {
+        agent_id: agent_b.clone(),
+        addr: format!("127.0.0.1:{}", pick_free_port()).parse().unwrap(),
+        pubkey: id_b.public_key_base64().to_string(),
+    };
+    let daemon_a = spawn_daemon_with_config(
+        dir_a.path(),
+        port_a,
+        Config {
+            port: Some(port_a),
+            peers: vec![peer_b.clone()],
+            tags: [(agent_b.clone(), vec!["prod".to_string()])].into(),
+            ..Default::default()
+        },
+    );
+    assert!(wait_for_socket(&daemon_a.paths, Duration::from_secs(5)).await);
+    let socket = &daemon_a.paths.socket;
+
+    let reply = ipc_command(socket, json!({"cmd": "peers", "tag": "prod"}))
+        .await
+        .unwrap();
+    assert_eq!(reply["peers"][0]["tags"], json!(["prod"]), "{reply}");
+    let reply = ipc_command(socket, json!({"cmd": "peers", "tag": "gpu"}))
+        .await
+        .unwrap();
+    assert_eq!(reply["peers"], json!([]), "{reply}");
+
+    let config = Config {
+        port: Some(port_a),
+        peers: vec![peer_b],
+        tags: [(agent_b, vec!["gpu".to_string()])].into(),
+        ..Default::default()
+    };
+    std::fs::write(
+        &daemon_a.paths.config,
+        serde_yaml::to_string(&config).unwrap(),
+    )
+    .unwrap();
+    let reply = ipc_command(socket, json!({"cmd": "reload"})).await.unwrap();
+    assert_eq!(reply["ok"], json!(true), "{reply}");
+    let reply = ipc_command(socket, json!({"cmd": "peers", "tag": "gpu"}))
+        .await
+        .unwrap();
+    assert_eq!(reply["peers"][0]["tags"], json!(["gpu"]), "{reply}");
+
+    daemon_a.shutdown().await;
+}
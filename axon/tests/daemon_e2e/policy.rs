@@ -0,0 +1,285 @@
+use super::*;
+
+/// `max_message_bytes` caps are enforced before sending (local IPC error)
+/// and on receipt (`message_too_large` reply, dropped messages).
+#[tokio::test]
+async fn message_size_caps_apply_per_kind_on_send_and_receive() {
+    let dir_a = tempdir().unwrap();
+    let dir_b = tempdir().unwrap();
+    let paths_a = AxonPaths::from_root(PathBuf::from(dir_a.path()));
+    paths_a.ensure_root_exists().unwrap();
+    let id_a = Identity::load_or_generate(&paths_a).unwrap();
+    let paths_b = AxonPaths::from_root(PathBuf::from(dir_b.path()));
+    paths_b.ensure_root_exists().unwrap();
+    let id_b = Identity::load_or_generate(&paths_b).unwrap();
+
+    let port_a = pick_free_port();
+    let port_b = pick_free_port();
+    let daemon_a = spawn_daemon_with_config(
+        dir_a.path(),
+        port_a,
+        Config {
+            port: Some(port_a),
+            peers: vec![StaticPeerConfig {
+                agent_id: id_b.agent_id().into(),
+                addr: format!("127.0.0.1:{port_b}").parse().unwrap(),
+                pubkey: id_b.public_key_base64().to_string(),
+            }],
+            max_message_bytes: MaxMessageBytesConfig {
+                message: Some(1024),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+    let daemon_b = spawn_daemon_with_config(
+        dir_b.path(),
+        port_b,
+        Config {
+            port: Some(port_b),
+            peers: vec![StaticPeerConfig {
+                agent_id: id_a.agent_id().into(),
+                addr: format!("127.0.0.1:{port_a}").parse().unwrap(),
+                pubkey: id_a.public_key_base64().to_string(),
+            }],
+            max_message_bytes: MaxMessageBytesConfig {
+                request: Some(1024),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+    assert!(wait_for_socket(&daemon_a.paths, Duration::from_secs(5)).await);
+    assert!(wait_for_socket(&daemon_b.paths, Duration::from_secs(5)).await);
+    assert!(
+        wait_for_peer_connected(
+            &daemon_a.paths.socket,
+            id_b.agent_id(),
+            Duration::from_secs(10)
+        )
+        .await,
+        "daemon A did not connect to B"
+    );
+
+    let big = json!({"blob": "x".repeat(2048)});
+    let send =
+        |kind: &str| json!({"cmd": "send", "to": id_b.agent_id(), "kind": kind, "payload": big});
+
+    let reply = ipc_command(&daemon_a.paths.socket, send("message"))
+        .await
+        .unwrap();
+    assert_eq!(reply["error"], json!("invalid_command"), "{reply}");
+
+    let reply = ipc_command(&daemon_a.paths.socket, send("request"))
+        .await
+        .unwrap();
+    assert_eq!(reply["ok"], json!(true), "{reply}");
+    assert_eq!(reply["response"]["kind"], json!("error"), "{reply}");
+    assert_eq!(
+        reply["response"]["payload"]["code"],
+        json!("message_too_large"),
+        "{reply}"
+    );
+
+    let status = ipc_command(&daemon_b.paths.socket, json!({"cmd": "status"}))
+        .await
+        .unwrap();
+    assert_eq!(status["messages_received"], json!(0), "{status}");
+
+    daemon_a.shutdown().await;
+    daemon_b.shutdown().await;
+}
+
+#[tokio::test]
+async fn kind_policy_rejects_disallowed_kinds_from_a_peer() {
+    let dir_a = tempdir().unwrap();
+    let dir_b = tempdir().unwrap();
+    let paths_a = AxonPaths::from_root(PathBuf::from(dir_a.path()));
+    paths_a.ensure_root_exists().unwrap();
+    let id_a = Identity::load_or_generate(&paths_a).unwrap();
+    let paths_b = AxonPaths::from_root(PathBuf::from(dir_b.path()));
+    paths_b.ensure_root_exists().unwrap();
+    let id_b = Identity::load_or_generate(&paths_b).unwrap();
+
+    let port_a = pick_free_port();
+    let port_b = pick_free_port();
+    // A accepts only fire-and-forget messages from B.
+    let daemon_a = spawn_daemon_with_config(
+        dir_a.path(),
+        port_a,
+        Config {
+            port: Some(port_a),
+            peers: vec![StaticPeerConfig {
+                agent_id: id_b.agent_id().into(),
+                addr: format!("127.0.0.1:{port_b}").parse().unwrap(),
+                pubkey: id_b.public_key_base64().to_string(),
+            }],
+            policy: PolicyConfig {
+                peers: [(
+                    id_b.agent_id().into(),
+                    PeerPolicyConfig {
+                        allow_kinds: vec!["message".to_string()],
+                    },
+                )]
+                .into(),
+            },
+            ..Default::default()
+        },
+    );
+    let daemon_b = spawn_daemon_with_config(
+        dir_b.path(),
+        port_b,
+        Config {
+            port: Some(port_b),
+            peers: vec![StaticPeerConfig {
+                agent_id: id_a.agent_id().into(),
+                addr: format!("127.0.0.1:{port_a}").parse().unwrap(),
+                pubkey: id_a.public_key_base64().to_string(),
+            }],
+            ..Default::default()
+        },
+    );
+    assert!(wait_for_socket(&daemon_a.paths, Duration::from_secs(5)).await);
+    assert!(wait_for_socket(&daemon_b.paths, Duration::from_secs(5)).await);
+    assert!(
+        wait_for_peer_connected(
+            &daemon_b.paths.socket,
+            id_a.agent_id(),
+            Duration::from_secs(10)
+        )
+        .await,
+        "daemon B did not connect to A"
+    );
+
+    let send = |kind: &str| json!({"cmd": "send", "to": id_a.agent_id(), "kind": kind, "payload": {"n": 1}});
+    let reply = ipc_command(&daemon_b.paths.socket, send("request"))
+        .await
+        .unwrap();
+    assert_eq!(reply["ok"], json!(true), "{reply}");
+    assert_eq!(reply["response"]["kind"], json!("error"), "{reply}");
+    assert_eq!(
+        reply["response"]["payload"]["code"],
+        json!("not_authorized"),
+        "{reply}"
+    );
+
+    let reply = ipc_command(&daemon_b.paths.socket, send("message"))
+        .await
+        .unwrap();
+    assert_eq!(reply["ok"], json!(true), "{reply}");
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(5);
+    loop {
+        let status = ipc_command(&daemon_a.paths.socket, json!({"cmd": "status"}))
+            .await
+            .unwrap();
+        if status["messages_received"] == json!(1) {
+            break;
+        }
+        assert!(
+            tokio::time::Instant::now() < deadline,
+            "allowed message was not received: {status}"
+        );
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+
+    daemon_a.shutdown().await;
+    daemon_b.shutdown().await;
+}
+
+/// With `audit.enabled`, sends, replies, and authenticated connections are
+/// appended to `audit.jsonl`.
+#[tokio::test]
+async fn audit_log_records_message_and_auth_activity() {
+    let dir_a = tempdir().unwrap();
+    let dir_b = tempdir().unwrap();
+    let paths_a = AxonPaths::from_root(PathBuf::from(dir_a.path()));
+    paths_a.ensure_root_exists().unwrap();
+    let id_a = Identity::load_or_generate(&paths_a).unwrap();
+    let paths_b = AxonPaths::from_root(PathBuf::from(dir_b.path()));
+    paths_b.ensure_root_exists().unwrap();
+    let id_b = Identity::load_or_generate(&paths_b).unwrap();
+
+    let port_a = pick_free_port();
+    let port_b = pick_free_port();
+    let mut config_a = Config {
+        port: Some(port_a),
+        peers: vec![StaticPeerConfig {
+            agent_id: id_b.agent_id().into(),
+            addr: format!("127.0.0.1:{port_b}").parse().unwrap(),
+            pubkey: id_b.public_key_base64().to_string(),
+        }],
+        ..Default::default()
+    };
+    config_a.audit.enabled = Some(true);
+    let daemon_a = spawn_daemon_with_config(dir_a.path(), port_a, config_a);
+    let daemon_b = spawn_daemon(
+        dir_b.path(),
+        port_b,
+        vec![StaticPeerConfig {
+            agent_id: id_a.agent_id().into(),
+            addr: format!("127.0.0.1:{port_a}").parse().unwrap(),
+            pubkey: id_a.public_key_base64().to_string(),
+        }],
+    );
+    assert!(wait_for_socket(&daemon_a.paths, Duration::from_secs(5)).await);
+    assert!(wait_for_socket(&daemon_b.paths, Duration::from_secs(5)).await);
+    assert!(
+        wait_for_peer_connected(
+            &daemon_a.paths.socket,
+            id_b.agent_id(),
+            Duration::from_secs(10)
+        )
+        .await,
+        "daemon A did not connect to B"
+    );
+
+    let ack = ipc_command(
+        &daemon_a.paths.socket,
+        json!({"cmd": "send", "to": id_b.agent_id(), "kind": "request", "payload": {"q": 1}}),
+    )
+    .await
+    .unwrap();
+    assert_eq!(ack["ok"], json!(true));
+
+    // Records are appended by the daemon's forwarders, not before the ack.
+    let read_records = || -> Vec<Value> {
+        std::fs::read_to_string(&paths_a.audit)
+            .unwrap_or_default()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    };
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(5);
+    let mut records = read_records();
+    while ["auth_accept", "send", "receive"]
+        .iter()
+        .any(|event| !records.iter().any(|record| record["event"] == json!(event)))
+        && tokio::time::Instant::now() < deadline
+    {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        records = read_records();
+    }
+    let find = |event: &str| {
+        records
+            .iter()
+            .find(|record| record["event"] == json!(event))
+            .unwrap_or_else(|| panic!("no {event} record in {records:?}"))
+    };
+    assert_eq!(find("auth_accept")["peer"], json!(id_b.agent_id()));
+    let sent = find("send");
+    assert_eq!(sent["msg_id"], ack["msg_id"]);
+    assert_eq!(sent["agent_id"], json!(id_a.agent_id()));
+    assert_eq!(sent["peer"], json!(id_b.agent_id()));
+    assert_eq!(find("receive")["peer"], json!(id_b.agent_id()));
+    assert!(!paths_b.audit.exists(), "audit is opt-in");
+
+    daemon_a.shutdown().await;
+    daemon_b.shutdown().await;
+
+    // Shutdown signs the chain, so the whole log verifies with A's key.
+    let report = axon::daemon::verify_audit_log(&paths_a.audit, &id_a.verifying_key()).unwrap();
+    assert!(report.ok(), "{:?}", report.problems);
+    assert!(report.starts_at_genesis);
+    assert!(report.checkpoints >= 1);
+    assert_eq!(report.unsigned_tail, 0, "{report:?}");
+}
@@ -0,0 +1,226 @@
+use std::sync::Arc;
+
+use axon::message::{Envelope, MessageKind};
+
+use super::*;
+
+/// Read lines from `reader` until one satisfies `done`, returning them all.
+async fn read_until(
+    reader: &mut BufReader<tokio::net::unix::OwnedReadHalf>,
+    done: impl Fn(&Value) -> bool,
+) -> Vec<Value> {
+    let mut lines = Vec::new();
+    loop {
+        let mut line = String::new();
+        timeout(Duration::from_secs(10), reader.read_line(&mut line))
+            .await
+            .expect("timed out waiting for IPC line")
+            .unwrap();
+        let value: Value = serde_json::from_str(line.trim()).unwrap();
+        let finished = done(&value);
+        lines.push(value);
+        if finished {
+            return lines;
+        }
+    }
+}
+
+/// A reply that arrives while the request is still waiting for its response
+/// is routed to the sender, so an `own_replies` client never sees it.
+#[tokio::test]
+async fn reply_during_send_reaches_only_the_sender() {
+    let td = setup_connected_pair_with(|id_a, paths_b| {
+        let (socket, a) = (paths_b.socket.clone(), id_a.agent_id().to_string());
+        let handler: ResponseHandlerFn = Arc::new(move |request: Arc<Envelope>| {
+            let (socket, a) = (socket.clone(), a.clone());
+            Box::pin(async move {
+                // Reply by message first, and only then answer the request.
+                let sent = ipc_command(
+                    &socket,
+                    json!({
+                        "cmd": "send",
+                        "to": a,
+                        "kind": "message",
+                        "ref": request.id,
+                        "payload": {"early": true}
+                    }),
+                )
+                .await
+                .ok()?;
+                assert_eq!(sent["ok"], true, "{sent}");
+                tokio::time::sleep(Duration::from_millis(300)).await;
+                let from = request.to.clone()?;
+                Some(Envelope::response_to(
+                    &request,
+                    from,
+                    MessageKind::Response,
+                    json!({"done": true}),
+                ))
+            })
+        });
+        Some(handler)
+    })
+    .await;
+
+    let (other_read, mut other_write) = UnixStream::connect(&td.daemon_a.paths.socket)
+        .await
+        .unwrap()
+        .into_split();
+    let mut other = BufReader::new(other_read);
+    other_write
+        .write_all(b"{\"cmd\":\"own_replies\"}\n")
+        .await
+        .unwrap();
+    read_until(&mut other, |line| line.get("ok").is_some()).await;
+
+    let (sender_read, mut sender_write) = UnixStream::connect(&td.daemon_a.paths.socket)
+        .await
+        .unwrap()
+        .into_split();
+    let mut sender = BufReader::new(sender_read);
+    let command = json!({
+        "cmd": "send",
+        "to": td.id_b.agent_id(),
+        "kind": "request",
+        "payload": {}
+    });
+    sender_write
+        .write_all(format!("{command}\n").as_bytes())
+        .await
+        .unwrap();
+    let lines = read_until(&mut sender, |line| line.get("msg_id").is_some()).await;
+    let ack = lines.last().unwrap();
+    assert_eq!(ack["ok"], true, "{ack}");
+    assert_eq!(ack["response"]["payload"]["done"], true);
+    let early = &lines[0];
+    assert_eq!(early["event"], "inbound", "reply arrives before the ack");
+    assert_eq!(early["envelope"]["payload"]["early"], true);
+    assert_eq!(early["envelope"]["ref"], ack["msg_id"]);
+
+    let mut line = String::new();
+    let leaked = timeout(Duration::from_millis(500), other.read_line(&mut line)).await;
+    assert!(leaked.is_err(), "own_replies client saw a reply: {line}");
+
+    td.daemon_a.shutdown().await;
+    td.daemon_b.shutdown().await;
+}
+
+/// A send waiting on a slow peer does not hold up commands sent after it.
+#[tokio::test]
+async fn commands_are_answered_while_a_send_waits() {
+    let td = setup_connected_pair_with(|_, _| {
+        let handler: ResponseHandlerFn = Arc::new(|request: Arc<Envelope>| {
+            Box::pin(async move {
+                tokio::time::sleep(Duration::from_secs(1)).await;
+                let from = request.to.clone()?;
+                Some(Envelope::response_to(
+                    &request,
+                    from,
+                    MessageKind::Response,
+                    json!({"done": true}),
+                ))
+            })
+        });
+        Some(handler)
+    })
+    .await;
+
+    let (read, mut write) = UnixStream::connect(&td.daemon_a.paths.socket)
+        .await
+        .unwrap()
+        .into_split();
+    let mut reader = BufReader::new(read);
+    let send = json!({
+        "cmd": "send",
+        "to": td.id_b.agent_id(),
+        "kind": "request",
+        "payload": {},
+        "req_id": "slow"
+    });
+    let status = json!({"cmd": "status", "req_id": "fast"});
+    write
+        .write_all(format!("{send}\n{status}\n").as_bytes())
+        .await
+        .unwrap();
+
+    let lines = read_until(&mut reader, |line| line["req_id"] == "slow").await;
+    let order: Vec<_> = lines
+        .iter()
+        .filter_map(|line| line["req_id"].as_str())
+        .collect();
+    assert_eq!(order, ["fast", "slow"]);
+    assert_eq!(lines.last().unwrap()["response"]["payload"]["done"], true);
+
+    td.daemon_a.shutdown().await;
+    td.daemon_b.shutdown().await;
+}
+
+/// A reply that arrives while shutdown drains an in-flight send still
+/// reaches the client, followed by the send's own result.
+#[tokio::test]
+async fn reply_during_shutdown_drain_is_delivered() {
+    let td = setup_connected_pair_with(|id_a, paths_b| {
+        let (socket, a) = (paths_b.socket.clone(), id_a.agent_id().to_string());
+        let handler: ResponseHandlerFn = Arc::new(move |request: Arc<Envelope>| {
+            let (socket, a) = (socket.clone(), a.clone());
+            Box::pin(async move {
+                // A has started shutting down by now.
+                tokio::time::sleep(Duration::from_millis(500)).await;
+                let sent = ipc_command(
+                    &socket,
+                    json!({
+                        "cmd": "send",
+                        "to": a,
+                        "kind": "message",
+                        "ref": request.id,
+                        "payload": {"early": true}
+                    }),
+                )
+                .await
+                .ok()?;
+                assert_eq!(sent["ok"], true, "{sent}");
+                tokio::time::sleep(Duration::from_millis(300)).await;
+                let from = request.to.clone()?;
+                Some(Envelope::response_to(
+                    &request,
+                    from,
+                    MessageKind::Response,
+                    json!({"done": true}),
+                ))
+            })
+        });
+        Some(handler)
+    })
+    .await;
+
+    let (read, mut write) = UnixStream::connect(&td.daemon_a.paths.socket)
+        .await
+        .unwrap()
+        .into_split();
+    let mut reader = BufReader::new(read);
+    let command = json!({
+        "cmd": "send",
+        "to": td.id_b.agent_id(),
+        "kind": "request",
+        "payload": {}
+    });
+    write
+        .write_all(format!("{command}\n").as_bytes())
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    let stopping = ipc_command(&td.daemon_a.paths.socket, json!({"cmd": "shutdown"}))
+        .await
+        .unwrap();
+    assert_eq!(stopping["ok"], true, "{stopping}");
+
+    let lines = read_until(&mut reader, |line| line.get("msg_id").is_some()).await;
+    let ack = lines.last().unwrap();
+    assert_eq!(ack["response"]["payload"]["done"], true, "{ack}");
+    let early = &lines[0];
+    assert_eq!(early["event"], "inbound", "{early}");
+    assert_eq!(early["envelope"]["ref"], ack["msg_id"]);
+
+    td.daemon_a.shutdown().await;
+    td.daemon_b.shutdown().await;
+}
@@ -0,0 +1,371 @@
+use super::*;
+
+/// revoke drops and disconnects the peer, persists its key, and keeps it out.
+#[tokio::test]
+async fn revoke_disconnects_peer_and_blocks_re_enrollment() {
+    let dir_a = tempdir().unwrap();
+    let dir_b = tempdir().unwrap();
+    let paths_a = AxonPaths::from_root(PathBuf::from(dir_a.path()));
+    paths_a.ensure_root_exists().unwrap();
+    let id_a = Identity::load_or_generate(&paths_a).unwrap();
+    let paths_b = AxonPaths::from_root(PathBuf::from(dir_b.path()));
+    paths_b.ensure_root_exists().unwrap();
+    let id_b = Identity::load_or_generate(&paths_b).unwrap();
+
+    let port_a = pick_free_port();
+    let port_b = pick_free_port();
+    let static_peer = |id: &Identity, port: u16| StaticPeerConfig {
+        agent_id: id.agent_id().into(),
+        addr: format!("127.0.0.1:{port}").parse().unwrap(),
+        pubkey: id.public_key_base64().to_string(),
+    };
+    let daemon_a = spawn_daemon_with_config(
+        dir_a.path(),
+        port_a,
+        Config {
+            port: Some(port_a),
+            peers: vec![static_peer(&id_b, port_b)],
+            ..Default::default()
+        },
+    );
+    let daemon_b = spawn_daemon_with_config(
+        dir_b.path(),
+        port_b,
+        Config {
+            port: Some(port_b),
+            peers: vec![static_peer(&id_a, port_a)],
+            ..Default::default()
+        },
+    );
+    assert!(wait_for_socket(&daemon_a.paths, Duration::from_secs(5)).await);
+    assert!(wait_for_socket(&daemon_b.paths, Duration::from_secs(5)).await);
+    assert!(
+        wait_for_peer_connected(
+            &daemon_b.paths.socket,
+            id_a.agent_id(),
+            Duration::from_secs(10)
+        )
+        .await,
+        "daemon B did not connect to A"
+    );
+
+    let reply = ipc_command(
+        &daemon_a.paths.socket,
+        json!({"cmd": "revoke", "agent_id": id_b.agent_id()}),
+    )
+    .await
+    .unwrap();
+    assert_eq!(reply["ok"], json!(true), "{reply}");
+    assert_eq!(reply["pubkey"], json!(id_b.public_key_base64()), "{reply}");
+    assert_eq!(reply["removed"], json!(true), "{reply}");
+
+    let revoked = axon::config::load_revocations(&daemon_a.paths.revocations)
+        .await
+        .unwrap();
+    assert_eq!(revoked.len(), 1);
+    assert_eq!(revoked[0].agent_id, id_b.agent_id());
+
+    // A closes its connections to B and its TLS verifier refuses B's redials,
+    // so no new connection from B shows up on A.
+    let mut events = BufReader::new(UnixStream::connect(&daemon_a.paths.socket).await.unwrap());
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(3);
+    let mut line = String::new();
+    while let Ok(read) = tokio::time::timeout_at(deadline, events.read_line(&mut line)).await {
+        assert!(read.unwrap() > 0, "daemon A closed the IPC connection");
+        let event: Value = serde_json::from_str(line.trim()).unwrap();
+        assert_ne!(event["event"], json!("connected"), "{event}");
+        line.clear();
+    }
+    let peers = ipc_command(&daemon_a.paths.socket, json!({"cmd": "peers"}))
+        .await
+        .unwrap();
+    assert_eq!(peers["peers"], json!([]), "{peers}");
+
+    let reply = ipc_command(
+        &daemon_a.paths.socket,
+        json!({"cmd": "add_peer", "pubkey": id_b.public_key_base64(), "addr": format!("127.0.0.1:{port_b}")}),
+    )
+    .await
+    .unwrap();
+    assert_eq!(reply["error"], json!("invalid_command"), "{reply}");
+
+    daemon_a.shutdown().await;
+    daemon_b.shutdown().await;
+}
+
+/// block_peer drops and disconnects the peer, keeps it out across reloads,
+/// and unblock_peer lets a reload bring the static peer back.
+#[tokio::test]
+async fn block_peer_disconnects_until_unblocked() {
+    let dir_a = tempdir().unwrap();
+    let dir_b = tempdir().unwrap();
+    let paths_a = AxonPaths::from_root(PathBuf::from(dir_a.path()));
+    paths_a.ensure_root_exists().unwrap();
+    let id_a = Identity::load_or_generate(&paths_a).unwrap();
+    let paths_b = AxonPaths::from_root(PathBuf::from(dir_b.path()));
+    paths_b.ensure_root_exists().unwrap();
+    let id_b = Identity::load_or_generate(&paths_b).unwrap();
+
+    let port_a = pick_free_port();
+    let port_b = pick_free_port();
+    let static_peer = |id: &Identity, port: u16| StaticPeerConfig {
+        agent_id: id.agent_id().into(),
+        addr: format!("127.0.0.1:{port}").parse().unwrap(),
+        pubkey: id.public_key_base64().to_string(),
+    };
+    let daemon_a = spawn_daemon_with_config(
+        dir_a.path(),
+        port_a,
+        Config {
+            port: Some(port_a),
+            peers: vec![static_peer(&id_b, port_b)],
+            ..Default::default()
+        },
+    );
+    let daemon_b = spawn_daemon_with_config(
+        dir_b.path(),
+        port_b,
+        Config {
+            port: Some(port_b),
+            peers: vec![static_peer(&id_a, port_a)],
+            ..Default::default()
+        },
+    );
+    assert!(wait_for_socket(&daemon_a.paths, Duration::from_secs(5)).await);
+    assert!(wait_for_socket(&daemon_b.paths, Duration::from_secs(5)).await);
+    assert!(
+        wait_for_peer_connected(
+            &daemon_b.paths.socket,
+            id_a.agent_id(),
+            Duration::from_secs(10)
+        )
+        .await,
+        "daemon B did not connect to A"
+    );
+    let socket = &daemon_a.paths.socket;
+
+    let reply = ipc_command(
+        socket,
+        json!({"cmd": "block_peer", "agent_id": id_b.agent_id()}),
+    )
+    .await
+    .unwrap();
+    assert_eq!(reply["ok"], json!(true), "{reply}");
+    assert_eq!(reply["pubkey"], json!(id_b.public_key_base64()), "{reply}");
+    let blocked = axon::config::load_blocklist(&daemon_a.paths.blocklist)
+        .await
+        .unwrap();
+    assert_eq!(blocked.len(), 1);
+    assert_eq!(blocked[0].agent_id, id_b.agent_id());
+
+    // The static peer stays out of the table across a reload.
+    let reply = ipc_command(socket, json!({"cmd": "reload"})).await.unwrap();
+    assert_eq!(reply["ok"], json!(true), "{reply}");
+    let peers = ipc_command(socket, json!({"cmd": "peers"})).await.unwrap();
+    assert_eq!(peers["peers"], json!([]), "{peers}");
+    let reply = ipc_command(socket, json!({"cmd": "blocked"}))
+        .await
+        .unwrap();
+    assert_eq!(
+        reply["blocked"][0]["agent_id"],
+        json!(id_b.agent_id()),
+        "{reply}"
+    );
+
+    let reply = ipc_command(
+        socket,
+        json!({"cmd": "unblock_peer", "agent_id": id_b.agent_id()}),
+    )
+    .await
+    .unwrap();
+    assert_eq!(reply["ok"], json!(true), "{reply}");
+    let reply = ipc_command(
+        socket,
+        json!({"cmd": "unblock_peer", "agent_id": id_b.agent_id()}),
+    )
+    .await
+    .unwrap();
+    assert_eq!(reply["error"], json!("peer_not_found"), "{reply}");
+
+    let reply = ipc_command(socket, json!({"cmd": "reload"})).await.unwrap();
+    assert_eq!(reply["ok"], json!(true), "{reply}");
+    let peers = ipc_command(socket, json!({"cmd": "peers"})).await.unwrap();
+    assert_eq!(
+        peers["peers"][0]["agent_id"],
+        json!(id_b.agent_id()),
+        "{peers}"
+    );
+
+    daemon_a.shutdown().await;
+    daemon_b.shutdown().await;
+}
+
+/// block_peer accepts an agent ID the daemon has never seen; the block then
+/// refuses the key that derives to it. Malformed IDs are rejected.
+#[tokio::test]
+async fn block_peer_by_unknown_agent_id_refuses_its_key() {
+    let dir_a = tempdir().unwrap();
+    let dir_b = tempdir().unwrap();
+    let paths_b = AxonPaths::from_root(PathBuf::from(dir_b.path()));
+    paths_b.ensure_root_exists().unwrap();
+    let id_b = Identity::load_or_generate(&paths_b).unwrap();
+
+    let daemon_a = spawn_daemon(dir_a.path(), pick_free_port(), vec![]);
+    assert!(wait_for_socket(&daemon_a.paths, Duration::from_secs(5)).await);
+    let socket = &daemon_a.paths.socket;
+
+    let reply = ipc_command(
+        socket,
+        json!({"cmd": "block_peer", "agent_id": "not-an-agent-id"}),
+    )
+    .await
+    .unwrap();
+    assert_eq!(reply["error"], json!("invalid_command"), "{reply}");
+
+    let reply = ipc_command(
+        socket,
+        json!({"cmd": "block_peer", "agent_id": id_b.agent_id()}),
+    )
+    .await
+    .unwrap();
+    assert_eq!(reply["ok"], json!(true), "{reply}");
+    assert!(reply.get("pubkey").is_none(), "{reply}");
+    let blocked = axon::config::load_blocklist(&daemon_a.paths.blocklist)
+        .await
+        .unwrap();
+    assert_eq!(blocked[0].agent_id, id_b.agent_id());
+    assert_eq!(blocked[0].pubkey, None);
+
+    let reply = ipc_command(
+        socket,
+        json!({
+            "cmd": "add_peer",
+            "pubkey": id_b.public_key_base64(),
+            "addr": format!("127.0.0.1:{}", pick_free_port()),
+        }),
+    )
+    .await
+    .unwrap();
+    assert_eq!(reply["error"], json!("invalid_command"), "{reply}");
+
+    daemon_a.shutdown().await;
+}
+
+/// A daemon bans the source address of a peer whose handshakes it keeps
+/// rejecting, and lists the ban over IPC.
+#[tokio::test]
+async fn repeated_handshake_failures_ban_the_source_address() {
+    let dir_a = tempdir().unwrap();
+    let dir_b = tempdir().unwrap();
+    let paths_a = AxonPaths::from_root(PathBuf::from(dir_a.path()));
+    paths_a.ensure_root_exists().unwrap();
+    let id_a = Identity::load_or_generate(&paths_a).unwrap();
+
+    let port_a = pick_free_port();
+    let port_b = pick_free_port();
+    // A does not know B, so every dial from B fails the TLS handshake.
+    let daemon_a = spawn_daemon_with_config(
+        dir_a.path(),
+        port_a,
+        Config {
+            port: Some(port_a),
+            handshake_ban: HandshakeBanConfig {
+                max_failures: Some(2),
+                ban_secs: Some(30),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+    let daemon_b = spawn_daemon(
+        dir_b.path(),
+        port_b,
+        vec![StaticPeerConfig {
+            agent_id: id_a.agent_id().into(),
+            addr: format!("127.0.0.1:{port_a}").parse().unwrap(),
+            pubkey: id_a.public_key_base64().to_string(),
+        }],
+    );
+    assert!(wait_for_socket(&daemon_a.paths, Duration::from_secs(5)).await);
+    assert!(wait_for_socket(&daemon_b.paths, Duration::from_secs(5)).await);
+
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(15);
+    let bans = loop {
+        let reply = ipc_command(&daemon_a.paths.socket, json!({"cmd": "bans"}))
+            .await
+            .unwrap();
+        assert_eq!(reply["ok"], json!(true), "{reply}");
+        if !reply["bans"].as_array().unwrap().is_empty() {
+            break reply["bans"].clone();
+        }
+        assert!(
+            tokio::time::Instant::now() < deadline,
+            "B was never banned: {reply}"
+        );
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    };
+    assert_eq!(bans[0]["addr"], json!("127.0.0.1"), "{bans}");
+    assert_eq!(bans[0]["strikes"], json!(1), "{bans}");
+    let remaining = bans[0]["remaining_secs"].as_u64().unwrap();
+    assert!((1..=30).contains(&remaining), "{bans}");
+
+    daemon_a.shutdown().await;
+    daemon_b.shutdown().await;
+}
+
+/// Handshake failures from a static peer's address never ban it.
+#[tokio::test]
+async fn static_peer_addresses_are_exempt_from_handshake_bans() {
+    let dir_a = tempdir().unwrap();
+    let dir_b = tempdir().unwrap();
+    let paths_a = AxonPaths::from_root(PathBuf::from(dir_a.path()));
+    paths_a.ensure_root_exists().unwrap();
+    let id_a = Identity::load_or_generate(&paths_a).unwrap();
+    let paths_b = AxonPaths::from_root(PathBuf::from(dir_b.path()));
+    paths_b.ensure_root_exists().unwrap();
+    let id_b = Identity::load_or_generate(&paths_b).unwrap();
+
+    let port_a = pick_free_port();
+    let port_b = pick_free_port();
+    // A lists B under the wrong key, so B's dials fail the handshake, but
+    // B's address belongs to a static peer.
+    let daemon_a = spawn_daemon_with_config(
+        dir_a.path(),
+        port_a,
+        Config {
+            port: Some(port_a),
+            peers: vec![StaticPeerConfig {
+                agent_id: id_b.agent_id().into(),
+                addr: format!("127.0.0.1:{port_b}").parse().unwrap(),
+                pubkey: "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=".to_string(),
+            }],
+            handshake_ban: HandshakeBanConfig {
+                max_failures: Some(1),
+                ban_secs: Some(30),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+    let daemon_b = spawn_daemon(
+        dir_b.path(),
+        port_b,
+        vec![StaticPeerConfig {
+            agent_id: id_a.agent_id().into(),
+            addr: format!("127.0.0.1:{port_a}").parse().unwrap(),
+            pubkey: id_a.public_key_base64().to_string(),
+        }],
+    );
+    assert!(wait_for_socket(&daemon_a.paths, Duration::from_secs(5)).await);
+    assert!(wait_for_socket(&daemon_b.paths, Duration::from_secs(5)).await);
+
+    // Give B time for several failed dials; one would be enough to ban.
+    tokio::time::sleep(Duration::from_secs(3)).await;
+    let reply = ipc_command(&daemon_a.paths.socket, json!({"cmd": "bans"}))
+        .await
+        .unwrap();
+    assert_eq!(reply["bans"], json!([]), "{reply}");
+
+    daemon_a.shutdown().await;
+    daemon_b.shutdown().await;
+}
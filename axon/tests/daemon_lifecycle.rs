@@ -23,6 +23,10 @@ use tokio_util::sync::CancellationToken;
 mod basic;
 #[path = "daemon_lifecycle/messaging.rs"]
 mod messaging;
+#[path = "daemon_lifecycle/peers.rs"]
+mod peers;
+#[path = "daemon_lifecycle/process.rs"]
+mod process;
 
 // =========================================================================
 // Helpers
@@ -87,7 +91,10 @@ fn spawn_daemon(
         port: Some(port),
         disable_mdns,
         axon_root: Some(PathBuf::from(dir)),
+        profile: None,
         cancel: Some(cancel.clone()),
+        handle_signals: true,
+        response_handler: None,
     };
 
     let handle = tokio::spawn(async move { run_daemon(opts).await });
@@ -120,15 +127,15 @@ async fn wait_for_peer_disconnected(
         if tokio::time::Instant::now() >= deadline {
             return false;
         }
-        if let Ok(resp) = ipc_command(socket_path, json!({"cmd": "peers"})).await {
-            if let Some(peers) = resp["peers"].as_array() {
-                let is_connected = peers.iter().any(|p| {
-                    p["agent_id"].as_str() == Some(peer_agent_id)
-                        && p["status"].as_str() == Some("connected")
-                });
-                if !is_connected {
-                    return true;
-                }
+        if let Ok(resp) = ipc_command(socket_path, json!({"cmd": "peers"})).await
+            && let Some(peers) = resp["peers"].as_array()
+        {
+            let is_connected = peers.iter().any(|p| {
+                p["agent_id"].as_str() == Some(peer_agent_id)
+                    && p["status"].as_str() == Some("connected")
+            });
+            if !is_connected {
+                return true;
             }
         }
         tokio::time::sleep(Duration::from_millis(200)).await;
@@ -146,15 +153,14 @@ async fn wait_for_peer_connected(
         if tokio::time::Instant::now() >= deadline {
             return false;
         }
-        if let Ok(resp) = ipc_command(socket_path, json!({"cmd": "peers"})).await {
-            if let Some(peers) = resp["peers"].as_array() {
-                if peers.iter().any(|p| {
-                    p["agent_id"].as_str() == Some(peer_agent_id)
-                        && p["status"].as_str() == Some("connected")
-                }) {
-                    return true;
-                }
-            }
+        if let Ok(resp) = ipc_command(socket_path, json!({"cmd": "peers"})).await
+            && let Some(peers) = resp["peers"].as_array()
+            && peers.iter().any(|p| {
+                p["agent_id"].as_str() == Some(peer_agent_id)
+                    && p["status"].as_str() == Some("connected")
+            })
+        {
+            return true;
         }
         tokio::time::sleep(Duration::from_millis(200)).await;
     }
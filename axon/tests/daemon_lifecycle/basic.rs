@@ -53,6 +53,34 @@ async fn graceful_shutdown_cleans_up() {
     );
 }
 
+/// Lifetime stats survive a restart; top-level counters start over.
+#[tokio::test]
+async fn lifetime_stats_persist_across_restart() {
+    let dir = tempdir().unwrap();
+    for expected_starts in 1..=2 {
+        let port = pick_free_port();
+        let (cancel, paths, handle) = spawn_daemon(dir.path(), port, true, vec![]);
+        assert!(
+            wait_for_socket(&paths, Duration::from_secs(5)).await,
+            "daemon socket did not appear"
+        );
+
+        let status = ipc_command(&paths.socket, json!({"cmd": "status"}))
+            .await
+            .expect("status command failed");
+        assert_eq!(status["lifetime"]["daemon_starts"], json!(expected_starts));
+        assert!(status["lifetime"]["first_started_ms"].as_u64().unwrap() > 0);
+
+        cancel.cancel();
+        let result = timeout(Duration::from_secs(10), handle)
+            .await
+            .expect("daemon did not shut down in time")
+            .expect("daemon task panicked");
+        assert!(result.is_ok(), "daemon returned error: {:?}", result);
+        assert!(paths.stats.exists(), "stats.json was not saved");
+    }
+}
+
 /// A second daemon on the same state root should fail fast with a clear error.
 #[tokio::test]
 async fn second_daemon_same_state_root_is_rejected() {
@@ -71,7 +99,10 @@ async fn second_daemon_same_state_root_is_rejected() {
         port: Some(port_b),
         disable_mdns: true,
         axon_root: Some(PathBuf::from(dir.path())),
+        profile: None,
         cancel: Some(second_cancel.clone()),
+        handle_signals: true,
+        response_handler: None,
     };
     let second_handle = tokio::spawn(async move { run_daemon(second_opts).await });
 
@@ -148,190 +179,3 @@ fn sigterm_shutdown_cleans_socket_and_lock_file() {
         "daemon.pid should be removed after SIGTERM shutdown"
     );
 }
-
-/// Both daemons connect: either side can dial. Start two daemons and
-/// verify both see each other as connected.
-#[tokio::test]
-
-async fn both_sides_connect() {
-    let dir_a = tempdir().unwrap();
-    let dir_b = tempdir().unwrap();
-
-    let paths_a = AxonPaths::from_root(PathBuf::from(dir_a.path()));
-    paths_a.ensure_root_exists().unwrap();
-    let id_a = Identity::load_or_generate(&paths_a).unwrap();
-
-    let paths_b = AxonPaths::from_root(PathBuf::from(dir_b.path()));
-    paths_b.ensure_root_exists().unwrap();
-    let id_b = Identity::load_or_generate(&paths_b).unwrap();
-
-    let port_a = pick_free_port();
-    let port_b = pick_free_port();
-
-    let peers_for_a = vec![StaticPeerConfig {
-        agent_id: id_b.agent_id().into(),
-        addr: format!("127.0.0.1:{port_b}").parse().unwrap(),
-        pubkey: id_b.public_key_base64().to_string(),
-    }];
-    let peers_for_b = vec![StaticPeerConfig {
-        agent_id: id_a.agent_id().into(),
-        addr: format!("127.0.0.1:{port_a}").parse().unwrap(),
-        pubkey: id_a.public_key_base64().to_string(),
-    }];
-
-    let (cancel_a, paths_a, handle_a) = spawn_daemon(dir_a.path(), port_a, true, peers_for_a);
-    let (cancel_b, paths_b, handle_b) = spawn_daemon(dir_b.path(), port_b, true, peers_for_b);
-
-    assert!(
-        wait_for_socket(&paths_a, Duration::from_secs(5)).await,
-        "daemon A socket did not appear"
-    );
-    assert!(
-        wait_for_socket(&paths_b, Duration::from_secs(5)).await,
-        "daemon B socket did not appear"
-    );
-
-    // Wait for both daemons to see each other as connected.
-    assert!(
-        wait_for_peer_connected(&paths_a.socket, id_b.agent_id(), Duration::from_secs(10)).await,
-        "daemon A did not connect to B"
-    );
-    assert!(
-        wait_for_peer_connected(&paths_b.socket, id_a.agent_id(), Duration::from_secs(10)).await,
-        "daemon B did not connect to A"
-    );
-
-    // Clean up.
-    cancel_a.cancel();
-    cancel_b.cancel();
-    let _ = timeout(Duration::from_secs(5), handle_a).await;
-    let _ = timeout(Duration::from_secs(5), handle_b).await;
-}
-
-/// Send to unknown peer: IPC send to an agent_id not in the peer table
-/// should return an error with ok=false.
-#[tokio::test]
-
-async fn send_to_unknown_peer_returns_error() {
-    let dir = tempdir().unwrap();
-    let port = pick_free_port();
-    let (cancel, paths, handle) = spawn_daemon(dir.path(), port, true, vec![]);
-
-    assert!(wait_for_socket(&paths, Duration::from_secs(5)).await);
-
-    let send_cmd = json!({
-        "cmd": "send",
-        "to": "ed25519.deadbeefdeadbeefdeadbeefdeadbeef",
-        "kind": "request",
-        "payload": {}
-    });
-    let reply = ipc_command(&paths.socket, send_cmd)
-        .await
-        .expect("send command should not fail at IPC level");
-    assert_eq!(reply["ok"], json!(false), "send to unknown peer must fail");
-    assert_eq!(
-        reply["error"].as_str().unwrap(),
-        "peer_not_found",
-        "error should be peer_not_found"
-    );
-
-    cancel.cancel();
-    let _ = timeout(Duration::from_secs(5), handle).await;
-}
-
-/// Send to own agent ID should return self_send instead of peer_not_found.
-#[tokio::test]
-async fn send_to_self_returns_specific_error() {
-    let dir = tempdir().unwrap();
-    let port = pick_free_port();
-    let (cancel, paths, handle) = spawn_daemon(dir.path(), port, true, vec![]);
-
-    assert!(wait_for_socket(&paths, Duration::from_secs(5)).await);
-
-    let local_identity = Identity::load_or_generate(&paths).expect("load local identity");
-    let send_cmd = json!({
-        "cmd": "send",
-        "to": local_identity.agent_id(),
-        "kind": "request",
-        "payload": {}
-    });
-    let reply = ipc_command(&paths.socket, send_cmd)
-        .await
-        .expect("send command should not fail at IPC level");
-    assert_eq!(reply["ok"], json!(false), "send to self must fail");
-    assert_eq!(
-        reply["error"].as_str().unwrap(),
-        "self_send",
-        "error should be self_send"
-    );
-    assert_eq!(
-        reply["message"].as_str().unwrap(),
-        "cannot send messages to self",
-        "self_send message should be actionable"
-    );
-
-    cancel.cancel();
-    let _ = timeout(Duration::from_secs(5), handle).await;
-}
-
-/// Peers command reflects connected peer after connection is established.
-#[tokio::test]
-
-async fn peers_command_shows_connected_peer() {
-    let dir_a = tempdir().unwrap();
-    let dir_b = tempdir().unwrap();
-
-    let paths_a = AxonPaths::from_root(PathBuf::from(dir_a.path()));
-    paths_a.ensure_root_exists().unwrap();
-    let id_a = Identity::load_or_generate(&paths_a).unwrap();
-
-    let paths_b = AxonPaths::from_root(PathBuf::from(dir_b.path()));
-    paths_b.ensure_root_exists().unwrap();
-    let id_b = Identity::load_or_generate(&paths_b).unwrap();
-
-    let port_a = pick_free_port();
-    let port_b = pick_free_port();
-
-    let peers_for_a = vec![StaticPeerConfig {
-        agent_id: id_b.agent_id().into(),
-        addr: format!("127.0.0.1:{port_b}").parse().unwrap(),
-        pubkey: id_b.public_key_base64().to_string(),
-    }];
-    let peers_for_b = vec![StaticPeerConfig {
-        agent_id: id_a.agent_id().into(),
-        addr: format!("127.0.0.1:{port_a}").parse().unwrap(),
-        pubkey: id_a.public_key_base64().to_string(),
-    }];
-
-    let (cancel_a, paths_a, handle_a) = spawn_daemon(dir_a.path(), port_a, true, peers_for_a);
-    let (cancel_b, paths_b, handle_b) = spawn_daemon(dir_b.path(), port_b, true, peers_for_b);
-
-    assert!(wait_for_socket(&paths_a, Duration::from_secs(5)).await);
-    assert!(wait_for_socket(&paths_b, Duration::from_secs(5)).await);
-    assert!(
-        wait_for_peer_connected(&paths_a.socket, id_b.agent_id(), Duration::from_secs(10)).await,
-        "daemon A did not connect to B"
-    );
-
-    // Check that peers command returns B with source=static.
-    let peers_resp = ipc_command(&paths_a.socket, json!({"cmd": "peers"}))
-        .await
-        .unwrap();
-    assert_eq!(peers_resp["ok"], json!(true));
-    let peer_list = peers_resp["peers"].as_array().unwrap();
-    let b_peer = peer_list
-        .iter()
-        .find(|p| p["agent_id"].as_str() == Some(id_b.agent_id()))
-        .expect("daemon B should appear in A's peer list");
-    assert_eq!(b_peer["status"], "connected");
-    assert_eq!(b_peer["source"], "static");
-    assert!(
-        b_peer.get("addr").is_some(),
-        "peer should have an addr field"
-    );
-
-    cancel_a.cancel();
-    cancel_b.cancel();
-    let _ = timeout(Duration::from_secs(5), handle_a).await;
-    let _ = timeout(Duration::from_secs(5), handle_b).await;
-}
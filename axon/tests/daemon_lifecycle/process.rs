@@ -0,0 +1,145 @@
+use super::*;
+
+/// systemd socket activation: the daemon serves IPC and QUIC on inherited
+/// descriptors, reports READY=1/STOPPING=1 over NOTIFY_SOCKET, and leaves the
+/// service-manager-owned socket file in place on shutdown.
+#[test]
+fn socket_activation_and_sd_notify() {
+    use std::io::{BufRead, BufReader, Write};
+    use std::os::unix::io::AsRawFd;
+    use std::os::unix::process::CommandExt;
+
+    let bin = axon_bin();
+    let dir = tempdir().unwrap();
+    let paths = AxonPaths::from_root(PathBuf::from(dir.path()));
+    paths.ensure_root_exists().unwrap();
+
+    let ipc_listener = std::os::unix::net::UnixListener::bind(&paths.socket).unwrap();
+    let quic_socket = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+    let notify_path = dir.path().join("notify.sock");
+    let notify = std::os::unix::net::UnixDatagram::bind(&notify_path).unwrap();
+    notify
+        .set_read_timeout(Some(Duration::from_secs(10)))
+        .unwrap();
+
+    let ipc_fd = ipc_listener.as_raw_fd();
+    let quic_fd = quic_socket.as_raw_fd();
+    let mut command = std::process::Command::new("/bin/sh");
+    command
+        .arg("-c")
+        .arg("LISTEN_PID=$$ LISTEN_FDS=2 exec \"$0\" \"$@\"")
+        .arg(&bin)
+        .args([
+            "--state-root",
+            dir.path().to_str().expect("utf8 path"),
+            "daemon",
+            "--disable-mdns",
+        ])
+        .env("NOTIFY_SOCKET", &notify_path);
+    // SAFETY: only async-signal-safe fcntl/dup2 calls between fork and exec.
+    unsafe {
+        command.pre_exec(move || {
+            let ipc_high = libc::fcntl(ipc_fd, libc::F_DUPFD, 100);
+            let quic_high = libc::fcntl(quic_fd, libc::F_DUPFD, 100);
+            if ipc_high < 0
+                || quic_high < 0
+                || libc::dup2(ipc_high, 3) < 0
+                || libc::dup2(quic_high, 4) < 0
+            {
+                return Err(std::io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
+    let mut child = command.spawn().expect("failed to spawn daemon");
+
+    let mut buf = [0u8; 64];
+    let n = notify.recv(&mut buf).expect("READY=1 notification");
+    assert_eq!(&buf[..n], b"READY=1");
+
+    let mut stream = std::os::unix::net::UnixStream::connect(&paths.socket).unwrap();
+    stream
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+    stream.write_all(b"{\"cmd\":\"status\"}\n").unwrap();
+    let mut line = String::new();
+    BufReader::new(&stream).read_line(&mut line).unwrap();
+    let reply: Value = serde_json::from_str(line.trim()).unwrap();
+    assert_eq!(reply["ok"], json!(true));
+    drop(stream);
+
+    // SAFETY: kill sends SIGTERM to the child process ID created by this test.
+    let rc = unsafe { libc::kill(child.id() as libc::pid_t, libc::SIGTERM) };
+    assert_eq!(rc, 0, "failed to send SIGTERM to daemon process");
+    let n = notify.recv(&mut buf).expect("STOPPING=1 notification");
+    assert_eq!(&buf[..n], b"STOPPING=1");
+
+    let shutdown_deadline = std::time::Instant::now() + Duration::from_secs(10);
+    let status = loop {
+        if let Some(status) = child.try_wait().expect("failed waiting for daemon process") {
+            break status;
+        }
+        if std::time::Instant::now() >= shutdown_deadline {
+            let _ = child.kill();
+            panic!("daemon did not exit after SIGTERM");
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    };
+    assert!(status.success(), "daemon exited with status: {status}");
+    assert!(
+        paths.socket.exists(),
+        "socket-activated IPC socket belongs to the service manager"
+    );
+}
+
+/// `daemon --detach` backgrounds the daemon; `restart` replaces it and `stop`
+/// shuts it down via IPC, cleaning up pidfile and socket.
+#[test]
+fn detach_restart_and_stop_lifecycle() {
+    let bin = axon_bin();
+    let dir = tempdir().unwrap();
+    let root_str = dir.path().to_str().expect("utf8 path");
+    let paths = AxonPaths::from_root(PathBuf::from(dir.path()));
+    let pid_path = paths.root.join("daemon.pid");
+    let port = pick_free_port().to_string();
+    let run = |args: &[&str]| {
+        let output = std::process::Command::new(&bin)
+            .args(["--state-root", root_str])
+            .args(args)
+            .output()
+            .expect("failed to run axon");
+        assert!(output.status.success(), "axon {args:?} failed: {output:?}");
+        String::from_utf8_lossy(&output.stdout).into_owned()
+    };
+    let read_pid = || {
+        std::fs::read_to_string(&pid_path)
+            .expect("daemon.pid")
+            .trim()
+            .to_string()
+    };
+
+    let started = run(&["daemon", "--detach", "--disable-mdns", "--port", &port]);
+    assert!(started.contains("daemon started"), "{started}");
+    assert!(paths.socket.exists());
+    let first_pid = read_pid();
+    assert!(started.contains(&first_pid));
+    assert!(run(&["status"]).contains("Uptime:"));
+
+    let restarted = run(&["restart", "--disable-mdns", "--port", &port]);
+    assert!(restarted.contains(&format!("daemon stopped (pid {first_pid})")));
+    let second_pid = read_pid();
+    assert_ne!(first_pid, second_pid);
+    assert!(run(&["status"]).contains("Uptime:"));
+
+    let stopped = run(&["stop"]);
+    assert!(stopped.contains(&format!("daemon stopped (pid {second_pid})")));
+    assert!(
+        !pid_path.exists(),
+        "daemon.pid should be removed after stop"
+    );
+    assert!(
+        !paths.socket.exists(),
+        "socket should be removed after stop"
+    );
+    assert!(paths.root.join("daemon.log").exists());
+}
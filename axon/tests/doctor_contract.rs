@@ -1,389 +0,0 @@
-use std::fs;
-use std::os::unix::fs::PermissionsExt;
-use std::path::{Path, PathBuf};
-use std::process::{Command, Output};
-
-use base64::{Engine as _, engine::general_purpose::STANDARD};
-use serde_json::Value;
-use tempfile::tempdir;
-
-fn axon_bin() -> PathBuf {
-    if let Some(bin) = std::env::var_os("CARGO_BIN_EXE_axon") {
-        return PathBuf::from(bin);
-    }
-
-    let current = std::env::current_exe().expect("resolve current test executable");
-    let debug_dir = current
-        .parent()
-        .and_then(Path::parent)
-        .expect("resolve target debug dir");
-    let fallback = if cfg!(windows) {
-        debug_dir.join("axon.exe")
-    } else {
-        debug_dir.join("axon")
-    };
-    assert!(
-        fallback.exists(),
-        "failed to locate axon binary via CARGO_BIN_EXE_axon and fallback path {}",
-        fallback.display()
-    );
-    fallback
-}
-
-fn run_command(cmd: &mut Command) -> Output {
-    cmd.output().expect("failed to execute axon binary")
-}
-
-fn run_doctor(root: &Path, args: &[&str]) -> Output {
-    let mut cmd = Command::new(axon_bin());
-    cmd.arg("--state-root")
-        .arg(root.to_str().expect("utf8 path"))
-        .arg("doctor");
-    cmd.args(args);
-    run_command(&mut cmd)
-}
-
-fn run_doctor_json(root: &Path, args: &[&str]) -> Output {
-    let mut cmd = Command::new(axon_bin());
-    cmd.arg("--state-root")
-        .arg(root.to_str().expect("utf8 path"))
-        .arg("doctor")
-        .arg("--json");
-    cmd.args(args);
-    run_command(&mut cmd)
-}
-
-fn parse_report(output: &Output) -> Value {
-    serde_json::from_slice(&output.stdout).expect("doctor stdout should be valid JSON")
-}
-
-fn check_by_name<'a>(report: &'a Value, name: &str) -> &'a Value {
-    report["checks"]
-        .as_array()
-        .expect("checks must be array")
-        .iter()
-        .find(|check| check.get("name") == Some(&Value::String(name.to_string())))
-        .unwrap_or_else(|| panic!("missing check '{name}'"))
-}
-
-#[test]
-fn doctor_check_mode_reports_missing_identity_without_creating_files() {
-    let root = tempdir().expect("tempdir");
-    fs::set_permissions(root.path(), fs::Permissions::from_mode(0o700)).expect("set perms");
-
-    let output = run_doctor_json(root.path(), &[]);
-    assert_eq!(output.status.code(), Some(2));
-
-    let report = parse_report(&output);
-    assert_eq!(report["mode"], "check");
-    assert_eq!(report["ok"], false);
-
-    let identity = check_by_name(&report, "identity");
-    assert_eq!(identity["ok"], false);
-    assert_eq!(identity["fixable"], true);
-    assert!(
-        identity["message"]
-            .as_str()
-            .expect("identity message")
-            .contains("doctor --fix")
-    );
-
-    assert!(
-        !root.path().join("identity.key").exists(),
-        "check mode should not generate identity files"
-    );
-}
-
-#[test]
-fn doctor_fix_mode_generates_identity() {
-    let root = tempdir().expect("tempdir");
-    fs::set_permissions(root.path(), fs::Permissions::from_mode(0o700)).expect("set perms");
-
-    let output = run_doctor_json(root.path(), &["--fix"]);
-    assert!(output.status.success());
-
-    let report = parse_report(&output);
-    assert_eq!(report["mode"], "fix");
-    assert_eq!(report["ok"], true);
-
-    let identity = check_by_name(&report, "identity");
-    assert_eq!(identity["ok"], true);
-
-    let key_contents = fs::read_to_string(root.path().join("identity.key")).expect("read key");
-    let decoded = STANDARD
-        .decode(key_contents.trim())
-        .expect("identity.key should be base64");
-    assert_eq!(decoded.len(), 32);
-}
-
-#[test]
-fn doctor_fix_requires_rekey_for_unrecoverable_identity() {
-    let root = tempdir().expect("tempdir");
-    fs::set_permissions(root.path(), fs::Permissions::from_mode(0o700)).expect("set perms");
-    fs::write(root.path().join("identity.key"), "not base64 at all").expect("write invalid key");
-
-    let output = run_doctor_json(root.path(), &["--fix"]);
-    assert_eq!(output.status.code(), Some(2));
-
-    let report = parse_report(&output);
-    assert_eq!(report["mode"], "fix");
-    assert_eq!(report["ok"], false);
-
-    let identity = check_by_name(&report, "identity");
-    assert_eq!(identity["ok"], false);
-    assert!(
-        identity["message"]
-            .as_str()
-            .expect("identity message")
-            .contains("--rekey")
-    );
-
-    let still_invalid = fs::read_to_string(root.path().join("identity.key")).expect("read key");
-    assert_eq!(still_invalid, "not base64 at all");
-}
-
-#[test]
-fn doctor_fix_requires_rekey_for_legacy_raw_identity() {
-    let root = tempdir().expect("tempdir");
-    fs::set_permissions(root.path(), fs::Permissions::from_mode(0o700)).expect("set perms");
-    fs::write(root.path().join("identity.key"), [7u8; 32]).expect("write legacy raw key");
-
-    let output = run_doctor_json(root.path(), &["--fix"]);
-    assert_eq!(output.status.code(), Some(2));
-
-    let report = parse_report(&output);
-    assert_eq!(report["mode"], "fix");
-    assert_eq!(report["ok"], false);
-
-    let identity = check_by_name(&report, "identity");
-    assert_eq!(identity["ok"], false);
-    assert!(
-        identity["message"]
-            .as_str()
-            .expect("identity message")
-            .contains("--rekey")
-    );
-}
-
-#[test]
-fn doctor_fix_rekey_backs_up_and_regenerates_identity() {
-    let root = tempdir().expect("tempdir");
-    fs::set_permissions(root.path(), fs::Permissions::from_mode(0o700)).expect("set perms");
-    fs::write(root.path().join("identity.key"), "not base64 at all").expect("write invalid key");
-
-    let output = run_doctor_json(root.path(), &["--fix", "--rekey"]);
-    assert!(output.status.success());
-
-    let report = parse_report(&output);
-    assert_eq!(report["mode"], "fix");
-    assert_eq!(report["ok"], true);
-
-    let identity = check_by_name(&report, "identity");
-    assert_eq!(identity["ok"], true);
-
-    let key_contents = fs::read_to_string(root.path().join("identity.key")).expect("read key");
-    let decoded = STANDARD
-        .decode(key_contents.trim())
-        .expect("identity.key should be base64");
-    assert_eq!(decoded.len(), 32);
-
-    let mut backup_found = false;
-    for entry in fs::read_dir(root.path()).expect("read dir") {
-        let path = entry.expect("dir entry").path();
-        let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
-            continue;
-        };
-        if name.starts_with("identity.key.bak.") {
-            backup_found = true;
-            break;
-        }
-    }
-    assert!(backup_found, "expected identity.key backup file");
-}
-
-#[test]
-fn doctor_check_reports_corrupt_config_as_fixable() {
-    let root = tempdir().expect("tempdir");
-    fs::set_permissions(root.path(), fs::Permissions::from_mode(0o700)).expect("set perms");
-
-    // Write valid identity so that check passes, isolating the config check.
-    let identity = axon::identity::Identity::load_or_generate(&axon::config::AxonPaths::from_root(
-        root.path().to_path_buf(),
-    ))
-    .expect("generate identity");
-    drop(identity);
-
-    // Write corrupt config.
-    fs::write(root.path().join("config.yaml"), b"\x80\x81 invalid yaml").expect("write corrupt");
-
-    let output = run_doctor_json(root.path(), &[]);
-    assert_eq!(output.status.code(), Some(2));
-
-    let report = parse_report(&output);
-    let config = check_by_name(&report, "config");
-    assert_eq!(config["ok"], false);
-    assert_eq!(config["fixable"], true);
-    assert!(
-        config["message"]
-            .as_str()
-            .expect("config message")
-            .contains("doctor --fix"),
-        "should suggest --fix"
-    );
-}
-
-#[test]
-fn doctor_fix_resets_corrupt_config_with_backup() {
-    let root = tempdir().expect("tempdir");
-    fs::set_permissions(root.path(), fs::Permissions::from_mode(0o700)).expect("set perms");
-
-    // Write valid identity so that check passes, isolating the config check.
-    let identity = axon::identity::Identity::load_or_generate(&axon::config::AxonPaths::from_root(
-        root.path().to_path_buf(),
-    ))
-    .expect("generate identity");
-    drop(identity);
-
-    let corrupt_content = b"\x80\x81 invalid binary";
-    fs::write(root.path().join("config.yaml"), corrupt_content).expect("write corrupt");
-
-    let output = run_doctor_json(root.path(), &["--fix"]);
-    assert!(output.status.success());
-
-    let report = parse_report(&output);
-    assert_eq!(report["ok"], true);
-
-    // Config check should pass after fix.
-    let config = check_by_name(&report, "config");
-    assert_eq!(config["ok"], true);
-
-    // Fixes should include config_reset.
-    let fixes = report["fixes_applied"]
-        .as_array()
-        .expect("fixes_applied array");
-    assert!(
-        fixes
-            .iter()
-            .any(|f| f["name"].as_str() == Some("config_reset")),
-        "should report config_reset fix"
-    );
-
-    // Backup file should exist with original corrupt content.
-    let mut backup_found = false;
-    for entry in fs::read_dir(root.path()).expect("read dir") {
-        let path = entry.expect("dir entry").path();
-        let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
-            continue;
-        };
-        if name.starts_with("config.yaml.bak.") {
-            let backup_content = fs::read(&path).expect("read backup");
-            assert_eq!(backup_content, corrupt_content);
-            backup_found = true;
-            break;
-        }
-    }
-    assert!(backup_found, "expected config.yaml backup file");
-
-    // New config.yaml should be valid and parseable.
-    let new_content = fs::read_to_string(root.path().join("config.yaml")).expect("read new config");
-    assert!(
-        !new_content.is_empty(),
-        "reset config.yaml should not be empty"
-    );
-}
-
-#[test]
-fn doctor_subcommand_is_visible_in_help() {
-    let output = run_command(Command::new(axon_bin()).arg("--help"));
-    assert!(output.status.success());
-
-    let help = String::from_utf8_lossy(&output.stdout);
-    assert!(help.contains("  doctor"));
-}
-
-#[test]
-fn doctor_check_detects_duplicate_peer_addresses() {
-    let root = tempdir().expect("tempdir");
-    fs::set_permissions(root.path(), fs::Permissions::from_mode(0o700)).expect("set perms");
-
-    // Generate identity so other checks pass
-    let _identity = axon::identity::Identity::load_or_generate(
-        &axon::config::AxonPaths::from_root(root.path().to_path_buf()),
-    )
-    .expect("generate identity");
-
-    // Write known_peers.json with two peers at the same address
-    let peers = serde_json::json!([
-        {"agent_id": "ed25519.aaaa", "addr": "10.0.0.1:7100", "pubkey": "a2V5MQ==", "last_seen_unix_ms": 1000, "source": "discovered"},
-        {"agent_id": "ed25519.bbbb", "addr": "10.0.0.1:7100", "pubkey": "a2V5Mg==", "last_seen_unix_ms": 2000, "source": "discovered"}
-    ]);
-    fs::write(
-        root.path().join("known_peers.json"),
-        serde_json::to_string(&peers).unwrap(),
-    )
-    .expect("write known_peers");
-
-    let output = run_doctor_json(root.path(), &[]);
-    assert_eq!(output.status.code(), Some(2));
-
-    let report = parse_report(&output);
-    let dup_check = check_by_name(&report, "duplicate_peer_addr");
-    assert_eq!(dup_check["ok"], false);
-    assert_eq!(dup_check["fixable"], true);
-    assert!(
-        dup_check["message"]
-            .as_str()
-            .unwrap()
-            .contains("duplicate address")
-    );
-}
-
-#[test]
-fn doctor_fix_prunes_duplicate_peer_addresses() {
-    let root = tempdir().expect("tempdir");
-    fs::set_permissions(root.path(), fs::Permissions::from_mode(0o700)).expect("set perms");
-
-    // Generate identity so other checks pass
-    let _identity = axon::identity::Identity::load_or_generate(
-        &axon::config::AxonPaths::from_root(root.path().to_path_buf()),
-    )
-    .expect("generate identity");
-
-    // Write known_peers.json with two peers at the same address
-    let peers = serde_json::json!([
-        {"agent_id": "ed25519.aaaa", "addr": "10.0.0.1:7100", "pubkey": "a2V5MQ==", "last_seen_unix_ms": 1000, "source": "discovered"},
-        {"agent_id": "ed25519.bbbb", "addr": "10.0.0.1:7100", "pubkey": "a2V5Mg==", "last_seen_unix_ms": 2000, "source": "discovered"}
-    ]);
-    fs::write(
-        root.path().join("known_peers.json"),
-        serde_json::to_string(&peers).unwrap(),
-    )
-    .expect("write known_peers");
-
-    let output = run_doctor_json(root.path(), &["--fix"]);
-    assert!(output.status.success());
-
-    let report = parse_report(&output);
-    let dup_check = check_by_name(&report, "duplicate_peer_addr");
-    assert_eq!(dup_check["ok"], true);
-
-    // Verify known_peers.json was updated — only one peer should remain
-    let saved: Vec<serde_json::Value> = serde_json::from_str(
-        &fs::read_to_string(root.path().join("known_peers.json")).expect("read peers"),
-    )
-    .expect("parse peers");
-    assert_eq!(saved.len(), 1);
-    // The one with higher last_seen should be kept
-    assert_eq!(saved[0]["agent_id"], "ed25519.bbbb");
-}
-
-#[test]
-fn doctor_default_output_is_human_readable() {
-    let root = tempdir().expect("tempdir");
-    fs::set_permissions(root.path(), fs::Permissions::from_mode(0o700)).expect("set perms");
-
-    let output = run_doctor(root.path(), &[]);
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("Doctor:"));
-    assert!(!stdout.trim_start().starts_with('{'));
-}
@@ -0,0 +1,182 @@
+use super::*;
+
+#[test]
+fn doctor_check_reports_corrupt_config_as_fixable() {
+    let root = tempdir().expect("tempdir");
+    fs::set_permissions(root.path(), fs::Permissions::from_mode(0o700)).expect("set perms");
+
+    // Write valid identity so that check passes, isolating the config check.
+    let identity = axon::identity::Identity::load_or_generate(&axon::config::AxonPaths::from_root(
+        root.path().to_path_buf(),
+    ))
+    .expect("generate identity");
+    drop(identity);
+
+    // Write corrupt config.
+    fs::write(root.path().join("config.yaml"), b"\x80\x81 invalid yaml").expect("write corrupt");
+
+    let output = run_doctor_json(root.path(), &[]);
+    assert_eq!(output.status.code(), Some(2));
+
+    let report = parse_report(&output);
+    let config = check_by_name(&report, "config");
+    assert_eq!(config["ok"], false);
+    assert_eq!(config["fixable"], true);
+    assert!(
+        config["message"]
+            .as_str()
+            .expect("config message")
+            .contains("doctor --fix"),
+        "should suggest --fix"
+    );
+}
+
+#[test]
+fn doctor_fix_resets_corrupt_config_with_backup() {
+    let root = tempdir().expect("tempdir");
+    fs::set_permissions(root.path(), fs::Permissions::from_mode(0o700)).expect("set perms");
+
+    // Write valid identity so that check passes, isolating the config check.
+    let identity = axon::identity::Identity::load_or_generate(&axon::config::AxonPaths::from_root(
+        root.path().to_path_buf(),
+    ))
+    .expect("generate identity");
+    drop(identity);
+
+    let corrupt_content = b"\x80\x81 invalid binary";
+    fs::write(root.path().join("config.yaml"), corrupt_content).expect("write corrupt");
+
+    let output = run_doctor_json(root.path(), &["--fix"]);
+    assert!(output.status.success());
+
+    let report = parse_report(&output);
+    assert_eq!(report["ok"], true);
+
+    // Config check should pass after fix.
+    let config = check_by_name(&report, "config");
+    assert_eq!(config["ok"], true);
+
+    // Fixes should include config_reset.
+    let fixes = report["fixes_applied"]
+        .as_array()
+        .expect("fixes_applied array");
+    assert!(
+        fixes
+            .iter()
+            .any(|f| f["name"].as_str() == Some("config_reset")),
+        "should report config_reset fix"
+    );
+
+    // Backup file should exist with original corrupt content.
+    let mut backup_found = false;
+    for entry in fs::read_dir(root.path()).expect("read dir") {
+        let path = entry.expect("dir entry").path();
+        let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        if name.starts_with("config.yaml.bak.") {
+            let backup_content = fs::read(&path).expect("read backup");
+            assert_eq!(backup_content, corrupt_content);
+            backup_found = true;
+            break;
+        }
+    }
+    assert!(backup_found, "expected config.yaml backup file");
+
+    // New config.yaml should be valid and parseable.
+    let new_content = fs::read_to_string(root.path().join("config.yaml")).expect("read new config");
+    assert!(
+        !new_content.is_empty(),
+        "reset config.yaml should not be empty"
+    );
+}
+
+#[test]
+fn doctor_fix_migrates_config_toml_and_legacy_known_peers() {
+    let root = tempdir().expect("tempdir");
+    fs::set_permissions(root.path(), fs::Permissions::from_mode(0o700)).expect("set perms");
+    drop(
+        axon::identity::Identity::load_or_generate(&axon::config::AxonPaths::from_root(
+            root.path().to_path_buf(),
+        ))
+        .expect("generate identity"),
+    );
+    let agent = "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+    let pubkey = STANDARD.encode([7u8; 32]);
+    let toml = format!(
+        "name = \"old-box\"\nport = 7200\n\n[[peers]]\nid = \"{agent}\"\naddr = \"127.0.0.1:7100\"\npubkey = \"{pubkey}\"\n"
+    );
+    fs::write(root.path().join("config.toml"), &toml).expect("write config.toml");
+    let known = format!(
+        r#"[{{"agent_id":"{agent}","addr":"127.0.0.1:7100","pubkey":"{pubkey}","last_seen_unix_ms":123}}]"#
+    );
+    fs::write(root.path().join("known_peers.json"), &known).expect("write known peers");
+
+    let report = parse_report(&run_doctor_json(root.path(), &[]));
+    assert_eq!(report["ok"], false);
+    assert_eq!(
+        check_by_name(&report, "legacy_config_toml")["fixable"],
+        true
+    );
+    assert_eq!(check_by_name(&report, "legacy_known_peers")["ok"], false);
+
+    let output = run_doctor_json(root.path(), &["--fix"]);
+    assert!(output.status.success(), "{output:?}");
+    let report = parse_report(&output);
+    assert_eq!(check_by_name(&report, "legacy_config_toml")["ok"], true);
+    assert!(
+        check_by_name(&report, "known_peers")["message"]
+            .as_str()
+            .expect("message")
+            .contains("1 entries"),
+        "{report}"
+    );
+    let config = fs::read_to_string(root.path().join("config.yaml")).expect("read config.yaml");
+    assert!(config.contains("name: old-box"), "{config}");
+    assert!(config.contains(&format!("agent_id: {agent}")), "{config}");
+    assert!(!root.path().join("config.toml").exists());
+    let backup = backup_of(root.path(), "config.toml").expect("config.toml backup");
+    assert_eq!(fs::read_to_string(backup).expect("read backup"), toml);
+    let known: Value = serde_json::from_str(
+        &fs::read_to_string(root.path().join("known_peers.json")).expect("read known peers"),
+    )
+    .expect("known peers JSON");
+    assert_eq!(known[0]["source"], "cached");
+    assert!(backup_of(root.path(), "known_peers.json").is_some());
+
+    let report = parse_report(&run_doctor_json(root.path(), &[]));
+    assert_eq!(check_by_name(&report, "legacy_state")["ok"], true);
+}
+
+#[test]
+fn doctor_fix_renames_legacy_peer_id_in_config_yaml() {
+    let root = tempdir().expect("tempdir");
+    let agent = "ed25519.bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb";
+    let pubkey = STANDARD.encode([9u8; 32]);
+    let yaml = format!(
+        "port: 7300\npeers:\n  - id: {agent}\n    addr: 127.0.0.1:7101\n    pubkey: {pubkey}\n"
+    );
+    fs::write(root.path().join("config.yaml"), &yaml).expect("write config.yaml");
+
+    let report = parse_report(&run_doctor_json(root.path(), &[]));
+    let check = check_by_name(&report, "legacy_peer_id");
+    assert_eq!(check["ok"], false);
+    assert!(
+        check["message"]
+            .as_str()
+            .expect("message")
+            .starts_with("1 peers"),
+        "{check}"
+    );
+
+    let report = parse_report(&run_doctor_json(root.path(), &["--fix"]));
+    assert_eq!(check_by_name(&report, "legacy_peer_id")["ok"], true);
+    assert_eq!(check_by_name(&report, "config")["ok"], true, "{report}");
+    let config = fs::read_to_string(root.path().join("config.yaml")).expect("read config.yaml");
+    assert!(config.contains(&format!("- agent_id: {agent}")), "{config}");
+    assert_eq!(
+        fs::read_to_string(backup_of(root.path(), "config.yaml").expect("backup"))
+            .expect("read backup"),
+        yaml
+    );
+}
@@ -0,0 +1,136 @@
+use super::*;
+
+#[test]
+fn doctor_check_mode_reports_missing_identity_without_creating_files() {
+    let root = tempdir().expect("tempdir");
+    fs::set_permissions(root.path(), fs::Permissions::from_mode(0o700)).expect("set perms");
+
+    let output = run_doctor_json(root.path(), &[]);
+    assert_eq!(output.status.code(), Some(2));
+
+    let report = parse_report(&output);
+    assert_eq!(report["mode"], "check");
+    assert_eq!(report["ok"], false);
+
+    let identity = check_by_name(&report, "identity");
+    assert_eq!(identity["ok"], false);
+    assert_eq!(identity["fixable"], true);
+    assert!(
+        identity["message"]
+            .as_str()
+            .expect("identity message")
+            .contains("doctor --fix")
+    );
+
+    assert!(
+        !root.path().join("identity.key").exists(),
+        "check mode should not generate identity files"
+    );
+}
+
+#[test]
+fn doctor_fix_mode_generates_identity() {
+    let root = tempdir().expect("tempdir");
+    fs::set_permissions(root.path(), fs::Permissions::from_mode(0o700)).expect("set perms");
+
+    let output = run_doctor_json(root.path(), &["--fix"]);
+    assert!(output.status.success());
+
+    let report = parse_report(&output);
+    assert_eq!(report["mode"], "fix");
+    assert_eq!(report["ok"], true);
+
+    let identity = check_by_name(&report, "identity");
+    assert_eq!(identity["ok"], true);
+
+    let key_contents = fs::read_to_string(root.path().join("identity.key")).expect("read key");
+    let decoded = STANDARD
+        .decode(key_contents.trim())
+        .expect("identity.key should be base64");
+    assert_eq!(decoded.len(), 32);
+}
+
+#[test]
+fn doctor_fix_requires_rekey_for_unrecoverable_identity() {
+    let root = tempdir().expect("tempdir");
+    fs::set_permissions(root.path(), fs::Permissions::from_mode(0o700)).expect("set perms");
+    fs::write(root.path().join("identity.key"), "not base64 at all").expect("write invalid key");
+
+    let output = run_doctor_json(root.path(), &["--fix"]);
+    assert_eq!(output.status.code(), Some(2));
+
+    let report = parse_report(&output);
+    assert_eq!(report["mode"], "fix");
+    assert_eq!(report["ok"], false);
+
+    let identity = check_by_name(&report, "identity");
+    assert_eq!(identity["ok"], false);
+    assert!(
+        identity["message"]
+            .as_str()
+            .expect("identity message")
+            .contains("--rekey")
+    );
+
+    let still_invalid = fs::read_to_string(root.path().join("identity.key")).expect("read key");
+    assert_eq!(still_invalid, "not base64 at all");
+}
+
+#[test]
+fn doctor_fix_requires_rekey_for_legacy_raw_identity() {
+    let root = tempdir().expect("tempdir");
+    fs::set_permissions(root.path(), fs::Permissions::from_mode(0o700)).expect("set perms");
+    fs::write(root.path().join("identity.key"), [7u8; 32]).expect("write legacy raw key");
+
+    let output = run_doctor_json(root.path(), &["--fix"]);
+    assert_eq!(output.status.code(), Some(2));
+
+    let report = parse_report(&output);
+    assert_eq!(report["mode"], "fix");
+    assert_eq!(report["ok"], false);
+
+    let identity = check_by_name(&report, "identity");
+    assert_eq!(identity["ok"], false);
+    assert!(
+        identity["message"]
+            .as_str()
+            .expect("identity message")
+            .contains("--rekey")
+    );
+}
+
+#[test]
+fn doctor_fix_rekey_backs_up_and_regenerates_identity() {
+    let root = tempdir().expect("tempdir");
+    fs::set_permissions(root.path(), fs::Permissions::from_mode(0o700)).expect("set perms");
+    fs::write(root.path().join("identity.key"), "not base64 at all").expect("write invalid key");
+
+    let output = run_doctor_json(root.path(), &["--fix", "--rekey"]);
+    assert!(output.status.success());
+
+    let report = parse_report(&output);
+    assert_eq!(report["mode"], "fix");
+    assert_eq!(report["ok"], true);
+
+    let identity = check_by_name(&report, "identity");
+    assert_eq!(identity["ok"], true);
+
+    let key_contents = fs::read_to_string(root.path().join("identity.key")).expect("read key");
+    let decoded = STANDARD
+        .decode(key_contents.trim())
+        .expect("identity.key should be base64");
+    assert_eq!(decoded.len(), 32);
+
+    let mut backup_found = false;
+    for entry in fs::read_dir(root.path()).expect("read dir") {
+        let path = entry.expect("dir entry").path();
+        let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        if name.starts_with("identity.key.bak.") {
+            backup_found = true;
+            break;
+        }
+    }
+    assert!(backup_found, "expected identity.key backup file");
+}
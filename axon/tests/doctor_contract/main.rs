@@ -0,0 +1,86 @@
+//! Black-box contract tests for `axon doctor`: the checks it reports, the
+//! exit codes, and what `--fix` (and `--fix --rekey`) changes on disk.
+
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+use base64::{Engine as _, engine::general_purpose::STANDARD};
+use serde_json::Value;
+use tempfile::tempdir;
+
+mod config;
+mod identity;
+mod output;
+mod peers;
+
+fn axon_bin() -> PathBuf {
+    if let Some(bin) = std::env::var_os("CARGO_BIN_EXE_axon") {
+        return PathBuf::from(bin);
+    }
+
+    let current = std::env::current_exe().expect("resolve current test executable");
+    let debug_dir = current
+        .parent()
+        .and_then(Path::parent)
+        .expect("resolve target debug dir");
+    let fallback = if cfg!(windows) {
+        debug_dir.join("axon.exe")
+    } else {
+        debug_dir.join("axon")
+    };
+    assert!(
+        fallback.exists(),
+        "failed to locate axon binary via CARGO_BIN_EXE_axon and fallback path {}",
+        fallback.display()
+    );
+    fallback
+}
+
+fn run_command(cmd: &mut Command) -> Output {
+    cmd.output().expect("failed to execute axon binary")
+}
+
+fn run_doctor(root: &Path, args: &[&str]) -> Output {
+    let mut cmd = Command::new(axon_bin());
+    cmd.arg("--state-root")
+        .arg(root.to_str().expect("utf8 path"))
+        .arg("doctor");
+    cmd.args(args);
+    run_command(&mut cmd)
+}
+
+fn run_doctor_json(root: &Path, args: &[&str]) -> Output {
+    let mut cmd = Command::new(axon_bin());
+    cmd.arg("--state-root")
+        .arg(root.to_str().expect("utf8 path"))
+        .arg("doctor")
+        .arg("--json");
+    cmd.args(args);
+    run_command(&mut cmd)
+}
+
+fn parse_report(output: &Output) -> Value {
+    serde_json::from_slice(&output.stdout).expect("doctor stdout should be valid JSON")
+}
+
+fn check_by_name<'a>(report: &'a Value, name: &str) -> &'a Value {
+    report["checks"]
+        .as_array()
+        .expect("checks must be array")
+        .iter()
+        .find(|check| check.get("name") == Some(&Value::String(name.to_string())))
+        .unwrap_or_else(|| panic!("missing check '{name}'"))
+}
+
+fn backup_of(root: &Path, file_name: &str) -> Option<PathBuf> {
+    fs::read_dir(root)
+        .expect("read dir")
+        .map(|entry| entry.expect("dir entry").path())
+        .find(|path| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|name| name.starts_with(&format!("{file_name}.bak.")))
+        })
+}
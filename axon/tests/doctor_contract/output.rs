@@ -0,0 +1,21 @@
+use super::*;
+
+#[test]
+fn doctor_subcommand_is_visible_in_help() {
+    let output = run_command(Command::new(axon_bin()).arg("--help"));
+    assert!(output.status.success());
+
+    let help = String::from_utf8_lossy(&output.stdout);
+    assert!(help.contains("  doctor"));
+}
+
+#[test]
+fn doctor_default_output_is_human_readable() {
+    let root = tempdir().expect("tempdir");
+    fs::set_permissions(root.path(), fs::Permissions::from_mode(0o700)).expect("set perms");
+
+    let output = run_doctor(root.path(), &[]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Doctor:"));
+    assert!(!stdout.trim_start().starts_with('{'));
+}
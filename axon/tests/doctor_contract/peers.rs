@@ -0,0 +1,131 @@
+use super::*;
+
+#[test]
+fn doctor_check_detects_duplicate_peer_addresses() {
+    let root = tempdir().expect("tempdir");
+    fs::set_permissions(root.path(), fs::Permissions::from_mode(0o700)).expect("set perms");
+
+    // Generate identity so other checks pass
+    let _identity = axon::identity::Identity::load_or_generate(
+        &axon::config::AxonPaths::from_root(root.path().to_path_buf()),
+    )
+    .expect("generate identity");
+
+    // Write known_peers.json with two peers at the same address
+    let peers = serde_json::json!([
+        {"agent_id": "ed25519.aaaa", "addr": "10.0.0.1:7100", "pubkey": "a2V5MQ==", "last_seen_unix_ms": 1000, "source": "discovered"},
+        {"agent_id": "ed25519.bbbb", "addr": "10.0.0.1:7100", "pubkey": "a2V5Mg==", "last_seen_unix_ms": 2000, "source": "discovered"}
+    ]);
+    fs::write(
+        root.path().join("known_peers.json"),
+        serde_json::to_string(&peers).unwrap(),
+    )
+    .expect("write known_peers");
+
+    let output = run_doctor_json(root.path(), &[]);
+    assert_eq!(output.status.code(), Some(2));
+
+    let report = parse_report(&output);
+    let dup_check = check_by_name(&report, "duplicate_peer_addr");
+    assert_eq!(dup_check["ok"], false);
+    assert_eq!(dup_check["fixable"], true);
+    assert!(
+        dup_check["message"]
+            .as_str()
+            .unwrap()
+            .contains("duplicate address")
+    );
+}
+
+#[test]
+fn doctor_fix_prunes_duplicate_peer_addresses() {
+    let root = tempdir().expect("tempdir");
+    fs::set_permissions(root.path(), fs::Permissions::from_mode(0o700)).expect("set perms");
+
+    // Generate identity so other checks pass
+    let _identity = axon::identity::Identity::load_or_generate(
+        &axon::config::AxonPaths::from_root(root.path().to_path_buf()),
+    )
+    .expect("generate identity");
+
+    // Write known_peers.json with two peers at the same address
+    let peers = serde_json::json!([
+        {"agent_id": "ed25519.aaaa", "addr": "10.0.0.1:7100", "pubkey": "a2V5MQ==", "last_seen_unix_ms": 1000, "source": "discovered"},
+        {"agent_id": "ed25519.bbbb", "addr": "10.0.0.1:7100", "pubkey": "a2V5Mg==", "last_seen_unix_ms": 2000, "source": "discovered"}
+    ]);
+    fs::write(
+        root.path().join("known_peers.json"),
+        serde_json::to_string(&peers).unwrap(),
+    )
+    .expect("write known_peers");
+
+    let output = run_doctor_json(root.path(), &["--fix"]);
+    assert!(output.status.success());
+
+    let report = parse_report(&output);
+    let dup_check = check_by_name(&report, "duplicate_peer_addr");
+    assert_eq!(dup_check["ok"], true);
+
+    // Verify known_peers.json was updated — only one peer should remain
+    let saved: Vec<serde_json::Value> = serde_json::from_str(
+        &fs::read_to_string(root.path().join("known_peers.json")).expect("read peers"),
+    )
+    .expect("parse peers");
+    assert_eq!(saved.len(), 1);
+    // The one with higher last_seen should be kept
+    assert_eq!(saved[0]["agent_id"], "ed25519.bbbb");
+}
+
+#[test]
+fn doctor_network_reports_port_in_use_and_closed_peer_port() {
+    let root = tempdir().expect("tempdir");
+    fs::set_permissions(root.path(), fs::Permissions::from_mode(0o700)).expect("set perms");
+
+    let held = std::net::UdpSocket::bind("0.0.0.0:0").expect("bind held port");
+    let held_port = held.local_addr().expect("held addr").port();
+    let closed_port = std::net::UdpSocket::bind("127.0.0.1:0")
+        .expect("bind probe port")
+        .local_addr()
+        .expect("probe addr")
+        .port();
+    let peer_id = "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+    fs::write(
+        root.path().join("config.yaml"),
+        format!(
+            "port: {held_port}\npeers:\n  - agent_id: {peer_id}\n    addr: 127.0.0.1:{closed_port}\n    pubkey: {}\n",
+            STANDARD.encode([7u8; 32])
+        ),
+    )
+    .expect("write config");
+
+    let plain = parse_report(&run_doctor_json(root.path(), &[]));
+    assert!(
+        plain["checks"]
+            .as_array()
+            .expect("checks")
+            .iter()
+            .all(|check| check["name"] != "udp_port"),
+        "network probes run only with --network"
+    );
+
+    let output = run_doctor_json(root.path(), &["--network"]);
+    assert_eq!(output.status.code(), Some(2));
+    let report = parse_report(&output);
+
+    let port = check_by_name(&report, "udp_port");
+    assert_eq!(port["ok"], false);
+    assert!(port["message"].as_str().unwrap().contains("in use"));
+
+    let udp = check_by_name(&report, "peer_udp");
+    assert_eq!(udp["ok"], false);
+    assert!(udp["message"].as_str().unwrap().contains("port closed"));
+
+    // No identity yet, so the handshake probe explains what to do instead.
+    let quic = check_by_name(&report, "peer_quic");
+    assert_eq!(quic["ok"], false);
+    assert!(quic["message"].as_str().unwrap().contains("doctor --fix"));
+
+    let mdns = check_by_name(&report, "mdns_advertisement");
+    assert_eq!(mdns["ok"], true);
+    drop(held);
+}
@@ -51,6 +51,9 @@ pub(crate) fn make_peer_record(
         status: ConnectionStatus::Discovered,
         rtt_ms: None,
         last_seen: Instant::now(),
+        first_seen_unix_ms: 0,
+        traffic: Default::default(),
+        software: None,
     }
 }
 
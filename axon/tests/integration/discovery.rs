@@ -12,11 +12,13 @@ async fn static_discovery_feeds_peer_table() {
             agent_id: "ed25519.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa".into(),
             addr: axon::config::PeerAddr::Socket("10.0.0.1:7100".parse().unwrap()),
             pubkey: "Zm9v".to_string(),
+            tuning: Default::default(),
         },
         axon::config::PersistedStaticPeerConfig {
             agent_id: "ed25519.bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb".into(),
             addr: axon::config::PeerAddr::Socket("10.0.0.2:7100".parse().unwrap()),
             pubkey: "YmFy".to_string(),
+            tuning: Default::default(),
         },
     ];
 
@@ -72,6 +72,9 @@ async fn ipc_status_roundtrip() {
                 peers_connected: 2,
                 messages_sent: 10,
                 messages_received: 5,
+                messages_rate_limited: 0,
+                lifetime: Default::default(),
+                degraded: Vec::new(),
                 req_id: None,
             },
         )
@@ -119,6 +122,9 @@ async fn ipc_multiple_commands_sequential() {
                     peers_connected: 0,
                     messages_sent: 0,
                     messages_received: 0,
+                    messages_rate_limited: 0,
+                    lifetime: Default::default(),
+                    degraded: Vec::new(),
                     req_id: None,
                 },
             )
@@ -214,6 +220,19 @@ fn ipc_send_without_ref_defaults_to_none() {
     }
 }
 
+/// IPC send with idempotency_key deserializes correctly.
+#[test]
+fn ipc_send_with_idempotency_key_deserializes() {
+    let input = r#"{"cmd":"send","to":"ed25519.deadbeef01234567deadbeef01234567","kind":"request","idempotency_key":"job-42","payload":{"task":"summarize"}}"#;
+    let cmd: IpcCommand = serde_json::from_str(input).unwrap();
+    match cmd {
+        IpcCommand::Send {
+            idempotency_key, ..
+        } => assert_eq!(idempotency_key.as_deref(), Some("job-42")),
+        _ => panic!("expected Send"),
+    }
+}
+
 /// IPC send with timeout field deserializes correctly.
 #[test]
 fn ipc_send_with_timeout_deserializes() {
@@ -359,3 +378,93 @@ async fn ipc_cleanup_removes_socket() {
     server.cleanup_socket().unwrap();
     assert!(!socket_path.exists());
 }
+
+/// Clients that send no command within the idle timeout are disconnected and
+/// their server-side state is released.
+#[tokio::test]
+async fn ipc_idle_client_is_disconnected() {
+    let dir = tempdir().unwrap();
+    let socket_path = dir.path().join("axon.sock");
+    let config = IpcServerConfig {
+        idle_timeout: Some(Duration::from_millis(200)),
+        ..IpcServerConfig::default()
+    };
+    let (server, _cmd_rx) = IpcServer::bind(socket_path.clone(), 64, config)
+        .await
+        .unwrap();
+
+    let client = UnixStream::connect(&socket_path).await.unwrap();
+    let mut reader = BufReader::new(client);
+    let mut line = String::new();
+    let bytes = tokio::time::timeout(Duration::from_secs(2), reader.read_line(&mut line))
+        .await
+        .expect("idle client should be closed before the test timeout")
+        .unwrap();
+    assert_eq!(bytes, 0, "idle client should observe EOF");
+
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(server.client_count().await, 0);
+}
+
+/// Each command resets the idle timer.
+#[tokio::test]
+async fn ipc_active_client_survives_idle_timeout() {
+    let dir = tempdir().unwrap();
+    let socket_path = dir.path().join("axon.sock");
+    let config = IpcServerConfig {
+        idle_timeout: Some(Duration::from_millis(300)),
+        ..IpcServerConfig::default()
+    };
+    let (server, mut cmd_rx) = IpcServer::bind(socket_path.clone(), 64, config)
+        .await
+        .unwrap();
+
+    let mut client = UnixStream::connect(&socket_path).await.unwrap();
+    for _ in 0..4 {
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        client.write_all(b"{\"cmd\":\"status\"}\n").await.unwrap();
+        tokio::time::timeout(Duration::from_secs(2), cmd_rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+    }
+    assert_eq!(server.client_count().await, 1);
+}
+
+/// Clients are disconnected at max lifetime even while active.
+#[tokio::test]
+async fn ipc_client_disconnected_at_max_lifetime() {
+    let dir = tempdir().unwrap();
+    let socket_path = dir.path().join("axon.sock");
+    let config = IpcServerConfig {
+        max_lifetime: Some(Duration::from_millis(300)),
+        ..IpcServerConfig::default()
+    };
+    let (server, _cmd_rx) = IpcServer::bind(socket_path.clone(), 64, config)
+        .await
+        .unwrap();
+
+    let client = UnixStream::connect(&socket_path).await.unwrap();
+    let (read_half, mut write_half) = client.into_split();
+    let writer = tokio::spawn(async move {
+        while write_half
+            .write_all(b"{\"cmd\":\"status\"}\n")
+            .await
+            .is_ok()
+        {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    });
+
+    let mut reader = BufReader::new(read_half);
+    let mut line = String::new();
+    let bytes = tokio::time::timeout(Duration::from_secs(2), reader.read_line(&mut line))
+        .await
+        .expect("client should be closed at max lifetime")
+        .unwrap();
+    assert_eq!(bytes, 0);
+    writer.abort();
+
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(server.client_count().await, 0);
+}
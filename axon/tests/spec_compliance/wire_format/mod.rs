@@ -33,6 +33,41 @@ fn wire_encoding_omits_from_and_to_fields() {
     assert!(decoded.get("to").is_none());
 }
 
+/// spec/WIRE_FORMAT.md envelope schema: `topic` is carried on the wire when
+/// set and omitted otherwise.
+#[test]
+fn wire_encoding_carries_topic_only_when_set() {
+    let mut env = Envelope::new(agent_a(), agent_b(), MessageKind::Message, json!({}));
+    let decoded: Value = serde_json::from_slice(&env.wire_encode().unwrap()).unwrap();
+    assert!(decoded.get("topic").is_none());
+
+    env.topic = Some("builds.main".to_string());
+    let encoded = env.wire_encode().unwrap();
+    let decoded: Value = serde_json::from_slice(&encoded).unwrap();
+    assert_eq!(decoded["topic"], "builds.main");
+    assert_eq!(
+        decode(&encoded).unwrap().topic.as_deref(),
+        Some("builds.main")
+    );
+}
+
+#[test]
+fn wire_encoding_carries_headers_only_when_set() {
+    let mut env = Envelope::new(agent_a(), agent_b(), MessageKind::Message, json!({}));
+    let decoded: Value = serde_json::from_slice(&env.wire_encode().unwrap()).unwrap();
+    assert!(decoded.get("headers").is_none());
+
+    env.headers.insert("trace_id".to_string(), json!("abc123"));
+    env.headers.insert("attempt".to_string(), json!(2));
+    let encoded = env.wire_encode().unwrap();
+    let decoded: Value = serde_json::from_slice(&encoded).unwrap();
+    assert_eq!(
+        decoded["headers"],
+        json!({"trace_id": "abc123", "attempt": 2})
+    );
+    assert_eq!(decode(&encoded).unwrap().headers, env.headers);
+}
+
 /// `spec/WIRE_FORMAT.md` limits: max message size is 64KB.
 #[test]
 fn max_message_size_is_64kb() {
@@ -159,6 +194,11 @@ fn ipc_peers_response_uses_agent_id_field() {
             status: "connected".to_string(),
             rtt_ms: Some(1.23),
             source: "static".to_string(),
+            first_seen_ms: 1_771_108_000_000,
+            expires_in_ms: None,
+            traffic: Default::default(),
+            version: Some("0.7.2".to_string()),
+            tags: vec!["prod".to_string()],
         }],
         req_id: None,
     };
@@ -171,41 +211,59 @@ fn ipc_peers_response_uses_agent_id_field() {
 /// `spec/IPC.md` error response has ok=false and an error code string.
 #[test]
 fn ipc_error_response_shape() {
-    let reply = axon::ipc::DaemonReply::Error {
-        ok: false,
-        error: axon::ipc::IpcErrorCode::PeerNotFound,
-        message: axon::ipc::IpcErrorCode::PeerNotFound.message(),
-        req_id: None,
-    };
+    let reply = axon::ipc::DaemonReply::error(axon::ipc::IpcErrorCode::PeerNotFound, None);
     let j: Value = serde_json::to_value(&reply).unwrap();
     assert_eq!(j["ok"], false);
     assert_eq!(j["error"], "peer_not_found");
+    assert_eq!(j["retryable"], false);
+    assert!(j.get("details").is_none());
 }
 
 /// `spec/IPC.md` error code table includes all daemon-emitted IPC error codes.
 #[test]
 fn ipc_error_codes_match_spec_table() {
-    let expected = vec![
-        "invalid_command".to_string(),
-        "command_too_large".to_string(),
-        "peer_not_found".to_string(),
-        "self_send".to_string(),
-        "peer_unreachable".to_string(),
-        "timeout".to_string(),
-        "internal_error".to_string(),
+    let expected = [
+        ("invalid_command", false, 2),
+        ("command_too_large", false, 2),
+        ("peer_not_found", false, 3),
+        ("self_send", false, 2),
+        ("peer_unreachable", true, 5),
+        ("timeout", true, 4),
+        ("reload_failed", false, 2),
+        ("history_disabled", false, 2),
+        ("reply_lost", false, 2),
+        ("shutting_down", false, 2),
+        ("internal_error", false, 2),
+    ];
+    let actual: Vec<_> = axon::ipc::IpcErrorCode::ALL
+        .into_iter()
+        .map(|code| (code.as_str(), code.retryable(), code.exit_code()))
+        .collect();
+
+    assert_eq!(actual, expected);
+}
+
+/// `spec/MESSAGE_TYPES.md` error payload table lists every daemon-emitted
+/// wire error code with its `retryable` flag.
+#[test]
+fn wire_error_codes_match_spec_table() {
+    let expected = [
+        ("unhandled", false),
+        ("unknown_kind", false),
+        ("invalid_envelope", false),
+        ("rate_limited", true),
+        ("message_too_large", false),
+        ("not_authorized", false),
+        ("handler_busy", true),
+        ("handler_timeout", true),
+        ("handler_failed", false),
+        ("handler_invalid_output", false),
+        ("rejected", false),
     ];
-    let actual: Vec<String> = vec![
-        axon::ipc::IpcErrorCode::InvalidCommand,
-        axon::ipc::IpcErrorCode::CommandTooLarge,
-        axon::ipc::IpcErrorCode::PeerNotFound,
-        axon::ipc::IpcErrorCode::SelfSend,
-        axon::ipc::IpcErrorCode::PeerUnreachable,
-        axon::ipc::IpcErrorCode::Timeout,
-        axon::ipc::IpcErrorCode::InternalError,
-    ]
-    .into_iter()
-    .map(|code| code.to_string())
-    .collect();
+    let actual: Vec<_> = axon::errors::WireErrorCode::ALL
+        .into_iter()
+        .map(|code| (code.as_str(), code.retryable()))
+        .collect();
 
     assert_eq!(actual, expected);
 }